@@ -0,0 +1,139 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use quick_m3u8::{Reader, config::ParsingOptionsBuilder, tag::TagValue};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// A global allocator that counts allocations, so that we can report on the number of allocations
+// made while parsing, in addition to the timing that criterion provides.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+const STREAM_INF_VALUE: &[u8] = b"BANDWIDTH=10000000,AVERAGE-BANDWIDTH=8000000,CODECS=\"hvc1.2.4.L153.b0,ec-3\",SUPPLEMENTAL-CODECS=\"dvh1.08.07/db4h\",RESOLUTION=3840x2160,FRAME-RATE=23.976,HDCP-LEVEL=TYPE-1,VIDEO-RANGE=PQ,AUDIO=\"atmos\",SUBTITLES=\"subs\",CLOSED-CAPTIONS=NONE,STABLE-VARIANT-ID=\"2160p-hdr\"";
+
+// Builds a multivariant playlist representative of a large production ladder (many variants across
+// codecs and dynamic ranges, several audio renditions, and subtitles in many languages).
+fn multivariant_playlist() -> String {
+    let mut playlist = String::from("#EXTM3U\n#EXT-X-VERSION:9\n#EXT-X-INDEPENDENT-SEGMENTS\n");
+    for language in ["en", "es", "fr", "de", "it", "ja", "ko", "pt", "zh", "ar"] {
+        playlist.push_str(&format!(
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"atmos\",LANGUAGE=\"{language}\",NAME=\"{language}\",AUTOSELECT=YES,CHANNELS=\"16/JOC\",URI=\"audio/{language}/atmos.m3u8\"\n"
+        ));
+        playlist.push_str(&format!(
+            "#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"subs\",LANGUAGE=\"{language}\",NAME=\"{language}\",AUTOSELECT=YES,FORCED=NO,URI=\"subs/{language}.m3u8\"\n"
+        ));
+    }
+    for (codecs, range) in [
+        ("avc1.640028,ec-3", "SDR"),
+        ("hvc1.2.4.L153.b0,ec-3", "PQ"),
+        ("dvh1.08.07,ec-3", "PQ"),
+    ] {
+        for (height, bandwidth) in [(360, 800_000), (540, 2_000_000), (720, 4_000_000)]
+            .into_iter()
+            .chain([(1080, 7_000_000), (1440, 11_000_000), (2160, 16_000_000)])
+        {
+            let width = height * 16 / 9;
+            playlist.push_str(&format!(
+                "#EXT-X-STREAM-INF:BANDWIDTH={bandwidth},AVERAGE-BANDWIDTH={},CODECS=\"{codecs}\",RESOLUTION={width}x{height},FRAME-RATE=23.976,VIDEO-RANGE={range},AUDIO=\"atmos\",SUBTITLES=\"subs\",CLOSED-CAPTIONS=NONE\nvideo/{height}p/{range}/playlist.m3u8\n",
+                bandwidth * 8 / 10
+            ));
+            playlist.push_str(&format!(
+                "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH={},CODECS=\"{codecs}\",RESOLUTION={width}x{height},VIDEO-RANGE={range},URI=\"video/{height}p/{range}/iframes.m3u8\"\n",
+                bandwidth / 10
+            ));
+        }
+    }
+    playlist
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let value = TagValue(STREAM_INF_VALUE);
+    assert_eq!(
+        value.try_as_ordered_attribute_list().unwrap(),
+        value
+            .try_as_attribute_map()
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>()
+    );
+    let allocations = [
+        count_allocations(|| drop(black_box(value.try_as_attribute_list()))),
+        count_allocations(|| drop(black_box(value.try_as_ordered_attribute_list()))),
+        count_allocations(|| drop(black_box(value.try_as_attribute_map()))),
+    ];
+    println!(
+        "Allocations for EXT-X-STREAM-INF attribute list: HashMap = {}, Vec = {}, AttributeMap = {}",
+        allocations[0], allocations[1], allocations[2]
+    );
+
+    // Compare the different collections for a single attribute list (including a lookup, as that
+    // is what parsing a known tag needs to do).
+    c.bench_function("EXT-X-STREAM-INF attributes into HashMap", |b| {
+        b.iter(|| {
+            let list = black_box(value).try_as_attribute_list().unwrap();
+            black_box(list.get("VIDEO-RANGE").copied())
+        });
+    });
+    c.bench_function("EXT-X-STREAM-INF attributes into ordered Vec", |b| {
+        b.iter(|| {
+            let list = black_box(value).try_as_ordered_attribute_list().unwrap();
+            black_box(
+                list.iter()
+                    .find(|(n, _)| *n == "VIDEO-RANGE")
+                    .map(|(_, v)| *v),
+            )
+        });
+    });
+    c.bench_function("EXT-X-STREAM-INF attributes into AttributeMap", |b| {
+        b.iter(|| {
+            let list = black_box(value).try_as_attribute_map().unwrap();
+            black_box(list.get("VIDEO-RANGE"))
+        });
+    });
+
+    // Parse a whole multivariant playlist, where almost every line is an attribute list.
+    let playlist = multivariant_playlist();
+    let options = ParsingOptionsBuilder::new()
+        .with_parsing_for_all_tags()
+        .build();
+    let parse = || {
+        let mut reader = Reader::from_str(black_box(playlist.as_str()), options.clone());
+        while let Some(line) = reader.read_line().expect("playlist should be valid") {
+            black_box(line);
+        }
+    };
+    println!(
+        "Allocations for parsing multivariant playlist ({} lines): {}",
+        playlist.lines().count(),
+        count_allocations(parse)
+    );
+    c.bench_function("Multivariant playlist, all tags, no writing", |b| {
+        b.iter(parse)
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
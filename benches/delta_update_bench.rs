@@ -88,10 +88,11 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         |b| {
             b.iter(|| {
                 output_playlist = vec![];
-                black_box(
-                    make_delta_update(LONG_MEDIA_PLAYLIST.as_bytes(), &mut output_playlist)
-                        .expect("should not fail"),
-                );
+                black_box(make_delta_update(
+                    LONG_MEDIA_PLAYLIST.as_bytes(),
+                    &mut output_playlist,
+                ))
+                .expect("should not fail");
             });
         },
     );
@@ -113,13 +114,11 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         |b| {
             b.iter(|| {
                 output_playlist = vec![];
-                black_box(
-                    make_delta_update_using_m3u8_rs(
-                        LONG_MEDIA_PLAYLIST.as_bytes(),
-                        &mut output_playlist,
-                    )
-                    .expect("should not fail"),
-                );
+                black_box(make_delta_update_using_m3u8_rs(
+                    LONG_MEDIA_PLAYLIST.as_bytes(),
+                    &mut output_playlist,
+                ))
+                .expect("should not fail");
             });
         },
     );
@@ -138,13 +137,11 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         |b| {
             b.iter(|| {
                 output_playlist = vec![];
-                black_box(
-                    make_delta_update_using_hls_m3u8(
-                        LONG_MEDIA_PLAYLIST.as_bytes(),
-                        &mut output_playlist,
-                    )
-                    .expect("should not fail"),
-                );
+                black_box(make_delta_update_using_hls_m3u8(
+                    LONG_MEDIA_PLAYLIST.as_bytes(),
+                    &mut output_playlist,
+                ))
+                .expect("should not fail");
             });
         },
     );
@@ -295,9 +292,7 @@ fn make_delta_update<W: Write>(input: &[u8], output: &mut W) -> Result<(), Box<d
                     // (or at least, the tag is marked as REQUIRED, and I believe the implication is
                     // that it is required before any Media Segment Tags).
                     let Some(skip_until) = state.skip_until else {
-                        return Err(
-                            format!("skip until must be defined before media segments").into()
-                        );
+                        return Err("skip until must be defined before media segments".into());
                     };
                     // The minimum version if we are introducing EXT-X-SKIP is 9.
                     if !state.did_write_version {
@@ -400,10 +395,7 @@ fn is_media_segment_tag(line: &HlsLine) -> bool {
         _ => None,
     };
     if let Some(tag_name) = tag_name {
-        match tag_name.tag_type() {
-            TagType::MediaSegment => true,
-            _ => false,
-        }
+        matches!(tag_name.tag_type(), TagType::MediaSegment)
     } else {
         true
     }
@@ -538,18 +530,13 @@ fn make_delta_update_using_m3u8_rs<W: Write>(
                 // To avoid re-writing a bunch of matching code, I'm just using our library code for
                 // determining tag name and tag type. We would have to write it otherwise and it
                 // would probably just be the same (nothing fancy there).
-                if let Some(tag_name) =
-                    std::str::from_utf8(&temporary_output[(last_tag_token_index + 4)..end_index])
-                        .ok()
-                        .and_then(|s| TagName::try_from(s).ok())
+                if std::str::from_utf8(&temporary_output[(last_tag_token_index + 4)..end_index])
+                    .ok()
+                    .and_then(|s| TagName::try_from(s).ok())
+                    .is_some_and(|tag_name| tag_name.tag_type() == TagType::MediaSegment)
                 {
-                    match tag_name.tag_type() {
-                        TagType::MediaSegment => {
-                            insertion_index = Some(last_tag_token_index);
-                            break;
-                        }
-                        _ => (),
-                    }
+                    insertion_index = Some(last_tag_token_index);
+                    break;
                 }
             }
             b'#' => last_tag_token_index = index,
@@ -754,18 +741,13 @@ fn make_delta_update_using_hls_m3u8<W: Write>(
                 // To avoid re-writing a bunch of matching code, I'm just using our library code for
                 // determining tag name and tag type. We would have to write it otherwise and it
                 // would probably just be the same (nothing fancy there).
-                if let Some(tag_name) =
-                    std::str::from_utf8(&temporary_output[(last_tag_token_index + 4)..end_index])
-                        .ok()
-                        .and_then(|s| TagName::try_from(s).ok())
+                if std::str::from_utf8(&temporary_output[(last_tag_token_index + 4)..end_index])
+                    .ok()
+                    .and_then(|s| TagName::try_from(s).ok())
+                    .is_some_and(|tag_name| tag_name.tag_type() == TagType::MediaSegment)
                 {
-                    match tag_name.tag_type() {
-                        TagType::MediaSegment => {
-                            insertion_index = Some(last_tag_token_index);
-                            break;
-                        }
-                        _ => (),
-                    }
+                    insertion_index = Some(last_tag_token_index);
+                    break;
                 }
             }
             b'#' => last_tag_token_index = index,
@@ -7,7 +7,7 @@ use quick_m3u8::{
 };
 use std::hint::black_box;
 
-const LONG_MEDIA_PLAYLIST: &'static str = include_str!("long_media_playlist.m3u8");
+const LONG_MEDIA_PLAYLIST: &str = include_str!("long_media_playlist.m3u8");
 
 macro_rules! reader_match {
     (MUTATE, $reader:ident, $writer:ident) => {
@@ -0,0 +1,371 @@
+use crate::{
+    line::HlsLine,
+    path_writer::{replace_atomically, unique_temp_path},
+    tag::WritableCustomTag,
+    writer::{LineEnding, Writer, WriterBuilder},
+};
+use std::{
+    borrow::Cow,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+const ENDLIST_LINE: &[u8] = b"#EXT-X-ENDLIST";
+
+/// A writer that appends HLS lines to an existing playlist file on disk.
+///
+/// Live playlists are typically updated by appending new segments (or parts) to the end of the
+/// existing file. While simple in principle, there are a few details that are easy to get wrong:
+/// * Readers of the playlist (e.g. an HTTP server) must never observe a partially written file.
+/// * If the playlist previously ended with `#EXT-X-ENDLIST` (e.g. an event that is being resumed),
+///   then that tag must be removed before new lines are added, otherwise the new lines are
+///   considered to be outside of the playlist.
+/// * The existing contents should be left byte-for-byte untouched, including a leading UTF-8 BOM
+///   and the line ending style (LF or CRLF) used by the file.
+///
+/// The `AppendWriter` handles all of these. On [`Self::open`] the existing playlist is read and any
+/// trailing `#EXT-X-ENDLIST` line is removed (tracked via [`Self::had_endlist`]). Lines are then
+/// buffered in memory using the same formatting as [`Writer`], and on [`Self::commit`] (or
+/// [`Self::commit_with_endlist`]) the full playlist is written to a temporary file in the same
+/// directory, flushed to disk, and then renamed over the original path. A rename within the same
+/// directory is atomic on all major platforms, so concurrent readers will only ever see either the
+/// previous or the new version of the playlist.
+///
+/// If the playlist is modified on disk between `open` and `commit` (as detected by a change in its
+/// length or modification time), then the commit fails (with [`io::ErrorKind::Other`]) rather than
+/// discarding the other writer's changes. This check is a safeguard against mistakes rather than a
+/// lock: it is not atomic with the rename that follows, and so a write that lands in between may
+/// still be lost. Appending to the same playlist from more than one writer at a time must be
+/// coordinated by the caller (e.g. with a single writer per playlist or an external lock).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{AppendWriter, HlsLine, tag::hls::Inf};
+/// # use std::fs;
+/// # let path = std::env::temp_dir().join("quick-m3u8-append-writer-doc-example.m3u8");
+/// fs::write(
+///     &path,
+///     concat!(
+///         "#EXTM3U\n",
+///         "#EXT-X-TARGETDURATION:4\n",
+///         "#EXTINF:4,\n",
+///         "segment_100.mp4\n",
+///         "#EXT-X-ENDLIST\n",
+///     ),
+/// )?;
+///
+/// let mut writer = AppendWriter::open(&path)?;
+/// assert!(writer.had_endlist());
+/// writer.write_line(HlsLine::from(Inf::new(4.0, "")))?;
+/// writer.write_uri("segment_101.mp4")?;
+/// writer.commit()?;
+///
+/// assert_eq!(
+///     concat!(
+///         "#EXTM3U\n",
+///         "#EXT-X-TARGETDURATION:4\n",
+///         "#EXTINF:4,\n",
+///         "segment_100.mp4\n",
+///         "#EXTINF:4\n",
+///         "segment_101.mp4\n",
+///     ),
+///     fs::read_to_string(&path)?
+/// );
+/// # fs::remove_file(&path)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct AppendWriter {
+    path: PathBuf,
+    existing: Vec<u8>,
+    had_endlist: bool,
+    uses_crlf: bool,
+    original_len: u64,
+    original_modified: Option<SystemTime>,
+    writer: Writer<Vec<u8>>,
+}
+
+impl AppendWriter {
+    /// Opens the playlist at the provided path in preparation for appending lines.
+    ///
+    /// The existing file must exist. If it ends with `#EXT-X-ENDLIST` (ignoring trailing blank
+    /// lines) then that line is removed from the output (this can be checked via
+    /// [`Self::had_endlist`]).
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let metadata = fs::metadata(&path)?;
+        let mut existing = fs::read(&path)?;
+        let uses_crlf = memchr::memchr(b'\n', &existing)
+            .map(|i| i > 0 && existing[i - 1] == b'\r')
+            .unwrap_or(false);
+        let had_endlist = remove_trailing_endlist(&mut existing);
+        if !existing.is_empty() && !existing.ends_with(b"\n") {
+            if uses_crlf {
+                existing.extend_from_slice(b"\r\n");
+            } else {
+                existing.push(b'\n');
+            }
+        }
+        Ok(Self {
+            path,
+            existing,
+            had_endlist,
+            uses_crlf,
+            original_len: metadata.len(),
+            original_modified: metadata.modified().ok(),
+            writer: WriterBuilder::new()
+                .with_line_ending(if uses_crlf {
+                    LineEnding::Crlf
+                } else {
+                    LineEnding::Lf
+                })
+                .build(Vec::new()),
+        })
+    }
+
+    /// The path of the playlist being appended to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Indicates whether the playlist ended with `#EXT-X-ENDLIST` when it was opened. The tag is
+    /// not included in the output unless [`Self::commit_with_endlist`] is used.
+    pub fn had_endlist(&self) -> bool {
+        self.had_endlist
+    }
+
+    /// Indicates whether the existing playlist uses CRLF line endings. When this is `true` all
+    /// appended lines are also written with CRLF line endings.
+    pub fn uses_crlf(&self) -> bool {
+        self.uses_crlf
+    }
+
+    /// Append the `HlsLine`. Returns the number of bytes buffered (before any line ending
+    /// conversion).
+    ///
+    /// See [`Writer::write_line`] for more details.
+    pub fn write_line(&mut self, line: HlsLine) -> io::Result<usize> {
+        self.writer.write_line(line)
+    }
+
+    /// Append the `HlsLine` where the line may carry a custom tag type.
+    ///
+    /// See [`Writer::write_custom_line`] for more details.
+    pub fn write_custom_line<'a, Custom>(&mut self, line: HlsLine<'a, Custom>) -> io::Result<usize>
+    where
+        Custom: WritableCustomTag<'a>,
+    {
+        self.writer.write_custom_line(line)
+    }
+
+    /// Append a custom tag.
+    ///
+    /// See [`Writer::write_custom_tag`] for more details.
+    pub fn write_custom_tag<'a, Custom>(&mut self, tag: Custom) -> io::Result<usize>
+    where
+        Custom: WritableCustomTag<'a>,
+    {
+        self.writer.write_custom_tag(tag)
+    }
+
+    /// Append a blank line.
+    pub fn write_blank(&mut self) -> io::Result<usize> {
+        self.writer.write_blank()
+    }
+
+    /// Append a comment line.
+    pub fn write_comment<'a>(&mut self, comment: impl Into<Cow<'a, str>>) -> io::Result<usize> {
+        self.writer.write_comment(comment)
+    }
+
+    /// Append a URI line.
+    pub fn write_uri<'a>(&mut self, uri: impl Into<Cow<'a, str>>) -> io::Result<usize> {
+        self.writer.write_uri(uri)
+    }
+
+    /// Atomically replace the playlist on disk with the existing contents plus all appended lines.
+    pub fn commit(self) -> io::Result<()> {
+        self.commit_inner(false)
+    }
+
+    /// Atomically replace the playlist on disk with the existing contents plus all appended lines,
+    /// followed by `#EXT-X-ENDLIST`.
+    pub fn commit_with_endlist(self) -> io::Result<()> {
+        self.commit_inner(true)
+    }
+
+    fn commit_inner(self, endlist: bool) -> io::Result<()> {
+        let metadata = fs::metadata(&self.path)?;
+        if metadata.len() != self.original_len || metadata.modified().ok() != self.original_modified
+        {
+            return Err(io::Error::other(
+                "playlist was modified since it was opened for appending",
+            ));
+        }
+        replace_atomically(&self.path, &unique_temp_path(&self.path), true, |file| {
+            self.write_all_to(file, endlist)
+        })
+    }
+
+    fn write_all_to(&self, file: &mut File, endlist: bool) -> io::Result<()> {
+        file.write_all(&self.existing)?;
+        file.write_all(self.writer.get_ref())?;
+        if endlist {
+            file.write_all(ENDLIST_LINE)?;
+            file.write_all(if self.uses_crlf { b"\r\n" } else { b"\n" })?;
+        }
+        Ok(())
+    }
+}
+
+// Removes the last non-blank line if it is `#EXT-X-ENDLIST`, returning whether it was removed.
+fn remove_trailing_endlist(bytes: &mut Vec<u8>) -> bool {
+    let content_end = bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let line_start = bytes[..content_end]
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let is_endlist = &bytes[line_start..content_end] == ENDLIST_LINE;
+    if is_endlist {
+        bytes.truncate(line_start);
+    }
+    is_endlist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "hls-tags")]
+    use crate::tag::hls::{Inf, Part};
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    struct TestFile(PathBuf);
+    impl TestFile {
+        fn new(contents: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "quick-m3u8-append-writer-test-{}-{}.m3u8",
+                std::process::id(),
+                FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            fs::write(&path, contents).unwrap();
+            Self(path)
+        }
+
+        fn read(&self) -> String {
+            fs::read_to_string(&self.0).unwrap()
+        }
+    }
+    impl Drop for TestFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn append_without_endlist_should_keep_existing_contents() {
+        let file = TestFile::new("#EXTM3U\n#EXTINF:4\nsegment_1.mp4\n");
+        let mut writer = AppendWriter::open(&file.0).unwrap();
+        assert!(!writer.had_endlist());
+        writer.write_line(HlsLine::from(Inf::new(4.0, ""))).unwrap();
+        writer.write_uri("segment_2.mp4").unwrap();
+        writer.commit().unwrap();
+        assert_eq!(
+            "#EXTM3U\n#EXTINF:4\nsegment_1.mp4\n#EXTINF:4\nsegment_2.mp4\n",
+            file.read()
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn append_should_remove_previous_endlist_and_optionally_re_add() {
+        let file = TestFile::new("#EXTM3U\n#EXTINF:4\nsegment_1.mp4\n#EXT-X-ENDLIST\n\n");
+        let mut writer = AppendWriter::open(&file.0).unwrap();
+        assert!(writer.had_endlist());
+        writer
+            .write_line(HlsLine::from(
+                Part::builder()
+                    .with_uri("part_2.0.mp4")
+                    .with_duration(1.0)
+                    .with_independent()
+                    .finish(),
+            ))
+            .unwrap();
+        writer.commit_with_endlist().unwrap();
+        assert_eq!(
+            concat!(
+                "#EXTM3U\n",
+                "#EXTINF:4\n",
+                "segment_1.mp4\n",
+                "#EXT-X-PART:URI=\"part_2.0.mp4\",DURATION=1,INDEPENDENT=YES\n",
+                "#EXT-X-ENDLIST\n",
+            ),
+            file.read()
+        );
+    }
+
+    #[test]
+    fn append_should_preserve_crlf_and_bom() {
+        let file = TestFile::new("\u{FEFF}#EXTM3U\r\n#EXTINF:4\r\nsegment_1.mp4\r\n#EXT-X-ENDLIST");
+        let mut writer = AppendWriter::open(&file.0).unwrap();
+        assert!(writer.had_endlist());
+        assert!(writer.uses_crlf());
+        writer.write_comment(" appended").unwrap();
+        writer.write_uri("segment_2.mp4").unwrap();
+        writer.commit().unwrap();
+        assert_eq!(
+            "\u{FEFF}#EXTM3U\r\n#EXTINF:4\r\nsegment_1.mp4\r\n# appended\r\nsegment_2.mp4\r\n",
+            file.read()
+        );
+    }
+
+    #[test]
+    fn append_should_add_missing_final_new_line() {
+        let file = TestFile::new("#EXTM3U\nsegment_1.mp4");
+        let mut writer = AppendWriter::open(&file.0).unwrap();
+        writer.write_uri("segment_2.mp4").unwrap();
+        writer.commit().unwrap();
+        assert_eq!("#EXTM3U\nsegment_1.mp4\nsegment_2.mp4\n", file.read());
+    }
+
+    #[test]
+    fn commit_should_fail_if_file_changed_since_open() {
+        let file = TestFile::new("#EXTM3U\n");
+        let mut writer = AppendWriter::open(&file.0).unwrap();
+        writer.write_uri("segment_1.mp4").unwrap();
+        fs::write(&file.0, "#EXTM3U\nother.mp4\n").unwrap();
+        assert!(writer.commit().is_err());
+        assert_eq!("#EXTM3U\nother.mp4\n", file.read());
+    }
+
+    #[test]
+    fn commit_should_not_be_blocked_by_stale_temporary_file() {
+        let file = TestFile::new("#EXTM3U\n");
+        let mut stale_name = std::ffi::OsString::from(".");
+        stale_name.push(file.0.file_name().unwrap());
+        stale_name.push(".tmp");
+        let stale = TestFile(file.0.with_file_name(stale_name));
+        fs::write(&stale.0, "partial").unwrap();
+        let mut writer = AppendWriter::open(&file.0).unwrap();
+        writer.write_uri("segment_1.mp4").unwrap();
+        writer.commit().unwrap();
+        assert_eq!("#EXTM3U\nsegment_1.mp4\n", file.read());
+    }
+
+    #[test]
+    fn endlist_not_at_end_should_not_be_removed() {
+        let mut bytes = b"#EXTM3U\n#EXT-X-ENDLIST\nsegment.mp4\n".to_vec();
+        assert!(!remove_trailing_endlist(&mut bytes));
+        assert_eq!(b"#EXTM3U\n#EXT-X-ENDLIST\nsegment.mp4\n".to_vec(), bytes);
+    }
+}
@@ -0,0 +1,191 @@
+//! Arena allocation for attribute values (available with the `arena` feature).
+//!
+//! Every `set_*` method on the tag types takes an `impl Into<Cow<'a, str>>`, and so, when a pass
+//! over a playlist sets many attributes, each newly formatted value is typically an owned `String`
+//! (a small heap allocation per attribute). For services that rewrite a very high volume of
+//! playlists, the churn in the allocator can become significant.
+//!
+//! [`StrArena`] is a bump allocator for string slices. Values are copied into large chunks owned
+//! by the arena, and the returned `&str` can be passed to any `set_*` method, where it becomes a
+//! `Cow::Borrowed` (so no further allocation happens for the value). The arena is intended to be
+//! owned by the pass (for example, one arena per worker thread), and [`StrArena::reset`] allows the
+//! chunks to be re-used for the next pass, such that, once warmed up, setting attribute values
+//! does not allocate at all.
+//!
+//! For example:
+//! ```
+//! # use quick_m3u8::{HlsLine, Reader, Writer, arena::StrArena, config::ParsingOptions};
+//! # use quick_m3u8::tag::{KnownTag, hls};
+//! const PLAYLIST: &str = "#EXTM3U\n#EXT-X-MAP:URI=\"init.mp4\"\n#EXTINF:4\nsegment.mp4\n";
+//! let mut arena = StrArena::new();
+//! for token in ["abc", "def"] {
+//!     let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+//!     let mut writer = Writer::new(Vec::new());
+//!     while let Some(line) = reader.read_line()? {
+//!         let line = match line {
+//!             HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Map(mut map))) => {
+//!                 let uri = arena.alloc_display(format_args!("{}?token={token}", map.uri()));
+//!                 map.set_uri(uri);
+//!                 HlsLine::from(map)
+//!             }
+//!             line => line,
+//!         };
+//!         writer.write_line(line)?;
+//!     }
+//!     assert!(String::from_utf8_lossy(&writer.into_inner()).contains(&format!("token={token}")));
+//!     // All values set in this pass have been written, so the arena can be re-used.
+//!     arena.reset();
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt::{Display, Write},
+};
+
+/// The default size of each chunk of the arena in bytes.
+pub const DEFAULT_CHUNK_SIZE: usize = 16 * 1024;
+
+/// A bump allocator for string slices.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Debug)]
+pub struct StrArena {
+    // The capacity of each chunk is fixed on creation and never exceeded, meaning that the bytes of
+    // a chunk never move (even when `chunks` itself is re-allocated), which is what allows handing
+    // out references into them while more values are being allocated.
+    chunks: RefCell<Vec<Vec<u8>>>,
+    current: Cell<usize>,
+    scratch: RefCell<String>,
+    chunk_size: usize,
+}
+
+impl Default for StrArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StrArena {
+    /// Creates an arena with the [`DEFAULT_CHUNK_SIZE`].
+    pub fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates an arena with the given chunk size (in bytes). Values larger than the chunk size are
+    /// given a chunk of their own.
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        Self {
+            chunks: RefCell::new(Vec::new()),
+            current: Cell::new(0),
+            scratch: RefCell::new(String::new()),
+            chunk_size: chunk_size.max(1),
+        }
+    }
+
+    /// Copies the string into the arena.
+    #[allow(unsafe_code)]
+    pub fn alloc_str(&self, value: &str) -> &str {
+        if value.is_empty() {
+            return "";
+        }
+        let mut chunks = self.chunks.borrow_mut();
+        let mut index = self.current.get();
+        while let Some(chunk) = chunks.get(index) {
+            if chunk.capacity() - chunk.len() >= value.len() {
+                break;
+            }
+            index += 1;
+        }
+        if index == chunks.len() {
+            chunks.push(Vec::with_capacity(self.chunk_size.max(value.len())));
+        }
+        // Only move on from the current chunk once it is (close to) full, so that a large value
+        // that needed a later chunk does not waste the space left in the current one.
+        if chunks[self.current.get()].capacity() - chunks[self.current.get()].len() < 64 {
+            self.current.set(index);
+        }
+        let chunk = &mut chunks[index];
+        let start = chunk.len();
+        chunk.extend_from_slice(value.as_bytes());
+        let bytes = &chunk[start..];
+        // SAFETY: The bytes were copied from a `&str`, and so are valid UTF-8. The capacity of the
+        // chunk was checked above, and so extending it did not re-allocate, meaning that the bytes
+        // do not move for as long as the chunk exists. Chunks are only dropped or cleared with
+        // `&mut self` (see `reset`), and so not while any `&self` borrow (which the returned slice
+        // is tied to) is alive.
+        unsafe {
+            let bytes = std::slice::from_raw_parts(bytes.as_ptr(), bytes.len());
+            std::str::from_utf8_unchecked(bytes)
+        }
+    }
+
+    /// Formats the value into the arena.
+    ///
+    /// The value is formatted into a scratch buffer that is re-used between calls, and so, unlike
+    /// `alloc_str(&value.to_string())`, this does not allocate once the arena is warmed up.
+    pub fn alloc_display(&self, value: impl Display) -> &str {
+        let mut scratch = self.scratch.borrow_mut();
+        scratch.clear();
+        // Writing to a `String` cannot fail.
+        let _ = write!(scratch, "{value}");
+        self.alloc_str(&scratch)
+    }
+
+    /// Clears the arena, keeping the chunks for re-use.
+    pub fn reset(&mut self) {
+        for chunk in self.chunks.get_mut() {
+            chunk.clear();
+        }
+        self.current.set(0);
+    }
+
+    /// The number of bytes currently allocated in the arena.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(Vec::len).sum()
+    }
+
+    /// The number of bytes reserved by the chunks of the arena.
+    pub fn capacity(&self) -> usize {
+        self.chunks.borrow().iter().map(Vec::capacity).sum()
+    }
+
+    /// The number of chunks held by the arena.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.borrow().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn values_should_remain_valid_while_new_chunks_are_added() {
+        let arena = StrArena::with_chunk_size(8);
+        let values: Vec<&str> = (0..100).map(|n| arena.alloc_display(n * 1000)).collect();
+        let large = arena.alloc_str("a value that is larger than the chunk");
+        for (n, value) in values.iter().enumerate() {
+            assert_eq!((n * 1000).to_string(), *value);
+        }
+        assert_eq!("a value that is larger than the chunk", large);
+        assert_eq!("", arena.alloc_str(""));
+        assert!(arena.chunk_count() > 1);
+    }
+
+    #[test]
+    fn reset_should_re_use_chunks() {
+        let mut arena = StrArena::with_chunk_size(1024);
+        for _ in 0..3 {
+            for n in 0..100 {
+                arena.alloc_display(format_args!("seg_{n}.ts"));
+            }
+            assert_eq!(1, arena.chunk_count());
+            assert_eq!(1024, arena.capacity());
+            arena.reset();
+            assert_eq!(0, arena.allocated_bytes());
+        }
+    }
+}
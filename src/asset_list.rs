@@ -0,0 +1,218 @@
+//! Parsing of HLS Interstitial asset lists (available with the `asset-list` feature).
+//!
+//! An interstitial `EXT-X-DATERANGE` identifies its content either with an `X-ASSET-URI` (the URI
+//! of a single asset) or with an `X-ASSET-LIST` (the URI of a JSON document listing the assets to
+//! play in order), as defined in [Appendix D]. This module parses that JSON document into an
+//! [`AssetList`], and [`resolve_assets`] (also available as
+//! [`crate::tag::hls::InterstitialExtensionAttributes::resolve_assets`]) handles both cases in the
+//! same way, so that a client only has to deal with the resulting [`InterstitialAssets`].
+//!
+//! For example:
+//! ```
+//! # use quick_m3u8::asset_list::{Asset, AssetList, InterstitialAssets};
+//! # use quick_m3u8::tag::hls::{Daterange, INTERSTITIAL_CLASS};
+//! let fetch = |uri: &str| match uri {
+//!     "ads.json" => Ok(br#"{"ASSETS":[{"URI":"ad1.m3u8","DURATION":15},{"URI":"ad2.m3u8","DURATION":10.5}]}"#.to_vec()),
+//!     _ => Err(std::io::Error::from(std::io::ErrorKind::NotFound)),
+//! };
+//! let mut daterange = Daterange::builder()
+//!     .with_id("ad")
+//!     .with_class(INTERSTITIAL_CLASS)
+//!     .finish();
+//! daterange
+//!     .interstitial_attributes_mut()
+//!     .expect("class is interstitial")
+//!     .set_asset_list("ads.json");
+//!
+//! let assets = daterange
+//!     .interstitial_attributes()
+//!     .expect("class is interstitial")
+//!     .resolve_assets(fetch)?;
+//! let InterstitialAssets::List(asset_list) = assets else {
+//!     panic!("expected asset list");
+//! };
+//! assert_eq!(
+//!     vec![
+//!         Asset { uri: "ad1.m3u8".to_string(), duration: 15.0 },
+//!         Asset { uri: "ad2.m3u8".to_string(), duration: 10.5 },
+//!     ],
+//!     asset_list.assets
+//! );
+//! assert_eq!(25.5, asset_list.duration());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [Appendix D]: https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#appendix-D
+
+use crate::{
+    error::{AssetListError, ResolveAssetsError},
+    tag::hls::InterstitialExtensionAttributes,
+};
+use serde::Deserialize;
+
+/// An asset list document, as referenced by the `X-ASSET-LIST` attribute of an interstitial.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct AssetList {
+    /// The assets to be played, in order (from the `ASSETS` array).
+    pub assets: Vec<Asset>,
+}
+
+impl AssetList {
+    /// The sum of the durations of the assets, in seconds.
+    pub fn duration(&self) -> f64 {
+        self.assets.iter().map(|asset| asset.duration).sum()
+    }
+}
+
+/// An asset in an [`AssetList`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct Asset {
+    /// The URI of the Multivariant Playlist of the asset (the `URI` member), which is relative to
+    /// the URI of the asset list.
+    pub uri: String,
+    /// The duration of the asset in seconds (the `DURATION` member).
+    pub duration: f64,
+}
+
+/// The assets of an interstitial, as provided by [`resolve_assets`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum InterstitialAssets<'a> {
+    /// The single asset identified by the `X-ASSET-URI` attribute.
+    Uri(&'a str),
+    /// The asset list fetched from the `X-ASSET-LIST` attribute.
+    List(AssetList),
+}
+
+/// Parses an asset list from its JSON text.
+///
+/// The document must be a JSON object with an `ASSETS` array, where each element is an object with
+/// a string `URI` and a number `DURATION` (which must be finite and not negative). Any other
+/// members (of the document or of the assets) are ignored.
+pub fn parse(json: &[u8]) -> Result<AssetList, AssetListError> {
+    let document = serde_json::from_slice::<AssetListDocument>(json)
+        .map_err(|error| AssetListError::InvalidJson(error.to_string()))?;
+    let assets = document
+        .assets
+        .into_iter()
+        .enumerate()
+        .map(|(index, asset)| {
+            if asset.duration.is_finite() && asset.duration >= 0.0 {
+                Ok(Asset {
+                    uri: asset.uri,
+                    duration: asset.duration,
+                })
+            } else {
+                Err(AssetListError::InvalidDuration(index))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(AssetList { assets })
+}
+
+/// Resolves the assets of an interstitial, fetching and parsing the asset list where the
+/// `X-ASSET-LIST` attribute is used, so that both cases can be handled in the same way.
+///
+/// The specification requires that exactly one of `X-ASSET-URI` or `X-ASSET-LIST` is present, and
+/// an error is returned otherwise. As with [`crate::playlist::resolve_session_data`], the library
+/// does not perform any I/O itself: `fetch` is provided the `X-ASSET-LIST` URI (which may need to be
+/// resolved against the URI of the Media Playlist) and is only called when that attribute is used.
+pub fn resolve_assets<'b, E>(
+    attributes: &InterstitialExtensionAttributes<'_, 'b>,
+    fetch: impl FnOnce(&str) -> Result<Vec<u8>, E>,
+) -> Result<InterstitialAssets<'b>, ResolveAssetsError<E>> {
+    match (attributes.asset_uri(), attributes.asset_list()) {
+        (Some(uri), None) => Ok(InterstitialAssets::Uri(uri)),
+        (None, Some(list_uri)) => {
+            let json = fetch(list_uri).map_err(ResolveAssetsError::Fetch)?;
+            parse(&json)
+                .map(InterstitialAssets::List)
+                .map_err(ResolveAssetsError::AssetList)
+        }
+        (None, None) => Err(ResolveAssetsError::MissingAssetUriAndList),
+        (Some(_), Some(_)) => Err(ResolveAssetsError::AssetUriAndList),
+    }
+}
+
+#[derive(Deserialize)]
+struct AssetListDocument {
+    #[serde(rename = "ASSETS")]
+    assets: Vec<AssetObject>,
+}
+
+#[derive(Deserialize)]
+struct AssetObject {
+    #[serde(rename = "URI")]
+    uri: String,
+    #[serde(rename = "DURATION")]
+    duration: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::hls::{Daterange, INTERSTITIAL_CLASS};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_should_ignore_unknown_members() {
+        let json = br#"{
+            "ASSETS": [{ "URI": "a.m3u8", "DURATION": 30, "X-COM-EXAMPLE-AD-ID": "1234" }],
+            "X-COM-EXAMPLE-BEACON": "https://example.com/beacon"
+        }"#;
+        assert_eq!(
+            Ok(AssetList {
+                assets: vec![Asset {
+                    uri: "a.m3u8".to_string(),
+                    duration: 30.0
+                }]
+            }),
+            parse(json)
+        );
+        assert_eq!(Ok(AssetList::default()), parse(br#"{"ASSETS":[]}"#));
+    }
+
+    #[test]
+    fn parse_should_fail_on_invalid_documents() {
+        assert!(matches!(
+            parse(br#"{"assets":[]}"#),
+            Err(AssetListError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            parse(br#"{"ASSETS":[{"URI":"a.m3u8"}]}"#),
+            Err(AssetListError::InvalidJson(_))
+        ));
+        assert_eq!(
+            Err(AssetListError::InvalidDuration(1)),
+            parse(br#"{"ASSETS":[{"URI":"a.m3u8","DURATION":1},{"URI":"b.m3u8","DURATION":-1}]}"#)
+        );
+    }
+
+    #[test]
+    fn resolve_assets_should_require_exactly_one_of_uri_and_list() {
+        let fetch = |_: &str| -> Result<Vec<u8>, ()> { panic!("should not fetch") };
+        let mut daterange = Daterange::builder()
+            .with_id("ad")
+            .with_class(INTERSTITIAL_CLASS)
+            .finish();
+        assert_eq!(
+            Err(ResolveAssetsError::MissingAssetUriAndList),
+            resolve_assets(&daterange.interstitial_attributes().unwrap(), fetch)
+        );
+        daterange
+            .interstitial_attributes_mut()
+            .unwrap()
+            .set_asset_uri("ad.m3u8");
+        assert_eq!(
+            Ok(InterstitialAssets::Uri("ad.m3u8")),
+            resolve_assets(&daterange.interstitial_attributes().unwrap(), fetch)
+        );
+        daterange
+            .interstitial_attributes_mut()
+            .unwrap()
+            .set_asset_list("ads.json");
+        assert_eq!(
+            Err(ResolveAssetsError::AssetUriAndList),
+            resolve_assets(&daterange.interstitial_attributes().unwrap(), fetch)
+        );
+    }
+}
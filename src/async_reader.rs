@@ -0,0 +1,259 @@
+use crate::{
+    config::ParsingOptions,
+    error::ReaderIoError,
+    line::HlsLine,
+    line_buffer::LineBuffer,
+    reader::is_at_line_limit,
+    tag::{CustomTag, NoCustomTag},
+};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+/// A reader that parses lines of input from a [`tokio::io::AsyncBufRead`] (available with the
+/// `tokio` feature).
+///
+/// This is the asynchronous counterpart to [`crate::Reader`], for services that proxy playlists
+/// and would otherwise have to buffer the entire response body before parsing it. Each line is
+/// read into a buffer owned by the reader, and the [`HlsLine`] that is provided borrows from that
+/// buffer (so parsing remains zero-copy). As a consequence, the line must be dropped before the
+/// next line is read.
+///
+/// The [`ParsingOptions`] have the same meaning as they do for the [`crate::Reader`], including
+/// the [`crate::config::ErrorRecovery`] behavior and comment attachment. See
+/// [`crate::Reader::from_reader`] for the blocking equivalent.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{AsyncReader, HlsLine, config::ParsingOptions};
+/// # use quick_m3u8::tag::{KnownTag, hls};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let body: &[u8] = b"#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:4\nsegment.ts\n";
+/// let mut reader = AsyncReader::new(body, ParsingOptions::default());
+/// let mut uris = Vec::new();
+/// while let Some(line) = reader.read_line().await? {
+///     if let HlsLine::Uri(uri) = line {
+///         uris.push(uri.to_string());
+///     }
+/// }
+/// assert_eq!(vec!["segment.ts"], uris);
+/// # Ok(())
+/// # }
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(example()).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct AsyncReader<R> {
+    inner: R,
+    options: ParsingOptions,
+    buffer: LineBuffer,
+    lines_read: usize,
+}
+
+impl<R> AsyncReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    /// Creates a reader over the provided input.
+    pub fn new(inner: R, options: ParsingOptions) -> Self {
+        Self {
+            inner,
+            options,
+            buffer: LineBuffer::default(),
+            lines_read: 0,
+        }
+    }
+
+    /// Indicates whether the input data started with a UTF-8 byte order mark (BOM).
+    ///
+    /// This is only known once the first line has been read. See [`crate::Reader::has_bom`] for
+    /// more information.
+    pub fn has_bom(&self) -> bool {
+        self.buffer.has_bom()
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Indicates whether the reader has stopped because of an error (only possible when
+    /// configured with [`crate::config::ErrorRecovery::Stop`]).
+    pub fn is_stopped(&self) -> bool {
+        self.buffer.stopped
+    }
+
+    /// Allows the reader to continue after it has stopped because of an error.
+    ///
+    /// When the reader stopped because it reached the line limit (see
+    /// [`crate::config::ParsingOptionsBuilder::with_max_lines`]), the reader is allowed to read up
+    /// to the limit again.
+    pub fn resume(&mut self) {
+        if self.buffer.resume() {
+            self.lines_read = 0;
+        }
+    }
+
+    /// The number of lines that have been read (since the reader was created, or since it was last
+    /// resumed after reaching the line limit).
+    pub fn lines_read(&self) -> usize {
+        self.lines_read
+    }
+
+    /// Reads a single HLS line from the input.
+    ///
+    /// `None` is provided once the input has been read to the end.
+    pub async fn read_line(&mut self) -> Result<Option<HlsLine<'_>>, ReaderIoError> {
+        self.read_line_with_custom::<NoCustomTag>().await
+    }
+
+    /// Reads a single HLS line from the input with support for the provided custom tag.
+    ///
+    /// This method differs from [`Self::read_line`] as it allows the user to provide their own
+    /// custom tag implementation for parsing (see [`crate::Reader::with_custom_from_str`]).
+    pub async fn read_line_with_custom<'a, Custom>(
+        &'a mut self,
+    ) -> Result<Option<HlsLine<'a, Custom>>, ReaderIoError>
+    where
+        Custom: CustomTag<'a>,
+    {
+        let buffer = &mut self.buffer;
+        if buffer.stopped {
+            return Ok(None);
+        }
+        if is_at_line_limit(&self.options, self.lines_read) {
+            if !buffer.has_next_line {
+                buffer.has_next_line =
+                    read_until_new_line(&mut self.inner, &mut buffer.next_line).await?;
+            }
+            return if buffer.has_next_line {
+                Err(buffer.line_limit_error())
+            } else {
+                Ok(None)
+            };
+        }
+        self.lines_read += 1;
+        if !buffer.take_next_line()
+            && !read_until_new_line(&mut self.inner, &mut buffer.line).await?
+        {
+            return Ok(None);
+        }
+        buffer.strip_bom();
+        if buffer.wants_next_line(&self.options) {
+            buffer.has_next_line =
+                read_until_new_line(&mut self.inner, &mut buffer.next_line).await?;
+        }
+        buffer.parse(&self.options)
+    }
+}
+
+// Reads the next line (including the new line characters) into the buffer, indicating whether
+// there was a line to read.
+async fn read_until_new_line<R>(inner: &mut R, buffer: &mut Vec<u8>) -> std::io::Result<bool>
+where
+    R: AsyncBufRead + Unpin,
+{
+    buffer.clear();
+    Ok(inner.read_until(b'\n', buffer).await? > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Reader,
+        config::ErrorRecovery,
+        tag::{KnownTag, hls},
+    };
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = concat!(
+        "\u{FEFF}#EXTM3U\r\n",
+        "#EXT-X-TARGETDURATION:4\n",
+        "# attached\n",
+        "#EXTINF:4\n",
+        "\n",
+        "# not attached\n",
+        "segment.ts\n",
+        "#EXT\n",
+        "# last",
+    );
+
+    #[tokio::test]
+    async fn lines_should_match_reader() {
+        let options = ParsingOptions::builder()
+            .with_parsing_for_all_tags()
+            .with_comment_attachment()
+            .build();
+        let mut reader = Reader::from_str(PLAYLIST, options.clone());
+        let mut async_reader = AsyncReader::new(PLAYLIST.as_bytes(), options);
+        loop {
+            let expected = reader.read_line().map_err(|e| e.errored_line.to_string());
+            let actual = async_reader.read_line().await.map_err(|e| match e {
+                ReaderIoError::Syntax { errored_line, .. } => {
+                    String::from_utf8_lossy(&errored_line).to_string()
+                }
+                ReaderIoError::Io(e) => e.to_string(),
+            });
+            assert_eq!(expected, actual);
+            if let Ok(None) = actual {
+                break;
+            }
+        }
+        assert!(async_reader.has_bom());
+    }
+
+    #[tokio::test]
+    async fn max_lines_should_stop_reader_until_resumed() {
+        let options = ParsingOptions::builder().with_max_lines(1).build();
+        let mut reader = AsyncReader::new(b"#EXTM3U\n0.ts\n" as &[u8], options);
+        assert!(reader.read_line().await.unwrap().is_some());
+        assert!(matches!(
+            reader.read_line().await,
+            Err(ReaderIoError::Syntax { errored_line, .. }) if errored_line == b"0.ts"
+        ));
+        assert!(reader.read_line().await.unwrap().is_none());
+        reader.resume();
+        assert_eq!(
+            Some(HlsLine::Uri("0.ts".into())),
+            reader.read_line().await.unwrap()
+        );
+        assert!(reader.read_line().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn resume_should_skip_errored_line_when_stopped_at_line_limit_by_error() {
+        let options = ParsingOptions::builder()
+            .with_parsing_for_all_tags()
+            .with_error_recovery(ErrorRecovery::Stop)
+            .with_max_lines(1)
+            .build();
+        let mut reader = AsyncReader::new(b"#EXT-X-\xFF\xFE\n0.ts\n" as &[u8], options);
+        assert!(reader.read_line().await.is_err());
+        reader.resume();
+        assert!(matches!(
+            reader.read_line().await,
+            Err(ReaderIoError::Syntax { errored_line, .. }) if errored_line == b"0.ts"
+        ));
+        reader.resume();
+        assert_eq!(
+            Some(HlsLine::Uri("0.ts".into())),
+            reader.read_line().await.unwrap()
+        );
+        assert!(reader.read_line().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn stop_recovery_should_stop_until_resumed() {
+        let options = ParsingOptions::builder()
+            .with_parsing_for_all_tags()
+            .with_error_recovery(ErrorRecovery::Stop)
+            .build();
+        let mut reader = AsyncReader::new(b"#EXT\n#EXTINF:4\n" as &[u8], options);
+        assert!(reader.read_line().await.is_err());
+        assert_eq!(None, reader.read_line().await.unwrap());
+        assert!(reader.is_stopped());
+        reader.resume();
+        assert!(matches!(
+            reader.read_line().await.unwrap(),
+            Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(_))))
+        ));
+    }
+}
@@ -0,0 +1,396 @@
+//! Authoring lints (available with the `authoring-lint` feature).
+//!
+//! The library validates tags against the HLS specification ([draft-pantos-hls-rfc8216bis]), but a
+//! playlist can be valid while still falling short of the recommendations in Apple's [HLS
+//! Authoring Specification for Apple Devices]. This module provides an opt-in set of checks for
+//! some of those recommendations, intended for tooling that verifies that content is ready to be
+//! submitted (e.g. to an App Store review, or to a distribution partner).
+//!
+//! Every [`LintWarning`] identifies the [`LintRule`] that was broken, and each rule belongs to a
+//! [`LintCategory`] so that warnings can be grouped (or filtered) in reports. The lints are
+//! recommendations rather than requirements, and so a warning does not mean that the playlist will
+//! fail to play.
+//!
+//! For example:
+//! ```
+//! # use quick_m3u8::authoring::{lint_multivariant_playlist, LintRule, Presentation};
+//! const PLAYLIST: &str = r#"#EXTM3U
+//! #EXT-X-INDEPENDENT-SEGMENTS
+//! #EXT-X-STREAM-INF:BANDWIDTH=1280000,CODECS="avc1.4d401f,mp4a.40.2",RESOLUTION=640x360,FRAME-RATE=30
+//! low.m3u8
+//! "#;
+//!
+//! let warnings = lint_multivariant_playlist(PLAYLIST, Presentation::Vod);
+//! assert_eq!(
+//!     vec![LintRule::MissingIFramePlaylists, LintRule::MissingAverageBandwidth],
+//!     warnings.iter().map(|w| w.rule).collect::<Vec<_>>()
+//! );
+//! assert_eq!(Some(3), warnings[1].line_number);
+//! ```
+//!
+//! [draft-pantos-hls-rfc8216bis]: https://datatracker.ietf.org/doc/draft-pantos-hls-rfc8216bis/
+//! [HLS Authoring Specification for Apple Devices]: https://developer.apple.com/documentation/http-live-streaming/hls-authoring-specification-for-apple-devices
+
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    tag::{
+        KnownTag,
+        hls::{self, TagName, VideoRange},
+    },
+};
+use std::fmt::{Display, Formatter};
+
+/// The target duration recommended by the authoring specification.
+pub const RECOMMENDED_TARGET_DURATION: u64 = 6;
+
+/// The codecs (by sample entry) that are capable of carrying HDR video.
+const HDR_CAPABLE_CODECS: [&str; 5] = ["hvc1", "hev1", "dvh1", "dvhe", "av01"];
+
+/// Whether the content described by a Multivariant Playlist is on-demand or live.
+///
+/// Some recommendations only apply to one kind of presentation, and this cannot be known from the
+/// Multivariant Playlist alone.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Presentation {
+    /// Video on demand.
+    Vod,
+    /// Live (including event) content.
+    Live,
+}
+
+/// A recommendation from the authoring specification that a playlist does not follow.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LintWarning {
+    /// The (one-based) line number of the offending line, or `None` when the warning concerns the
+    /// playlist as a whole (e.g. a tag that is missing).
+    pub line_number: Option<usize>,
+    /// The rule that was broken.
+    pub rule: LintRule,
+}
+
+impl LintWarning {
+    /// The category of the rule that was broken.
+    pub fn category(&self) -> LintCategory {
+        self.rule.category()
+    }
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.line_number {
+            Some(line_number) => write!(f, "line {line_number}: {}", self.rule),
+            None => write!(f, "{}", self.rule),
+        }
+    }
+}
+
+/// A rule checked by the lints.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LintRule {
+    /// The `EXT-X-TARGETDURATION` of a Media Playlist is not the recommended 6 seconds.
+    TargetDuration {
+        /// The target duration found.
+        target_duration: u64,
+    },
+    /// A Media Playlist that ends with `EXT-X-ENDLIST` has no `EXT-X-PLAYLIST-TYPE`.
+    MissingPlaylistType,
+    /// A Multivariant Playlist has no `EXT-X-INDEPENDENT-SEGMENTS`.
+    MissingIndependentSegments,
+    /// A VOD Multivariant Playlist has no `EXT-X-I-FRAME-STREAM-INF` (and so no trick-play).
+    MissingIFramePlaylists,
+    /// An `EXT-X-STREAM-INF` has no `AVERAGE-BANDWIDTH`.
+    MissingAverageBandwidth,
+    /// An `EXT-X-STREAM-INF` has no `CODECS`.
+    MissingCodecs,
+    /// An `EXT-X-STREAM-INF` with a `RESOLUTION` (i.e. a video variant) has no `FRAME-RATE`.
+    MissingFrameRate,
+    /// An `EXT-X-STREAM-INF` has `SUPPLEMENTAL-CODECS` (e.g. Dolby Vision) but no `VIDEO-RANGE`.
+    SupplementalCodecsWithoutVideoRange,
+    /// An `EXT-X-STREAM-INF` has a `VIDEO-RANGE` of `PQ` or `HLG`, but none of the `CODECS` are
+    /// capable of carrying HDR video (HEVC, Dolby Vision, or AV1).
+    HdrWithoutHdrCapableCodec,
+}
+
+impl LintRule {
+    /// The category that the rule belongs to.
+    pub fn category(&self) -> LintCategory {
+        match self {
+            Self::TargetDuration { .. } | Self::MissingPlaylistType => LintCategory::MediaPlaylist,
+            Self::MissingIndependentSegments | Self::MissingIFramePlaylists => {
+                LintCategory::MultivariantPlaylist
+            }
+            Self::MissingAverageBandwidth | Self::MissingCodecs | Self::MissingFrameRate => {
+                LintCategory::VariantAttributes
+            }
+            Self::SupplementalCodecsWithoutVideoRange | Self::HdrWithoutHdrCapableCodec => {
+                LintCategory::Hdr
+            }
+        }
+    }
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TargetDuration { target_duration } => write!(
+                f,
+                "target duration of {target_duration}s differs from the recommended {RECOMMENDED_TARGET_DURATION}s"
+            ),
+            Self::MissingPlaylistType => {
+                write!(f, "ended playlist should have EXT-X-PLAYLIST-TYPE")
+            }
+            Self::MissingIndependentSegments => {
+                write!(f, "playlist should have EXT-X-INDEPENDENT-SEGMENTS")
+            }
+            Self::MissingIFramePlaylists => {
+                write!(f, "VOD playlist should provide I-frame playlists")
+            }
+            Self::MissingAverageBandwidth => {
+                write!(f, "variant should have AVERAGE-BANDWIDTH")
+            }
+            Self::MissingCodecs => write!(f, "variant should have CODECS"),
+            Self::MissingFrameRate => write!(f, "video variant should have FRAME-RATE"),
+            Self::SupplementalCodecsWithoutVideoRange => {
+                write!(
+                    f,
+                    "variant with SUPPLEMENTAL-CODECS should have VIDEO-RANGE"
+                )
+            }
+            Self::HdrWithoutHdrCapableCodec => {
+                write!(f, "HDR variant should use an HDR capable codec")
+            }
+        }
+    }
+}
+
+/// How [`LintRule`]s are grouped.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum LintCategory {
+    /// Rules that apply to a Media Playlist as a whole.
+    MediaPlaylist,
+    /// Rules that apply to a Multivariant Playlist as a whole.
+    MultivariantPlaylist,
+    /// Rules that apply to the attributes describing each Variant Stream.
+    VariantAttributes,
+    /// Rules that apply to HDR video.
+    Hdr,
+}
+
+/// Lints a Media Playlist.
+///
+/// The warnings are provided in playlist order, followed by any warnings that concern the playlist
+/// as a whole. Lines that cannot be parsed are skipped.
+pub fn lint_media_playlist(playlist: &str) -> Vec<LintWarning> {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_tags([
+                TagName::Targetduration,
+                TagName::PlaylistType,
+                TagName::Endlist,
+            ])
+            .build(),
+    );
+    let mut warnings = Vec::new();
+    let mut playlist_type = false;
+    let mut ended = false;
+    let mut line_number = 0;
+    loop {
+        line_number += 1;
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => match tag {
+                hls::Tag::Targetduration(tag)
+                    if tag.target_duration() != RECOMMENDED_TARGET_DURATION =>
+                {
+                    warnings.push(LintWarning {
+                        line_number: Some(line_number),
+                        rule: LintRule::TargetDuration {
+                            target_duration: tag.target_duration(),
+                        },
+                    });
+                }
+                hls::Tag::PlaylistType(_) => playlist_type = true,
+                hls::Tag::Endlist(_) => ended = true,
+                _ => (),
+            },
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    if ended && !playlist_type {
+        warnings.push(LintWarning {
+            line_number: None,
+            rule: LintRule::MissingPlaylistType,
+        });
+    }
+    warnings
+}
+
+/// Lints a Multivariant Playlist.
+///
+/// The warnings concerning the playlist as a whole are provided first, followed by the warnings
+/// for each `EXT-X-STREAM-INF` in playlist order. Lines that cannot be parsed are skipped.
+pub fn lint_multivariant_playlist(playlist: &str, presentation: Presentation) -> Vec<LintWarning> {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_tags([
+                TagName::IndependentSegments,
+                TagName::StreamInf,
+                TagName::IFrameStreamInf,
+            ])
+            .build(),
+    );
+    let mut variant_warnings = Vec::new();
+    let mut independent_segments = false;
+    let mut i_frame_playlists = false;
+    let mut line_number = 0;
+    loop {
+        line_number += 1;
+        let tag = match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => tag,
+            Ok(Some(_)) | Err(_) => continue,
+            Ok(None) => break,
+        };
+        let stream_inf = match tag {
+            hls::Tag::IndependentSegments(_) => {
+                independent_segments = true;
+                continue;
+            }
+            hls::Tag::IFrameStreamInf(_) => {
+                i_frame_playlists = true;
+                continue;
+            }
+            hls::Tag::StreamInf(stream_inf) => stream_inf,
+            _ => continue,
+        };
+        let mut warn = |rule| {
+            variant_warnings.push(LintWarning {
+                line_number: Some(line_number),
+                rule,
+            })
+        };
+        if stream_inf.average_bandwidth().is_none() {
+            warn(LintRule::MissingAverageBandwidth);
+        }
+        if stream_inf.codecs().is_none() {
+            warn(LintRule::MissingCodecs);
+        }
+        if stream_inf.resolution().is_some() && stream_inf.frame_rate().is_none() {
+            warn(LintRule::MissingFrameRate);
+        }
+        let video_range = stream_inf.video_range();
+        if stream_inf.supplemental_codecs().is_some() && video_range.is_none() {
+            warn(LintRule::SupplementalCodecsWithoutVideoRange);
+        }
+        let hdr = matches!(
+            video_range.and_then(|range| range.known().copied()),
+            Some(VideoRange::Pq | VideoRange::Hlg)
+        );
+        if hdr
+            && let Some(codecs) = stream_inf.codecs()
+            && !codecs.split(',').any(|codec| {
+                HDR_CAPABLE_CODECS
+                    .iter()
+                    .any(|hdr_codec| codec.trim().starts_with(hdr_codec))
+            })
+        {
+            warn(LintRule::HdrWithoutHdrCapableCodec);
+        }
+    }
+    let mut warnings = Vec::new();
+    if !independent_segments {
+        warnings.push(LintWarning {
+            line_number: None,
+            rule: LintRule::MissingIndependentSegments,
+        });
+    }
+    if presentation == Presentation::Vod && !i_frame_playlists {
+        warnings.push(LintWarning {
+            line_number: None,
+            rule: LintRule::MissingIFramePlaylists,
+        });
+    }
+    warnings.extend(variant_warnings);
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn media_playlist_should_warn_on_target_duration_and_missing_playlist_type() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:10\n",
+            "#EXTINF:10\n",
+            "1.ts\n",
+            "#EXT-X-ENDLIST\n",
+        );
+        let warnings = lint_media_playlist(PLAYLIST);
+        assert_eq!(
+            vec![
+                LintWarning {
+                    line_number: Some(2),
+                    rule: LintRule::TargetDuration {
+                        target_duration: 10
+                    },
+                },
+                LintWarning {
+                    line_number: None,
+                    rule: LintRule::MissingPlaylistType,
+                },
+            ],
+            warnings
+        );
+        assert_eq!(
+            "line 2: target duration of 10s differs from the recommended 6s",
+            warnings[0].to_string()
+        );
+        assert_eq!(
+            Vec::<LintWarning>::new(),
+            lint_media_playlist(concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:6\n",
+                "#EXT-X-PLAYLIST-TYPE:VOD\n",
+                "#EXTINF:6\n",
+                "1.ts\n",
+                "#EXT-X-ENDLIST\n",
+            ))
+        );
+    }
+
+    #[test]
+    fn hdr_variants_should_be_checked_for_codecs_and_video_range() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1,AVERAGE-BANDWIDTH=1,CODECS=\"avc1.640028\",RESOLUTION=1920x1080,FRAME-RATE=30,VIDEO-RANGE=PQ\n",
+            "a.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1,AVERAGE-BANDWIDTH=1,CODECS=\"hvc1.2.4.L123.B0\",SUPPLEMENTAL-CODECS=\"dvh1.08.07/db4h\",RESOLUTION=1920x1080,FRAME-RATE=30\n",
+            "b.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1,AVERAGE-BANDWIDTH=1,CODECS=\"hvc1.2.4.L123.B0\",RESOLUTION=1920x1080,FRAME-RATE=30,VIDEO-RANGE=HLG\n",
+            "c.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1,RESOLUTION=1920x1080\n",
+            "d.m3u8\n",
+        );
+        let warnings = lint_multivariant_playlist(PLAYLIST, Presentation::Live);
+        assert_eq!(
+            vec![
+                (None, LintRule::MissingIndependentSegments),
+                (Some(2), LintRule::HdrWithoutHdrCapableCodec),
+                (Some(4), LintRule::SupplementalCodecsWithoutVideoRange),
+                (Some(8), LintRule::MissingAverageBandwidth),
+                (Some(8), LintRule::MissingCodecs),
+                (Some(8), LintRule::MissingFrameRate),
+            ],
+            warnings
+                .iter()
+                .map(|w| (w.line_number, w.rule))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(LintCategory::Hdr, warnings[1].category());
+    }
+}
@@ -3,67 +3,158 @@
 //! This module provides configuration options for [`crate::Reader`] along with helper API (such as
 //! [`ParsingOptionsBuilder`]) for constructing config options.
 
-use crate::tag::hls::TagName;
-use std::collections::HashSet;
-
-const ALL_KNOWN_HLS_TAG_NAMES: [TagName; 32] = [
-    TagName::M3u,
-    TagName::Version,
-    TagName::IndependentSegments,
-    TagName::Start,
-    TagName::Define,
-    TagName::Targetduration,
-    TagName::MediaSequence,
-    TagName::DiscontinuitySequence,
-    TagName::Endlist,
-    TagName::PlaylistType,
-    TagName::IFramesOnly,
-    TagName::PartInf,
-    TagName::ServerControl,
-    TagName::Inf,
-    TagName::Byterange,
-    TagName::Discontinuity,
-    TagName::Key,
-    TagName::Map,
-    TagName::ProgramDateTime,
-    TagName::Gap,
-    TagName::Bitrate,
-    TagName::Part,
-    TagName::Daterange,
-    TagName::Skip,
-    TagName::PreloadHint,
-    TagName::RenditionReport,
-    TagName::Media,
-    TagName::StreamInf,
-    TagName::IFrameStreamInf,
-    TagName::SessionData,
-    TagName::SessionKey,
-    TagName::ContentSteering,
-];
+#[cfg(feature = "hls-tags")]
+use crate::tag::hls;
+use crate::{
+    date::DateTimeParser,
+    tag::hls::{TagName, TagType},
+};
+use std::{collections::HashSet, sync::Arc};
 
 /// Parsing options for the [`crate::Reader`] to follow.
 ///
-/// For now the only option that can be set is `hls_tag_names_to_parse`. For convenience, a builder
-/// struct [ParsingOptionsBuilder] has been provided, to make constructing this struct easier.
+/// The options that can be set are `hls_tag_names_to_parse`, `error_recovery`,
+/// `comment_attachment`, `case_insensitive_tag_names`, `max_lines`,
+/// `leading_whitespace_trimming`, `uri_trailing_whitespace_trimming`, `date_time_parser`, and the
+/// tag transformers. For convenience, a builder struct [ParsingOptionsBuilder] has been provided, to
+/// make constructing this struct easier.
 #[derive(Debug, PartialEq, Clone)]
 pub struct ParsingOptions {
     hls_tag_names_to_parse: HashSet<TagName>,
+    error_recovery: ErrorRecovery,
+    comment_attachment: bool,
+    case_insensitive_tag_names: bool,
+    max_lines: Option<usize>,
+    leading_whitespace_trimming: bool,
+    uri_trailing_whitespace_trimming: bool,
+    date_time_parser: Option<SharedDateTimeParser>,
+    #[cfg(feature = "hls-tags")]
+    tag_transformers: Vec<(TagName, SharedTagTransformer)>,
+}
+
+// The parser is shared between clones of the options, and as a trait object cannot be compared by
+// value, parsers are only equal when they are the same instance.
+#[derive(Debug, Clone)]
+struct SharedDateTimeParser(Arc<dyn DateTimeParser>);
+
+impl PartialEq for SharedDateTimeParser {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Transforms HLS tags after they have been parsed by the [`crate::Reader`].
+///
+/// Transformers are registered per [`TagName`] via [`ParsingOptionsBuilder::with_tag_transformer`],
+/// and are applied to every tag of that name before the line is returned. This allows for global
+/// policies (e.g. clamping the `SCORE` of every Variant Stream) to be applied in one place, rather
+/// than by every consumer of the lines.
+///
+/// The trait is implemented for all functions (and closures) that take and return a tag, such that
+/// a plain function can be registered. Implementing the trait directly is useful when the
+/// transformer needs configuration. See [`ParsingOptionsBuilder::with_tag_transformer`] for an
+/// example.
+#[cfg(feature = "hls-tags")]
+pub trait TagTransformer: Send + Sync {
+    /// Transforms the tag. The tag provided is always of the name that the transformer was
+    /// registered for; however, the tag returned may be of any name.
+    fn transform<'a>(&self, tag: hls::Tag<'a>) -> hls::Tag<'a>;
+}
+
+#[cfg(feature = "hls-tags")]
+impl<F> TagTransformer for F
+where
+    F: for<'a> Fn(hls::Tag<'a>) -> hls::Tag<'a> + Send + Sync,
+{
+    fn transform<'a>(&self, tag: hls::Tag<'a>) -> hls::Tag<'a> {
+        self(tag)
+    }
+}
+
+// As with the date/time parser, transformers are shared between clones of the options and are only
+// equal when they are the same instance. Transformers are commonly plain functions, and so are not
+// required to implement `Debug`.
+#[cfg(feature = "hls-tags")]
+#[derive(Clone)]
+struct SharedTagTransformer(Arc<dyn TagTransformer>);
+
+#[cfg(feature = "hls-tags")]
+impl PartialEq for SharedTagTransformer {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(feature = "hls-tags")]
+impl std::fmt::Debug for SharedTagTransformer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TagTransformer")
+    }
 }
 
 impl Default for ParsingOptions {
     fn default() -> Self {
         Self {
-            hls_tag_names_to_parse: HashSet::from(ALL_KNOWN_HLS_TAG_NAMES),
+            hls_tag_names_to_parse: HashSet::from_iter(TagName::all().iter().copied()),
+            error_recovery: ErrorRecovery::default(),
+            comment_attachment: false,
+            case_insensitive_tag_names: false,
+            max_lines: None,
+            leading_whitespace_trimming: false,
+            uri_trailing_whitespace_trimming: false,
+            date_time_parser: None,
+            #[cfg(feature = "hls-tags")]
+            tag_transformers: Vec::new(),
         }
     }
 }
 
+/// What the [`crate::Reader`] should do after experiencing an error while reading a line (for
+/// example, invalid UTF-8 in the middle of a playlist).
+///
+/// In either case the error is returned from [`crate::Reader::read_line`], and the error exposes
+/// the raw data of the offending line.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ErrorRecovery {
+    /// Skip the offending line, so that the next call to `read_line` continues from the line after.
+    /// This allows for best-effort processing of corrupted data.
+    #[default]
+    SkipLine,
+    /// Stop reading, so that subsequent calls to `read_line` return `Ok(None)`. The remaining data
+    /// (starting with the offending line) is available via `Reader::into_inner`, and reading can be
+    /// continued from the line after the offending line via `Reader::resume`.
+    Stop,
+}
+
 impl ParsingOptions {
     /// Start a builder for constructing the `ParsingOptions`.
     pub fn builder() -> ParsingOptionsBuilder {
         ParsingOptionsBuilder::new()
     }
 
+    /// Creates the default options, except that only the provided tags are parsed.
+    ///
+    /// This is useful when the tags to parse are decided at runtime (e.g. from configuration). For
+    /// example:
+    /// ```
+    /// # use quick_m3u8::{config::ParsingOptions, tag::hls::TagName};
+    /// let configured = ["#EXT-X-TARGETDURATION", "#EXTINF"];
+    /// let names = configured
+    ///     .iter()
+    ///     .map(|name| name.parse::<TagName>())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// let options = ParsingOptions::from_tag_names(&names);
+    /// assert_eq!(2, options.hls_tag_names_to_parse().len());
+    /// assert!(options.hls_tag_names_to_parse().contains(&TagName::Inf));
+    /// # Ok::<(), quick_m3u8::error::ValidationError>(())
+    /// ```
+    pub fn from_tag_names(tag_names: &[TagName]) -> Self {
+        Self {
+            hls_tag_names_to_parse: tag_names.iter().copied().collect(),
+            ..Self::default()
+        }
+    }
+
     /// The tag names that will be parsed by the [`crate::Reader`].
     ///
     /// HLS tags that are not included in this list will be parsed as
@@ -72,12 +163,92 @@ impl ParsingOptions {
         &self.hls_tag_names_to_parse
     }
 
+    /// What the [`crate::Reader`] should do after experiencing an error while reading a line.
+    pub fn error_recovery(&self) -> ErrorRecovery {
+        self.error_recovery
+    }
+
+    /// Whether the [`crate::Reader`] attaches a comment line to the known HLS tag that immediately
+    /// follows it (see [`ParsingOptionsBuilder::with_comment_attachment`]).
+    pub fn comment_attachment(&self) -> bool {
+        self.comment_attachment
+    }
+
+    /// Whether the [`crate::Reader`] matches the `#EXT` prefix and the names of HLS tags without
+    /// regard to ASCII case (see [`ParsingOptionsBuilder::with_case_insensitive_tag_names`]).
+    pub fn case_insensitive_tag_names(&self) -> bool {
+        self.case_insensitive_tag_names
+    }
+
+    /// The maximum number of lines that the [`crate::Reader`] reads before stopping (see
+    /// [`ParsingOptionsBuilder::with_max_lines`]), where `None` indicates no limit.
+    pub fn max_lines(&self) -> Option<usize> {
+        self.max_lines
+    }
+
+    /// Whether the [`crate::Reader`] ignores spaces and tabs at the start of a line (see
+    /// [`ParsingOptionsBuilder::with_leading_whitespace_trimming`]).
+    pub fn leading_whitespace_trimming(&self) -> bool {
+        self.leading_whitespace_trimming
+    }
+
+    /// Whether the [`crate::Reader`] ignores spaces and tabs at the end of a URI line (see
+    /// [`ParsingOptionsBuilder::with_uri_trailing_whitespace_trimming`]).
+    pub fn uri_trailing_whitespace_trimming(&self) -> bool {
+        self.uri_trailing_whitespace_trimming
+    }
+
+    /// The parser used for the date and time values of tags, when one has been provided in place of
+    /// the parser built into the library (see
+    /// [`ParsingOptionsBuilder::with_date_time_parser`]).
+    pub fn date_time_parser(&self) -> Option<&dyn DateTimeParser> {
+        self.date_time_parser
+            .as_ref()
+            .map(|parser| parser.0.as_ref())
+    }
+
+    /// Whether any [`TagTransformer`] has been registered (see
+    /// [`ParsingOptionsBuilder::with_tag_transformer`]).
+    #[cfg(feature = "hls-tags")]
+    pub fn has_tag_transformers(&self) -> bool {
+        !self.tag_transformers.is_empty()
+    }
+
+    // Applies the transformers registered for the name of the tag, in the order of registration.
+    #[cfg(feature = "hls-tags")]
+    pub(crate) fn transform_tag<'a>(&self, tag: hls::Tag<'a>) -> hls::Tag<'a> {
+        if self.tag_transformers.is_empty() {
+            return tag;
+        }
+        let name = tag.name();
+        self.tag_transformers
+            .iter()
+            .filter(|(tag_name, _)| *tag_name == name)
+            .fold(tag, |tag, (_, transformer)| transformer.0.transform(tag))
+    }
+
     pub(crate) fn is_known_name(&self, name: &'_ str) -> bool {
+        // Without the tag definitions there is nothing to parse the HLS tags into.
+        if !cfg!(feature = "hls-tags") {
+            return false;
+        }
         let Ok(tag_name) = TagName::try_from(name) else {
             return false;
         };
         self.hls_tag_names_to_parse.contains(&tag_name)
     }
+
+    // Provides the canonical name of a HLS tag that is included in parsing, when the name only
+    // matches without regard to ASCII case (and case insensitive matching is enabled).
+    pub(crate) fn case_insensitive_known_name(&self, name: &'_ str) -> Option<&'static str> {
+        if !self.case_insensitive_tag_names || !cfg!(feature = "hls-tags") {
+            return None;
+        }
+        TagName::try_from_ignore_ascii_case(name)
+            .ok()
+            .filter(|tag_name| self.hls_tag_names_to_parse.contains(tag_name))
+            .map(|tag_name| tag_name.as_str())
+    }
 }
 
 /// A builder type to provide convenience for constructing [`ParsingOptions`].
@@ -88,6 +259,15 @@ impl ParsingOptions {
 #[derive(Default, Debug)]
 pub struct ParsingOptionsBuilder {
     hls_tag_names_to_parse: HashSet<TagName>,
+    error_recovery: ErrorRecovery,
+    comment_attachment: bool,
+    case_insensitive_tag_names: bool,
+    max_lines: Option<usize>,
+    leading_whitespace_trimming: bool,
+    uri_trailing_whitespace_trimming: bool,
+    date_time_parser: Option<SharedDateTimeParser>,
+    #[cfg(feature = "hls-tags")]
+    tag_transformers: Vec<(TagName, SharedTagTransformer)>,
 }
 
 impl ParsingOptionsBuilder {
@@ -95,6 +275,15 @@ impl ParsingOptionsBuilder {
     pub fn new() -> Self {
         Self {
             hls_tag_names_to_parse: HashSet::default(),
+            error_recovery: ErrorRecovery::default(),
+            comment_attachment: false,
+            case_insensitive_tag_names: false,
+            max_lines: None,
+            leading_whitespace_trimming: false,
+            uri_trailing_whitespace_trimming: false,
+            date_time_parser: None,
+            #[cfg(feature = "hls-tags")]
+            tag_transformers: Vec::new(),
         }
     }
 
@@ -102,15 +291,337 @@ impl ParsingOptionsBuilder {
     pub fn build(&self) -> ParsingOptions {
         ParsingOptions {
             hls_tag_names_to_parse: self.hls_tag_names_to_parse.clone(),
+            error_recovery: self.error_recovery,
+            comment_attachment: self.comment_attachment,
+            case_insensitive_tag_names: self.case_insensitive_tag_names,
+            max_lines: self.max_lines,
+            leading_whitespace_trimming: self.leading_whitespace_trimming,
+            uri_trailing_whitespace_trimming: self.uri_trailing_whitespace_trimming,
+            date_time_parser: self.date_time_parser.clone(),
+            #[cfg(feature = "hls-tags")]
+            tag_transformers: self.tag_transformers.clone(),
         }
     }
 
+    /// Set what the [`crate::Reader`] should do after experiencing an error while reading a line.
+    pub fn with_error_recovery(&mut self, error_recovery: ErrorRecovery) -> &mut Self {
+        self.error_recovery = error_recovery;
+        self
+    }
+
+    /// Attach comment lines to the known HLS tag that immediately follows them.
+    ///
+    /// When enabled, a comment line that is immediately followed by a parsed HLS tag that supports
+    /// comments (e.g. [`crate::tag::hls::Inf::comment`]) is not read as
+    /// [`crate::HlsLine::Comment`], but is instead attached to the tag. Since the
+    /// [`crate::Writer`] writes an attached comment on the line before the tag, this allows
+    /// annotations to survive a read-modify-write cycle while staying associated with the tag. Tags
+    /// without a value (e.g. `EXT-X-ENDLIST`) do not support comments, and so a comment before
+    /// them is read as usual.
+    pub fn with_comment_attachment(&mut self) -> &mut Self {
+        self.comment_attachment = true;
+        self
+    }
+
+    /// Match the `#EXT` prefix and the names of HLS tags without regard to ASCII case.
+    ///
+    /// Some legacy encoders write tags in lowercase (e.g. `#ext-x-endlist`), which the HLS
+    /// specification does not allow (and so, by default, such lines are read as comments). When
+    /// enabled, these lines are read as tags, and the HLS tags among them are parsed as known tags
+    /// (if included in parsing). The original bytes of the line are preserved, such that an
+    /// unmodified tag is written exactly as it was read; only when the tag is modified is it written
+    /// with the name as defined in the specification. The exception is tags that have no value
+    /// (such as `EXT-X-ENDLIST`), which carry no data from the line and so are always written with
+    /// the name as defined in the specification. Custom tag names are still matched exactly.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions, tag::{KnownTag, hls}};
+    /// let options = ParsingOptions::builder()
+    ///     .with_parsing_for_all_tags()
+    ///     .with_case_insensitive_tag_names()
+    ///     .build();
+    /// let mut reader = Reader::from_str("#ext-x-targetduration:4\n#ext-x-endlist", options);
+    /// let line = reader.read_line()?.expect("should have a line");
+    /// assert!(matches!(line, HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Targetduration(_)))));
+    /// assert_eq!("#ext-x-targetduration:4", line.to_string());
+    /// let line = reader.read_line()?.expect("should have a line");
+    /// assert_eq!("#EXT-X-ENDLIST", line.to_string());
+    /// # Ok::<(), quick_m3u8::error::ReaderStrError>(())
+    /// ```
+    pub fn with_case_insensitive_tag_names(&mut self) -> &mut Self {
+        self.case_insensitive_tag_names = true;
+        self
+    }
+
+    /// Limit the number of lines that the [`crate::Reader`] reads.
+    ///
+    /// This is a safeguard for services that must bound the work done on untrusted input (e.g. to
+    /// stay within the deadline of a watchdog). Once the reader has provided this many lines (where
+    /// a comment that is attached to a tag counts as one line along with the tag, and an error counts
+    /// as a line), the next call to `read_line` returns an error of
+    /// [`crate::error::GenericSyntaxError::LineLimitExceeded`] (with the next line as the errored
+    /// line) and the reader stops, without consuming that line. Calling `resume` on the reader
+    /// allows it to read up to this many lines again, starting from that line.
+    ///
+    /// A limit of `0` is taken to be a limit of `1`, as otherwise the reader could never make
+    /// progress (each `resume` would be followed by the same error).
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{
+    /// #     HlsLine, Reader, config::ParsingOptions,
+    /// #     error::{GenericSyntaxError, SyntaxError},
+    /// # };
+    /// let options = ParsingOptions::builder()
+    ///     .with_parsing_for_all_tags()
+    ///     .with_max_lines(2)
+    ///     .build();
+    /// let mut reader = Reader::from_str("#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXT-X-ENDLIST", options);
+    /// assert!(reader.read_line()?.is_some());
+    /// assert!(reader.read_line()?.is_some());
+    /// let error = reader.read_line().expect_err("should reach limit");
+    /// assert_eq!(SyntaxError::from(GenericSyntaxError::LineLimitExceeded), error.error);
+    /// assert_eq!("#EXT-X-ENDLIST", error.errored_line);
+    /// assert_eq!(None, reader.read_line()?);
+    ///
+    /// reader.resume();
+    /// assert_eq!("#EXT-X-ENDLIST", reader.read_line()?.expect("should read").to_string());
+    /// # Ok::<(), quick_m3u8::error::ReaderStrError>(())
+    /// ```
+    pub fn with_max_lines(&mut self, max_lines: usize) -> &mut Self {
+        self.max_lines = Some(max_lines.max(1));
+        self
+    }
+
+    /// Ignore spaces and tabs at the start of a line.
+    ///
+    /// Some encoders write stray whitespace before the `#` of a tag (e.g. `  #EXTM3U`), which the
+    /// HLS specification does not allow (and so, by default, such a line is read as a URI). When
+    /// enabled, the whitespace is ignored, such that the line is read as it would be without it
+    /// (and is written without it). Lines made up entirely of whitespace are still read as
+    /// [`crate::HlsLine::Whitespace`].
+    ///
+    /// A UTF-8 byte order mark at the start of the data, and CRLF line endings, are always handled
+    /// by the [`crate::Reader`] (see [`crate::Reader::has_bom`]), and so need no option.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions, tag::hls::M3u};
+    /// let options = ParsingOptions::builder()
+    ///     .with_parsing_for_all_tags()
+    ///     .with_leading_whitespace_trimming()
+    ///     .build();
+    /// let mut reader = Reader::from_str("\u{FEFF}  #EXTM3U\r\n", options);
+    /// assert_eq!(Some(HlsLine::from(M3u)), reader.read_line()?);
+    /// # Ok::<(), quick_m3u8::error::ReaderStrError>(())
+    /// ```
+    pub fn with_leading_whitespace_trimming(&mut self) -> &mut Self {
+        self.leading_whitespace_trimming = true;
+        self
+    }
+
+    /// Ignore spaces and tabs (along with a carriage return that is not part of a CRLF line ending)
+    /// at the end of a URI line.
+    ///
+    /// Whitespace is not valid in a URI, and so trailing whitespace is almost certainly a mistake
+    /// of the encoder; however, by default, the URI is read exactly as found. When enabled, the URI
+    /// is read (and written) without it.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions};
+    /// # use std::borrow::Cow;
+    /// let options = ParsingOptions::builder()
+    ///     .with_uri_trailing_whitespace_trimming()
+    ///     .build();
+    /// let mut reader = Reader::from_str("segment.ts \t\r\n", options);
+    /// assert_eq!(Some(HlsLine::Uri(Cow::Borrowed("segment.ts"))), reader.read_line()?);
+    /// # Ok::<(), quick_m3u8::error::ReaderStrError>(())
+    /// ```
+    pub fn with_uri_trailing_whitespace_trimming(&mut self) -> &mut Self {
+        self.uri_trailing_whitespace_trimming = true;
+        self
+    }
+
+    /// Parse the date and time values of tags (the value of `EXT-X-PROGRAM-DATE-TIME`, and the
+    /// `START-DATE` and `END-DATE` attributes of `EXT-X-DATERANGE`) with the provided parser, in
+    /// place of the parser built into the library.
+    ///
+    /// An `EXT-X-PROGRAM-DATE-TIME` that the parser rejects is read as an unknown tag (with the
+    /// [`crate::tag::UnknownTag::validation_error`]), whereas a rejected `START-DATE` or `END-DATE`
+    /// is treated as not provided (as the attributes are otherwise parsed only when requested). See
+    /// [`crate::date::DateTimeParser`] for an example.
+    pub fn with_date_time_parser(&mut self, parser: impl DateTimeParser + 'static) -> &mut Self {
+        self.date_time_parser = Some(SharedDateTimeParser(Arc::new(parser)));
+        self
+    }
+
+    /// Transform every tag of the provided name with the transformer, after the tag has been parsed
+    /// and before the line is returned by the [`crate::Reader`]. This also includes parsing of the
+    /// tag (as only parsed tags can be transformed). Multiple transformers may be registered for
+    /// the same name, in which case they are applied in the order that they were registered.
+    ///
+    /// For example, clamping the `SCORE` of every Variant Stream and removing the `PATHWAY-ID`:
+    /// ```
+    /// # use quick_m3u8::{
+    /// #     HlsLine, Reader,
+    /// #     config::ParsingOptions,
+    /// #     tag::{KnownTag, hls::{self, TagName}},
+    /// # };
+    /// fn apply_policy(tag: hls::Tag) -> hls::Tag {
+    ///     let hls::Tag::StreamInf(mut stream_inf) = tag else {
+    ///         return tag;
+    ///     };
+    ///     if let Some(score) = stream_inf.score().filter(|score| *score > 10.0) {
+    ///         stream_inf.set_score(score.min(10.0));
+    ///     }
+    ///     stream_inf.unset_pathway_id();
+    ///     hls::Tag::StreamInf(stream_inf)
+    /// }
+    ///
+    /// let options = ParsingOptions::builder()
+    ///     .with_tag_transformer(TagName::StreamInf, apply_policy)
+    ///     .build();
+    /// let mut reader = Reader::from_str(
+    ///     "#EXT-X-STREAM-INF:BANDWIDTH=1000000,SCORE=50,PATHWAY-ID=\"CDN-A\"\nlow.m3u8\n",
+    ///     options,
+    /// );
+    /// let Some(HlsLine::KnownTag(KnownTag::Hls(tag))) = reader.read_line()? else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!("#EXT-X-STREAM-INF:BANDWIDTH=1000000,SCORE=10.0", tag.to_string());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "hls-tags")]
+    pub fn with_tag_transformer(
+        &mut self,
+        name: TagName,
+        transformer: impl TagTransformer + 'static,
+    ) -> &mut Self {
+        self.hls_tag_names_to_parse.insert(name);
+        self.tag_transformers
+            .push((name, SharedTagTransformer(Arc::new(transformer))));
+        self
+    }
+
     /// Include parsing of all known HLS tags.
     pub fn with_parsing_for_all_tags(&mut self) -> &mut Self {
-        self.hls_tag_names_to_parse.extend(ALL_KNOWN_HLS_TAG_NAMES);
+        self.hls_tag_names_to_parse.extend(TagName::all());
         self
     }
 
+    /// Include parsing of each of the provided tags.
+    ///
+    /// This is useful when the tags to parse are decided at runtime (e.g. from configuration), and
+    /// composes with [`TagName::all`] and [`TagName::of_type`]. For example:
+    /// ```
+    /// # use quick_m3u8::{config::ParsingOptionsBuilder, tag::hls::{TagName, TagType}};
+    /// let options = ParsingOptionsBuilder::new()
+    ///     .with_tags(TagName::of_type(TagType::MultivariantPlaylist))
+    ///     .with_tags(["#EXT-X-VERSION".parse::<TagName>()?])
+    ///     .build();
+    /// let tags = options.hls_tag_names_to_parse();
+    /// assert!(tags.contains(&TagName::StreamInf));
+    /// assert!(tags.contains(&TagName::Version));
+    /// assert!(!tags.contains(&TagName::Inf));
+    /// # Ok::<(), quick_m3u8::error::ValidationError>(())
+    /// ```
+    pub fn with_tags(&mut self, tags: impl IntoIterator<Item = TagName>) -> &mut Self {
+        self.hls_tag_names_to_parse.extend(tags);
+        self
+    }
+
+    /// Include parsing of all known HLS tags of the [`TagType`].
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{config::ParsingOptionsBuilder, tag::hls::{TagName, TagType}};
+    /// let options = ParsingOptionsBuilder::new()
+    ///     .with_parsing_for_tag_type(TagType::MediaSegment)
+    ///     .with_parsing_for_media_playlist_tags()
+    ///     .without_parsing_for_daterange()
+    ///     .build();
+    /// let tags = options.hls_tag_names_to_parse();
+    /// assert!(tags.contains(&TagName::Inf));
+    /// assert!(tags.contains(&TagName::Targetduration));
+    /// assert!(!tags.contains(&TagName::Daterange));
+    /// assert!(!tags.contains(&TagName::StreamInf));
+    /// ```
+    pub fn with_parsing_for_tag_type(&mut self, tag_type: TagType) -> &mut Self {
+        self.hls_tag_names_to_parse
+            .extend(TagName::of_type(tag_type));
+        self
+    }
+
+    /// Ignore parsing of all known HLS tags of the [`TagType`].
+    pub fn without_parsing_for_tag_type(&mut self, tag_type: TagType) -> &mut Self {
+        for name in TagName::of_type(tag_type) {
+            self.hls_tag_names_to_parse.remove(&name);
+        }
+        self
+    }
+
+    /// Include parsing of the Basic Tags (see [`TagType::Basic`]).
+    pub fn with_parsing_for_basic_tags(&mut self) -> &mut Self {
+        self.with_parsing_for_tag_type(TagType::Basic)
+    }
+
+    /// Ignore parsing of the Basic Tags (see [`TagType::Basic`]).
+    pub fn without_parsing_for_basic_tags(&mut self) -> &mut Self {
+        self.without_parsing_for_tag_type(TagType::Basic)
+    }
+
+    /// Include parsing of the Media or Multivariant Playlist Tags (see [`TagType::MediaOrMultivariantPlaylist`]).
+    pub fn with_parsing_for_media_or_multivariant_tags(&mut self) -> &mut Self {
+        self.with_parsing_for_tag_type(TagType::MediaOrMultivariantPlaylist)
+    }
+
+    /// Ignore parsing of the Media or Multivariant Playlist Tags (see [`TagType::MediaOrMultivariantPlaylist`]).
+    pub fn without_parsing_for_media_or_multivariant_tags(&mut self) -> &mut Self {
+        self.without_parsing_for_tag_type(TagType::MediaOrMultivariantPlaylist)
+    }
+
+    /// Include parsing of the Media Playlist Tags (see [`TagType::MediaPlaylist`]).
+    pub fn with_parsing_for_media_playlist_tags(&mut self) -> &mut Self {
+        self.with_parsing_for_tag_type(TagType::MediaPlaylist)
+    }
+
+    /// Ignore parsing of the Media Playlist Tags (see [`TagType::MediaPlaylist`]).
+    pub fn without_parsing_for_media_playlist_tags(&mut self) -> &mut Self {
+        self.without_parsing_for_tag_type(TagType::MediaPlaylist)
+    }
+
+    /// Include parsing of the Media Segment Tags (see [`TagType::MediaSegment`]).
+    pub fn with_parsing_for_media_segment_tags(&mut self) -> &mut Self {
+        self.with_parsing_for_tag_type(TagType::MediaSegment)
+    }
+
+    /// Ignore parsing of the Media Segment Tags (see [`TagType::MediaSegment`]).
+    pub fn without_parsing_for_media_segment_tags(&mut self) -> &mut Self {
+        self.without_parsing_for_tag_type(TagType::MediaSegment)
+    }
+
+    /// Include parsing of the Media Metadata Tags (see [`TagType::MediaMetadata`]).
+    pub fn with_parsing_for_media_metadata_tags(&mut self) -> &mut Self {
+        self.with_parsing_for_tag_type(TagType::MediaMetadata)
+    }
+
+    /// Ignore parsing of the Media Metadata Tags (see [`TagType::MediaMetadata`]).
+    pub fn without_parsing_for_media_metadata_tags(&mut self) -> &mut Self {
+        self.without_parsing_for_tag_type(TagType::MediaMetadata)
+    }
+
+    /// Include parsing of the Multivariant Playlist Tags (see [`TagType::MultivariantPlaylist`]).
+    pub fn with_parsing_for_multivariant_tags(&mut self) -> &mut Self {
+        self.with_parsing_for_tag_type(TagType::MultivariantPlaylist)
+    }
+
+    /// Ignore parsing of the Multivariant Playlist Tags (see [`TagType::MultivariantPlaylist`]).
+    pub fn without_parsing_for_multivariant_tags(&mut self) -> &mut Self {
+        self.without_parsing_for_tag_type(TagType::MultivariantPlaylist)
+    }
+
     /// Include parsing of [`crate::tag::hls::M3u`].
     pub fn with_parsing_for_m3u(&mut self) -> &mut Self {
         self.hls_tag_names_to_parse.insert(TagName::M3u);
@@ -517,7 +1028,7 @@ mod tests {
         let mut count = 0;
         for name in options.hls_tag_names_to_parse {
             count += 1;
-            assert!(ALL_KNOWN_HLS_TAG_NAMES.contains(&name));
+            assert!(TagName::all().contains(&name));
         }
         assert_eq!(32, count);
     }
@@ -550,4 +1061,49 @@ mod tests {
         );
         assert_eq!(30, options.hls_tag_names_to_parse.len());
     }
+
+    #[test]
+    fn builder_with_tag_types() {
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_all_tags()
+            .without_parsing_for_multivariant_tags()
+            .without_parsing_for_media_or_multivariant_tags()
+            .build();
+        assert_eq!(
+            TagName::all()
+                .iter()
+                .filter(|name| !matches!(
+                    name.tag_type(),
+                    TagType::MultivariantPlaylist | TagType::MediaOrMultivariantPlaylist
+                ))
+                .count(),
+            options.hls_tag_names_to_parse.len()
+        );
+        assert!(!options.hls_tag_names_to_parse.contains(&TagName::Media));
+        assert!(!options.hls_tag_names_to_parse.contains(&TagName::Start));
+
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_basic_tags()
+            .with_parsing_for_media_metadata_tags()
+            .build();
+        assert_eq!(
+            TagName::of_type(TagType::Basic)
+                .chain(TagName::of_type(TagType::MediaMetadata))
+                .collect::<HashSet<_>>(),
+            options.hls_tag_names_to_parse
+        );
+    }
+
+    #[test]
+    fn from_tag_names_should_only_parse_provided_tags() {
+        let options = ParsingOptions::from_tag_names(&[TagName::Inf, TagName::Inf, TagName::Map]);
+        assert_eq!(
+            HashSet::from([TagName::Inf, TagName::Map]),
+            options.hls_tag_names_to_parse
+        );
+        assert_eq!(
+            ParsingOptions::default(),
+            ParsingOptions::from_tag_names(TagName::all())
+        );
+    }
 }
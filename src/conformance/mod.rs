@@ -0,0 +1,177 @@
+//! Conformance test vectors (available with the `conformance` feature).
+//!
+//! This module embeds the example playlists from the HLS specification
+//! ([draft-pantos-hls-rfc8216bis]) along with the example playlists published by Apple, and provides
+//! a way to run each of them through the library. This allows downstream packagers to certify their
+//! integration of the library (for example, as part of their own test suite), and allows regressions
+//! to be caught when new drafts of the specification are adopted.
+//!
+//! Each vector is checked line by line, and the following is verified:
+//! * The line can be read by the [`crate::Reader`] without a syntax error.
+//! * If the line is a known HLS tag, then it passes validation (i.e. it is not read as
+//!   [`crate::HlsLine::UnknownTag`] with a [`crate::tag::UnknownTag::validation_error`]).
+//! * Writing the line with the [`crate::Writer`] reproduces the original line exactly.
+//!
+//! For example:
+//! ```
+//! # use quick_m3u8::conformance;
+//! let reports = conformance::run_all();
+//! assert!(!reports.is_empty());
+//! for report in reports {
+//!     assert!(report.passed(), "{}: {:?}", report.vector.name, report.failures);
+//! }
+//! ```
+//!
+//! [draft-pantos-hls-rfc8216bis]: https://datatracker.ietf.org/doc/draft-pantos-hls-rfc8216bis/
+
+use crate::{HlsLine, Reader, Writer, config::ParsingOptions, error::ConformanceFailure};
+
+/// A playlist that the library is expected to handle.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TestVector {
+    /// A unique name for the vector.
+    pub name: &'static str,
+    /// Where the playlist was taken from.
+    pub source: &'static str,
+    /// The playlist.
+    pub playlist: &'static str,
+}
+
+impl TestVector {
+    /// Runs the vector, collecting every failure found.
+    pub fn run(&'static self) -> VectorReport {
+        let mut failures = Vec::new();
+        let mut reader = Reader::from_str(self.playlist, ParsingOptions::default());
+        for (index, expected) in self.playlist.lines().enumerate() {
+            let line_number = index + 1;
+            let line = match reader.read_line() {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(error) => {
+                    failures.push(ConformanceFailure::Syntax { line_number, error });
+                    continue;
+                }
+            };
+            if let HlsLine::UnknownTag(tag) = &line
+                && let Some(error) = tag.validation_error()
+            {
+                failures.push(ConformanceFailure::Validation {
+                    line_number,
+                    tag_name: tag.name(),
+                    error,
+                });
+            }
+            let mut writer = Writer::new(Vec::new());
+            // Writing to a `Vec` cannot fail.
+            let _ = writer.write_line(line);
+            let written = writer.into_inner();
+            let actual = String::from_utf8_lossy(&written);
+            let actual = actual.strip_suffix('\n').unwrap_or(&actual);
+            if actual != expected {
+                failures.push(ConformanceFailure::RoundTrip {
+                    line_number,
+                    expected,
+                    actual: actual.to_string(),
+                });
+            }
+        }
+        VectorReport {
+            vector: self,
+            failures,
+        }
+    }
+}
+
+/// The result of running a [`TestVector`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct VectorReport {
+    /// The vector that was run.
+    pub vector: &'static TestVector,
+    /// All failures found when running the vector (empty if the vector passed).
+    pub failures: Vec<ConformanceFailure>,
+}
+
+impl VectorReport {
+    /// Whether the vector passed (i.e. there were no failures).
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Runs every vector in [`VECTORS`], providing a report for each.
+pub fn run_all() -> Vec<VectorReport> {
+    VECTORS.iter().map(TestVector::run).collect()
+}
+
+const RFC: &str = "draft-pantos-hls-rfc8216bis";
+const APPLE: &str = "Apple HTTP Live Streaming examples";
+
+macro_rules! vector {
+    ($name:literal, $source:expr) => {
+        TestVector {
+            name: $name,
+            source: $source,
+            playlist: include_str!(concat!("vectors/", $name, ".m3u8")),
+        }
+    };
+}
+
+/// All of the embedded conformance test vectors.
+pub const VECTORS: &[TestVector] = &[
+    vector!("simple_media_playlist", RFC),
+    vector!("live_media_playlist_using_https", RFC),
+    vector!("encrypted_media_segments", RFC),
+    vector!("multivariant_playlist", RFC),
+    vector!("multivariant_playlist_with_i_frames", RFC),
+    vector!("multivariant_playlist_with_alternative_audio", RFC),
+    vector!("multivariant_playlist_with_alternative_video", RFC),
+    vector!("session_data_in_multivariant_playlist", RFC),
+    vector!("content_steering", RFC),
+    vector!("variable_substitution", RFC),
+    vector!("low_latency_media_playlist", RFC),
+    vector!("delta_update_playlist", RFC),
+    vector!("vod_playlist", APPLE),
+    vector!("event_playlist", APPLE),
+    vector!("live_sliding_window_playlist", APPLE),
+    vector!("advanced_multivariant_playlist", APPLE),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ValidationError;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn all_vectors_should_pass() {
+        for report in run_all() {
+            assert_eq!(Vec::<ConformanceFailure>::new(), report.failures);
+        }
+    }
+
+    #[test]
+    fn vector_names_should_be_unique() {
+        for (index, vector) in VECTORS.iter().enumerate() {
+            assert!(!VECTORS[..index].iter().any(|v| v.name == vector.name));
+        }
+    }
+
+    #[test]
+    fn invalid_vector_should_report_failures() {
+        static INVALID: TestVector = TestVector {
+            name: "invalid",
+            source: "test",
+            playlist: "#EXTM3U\n#EXT-X-STREAM-INF:AVERAGE-BANDWIDTH=1000\nlow.m3u8\n",
+        };
+        let report = INVALID.run();
+        assert!(!report.passed());
+        assert_eq!(
+            vec![ConformanceFailure::Validation {
+                line_number: 2,
+                tag_name: "-X-STREAM-INF",
+                error: ValidationError::MissingRequiredAttribute("BANDWIDTH"),
+            }],
+            report.failures
+        );
+    }
+}
@@ -1,13 +1,22 @@
 //! Constructs to reason about date and time in HLS
 //!
 //! The structs offered here don't provide much functionality. The purpose is primarily
-//! informational. These types can be used with another date/time library (such as [chrono]) for
-//! more feature rich date/time comparisons and operations.
+//! informational, though dates can be compared, ordered, hashed, and offset by a number of seconds
+//! (see [`DateTime`]). These types can be converted to and from the types of another date/time
+//! library for more feature rich date/time operations: [chrono] (via the `chrono` feature) and
+//! [time] (via the `time` feature).
 //!
 //! [chrono]: https://crates.io/crates/chrono
+//! [time]: https://crates.io/crates/time
 
+#[cfg(any(feature = "chrono", feature = "time"))]
+use crate::error::DateTimeConversionError;
 use crate::{error::DateTimeSyntaxError, utils::parse_date_time_bytes};
-use std::fmt::Display;
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+};
 
 /// A macro to help constructing a [`DateTime`] struct.
 ///
@@ -96,37 +105,68 @@ macro_rules! date_time {
         date_time!($Y-$M-$D T $h:$m:$s 0:0)
     };
     ($Y:literal-$M:literal-$D:literal T $h:literal:$m:literal:$s:literal $x:literal:$y:literal) => {{
-        const _: () = assert!($Y <= 9999, "Year must be at most 4 digits");
-        const _: () = assert!($M > 0, "Month must be greater than 0");
-        const _: () = assert!($M <= 12, "Month must be less than or equal to 12");
-        const _: () = assert!($D > 0, "Day must be greater than 0");
-        const _: () = assert!($D <= 31, "Day must be less than or equal to 31");
-        const _: () = assert!($h < 24, "Hour must be less than 24");
-        const _: () = assert!($m < 60, "Minute must be less than 60");
-        const _: () = assert!($s >= 0.0, "Seconds must be positive");
-        const _: () = assert!($s < 60.0, "Seconds must be less than 60.0");
-        const _: () = assert!($x > -24, "Hour offset must be greater than -24");
-        const _: () = assert!($x < 24, "Hour offset must be less than 24");
-        const _: () = assert!($y < 60, "Minute offset must be less than 60");
-        $crate::date::DateTime {
-            date_fullyear: $Y,
-            date_month: $M,
-            date_mday: $D,
-            time_hour: $h,
-            time_minute: $m,
-            time_second: $s,
-            timezone_offset: $crate::date::DateTimeTimezoneOffset {
-                time_hour: $x,
-                time_minute: $y,
-            },
-        }
+        // Allowing zero prefixed literals so that the macro can be written like a date string (e.g.
+        // `date_time!(2025-06-03 T 01:02:03.456 -05:00)`).
+        #[allow(clippy::zero_prefixed_literal)]
+        const DATE_TIME: $crate::date::DateTime = {
+            const _: () = assert!($Y <= 9999, "Year must be at most 4 digits");
+            const _: () = assert!($M > 0, "Month must be greater than 0");
+            const _: () = assert!($M <= 12, "Month must be less than or equal to 12");
+            const _: () = assert!($D > 0, "Day must be greater than 0");
+            const _: () = assert!($D <= 31, "Day must be less than or equal to 31");
+            const _: () = assert!($h < 24, "Hour must be less than 24");
+            const _: () = assert!($m < 60, "Minute must be less than 60");
+            const _: () = assert!($s >= 0.0, "Seconds must be positive");
+            const _: () = assert!($s < 60.0, "Seconds must be less than 60.0");
+            const _: () = assert!($x > -24, "Hour offset must be greater than -24");
+            const _: () = assert!($x < 24, "Hour offset must be less than 24");
+            const _: () = assert!($y < 60, "Minute offset must be less than 60");
+            $crate::date::DateTime {
+                date_fullyear: $Y,
+                date_month: $M,
+                date_mday: $D,
+                time_hour: $h,
+                time_minute: $m,
+                time_second: $s,
+                timezone_offset: $crate::date::DateTimeTimezoneOffset {
+                    time_hour: $x,
+                    time_minute: $y,
+                },
+            }
+        };
+        DATE_TIME
     }};
 }
 
 /// A struct representing a date in the format of [RFC3339].
 ///
+/// Equality, ordering, and hashing are based on the instant in time that the date represents (to
+/// nanosecond precision), rather than on the individual fields. This means that dates with
+/// differing timezone offsets that represent the same instant are equal, and that dates can be used
+/// as keys in a `BTreeMap` or `HashMap` (for example, when indexing a timeline). For example:
+/// ```
+/// # use quick_m3u8::date_time;
+/// # use std::collections::BTreeMap;
+/// assert_eq!(
+///     date_time!(2025-06-22 T 23:13:42.500),
+///     date_time!(2025-06-22 T 21:43:42.500 -01:30)
+/// );
+/// assert!(date_time!(2025-06-22 T 23:13:42.500) < date_time!(2025-06-22 T 23:13:42.501));
+///
+/// let mut timeline = BTreeMap::new();
+/// timeline.insert(date_time!(2025-06-22 T 23:13:46.500), "segment2.ts");
+/// timeline.insert(date_time!(2025-06-22 T 23:13:42.500), "segment1.ts");
+/// assert_eq!(
+///     vec!["segment1.ts", "segment2.ts"],
+///     timeline.into_values().collect::<Vec<_>>()
+/// );
+/// ```
+///
+/// See [`Self::eq_with_tolerance`] for comparing dates that are allowed to differ by a fractional
+/// amount of seconds.
+///
 /// [RFC3339]: https://datatracker.ietf.org/doc/html/rfc3339#section-5.6
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 pub struct DateTime {
     /// The full year (must be `4DIGIT`).
     pub date_fullyear: u32,
@@ -164,12 +204,229 @@ impl Display for DateTime {
     }
 }
 
+impl PartialEq for DateTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.instant() == other.instant()
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.instant().cmp(&other.instant())
+    }
+}
+
+impl Hash for DateTime {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.instant().hash(state);
+    }
+}
+
 impl From<DateTime> for String {
     fn from(value: DateTime) -> Self {
         format!("{value}")
     }
 }
 
+impl DateTime {
+    #[cfg(feature = "hls-tags")]
+    // The number of seconds since the Unix epoch (1970-01-01T00:00:00Z).
+    pub(crate) fn timestamp(&self) -> f64 {
+        let minutes = self.local_minutes_since_epoch() - self.timezone_offset.minutes();
+        (minutes * 60) as f64 + self.time_second
+    }
+
+    /// Offsets the date by the provided number of seconds (which may be negative), keeping the same
+    /// timezone offset.
+    ///
+    /// The result is rounded to microsecond precision, so that floating point error does not
+    /// accumulate when repeatedly adding durations. This makes it simple to derive the date of each
+    /// segment in a playlist from the `EXT-X-PROGRAM-DATE-TIME` and the `EXTINF` durations:
+    /// ```
+    /// # use quick_m3u8::date_time;
+    /// let program_date_time = date_time!(2025-06-22 T 23:59:58.500 01:00);
+    /// let segment_durations = [1.5, 1.5, 0.1];
+    ///
+    /// let mut segment_date = program_date_time;
+    /// for duration in segment_durations {
+    ///     segment_date = segment_date.add_seconds(duration);
+    /// }
+    /// assert_eq!("2025-06-23T00:00:01.600+01:00", segment_date.to_string());
+    /// ```
+    ///
+    /// A date can only be written with a 4 digit year, and so the result saturates at the start of
+    /// year `0000` and at the end of year `9999` (including when `seconds` is infinite). When
+    /// `seconds` is NaN the date is returned unchanged.
+    pub fn add_seconds(&self, seconds: f64) -> Self {
+        // Comfortably beyond the minutes between the start of year `0000` and the end of year
+        // `9999`, while small enough that adding it to a representable date cannot overflow.
+        const MAX_CARRY_MINUTES: f64 = 1e12;
+        if seconds.is_nan() {
+            return *self;
+        }
+        // Rounding to microsecond precision to avoid floating point errors accumulating (e.g.
+        // `42.218 + 44.0 - 60.0` is `26.218000000000004`).
+        let round = |seconds: f64| (seconds * 1e6).round() / 1e6;
+        let total_seconds = round(self.time_second + seconds);
+        let carry_minutes = (total_seconds / 60.0)
+            .floor()
+            .clamp(-MAX_CARRY_MINUTES, MAX_CARRY_MINUTES);
+        let time_second = round(total_seconds - (carry_minutes * 60.0));
+        let minutes = self.local_minutes_since_epoch() + carry_minutes as i64;
+        let (date_fullyear, date_month, date_mday) = civil_from_days(minutes.div_euclid(1440));
+        let date_fullyear = match u32::try_from(date_fullyear) {
+            Ok(date_fullyear) if date_fullyear <= 9999 => date_fullyear,
+            Ok(_) => return self.with_local_date_time(9999, 12, 31, 23, 59, 59.999),
+            Err(_) => return self.with_local_date_time(0, 1, 1, 0, 0, 0.0),
+        };
+        let minute_of_day = minutes.rem_euclid(1440);
+        Self {
+            date_fullyear,
+            date_month,
+            date_mday,
+            time_hour: (minute_of_day / 60) as u8,
+            time_minute: (minute_of_day % 60) as u8,
+            time_second,
+            timezone_offset: self.timezone_offset,
+        }
+    }
+
+    fn with_local_date_time(
+        &self,
+        date_fullyear: u32,
+        date_month: u8,
+        date_mday: u8,
+        time_hour: u8,
+        time_minute: u8,
+        time_second: f64,
+    ) -> Self {
+        Self {
+            date_fullyear,
+            date_month,
+            date_mday,
+            time_hour,
+            time_minute,
+            time_second,
+            timezone_offset: self.timezone_offset,
+        }
+    }
+
+    /// Indicates whether the two dates represent instants that are no more than `tolerance`
+    /// seconds apart.
+    ///
+    /// This is useful where dates may have been rounded differently (for example, when one date has
+    /// been derived by adding segment durations to another). For example:
+    /// ```
+    /// # use quick_m3u8::date_time;
+    /// let a = date_time!(2025-06-22 T 23:13:42.500);
+    /// let b = date_time!(2025-06-22 T 21:43:42.501 -01:30);
+    /// assert_ne!(a, b);
+    /// assert!(a.eq_with_tolerance(&b, 0.001));
+    /// assert!(!a.eq_with_tolerance(&b, 0.0001));
+    /// ```
+    pub fn eq_with_tolerance(&self, other: &Self, tolerance: f64) -> bool {
+        let difference = self.duration_since(other);
+        // Allowing a nanosecond for floating point error in the tolerance itself.
+        difference.abs() <= tolerance + 1e-9
+    }
+
+    /// The number of seconds from `earlier` to this date (negative when `earlier` is actually later).
+    ///
+    /// The timezone offsets of the dates are taken into account. For example:
+    /// ```
+    /// # use quick_m3u8::date_time;
+    /// let start = date_time!(2025-06-22 T 23:59:58.500);
+    /// let end = date_time!(2025-06-23 T 01:00:04.250 01:00);
+    /// assert_eq!(5.75, end.duration_since(&start));
+    /// assert_eq!(-5.75, start.duration_since(&end));
+    /// ```
+    pub fn duration_since(&self, earlier: &Self) -> f64 {
+        let (seconds, nanos) = self.instant();
+        let (earlier_seconds, earlier_nanos) = earlier.instant();
+        (seconds - earlier_seconds) as f64 + (f64::from(nanos) - f64::from(earlier_nanos)) / 1e9
+    }
+
+    /// Provides the same instant expressed with a UTC (`Z`) timezone offset.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::date_time;
+    /// assert_eq!(
+    ///     "2025-06-22T23:13:42.500Z",
+    ///     date_time!(2025-06-22 T 21:43:42.500 -01:30).to_utc().to_string()
+    /// );
+    /// ```
+    pub fn to_utc(&self) -> Self {
+        self.with_timezone_offset(DateTimeTimezoneOffset::default())
+    }
+
+    /// Provides the same instant expressed with the provided timezone offset.
+    pub fn with_timezone_offset(&self, timezone_offset: DateTimeTimezoneOffset) -> Self {
+        let minutes = self.timezone_offset.minutes() - timezone_offset.minutes();
+        Self {
+            timezone_offset,
+            ..*self
+        }
+        .add_seconds(-(minutes * 60) as f64)
+    }
+
+    // The instant represented by the date as whole seconds since the Unix epoch along with the
+    // nanoseconds within that second. This is used for equality, ordering, and hashing.
+    fn instant(&self) -> (i64, u32) {
+        let whole_seconds = self.time_second.floor();
+        let minutes = self.local_minutes_since_epoch() - self.timezone_offset.minutes();
+        let mut seconds = minutes * 60 + whole_seconds as i64;
+        let mut nanos = ((self.time_second - whole_seconds) * 1e9).round() as u32;
+        if nanos >= 1_000_000_000 {
+            seconds += 1;
+            nanos -= 1_000_000_000;
+        }
+        (seconds, nanos)
+    }
+
+    fn local_minutes_since_epoch(&self) -> i64 {
+        let days = days_from_civil(
+            i64::from(self.date_fullyear),
+            i64::from(self.date_month),
+            i64::from(self.date_mday),
+        );
+        days * 1440 + i64::from(self.time_hour) * 60 + i64::from(self.time_minute)
+    }
+}
+
+// Algorithms for converting between days since epoch and the civil (proleptic Gregorian) calendar
+// taken from http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
 impl Default for DateTime {
     fn default() -> Self {
         Self {
@@ -185,7 +442,7 @@ impl Default for DateTime {
 }
 
 /// The timezone offset.
-#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
 pub struct DateTimeTimezoneOffset {
     /// The hour offset (plus or minus `0-23`).
     pub time_hour: i8,
@@ -203,12 +460,197 @@ impl Display for DateTimeTimezoneOffset {
     }
 }
 
+impl DateTimeTimezoneOffset {
+    fn minutes(&self) -> i64 {
+        let minutes = i64::from(self.time_hour.unsigned_abs()) * 60 + i64::from(self.time_minute);
+        if self.time_hour < 0 {
+            -minutes
+        } else {
+            minutes
+        }
+    }
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl DateTimeTimezoneOffset {
+    // Offsets that are not a whole number of minutes, or that are negative and less than an hour
+    // (as the sign is carried by the hour), cannot be represented.
+    fn try_from_seconds(seconds: i32) -> Result<Self, DateTimeConversionError> {
+        if seconds % 60 != 0 || (-3600 < seconds && seconds < 0) {
+            return Err(DateTimeConversionError::UnsupportedTimezoneOffset);
+        }
+        let minutes = seconds / 60;
+        Ok(Self {
+            time_hour: i8::try_from(minutes / 60)
+                .map_err(|_| DateTimeConversionError::UnsupportedTimezoneOffset)?,
+            time_minute: (minutes % 60).unsigned_abs() as u8,
+        })
+    }
+
+    // The offset in seconds, where this fits in the range supported by the other libraries.
+    fn seconds(&self) -> Result<i32, DateTimeConversionError> {
+        let seconds = self.minutes() * 60;
+        if seconds.abs() < 86_400 {
+            Ok(seconds as i32)
+        } else {
+            Err(DateTimeConversionError::UnsupportedTimezoneOffset)
+        }
+    }
+}
+
 impl From<DateTimeTimezoneOffset> for String {
     fn from(value: DateTimeTimezoneOffset) -> Self {
         format!("{value}")
     }
 }
 
+impl TryFrom<&str> for DateTime {
+    type Error = DateTimeSyntaxError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse(value)
+    }
+}
+
+impl TryFrom<&[u8]> for DateTime {
+    type Error = DateTimeSyntaxError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        parse_bytes(value)
+    }
+}
+
+impl std::str::FromStr for DateTime {
+    type Err = DateTimeSyntaxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+// The date is serialized in the same format as it is written to a playlist (see `Display`).
+// Builds a date from the local date and time components provided by another library.
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn try_from_components(
+    year: i32,
+    month: u8,
+    day: u8,
+    (hour, minute, second, nanosecond): (u8, u8, u8, u32),
+    timezone_offset: DateTimeTimezoneOffset,
+) -> Result<DateTime, DateTimeConversionError> {
+    // The year must be `4DIGIT`.
+    if !(0..=9999).contains(&year) {
+        return Err(DateTimeConversionError::OutOfRange);
+    }
+    Ok(DateTime {
+        date_fullyear: year as u32,
+        date_month: month,
+        date_mday: day,
+        time_hour: hour,
+        time_minute: minute,
+        time_second: f64::from(second) + f64::from(nanosecond) / 1e9,
+        timezone_offset,
+    })
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<DateTime> for chrono::DateTime<chrono::FixedOffset> {
+    type Error = DateTimeConversionError;
+
+    fn try_from(value: DateTime) -> Result<Self, Self::Error> {
+        let offset = chrono::FixedOffset::east_opt(value.timezone_offset.seconds()?)
+            .ok_or(DateTimeConversionError::UnsupportedTimezoneOffset)?;
+        let (seconds, nanos) = value.instant();
+        chrono::DateTime::from_timestamp(seconds, nanos)
+            .map(|date| date.with_timezone(&offset))
+            .ok_or(DateTimeConversionError::OutOfRange)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::DateTime<chrono::FixedOffset>> for DateTime {
+    type Error = DateTimeConversionError;
+
+    fn try_from(value: chrono::DateTime<chrono::FixedOffset>) -> Result<Self, Self::Error> {
+        use chrono::{Datelike, Timelike};
+        let timezone_offset =
+            DateTimeTimezoneOffset::try_from_seconds(value.offset().local_minus_utc())?;
+        let local = value.naive_local();
+        try_from_components(
+            local.year(),
+            local.month() as u8,
+            local.day() as u8,
+            (
+                local.hour() as u8,
+                local.minute() as u8,
+                local.second() as u8,
+                local.nanosecond(),
+            ),
+            timezone_offset,
+        )
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<DateTime> for time::OffsetDateTime {
+    type Error = DateTimeConversionError;
+
+    fn try_from(value: DateTime) -> Result<Self, Self::Error> {
+        let offset = time::UtcOffset::from_whole_seconds(value.timezone_offset.seconds()?)
+            .map_err(|_| DateTimeConversionError::UnsupportedTimezoneOffset)?;
+        let (seconds, nanos) = value.instant();
+        time::OffsetDateTime::from_unix_timestamp_nanos(
+            i128::from(seconds) * 1_000_000_000 + i128::from(nanos),
+        )
+        .ok()
+        .and_then(|date| date.checked_to_offset(offset))
+        .ok_or(DateTimeConversionError::OutOfRange)
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::OffsetDateTime> for DateTime {
+    type Error = DateTimeConversionError;
+
+    fn try_from(value: time::OffsetDateTime) -> Result<Self, Self::Error> {
+        let timezone_offset =
+            DateTimeTimezoneOffset::try_from_seconds(value.offset().whole_seconds())?;
+        try_from_components(
+            value.year(),
+            value.month().into(),
+            value.day(),
+            (
+                value.hour(),
+                value.minute(),
+                value.second(),
+                value.nanosecond(),
+            ),
+            timezone_offset,
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for DateTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for DateTime {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let date = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        parse(&date).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Parses a string slice into a `DateTime`.
 pub fn parse(input: &str) -> Result<DateTime, DateTimeSyntaxError> {
     parse_bytes(input.as_bytes())
@@ -219,6 +661,68 @@ pub fn parse_bytes(input: &[u8]) -> Result<DateTime, DateTimeSyntaxError> {
     Ok(parse_date_time_bytes(input)?.parsed)
 }
 
+/// A parser for the date and time values found in a playlist, which can be provided to the
+/// [`crate::Reader`] via [`crate::config::ParsingOptionsBuilder::with_date_time_parser`] in place of
+/// the parser built into the library (see [`parse`]).
+///
+/// The parser is used for the value of `EXT-X-PROGRAM-DATE-TIME`, and for the `START-DATE` and
+/// `END-DATE` attributes of `EXT-X-DATERANGE`. This allows for enforcing stricter rules than the
+/// library does (e.g. requiring the fractional seconds that the HLS specification recommends), or
+/// for delegating to an existing date/time library. The parser only affects reading: a tag that
+/// is modified is written with its dates formatted as [`DateTime`] is displayed.
+///
+/// For example, rejecting dates without a timezone of `Z`:
+/// ```
+/// # use quick_m3u8::{
+/// #     HlsLine, Reader,
+/// #     config::ParsingOptions,
+/// #     date::{self, DateTime, DateTimeParser},
+/// #     error::DateTimeSyntaxError,
+/// # };
+/// #[derive(Debug)]
+/// struct UtcOnly;
+/// impl DateTimeParser for UtcOnly {
+///     fn parse(&self, input: &str) -> Result<DateTime, DateTimeSyntaxError> {
+///         if !input.ends_with(['Z', 'z']) {
+///             return Err(DateTimeSyntaxError::UnexpectedCharactersAfterTimezone);
+///         }
+///         date::parse(input)
+///     }
+/// }
+///
+/// let options = ParsingOptions::builder()
+///     .with_parsing_for_program_date_time()
+///     .with_date_time_parser(UtcOnly)
+///     .build();
+/// let mut reader = Reader::from_str(
+///     "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n\
+///      #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000+01:00\n",
+///     options,
+/// );
+/// assert!(matches!(reader.read_line()?, Some(HlsLine::KnownTag(_))));
+/// let Some(HlsLine::UnknownTag(tag)) = reader.read_line()? else {
+///     unreachable!()
+/// };
+/// assert!(tag.validation_error().is_some());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub trait DateTimeParser: Debug + Send + Sync {
+    /// Parses the date and time value (without any quotes).
+    fn parse(&self, input: &str) -> Result<DateTime, DateTimeSyntaxError>;
+}
+
+/// The parser built into the library (the same as [`parse`]), which is used when no other
+/// [`DateTimeParser`] is provided. This is useful for implementations that only add checks on top
+/// of the default parsing.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct DefaultDateTimeParser;
+
+impl DateTimeParser for DefaultDateTimeParser {
+    fn parse(&self, input: &str) -> Result<DateTime, DateTimeSyntaxError> {
+        parse(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -360,4 +864,261 @@ mod tests {
             }
         );
     }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn timestamp_should_account_for_timezone_offset() {
+        assert_eq!(0.0, date_time!(1970-01-01 T 00:00:00.000).timestamp());
+        assert_eq!(
+            date_time!(2025-06-22 T 22:13:42.500 -01:30).timestamp(),
+            date_time!(2025-06-22 T 23:43:42.500).timestamp()
+        );
+        assert_eq!(
+            1750634022.5,
+            date_time!(2025-06-22 T 23:13:42.500).timestamp()
+        );
+    }
+
+    #[test]
+    fn add_seconds_should_carry_across_date_boundaries() {
+        assert_eq!(
+            date_time!(2025-01-01 T 00:00:02.000 -05:00),
+            date_time!(2024-12-31 T 23:59:58.000 -05:00).add_seconds(4.0)
+        );
+        assert_eq!(
+            date_time!(2024-02-29 T 00:00:00.000),
+            date_time!(2024-02-28 T 23:00:00.000).add_seconds(3600.0)
+        );
+        assert_eq!(
+            date_time!(2025-02-28 T 23:59:59.000),
+            date_time!(2025-03-01 T 00:00:01.000).add_seconds(-2.0)
+        );
+        assert_eq!(
+            date_time!(2025-06-22 T 22:14:26.218),
+            date_time!(2025-06-22 T 22:13:42.218).add_seconds(44.0)
+        );
+    }
+
+    #[test]
+    fn equality_should_normalize_timezone_offsets() {
+        assert_eq!(
+            date_time!(2025-01-01 T 00:30:00.000 01:30),
+            date_time!(2024-12-31 T 23:00:00.000)
+        );
+        assert_ne!(
+            date_time!(2025-01-01 T 00:30:00.000 01:30),
+            date_time!(2025-01-01 T 00:30:00.000)
+        );
+    }
+
+    #[test]
+    fn ordering_should_be_by_instant() {
+        let mut dates = vec![
+            date_time!(2025-01-01 T 00:00:00.001),
+            date_time!(2025-01-01 T 00:30:00.000 01:00),
+            date_time!(2024-12-31 T 20:00:00.000 -05:00),
+        ];
+        dates.sort();
+        assert_eq!(
+            vec![
+                date_time!(2025-01-01 T 00:30:00.000 01:00),
+                date_time!(2025-01-01 T 00:00:00.001),
+                date_time!(2024-12-31 T 20:00:00.000 -05:00),
+            ],
+            dates
+        );
+    }
+
+    #[test]
+    fn hash_should_match_for_same_instant() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(date_time!(2025-06-22 T 23:13:42.500), 1);
+        map.insert(date_time!(2025-06-23 T 00:13:42.500 01:00), 2);
+        assert_eq!(1, map.len());
+        assert_eq!(Some(&2), map.get(&date_time!(2025-06-22 T 23:13:42.500)));
+    }
+
+    #[test]
+    fn eq_with_tolerance_should_allow_small_differences() {
+        let date = date_time!(2025-06-22 T 23:13:59.999);
+        let later = date_time!(2025-06-22 T 23:14:00.001);
+        assert!(date.eq_with_tolerance(&later, 0.002));
+        assert!(later.eq_with_tolerance(&date, 0.002));
+        assert!(!date.eq_with_tolerance(&later, 0.001));
+    }
+
+    #[test]
+    fn to_utc_should_preserve_instant() {
+        let date = date_time!(2025-01-01 T 00:30:00.250 01:30);
+        let utc = date.to_utc();
+        assert_eq!(date, utc);
+        assert_eq!("2024-12-31T23:00:00.250Z", utc.to_string());
+        assert_eq!(
+            "2024-12-31T18:00:00.250-05:00",
+            date.with_timezone_offset(DateTimeTimezoneOffset {
+                time_hour: -5,
+                time_minute: 0
+            })
+            .to_string()
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn segment_dates_should_be_derivable_from_program_date_time_and_durations() {
+        use crate::{
+            HlsLine, Reader,
+            config::ParsingOptions,
+            tag::{KnownTag, hls},
+        };
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:5\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-06-22T23:59:55.000-01:30\n",
+            "#EXTINF:4.004\n",
+            "segment_1.mp4\n",
+            "#EXTINF:4.004\n",
+            "segment_2.mp4\n",
+            "#EXTINF:0.992\n",
+            "segment_3.mp4\n",
+        );
+        let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+        let mut date = None;
+        let mut segment_dates = Vec::new();
+        loop {
+            match reader.read_line() {
+                Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::ProgramDateTime(tag))))) => {
+                    date = Some(tag.program_date_time());
+                }
+                Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(tag))))) => {
+                    let start = date.unwrap();
+                    segment_dates.push(start);
+                    date = Some(start.add_seconds(tag.duration()));
+                }
+                Ok(Some(_)) | Err(_) => (),
+                Ok(None) => break,
+            }
+        }
+        assert_eq!(
+            vec![
+                "2025-06-22T23:59:55.000-01:30",
+                "2025-06-22T23:59:59.004-01:30",
+                "2025-06-23T00:00:03.008-01:30",
+            ],
+            segment_dates
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(9.0, date.unwrap().duration_since(&segment_dates[0]));
+        assert_eq!(
+            date_time!(2025-06-23 T 01:30:04.000),
+            date.unwrap().to_utc()
+        );
+    }
+
+    #[test]
+    fn add_seconds_should_saturate_at_representable_years() {
+        assert_eq!(
+            "0000-01-01T00:00:00.000-05:00",
+            date_time!(0000-01-01 T 00:00:01.000 -05:00)
+                .add_seconds(-86_400.0)
+                .to_string()
+        );
+        assert_eq!(
+            "9999-12-31T23:59:59.999Z",
+            date_time!(9999-12-31 T 23:59:59.000)
+                .add_seconds(2.0)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn add_seconds_should_saturate_for_huge_and_non_finite_durations() {
+        let date = date_time!(2025-06-22 T 23:13:42.500);
+        let latest = "9999-12-31T23:59:59.999Z";
+        let earliest = "0000-01-01T00:00:00.000Z";
+        assert_eq!(latest, date.add_seconds(1e308).to_string());
+        assert_eq!(latest, date.add_seconds(f64::MAX).to_string());
+        assert_eq!(latest, date.add_seconds(f64::INFINITY).to_string());
+        assert_eq!(earliest, date.add_seconds(-1e308).to_string());
+        assert_eq!(earliest, date.add_seconds(f64::NEG_INFINITY).to_string());
+        assert_eq!(date.to_string(), date.add_seconds(f64::NAN).to_string());
+    }
+
+    #[test]
+    fn add_seconds_should_handle_negative_offsets_across_days() {
+        let date = date_time!(2025-03-01 T 00:00:00.500 01:00);
+        let earlier = date.add_seconds(-1.0);
+        assert_eq!("2025-02-28T23:59:59.500+01:00", earlier.to_string());
+        assert_eq!(-1.0, earlier.duration_since(&date));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_conversion_should_round_trip() {
+        let date = date_time!(2025-06-22 T 21:43:42.123 -01:30);
+        let chrono_date = chrono::DateTime::<chrono::FixedOffset>::try_from(date).unwrap();
+        assert_eq!(1750634022, chrono_date.timestamp());
+        assert_eq!(123, chrono_date.timestamp_subsec_millis());
+        assert_eq!(-5400, chrono_date.offset().local_minus_utc());
+        let round_trip = DateTime::try_from(chrono_date).unwrap();
+        assert_eq!(date, round_trip);
+        assert_eq!(date.to_string(), round_trip.to_string());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_conversion_should_reject_unrepresentable_dates() {
+        let offset = chrono::FixedOffset::west_opt(30 * 60).unwrap();
+        let date = chrono::DateTime::from_timestamp(0, 0)
+            .unwrap()
+            .with_timezone(&offset);
+        assert_eq!(
+            Err(DateTimeConversionError::UnsupportedTimezoneOffset),
+            DateTime::try_from(date)
+        );
+        let date = chrono::DateTime::from_timestamp(-62_167_219_201, 0)
+            .unwrap()
+            .fixed_offset();
+        assert_eq!(
+            Err(DateTimeConversionError::OutOfRange),
+            DateTime::try_from(date)
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_conversion_should_round_trip() {
+        let date = date_time!(2025-01-01 T 00:30:00.250 05:45);
+        let time_date = time::OffsetDateTime::try_from(date).unwrap();
+        assert_eq!(1735670700, time_date.unix_timestamp());
+        assert_eq!(250_000_000, time_date.nanosecond());
+        assert_eq!((5, 45, 0), time_date.offset().as_hms());
+        let round_trip = DateTime::try_from(time_date).unwrap();
+        assert_eq!(date, round_trip);
+        assert_eq!(date.to_string(), round_trip.to_string());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_conversion_should_reject_unrepresentable_offsets() {
+        let offset = time::UtcOffset::from_hms(1, 0, 30).unwrap();
+        let date = time::OffsetDateTime::UNIX_EPOCH.to_offset(offset);
+        assert_eq!(
+            Err(DateTimeConversionError::UnsupportedTimezoneOffset),
+            DateTime::try_from(date)
+        );
+        let date = DateTime {
+            timezone_offset: DateTimeTimezoneOffset {
+                time_hour: 30,
+                time_minute: 0,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(DateTimeConversionError::UnsupportedTimezoneOffset),
+            time::OffsetDateTime::try_from(date)
+        );
+    }
 }
@@ -0,0 +1,74 @@
+//! Helpers for dealing with the text encoding of playlist data.
+//!
+//! HLS playlists must be encoded in UTF-8 and must not contain a byte order mark (BOM); however, it
+//! is not uncommon to find playlists in the wild that break these rules (for example, a UTF-8 BOM
+//! added by Windows tooling, or Latin-1 characters in a `#EXTINF` title or `NAME` attribute). The
+//! [`crate::Reader`] transparently strips a leading UTF-8 BOM (see [`crate::Reader::has_bom`]), and
+//! [`crate::Writer::write_bom`] allows for re-emitting it. With the `latin1` feature enabled,
+//! `decode_lenient` can be used to convert data that is not valid UTF-8 before reading it.
+
+#[cfg(feature = "latin1")]
+use std::borrow::Cow;
+
+/// The UTF-8 encoding of the byte order mark (`U+FEFF`).
+pub const UTF_8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+
+/// Decodes playlist data as UTF-8, falling back to Latin-1 (ISO-8859-1) if the data is not valid
+/// UTF-8.
+///
+/// When the data is valid UTF-8 it is borrowed without copying. Otherwise, every byte is mapped to
+/// the Unicode code point of the same value (which is how Latin-1 maps to Unicode), so that the
+/// playlist can still be parsed rather than failing validation at the first high byte. A leading
+/// UTF-8 BOM is preserved as `U+FEFF` in either case, so that it is still detected by the
+/// [`crate::Reader`].
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions, encoding::decode_lenient};
+/// # use quick_m3u8::tag::{KnownTag, hls};
+/// let data = b"#EXTINF:4,Caf\xE9\nsegment.ts\n";
+/// let decoded = decode_lenient(data);
+/// let mut reader = Reader::from_str(&decoded, ParsingOptions::default());
+/// match reader.read_line() {
+///     Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(tag))))) => {
+///         assert_eq!("Café", tag.title());
+///     }
+///     r => panic!("unexpected result {r:?}"),
+/// }
+/// ```
+#[cfg(feature = "latin1")]
+pub fn decode_lenient(data: &[u8]) -> Cow<'_, str> {
+    if let Ok(utf_8) = std::str::from_utf8(data) {
+        return Cow::Borrowed(utf_8);
+    }
+    let mut decoded = String::with_capacity(data.len() + data.len() / 2);
+    let data = match data.strip_prefix(UTF_8_BOM.as_slice()) {
+        Some(stripped) => {
+            decoded.push('\u{FEFF}');
+            stripped
+        }
+        None => data,
+    };
+    decoded.extend(data.iter().map(|byte| char::from(*byte)));
+    Cow::Owned(decoded)
+}
+
+#[cfg(all(test, feature = "latin1"))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn valid_utf_8_should_be_borrowed() {
+        let decoded = decode_lenient("#EXTINF:4,Café\n".as_bytes());
+        assert!(matches!(decoded, Cow::Borrowed("#EXTINF:4,Café\n")));
+    }
+
+    #[test]
+    fn bom_should_be_preserved_when_falling_back_to_latin_1() {
+        assert_eq!(
+            "\u{FEFF}#EXTINF:4,Caf\u{E9}\n",
+            decode_lenient(b"\xEF\xBB\xBF#EXTINF:4,Caf\xE9\n")
+        );
+    }
+}
@@ -33,6 +33,48 @@ pub struct ReaderBytesError<'a> {
     pub error: SyntaxError,
 }
 
+/// Error in reading a line from a [`crate::Reader`] constructed with [`crate::Reader::from_reader`]
+/// (or from a `crate::AsyncReader`, available with the `tokio` feature).
+///
+/// Unlike the other reader errors, this error owns the line that caused it, as the line is read
+/// into a buffer that is re-used for the next line.
+#[derive(Debug)]
+pub enum ReaderIoError {
+    /// The underlying reader experienced an I/O error.
+    ///
+    /// The line that was being read when the error happened is lost, as is the case with
+    /// [`std::io::BufRead::read_until`].
+    Io(std::io::Error),
+    /// The line that was read could not be parsed.
+    Syntax {
+        /// The original line that caused the error.
+        errored_line: Vec<u8>,
+        /// The underlying error that was experienced.
+        error: SyntaxError,
+    },
+}
+impl Display for ReaderIoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::Syntax { error, .. } => error.fmt(f),
+        }
+    }
+}
+impl Error for ReaderIoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Syntax { error, .. } => Some(error),
+        }
+    }
+}
+impl From<std::io::Error> for ReaderIoError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
 /// Error in reading a line from [`crate::line::parse`] (or [`crate::line::parse_with_custom`]).
 #[derive(Debug, PartialEq, Clone)]
 pub struct ParseLineStrError<'a> {
@@ -102,8 +144,14 @@ pub enum GenericSyntaxError {
     CarriageReturnWithoutLineFeed,
     /// The line ended unexpectedly (e.g. within a quoted string in an attribute list).
     UnexpectedEndOfLine,
+    /// More than one line was provided where only a single line was expected (see
+    /// [`crate::parse_tag_line`]).
+    UnexpectedLineBreak,
     /// Some part of the line could not be decoded as UTF-8.
     InvalidUtf8(Utf8Error),
+    /// The reader has read the maximum number of lines that it was configured to read (see
+    /// [`crate::config::ParsingOptionsBuilder::with_max_lines`]).
+    LineLimitExceeded,
 }
 impl Display for GenericSyntaxError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -113,7 +161,14 @@ impl Display for GenericSyntaxError {
                 "carriage return (U+000D) without a following line feed (U+000A) is not supported"
             ),
             Self::UnexpectedEndOfLine => write!(f, "line ended unexpectedly during parsing"),
+            Self::UnexpectedLineBreak => {
+                write!(
+                    f,
+                    "expected a single line but found content after the line break"
+                )
+            }
             Self::InvalidUtf8(e) => write!(f, "invalid utf-8 due to {e}"),
+            Self::LineLimitExceeded => write!(f, "maximum number of lines read"),
         }
     }
 }
@@ -283,6 +338,28 @@ impl From<GenericSyntaxError> for DateTimeSyntaxError {
     }
 }
 
+/// An error experienced while converting between [`crate::date::DateTime`] and the date types of
+/// another library (`chrono` or `time`).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DateTimeConversionError {
+    /// The date is outside of the range supported by the destination type (for example, the year
+    /// is not `4DIGIT`).
+    OutOfRange,
+    /// The timezone offset cannot be represented by the destination type. For example, a
+    /// `DateTime` cannot represent an offset that is not a whole number of minutes, nor one that is
+    /// negative and less than an hour (as the sign is carried by `time_hour`).
+    UnsupportedTimezoneOffset,
+}
+impl Display for DateTimeConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfRange => write!(f, "date out of range"),
+            Self::UnsupportedTimezoneOffset => write!(f, "unsupported timezone offset"),
+        }
+    }
+}
+impl Error for DateTimeConversionError {}
+
 /// A syntax error found while trying to parse a tag value.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum TagValueSyntaxError {
@@ -386,6 +463,10 @@ pub enum ValidationError {
     /// The enumerated string extracted from [`crate::tag::UnquotedAttributeValue`] was not a known
     /// value.
     InvalidEnumeratedString,
+    /// A required numeric attribute was a number that cannot be represented by the type of the
+    /// attribute, such as a negative or overflowing integer (the associated value should be the
+    /// attribute name).
+    NumericOutOfRange(&'static str),
 }
 impl Display for ValidationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -398,6 +479,7 @@ impl Display for ValidationError {
                 write!(f, "attribute list value error - {e}")
             }
             Self::InvalidEnumeratedString => write!(f, "invalid enumerated string in value"),
+            Self::NumericOutOfRange(a) => write!(f, "attribute {a} is out of range"),
         }
     }
 }
@@ -705,6 +787,68 @@ impl From<ParseDecimalIntegerRangeError> for ParseMapByterangeError {
     }
 }
 
+/// An error found when converting a byte range into a [`crate::tag::RangeRequest`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RangeRequestError {
+    /// The offset component was missing, and so the start of the range is not known (see
+    /// [`crate::tag::DecimalIntegerRange::with_implicit_offset`]).
+    MissingOffset,
+    /// The end of the range would overflow `u64`.
+    Overflow,
+}
+impl Display for RangeRequestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingOffset => write!(f, "missing offset component"),
+            Self::Overflow => write!(f, "end of range overflows u64"),
+        }
+    }
+}
+impl Error for RangeRequestError {}
+
+/// An error found when parsing the `IV` attribute of `EXT-X-KEY` into bytes (see
+/// [`crate::tag::hls::Key::iv_bytes`]).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ParseIvError {
+    /// The value did not start with `0x` (or `0X`).
+    MissingHexPrefix,
+    /// There were no hexadecimal digits after the prefix.
+    Empty,
+    /// A character after the prefix was not a hexadecimal digit.
+    InvalidDigit,
+    /// The value has more than 32 hexadecimal digits, and so does not fit in 128 bits.
+    TooLong,
+}
+impl Display for ParseIvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingHexPrefix => write!(f, "missing 0x prefix"),
+            Self::Empty => write!(f, "no hexadecimal digits"),
+            Self::InvalidDigit => write!(f, "invalid hexadecimal digit"),
+            Self::TooLong => write!(f, "more than 128 bits"),
+        }
+    }
+}
+impl Error for ParseIvError {}
+
+/// An error found when trying to parse a [`crate::tag::SegmentDuration`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ParseSegmentDurationError {
+    /// The value was not a decimal number (`<digits>[.<digits>]`).
+    InvalidDecimal,
+    /// The value has more significant digits than can be represented exactly (19).
+    TooPrecise,
+}
+impl Display for ParseSegmentDurationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidDecimal => write!(f, "invalid decimal"),
+            Self::TooPrecise => write!(f, "decimal has too many significant digits"),
+        }
+    }
+}
+impl Error for ParseSegmentDurationError {}
+
 /// An error found when trying to parse a float from a byte slice (`&[u8]`).
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct ParseFloatError;
@@ -805,3 +949,456 @@ impl From<std::str::Utf8Error> for AttributeListParsingError {
         Self::InvalidUtf8(value)
     }
 }
+
+/// An error found when clipping a playlist with [`crate::playlist::clip`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ClipError {
+    /// The playlist does not contain an `EXT-X-PROGRAM-DATE-TIME` tag, so segments cannot be
+    /// located in time.
+    MissingProgramDateTime,
+    /// None of the segments in the playlist fall within the requested time range.
+    NoSegmentsInRange,
+    /// The media sequence (or discontinuity sequence) number of the first segment of the clip would
+    /// be greater than `u64::MAX`.
+    SequenceOverflow,
+}
+impl Display for ClipError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingProgramDateTime => write!(f, "missing EXT-X-PROGRAM-DATE-TIME"),
+            Self::NoSegmentsInRange => write!(f, "no segments in range"),
+            Self::SequenceOverflow => write!(f, "sequence number greater than u64::MAX"),
+        }
+    }
+}
+impl Error for ClipError {}
+
+/// An error found when filling gaps with [`crate::playlist::fill_gaps`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GapFillError {
+    /// More segments are missing from a gap than allowed (see
+    /// [`crate::playlist::GapFillOptions::with_max_gap`]).
+    GapTooLarge {
+        /// The Media Sequence Number of the segment before the gap.
+        previous_media_sequence: u64,
+        /// The number of segments missing from the gap.
+        missing: u64,
+    },
+}
+impl Display for GapFillError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GapTooLarge {
+                previous_media_sequence,
+                missing,
+            } => write!(
+                f,
+                "{missing} segments missing after media sequence {previous_media_sequence}"
+            ),
+        }
+    }
+}
+impl Error for GapFillError {}
+
+/// An error found when lazily parsing a numeric attribute value from a tag (see, for example,
+/// [`crate::tag::hls::StreamInf::try_average_bandwidth`]).
+///
+/// The raw text of the attribute value is provided in each case, so that it is still possible to
+/// make use of the value, even though the library could not represent it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NumericAttributeError<'a> {
+    /// The value is a number but it is not in the range that can be represented by the type of the
+    /// attribute (for example, `BANDWIDTH=-1`, or an integer that overflows `u64`).
+    NumericOutOfRange(&'a str),
+    /// The value is not a number.
+    NotNumeric(&'a str),
+}
+impl<'a> NumericAttributeError<'a> {
+    /// The raw text of the attribute value.
+    pub fn raw(&self) -> &'a str {
+        match self {
+            Self::NumericOutOfRange(raw) | Self::NotNumeric(raw) => raw,
+        }
+    }
+}
+impl Display for NumericAttributeError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NumericOutOfRange(raw) => write!(f, "{raw} is out of range"),
+            Self::NotNumeric(raw) => write!(f, "{raw} is not a number"),
+        }
+    }
+}
+impl Error for NumericAttributeError<'_> {}
+
+/// An error found when instantiating a [`crate::playlist::PlaylistTemplate`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TemplateError<'a> {
+    /// A variable referenced in the template had no value provided.
+    UndefinedVariable(&'a str),
+}
+impl Display for TemplateError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UndefinedVariable(name) => write!(f, "undefined variable {name}"),
+        }
+    }
+}
+impl Error for TemplateError<'_> {}
+
+/// An error found when parsing or applying a [`crate::playlist::PlaylistPatch`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PatchError {
+    /// The patch is not in the expected format.
+    InvalidFormat {
+        /// The 1-based line number within the patch.
+        line_number: usize,
+    },
+    /// The patch format version is not supported by this version of the library.
+    UnsupportedVersion(u64),
+    /// The playlist that the patch was applied to is not the playlist that the patch was created
+    /// from.
+    SourceMismatch,
+    /// The operation at the index does not follow on from the previous operation (or the
+    /// operations do not cover every line of the source playlist, in which case the index is the
+    /// number of operations).
+    InvalidOperation(usize),
+}
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFormat { line_number } => write!(f, "invalid patch line {line_number}"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported patch version {version}"),
+            Self::SourceMismatch => write!(f, "patch does not apply to this playlist"),
+            Self::InvalidOperation(index) => write!(f, "invalid patch operation {index}"),
+        }
+    }
+}
+impl Error for PatchError {}
+
+/// An error found when parsing a SCTE-35 `splice_info_section` (see `scte35::parse`, available
+/// with the `scte35` feature).
+#[cfg(feature = "scte35")]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Scte35Error {
+    /// The value is not a hexadecimal sequence.
+    InvalidHex,
+    /// The section ended before all of its fields were read.
+    UnexpectedEnd,
+    /// The `table_id` is not `0xFC`.
+    UnexpectedTableId(u8),
+    /// The section is encrypted, and so cannot be parsed.
+    EncryptedPacket,
+}
+#[cfg(feature = "scte35")]
+impl Display for Scte35Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidHex => write!(f, "invalid hexadecimal sequence"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of splice_info_section"),
+            Self::UnexpectedTableId(id) => write!(f, "unexpected table_id 0x{id:02X}"),
+            Self::EncryptedPacket => write!(f, "encrypted splice_info_section"),
+        }
+    }
+}
+#[cfg(feature = "scte35")]
+impl Error for Scte35Error {}
+
+/// An error found when replacing the raw bytes of a line (see [`crate::HlsLine::replace_raw`]).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ReplaceRawError {
+    /// The line has no serialized content that can be replaced. This is the case for
+    /// [`crate::HlsLine::Blank`], and for known tags that have no value (e.g. `EXT-X-ENDLIST`)
+    /// along with `EXT-X-PLAYLIST-TYPE`.
+    UnsupportedLine,
+    /// The line is a comment, but the replacement does not start with `#`.
+    MissingCommentPrefix,
+    /// The line is a comment or URI, but the replacement is not valid UTF-8.
+    InvalidUtf8(Utf8Error),
+}
+impl Display for ReplaceRawError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedLine => write!(f, "line does not support raw replacement"),
+            Self::MissingCommentPrefix => write!(f, "comment replacement must start with #"),
+            Self::InvalidUtf8(e) => write!(f, "invalid utf-8 due to {e}"),
+        }
+    }
+}
+impl Error for ReplaceRawError {}
+
+/// An error in parsing a playlist via [`crate::parse_media_playlist`] or
+/// [`crate::parse_multivariant_playlist`], where the playlist is not of the expected kind.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PlaylistKindError {
+    /// A Media Playlist was expected, but the playlist is a Multivariant Playlist.
+    ExpectedMediaPlaylist,
+    /// A Multivariant Playlist was expected, but the playlist is a Media Playlist.
+    ExpectedMultivariantPlaylist,
+}
+impl Display for PlaylistKindError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ExpectedMediaPlaylist => {
+                write!(
+                    f,
+                    "expected a media playlist but found a multivariant playlist"
+                )
+            }
+            Self::ExpectedMultivariantPlaylist => {
+                write!(
+                    f,
+                    "expected a multivariant playlist but found a media playlist"
+                )
+            }
+        }
+    }
+}
+impl Error for PlaylistKindError {}
+
+/// An error in the Delivery Directives of a playlist request (see
+/// [`crate::playlist::DeliveryDirectives::from_uri`] and
+/// [`crate::playlist::DeliveryDirectives::for_next_reload`]).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DeliveryDirectiveError {
+    /// The value of the directive is invalid.
+    InvalidValue(&'static str),
+    /// The directive is provided more than once.
+    RepeatedDirective(&'static str),
+    /// `_HLS_part` is provided without `_HLS_msn`.
+    PartWithoutMsn,
+    /// The media sequence number to request would be greater than `u64::MAX`.
+    MsnOverflow,
+}
+impl Display for DeliveryDirectiveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidValue(directive) => write!(f, "invalid value for {directive}"),
+            Self::RepeatedDirective(directive) => write!(f, "{directive} is repeated"),
+            Self::PartWithoutMsn => write!(f, "_HLS_part is provided without _HLS_msn"),
+            Self::MsnOverflow => write!(f, "_HLS_msn would be greater than u64::MAX"),
+        }
+    }
+}
+impl Error for DeliveryDirectiveError {}
+
+/// An error found when resolving an `EXT-X-SESSION-DATA` tag via
+/// [`crate::playlist::resolve_session_data`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum SessionDataError<E> {
+    /// The tag has neither a `VALUE` nor a `URI` (exactly one is required).
+    MissingValueAndUri,
+    /// The tag has both a `VALUE` and a `URI` (exactly one is required).
+    ValueAndUri,
+    /// The data fetched from the `URI` has `FORMAT=JSON` but is not valid UTF-8 (as is required
+    /// of JSON by RFC8259).
+    InvalidJsonEncoding(Utf8Error),
+    /// The resolver failed to fetch the data from the `URI`.
+    Fetch(E),
+}
+impl<E> Display for SessionDataError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingValueAndUri => write!(f, "session data has neither VALUE nor URI"),
+            Self::ValueAndUri => write!(f, "session data has both VALUE and URI"),
+            Self::InvalidJsonEncoding(e) => write!(f, "invalid JSON encoding due to {e}"),
+            Self::Fetch(e) => write!(f, "failed to fetch session data due to {e}"),
+        }
+    }
+}
+impl<E> Error for SessionDataError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidJsonEncoding(e) => Some(e),
+            Self::Fetch(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// An error found when parsing an interstitial asset list via [`crate::asset_list::parse`]
+/// (available with the `asset-list` feature).
+#[cfg(feature = "asset-list")]
+#[derive(Debug, PartialEq, Clone)]
+pub enum AssetListError {
+    /// The document is not valid JSON, or is not an object with an `ASSETS` array of objects that
+    /// each have a string `URI` and a number `DURATION` (the description of the problem is
+    /// provided).
+    InvalidJson(String),
+    /// The `DURATION` of the asset at the index within `ASSETS` is negative or not finite.
+    InvalidDuration(usize),
+}
+#[cfg(feature = "asset-list")]
+impl Display for AssetListError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidJson(e) => write!(f, "invalid asset list due to {e}"),
+            Self::InvalidDuration(index) => write!(f, "invalid DURATION of asset {index}"),
+        }
+    }
+}
+#[cfg(feature = "asset-list")]
+impl Error for AssetListError {}
+
+/// An error found when resolving the assets of an interstitial via
+/// [`crate::asset_list::resolve_assets`] (available with the `asset-list` feature).
+#[cfg(feature = "asset-list")]
+#[derive(Debug, PartialEq, Clone)]
+pub enum ResolveAssetsError<E> {
+    /// The interstitial has neither an `X-ASSET-URI` nor an `X-ASSET-LIST` (exactly one is
+    /// required).
+    MissingAssetUriAndList,
+    /// The interstitial has both an `X-ASSET-URI` and an `X-ASSET-LIST` (exactly one is required).
+    AssetUriAndList,
+    /// The asset list could not be fetched from the `X-ASSET-LIST` URI.
+    Fetch(E),
+    /// The asset list fetched could not be parsed.
+    AssetList(AssetListError),
+}
+#[cfg(feature = "asset-list")]
+impl<E> Display for ResolveAssetsError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingAssetUriAndList => {
+                write!(f, "interstitial has neither X-ASSET-URI nor X-ASSET-LIST")
+            }
+            Self::AssetUriAndList => {
+                write!(f, "interstitial has both X-ASSET-URI and X-ASSET-LIST")
+            }
+            Self::Fetch(e) => write!(f, "failed to fetch asset list due to {e}"),
+            Self::AssetList(e) => write!(f, "{e}"),
+        }
+    }
+}
+#[cfg(feature = "asset-list")]
+impl<E> Error for ResolveAssetsError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Fetch(e) => Some(e),
+            Self::AssetList(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// An error found when a tag about to be written by the [`crate::Writer`] would not be parsable when
+/// read back (see [`crate::ValueValidation`]).
+///
+/// This happens when a value set on a tag breaks the quoting rules of the attribute list (e.g. a
+/// quoted-string containing `"`, or an enumerated-string containing whitespace), or when a value
+/// contains a line break.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnparsableTagError {
+    /// The name of the tag (everything after the `#EXT` prefix).
+    pub tag_name: String,
+    /// The reason the tag would not be parsable.
+    pub reason: UnparsableTagReason,
+}
+impl Display for UnparsableTagError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "#EXT{} would not be parsable: {}",
+            self.tag_name, self.reason
+        )
+    }
+}
+impl Error for UnparsableTagError {}
+
+/// The reason that a tag would not be parsable (see [`UnparsableTagError`]).
+#[derive(Debug, PartialEq, Clone)]
+pub enum UnparsableTagReason {
+    /// The line contains a carriage return or line feed, and so would be split into more than one
+    /// line.
+    LineBreak,
+    /// The attribute list could not be parsed. This is the case when a quoted-string value contains
+    /// `"`, or when an unquoted value contains `"`, `,` or `=`.
+    InvalidAttributeList(AttributeListParsingError),
+    /// An unquoted value (e.g. an enumerated-string) contains whitespace.
+    WhitespaceInUnquotedValue {
+        /// The name of the attribute with the invalid value.
+        attr_name: String,
+    },
+}
+impl Display for UnparsableTagReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LineBreak => write!(f, "line break in value"),
+            Self::InvalidAttributeList(e) => write!(f, "invalid attribute list due to {e}"),
+            Self::WhitespaceInUnquotedValue { attr_name } => {
+                write!(f, "whitespace in unquoted value of {attr_name}")
+            }
+        }
+    }
+}
+
+/// A failure found when running a conformance test vector (see
+/// `conformance::TestVector::run`, available with the `conformance` feature).
+#[cfg(feature = "conformance")]
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConformanceFailure {
+    /// The line could not be read by the [`crate::Reader`].
+    Syntax {
+        /// The 1-based line number within the vector playlist.
+        line_number: usize,
+        /// The underlying error.
+        error: ReaderStrError<'static>,
+    },
+    /// The line is a known HLS tag, but it failed validation, and so was read as
+    /// [`crate::HlsLine::UnknownTag`].
+    Validation {
+        /// The 1-based line number within the vector playlist.
+        line_number: usize,
+        /// The name of the tag (everything after the `#EXT` prefix).
+        tag_name: &'static str,
+        /// The underlying error.
+        error: ValidationError,
+    },
+    /// Writing the line read by the [`crate::Reader`] with the [`crate::Writer`] did not reproduce
+    /// the original line.
+    RoundTrip {
+        /// The 1-based line number within the vector playlist.
+        line_number: usize,
+        /// The original line.
+        expected: &'static str,
+        /// The line that was written.
+        actual: String,
+    },
+}
+#[cfg(feature = "conformance")]
+impl Display for ConformanceFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { line_number, error } => {
+                write!(f, "line {line_number}: {}", error.error)
+            }
+            Self::Validation {
+                line_number,
+                tag_name,
+                error,
+            } => write!(f, "line {line_number}: #EXT{tag_name} is invalid: {error}"),
+            Self::RoundTrip {
+                line_number,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "line {line_number}: expected {expected} to be written but got {actual}"
+            ),
+        }
+    }
+}
+#[cfg(feature = "conformance")]
+impl Error for ConformanceFailure {}
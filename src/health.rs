@@ -0,0 +1,364 @@
+//! A health report of a playlist, for exposing from monitoring endpoints.
+//!
+//! Monitoring a live stream usually involves a sidecar that fetches the playlist periodically and
+//! exposes some numbers about it (how many segments there are, how far behind the wall clock the
+//! live edge is, whether the playlist is valid, etc.). This module combines the
+//! [`crate::playlist::segment_list`], [`crate::playlist::live_edge`], and [`crate::validation`]
+//! helpers into a single flat map of metrics (see [`HealthReport`]), that can also be provided as
+//! [Prometheus exposition text].
+//!
+//! For example:
+//! ```
+//! # use quick_m3u8::{date_time, health::HealthChecks};
+//! const PLAYLIST: &str = r#"#EXTM3U
+//! #EXT-X-TARGETDURATION:4
+//! #EXT-X-MEDIA-SEQUENCE:100
+//! #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z
+//! #EXTINF:4
+//! 100.ts
+//! #EXTINF:4.5
+//! 101.ts
+//! #EXTINF:4
+//! 102.ts
+//! #EXTINF:4
+//! 103.ts
+//! "#;
+//!
+//! let report = HealthChecks::new()
+//!     .with_now(date_time!(2025-01-01 T 00:00:20.000))
+//!     .report(PLAYLIST);
+//! assert_eq!(Some(4.0), report.get("segments"));
+//! assert_eq!(Some(16.5), report.get("duration_seconds"));
+//! assert_eq!(Some(12.0), report.get("live_edge_hold_back_seconds"));
+//! assert_eq!(Some(15.5), report.get("live_edge_latency_seconds"));
+//! assert_eq!(Some(3.5), report.get("playlist_age_seconds"));
+//! // Both the 4.5s segment exceeding the target duration and the lack of EXT-X-VERSION:3.
+//! assert_eq!(Some(2.0), report.get("validation_errors"));
+//!
+//! let text = report.to_prometheus("hls_playlist", &[("playlist", "main.m3u8")]);
+//! assert!(text.contains("# TYPE hls_playlist_segments gauge\n"));
+//! assert!(text.contains("hls_playlist_segments{playlist=\"main.m3u8\"} 4\n"));
+//! ```
+//!
+//! [Prometheus exposition text]: https://prometheus.io/docs/instrumenting/exposition_formats/
+
+use crate::{
+    date::DateTime,
+    playlist::{live_edge, segment_list},
+    validation::{Severity, Validator},
+};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Provides a [`HealthReport`] of the playlist with all checks enabled.
+///
+/// See [`HealthChecks`] for more control over the checks.
+pub fn report(playlist: &str) -> HealthReport {
+    HealthChecks::new().report(playlist)
+}
+
+/// Configures which checks are included in a [`HealthReport`].
+///
+/// Each check reads the playlist separately, and so disabling those that are not needed keeps the
+/// report cheap. By default, all checks are enabled, and no wall clock time is provided (and so
+/// the metrics that depend on it are not reported).
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct HealthChecks {
+    without_stats: bool,
+    without_latency: bool,
+    without_validation: bool,
+    validator: Validator,
+    now: Option<DateTime>,
+}
+
+impl HealthChecks {
+    /// Creates the checks with all checks enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the stats of the segments are reported (see [`HealthReport`] for the metrics).
+    pub fn with_stats(mut self, enabled: bool) -> Self {
+        self.without_stats = !enabled;
+        self
+    }
+
+    /// Sets whether the position and latency of the live edge are reported.
+    pub fn with_latency(mut self, enabled: bool) -> Self {
+        self.without_latency = !enabled;
+        self
+    }
+
+    /// Sets whether the playlist is validated (and the counts of diagnostics reported).
+    pub fn with_validation(mut self, enabled: bool) -> Self {
+        self.without_validation = !enabled;
+        self
+    }
+
+    /// Sets the validator used to validate the playlist (for configuring the rules that are
+    /// checked, and the severity of their violations).
+    pub fn with_validator(mut self, validator: Validator) -> Self {
+        self.validator = validator;
+        self
+    }
+
+    /// Sets the wall clock time at which the playlist was fetched, which enables the metrics that
+    /// compare the `EXT-X-PROGRAM-DATE-TIME` of the playlist to the wall clock.
+    pub fn with_now(mut self, now: DateTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Checks the playlist.
+    pub fn report(&self, playlist: &str) -> HealthReport {
+        let mut report = HealthReport::default();
+        if !self.without_stats {
+            self.report_stats(playlist, &mut report);
+        }
+        if !self.without_latency {
+            self.report_latency(playlist, &mut report);
+        }
+        if !self.without_validation {
+            let diagnostics = self.validator.validate_playlist(playlist);
+            let count = |severity| {
+                diagnostics
+                    .iter()
+                    .filter(|diagnostic| diagnostic.severity == severity)
+                    .count() as f64
+            };
+            report.insert("validation_errors", count(Severity::Error));
+            report.insert("validation_warnings", count(Severity::Warning));
+        }
+        report
+    }
+
+    fn report_stats(&self, playlist: &str, report: &mut HealthReport) {
+        let list = segment_list(playlist);
+        report.insert("segments", list.segments.len() as f64);
+        report.insert("duration_seconds", list.duration());
+        report.insert("media_sequence", list.media_sequence as f64);
+        report.insert("discontinuity_sequence", list.discontinuity_sequence as f64);
+        report.insert("ended", f64::from(u8::from(list.ended)));
+        report.insert(
+            "gap_segments",
+            list.segments.iter().filter(|segment| segment.gap).count() as f64,
+        );
+        if let Some(target_duration) = list.target_duration {
+            report.insert("target_duration_seconds", target_duration as f64);
+        }
+        if let Some(max) = list
+            .segments
+            .iter()
+            .map(|segment| segment.duration)
+            .reduce(f64::max)
+        {
+            report.insert("max_segment_duration_seconds", max);
+        }
+        if let (Some(now), Some(last)) = (self.now, list.segments.last())
+            && let Some(date) = last.program_date_time
+        {
+            let end = date.add_seconds(last.duration);
+            report.insert("playlist_age_seconds", now.duration_since(&end));
+        }
+    }
+
+    fn report_latency(&self, playlist: &str, report: &mut HealthReport) {
+        let Some(edge) = live_edge(playlist) else {
+            return;
+        };
+        report.insert("live_edge_hold_back_seconds", edge.hold_back);
+        report.insert("live_edge_position_seconds", edge.position);
+        if let (Some(now), Some(date)) = (self.now, edge.program_date_time) {
+            let position_date = date.add_seconds(edge.position - edge.start);
+            report.insert(
+                "live_edge_latency_seconds",
+                now.duration_since(&position_date),
+            );
+        }
+    }
+}
+
+/// The metrics of a playlist, as provided by [`HealthChecks::report`].
+///
+/// The metrics are a flat map of name to value, where the following metrics are provided depending
+/// on the checks that are enabled (and on the content of the playlist):
+///
+/// | Check      | Name                           | Description                                    |
+/// |------------|--------------------------------|------------------------------------------------|
+/// | stats      | `segments`                     | The number of segments.                        |
+/// | stats      | `duration_seconds`             | The sum of the segment durations.              |
+/// | stats      | `media_sequence`               | The media sequence number of the first segment.|
+/// | stats      | `discontinuity_sequence`       | The discontinuity sequence number.             |
+/// | stats      | `ended`                        | `1` when there is `EXT-X-ENDLIST`, else `0`.   |
+/// | stats      | `gap_segments`                 | The number of segments with `EXT-X-GAP`.       |
+/// | stats      | `target_duration_seconds`      | The `EXT-X-TARGETDURATION` (when present).     |
+/// | stats      | `max_segment_duration_seconds` | The longest segment (when there are segments). |
+/// | stats      | `playlist_age_seconds`         | The time from the end of the last segment to the wall clock (when dated). |
+/// | latency    | `live_edge_hold_back_seconds`  | The hold back from the end of the playlist (when live). |
+/// | latency    | `live_edge_position_seconds`   | The recommended playback position (when live). |
+/// | latency    | `live_edge_latency_seconds`    | The time from the playback position to the wall clock (when live and dated). |
+/// | validation | `validation_errors`            | The number of [`Severity::Error`] diagnostics. |
+/// | validation | `validation_warnings`          | The number of [`Severity::Warning`] diagnostics. |
+///
+/// The metrics that compare the playlist to the wall clock are only provided when the time is set
+/// via [`HealthChecks::with_now`].
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct HealthReport {
+    metrics: BTreeMap<&'static str, f64>,
+}
+
+impl HealthReport {
+    /// The value of the metric with the given name (if reported).
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.metrics.get(name).copied()
+    }
+
+    /// The metrics that were reported, ordered by name.
+    pub fn metrics(&self) -> &BTreeMap<&'static str, f64> {
+        &self.metrics
+    }
+
+    /// Provides the metrics in the Prometheus text exposition format, where each metric is a gauge
+    /// named `<prefix>_<name>`, and has the provided labels.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::health::HealthChecks;
+    /// let report = HealthChecks::new()
+    ///     .with_latency(false)
+    ///     .with_validation(false)
+    ///     .report("#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:4\n0.ts\n#EXT-X-ENDLIST\n");
+    /// assert_eq!(
+    ///     concat!(
+    ///         "# TYPE hls_ended gauge\n",
+    ///         "hls_ended 1\n",
+    ///         "# TYPE hls_segments gauge\n",
+    ///         "hls_segments 1\n",
+    ///     ),
+    ///     report.to_prometheus("hls", &[])
+    ///         .lines()
+    ///         .filter(|line| line.contains("ended") || line.contains("hls_segments"))
+    ///         .map(|line| format!("{line}\n"))
+    ///         .collect::<String>()
+    /// );
+    /// ```
+    pub fn to_prometheus(&self, prefix: &str, labels: &[(&str, &str)]) -> String {
+        let mut label_text = String::new();
+        if !labels.is_empty() {
+            label_text.push('{');
+            for (index, (name, value)) in labels.iter().enumerate() {
+                if index > 0 {
+                    label_text.push(',');
+                }
+                label_text.push_str(name);
+                label_text.push_str("=\"");
+                for c in value.chars() {
+                    match c {
+                        '\\' => label_text.push_str("\\\\"),
+                        '"' => label_text.push_str("\\\""),
+                        '\n' => label_text.push_str("\\n"),
+                        c => label_text.push(c),
+                    }
+                }
+                label_text.push('"');
+            }
+            label_text.push('}');
+        }
+        let mut text = String::new();
+        for (name, value) in &self.metrics {
+            // Writing to a String does not fail.
+            let _ = write!(
+                text,
+                "# TYPE {prefix}_{name} gauge\n{prefix}_{name}{label_text} {value}\n"
+            );
+        }
+        text
+    }
+
+    fn insert(&mut self, name: &'static str, value: f64) {
+        self.metrics.insert(name, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{date_time, validation::ValidationRule};
+    use pretty_assertions::assert_eq;
+
+    const LIVE_PLAYLIST: &str = concat!(
+        "#EXTM3U\n",
+        "#EXT-X-VERSION:3\n",
+        "#EXT-X-TARGETDURATION:4\n",
+        "#EXT-X-MEDIA-SEQUENCE:7\n",
+        "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+        "#EXTINF:4\n",
+        "7.ts\n",
+        "#EXT-X-GAP\n",
+        "#EXTINF:4\n",
+        "8.ts\n",
+        "#EXTINF:4.5\n",
+        "9.ts\n",
+        "#EXTINF:4\n",
+        "10.ts\n",
+    );
+
+    #[test]
+    fn report_should_include_all_checks_by_default() {
+        let report = report(LIVE_PLAYLIST);
+        assert_eq!(
+            vec![
+                ("discontinuity_sequence", 0.0),
+                ("duration_seconds", 16.5),
+                ("ended", 0.0),
+                ("gap_segments", 1.0),
+                ("live_edge_hold_back_seconds", 12.0),
+                ("live_edge_position_seconds", 4.5),
+                ("max_segment_duration_seconds", 4.5),
+                ("media_sequence", 7.0),
+                ("segments", 4.0),
+                ("target_duration_seconds", 4.0),
+                ("validation_errors", 1.0),
+                ("validation_warnings", 0.0),
+            ],
+            report
+                .metrics()
+                .iter()
+                .map(|(name, value)| (*name, *value))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn disabled_checks_should_not_be_reported() {
+        let checks = HealthChecks::new()
+            .with_stats(false)
+            .with_validator(Validator::new().without_rule(ValidationRule::TargetDuration))
+            .with_now(date_time!(2025-01-01 T 01:00:10.000 01:00));
+        let report = checks.report(LIVE_PLAYLIST);
+        assert_eq!(None, report.get("segments"));
+        assert_eq!(None, report.get("playlist_age_seconds"));
+        assert_eq!(Some(0.0), report.get("validation_errors"));
+        assert_eq!(Some(5.5), report.get("live_edge_latency_seconds"));
+
+        let report = checks
+            .with_latency(false)
+            .with_validation(false)
+            .report(LIVE_PLAYLIST);
+        assert_eq!(HealthReport::default(), report);
+        assert_eq!("", report.to_prometheus("hls", &[]));
+    }
+
+    #[test]
+    fn prometheus_labels_should_be_escaped() {
+        let report = HealthChecks::new()
+            .with_latency(false)
+            .with_validation(false)
+            .report("#EXTM3U\n#EXT-X-ENDLIST\n");
+        let text = report.to_prometheus("hls", &[("uri", "a\"b\\c\nd"), ("cdn", "x")]);
+        assert!(
+            text.contains("hls_segments{uri=\"a\\\"b\\\\c\\nd\",cdn=\"x\"} 0\n"),
+            "{text}"
+        );
+        assert_eq!(12, text.lines().count());
+    }
+}
@@ -3,6 +3,7 @@
     missing_copy_implementations,
     missing_debug_implementations
 )]
+#![deny(unsafe_code)]
 #![doc(
     html_favicon_url = "https://raw.githubusercontent.com/theRealRobG/m3u8/refs/heads/main/quick-m3u8-logo.ico"
 )]
@@ -118,12 +119,25 @@
 //! is a tag, based on the `#EXT` prefix, but not one that we know about), and also allows
 //! `CustomTag`. The [`tag::CustomTag`] is a means for the user of the library to define support for
 //! their own custom tag specification in addition to what is provided via the HLS specification.
-//! The documentation for `CustomTag` provides more details on how that is achieved.
+//! The documentation for `CustomTag` provides more details on how that is achieved, and for tags
+//! whose value is an attribute list, the [`custom_tag!`] macro generates the implementation.
 //!
 //! The `Reader` also takes a configuration that allows the user to select what HLS tags the reader
 //! should parse. [`config::ParsingOptions`] provides more details, but in short, better performance
 //! can be squeezed out by only parsing the tags that you need.
 //!
+//! When all that is needed is a model of the whole playlist, [`parse_media_playlist`] and
+//! [`parse_multivariant_playlist`] read the playlist in one call with default options, providing
+//! the assembled playlist along with any problems found while parsing:
+//! ```
+//! # use quick_m3u8::parse_media_playlist;
+//! # const EXAMPLE_MANIFEST: &str = "#EXTM3U\n#EXT-X-TARGETDURATION:10\n#EXTINF:9.009,\nfirst.ts\n";
+//! let parsed = parse_media_playlist(EXAMPLE_MANIFEST)?;
+//! assert!(parsed.diagnostics.is_empty());
+//! assert_eq!("first.ts", parsed.playlist.segments[0].uri);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
 //! ## Writing
 //!
 //! The other component to quick-m3u8 is [`Writer`]. This allows the user to write to a given
@@ -179,6 +193,69 @@
 //! # Ok::<(), io::Error>(())
 //! ```
 //!
+//! # Without the HLS tag definitions
+//!
+//! The definitions of the HLS tags ([`tag::hls`]) are enabled by the default `hls-tags` feature.
+//! Tools that only pass lines through, or only work with custom tags, can disable the default
+//! features to compile just the line and attribute scanning along with [`tag::UnknownTag`] and
+//! [`tag::CustomTag`] handling. In that case every HLS tag is read as an [`HlsLine::UnknownTag`]
+//! (regardless of the [`config::ParsingOptions`]), `tag::hls` only provides the tag names, and the
+//! modules that build on the tag definitions (such as [`playlist`] and [`validation`]) are not
+//! available.
+//!
+//! # Serde
+//!
+//! With the `serde` feature enabled, [`HlsLine`] (along with the tag types in [`tag::hls`],
+//! [`date::DateTime`], [`tag::DecimalResolution`], and the other value types used by the tags)
+//! implements `Serialize` and `Deserialize`. This is intended for purposes such as dumping a
+//! parsed playlist to JSON while debugging a pipeline, and ingesting it back again later. Tags are
+//! represented by their attributes (e.g. `{"Inf":{"duration":4.0,"title":""}}`), and a
+//! deserialized tag is constructed from scratch (in the same way as via a builder), so the
+//! [`Writer`] recalculates its output line. The following details should be noted:
+//! * Attribute values that fail to parse into the type of the attribute are not included.
+//! * Comments attached to tags (see [`config::ParsingOptionsBuilder::with_comment_attachment`])
+//!   are not included.
+//! * An [`HlsLine::UnknownTag`] is represented by its line, and as [`tag::UnknownTag`] borrows the
+//!   line, it can only be deserialized from borrowed data. For JSON, this means deserializing via
+//!   `serde_json::from_str` (or `from_slice`), and the line must not contain characters that are
+//!   escaped (such as `"`).
+//! * Custom tags ([`tag::KnownTag::Custom`]) are represented by the custom tag type, and so that
+//!   type must also implement `Serialize` and `Deserialize`.
+//!
+//! ```
+//! # #[cfg(feature = "serde")]
+//! # {
+//! # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions};
+//! let mut reader = Reader::from_str("#EXTINF:4.0,\nsegment.ts\n", ParsingOptions::default());
+//! let mut lines = Vec::new();
+//! while let Some(line) = reader.read_line()? {
+//!     lines.push(line);
+//! }
+//! let json = serde_json::to_string(&lines)?;
+//! assert_eq!(
+//!     r#"[{"KnownTag":{"Hls":{"Inf":{"duration":4.0,"title":""}}}},{"Uri":"segment.ts"}]"#,
+//!     json
+//! );
+//! let deserialized: Vec<HlsLine> = serde_json::from_str(&json)?;
+//! assert_eq!(lines, deserialized);
+//! # }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Safety
+//!
+//! Parsing and mutation are intended to never panic, whatever the input (errors are provided
+//! instead). The crate denies `unsafe` code, with two documented exceptions:
+//! * the internal conversion of a sub-slice of the input back to `&str` skips the UTF-8 check, as
+//!   the input is known to be valid UTF-8 and is only ever split on ASCII characters (this is on
+//!   the hot path of parsing),
+//! * the bump allocator of the `arena` module (only available with the `arena` feature) hands out
+//!   references into its chunks, which are never re-allocated while borrowed.
+//!
+//! This is why the crate uses `#![deny(unsafe_code)]` rather than `#![forbid(unsafe_code)]`: a
+//! `forbid` cannot be overridden by an `#[allow(unsafe_code)]` on the item, and so would not permit
+//! the exceptions above. Any other `unsafe` block still fails to compile.
+//!
 //! [M3U8]: https://datatracker.ietf.org/doc/draft-pantos-hls-rfc8216bis/
 //! [quick-xml]: https://crates.io/crates/quick-xml
 //! [EXT-X-TARGETDURATION]: https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.3.1
@@ -186,13 +263,42 @@
 //! [Simple Media Playlist]: https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-9.1
 //! [Section 4.1]: https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.1
 
+mod append_writer;
+#[cfg(feature = "arena")]
+pub mod arena;
+#[cfg(feature = "asset-list")]
+pub mod asset_list;
+#[cfg(feature = "tokio")]
+mod async_reader;
+#[cfg(feature = "authoring-lint")]
+pub mod authoring;
 pub mod config;
+#[cfg(feature = "conformance")]
+pub mod conformance;
 pub mod date;
+pub mod encoding;
 pub mod error;
+#[cfg(feature = "hls-tags")]
+pub mod health;
 mod line;
+mod line_buffer;
+mod path_writer;
+#[cfg(feature = "hls-tags")]
+pub mod playlist;
 mod reader;
+mod reader_metrics;
+#[cfg(feature = "hls-tags")]
+pub mod recipes;
+#[cfg(feature = "scte35")]
+pub mod scte35;
+mod sink;
 mod tag_internal;
 mod utils;
+#[cfg(feature = "hls-tags")]
+pub mod validation;
+mod visitor;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 mod writer;
 
 pub mod custom_parsing {
@@ -221,9 +327,18 @@ pub mod tag {
     pub use crate::tag_internal::{known::*, unknown::UnknownTag, value::*};
 }
 
-pub use line::HlsLine;
-pub use reader::Reader;
-pub use writer::Writer;
+pub use append_writer::AppendWriter;
+#[cfg(feature = "tokio")]
+pub use async_reader::AsyncReader;
+pub use line::{HlsLine, parse_tag_line, parse_tag_line_with_custom};
+pub use path_writer::{PathWriteOptions, write_playlist_to_path};
+#[cfg(feature = "hls-tags")]
+pub use playlist::{parse_media_playlist, parse_multivariant_playlist};
+pub use reader::{BufReadInput, Reader};
+pub use reader_metrics::{ReaderMetrics, ReaderPosition};
+pub use sink::{FmtWriteAdapter, SliceBuffer};
+pub use visitor::HlsLineVisitor;
+pub use writer::{BlankLines, LineEnding, ValueValidation, Writer, WriterBuilder};
 
 // This allows the Rust compiler to validate any Rust snippets in my README, which seems like a very
 // cool trick. I saw this technique in clap-rs/clap, for example:
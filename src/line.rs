@@ -4,14 +4,23 @@
 //! main informational type of the library, [`HlsLine`], exists in this module (and re-exported at
 //! the top level), along with parsing functions to extract `HlsLine` from input data.
 
+#[cfg(feature = "hls-tags")]
+use crate::tag::hls;
 use crate::{
     config::ParsingOptions,
-    error::{ParseLineBytesError, ParseLineStrError, SyntaxError},
-    tag::{CustomTag, CustomTagAccess, KnownTag, NoCustomTag, UnknownTag, hls},
+    error::{
+        GenericSyntaxError, ParseLineBytesError, ParseLineStrError, ReplaceRawError, SyntaxError,
+        ValidationError,
+    },
+    tag::{CustomTag, CustomTagAccess, KnownTag, NoCustomTag, UnknownTag, WritableCustomTag},
     tag_internal::unknown::parse_assuming_ext_taken,
     utils::{split_on_new_line, str_from},
 };
-use std::{borrow::Cow, cmp::PartialEq, fmt::Debug};
+use std::{
+    borrow::Cow,
+    cmp::PartialEq,
+    fmt::{Debug, Display, Formatter},
+};
 
 /// A parsed line from a HLS playlist.
 ///
@@ -29,6 +38,7 @@ use std::{borrow::Cow, cmp::PartialEq, fmt::Debug};
 ///
 /// [Section 4.1. Definition of a Playlist]: https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.1
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::large_enum_variant)] // See comment on crate::tag::known::Tag.
 pub enum HlsLine<'a, Custom = NoCustomTag>
 where
@@ -44,6 +54,7 @@ where
     /// deliberately ignored via [`ParsingOptions`].
     ///
     /// See [`UnknownTag`] for more information.
+    #[cfg_attr(feature = "serde", serde(borrow))]
     UnknownTag(UnknownTag<'a>),
     /// A comment line. These are lines that begin with `#` and are followed by a string of UTF-8
     /// characters (though not BOM or UTF-8 control characters). The line is terminated by either a
@@ -102,14 +113,10 @@ where
     ///
     /// [url]: https://crates.io/crates/url
     Uri(Cow<'a, str>),
-    /// A blank line. This line contained no characters other than a new line. Note that since the
-    /// library does not validate characters in a URI line, a line comprised entirely of whitespace
-    /// will still be parsed as a URI line, rather than a blank line. As mentioned, it is up to the
-    /// user of the library to properly validate URI lines.
+    /// A blank line. This line contained no characters other than a new line.
     /// ```
     /// # use quick_m3u8::{config::ParsingOptions, HlsLine, error::ParseLineStrError};
     /// # use quick_m3u8::custom_parsing::line::parse;
-    /// # use std::borrow::Cow;
     /// # let options = ParsingOptions::default();
     /// // Demonstrating what is considered a blank line:
     /// assert_eq!(
@@ -124,16 +131,33 @@ where
     ///     HlsLine::Blank,
     ///     parse("\r\n", &options)?.parsed,
     /// );
-    /// // Demonstrating that a whitespace only line is still parsed as a URI:
+    /// # Ok::<(), ParseLineStrError>(())
+    /// ```
+    Blank,
+    /// A line comprised entirely of whitespace (spaces, tabs, and carriage returns that are not
+    /// part of a CRLF line ending), such as stray spaces on a line used as a visual separator. The
+    /// whitespace is kept exactly as found, so that the line is reproduced exactly by the
+    /// [`crate::Writer`] (unless configured otherwise, see [`crate::BlankLines`]).
+    /// ```
+    /// # use quick_m3u8::{config::ParsingOptions, HlsLine, error::ParseLineStrError};
+    /// # use quick_m3u8::custom_parsing::line::parse;
+    /// # use std::borrow::Cow;
+    /// # let options = ParsingOptions::default();
+    /// assert_eq!(
+    ///     HlsLine::Whitespace(Cow::Borrowed("  \t")),
+    ///     parse("  \t\n", &options)?.parsed,
+    /// );
+    /// // A carriage return that is not part of a CRLF line ending:
     /// assert_eq!(
-    ///     HlsLine::Uri(Cow::Borrowed("    ")),
-    ///     parse("    \n", &options)?.parsed,
+    ///     HlsLine::Whitespace(Cow::Borrowed("\r")),
+    ///     parse("\r\r\n", &options)?.parsed,
     /// );
     /// # Ok::<(), ParseLineStrError>(())
     /// ```
-    Blank,
+    Whitespace(Cow<'a, str>),
 }
 
+#[cfg(feature = "hls-tags")]
 impl<'a, Custom> From<hls::Tag<'a>> for HlsLine<'a, Custom>
 where
     Custom: CustomTag<'a>,
@@ -161,6 +185,194 @@ where
     }
 }
 
+/// Formats the line exactly as the [`crate::Writer`] would write it (minus the trailing new line).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions};
+/// # use quick_m3u8::tag::{KnownTag, hls};
+/// let mut reader = Reader::from_str("#EXTINF:4.0,title", ParsingOptions::default());
+/// let Ok(Some(line)) = reader.read_line() else { panic!("unexpected error") };
+/// assert_eq!("#EXTINF:4.0,title", line.to_string());
+///
+/// let HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(mut inf))) = line else {
+///     panic!("unexpected line");
+/// };
+/// inf.set_title("changed");
+/// assert_eq!("#EXTINF:4,changed", format!("{inf}"));
+/// ```
+impl<'a, Custom> Display for HlsLine<'a, Custom>
+where
+    Custom: WritableCustomTag<'a> + Clone,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HlsLine::KnownTag(tag) => Display::fmt(tag, f),
+            HlsLine::UnknownTag(tag) => Display::fmt(tag, f),
+            HlsLine::Comment(comment) => write!(f, "#{comment}"),
+            HlsLine::Uri(uri) => f.write_str(uri),
+            HlsLine::Blank => Ok(()),
+            HlsLine::Whitespace(whitespace) => f.write_str(whitespace),
+        }
+    }
+}
+
+impl<'a, Custom> HlsLine<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    /// Replaces the serialized content of the line (the bytes that the [`crate::Writer`] writes,
+    /// minus the trailing new line) without reclassifying the line.
+    ///
+    /// This is a low-level escape hatch for fix-ups that are not covered by the typed setters (e.g.
+    /// reordering the attributes of a vendor tag), such that the line can still flow through a
+    /// `Reader` to `Writer` pipeline. The bytes are not parsed, and so the line remains the same
+    /// variant with the same typed values (e.g. [`crate::tag::UnknownTag::value`] or the getters of
+    /// a known tag); only what is written (and displayed) changes. It is up to the caller to ensure
+    /// that the bytes make sense for the line. Any later use of a typed setter (or
+    /// [`CustomTagAccess::as_mut`]) recalculates the serialized content, and so discards the
+    /// replacement.
+    ///
+    /// Comment replacements must start with `#`, and comment and URI replacements must be valid
+    /// UTF-8. Blank (and whitespace) lines, and known tags that have no value (along with `EXT-X-PLAYLIST-TYPE`),
+    /// have no content that can be replaced.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, Reader, Writer, config::ParsingOptions};
+    /// const PLAYLIST: &str = "#EXT-X-VENDOR:B=2,A=1\n#EXT-X-TARGETDURATION:4\n";
+    /// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+    /// let mut writer = Writer::new(Vec::new());
+    /// while let Some(mut line) = reader.read_line()? {
+    ///     if let HlsLine::UnknownTag(tag) = &line
+    ///         && tag.name() == "-X-VENDOR"
+    ///     {
+    ///         line.replace_raw(b"#EXT-X-VENDOR:A=1,B=2".to_vec())?;
+    ///         assert!(matches!(line, HlsLine::UnknownTag(_)));
+    ///     }
+    ///     writer.write_line(line)?;
+    /// }
+    /// assert_eq!(
+    ///     "#EXT-X-VENDOR:A=1,B=2\n#EXT-X-TARGETDURATION:4\n",
+    ///     String::from_utf8_lossy(&writer.into_inner())
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn replace_raw(&mut self, bytes: impl Into<Cow<'a, [u8]>>) -> Result<(), ReplaceRawError> {
+        let bytes = bytes.into();
+        match self {
+            #[cfg(feature = "hls-tags")]
+            HlsLine::KnownTag(KnownTag::Hls(tag)) => tag
+                .replace_output_line(bytes)
+                .map_err(|_| ReplaceRawError::UnsupportedLine),
+            HlsLine::KnownTag(KnownTag::Custom(tag)) => {
+                tag.replacement = Some(bytes);
+                Ok(())
+            }
+            HlsLine::UnknownTag(tag) => {
+                tag.replacement = Some(bytes);
+                Ok(())
+            }
+            HlsLine::Comment(comment) => {
+                let rest = match bytes {
+                    Cow::Borrowed([b'#', rest @ ..]) => Cow::Borrowed(str_from_utf8(rest)?),
+                    Cow::Owned(mut bytes) if bytes.first() == Some(&b'#') => {
+                        bytes.remove(0);
+                        Cow::Owned(string_from_utf8(bytes)?)
+                    }
+                    _ => return Err(ReplaceRawError::MissingCommentPrefix),
+                };
+                *comment = rest;
+                Ok(())
+            }
+            HlsLine::Uri(uri) => {
+                *uri = match bytes {
+                    Cow::Borrowed(bytes) => Cow::Borrowed(str_from_utf8(bytes)?),
+                    Cow::Owned(bytes) => Cow::Owned(string_from_utf8(bytes)?),
+                };
+                Ok(())
+            }
+            HlsLine::Blank | HlsLine::Whitespace(_) => Err(ReplaceRawError::UnsupportedLine),
+        }
+    }
+
+    /// The URI of the line, if the line is [`HlsLine::Uri`].
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::HlsLine;
+    /// assert_eq!(Some("segment.ts"), HlsLine::uri("segment.ts").uri_str());
+    /// assert_eq!(None, HlsLine::comment("segment.ts").uri_str());
+    /// ```
+    pub fn uri_str(&self) -> Option<&str> {
+        match self {
+            HlsLine::Uri(uri) => Some(uri),
+            _ => None,
+        }
+    }
+
+    /// The text of the comment (without the leading `#`), if the line is [`HlsLine::Comment`].
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::HlsLine;
+    /// assert_eq!(Some(" hello"), HlsLine::comment(" hello").comment_str());
+    /// assert_eq!(None, HlsLine::uri("hello").comment_str());
+    /// ```
+    pub fn comment_str(&self) -> Option<&str> {
+        match self {
+            HlsLine::Comment(comment) => Some(comment),
+            _ => None,
+        }
+    }
+
+    /// The whitespace that makes up the line, if the line is [`HlsLine::Whitespace`].
+    pub fn whitespace_str(&self) -> Option<&str> {
+        match self {
+            HlsLine::Whitespace(whitespace) => Some(whitespace),
+            _ => None,
+        }
+    }
+
+    /// Indicates whether the line has no content; i.e. is [`HlsLine::Blank`] or
+    /// [`HlsLine::Whitespace`].
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::HlsLine;
+    /// assert!(HlsLine::blank().is_blank());
+    /// assert!(HlsLine::whitespace("  ").is_blank());
+    /// assert!(!HlsLine::comment("").is_blank());
+    /// ```
+    pub fn is_blank(&self) -> bool {
+        matches!(self, HlsLine::Blank | HlsLine::Whitespace(_))
+    }
+
+    /// The tag, if the line is [`HlsLine::KnownTag`].
+    pub fn known_tag(&self) -> Option<&KnownTag<'a, Custom>> {
+        match self {
+            HlsLine::KnownTag(tag) => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// The tag, if the line is [`HlsLine::UnknownTag`].
+    pub fn unknown_tag(&self) -> Option<&UnknownTag<'a>> {
+        match self {
+            HlsLine::UnknownTag(tag) => Some(tag),
+            _ => None,
+        }
+    }
+}
+
+fn str_from_utf8(bytes: &[u8]) -> Result<&str, ReplaceRawError> {
+    std::str::from_utf8(bytes).map_err(ReplaceRawError::InvalidUtf8)
+}
+
+fn string_from_utf8(bytes: Vec<u8>) -> Result<String, ReplaceRawError> {
+    String::from_utf8(bytes).map_err(|e| ReplaceRawError::InvalidUtf8(e.utf8_error()))
+}
+
 impl<'a> HlsLine<'a> {
     /// Convenience constructor for [`HlsLine::Comment`]. This will construct the line with the
     /// generic `Custom` in [`HlsLine::KnownTag`] being [`NoCustomTag`].
@@ -179,10 +391,46 @@ impl<'a> HlsLine<'a> {
     pub fn blank() -> Self {
         Self::Blank
     }
+
+    /// Convenience constructor for [`HlsLine::Comment`] from a full comment line. Unlike
+    /// [`Self::comment`], which takes the comment text that follows the `#`, this strips one
+    /// leading `#` from the input when present, so that the line can be built from what would
+    /// appear in the playlist. This will construct the line with the generic `Custom` in
+    /// [`HlsLine::KnownTag`] being [`NoCustomTag`].
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::HlsLine;
+    /// assert_eq!(HlsLine::comment(" note"), HlsLine::comment_line("# note"));
+    /// assert_eq!(HlsLine::comment(" note"), HlsLine::comment_line(" note"));
+    /// assert_eq!("# note", HlsLine::comment_line("# note").to_string());
+    /// ```
+    pub fn comment_line(line: impl Into<Cow<'a, str>>) -> Self {
+        let comment = match line.into() {
+            Cow::Borrowed(line) => Cow::Borrowed(line.strip_prefix('#').unwrap_or(line)),
+            Cow::Owned(mut line) => {
+                if line.starts_with('#') {
+                    line.remove(0);
+                }
+                Cow::Owned(line)
+            }
+        };
+        Self::Comment(comment)
+    }
+
+    /// Convenience constructor for [`HlsLine::Whitespace`]. This will construct the line with the
+    /// generic `Custom` in [`HlsLine::KnownTag`] being [`NoCustomTag`].
+    ///
+    /// The value is written as-is and so should only contain whitespace (and no new line
+    /// characters), as otherwise the line would not be read back as whitespace.
+    pub fn whitespace(whitespace: impl Into<Cow<'a, str>>) -> Self {
+        Self::Whitespace(whitespace.into())
+    }
 }
 
 macro_rules! impl_line_from_tag {
     ($tag_mod_path:path, $tag_name:ident) => {
+        #[cfg(feature = "hls-tags")]
         impl<'a, Custom> From<$tag_mod_path> for HlsLine<'a, Custom>
         where
             Custom: CustomTag<'a>,
@@ -401,6 +649,68 @@ where
         })
 }
 
+/// Parse a single line of HLS data in isolation with the provided options.
+///
+/// This is a convenience over [`parse`] for tools that handle individual lines (for example, lines
+/// taken from logs or from the output of a diff) rather than whole playlists, and so do not need a
+/// [`crate::Reader`]. The full semantics of the [`ParsingOptions`] apply, meaning that the line is
+/// only parsed as a [`KnownTag`] if the tag is included in the options.
+///
+/// A single trailing line break (`\n` or `\r\n`) is accepted; however, if any content follows
+/// the line break then [`crate::error::GenericSyntaxError::UnexpectedLineBreak`] is returned (use
+/// [`parse`] to work through multiple lines).
+///
+/// ## Example
+/// ```
+/// # use quick_m3u8::{
+/// # HlsLine, parse_tag_line,
+/// # config::ParsingOptions,
+/// # error::{GenericSyntaxError, ParseLineStrError, SyntaxError},
+/// # tag::hls::Targetduration,
+/// # };
+/// let options = ParsingOptions::default();
+/// assert_eq!(
+///     HlsLine::from(Targetduration::new(10)),
+///     parse_tag_line("#EXT-X-TARGETDURATION:10\n", &options)?
+/// );
+///
+/// let error = parse_tag_line("#EXTINF:4\nsegment.ts", &options).unwrap_err();
+/// assert_eq!(SyntaxError::from(GenericSyntaxError::UnexpectedLineBreak), error.error);
+/// # Ok::<(), ParseLineStrError>(())
+/// ```
+pub fn parse_tag_line<'a>(
+    line: &'a str,
+    options: &ParsingOptions,
+) -> Result<HlsLine<'a>, ParseLineStrError<'a>> {
+    parse_tag_line_with_custom::<NoCustomTag>(line, options)
+}
+
+/// Parse a single line of HLS data in isolation with the provided options with support for the
+/// provided custom tag.
+///
+/// This method differs from [`parse_tag_line`] as it allows the user to provide their own custom
+/// tag implementation for parsing. Refer to documentation of [`parse_tag_line`] and
+/// [`parse_with_custom`] for more information.
+pub fn parse_tag_line_with_custom<'a, Custom>(
+    line: &'a str,
+    options: &ParsingOptions,
+) -> Result<HlsLine<'a, Custom>, ParseLineStrError<'a>>
+where
+    Custom: CustomTag<'a>,
+{
+    let ParsedLineSlice { parsed, remaining } = parse_with_custom(line, options)?;
+    match remaining {
+        Some(remaining) if !remaining.is_empty() => Err(ParseLineStrError {
+            errored_line_slice: ParsedLineSlice {
+                parsed: line,
+                remaining: None,
+            },
+            error: GenericSyntaxError::UnexpectedLineBreak.into(),
+        }),
+        _ => Ok(parsed),
+    }
+}
+
 /// Parse an input byte slice with the provided options.
 ///
 /// This method is equivalent to [`parse`] but using `&[u8]` instead of `&str`. Refer to
@@ -423,18 +733,33 @@ pub fn parse_bytes_with_custom<'a, 'b, Custom>(
 where
     Custom: CustomTag<'a>,
 {
+    let input = if options.leading_whitespace_trimming() {
+        trim_leading_whitespace(input)
+    } else {
+        input
+    };
     if input.is_empty() {
         Ok(ParsedByteSlice {
             parsed: HlsLine::Blank,
             remaining: None,
         })
     } else if input[0] == b'#' {
-        if input.get(3) == Some(&b'T') && &input[..3] == b"#EX" {
+        if input.get(3) == Some(&b'T') && &input[..3] == b"#EX"
+            || options.case_insensitive_tag_names()
+                && input
+                    .get(1..4)
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case(b"EXT"))
+        {
             let tag_rest = &input[4..];
             let mut tag = parse_assuming_ext_taken(tag_rest, input)
                 .map_err(|error| map_err_bytes(error, input))?;
+            // The name is replaced with the canonical name, but the original input is kept, so that
+            // an unmodified tag is still written as it was read.
+            if let Some(name) = options.case_insensitive_known_name(tag.parsed.name) {
+                tag.parsed.name = name;
+            }
             if options.is_known_name(tag.parsed.name) || Custom::is_known_name(tag.parsed.name) {
-                match KnownTag::try_from(tag.parsed) {
+                match known_tag(tag.parsed.clone(), options) {
                     Ok(known_tag) => Ok(ParsedByteSlice {
                         parsed: HlsLine::KnownTag(known_tag),
                         remaining: tag.remaining,
@@ -470,7 +795,17 @@ where
                 parsed: HlsLine::Blank,
                 remaining,
             })
+        } else if uri.bytes().all(|b| matches!(b, b' ' | b'\t' | b'\r')) {
+            Ok(ParsedByteSlice {
+                parsed: HlsLine::Whitespace(Cow::Borrowed(uri)),
+                remaining,
+            })
         } else {
+            let uri = if options.uri_trailing_whitespace_trimming() {
+                uri.trim_end_matches([' ', '\t', '\r'])
+            } else {
+                uri
+            };
             Ok(ParsedByteSlice {
                 parsed: HlsLine::Uri(Cow::Borrowed(uri)),
                 remaining,
@@ -479,6 +814,64 @@ where
     }
 }
 
+// Converts the tag into a known tag, making use of the date time parser from the options (if set)
+// for the HLS tags with dates.
+fn known_tag<'a, Custom>(
+    tag: UnknownTag<'a>,
+    options: &ParsingOptions,
+) -> Result<KnownTag<'a, Custom>, ValidationError>
+where
+    Custom: CustomTag<'a>,
+{
+    let known_tag = parse_known_tag(tag, options)?;
+    #[cfg(feature = "hls-tags")]
+    if let KnownTag::Hls(tag) = known_tag {
+        return Ok(KnownTag::Hls(options.transform_tag(tag)));
+    }
+    Ok(known_tag)
+}
+
+fn parse_known_tag<'a, Custom>(
+    tag: UnknownTag<'a>,
+    options: &ParsingOptions,
+) -> Result<KnownTag<'a, Custom>, ValidationError>
+where
+    Custom: CustomTag<'a>,
+{
+    #[cfg(feature = "hls-tags")]
+    if let Some(parser) = options.date_time_parser()
+        && !Custom::is_known_name(tag.name)
+    {
+        match hls::TagName::try_from(tag.name) {
+            Ok(hls::TagName::ProgramDateTime) => {
+                let tag = hls::ProgramDateTime::try_from_with_parser(tag, parser)?;
+                return Ok(KnownTag::Hls(hls::Tag::ProgramDateTime(tag)));
+            }
+            Ok(hls::TagName::Daterange) => {
+                let mut tag = hls::Daterange::try_from(tag)?;
+                tag.parse_dates_with(parser);
+                return Ok(KnownTag::Hls(hls::Tag::Daterange(tag)));
+            }
+            _ => (),
+        }
+    }
+    #[cfg(not(feature = "hls-tags"))]
+    let _ = options;
+    KnownTag::try_from(tag)
+}
+
+// Skips the spaces and tabs at the start of the line, unless the line is entirely whitespace.
+fn trim_leading_whitespace(input: &[u8]) -> &[u8] {
+    let start = input
+        .iter()
+        .position(|b| !matches!(b, b' ' | b'\t'))
+        .unwrap_or(input.len());
+    match input.get(start) {
+        Some(b'\r' | b'\n') | None => input,
+        Some(_) => &input[start..],
+    }
+}
+
 fn map_err_bytes<E: Into<SyntaxError>>(error: E, input: &[u8]) -> ParseLineBytesError<'_> {
     let errored_line_slice = split_on_new_line(input);
     ParseLineBytesError {
@@ -490,11 +883,12 @@ fn map_err_bytes<E: Into<SyntaxError>>(error: E, input: &[u8]) -> ParseLineBytes
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{config::ParsingOptionsBuilder, error::ParseTagValueError, tag::AttributeValue};
+    #[cfg(feature = "hls-tags")]
     use crate::{
-        config::ParsingOptionsBuilder,
-        error::{ParseTagValueError, ValidationError},
+        error::DateTimeSyntaxError,
         tag::{
-            AttributeValue, TagValue,
+            TagValue,
             hls::{self, M3u, Start},
         },
     };
@@ -524,6 +918,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn constructed_lines_should_parse_back_to_the_same_line() {
+        let options = ParsingOptions::default();
+        for line in [
+            HlsLine::uri("hello/world.m3u8"),
+            HlsLine::comment("Comment"),
+            HlsLine::comment_line("#Comment".to_string()),
+            HlsLine::comment_line("#"),
+            HlsLine::blank(),
+            HlsLine::whitespace(" \t"),
+        ] {
+            let serialized = line.to_string();
+            assert_eq!(Ok(line), parse(&serialized, &options).map(|p| p.parsed));
+        }
+    }
+
+    #[test]
+    fn accessors_should_only_match_their_variant() {
+        let uri = HlsLine::uri("a.ts");
+        assert_eq!(Some("a.ts"), uri.uri_str());
+        assert_eq!(None, uri.comment_str());
+        assert_eq!(None, uri.whitespace_str());
+        assert_eq!(None, uri.known_tag());
+        assert!(!uri.is_blank());
+        let comment = HlsLine::comment_line("##double");
+        assert_eq!(Some("#double"), comment.comment_str());
+        assert_eq!(None, comment.uri_str());
+        let whitespace = HlsLine::whitespace("  ");
+        assert_eq!(Some("  "), whitespace.whitespace_str());
+        assert!(whitespace.is_blank());
+        let tag = parse("#EXT-X-VENDOR", &ParsingOptions::default())
+            .unwrap()
+            .parsed;
+        assert!(tag.unknown_tag().is_some());
+        assert!(tag.known_tag().is_none());
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn replace_raw_should_keep_line_classification() {
+        let options = ParsingOptions::default();
+        let mut line = parse("#EXT-X-START:TIME-OFFSET=-5", &options)
+            .unwrap()
+            .parsed;
+        line.replace_raw(b"#EXT-X-START:PRECISE=NO,TIME-OFFSET=-5".as_slice())
+            .unwrap();
+        assert_eq!("#EXT-X-START:PRECISE=NO,TIME-OFFSET=-5", line.to_string());
+        let HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Start(mut start))) = line else {
+            panic!("unexpected line {line:?}");
+        };
+        assert_eq!(-5.0, start.time_offset());
+        start.set_time_offset(-4.0);
+        assert_eq!("#EXT-X-START:TIME-OFFSET=-4", start.to_string());
+
+        let mut line = parse("#comment", &options).unwrap().parsed;
+        line.replace_raw(b"#changed".to_vec()).unwrap();
+        assert_eq!(HlsLine::Comment("changed".into()), line);
+        assert_eq!(
+            Err(ReplaceRawError::MissingCommentPrefix),
+            line.replace_raw(b"changed".as_slice())
+        );
+
+        let mut line = parse("a.ts", &options).unwrap().parsed;
+        line.replace_raw(b"b.ts".as_slice()).unwrap();
+        assert_eq!(HlsLine::Uri("b.ts".into()), line);
+        assert!(matches!(
+            line.replace_raw(vec![0xff]),
+            Err(ReplaceRawError::InvalidUtf8(_))
+        ));
+
+        for input in ["", "#EXT-X-ENDLIST", "#EXT-X-PLAYLIST-TYPE:VOD"] {
+            let mut line = parse(input, &options).unwrap().parsed;
+            assert_eq!(
+                Err(ReplaceRawError::UnsupportedLine),
+                line.replace_raw(b"#EXT-X-GAP".as_slice())
+            );
+        }
+    }
+
+    #[cfg(feature = "hls-tags")]
     #[test]
     fn basic_tag() {
         assert_eq!(
@@ -586,13 +1060,14 @@ mod tests {
         assert_eq!(
             Ok(HlsLine::from(CustomTagAccess {
                 custom_tag: TestTag {
-                    greeting_type: "GREETING".into(),
-                    message: "Hello, World!".into(),
+                    greeting_type: "GREETING",
+                    message: "Hello, World!",
                     times: 42,
                     score: None,
                 },
                 is_dirty: false,
-                original_input: b"#EXT-X-TEST-TAG:TYPE=GREETING,MESSAGE=\"Hello, World!\",TIMES=42"
+                original_input: b"#EXT-X-TEST-TAG:TYPE=GREETING,MESSAGE=\"Hello, World!\",TIMES=42",
+                replacement: None
             })),
             parse_with_custom::<TestTag>(
                 "#EXT-X-TEST-TAG:TYPE=GREETING,MESSAGE=\"Hello, World!\",TIMES=42",
@@ -602,6 +1077,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "hls-tags")]
     #[test]
     fn avoiding_parsing_known_tag_when_configured_to_avoid_via_parsing_options() {
         assert_eq!(
@@ -616,6 +1092,7 @@ mod tests {
                 value: Some(TagValue(b"TIME-OFFSET=-18")),
                 original_input: b"#EXT-X-START:TIME-OFFSET=-18",
                 validation_error: None,
+                replacement: None,
             })),
             parse(
                 "#EXT-X-START:TIME-OFFSET=-18",
@@ -639,4 +1116,299 @@ mod tests {
             parse(input, &ParsingOptions::default()).unwrap()
         );
     }
+
+    #[test]
+    fn parse_tag_line_should_accept_single_line_with_trailing_line_break() {
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_all_tags()
+            .without_parsing_for_start()
+            .build();
+        for line in [
+            "#EXT-X-START:TIME-OFFSET=-18",
+            "#EXT-X-START:TIME-OFFSET=-18\n",
+            "#EXT-X-START:TIME-OFFSET=-18\r\n",
+        ] {
+            assert!(matches!(
+                parse_tag_line(line, &options),
+                Ok(HlsLine::UnknownTag(tag)) if tag.name() == "-X-START"
+            ));
+        }
+        assert_eq!(Ok(HlsLine::Blank), parse_tag_line("", &options));
+        assert_eq!(
+            Err(ParseLineStrError {
+                errored_line_slice: ParsedLineSlice {
+                    parsed: "#EXTM3U\n\n",
+                    remaining: None
+                },
+                error: GenericSyntaxError::UnexpectedLineBreak.into()
+            }),
+            parse_tag_line("#EXTM3U\n\n", &options)
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn case_insensitive_tag_names_should_only_apply_when_enabled() {
+        let line = "#ext-x-targetduration:4";
+        assert_eq!(
+            Ok(HlsLine::Comment(Cow::Borrowed("ext-x-targetduration:4"))),
+            parse_tag_line(line, &ParsingOptions::default())
+        );
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_all_tags()
+            .with_case_insensitive_tag_names()
+            .build();
+        let Ok(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Targetduration(mut tag)))) =
+            parse_tag_line(line, &options)
+        else {
+            panic!("should parse as EXT-X-TARGETDURATION");
+        };
+        assert_eq!(4, tag.target_duration());
+        assert_eq!(line, HlsLine::<NoCustomTag>::from(tag.clone()).to_string());
+        tag.set_target_duration(6);
+        assert_eq!(
+            "#EXT-X-TARGETDURATION:6",
+            HlsLine::<NoCustomTag>::from(tag).to_string()
+        );
+        assert!(matches!(
+            parse_tag_line("#Ext-X-Vendor:1", &options),
+            Ok(HlsLine::UnknownTag(tag)) if tag.name() == "-X-Vendor"
+        ));
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn whitespace_trimming_should_only_apply_when_enabled() {
+        let options = ParsingOptions::default();
+        assert_eq!(
+            Ok(HlsLine::Uri(Cow::Borrowed(" #EXTM3U"))),
+            parse_tag_line(" #EXTM3U", &options)
+        );
+        assert_eq!(
+            Ok(HlsLine::Uri(Cow::Borrowed("a.ts "))),
+            parse_tag_line("a.ts ", &options)
+        );
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_all_tags()
+            .with_leading_whitespace_trimming()
+            .with_uri_trailing_whitespace_trimming()
+            .build();
+        assert_eq!(
+            Ok(HlsLine::from(M3u)),
+            parse_tag_line("\t #EXTM3U", &options)
+        );
+        assert_eq!(
+            Ok(HlsLine::Comment(Cow::Borrowed(" note"))),
+            parse_tag_line("  # note", &options)
+        );
+        assert_eq!(
+            Ok(HlsLine::Uri(Cow::Borrowed("a.ts"))),
+            parse_tag_line("  a.ts \t", &options)
+        );
+        assert_eq!(
+            Ok(HlsLine::Whitespace(Cow::Borrowed("  "))),
+            parse_tag_line("  \r\n", &options)
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn display_should_match_writer_output() {
+        let playlist = concat!(
+            "#EXTM3U\r\n",
+            "#EXT-X-PLAYLIST-TYPE:VOD\n",
+            "#EXT-X-STREAM-INF:AVERAGE-BANDWIDTH=10\n",
+            "# comment\n",
+            "\n",
+            "#EXT-X-DEFINE:NAME=\"a\",VALUE=\"b\"\n",
+            "#EXT-X-START:TIME-OFFSET=-10\n",
+            "#EXTINF:4.0,\n",
+            "segment.ts\n",
+        );
+        let mut reader = crate::Reader::from_str(playlist, ParsingOptions::default());
+        let mut lines = Vec::new();
+        while let Ok(Some(line)) = reader.read_line() {
+            lines.push(line);
+        }
+        if let Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Start(start)))) = lines.get_mut(6) {
+            start.set_precise(true);
+        }
+        lines.push(HlsLine::from(M3u));
+        for line in lines {
+            let displayed = line.to_string();
+            let mut writer = crate::Writer::new(Vec::new());
+            writer.write_line(line).expect("writing should succeed");
+            assert_eq!(
+                format!("{displayed}\n"),
+                String::from_utf8(writer.into_inner()).expect("output should be UTF-8")
+            );
+        }
+    }
+
+    #[test]
+    fn parsing_should_not_panic_on_truncated_lines() {
+        const LINES: &[&str] = &[
+            "#EXT-X-PROGRAM-DATE-TIME:2025-06-17T01:37:15.129-05:00",
+            "#EXT-X-DATERANGE:ID=\"é\",START-DATE=\"2025-01-01T00:00:00Z\",DURATION=1,X-A=0x1F",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1,RESOLUTION=1920x1080,ALLOWED-CPC=\"a:b/c,d:\"",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"a\",NAME=\"ü\",CHANNELS=\"16/JOC,3OA/BED-4\"",
+            "#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"1000@0\"",
+            "#EXT-X-BYTERANGE:1000@0",
+            "#EXTINF:4.004,title with ü",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"k\",IV=0x0123456789abcdef",
+            "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"p\",BYTERANGE-START=1,BYTERANGE-LENGTH=2",
+            "#EXT-X-SERVER-CONTROL:CAN-SKIP-UNTIL=12.0,HOLD-BACK=3",
+            "#EXT-X-START:TIME-OFFSET=-1.5,PRECISE=YES",
+        ];
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_all_tags()
+            .build();
+        for line in LINES {
+            for end in 0..=line.len() {
+                let bytes = &line.as_bytes()[..end];
+                let _ = parse_bytes(bytes, &options);
+                if let Some(line) = line.get(..end) {
+                    let _ = parse(line, &options);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_should_preserve_lines() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:9\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,PART-HOLD-BACK=3.0\n",
+            "#EXT-X-PART-INF:PART-TARGET=1.0\n",
+            "#EXT-X-DEFINE:NAME=\"host\",VALUE=\"example.com\"\n",
+            "#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"720@0\"\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://key\",KEYFORMAT=\"com.apple.streamingkeydelivery\",KEYFORMATVERSIONS=\"1\"\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-06-01T12:00:00.000Z\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2025-06-01T12:00:00.000Z\",DURATION=30.0,X-COM-EXAMPLE=\"a\"\n",
+            "# A comment\n",
+            "#EXTINF:4.0,Title\n",
+            "#EXT-X-BYTERANGE:1000@0\n",
+            "segment.mp4\n",
+            "\n",
+            "#EXT-X-PART:DURATION=1.0,URI=\"part.mp4\",INDEPENDENT=YES\n",
+            "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"next.mp4\"\n",
+            "#EXT-X-RENDITION-REPORT:URI=\"other.m3u8\",LAST-MSN=10,LAST-PART=2\n",
+            "#EXT-X-UNKNOWN:1\n",
+            "#EXT-X-ENDLIST\n",
+        );
+        let mut reader = crate::Reader::from_str(
+            PLAYLIST,
+            ParsingOptionsBuilder::new()
+                .with_parsing_for_all_tags()
+                .build(),
+        );
+        let mut lines = Vec::new();
+        while let Some(line) = reader.read_line().unwrap() {
+            lines.push(line);
+        }
+        let json = serde_json::to_string(&lines).unwrap();
+        let deserialized: Vec<HlsLine> = serde_json::from_str(&json).unwrap();
+        assert_eq!(lines.len(), deserialized.len());
+        for (line, deserialized) in lines.iter().zip(deserialized.iter()) {
+            match (line, deserialized) {
+                (HlsLine::UnknownTag(line), HlsLine::UnknownTag(deserialized)) => {
+                    assert_eq!("-X-UNKNOWN", line.name());
+                    assert_eq!(line.as_bytes(), deserialized.as_bytes());
+                }
+                (line, deserialized) => assert_eq!(line, deserialized),
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_should_represent_tags_by_their_attributes() {
+        let key = hls::Key::builder()
+            .with_method("AES-128")
+            .with_uri("key.bin")
+            .finish();
+        assert_eq!(
+            r#"{"KnownTag":{"Hls":{"Key":{"method":"AES-128","uri":"key.bin","iv":null,"keyformat":null,"keyformatversions":null}}}}"#,
+            serde_json::to_string(&HlsLine::<NoCustomTag>::from(key)).unwrap()
+        );
+        let inf: HlsLine =
+            serde_json::from_str(r#"{"KnownTag":{"Hls":{"Inf":{"duration":4.0,"title":""}}}}"#)
+                .unwrap();
+        assert_eq!(HlsLine::from(hls::Inf::new(4.0, "")), inf);
+        assert_eq!(
+            r#""2025-06-01T12:00:00.000Z""#,
+            serde_json::to_string(&crate::date_time!(2025-06-01 T 12:00:00.000 0:0)).unwrap()
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn tag_transformers_should_apply_in_registration_order_to_their_tag_only() {
+        struct AddToTargetDuration(u64);
+        impl crate::config::TagTransformer for AddToTargetDuration {
+            fn transform<'a>(&self, tag: hls::Tag<'a>) -> hls::Tag<'a> {
+                match tag {
+                    hls::Tag::Targetduration(mut tag) => {
+                        tag.set_target_duration(tag.target_duration() * 10 + self.0);
+                        hls::Tag::Targetduration(tag)
+                    }
+                    tag => tag,
+                }
+            }
+        }
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_version()
+            .with_tag_transformer(hls::TagName::Targetduration, AddToTargetDuration(1))
+            .with_tag_transformer(hls::TagName::Targetduration, AddToTargetDuration(2))
+            .build();
+        assert!(options.has_tag_transformers());
+        assert_eq!(
+            "#EXT-X-TARGETDURATION:412",
+            parse("#EXT-X-TARGETDURATION:4", &options)
+                .unwrap()
+                .parsed
+                .to_string()
+        );
+        assert_eq!(
+            "#EXT-X-VERSION:4",
+            parse("#EXT-X-VERSION:4", &options)
+                .unwrap()
+                .parsed
+                .to_string()
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn date_time_parser_should_apply_to_daterange_dates() {
+        #[derive(Debug)]
+        struct EpochOnly;
+        impl crate::date::DateTimeParser for EpochOnly {
+            fn parse(&self, input: &str) -> Result<crate::date::DateTime, DateTimeSyntaxError> {
+                if input.starts_with("1970") {
+                    crate::date::parse(input)
+                } else {
+                    Err(DateTimeSyntaxError::InvalidSecond)
+                }
+            }
+        }
+        let line = "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"1970-01-01T00:00:00Z\",\
+                    END-DATE=\"2025-01-01T00:00:00Z\"";
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_daterange()
+            .with_date_time_parser(EpochOnly)
+            .build();
+        let Ok(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Daterange(tag)))) =
+            parse(line, &options).map(|slice| slice.parsed)
+        else {
+            panic!("should parse daterange")
+        };
+        assert_eq!(Some(crate::date::DateTime::default()), tag.start_date());
+        assert_eq!(None, tag.end_date());
+        assert!(!tag.is_dirty());
+        assert_eq!(line, tag.to_string());
+    }
 }
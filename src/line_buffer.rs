@@ -0,0 +1,142 @@
+#[cfg(feature = "hls-tags")]
+use crate::tag::KnownTag;
+use crate::{
+    config::{ErrorRecovery, ParsingOptions},
+    encoding::UTF_8_BOM,
+    error::{GenericSyntaxError, ReaderIoError},
+    line::{HlsLine, parse_bytes_with_custom},
+    reader_metrics::ReaderPosition,
+    tag::CustomTag,
+    utils::split_on_new_line,
+};
+
+// The buffers used by the readers that read lines incrementally from an I/O source (as opposed to
+// the `Reader` over a `&str` or `&[u8]`, that has all of the data available up front).
+//
+// Each line is read into a buffer owned by the reader, and the parsed `HlsLine` borrows from that
+// buffer. When comments are attached to tags the line after a comment must be read ahead, and so a
+// second buffer holds that line until it is either attached to or provided by the next read.
+//
+// The I/O is left to the owner (as it may be blocking or async), and a read goes as follows:
+// * return if `stopped`,
+// * if at the line limit, read into `next_line` (unless `has_next_line`) and return
+//   `line_limit_error` (or nothing, if there was nothing to read),
+// * if not `take_next_line`, read into `line` (and return if there was nothing to read),
+// * `strip_bom`,
+// * if `wants_next_line`, read into `next_line` and set `has_next_line`,
+// * `parse`.
+#[derive(Debug, Default)]
+pub(crate) struct LineBuffer {
+    pub(crate) line: Vec<u8>,
+    pub(crate) next_line: Vec<u8>,
+    pub(crate) has_next_line: bool,
+    pub(crate) stopped: bool,
+    stopped_at_line_limit: bool,
+    started: bool,
+    has_bom: bool,
+    position: ReaderPosition,
+}
+
+impl LineBuffer {
+    pub(crate) fn has_bom(&self) -> bool {
+        self.has_bom
+    }
+
+    // The position of the data after the lines that have been parsed.
+    pub(crate) fn position(&self) -> ReaderPosition {
+        self.position
+    }
+
+    // Moves the line that was read ahead into `line`, indicating whether there was one.
+    pub(crate) fn take_next_line(&mut self) -> bool {
+        if self.has_next_line {
+            std::mem::swap(&mut self.line, &mut self.next_line);
+            self.has_next_line = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn strip_bom(&mut self) {
+        if !self.started {
+            self.started = true;
+            if self.line.starts_with(UTF_8_BOM.as_slice()) {
+                self.line.drain(..UTF_8_BOM.len());
+                self.has_bom = true;
+                self.position.advance(UTF_8_BOM.as_slice());
+            }
+        }
+    }
+
+    // Stops the reader because it has read the maximum number of lines, where the next line is held
+    // in `next_line` so that it is read when the reader is resumed.
+    pub(crate) fn line_limit_error(&mut self) -> ReaderIoError {
+        self.stopped = true;
+        self.stopped_at_line_limit = true;
+        ReaderIoError::Syntax {
+            errored_line: split_on_new_line(&self.next_line).parsed.to_vec(),
+            error: GenericSyntaxError::LineLimitExceeded.into(),
+        }
+    }
+
+    // Allows reading to continue after the reader stopped, indicating whether it stopped because
+    // of the line limit (in which case the count of lines read should be reset).
+    pub(crate) fn resume(&mut self) -> bool {
+        self.stopped = false;
+        std::mem::take(&mut self.stopped_at_line_limit)
+    }
+
+    pub(crate) fn wants_next_line(&self, options: &ParsingOptions) -> bool {
+        options.comment_attachment()
+            && self.line.first() == Some(&b'#')
+            && !self.line.starts_with(b"#EXT")
+    }
+
+    pub(crate) fn parse<'a, Custom>(
+        &'a mut self,
+        options: &ParsingOptions,
+    ) -> Result<Option<HlsLine<'a, Custom>>, ReaderIoError>
+    where
+        Custom: CustomTag<'a>,
+    {
+        self.position.advance(&self.line);
+        let parsed = match parse_bytes_with_custom::<Custom>(&self.line, options) {
+            Ok(slice) => slice.parsed,
+            Err(error) => {
+                if options.error_recovery() == ErrorRecovery::Stop {
+                    self.stopped = true;
+                }
+                return Err(ReaderIoError::Syntax {
+                    errored_line: error.errored_line_slice.parsed.to_vec(),
+                    error: error.error,
+                });
+            }
+        };
+        match parsed {
+            HlsLine::Comment(comment) if self.has_next_line => {
+                // Attaches the comment to the next line when that line is a known HLS tag that
+                // supports comments, otherwise the comment is provided as is and the next line is
+                // left for the next read.
+                match parse_bytes_with_custom::<Custom>(&self.next_line, options) {
+                    Ok(slice) => match slice.parsed {
+                        #[cfg(feature = "hls-tags")]
+                        HlsLine::KnownTag(KnownTag::Hls(mut tag)) => {
+                            match tag.attach_comment(comment) {
+                                Ok(()) => {
+                                    self.has_next_line = false;
+                                    self.position.advance(&self.next_line);
+                                    Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag))))
+                                }
+                                Err(comment) => Ok(Some(HlsLine::Comment(comment))),
+                            }
+                        }
+                        _ => Ok(Some(HlsLine::Comment(comment))),
+                    },
+                    Err(_) => Ok(Some(HlsLine::Comment(comment))),
+                }
+            }
+            parsed => Ok(Some(parsed)),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use crate::{
+    line::HlsLine,
+    tag::WritableCustomTag,
+    writer::{LineEnding, WriterBuilder},
+};
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes the lines of a playlist to the file at the provided path, atomically replacing any
+/// existing file.
+///
+/// Serving a playlist that is still being written (or that was left truncated by a crash) is a
+/// common source of playback errors. This helper avoids that by writing the playlist to a temporary
+/// file in the same directory, and then renaming it over the destination. A rename within the same
+/// directory is atomic on all major platforms (Linux, macOS, and Windows), so readers of the path
+/// only ever see either the previous or the new version of the playlist. The temporary file is
+/// removed if writing fails.
+///
+/// By default the file is flushed to disk before the rename (and on Unix the directory is also
+/// flushed after the rename), such that the new playlist survives a power loss once this returns.
+/// This can be turned off via [`PathWriteOptions::with_fsync`] for cases where throughput matters
+/// more than durability. The lines are formatted as per [`crate::Writer`], with the line ending
+/// set via [`PathWriteOptions::with_line_ending`].
+///
+/// Returns the number of bytes written.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{HlsLine, LineEnding, PathWriteOptions, write_playlist_to_path};
+/// # use quick_m3u8::tag::hls::{Inf, Targetduration};
+/// # use std::fs;
+/// # let path = std::env::temp_dir().join("quick-m3u8-path-writer-doc-example.m3u8");
+/// let lines = [
+///     HlsLine::from(Targetduration::new(4)),
+///     HlsLine::from(Inf::new(4.0, "")),
+///     HlsLine::uri("segment_1.mp4"),
+/// ];
+/// let options = PathWriteOptions::new().with_line_ending(LineEnding::Crlf);
+/// write_playlist_to_path(&path, lines, &options)?;
+///
+/// assert_eq!(
+///     "#EXT-X-TARGETDURATION:4\r\n#EXTINF:4\r\nsegment_1.mp4\r\n",
+///     fs::read_to_string(&path)?
+/// );
+/// # fs::remove_file(&path)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn write_playlist_to_path<'a, Custom>(
+    path: impl AsRef<Path>,
+    lines: impl IntoIterator<Item = HlsLine<'a, Custom>>,
+    options: &PathWriteOptions,
+) -> io::Result<u64>
+where
+    Custom: WritableCustomTag<'a>,
+{
+    let path = path.as_ref();
+    let mut bytes_written = 0;
+    replace_atomically(path, &unique_temp_path(path), options.fsync, |file| {
+        let mut writer = WriterBuilder::new()
+            .with_line_ending(options.line_ending)
+            .build(BufWriter::new(file));
+        for line in lines {
+            writer.write_custom_line(line)?;
+        }
+        bytes_written = writer.bytes_written();
+        writer.into_inner().flush()
+    })?;
+    Ok(bytes_written)
+}
+
+/// Options for [`write_playlist_to_path`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathWriteOptions {
+    fsync: bool,
+    line_ending: LineEnding,
+}
+
+impl PathWriteOptions {
+    /// Constructs the default options: the file is flushed to disk and lines end with `\n`.
+    pub const fn new() -> Self {
+        Self {
+            fsync: true,
+            line_ending: LineEnding::Lf,
+        }
+    }
+
+    /// Sets whether the file (and, on Unix, its directory) is flushed to disk. Without this, the
+    /// replacement is still atomic for readers, but may be lost (or be empty) after a power loss.
+    pub const fn with_fsync(mut self, fsync: bool) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
+    /// Sets the line ending written after each line.
+    pub const fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Whether the file is flushed to disk.
+    pub const fn fsync(&self) -> bool {
+        self.fsync
+    }
+
+    /// The line ending written after each line.
+    pub const fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+}
+
+impl Default for PathWriteOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Writes to a newly created `temp_path` and renames it over `path`, removing the temporary file on
+// failure. The temporary file is created exclusively, so this fails if it already exists.
+pub(crate) fn replace_atomically(
+    path: &Path,
+    temp_path: &Path,
+    fsync: bool,
+    write: impl FnOnce(&mut File) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp_path)?;
+    let result = write(&mut file).and_then(|_| if fsync { file.sync_all() } else { Ok(()) });
+    drop(file);
+    match result.and_then(|_| fs::rename(temp_path, path)) {
+        Ok(()) => {
+            if fsync {
+                sync_parent_directory(path)?;
+            }
+            Ok(())
+        }
+        Err(e) => {
+            let _ = fs::remove_file(temp_path);
+            Err(e)
+        }
+    }
+}
+
+// The rename is only durable once the directory entry has been flushed. Directories cannot be opened
+// as files on Windows (where the rename is flushed as part of the file metadata), so this is Unix
+// only.
+#[cfg(unix)]
+fn sync_parent_directory(path: &Path) -> io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_parent_directory(_: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+// Each write uses its own temporary file, so that concurrent writes of the same playlist do not
+// interfere with each other (the last rename wins), and so that a temporary file left behind by a
+// crash does not block later writes.
+pub(crate) fn unique_temp_path(path: &Path) -> PathBuf {
+    let mut file_name = OsString::from(".");
+    file_name.push(path.file_name().unwrap_or_default());
+    file_name.push(format!(
+        ".{}.{}.tmp",
+        process_id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(file_name)
+}
+
+// There are no processes on `wasm32-unknown-unknown` (where `std::process::id` panics), though
+// neither is there a file system, and so opening the file fails with `Unsupported` anyway.
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+fn process_id() -> u32 {
+    std::process::id()
+}
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+fn process_id() -> u32 {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct TestPath(PathBuf);
+    impl TestPath {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!(
+                "quick-m3u8-path-writer-test-{}-{name}.m3u8",
+                std::process::id()
+            )))
+        }
+
+        fn read(&self) -> String {
+            fs::read_to_string(&self.0).unwrap()
+        }
+    }
+    impl Drop for TestPath {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn leftover_temp_files(path: &Path) -> usize {
+        let prefix = format!(".{}.", path.file_name().unwrap().to_string_lossy());
+        fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .count()
+    }
+
+    #[test]
+    fn write_should_replace_existing_file() {
+        let path = TestPath::new("replace");
+        fs::write(&path.0, "#EXTM3U\nold.mp4\n").unwrap();
+        let bytes = write_playlist_to_path(
+            &path.0,
+            [HlsLine::comment("EXTM3U"), HlsLine::uri("new.mp4")],
+            &PathWriteOptions::new().with_fsync(false),
+        )
+        .unwrap();
+        assert_eq!("#EXTM3U\nnew.mp4\n", path.read());
+        assert_eq!(16, bytes);
+        assert_eq!(0, leftover_temp_files(&path.0));
+    }
+
+    #[test]
+    fn write_should_create_missing_file() {
+        let path = TestPath::new("create");
+        write_playlist_to_path(
+            &path.0,
+            [HlsLine::uri("a.mp4"), HlsLine::blank()],
+            &PathWriteOptions::default(),
+        )
+        .unwrap();
+        assert_eq!("a.mp4\n\n", path.read());
+    }
+
+    #[test]
+    fn write_to_missing_directory_should_fail() {
+        let path = TestPath::new("missing-dir");
+        let missing = path.0.join("playlist.m3u8");
+        let error =
+            write_playlist_to_path(&missing, Vec::<HlsLine>::new(), &PathWriteOptions::new())
+                .unwrap_err();
+        assert_eq!(io::ErrorKind::NotFound, error.kind());
+        assert!(!missing.exists());
+    }
+}
@@ -0,0 +1,632 @@
+use crate::{
+    HlsLine, Reader, Writer,
+    config::ParsingOptions,
+    date::{self, DateTime},
+    tag::{
+        AttributeMap, AttributeValue, KnownTag, UnknownTag,
+        hls::{self, TagName},
+    },
+};
+use std::io::{self, Write};
+
+const CUE_OUT: &str = "-X-CUE-OUT";
+const CUE_OUT_CONT: &str = "-X-CUE-OUT-CONT";
+const CUE_IN: &str = "-X-CUE-IN";
+const SCTE35: &str = "-X-SCTE35";
+const SCTE35_CLASS_PREFIX: &str = "urn:scte:scte35";
+
+/// Extracts all ad breaks signaled within a Media Playlist.
+///
+/// There are three common styles of signaling ad breaks in HLS, and this method recognizes all of
+/// them, normalizing the result into [`AdBreak`] records:
+/// * `EXT-X-CUE-OUT` / `EXT-X-CUE-OUT-CONT` / `EXT-X-CUE-IN` tags ([`AdBreakSignal::CueOut`]).
+/// * `EXT-X-DATERANGE` tags that carry `SCTE35-OUT` (or `SCTE35-CMD`) attributes, or that have a
+///   `CLASS` beginning with `urn:scte:scte35` ([`AdBreakSignal::Daterange`]).
+/// * `EXT-X-SCTE35` tags as defined in the SCTE-35 specification ([`AdBreakSignal::Scte35`]).
+///
+/// The media sequence number and program date time of the first segment of the break are resolved
+/// by walking the playlist (propagating `EXT-X-MEDIA-SEQUENCE`, `EXT-X-PROGRAM-DATE-TIME` and
+/// `EXTINF`). When a break does not declare a duration, but an end is signaled (`EXT-X-CUE-IN`,
+/// `CUE-IN=YES`, or `END-DATE`), the duration is calculated from the signaled end. When the
+/// playlist begins in the middle of a break (signaled via `EXT-X-CUE-OUT-CONT` or `CUE-OUT=CONT`)
+/// the break is still reported, with a start date derived from the elapsed time (if known), but no
+/// media sequence number.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{date_time, playlist::{ad_breaks, AdBreak, AdBreakSignal}};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:10
+/// #EXT-X-MEDIA-SEQUENCE:100
+/// #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z
+/// #EXTINF:10
+/// segment_100.ts
+/// #EXT-X-CUE-OUT:DURATION=20
+/// #EXTINF:10
+/// ad_1.ts
+/// #EXTINF:10
+/// ad_2.ts
+/// #EXT-X-CUE-IN
+/// #EXTINF:10
+/// segment_103.ts
+/// "#;
+///
+/// assert_eq!(
+///     vec![AdBreak {
+///         signal: AdBreakSignal::CueOut,
+///         id: None,
+///         media_sequence: Some(101),
+///         start_date: Some(date_time!(2025-01-01 T 00:00:10.000)),
+///         duration: Some(20.0),
+///         scte35: None,
+///     }],
+///     ad_breaks(PLAYLIST)
+/// );
+/// ```
+pub fn ad_breaks(playlist: &str) -> Vec<AdBreak<'_>> {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_parsing_for_media_sequence()
+            .with_parsing_for_program_date_time()
+            .with_parsing_for_inf()
+            .build(),
+    );
+    let mut state = ExtractionState::default();
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => match tag {
+                hls::Tag::MediaSequence(tag) => state.media_sequence = tag.media_sequence(),
+                hls::Tag::ProgramDateTime(tag) => state.next_date = Some(tag.program_date_time()),
+                hls::Tag::Inf(tag) => state.next_duration = tag.duration(),
+                _ => (),
+            },
+            Ok(Some(HlsLine::UnknownTag(tag))) => state.handle_tag(tag),
+            Ok(Some(HlsLine::Uri(_))) => state.handle_segment(),
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    state.finish()
+}
+
+/// A normalized record of an ad break.
+///
+/// See [`ad_breaks`] for more information on how these are extracted from a playlist.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AdBreak<'a> {
+    /// The style of signaling that the break was derived from.
+    pub signal: AdBreakSignal,
+    /// The identifier of the break (if signaled).
+    pub id: Option<&'a str>,
+    /// The media sequence number of the first segment in the break (if known). This saturates at
+    /// `u64::MAX`.
+    pub media_sequence: Option<u64>,
+    /// The date of the start of the break (if known).
+    pub start_date: Option<DateTime>,
+    /// The duration of the break in seconds (if known).
+    pub duration: Option<f64>,
+    /// The SCTE-35 splice info section that signaled the start of the break (if any). This is the
+    /// value as found in the playlist (hex for `EXT-X-DATERANGE`, base64 for `EXT-X-SCTE35`).
+    pub scte35: Option<&'a str>,
+}
+
+/// The style of signaling used for an ad break.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum AdBreakSignal {
+    /// `#EXT-X-CUE-OUT:<duration>` at the start of the break and `#EXT-X-CUE-IN` at the end.
+    CueOut,
+    /// `#EXT-X-DATERANGE` with `SCTE35-OUT` as defined in the HLS specification.
+    Daterange,
+    /// `#EXT-X-SCTE35:CUE-OUT=YES` at the start of the break and `#EXT-X-SCTE35:CUE-IN=YES` at the
+    /// end, as defined in the SCTE-35 specification.
+    Scte35,
+}
+
+impl<W> Writer<W>
+where
+    W: Write,
+{
+    /// Write the lines that signal the start of an ad break in the chosen signaling style. This
+    /// should be written before the first segment of the break. Returns the number of bytes
+    /// written.
+    ///
+    /// When writing in the [`AdBreakSignal::Daterange`] style and the break has no `id`, then an
+    /// identifier is derived from the media sequence number (or start date) of the break.
+    ///
+    /// For example, converting breaks signaled by `EXT-X-DATERANGE` into `EXT-X-CUE-OUT`:
+    /// ```
+    /// # use quick_m3u8::{Writer, date_time, playlist::{AdBreak, AdBreakSignal}};
+    /// let ad_break = AdBreak {
+    ///     signal: AdBreakSignal::Daterange,
+    ///     id: Some("break-1"),
+    ///     media_sequence: Some(101),
+    ///     start_date: Some(date_time!(2025-01-01 T 00:00:10.000)),
+    ///     duration: Some(20.0),
+    ///     scte35: Some("0xFC30"),
+    /// };
+    /// let mut writer = Writer::new(Vec::new());
+    /// writer.write_ad_break_start(&ad_break, AdBreakSignal::CueOut)?;
+    /// writer.write_uri("ad_1.ts")?;
+    /// writer.write_uri("ad_2.ts")?;
+    /// writer.write_ad_break_end(&ad_break, AdBreakSignal::CueOut)?;
+    /// assert_eq!(
+    ///     "#EXT-X-CUE-OUT:20\nad_1.ts\nad_2.ts\n#EXT-X-CUE-IN\n",
+    ///     String::from_utf8_lossy(&writer.into_inner())
+    /// );
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn write_ad_break_start(
+        &mut self,
+        ad_break: &AdBreak,
+        signal: AdBreakSignal,
+    ) -> io::Result<usize> {
+        match signal {
+            AdBreakSignal::CueOut => match ad_break.duration {
+                Some(duration) => {
+                    self.write_raw_line(format!("#EXT{CUE_OUT}:{duration}").as_bytes())
+                }
+                None => self.write_raw_line(format!("#EXT{CUE_OUT}").as_bytes()),
+            },
+            AdBreakSignal::Daterange => {
+                let id = match (ad_break.id, ad_break.media_sequence, ad_break.start_date) {
+                    (Some(id), _, _) => id.to_string(),
+                    (None, Some(media_sequence), _) => format!("ad-break-{media_sequence}"),
+                    (None, None, Some(start_date)) => format!("ad-break-{start_date}"),
+                    (None, None, None) => "ad-break".to_string(),
+                };
+                let mut daterange = hls::Daterange::builder().with_id(id).finish();
+                if let Some(start_date) = ad_break.start_date {
+                    daterange.set_start_date(start_date);
+                }
+                if let Some(duration) = ad_break.duration {
+                    daterange.set_duration(duration);
+                }
+                if let Some(scte35) = ad_break.scte35 {
+                    daterange.set_scte35_out(scte35.to_string());
+                }
+                self.write_line(HlsLine::from(daterange))
+            }
+            AdBreakSignal::Scte35 => {
+                let mut line = format!("#EXT{SCTE35}:");
+                if let Some(scte35) = ad_break.scte35 {
+                    line.push_str(&format!("CUE=\"{scte35}\","));
+                }
+                line.push_str("CUE-OUT=YES");
+                if let Some(duration) = ad_break.duration {
+                    line.push_str(&format!(",DURATION={duration}"));
+                }
+                if let Some(id) = ad_break.id {
+                    line.push_str(&format!(",ID=\"{id}\""));
+                }
+                self.write_raw_line(line.as_bytes())
+            }
+        }
+    }
+
+    /// Write the lines that signal the end of an ad break in the chosen signaling style. This
+    /// should be written after the last segment of the break. Returns the number of bytes written.
+    ///
+    /// For the [`AdBreakSignal::Daterange`] style nothing is written, as the end of the break is
+    /// defined by the duration provided on the start tag.
+    pub fn write_ad_break_end(
+        &mut self,
+        ad_break: &AdBreak,
+        signal: AdBreakSignal,
+    ) -> io::Result<usize> {
+        match signal {
+            AdBreakSignal::CueOut => self.write_raw_line(format!("#EXT{CUE_IN}").as_bytes()),
+            AdBreakSignal::Daterange => Ok(0),
+            AdBreakSignal::Scte35 => match ad_break.id {
+                Some(id) => {
+                    self.write_raw_line(format!("#EXT{SCTE35}:CUE-IN=YES,ID=\"{id}\"").as_bytes())
+                }
+                None => self.write_raw_line(format!("#EXT{SCTE35}:CUE-IN=YES").as_bytes()),
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct ExtractionState<'a> {
+    breaks: Vec<AdBreak<'a>>,
+    media_sequence: u64,
+    next_date: Option<DateTime>,
+    next_duration: f64,
+    // Breaks where the first segment has not yet been found.
+    awaiting_first_segment: Vec<usize>,
+    // The break opened by CUE-OUT or SCTE35 tags, along with the duration of segments found since.
+    open_break: Option<(usize, f64)>,
+    // (media sequence, start date, duration) of each segment, used to resolve daterange breaks.
+    segments: Vec<(u64, Option<DateTime>, f64)>,
+}
+
+impl<'a> ExtractionState<'a> {
+    fn handle_tag(&mut self, tag: UnknownTag<'a>) {
+        match tag.name() {
+            CUE_OUT => {
+                let duration = tag.value().and_then(|value| {
+                    value.try_as_decimal_floating_point().ok().or_else(|| {
+                        let list = value.try_as_attribute_map().ok()?;
+                        find_float(&list, "DURATION")
+                    })
+                });
+                self.open(AdBreakSignal::CueOut, None, duration, None);
+            }
+            CUE_OUT_CONT if self.open_break.is_none() => {
+                let (elapsed, duration) = tag.value().map(parse_cue_out_cont).unwrap_or_default();
+                self.open_in_progress(AdBreakSignal::CueOut, None, duration, None, elapsed);
+            }
+            CUE_IN => self.close(),
+            SCTE35 => {
+                let Some(list) = tag
+                    .value()
+                    .and_then(|value| value.try_as_attribute_map().ok())
+                else {
+                    return;
+                };
+                let id = find_str(&list, "ID");
+                let duration = find_float(&list, "DURATION");
+                let cue = find_str(&list, "CUE");
+                match find_str(&list, "CUE-OUT") {
+                    Some("YES") => self.open(AdBreakSignal::Scte35, id, duration, cue),
+                    Some("CONT") if self.open_break.is_none() => {
+                        let elapsed = find_float(&list, "ELAPSED");
+                        self.open_in_progress(AdBreakSignal::Scte35, id, duration, cue, elapsed);
+                    }
+                    _ => (),
+                }
+                if find_str(&list, "CUE-IN") == Some("YES") {
+                    self.close();
+                }
+            }
+            name if matches!(TagName::try_from(name), Ok(TagName::Daterange)) => {
+                if let Some(list) = tag
+                    .value()
+                    .and_then(|value| value.try_as_attribute_map().ok())
+                {
+                    self.handle_daterange(&list);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn handle_daterange(&mut self, list: &AttributeMap<'a>) {
+        let Some(id) = find_str(list, "ID") else {
+            return;
+        };
+        let scte35 = find_str(list, "SCTE35-OUT").or_else(|| find_str(list, "SCTE35-CMD"));
+        let start_date = find_str(list, "START-DATE").and_then(|d| date::parse(d).ok());
+        let end_date = find_str(list, "END-DATE").and_then(|d| date::parse(d).ok());
+        let duration = find_float(list, "DURATION")
+            .or_else(|| find_float(list, "PLANNED-DURATION"))
+            .or_else(|| match (start_date, end_date) {
+                (Some(start), Some(end)) => Some(end.timestamp() - start.timestamp()),
+                _ => None,
+            });
+        let existing = self
+            .breaks
+            .iter_mut()
+            .find(|b| b.signal == AdBreakSignal::Daterange && b.id == Some(id));
+        if let Some(existing) = existing {
+            existing.start_date = existing.start_date.or(start_date);
+            existing.duration = existing.duration.or(duration);
+            existing.scte35 = existing.scte35.or(scte35);
+            return;
+        }
+        let is_scte35_class =
+            find_str(list, "CLASS").is_some_and(|c| c.starts_with(SCTE35_CLASS_PREFIX));
+        if scte35.is_none() && !is_scte35_class {
+            return;
+        }
+        self.breaks.push(AdBreak {
+            signal: AdBreakSignal::Daterange,
+            id: Some(id),
+            media_sequence: None,
+            start_date,
+            duration,
+            scte35,
+        });
+    }
+
+    fn open(
+        &mut self,
+        signal: AdBreakSignal,
+        id: Option<&'a str>,
+        duration: Option<f64>,
+        scte35: Option<&'a str>,
+    ) {
+        self.close();
+        self.breaks.push(AdBreak {
+            signal,
+            id,
+            media_sequence: None,
+            start_date: None,
+            duration,
+            scte35,
+        });
+        let index = self.breaks.len() - 1;
+        self.awaiting_first_segment.push(index);
+        self.open_break = Some((index, 0.0));
+    }
+
+    fn open_in_progress(
+        &mut self,
+        signal: AdBreakSignal,
+        id: Option<&'a str>,
+        duration: Option<f64>,
+        scte35: Option<&'a str>,
+        elapsed: Option<f64>,
+    ) {
+        let start_date = self
+            .next_date
+            .zip(elapsed)
+            .map(|(date, elapsed)| date.add_seconds(-elapsed));
+        self.breaks.push(AdBreak {
+            signal,
+            id,
+            media_sequence: None,
+            start_date,
+            duration,
+            scte35,
+        });
+        self.open_break = Some((self.breaks.len() - 1, elapsed.unwrap_or_default()));
+    }
+
+    fn close(&mut self) {
+        if let Some((index, elapsed)) = self.open_break.take() {
+            let ad_break = &mut self.breaks[index];
+            if ad_break.duration.is_none() {
+                ad_break.duration = Some(elapsed);
+            }
+        }
+    }
+
+    fn handle_segment(&mut self) {
+        for index in self.awaiting_first_segment.drain(..) {
+            self.breaks[index].media_sequence = Some(self.media_sequence);
+            self.breaks[index].start_date = self.next_date;
+        }
+        if let Some((_, elapsed)) = &mut self.open_break {
+            *elapsed += self.next_duration;
+        }
+        self.segments
+            .push((self.media_sequence, self.next_date, self.next_duration));
+        self.next_date = self
+            .next_date
+            .map(|date| date.add_seconds(self.next_duration));
+        self.media_sequence = self.media_sequence.saturating_add(1);
+        self.next_duration = 0.0;
+    }
+
+    fn finish(mut self) -> Vec<AdBreak<'a>> {
+        // Resolve the first segment of dateranges by finding the segment that contains the start.
+        for ad_break in &mut self.breaks {
+            if ad_break.signal != AdBreakSignal::Daterange {
+                continue;
+            }
+            let Some(start) = ad_break.start_date.map(|d| d.timestamp()) else {
+                continue;
+            };
+            ad_break.media_sequence = self.segments.iter().find_map(|(msn, date, duration)| {
+                let segment_start = (*date)?.timestamp();
+                // Allowing for a millisecond of error in the segment timing.
+                (start >= segment_start - 0.001 && start < segment_start + duration - 0.001)
+                    .then_some(*msn)
+            });
+        }
+        self.breaks
+    }
+}
+
+fn find_str<'a>(list: &AttributeMap<'a>, name: &str) -> Option<&'a str> {
+    list.get(name).and_then(|value| match value {
+        AttributeValue::Quoted(s) => Some(s),
+        AttributeValue::Unquoted(v) => v.try_as_utf_8().ok(),
+    })
+}
+
+fn find_float(list: &AttributeMap, name: &str) -> Option<f64> {
+    list.get(name)
+        .and_then(|value| value.unquoted())
+        .and_then(|v| v.try_as_decimal_floating_point().ok())
+}
+
+// EXT-X-CUE-OUT-CONT is seen in a few different forms:
+// * `#EXT-X-CUE-OUT-CONT:10/30`
+// * `#EXT-X-CUE-OUT-CONT:ELAPSED=10,DURATION=30`
+// * `#EXT-X-CUE-OUT-CONT:ElapsedTime=10,Duration=30`
+fn parse_cue_out_cont(value: crate::tag::TagValue) -> (Option<f64>, Option<f64>) {
+    if let Ok(list) = value.try_as_attribute_map() {
+        let elapsed = find_float(&list, "ELAPSED").or_else(|| find_float(&list, "ElapsedTime"));
+        let duration = find_float(&list, "DURATION").or_else(|| find_float(&list, "Duration"));
+        return (elapsed, duration);
+    }
+    let Ok(value) = std::str::from_utf8(value.0) else {
+        return (None, None);
+    };
+    let mut split = value.splitn(2, '/');
+    let elapsed = split.next().and_then(|s| s.trim().parse().ok());
+    let duration = split.next().and_then(|s| s.trim().parse().ok());
+    (elapsed, duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_time;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn daterange_breaks_should_be_resolved_to_segments() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+            "#EXT-X-DATERANGE:ID=\"splice-1\",START-DATE=\"2025-01-01T00:00:04.000Z\",",
+            "PLANNED-DURATION=8,SCTE35-OUT=0xFC30\n",
+            "#EXT-X-DATERANGE:ID=\"other\",START-DATE=\"2025-01-01T00:00:00.000Z\"\n",
+            "#EXTINF:4\n",
+            "segment_10.ts\n",
+            "#EXTINF:4\n",
+            "segment_11.ts\n",
+            "#EXT-X-DATERANGE:ID=\"splice-1\",START-DATE=\"2025-01-01T00:00:04.000Z\",",
+            "DURATION=7.5,SCTE35-IN=0xFC31\n",
+            "#EXT-X-DATERANGE:ID=\"splice-2\",CLASS=\"urn:scte:scte35:2014:bin\",",
+            "START-DATE=\"2025-01-01T00:00:08.000Z\",END-DATE=\"2025-01-01T00:00:10.000Z\"\n",
+            "#EXTINF:4\n",
+            "segment_12.ts\n",
+        );
+        assert_eq!(
+            vec![
+                AdBreak {
+                    signal: AdBreakSignal::Daterange,
+                    id: Some("splice-1"),
+                    media_sequence: Some(11),
+                    start_date: Some(date_time!(2025-01-01 T 00:00:04.000)),
+                    duration: Some(8.0),
+                    scte35: Some("0xFC30"),
+                },
+                AdBreak {
+                    signal: AdBreakSignal::Daterange,
+                    id: Some("splice-2"),
+                    media_sequence: Some(12),
+                    start_date: Some(date_time!(2025-01-01 T 00:00:08.000)),
+                    duration: Some(2.0),
+                    scte35: None,
+                },
+            ],
+            ad_breaks(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn scte35_tag_breaks_should_be_extracted() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXTINF:6\n",
+            "segment_0.ts\n",
+            "#EXT-X-SCTE35:CUE=\"/DAlAAAA\",CUE-OUT=YES,ID=\"123\"\n",
+            "#EXTINF:6\n",
+            "ad_0.ts\n",
+            "#EXTINF:6\n",
+            "ad_1.ts\n",
+            "#EXT-X-SCTE35:CUE=\"/DAgAAAA\",CUE-IN=YES\n",
+            "#EXTINF:6\n",
+            "segment_3.ts\n",
+        );
+        assert_eq!(
+            vec![AdBreak {
+                signal: AdBreakSignal::Scte35,
+                id: Some("123"),
+                media_sequence: Some(1),
+                start_date: None,
+                duration: Some(12.0),
+                scte35: Some("/DAlAAAA"),
+            }],
+            ad_breaks(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn cue_out_cont_at_start_of_playlist_should_be_in_progress_break() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA-SEQUENCE:50\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:01:00.000Z\n",
+            "#EXT-X-CUE-OUT-CONT:ElapsedTime=20,Duration=30,SCTE35=/DAlAAAA\n",
+            "#EXTINF:10\n",
+            "ad_2.ts\n",
+            "#EXT-X-CUE-IN\n",
+            "#EXT-X-CUE-OUT:15.5\n",
+            "#EXTINF:10\n",
+            "ad_0.ts\n",
+        );
+        assert_eq!(
+            vec![
+                AdBreak {
+                    signal: AdBreakSignal::CueOut,
+                    id: None,
+                    media_sequence: None,
+                    start_date: Some(date_time!(2025-01-01 T 00:00:40.000)),
+                    duration: Some(30.0),
+                    scte35: None,
+                },
+                AdBreak {
+                    signal: AdBreakSignal::CueOut,
+                    id: None,
+                    media_sequence: Some(51),
+                    start_date: Some(date_time!(2025-01-01 T 00:01:10.000)),
+                    duration: Some(15.5),
+                    scte35: None,
+                },
+            ],
+            ad_breaks(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn cue_in_should_set_duration_when_not_signaled() {
+        const PLAYLIST: &str =
+            "#EXT-X-CUE-OUT\n#EXTINF:4\na.ts\n#EXTINF:3.5\nb.ts\n#EXT-X-CUE-IN\n";
+        assert_eq!(Some(7.5), ad_breaks(PLAYLIST)[0].duration);
+    }
+
+    #[test]
+    fn extreme_sequences_and_durations_should_saturate() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA-SEQUENCE:18446744073709551615\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+            "#EXTINF:1e308,\n",
+            "segment.ts\n",
+            "#EXT-X-CUE-OUT:DURATION=20\n",
+            "#EXTINF:10\n",
+            "ad.ts\n",
+        );
+        assert_eq!(
+            vec![AdBreak {
+                signal: AdBreakSignal::CueOut,
+                id: None,
+                media_sequence: Some(u64::MAX),
+                start_date: Some(date_time!(9999-12-31 T 23:59:59.999)),
+                duration: Some(20.0),
+                scte35: None,
+            }],
+            ad_breaks(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn write_ad_break_in_each_style() {
+        let ad_break = AdBreak {
+            signal: AdBreakSignal::CueOut,
+            id: None,
+            media_sequence: Some(101),
+            start_date: Some(date_time!(2025-01-01 T 00:00:10.000)),
+            duration: Some(30.0),
+            scte35: Some("0xFC30"),
+        };
+        let mut writer = Writer::new(Vec::new());
+        for signal in [
+            AdBreakSignal::CueOut,
+            AdBreakSignal::Daterange,
+            AdBreakSignal::Scte35,
+        ] {
+            writer.write_ad_break_start(&ad_break, signal).unwrap();
+            writer.write_ad_break_end(&ad_break, signal).unwrap();
+        }
+        assert_eq!(
+            concat!(
+                "#EXT-X-CUE-OUT:30\n",
+                "#EXT-X-CUE-IN\n",
+                "#EXT-X-DATERANGE:ID=\"ad-break-101\",START-DATE=\"2025-01-01T00:00:10.000Z\",",
+                "DURATION=30,SCTE35-OUT=0xFC30\n",
+                "#EXT-X-SCTE35:CUE=\"0xFC30\",CUE-OUT=YES,DURATION=30\n",
+                "#EXT-X-SCTE35:CUE-IN=YES\n",
+            ),
+            String::from_utf8_lossy(&writer.into_inner())
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use crate::tag::hls::{AudioCodingIdentifier, GetValid, Media, MediaType};
+
+/// The audio capabilities of a device, used to select the audio renditions (`EXT-X-MEDIA` with
+/// `TYPE=AUDIO`) that it can play (see [`compatible_audio_renditions`]).
+///
+/// By default, no constraint is placed on the renditions: each capability that is not set is
+/// assumed to be supported.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct AudioCapabilities {
+    max_channels: Option<u32>,
+    sample_rates: Option<Vec<u64>>,
+    bit_depths: Option<Vec<u64>>,
+    spatial_audio: Option<Vec<AudioCodingIdentifier>>,
+}
+
+impl AudioCapabilities {
+    /// Creates capabilities that place no constraint on the renditions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of channels (the count of the `CHANNELS` attribute) that can be
+    /// played.
+    pub fn with_max_channels(mut self, max_channels: u32) -> Self {
+        self.max_channels = Some(max_channels);
+        self
+    }
+
+    /// Sets the sample rates (the `SAMPLE-RATE` attribute) that can be played.
+    pub fn with_sample_rates(mut self, sample_rates: impl IntoIterator<Item = u64>) -> Self {
+        self.sample_rates = Some(sample_rates.into_iter().collect());
+        self
+    }
+
+    /// Sets the bit depths (the `BIT-DEPTH` attribute) that can be played.
+    pub fn with_bit_depths(mut self, bit_depths: impl IntoIterator<Item = u64>) -> Self {
+        self.bit_depths = Some(bit_depths.into_iter().collect());
+        self
+    }
+
+    /// Sets the spatial audio coding identifiers (the second parameter of the `CHANNELS` attribute)
+    /// that can be played. An empty list indicates that spatial audio is not supported at all.
+    pub fn with_spatial_audio(
+        mut self,
+        spatial_audio: impl IntoIterator<Item = AudioCodingIdentifier>,
+    ) -> Self {
+        self.spatial_audio = Some(spatial_audio.into_iter().collect());
+        self
+    }
+
+    /// Indicates whether the rendition can be played.
+    ///
+    /// Only audio renditions are compatible. An attribute that is absent from the rendition (or
+    /// that cannot be parsed) does not make it incompatible, as nothing is known about it. A
+    /// rendition with spatial audio is only compatible when each of its audio coding identifiers is
+    /// supported, and an unrecognized identifier is never supported (unless spatial audio is not
+    /// constrained).
+    pub fn is_compatible(&self, media: &Media) -> bool {
+        if media.media_type().known() != Some(&MediaType::Audio) {
+            return false;
+        }
+        let is_supported =
+            |value: Option<u64>, supported: &Option<Vec<u64>>| match (value, supported) {
+                (Some(value), Some(supported)) => supported.contains(&value),
+                _ => true,
+            };
+        if !is_supported(media.sample_rate(), &self.sample_rates)
+            || !is_supported(media.bit_depth(), &self.bit_depths)
+        {
+            return false;
+        }
+        let Some(channels) = media.channels().valid() else {
+            return true;
+        };
+        if self
+            .max_channels
+            .is_some_and(|max_channels| channels.count() > max_channels)
+        {
+            return false;
+        }
+        match &self.spatial_audio {
+            Some(supported) => channels.spatial_audio().iter().all(|identifier| {
+                identifier
+                    .known()
+                    .is_some_and(|identifier| supported.contains(identifier))
+            }),
+            None => true,
+        }
+    }
+}
+
+/// The audio renditions that are compatible with the capabilities of the device (see
+/// [`AudioCapabilities::is_compatible`]), in order of preference.
+///
+/// Renditions are preferred in the following order:
+/// 1. Those with spatial audio (which are only compatible when the device supports it).
+/// 2. Those with a higher channel count (where renditions without a `CHANNELS` count come last).
+/// 3. Those that are not a downmix of another rendition.
+/// 4. Those with a higher `SAMPLE-RATE`, and then a higher `BIT-DEPTH`.
+/// 5. Those with `DEFAULT=YES`.
+///
+/// Renditions that are equally preferred remain in the order that they were provided. The language
+/// and characteristics of the renditions are not considered, and so the renditions would typically
+/// be those of a single group that have already been filtered by language (see, for example,
+/// [`super::RenditionGroup::compatible_audio_renditions`]).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{AudioCapabilities, compatible_audio_renditions};
+/// # use quick_m3u8::tag::hls::{AudioCodingIdentifier, Media, MediaType};
+/// let stereo = Media::builder()
+///     .with_media_type(MediaType::Audio)
+///     .with_name("Stereo")
+///     .with_group_id("aud")
+///     .with_channels("2")
+///     .with_sample_rate(48000)
+///     .finish();
+/// let surround = Media::builder()
+///     .with_media_type(MediaType::Audio)
+///     .with_name("Surround")
+///     .with_group_id("aud")
+///     .with_channels("6")
+///     .with_sample_rate(48000)
+///     .finish();
+/// let atmos = Media::builder()
+///     .with_media_type(MediaType::Audio)
+///     .with_name("Atmos")
+///     .with_group_id("aud")
+///     .with_channels("16/JOC")
+///     .with_sample_rate(48000)
+///     .finish();
+/// let renditions = [stereo, surround, atmos];
+///
+/// let names = |capabilities: &AudioCapabilities| {
+///     compatible_audio_renditions(&renditions, capabilities)
+///         .into_iter()
+///         .map(|media| media.name())
+///         .collect::<Vec<_>>()
+/// };
+/// assert_eq!(
+///     vec!["Surround", "Stereo"],
+///     names(&AudioCapabilities::new().with_max_channels(8).with_spatial_audio([]))
+/// );
+/// assert_eq!(
+///     vec!["Atmos", "Surround", "Stereo"],
+///     names(
+///         &AudioCapabilities::new()
+///             .with_max_channels(16)
+///             .with_spatial_audio([AudioCodingIdentifier::JointObjectCoding])
+///     )
+/// );
+/// assert_eq!(
+///     Vec::<&str>::new(),
+///     names(&AudioCapabilities::new().with_sample_rates([44100]))
+/// );
+/// ```
+pub fn compatible_audio_renditions<'p, 'a>(
+    renditions: impl IntoIterator<Item = &'p Media<'a>>,
+    capabilities: &AudioCapabilities,
+) -> Vec<&'p Media<'a>>
+where
+    'a: 'p,
+{
+    let mut compatible = renditions
+        .into_iter()
+        .filter(|media| capabilities.is_compatible(media))
+        .collect::<Vec<_>>();
+    compatible.sort_by(|a, b| preference(b).cmp(&preference(a)));
+    compatible
+}
+
+// The properties that a rendition is preferred by, where a greater value is preferred.
+fn preference(media: &Media) -> impl Ord {
+    let channels = media.channels().valid();
+    (
+        channels
+            .as_ref()
+            .is_some_and(|channels| !channels.spatial_audio().is_empty()),
+        channels.as_ref().map(|channels| channels.count()),
+        !channels
+            .as_ref()
+            .is_some_and(|channels| channels.is_downmix()),
+        media.sample_rate(),
+        media.bit_depth(),
+        media.default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn audio(name: &'static str, channels: Option<&'static str>) -> Media<'static> {
+        Media::builder()
+            .with_media_type(MediaType::Audio)
+            .with_name(name)
+            .with_group_id("aud")
+            .with_channels_opt(channels)
+            .finish()
+    }
+
+    fn names<'p>(renditions: Vec<&'p Media<'_>>) -> Vec<&'p str> {
+        renditions.into_iter().map(|media| media.name()).collect()
+    }
+
+    #[test]
+    fn renditions_without_attributes_should_be_compatible_but_least_preferred() {
+        let mut hi_res = audio("Hi-Res", Some("2"));
+        hi_res.set_sample_rate(96000);
+        hi_res.set_bit_depth(24);
+        let mut cd = audio("CD", Some("2"));
+        cd.set_sample_rate(44100);
+        cd.set_bit_depth(16);
+        let mut default = audio("Default", Some("2"));
+        default.set_sample_rate(44100);
+        default.set_bit_depth(16);
+        default.set_default(true);
+        let renditions = [audio("Unknown", None), cd, default, hi_res];
+        assert_eq!(
+            vec!["Hi-Res", "Default", "CD", "Unknown"],
+            names(compatible_audio_renditions(
+                &renditions,
+                &AudioCapabilities::new()
+            ))
+        );
+        assert_eq!(
+            vec!["Default", "CD", "Unknown"],
+            names(compatible_audio_renditions(
+                &renditions,
+                &AudioCapabilities::new().with_bit_depths([16])
+            ))
+        );
+    }
+
+    #[test]
+    fn downmix_should_be_less_preferred_than_original_with_same_count() {
+        let renditions = [
+            audio("Downmix", Some("2/-/DOWNMIX")),
+            audio("Stereo", Some("2")),
+            audio("Unrecognized spatial", Some("2/XYZ")),
+        ];
+        assert_eq!(
+            vec!["Stereo", "Downmix"],
+            names(compatible_audio_renditions(
+                &renditions,
+                &AudioCapabilities::new()
+                    .with_spatial_audio([AudioCodingIdentifier::JointObjectCoding])
+            ))
+        );
+    }
+
+    #[test]
+    fn only_audio_renditions_should_be_compatible() {
+        let subtitles = Media::builder()
+            .with_media_type(MediaType::Subtitles)
+            .with_name("English")
+            .with_group_id("subs")
+            .with_uri("en.m3u8")
+            .finish();
+        assert!(!AudioCapabilities::new().is_compatible(&subtitles));
+        assert!(AudioCapabilities::new().is_compatible(&audio("English", None)));
+    }
+}
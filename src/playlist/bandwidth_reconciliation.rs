@@ -0,0 +1,341 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    tag::{
+        KnownTag,
+        hls::{self, TagName},
+    },
+};
+
+/// Measures the bit rate of a Media Playlist from the segment information that it carries.
+///
+/// The bit rate of a Media Segment is known when either:
+/// * the segment has an `EXT-X-BYTERANGE`, in which case the bit rate is the size of the range
+///   divided by the `EXTINF` duration, or
+/// * an `EXT-X-BITRATE` precedes the segment (and the segment has no `EXT-X-BYTERANGE`), in which
+///   case the bit rate is the value of that tag (converted from kbps).
+///
+/// Segments that have neither, have no (or a zero) `EXTINF` duration, or are marked with
+/// `EXT-X-GAP`, are not measured (and are counted in [`MeasuredBandwidth::unmeasured_segments`]).
+/// This returns `None` if no segment could be measured. Lines that cannot be parsed are skipped.
+///
+/// The peak is the highest bit rate of any single segment. Note that the HLS specification defines
+/// the peak segment bit rate over runs of segments that last between 0.5 and 1.5 times the Target
+/// Duration; the per-segment peak is at least as high as that, and so is the more conservative
+/// measure when checking that `BANDWIDTH` is not under-advertised.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{measure_bandwidth, MeasuredBandwidth};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-BITRATE:1000
+/// #EXTINF:4
+/// segment_1.mp4
+/// #EXT-X-BITRATE:2000
+/// #EXTINF:4
+/// segment_2.mp4
+/// "#;
+///
+/// assert_eq!(
+///     Some(MeasuredBandwidth {
+///         peak: 2_000_000,
+///         average: 1_500_000,
+///         measured_segments: 2,
+///         unmeasured_segments: 0,
+///     }),
+///     measure_bandwidth(PLAYLIST)
+/// );
+/// ```
+pub fn measure_bandwidth(media_playlist: &str) -> Option<MeasuredBandwidth> {
+    let mut reader = Reader::from_str(
+        media_playlist,
+        ParsingOptions::builder()
+            .with_tags([
+                TagName::Inf,
+                TagName::Bitrate,
+                TagName::Byterange,
+                TagName::Gap,
+            ])
+            .build(),
+    );
+    let mut bitrate_kbps = None;
+    let mut duration = None;
+    let mut byterange_length = None;
+    let mut gap = false;
+    let mut peak = 0.0_f64;
+    let mut total_bits = 0.0;
+    let mut total_duration = 0.0;
+    let mut measured_segments = 0;
+    let mut unmeasured_segments = 0;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => match tag {
+                hls::Tag::Inf(tag) => duration = Some(tag.duration()),
+                hls::Tag::Bitrate(tag) => bitrate_kbps = Some(tag.bitrate()),
+                hls::Tag::Byterange(tag) => byterange_length = Some(tag.length()),
+                hls::Tag::Gap(_) => gap = true,
+                _ => (),
+            },
+            Ok(Some(HlsLine::Uri(_))) => {
+                let duration = duration.take().filter(|duration| *duration > 0.0);
+                let bits = match byterange_length.take() {
+                    Some(length) => Some(length as f64 * 8.0),
+                    None => bitrate_kbps
+                        .zip(duration)
+                        .map(|(kbps, duration)| kbps as f64 * 1000.0 * duration),
+                };
+                match bits.zip(duration).filter(|_| !gap) {
+                    Some((bits, duration)) => {
+                        peak = peak.max(bits / duration);
+                        total_bits += bits;
+                        total_duration += duration;
+                        measured_segments += 1;
+                    }
+                    None => unmeasured_segments += 1,
+                }
+                gap = false;
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    if measured_segments == 0 {
+        return None;
+    }
+    Some(MeasuredBandwidth {
+        peak: peak.round() as u64,
+        average: (total_bits / total_duration).round() as u64,
+        measured_segments,
+        unmeasured_segments,
+    })
+}
+
+/// Checks the `BANDWIDTH` and `AVERAGE-BANDWIDTH` advertised for each Variant Stream of a
+/// Multivariant Playlist against the bit rate measured from its Media Playlist (see
+/// [`measure_bandwidth`]).
+///
+/// The Media Playlists are provided as pairs of the Variant Stream URI (as written in the
+/// Multivariant Playlist) and the playlist content. Variant Streams without a Media Playlist
+/// provided, or whose Media Playlist has no measurable segments, are not checked. The measured peak
+/// is compared against `BANDWIDTH`, and the measured average against `AVERAGE-BANDWIDTH` (when
+/// present), and a [`BandwidthDeviation`] is reported when the measured value differs from the
+/// advertised value by more than the `tolerance`, which is a fraction of the advertised value
+/// (e.g. `0.1` for 10%).
+///
+/// Note that the advertised values of a Variant Stream also account for the Renditions that play
+/// alongside it (e.g. a separate audio group), whereas only the Media Playlist of the Variant Stream
+/// itself is measured; the tolerance should allow for this.
+///
+/// The deviations are provided in the order of the Variant Streams, with `BANDWIDTH` before
+/// `AVERAGE-BANDWIDTH`. Lines that cannot be parsed are skipped.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{reconcile_bandwidth, BandwidthAttribute, BandwidthDeviation};
+/// const MULTIVARIANT: &str = r#"#EXTM3U
+/// #EXT-X-STREAM-INF:BANDWIDTH=1000000,AVERAGE-BANDWIDTH=1200000
+/// low.m3u8
+/// "#;
+/// const LOW: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXTINF:4
+/// #EXT-X-BYTERANGE:500000@0
+/// low.mp4
+/// #EXTINF:4
+/// #EXT-X-BYTERANGE:700000
+/// low.mp4
+/// "#;
+///
+/// let deviations = reconcile_bandwidth(MULTIVARIANT, &[("low.m3u8", LOW)], 0.1);
+/// assert_eq!(
+///     vec![BandwidthDeviation {
+///         variant_uri: "low.m3u8",
+///         attribute: BandwidthAttribute::Bandwidth,
+///         advertised: 1_000_000,
+///         measured: 1_400_000,
+///     }],
+///     deviations
+/// );
+/// assert_eq!(0.4, deviations[0].relative_deviation());
+/// ```
+pub fn reconcile_bandwidth<'a>(
+    multivariant_playlist: &'a str,
+    media_playlists: &[(&str, &str)],
+    tolerance: f64,
+) -> Vec<BandwidthDeviation<'a>> {
+    let mut reader = Reader::from_str(
+        multivariant_playlist,
+        ParsingOptions::builder()
+            .with_tags([TagName::StreamInf])
+            .build(),
+    );
+    let mut deviations = Vec::new();
+    let mut advertised = None;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::StreamInf(tag))))) => {
+                advertised = Some((tag.bandwidth(), tag.average_bandwidth()));
+            }
+            Ok(Some(input_uri!(variant_uri))) => {
+                let Some((bandwidth, average_bandwidth)) = advertised.take() else {
+                    continue;
+                };
+                let Some(measured) = media_playlists
+                    .iter()
+                    .find(|(uri, _)| *uri == variant_uri)
+                    .and_then(|(_, playlist)| measure_bandwidth(playlist))
+                else {
+                    continue;
+                };
+                let comparisons = [
+                    Some((BandwidthAttribute::Bandwidth, bandwidth, measured.peak)),
+                    average_bandwidth.map(|average_bandwidth| {
+                        (
+                            BandwidthAttribute::AverageBandwidth,
+                            average_bandwidth,
+                            measured.average,
+                        )
+                    }),
+                ];
+                for (attribute, advertised, measured) in comparisons.into_iter().flatten() {
+                    let deviation = BandwidthDeviation {
+                        variant_uri,
+                        attribute,
+                        advertised,
+                        measured,
+                    };
+                    if deviation.relative_deviation().abs() > tolerance {
+                        deviations.push(deviation);
+                    }
+                }
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    deviations
+}
+
+/// The bit rate of a Media Playlist, as provided by [`measure_bandwidth`]. All bit rates are in
+/// bits per second.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct MeasuredBandwidth {
+    /// The highest bit rate of any measured segment.
+    pub peak: u64,
+    /// The total size of the measured segments divided by their total duration.
+    pub average: u64,
+    /// The number of segments that were measured.
+    pub measured_segments: usize,
+    /// The number of segments that could not be measured.
+    pub unmeasured_segments: usize,
+}
+
+/// A difference between an advertised and measured bit rate, as found by [`reconcile_bandwidth`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BandwidthDeviation<'a> {
+    /// The URI of the Variant Stream (as written in the Multivariant Playlist).
+    pub variant_uri: &'a str,
+    /// The attribute that deviated.
+    pub attribute: BandwidthAttribute,
+    /// The advertised bit rate.
+    pub advertised: u64,
+    /// The measured bit rate (the peak for `BANDWIDTH` and the average for `AVERAGE-BANDWIDTH`).
+    pub measured: u64,
+}
+
+impl BandwidthDeviation<'_> {
+    /// The difference between the measured and advertised bit rates as a fraction of the
+    /// advertised bit rate. This is positive when the Variant Stream is under-advertised (i.e.
+    /// needs more bandwidth than advertised), and infinite when the advertised bit rate is 0.
+    pub fn relative_deviation(&self) -> f64 {
+        (self.measured as f64 - self.advertised as f64) / self.advertised as f64
+    }
+}
+
+/// The `EXT-X-STREAM-INF` attribute compared by [`reconcile_bandwidth`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum BandwidthAttribute {
+    /// `BANDWIDTH`, which is compared with the measured peak.
+    Bandwidth,
+    /// `AVERAGE-BANDWIDTH`, which is compared with the measured average.
+    AverageBandwidth,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bitrate_should_not_apply_to_byterange_segments() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:2\n",
+            "#EXT-X-BITRATE:8000\n",
+            "#EXTINF:2\n",
+            "#EXT-X-BYTERANGE:250000@0\n",
+            "a.mp4\n",
+            "#EXTINF:2\n",
+            "b.mp4\n",
+        );
+        assert_eq!(
+            Some(MeasuredBandwidth {
+                peak: 8_000_000,
+                average: 4_500_000,
+                measured_segments: 2,
+                unmeasured_segments: 0,
+            }),
+            measure_bandwidth(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn gap_and_unknown_segments_should_not_be_measured() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:2\n",
+            "#EXTINF:2\n",
+            "a.mp4\n",
+            "#EXT-X-BITRATE:1000\n",
+            "#EXTINF:2\n",
+            "#EXT-X-GAP\n",
+            "b.mp4\n",
+            "#EXTINF:2\n",
+            "c.mp4\n",
+        );
+        assert_eq!(
+            Some(MeasuredBandwidth {
+                peak: 1_000_000,
+                average: 1_000_000,
+                measured_segments: 1,
+                unmeasured_segments: 2,
+            }),
+            measure_bandwidth(PLAYLIST)
+        );
+        assert_eq!(None, measure_bandwidth("#EXTM3U\n#EXTINF:2\na.mp4\n"));
+    }
+
+    #[test]
+    fn variants_within_tolerance_or_without_media_playlist_should_not_deviate() {
+        const MULTIVARIANT: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1050000,AVERAGE-BANDWIDTH=500000\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=2000000\n",
+            "high.m3u8\n",
+        );
+        const LOW: &str = "#EXTM3U\n#EXT-X-BITRATE:1000\n#EXTINF:4\nlow.mp4\n";
+        let deviations = reconcile_bandwidth(MULTIVARIANT, &[("low.m3u8", LOW)], 0.1);
+        assert_eq!(
+            vec![BandwidthDeviation {
+                variant_uri: "low.m3u8",
+                attribute: BandwidthAttribute::AverageBandwidth,
+                advertised: 500_000,
+                measured: 1_000_000,
+            }],
+            deviations
+        );
+        assert_eq!(1.0, deviations[0].relative_deviation());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    tag::{
+        HlsPlaylistType, KnownTag,
+        hls::{self, TagName, TagType},
+    },
+};
+
+/// The values used by [`cache_advice`] for playlists that are not expected to change.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CachePolicy {
+    multivariant_max_age: u64,
+    complete_max_age: u64,
+}
+
+impl Default for CachePolicy {
+    /// Caches Multivariant Playlists for 5 minutes, and complete Media Playlists for a day.
+    fn default() -> Self {
+        Self {
+            multivariant_max_age: 300,
+            complete_max_age: 86_400,
+        }
+    }
+}
+
+impl CachePolicy {
+    /// Creates the default policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `max-age` (in seconds) for Multivariant Playlists.
+    ///
+    /// A Multivariant Playlist rarely changes; however, it does not indicate whether it will, and so
+    /// the value is a choice of the origin.
+    pub fn with_multivariant_max_age(mut self, max_age: u64) -> Self {
+        self.multivariant_max_age = max_age;
+        self
+    }
+
+    /// Sets the `max-age` (in seconds) for Media Playlists that will not change (i.e. that have
+    /// `EXT-X-ENDLIST`, or are `EXT-X-PLAYLIST-TYPE:VOD`).
+    pub fn with_complete_max_age(mut self, max_age: u64) -> Self {
+        self.complete_max_age = max_age;
+        self
+    }
+
+    /// The `max-age` (in seconds) for Multivariant Playlists.
+    pub fn multivariant_max_age(&self) -> u64 {
+        self.multivariant_max_age
+    }
+
+    /// The `max-age` (in seconds) for Media Playlists that will not change.
+    pub fn complete_max_age(&self) -> u64 {
+        self.complete_max_age
+    }
+}
+
+/// The recommended HTTP caching for a playlist response, as provided by [`cache_advice`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CacheAdvice {
+    /// The `max-age` (in seconds) for a request of the playlist.
+    pub max_age: u64,
+    /// The `max-age` (in seconds) for a blocking request of the playlist (one with the `_HLS_msn`
+    /// Delivery Directive, see [`crate::playlist::DeliveryDirectives`]). This is only provided when
+    /// the server supports blocking reloads (`CAN-BLOCK-RELOAD=YES`).
+    pub blocking_max_age: Option<u64>,
+    /// Indicates whether the playlist will not change.
+    pub immutable: bool,
+}
+
+impl CacheAdvice {
+    /// The value to use for the HTTP `Cache-Control` header of a request of the playlist.
+    pub fn cache_control(&self) -> String {
+        format_cache_control(self.max_age, self.immutable)
+    }
+
+    /// The value to use for the HTTP `Cache-Control` header of a blocking request of the playlist
+    /// (see [`Self::blocking_max_age`]).
+    pub fn blocking_cache_control(&self) -> Option<String> {
+        self.blocking_max_age
+            .map(|max_age| format_cache_control(max_age, false))
+    }
+}
+
+fn format_cache_control(max_age: u64, immutable: bool) -> String {
+    if immutable {
+        format!("max-age={max_age}, immutable")
+    } else {
+        format!("max-age={max_age}")
+    }
+}
+
+/// Computes the recommended HTTP caching for a playlist response, so that origins and proxies can
+/// set a consistent policy from the playlist itself.
+///
+/// The advice depends on the kind of playlist:
+/// * A Multivariant Playlist uses [`CachePolicy::multivariant_max_age`].
+/// * A Media Playlist that will not change (it has `EXT-X-ENDLIST`, or is
+///   `EXT-X-PLAYLIST-TYPE:VOD`) uses [`CachePolicy::complete_max_age`], and is immutable.
+/// * A live Media Playlist is cached for half of the Target Duration, such that a client reloading
+///   the playlist (which the HLS specification requires to happen no sooner than one Target
+///   Duration after the previous load, or half of one when the playlist has not changed) is not
+///   served a stale playlist for more than one reload. When the playlist has `EXT-X-PART-INF`,
+///   half of the Part Target Duration is used instead (rounded down, and so possibly 0).
+///
+/// A response to a blocking request (supported when the `EXT-X-SERVER-CONTROL` has
+/// `CAN-BLOCK-RELOAD=YES`) is for a specific Media Sequence Number (and part), and so is still
+/// correct (as the starting point for that client) for as long as the segment remains in the
+/// playlist. Such a response can therefore be cached for the duration of the playlist (the sum of
+/// the segment durations, and at least the Target Duration).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{CacheAdvice, CachePolicy, cache_advice};
+/// const LIVE: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:6
+/// #EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES
+/// #EXTINF:6
+/// 0.ts
+/// #EXTINF:6
+/// 1.ts
+/// #EXTINF:6
+/// 2.ts
+/// "#;
+/// let advice = cache_advice(LIVE, &CachePolicy::default());
+/// assert_eq!("max-age=3", advice.cache_control());
+/// assert_eq!(Some("max-age=18".to_string()), advice.blocking_cache_control());
+///
+/// let vod = format!("{LIVE}#EXT-X-ENDLIST\n");
+/// let advice = cache_advice(&vod, &CachePolicy::new().with_complete_max_age(3600));
+/// assert_eq!("max-age=3600, immutable", advice.cache_control());
+/// ```
+pub fn cache_advice(playlist: &str, policy: &CachePolicy) -> CacheAdvice {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_parsing_for_targetduration()
+            .with_parsing_for_part_inf()
+            .with_parsing_for_playlist_type()
+            .with_parsing_for_server_control()
+            .with_parsing_for_inf()
+            .build(),
+    );
+    let mut is_multivariant = false;
+    let mut is_complete = false;
+    let mut can_block_reload = false;
+    let mut target_duration = 0;
+    let mut part_target = None;
+    let mut playlist_duration = 0.0;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => match tag {
+                hls::Tag::Targetduration(tag) => target_duration = tag.target_duration(),
+                hls::Tag::PartInf(tag) => part_target = Some(tag.part_target()),
+                hls::Tag::PlaylistType(tag) => {
+                    is_complete |= tag.playlist_type() == HlsPlaylistType::Vod
+                }
+                hls::Tag::ServerControl(tag) => can_block_reload = tag.can_block_reload(),
+                hls::Tag::Inf(tag) => playlist_duration += tag.duration(),
+                _ => (),
+            },
+            Ok(Some(HlsLine::UnknownTag(tag))) => match TagName::try_from(tag.name()) {
+                Ok(TagName::Endlist) => is_complete = true,
+                Ok(name) if name.tag_type() == TagType::MultivariantPlaylist => {
+                    is_multivariant = true
+                }
+                _ => (),
+            },
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    if is_multivariant {
+        CacheAdvice {
+            max_age: policy.multivariant_max_age,
+            blocking_max_age: None,
+            immutable: false,
+        }
+    } else if is_complete {
+        CacheAdvice {
+            max_age: policy.complete_max_age,
+            blocking_max_age: None,
+            immutable: true,
+        }
+    } else {
+        let reload_interval = part_target.unwrap_or(target_duration as f64);
+        CacheAdvice {
+            max_age: (reload_interval / 2.0) as u64,
+            blocking_max_age: can_block_reload
+                .then(|| (playlist_duration as u64).max(target_duration)),
+            immutable: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn multivariant_playlist_should_use_policy() {
+        let playlist = "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1000\nlow.m3u8\n";
+        assert_eq!(
+            CacheAdvice {
+                max_age: 60,
+                blocking_max_age: None,
+                immutable: false,
+            },
+            cache_advice(playlist, &CachePolicy::new().with_multivariant_max_age(60))
+        );
+    }
+
+    #[test]
+    fn low_latency_playlist_should_use_part_target() {
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:4\n\
+            #EXT-X-PART-INF:PART-TARGET=1.004\n\
+            #EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES\n\
+            #EXTINF:3.9\n\
+            0.mp4\n";
+        assert_eq!(
+            CacheAdvice {
+                max_age: 0,
+                blocking_max_age: Some(4),
+                immutable: false,
+            },
+            cache_advice(playlist, &CachePolicy::default())
+        );
+    }
+
+    #[test]
+    fn vod_playlist_type_should_be_immutable() {
+        let playlist = "#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXT-X-PLAYLIST-TYPE:VOD\n";
+        let advice = cache_advice(playlist, &CachePolicy::default());
+        assert!(advice.immutable);
+        assert_eq!(86_400, advice.max_age);
+    }
+}
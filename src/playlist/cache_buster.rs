@@ -0,0 +1,259 @@
+use crate::{
+    HlsLine, HlsLineVisitor,
+    tag::hls::{IFrameStreamInf, Key, Map, Media, Part, PreloadHint, RenditionReport},
+    utils::fnv1a,
+};
+use std::borrow::Cow;
+
+/// Injects deterministic pseudo-random tokens into the URIs (and, optionally, comments) of a
+/// playlist, for cache-busting and cache partitioning in proxies.
+///
+/// The tokens are derived from a session identifier, such that the same session always gets the
+/// same token for the same URI (meaning that repeated playlist reloads within a session remain
+/// cacheable), while different sessions get different tokens. [`Self::partition`] provides a
+/// stable bucket for the session, so that A/B cache partitioning can be decided in the same pass.
+///
+/// `CacheBuster` implements [`HlsLineVisitor`], and so can be used directly to rewrite a playlist.
+/// When a rewrite is already being done with another visitor, [`Self::tokenize_uri`] and
+/// [`Self::salt_comment`] can be called from within that visitor instead, so that the playlist does
+/// not need to be rewritten twice.
+///
+/// A token is added as a query parameter to URI lines, and to the `URI` attribute of
+/// `EXT-X-MAP`, `EXT-X-KEY`, `EXT-X-PART`, `EXT-X-PRELOAD-HINT`, `EXT-X-RENDITION-REPORT`,
+/// `EXT-X-MEDIA`, and `EXT-X-I-FRAME-STREAM-INF`. URIs with a scheme other than `http` or `https`
+/// (e.g. `data:` or `skd://` key URIs) are left unchanged.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{HlsLineVisitor, Reader, Writer, config::ParsingOptions};
+/// # use quick_m3u8::playlist::CacheBuster;
+/// const PLAYLIST: &str = "#EXTM3U\n#EXT-X-MAP:URI=\"init.mp4\"\n#EXTINF:4\nsegment.mp4?a=1\n";
+///
+/// let mut cache_buster = CacheBuster::new("session-1234", "cb");
+/// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+/// let mut writer = Writer::new(Vec::new());
+/// while let Some(line) = reader.read_line()? {
+///     if let Some(line) = cache_buster.visit_line(line) {
+///         writer.write_line(line)?;
+///     }
+/// }
+/// let output = String::from_utf8(writer.into_inner())?;
+/// assert_eq!(
+///     format!(
+///         "#EXTM3U\n#EXT-X-MAP:URI=\"{}\"\n#EXTINF:4\n{}\n",
+///         cache_buster.tokenize_uri("init.mp4"),
+///         cache_buster.tokenize_uri("segment.mp4?a=1"),
+///     ),
+///     output
+/// );
+/// assert!(output.contains("init.mp4?cb="));
+/// assert!(output.contains("segment.mp4?a=1&cb="));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheBuster {
+    seed: u64,
+    query_parameter: String,
+    comment_salt_prefix: Option<String>,
+}
+
+impl CacheBuster {
+    /// Creates a new `CacheBuster` for the session, adding tokens to URIs using `query_parameter`
+    /// as the name of the query parameter.
+    pub fn new(session: &str, query_parameter: impl Into<String>) -> Self {
+        Self {
+            seed: fnv1a(session.as_bytes()),
+            query_parameter: query_parameter.into(),
+            comment_salt_prefix: None,
+        }
+    }
+
+    /// Sets a prefix that identifies salt comments. Comments that start with the prefix are
+    /// rewritten as the prefix followed by a token for the session (see [`Self::salt_comment`]).
+    pub fn with_comment_salt_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.comment_salt_prefix = Some(prefix.into());
+        self
+    }
+
+    /// The deterministic token for the value within this session.
+    pub fn token(&self, value: &str) -> u64 {
+        mix(self.seed ^ fnv1a(value.as_bytes()))
+    }
+
+    /// A stable partition (in the range `0..partitions`) for this session, for use in A/B cache
+    /// partitioning.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `partitions` is zero.
+    pub fn partition(&self, partitions: u64) -> u64 {
+        mix(self.seed) % partitions
+    }
+
+    /// Adds the token for the URI as a query parameter. URIs with a scheme other than `http` or
+    /// `https` are returned unchanged.
+    pub fn tokenize_uri<'a>(&self, uri: &'a str) -> Cow<'a, str> {
+        if let Some((scheme, _)) = uri.split_once(':')
+            && !scheme.contains(['/', '?', '#'])
+            && !scheme.eq_ignore_ascii_case("http")
+            && !scheme.eq_ignore_ascii_case("https")
+        {
+            return Cow::Borrowed(uri);
+        }
+        let (uri_without_fragment, fragment) = match uri.split_once('#') {
+            Some((uri, fragment)) => (uri, Some(fragment)),
+            None => (uri, None),
+        };
+        let separator = if uri_without_fragment.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        let mut tokenized = format!(
+            "{uri_without_fragment}{separator}{}={:016x}",
+            self.query_parameter,
+            self.token(uri)
+        );
+        if let Some(fragment) = fragment {
+            tokenized.push('#');
+            tokenized.push_str(fragment);
+        }
+        Cow::Owned(tokenized)
+    }
+
+    /// Rewrites the salt in a comment that starts with the prefix set via
+    /// [`Self::with_comment_salt_prefix`]. Other comments are returned unchanged.
+    pub fn salt_comment<'a>(&self, comment: Cow<'a, str>) -> Cow<'a, str> {
+        match &self.comment_salt_prefix {
+            Some(prefix) if comment.starts_with(prefix.as_str()) => {
+                Cow::Owned(format!("{prefix}{:016x}", self.token(prefix)))
+            }
+            _ => comment,
+        }
+    }
+
+    fn tokenize_owned(&self, uri: &str) -> Option<String> {
+        match self.tokenize_uri(uri) {
+            Cow::Owned(uri) => Some(uri),
+            Cow::Borrowed(_) => None,
+        }
+    }
+}
+
+impl<'a> HlsLineVisitor<'a> for CacheBuster {
+    fn visit_key(&mut self, mut tag: Key<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = tag.uri().and_then(|uri| self.tokenize_owned(uri)) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_media(&mut self, mut tag: Media<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = tag.uri().and_then(|uri| self.tokenize_owned(uri)) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_map(&mut self, mut tag: Map<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.tokenize_owned(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_part(&mut self, mut tag: Part<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.tokenize_owned(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_preload_hint(&mut self, mut tag: PreloadHint<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.tokenize_owned(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_rendition_report(&mut self, mut tag: RenditionReport<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.tokenize_owned(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_i_frame_stream_inf(&mut self, mut tag: IFrameStreamInf<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.tokenize_owned(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_comment(&mut self, comment: Cow<'a, str>) -> Option<HlsLine<'a>> {
+        Some(HlsLine::Comment(self.salt_comment(comment)))
+    }
+
+    fn visit_uri(&mut self, uri: Cow<'a, str>) -> Option<HlsLine<'a>> {
+        match self.tokenize_owned(&uri) {
+            Some(tokenized) => Some(HlsLine::Uri(Cow::Owned(tokenized))),
+            None => Some(HlsLine::Uri(uri)),
+        }
+    }
+}
+
+// The finalizer of SplitMix64, used to spread the bits of the hash.
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tokens_should_be_deterministic_per_session() {
+        let a = CacheBuster::new("a", "cb");
+        assert_eq!(a.token("x.ts"), CacheBuster::new("a", "cb").token("x.ts"));
+        assert_ne!(a.token("x.ts"), CacheBuster::new("b", "cb").token("x.ts"));
+        assert_ne!(a.token("x.ts"), a.token("y.ts"));
+        assert_eq!(a.partition(2), CacheBuster::new("a", "other").partition(2));
+        assert!(a.partition(3) < 3);
+    }
+
+    #[test]
+    fn tokenize_uri_should_respect_query_fragment_and_scheme() {
+        let cache_buster = CacheBuster::new("session", "cb");
+        let token = |uri| format!("{:016x}", cache_buster.token(uri));
+        assert_eq!(
+            format!("a.ts?cb={}", token("a.ts")),
+            cache_buster.tokenize_uri("a.ts")
+        );
+        assert_eq!(
+            format!(
+                "https://x.com/a.ts?q=1&cb={}#t=1",
+                token("https://x.com/a.ts?q=1#t=1")
+            ),
+            cache_buster.tokenize_uri("https://x.com/a.ts?q=1#t=1")
+        );
+        assert_eq!("skd://key", cache_buster.tokenize_uri("skd://key"));
+        assert_eq!(
+            "data:text/plain,a",
+            cache_buster.tokenize_uri("data:text/plain,a")
+        );
+    }
+
+    #[test]
+    fn salt_comments_should_be_rewritten() {
+        let cache_buster = CacheBuster::new("session", "cb").with_comment_salt_prefix("salt=");
+        assert_eq!(
+            format!("salt={:016x}", cache_buster.token("salt=")),
+            cache_buster.salt_comment(Cow::Borrowed("salt=old"))
+        );
+        assert_eq!("other", cache_buster.salt_comment(Cow::Borrowed("other")));
+    }
+}
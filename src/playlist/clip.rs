@@ -0,0 +1,522 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    date::DateTime,
+    error::ClipError,
+    playlist::range_requests::resolve,
+    tag::{HlsPlaylistType, KnownTag, hls},
+};
+use std::borrow::Cow;
+
+/// Options for [`clip`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipOptions {
+    /// Whether Media Segments that only partially overlap the requested range (i.e. the segments at
+    /// either edge of the range) are included in the clip.
+    ///
+    /// When `true` (the default) the clip covers at least the requested range, and may extend
+    /// beyond it by up to a segment at either edge. When `false` the edge segments are trimmed, so
+    /// that the clip only contains segments that are entirely within the requested range.
+    pub include_partial_segments: bool,
+}
+impl Default for ClipOptions {
+    fn default() -> Self {
+        Self {
+            include_partial_segments: true,
+        }
+    }
+}
+
+/// Extracts a VOD playlist from a Media Playlist, covering the Media Segments between the program
+/// date times `start` (inclusive) and `end` (exclusive).
+///
+/// This is the standard primitive for producing a clip (or a VOD asset) from a live playlist. The
+/// resulting lines describe a complete playlist, where:
+/// * The playlist tags (e.g. `EXT-X-VERSION`, `EXT-X-TARGETDURATION`) are preserved, while tags that
+///   only apply to live playlists (e.g. `EXT-X-SERVER-CONTROL`, `EXT-X-PART`) are removed.
+/// * `EXT-X-PLAYLIST-TYPE:VOD` and `EXT-X-ENDLIST` are added, and `EXT-X-MEDIA-SEQUENCE` (and
+///   `EXT-X-DISCONTINUITY-SEQUENCE` when necessary) are updated to reflect the first segment.
+/// * The `EXT-X-KEY`, `EXT-X-MAP`, and `EXT-X-BITRATE` that apply to the first segment are carried
+///   over, even if they were declared before the start of the clip.
+/// * An `EXT-X-PROGRAM-DATE-TIME` is written for the first segment.
+/// * `EXT-X-BYTERANGE` tags are passed through, with any implicit offset made explicit, given that
+///   the segment that previously defined the offset may not be part of the clip.
+/// * `EXT-X-DATERANGE` tags that overlap the requested range are preserved.
+///
+/// Whether the segments at the edges of the range are included is determined by
+/// [`ClipOptions::include_partial_segments`]. Segments that precede the first
+/// `EXT-X-PROGRAM-DATE-TIME` cannot be located in time, and so are never included. Lines that
+/// cannot be parsed are skipped. When the media sequence (or discontinuity sequence) number of the
+/// first segment of the clip would be greater than `u64::MAX`, [`ClipError::SequenceOverflow`] is
+/// returned.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{Writer, date_time, playlist::{ClipOptions, clip}};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MEDIA-SEQUENCE:100
+/// #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z
+/// #EXT-X-KEY:METHOD=AES-128,URI="key"
+/// #EXTINF:4
+/// a.ts
+/// #EXTINF:4
+/// b.ts
+/// #EXTINF:4
+/// c.ts
+/// #EXTINF:4
+/// d.ts
+/// "#;
+///
+/// let lines = clip(
+///     PLAYLIST,
+///     date_time!(2025-01-01 T 00:00:05.000),
+///     date_time!(2025-01-01 T 00:00:12.000),
+///     ClipOptions::default(),
+/// )?;
+/// let mut writer = Writer::new(Vec::new());
+/// for line in lines {
+///     writer.write_line(line)?;
+/// }
+/// assert_eq!(
+///     r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-PLAYLIST-TYPE:VOD
+/// #EXT-X-MEDIA-SEQUENCE:101
+/// #EXT-X-KEY:METHOD=AES-128,URI="key"
+/// #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:04.000Z
+/// #EXTINF:4
+/// b.ts
+/// #EXTINF:4
+/// c.ts
+/// #EXT-X-ENDLIST
+/// "#,
+///     String::from_utf8_lossy(&writer.into_inner())
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn clip(
+    playlist: &str,
+    start: DateTime,
+    end: DateTime,
+    options: ClipOptions,
+) -> Result<Vec<HlsLine<'_>>, ClipError> {
+    let mut reader = Reader::from_str(playlist, ParsingOptions::default());
+    let mut header = Vec::new();
+    let mut segments = Vec::new();
+    let mut dateranges = Vec::new();
+    let mut next = NextSegment::default();
+    // These are `None` when the sequence number would be greater than `u64::MAX`.
+    let mut media_sequence = Some(0_u64);
+    let mut discontinuity_sequence = Some(0_u64);
+    let mut program_date_time: Option<DateTime> = None;
+    let mut init_section = None;
+    let mut keys: Vec<hls::Key> = Vec::new();
+    let mut bitrate = None;
+    let mut previous_sub_range_end: Option<(&str, u64)> = None;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => match tag {
+                hls::Tag::M3u(_)
+                | hls::Tag::Version(_)
+                | hls::Tag::IndependentSegments(_)
+                | hls::Tag::Start(_)
+                | hls::Tag::Define(_)
+                | hls::Tag::Targetduration(_)
+                | hls::Tag::IFramesOnly(_) => header.push(HlsLine::KnownTag(KnownTag::Hls(tag))),
+                hls::Tag::MediaSequence(tag) => media_sequence = Some(tag.media_sequence()),
+                hls::Tag::DiscontinuitySequence(tag) => {
+                    discontinuity_sequence = Some(tag.discontinuity_sequence())
+                }
+                hls::Tag::Inf(tag) => {
+                    next.duration = tag.duration();
+                    next.lines.push(HlsLine::from(tag));
+                }
+                hls::Tag::Byterange(tag) => next.byterange = Some(tag),
+                hls::Tag::Discontinuity(_) => {
+                    next.discontinuity = true;
+                    discontinuity_sequence = discontinuity_sequence.and_then(|s| s.checked_add(1));
+                }
+                hls::Tag::Key(key) => {
+                    if key.method() == hls::EnumeratedString::Known(hls::Method::None) {
+                        keys.clear();
+                    } else {
+                        keys.retain(|existing| existing.keyformat() != key.keyformat());
+                        keys.push(key);
+                    }
+                }
+                hls::Tag::Map(tag) => init_section = Some(tag),
+                hls::Tag::Bitrate(tag) => bitrate = Some(tag),
+                hls::Tag::ProgramDateTime(tag) => {
+                    program_date_time = Some(tag.program_date_time());
+                    next.explicit_program_date_time = true;
+                }
+                hls::Tag::Daterange(tag) => dateranges.push(tag),
+                // These either only apply to live playlists or are re-written in the clip.
+                hls::Tag::Endlist(_)
+                | hls::Tag::PlaylistType(_)
+                | hls::Tag::PartInf(_)
+                | hls::Tag::ServerControl(_)
+                | hls::Tag::Part(_)
+                | hls::Tag::Skip(_)
+                | hls::Tag::PreloadHint(_)
+                | hls::Tag::RenditionReport(_) => (),
+                tag => next.lines.push(HlsLine::KnownTag(KnownTag::Hls(tag))),
+            },
+            Ok(Some(input_uri!(uri))) => {
+                let byterange = next.byterange.take().map(|tag| {
+                    // A byte range that cannot be represented (because the end would overflow
+                    // `u64`) is kept as it was written.
+                    let request = resolve(tag.range(), previous_sub_range_end, uri);
+                    previous_sub_range_end = request.map(|request| (uri, request.end));
+                    match request {
+                        Some(request) if tag.offset().is_none() => {
+                            hls::Byterange::new(tag.length(), Some(request.start))
+                        }
+                        _ => tag,
+                    }
+                });
+                if byterange.is_none() {
+                    previous_sub_range_end = None;
+                }
+                let next = std::mem::take(&mut next);
+                segments.push(Segment {
+                    lines: next.lines,
+                    uri,
+                    media_sequence,
+                    discontinuity_sequence,
+                    discontinuity: next.discontinuity,
+                    program_date_time,
+                    explicit_program_date_time: next.explicit_program_date_time,
+                    duration: next.duration,
+                    byterange,
+                    init_section: init_section.clone(),
+                    keys: keys.clone(),
+                    bitrate: bitrate.clone(),
+                });
+                media_sequence = media_sequence.and_then(|s| s.checked_add(1));
+                program_date_time = program_date_time.map(|date| date.add_seconds(next.duration));
+            }
+            Ok(Some(line)) => next.lines.push(line),
+            Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+
+    if segments
+        .iter()
+        .all(|segment| segment.program_date_time.is_none())
+    {
+        return Err(ClipError::MissingProgramDateTime);
+    }
+    let (start, end) = (start.timestamp(), end.timestamp());
+    let mut segments = segments
+        .into_iter()
+        .filter(|segment| {
+            let Some(program_date_time) = segment.program_date_time else {
+                return false;
+            };
+            let segment_start = program_date_time.timestamp();
+            let segment_end = segment_start + segment.duration;
+            if options.include_partial_segments {
+                segment_start < end && segment_end > start
+            } else {
+                segment_start >= start && segment_end <= end
+            }
+        })
+        .peekable();
+    let Some(first) = segments.peek() else {
+        return Err(ClipError::NoSegmentsInRange);
+    };
+    let (Some(media_sequence), Some(discontinuity_sequence)) =
+        (first.media_sequence, first.discontinuity_sequence)
+    else {
+        return Err(ClipError::SequenceOverflow);
+    };
+
+    let mut lines = header;
+    lines.push(HlsLine::from(hls::PlaylistType::new(HlsPlaylistType::Vod)));
+    lines.push(HlsLine::from(hls::MediaSequence::new(media_sequence)));
+    if discontinuity_sequence > 0 {
+        lines.push(HlsLine::from(hls::DiscontinuitySequence::new(
+            discontinuity_sequence,
+        )));
+    }
+    let mut dateranges = dateranges
+        .into_iter()
+        .filter(|daterange| overlaps(daterange, start, end));
+    let mut current_init_section = None;
+    let mut current_keys = Vec::new();
+    let mut current_bitrate = None;
+    for (index, segment) in segments.enumerate() {
+        let is_first = index == 0;
+        if segment.discontinuity && !is_first {
+            lines.push(HlsLine::from(hls::Discontinuity));
+        }
+        if segment.keys != current_keys {
+            if segment.keys.is_empty() {
+                lines.push(HlsLine::from(
+                    hls::Key::builder().with_method(hls::Method::None).finish(),
+                ));
+            }
+            lines.extend(segment.keys.iter().cloned().map(HlsLine::from));
+            current_keys = segment.keys;
+        }
+        if segment.init_section != current_init_section {
+            lines.extend(segment.init_section.clone().map(HlsLine::from));
+            current_init_section = segment.init_section;
+        }
+        if segment.bitrate != current_bitrate {
+            lines.extend(segment.bitrate.clone().map(HlsLine::from));
+            current_bitrate = segment.bitrate;
+        }
+        if is_first || segment.explicit_program_date_time {
+            lines.extend(
+                segment
+                    .program_date_time
+                    .map(|date| HlsLine::from(hls::ProgramDateTime::new(date))),
+            );
+        }
+        if is_first {
+            lines.extend(dateranges.by_ref().map(HlsLine::from));
+        }
+        lines.extend(segment.lines);
+        lines.extend(segment.byterange.map(HlsLine::from));
+        lines.push(HlsLine::Uri(Cow::Borrowed(segment.uri)));
+    }
+    lines.push(HlsLine::from(hls::Endlist));
+    Ok(lines)
+}
+
+#[derive(Default)]
+struct NextSegment<'a> {
+    lines: Vec<HlsLine<'a>>,
+    duration: f64,
+    byterange: Option<hls::Byterange<'a>>,
+    discontinuity: bool,
+    explicit_program_date_time: bool,
+}
+
+struct Segment<'a> {
+    lines: Vec<HlsLine<'a>>,
+    uri: &'a str,
+    media_sequence: Option<u64>,
+    discontinuity_sequence: Option<u64>,
+    discontinuity: bool,
+    program_date_time: Option<DateTime>,
+    explicit_program_date_time: bool,
+    duration: f64,
+    byterange: Option<hls::Byterange<'a>>,
+    init_section: Option<hls::Map<'a>>,
+    keys: Vec<hls::Key<'a>>,
+    bitrate: Option<hls::Bitrate<'a>>,
+}
+
+// Dateranges without a defined end (or duration) are treated as a single point in time, unless they
+// are marked as `END-ON-NEXT`, in which case they are treated as open ended.
+fn overlaps(daterange: &hls::Daterange, start: f64, end: f64) -> bool {
+    let Some(daterange_start) = daterange.start_date().map(|date| date.timestamp()) else {
+        return false;
+    };
+    if daterange.end_on_next() {
+        return daterange_start < end;
+    }
+    let daterange_end = daterange
+        .end_date()
+        .map(|date| date.timestamp())
+        .or_else(|| daterange.duration().map(|d| daterange_start + d))
+        .or_else(|| daterange.planned_duration().map(|d| daterange_start + d));
+    match daterange_end {
+        Some(daterange_end) => daterange_start < end && daterange_end > start,
+        None => daterange_start >= start && daterange_start < end,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Writer, date_time};
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = concat!(
+        "#EXTM3U\n",
+        "#EXT-X-VERSION:7\n",
+        "#EXT-X-TARGETDURATION:4\n",
+        "#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES\n",
+        "#EXT-X-MEDIA-SEQUENCE:10\n",
+        "#EXT-X-DISCONTINUITY-SEQUENCE:2\n",
+        "#EXT-X-MAP:URI=\"init.mp4\"\n",
+        "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+        "#EXT-X-DATERANGE:ID=\"early\",START-DATE=\"2025-01-01T00:00:00.000Z\",DURATION=2\n",
+        "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2025-01-01T00:00:03.000Z\",DURATION=6\n",
+        "#EXTINF:4,\n",
+        "#EXT-X-BYTERANGE:1000@0\n",
+        "main.mp4\n",
+        "#EXTINF:4,\n",
+        "#EXT-X-BYTERANGE:1000\n",
+        "main.mp4\n",
+        "#EXT-X-DISCONTINUITY\n",
+        "#EXTINF:4,\n",
+        "#EXT-X-BYTERANGE:1000\n",
+        "main.mp4\n",
+        "#EXTINF:4,\n",
+        "#EXT-X-BYTERANGE:1000\n",
+        "main.mp4\n",
+        "#EXT-X-PART:DURATION=2,URI=\"part.mp4\"\n",
+        "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"next.mp4\"\n",
+    );
+
+    fn write(lines: Vec<HlsLine>) -> String {
+        let mut writer = Writer::new(Vec::new());
+        for line in lines {
+            writer.write_line(line).unwrap();
+        }
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn clip_should_make_byteranges_explicit_and_carry_over_state() {
+        let lines = clip(
+            PLAYLIST,
+            date_time!(2025-01-01 T 00:00:06.000),
+            date_time!(2025-01-01 T 00:00:10.000),
+            ClipOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:7\n",
+                "#EXT-X-TARGETDURATION:4\n",
+                "#EXT-X-PLAYLIST-TYPE:VOD\n",
+                "#EXT-X-MEDIA-SEQUENCE:11\n",
+                "#EXT-X-DISCONTINUITY-SEQUENCE:2\n",
+                "#EXT-X-MAP:URI=\"init.mp4\"\n",
+                "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:04.000Z\n",
+                "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2025-01-01T00:00:03.000Z\",DURATION=6\n",
+                "#EXTINF:4,\n",
+                "#EXT-X-BYTERANGE:1000@1000\n",
+                "main.mp4\n",
+                "#EXT-X-DISCONTINUITY\n",
+                "#EXTINF:4,\n",
+                "#EXT-X-BYTERANGE:1000@2000\n",
+                "main.mp4\n",
+                "#EXT-X-ENDLIST\n",
+            ),
+            write(lines)
+        );
+    }
+
+    #[test]
+    fn clip_should_trim_partial_segments_when_requested() {
+        let lines = clip(
+            PLAYLIST,
+            date_time!(2025-01-01 T 00:00:06.000),
+            date_time!(2025-01-01 T 00:00:16.000),
+            ClipOptions {
+                include_partial_segments: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-VERSION:7\n",
+                "#EXT-X-TARGETDURATION:4\n",
+                "#EXT-X-PLAYLIST-TYPE:VOD\n",
+                "#EXT-X-MEDIA-SEQUENCE:12\n",
+                "#EXT-X-DISCONTINUITY-SEQUENCE:3\n",
+                "#EXT-X-MAP:URI=\"init.mp4\"\n",
+                "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:08.000Z\n",
+                "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2025-01-01T00:00:03.000Z\",DURATION=6\n",
+                "#EXTINF:4,\n",
+                "#EXT-X-BYTERANGE:1000@2000\n",
+                "main.mp4\n",
+                "#EXTINF:4,\n",
+                "#EXT-X-BYTERANGE:1000@3000\n",
+                "main.mp4\n",
+                "#EXT-X-ENDLIST\n",
+            ),
+            write(lines)
+        );
+    }
+
+    #[test]
+    fn clip_should_error_when_no_segments_can_be_found() {
+        let start = date_time!(2025-01-01 T 00:00:00.000);
+        let end = date_time!(2025-01-01 T 00:00:10.000);
+        assert_eq!(
+            Err(ClipError::MissingProgramDateTime),
+            clip(
+                "#EXTM3U\n#EXTINF:4\na.ts\n",
+                start,
+                end,
+                ClipOptions::default()
+            )
+        );
+        assert_eq!(
+            Err(ClipError::NoSegmentsInRange),
+            clip(
+                "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:01:00.000Z\n#EXTINF:4\na.ts\n",
+                start,
+                end,
+                ClipOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn clip_should_keep_byteranges_that_overflow_as_written() {
+        let lines = clip(
+            concat!(
+                "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+                "#EXTINF:4\n",
+                "#EXT-X-BYTERANGE:100@18446744073709551600\n",
+                "a.mp4\n",
+                "#EXTINF:4\n",
+                "#EXT-X-BYTERANGE:100\n",
+                "a.mp4\n",
+            ),
+            date_time!(2025-01-01 T 00:00:00.000),
+            date_time!(2025-01-01 T 00:00:08.000),
+            ClipOptions::default(),
+        )
+        .unwrap();
+        let output = write(lines);
+        assert!(output.contains("#EXT-X-BYTERANGE:100@18446744073709551600\na.mp4\n"));
+        assert!(output.contains("#EXT-X-BYTERANGE:100@0\na.mp4\n"));
+    }
+
+    #[test]
+    fn clip_should_error_when_first_sequence_number_overflows() {
+        const PLAYLIST: &str = concat!(
+            "#EXT-X-MEDIA-SEQUENCE:18446744073709551615\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:18446744073709551615\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+            "#EXTINF:4\n",
+            "a.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:4\n",
+            "b.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:4\n",
+            "c.ts\n",
+        );
+        let clip_from = |start| {
+            clip(
+                PLAYLIST,
+                start,
+                date_time!(2025-01-01 T 00:00:12.000),
+                ClipOptions::default(),
+            )
+        };
+        let lines = clip_from(date_time!(2025-01-01 T 00:00:00.000)).unwrap();
+        let output = write(lines);
+        assert!(output.contains("#EXT-X-MEDIA-SEQUENCE:18446744073709551615\n"));
+        assert!(output.contains("#EXT-X-DISCONTINUITY-SEQUENCE:18446744073709551615\n"));
+        assert_eq!(
+            Err(ClipError::SequenceOverflow),
+            clip_from(date_time!(2025-01-01 T 00:00:04.000))
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use crate::HlsLine;
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+};
+
+/// A segment URI template, made up of a numeric placeholder between a fixed prefix and suffix (e.g.
+/// `seg_%05d.ts`).
+///
+/// The placeholder is zero-padded to a minimum width, where the width is the number of digits found
+/// when the template was detected. Since padding only ever adds digits, the template remains
+/// consistent when the number outgrows the width (e.g. `seg_%01d.ts` expands `10` to `seg_10.ts`),
+/// and so the same template matches both padded (`seg_00099.ts` followed by `seg_00100.ts`) and
+/// unpadded (`seg_99.ts` followed by `seg_100.ts`) sequences.
+///
+/// The `Display` implementation writes the template in `printf` style (e.g. `seg_%05d.ts`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UriTemplate {
+    prefix: String,
+    width: usize,
+    suffix: String,
+}
+
+impl UriTemplate {
+    /// Detects the template of the URI, along with the number that the URI uses.
+    ///
+    /// The placeholder is taken to be the last run of digits in the path of the URI (i.e. before
+    /// any query or fragment), excluding the file extension, such that a URI like
+    /// `seg_00001.mp4?v=2` is detected as `seg_%05d.mp4?v=2`. `None` is returned when there is no such run of digits, or when the number
+    /// is too large for a `u64`.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::playlist::UriTemplate;
+    /// let (template, number) = UriTemplate::detect("hi/seg_00042.ts?v=2").unwrap();
+    /// assert_eq!("hi/seg_%05d.ts?v=2", template.to_string());
+    /// assert_eq!(42, number);
+    /// assert_eq!("hi/seg_00043.ts?v=2", template.expand(43));
+    /// assert_eq!(Some(100000), template.number("hi/seg_100000.ts?v=2"));
+    /// assert_eq!(None, template.number("hi/seg_0042.ts?v=2"));
+    /// ```
+    pub fn detect(uri: &str) -> Option<(Self, u64)> {
+        let path_end = uri.find(['?', '#']).unwrap_or(uri.len());
+        let file_start = uri[..path_end].rfind('/').map(|i| i + 1).unwrap_or(0);
+        // Digits in the extension (e.g. `.mp4`) are never a placeholder.
+        let stem_end = uri[file_start..path_end]
+            .rfind('.')
+            .map(|i| file_start + i)
+            .unwrap_or(path_end);
+        let path = &uri.as_bytes()[..stem_end];
+        let end = path.iter().rposition(u8::is_ascii_digit)? + 1;
+        let start = path[..end]
+            .iter()
+            .rposition(|b| !b.is_ascii_digit())
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        // Splitting on ASCII digits always happens on a character boundary.
+        let number = uri[start..end].parse().ok()?;
+        let template = Self {
+            prefix: uri[..start].to_string(),
+            width: end - start,
+            suffix: uri[end..].to_string(),
+        };
+        Some((template, number))
+    }
+
+    /// The fixed part of the URI before the placeholder.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The fixed part of the URI after the placeholder.
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
+
+    /// The minimum number of digits in the placeholder (the number is zero-padded to this width).
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Expands the template for the number.
+    pub fn expand(&self, number: u64) -> String {
+        format!(
+            "{}{:0width$}{}",
+            self.prefix,
+            number,
+            self.suffix,
+            width = self.width
+        )
+    }
+
+    /// The number that the URI uses, when the URI is an expansion of this template (i.e. this is
+    /// the inverse of [`Self::expand`]).
+    pub fn number(&self, uri: &str) -> Option<u64> {
+        let digits = uri
+            .strip_prefix(self.prefix.as_str())?
+            .strip_suffix(self.suffix.as_str())?;
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        // Padding is only consistent with the template when it is exactly up to the width.
+        if digits.len() > self.width && digits.starts_with('0') || digits.len() < self.width {
+            return None;
+        }
+        digits.parse().ok()
+    }
+}
+
+impl Display for UriTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}%0{}d{}", self.prefix, self.width, self.suffix)
+    }
+}
+
+/// An experimental compact in-memory representation of a list of segment URIs.
+///
+/// Most playlists follow a strict URI template (e.g. `seg_%05d.ts`), where each segment URI uses
+/// the number after the one before it. Rather than holding each URI, the list holds runs of
+/// consecutive numbers for each [`UriTemplate`] (with the templates shared between runs), falling
+/// back to holding the URI when it does not continue the previous run and has no template. This
+/// drastically reduces memory for very long playlists (e.g. an event playlist of a million
+/// segments held by a monitoring service is, in the best case, a single run), while the URIs can
+/// still be re-expanded (see [`Self::iter`] and [`Self::lines`]) when the playlist is written.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{CompactUriList, segment_list};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXTINF:4
+/// seg_00098.ts
+/// #EXTINF:4
+/// seg_00099.ts
+/// #EXTINF:4
+/// seg_00100.ts
+/// #EXTINF:4
+/// slate.ts
+/// "#;
+///
+/// let list = segment_list(PLAYLIST);
+/// let uris: CompactUriList = list.segments.iter().map(|s| s.uri).collect();
+/// assert_eq!(4, uris.len());
+/// assert_eq!(2, uris.run_count());
+/// assert_eq!("seg_%05d.ts", uris.templates()[0].to_string());
+/// assert_eq!(Some("seg_00100.ts".into()), uris.get(2));
+/// assert_eq!(
+///     vec!["seg_00098.ts", "seg_00099.ts", "seg_00100.ts", "slate.ts"],
+///     uris.iter().collect::<Vec<_>>()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CompactUriList {
+    templates: Vec<UriTemplate>,
+    runs: Vec<UriRun>,
+    len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum UriRun {
+    Sequence {
+        template: usize,
+        first: u64,
+        count: u64,
+    },
+    Literal(String),
+}
+
+impl CompactUriList {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the URI to the list.
+    pub fn push(&mut self, uri: &str) {
+        self.len += 1;
+        if let Some(UriRun::Sequence {
+            template,
+            first,
+            count,
+        }) = self.runs.last_mut()
+            && self.templates[*template].number(uri) == first.checked_add(*count)
+        {
+            *count += 1;
+            return;
+        }
+        let run = match UriTemplate::detect(uri) {
+            Some((detected, first)) => {
+                let template = match self.templates.iter().position(|t| *t == detected) {
+                    Some(index) => index,
+                    None => {
+                        self.templates.push(detected);
+                        self.templates.len() - 1
+                    }
+                };
+                UriRun::Sequence {
+                    template,
+                    first,
+                    count: 1,
+                }
+            }
+            None => UriRun::Literal(uri.to_string()),
+        };
+        self.runs.push(run);
+    }
+
+    /// The number of URIs in the list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the list has no URIs.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of runs that the URIs are held in. The closer this is to `1` (compared to
+    /// [`Self::len`]), the more compact the list is.
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// The templates detected in the list (in order of first use).
+    pub fn templates(&self) -> &[UriTemplate] {
+        &self.templates
+    }
+
+    /// The URI at the index (re-expanded from its template when necessary).
+    pub fn get(&self, mut index: usize) -> Option<Cow<'_, str>> {
+        for run in &self.runs {
+            match run {
+                UriRun::Sequence {
+                    template,
+                    first,
+                    count,
+                } => match u64::try_from(index) {
+                    Ok(offset) if offset < *count => {
+                        return Some(Cow::Owned(self.templates[*template].expand(first + offset)));
+                    }
+                    // The count is never more than the length, and so fits in a usize.
+                    _ => index -= *count as usize,
+                },
+                UriRun::Literal(uri) if index == 0 => return Some(Cow::Borrowed(uri)),
+                UriRun::Literal(_) => index -= 1,
+            }
+        }
+        None
+    }
+
+    /// Iterates over the URIs (in order), re-expanding each from its template when necessary.
+    pub fn iter(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.runs.iter().flat_map(|run| {
+            let (literal, sequence) = match run {
+                UriRun::Sequence {
+                    template,
+                    first,
+                    count,
+                } => {
+                    let template = &self.templates[*template];
+                    let numbers = *first..first + count;
+                    (None, Some(numbers.map(|n| Cow::Owned(template.expand(n)))))
+                }
+                UriRun::Literal(uri) => (Some(Cow::Borrowed(uri.as_str())), None),
+            };
+            literal.into_iter().chain(sequence.into_iter().flatten())
+        })
+    }
+
+    /// Iterates over the URIs as lines, ready to be written (e.g. using [`crate::Writer`]).
+    pub fn lines(&self) -> impl Iterator<Item = HlsLine<'_>> {
+        self.iter().map(HlsLine::Uri)
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for CompactUriList {
+    fn extend<T: IntoIterator<Item = S>>(&mut self, iter: T) {
+        for uri in iter {
+            self.push(uri.as_ref());
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for CompactUriList {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Writer;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn unpadded_and_padded_numbers_should_continue_runs_past_their_width() {
+        let uris: Vec<String> = (1..=1000).map(|n| format!("seg{n}.ts")).collect();
+        let list: CompactUriList = uris.iter().collect();
+        assert_eq!(1000, list.len());
+        assert_eq!(1, list.run_count());
+        assert_eq!(uris, list.iter().collect::<Vec<_>>());
+
+        let uris: Vec<String> = (998..1002).map(|n| format!("a/{n:03}/x.mp4?v=9")).collect();
+        let list: CompactUriList = uris.iter().collect();
+        assert_eq!(1, list.run_count());
+        assert_eq!(Some(Cow::from("a/998/x.mp4?v=9")), list.get(0));
+        assert_eq!(Some(Cow::from("a/1001/x.mp4?v=9")), list.get(3));
+        assert_eq!(None, list.get(4));
+    }
+
+    #[test]
+    fn discontinuous_uris_should_start_new_runs_sharing_templates() {
+        let uris = [
+            "seg_00001.ts",
+            "seg_00002.ts",
+            "ad.ts",
+            "seg_00010.ts",
+            "seg_0011.ts",
+            "ad.ts",
+        ];
+        let list: CompactUriList = uris.iter().collect();
+        assert_eq!(6, list.len());
+        assert_eq!(5, list.run_count());
+        assert_eq!(
+            vec!["seg_%05d.ts", "seg_%04d.ts"],
+            list.templates()
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+        );
+        let mut writer = Writer::new(Vec::new());
+        for line in list.lines() {
+            writer.write_line(line).unwrap();
+        }
+        assert_eq!(
+            uris.map(|uri| format!("{uri}\n")).concat(),
+            String::from_utf8_lossy(&writer.into_inner())
+        );
+    }
+}
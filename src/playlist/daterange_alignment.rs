@@ -0,0 +1,251 @@
+use crate::{
+    playlist::SegmentList,
+    tag::hls::{Daterange, Snap},
+};
+use std::ops::Range;
+
+/// The tolerance (in seconds) within which a date is considered to be on a segment boundary.
+///
+/// `EXT-X-PROGRAM-DATE-TIME` is typically written with millisecond precision, and so a difference
+/// of less than a millisecond is not considered to be a misalignment.
+pub const ALIGNMENT_TOLERANCE: f64 = 0.001;
+
+/// Reports how an `EXT-X-DATERANGE` (such as an interstitial or an SCTE-35 signaled ad break) lines
+/// up with the segments of a Media Playlist.
+///
+/// The `START-DATE` (and the end of the date range, from `END-DATE`, `DURATION`, or, failing that,
+/// `PLANNED-DURATION`) is mapped onto the segment timeline using the program date time of the
+/// segments (see [`SegmentList::offset_of`]). Each boundary is compared to the closest segment
+/// boundary (the start of a segment, or the end of the last segment), and is considered aligned when
+/// within the [`ALIGNMENT_TOLERANCE`].
+///
+/// `None` is returned when the date range has no `START-DATE`, or when the playlist has no
+/// segments with a program date time.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{
+/// #     date_time,
+/// #     playlist::{daterange_alignment, segment_list},
+/// #     tag::hls::{Daterange, Snap},
+/// # };
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z
+/// #EXTINF:4
+/// 0.ts
+/// #EXTINF:4
+/// 1.ts
+/// #EXTINF:4
+/// 2.ts
+/// "#;
+/// let daterange = Daterange::builder()
+///     .with_id("ad")
+///     .with_start_date(date_time!(2025-01-01 T 00:00:03.000))
+///     .with_duration(5.0)
+///     .finish();
+///
+/// let alignment = daterange_alignment(&daterange, &segment_list(PLAYLIST)).unwrap();
+/// assert_eq!(0..2, alignment.covered);
+/// assert_eq!(4.0, alignment.start.boundary);
+/// assert!(!alignment.start.aligned);
+/// assert!(alignment.end.unwrap().aligned);
+/// assert_eq!(vec![Snap::Out], alignment.suggested_snap());
+/// ```
+pub fn daterange_alignment(
+    daterange: &Daterange,
+    timeline: &SegmentList,
+) -> Option<DaterangeAlignment> {
+    let start_date = daterange.start_date()?;
+    let start = timeline.offset_of(start_date)?;
+    let end = daterange
+        .end_date()
+        .and_then(|end_date| timeline.offset_of(end_date))
+        .or(daterange.duration().map(|duration| start + duration))
+        .or(daterange
+            .planned_duration()
+            .map(|duration| start + duration));
+    let mut covered = None::<Range<u64>>;
+    for segment in &timeline.segments {
+        let segment_end = segment.start + segment.duration;
+        let overlaps = match end {
+            Some(end) => {
+                segment.start < end - ALIGNMENT_TOLERANCE
+                    && segment_end > start + ALIGNMENT_TOLERANCE
+            }
+            None => {
+                segment.start <= start + ALIGNMENT_TOLERANCE
+                    && segment_end > start + ALIGNMENT_TOLERANCE
+            }
+        };
+        if overlaps {
+            let range = covered.get_or_insert(segment.media_sequence..segment.media_sequence);
+            range.end = segment.media_sequence.saturating_add(1);
+        }
+    }
+    Some(DaterangeAlignment {
+        covered: covered.unwrap_or_default(),
+        start: boundary(timeline, start)?,
+        end: end.and_then(|end| boundary(timeline, end)),
+    })
+}
+
+/// How an `EXT-X-DATERANGE` lines up with the segments of a Media Playlist, as reported by
+/// [`daterange_alignment`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct DaterangeAlignment {
+    /// The media sequence numbers of the segments that the date range covers (i.e. that overlap
+    /// it by more than the [`ALIGNMENT_TOLERANCE`]). When the date range has no end, this is the
+    /// segment that contains its start. The range is empty when the date range is entirely outside
+    /// of the playlist. The end of the range saturates at `u64::MAX`.
+    pub covered: Range<u64>,
+    /// How the start of the date range lines up with the segments.
+    pub start: BoundaryAlignment,
+    /// How the end of the date range lines up with the segments (`None` when the date range has no
+    /// known end).
+    pub end: Option<BoundaryAlignment>,
+}
+
+impl DaterangeAlignment {
+    /// Whether both the start and the end (when known) are on segment boundaries.
+    pub fn is_aligned(&self) -> bool {
+        self.start.aligned && self.end.is_none_or(|end| end.aligned)
+    }
+
+    /// The `X-SNAP` values that would have a client transition at segment boundaries, for an
+    /// interstitial that is scheduled with this date range: `OUT` when the start is not aligned, and
+    /// `IN` when the end is not aligned.
+    pub fn suggested_snap(&self) -> Vec<Snap> {
+        let mut snap = Vec::new();
+        if !self.start.aligned {
+            snap.push(Snap::Out);
+        }
+        if self.end.is_some_and(|end| !end.aligned) {
+            snap.push(Snap::In);
+        }
+        snap
+    }
+}
+
+/// How a boundary of a date range (its start or end) lines up with the segments.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BoundaryAlignment {
+    /// The offset of the boundary (in seconds) relative to the start of the playlist.
+    pub offset: f64,
+    /// The closest segment boundary (in seconds) relative to the start of the playlist.
+    pub boundary: f64,
+    /// The media sequence number of the segment that starts at the closest segment boundary (or,
+    /// when that boundary is the end of the playlist, the number after the last segment, saturating
+    /// at `u64::MAX`).
+    pub media_sequence: u64,
+    /// Whether the boundary is within the [`ALIGNMENT_TOLERANCE`] of the closest segment boundary.
+    pub aligned: bool,
+}
+
+impl BoundaryAlignment {
+    /// The adjustment (in seconds) that would move the boundary onto the closest segment boundary.
+    pub fn adjustment(&self) -> f64 {
+        self.boundary - self.offset
+    }
+}
+
+fn boundary(timeline: &SegmentList, offset: f64) -> Option<BoundaryAlignment> {
+    let last = timeline.segments.last()?;
+    let boundaries = timeline
+        .segments
+        .iter()
+        .map(|segment| (segment.start, segment.media_sequence))
+        .chain(std::iter::once((
+            last.start + last.duration,
+            last.media_sequence.saturating_add(1),
+        )));
+    let (boundary, media_sequence) =
+        boundaries.min_by(|(a, _), (b, _)| (a - offset).abs().total_cmp(&(b - offset).abs()))?;
+    Some(BoundaryAlignment {
+        offset,
+        boundary,
+        media_sequence,
+        aligned: (boundary - offset).abs() < ALIGNMENT_TOLERANCE,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{date_time, playlist::segment_list};
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = concat!(
+        "#EXTM3U\n",
+        "#EXT-X-TARGETDURATION:6\n",
+        "#EXT-X-MEDIA-SEQUENCE:10\n",
+        "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+        "#EXTINF:6.006\n",
+        "10.ts\n",
+        "#EXTINF:6.006\n",
+        "11.ts\n",
+        "#EXTINF:6.006\n",
+        "12.ts\n",
+    );
+
+    #[test]
+    fn aligned_scte_window_should_need_no_snap() {
+        let daterange = Daterange::builder()
+            .with_id("splice-1")
+            .with_start_date(date_time!(2025-01-01 T 00:00:06.006))
+            .with_planned_duration(6.0064)
+            .finish();
+        let alignment = daterange_alignment(&daterange, &segment_list(PLAYLIST)).unwrap();
+        assert_eq!(11..12, alignment.covered);
+        assert_eq!(11, alignment.start.media_sequence);
+        assert_eq!(Some(12), alignment.end.map(|end| end.media_sequence));
+        assert!(alignment.is_aligned());
+        assert_eq!(Vec::<Snap>::new(), alignment.suggested_snap());
+    }
+
+    #[test]
+    fn misaligned_boundaries_should_report_adjustments() {
+        let daterange = Daterange::builder()
+            .with_id("ad")
+            .with_start_date(date_time!(2025-01-01 T 00:00:05.000))
+            .with_end_date(date_time!(2025-01-01 T 00:00:20.000))
+            .finish();
+        let alignment = daterange_alignment(&daterange, &segment_list(PLAYLIST)).unwrap();
+        assert_eq!(10..13, alignment.covered);
+        assert_eq!(6.006, alignment.start.boundary);
+        assert!((alignment.start.adjustment() - 1.006).abs() < 1e-9);
+        let end = alignment.end.unwrap();
+        assert!((end.boundary - 18.018).abs() < 1e-9);
+        assert_eq!(13, end.media_sequence);
+        assert_eq!(vec![Snap::Out, Snap::In], alignment.suggested_snap());
+
+        let open_ended = Daterange::builder()
+            .with_id("open")
+            .with_start_date(date_time!(2025-01-01 T 00:00:07.000))
+            .finish();
+        let alignment = daterange_alignment(&open_ended, &segment_list(PLAYLIST)).unwrap();
+        assert_eq!(11..12, alignment.covered);
+        assert_eq!(None, alignment.end);
+    }
+
+    #[test]
+    fn media_sequence_after_the_largest_value_should_saturate() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA-SEQUENCE:18446744073709551615\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+            "#EXTINF:4\n",
+            "a.ts\n",
+        );
+        let daterange = Daterange::builder()
+            .with_id("ad")
+            .with_start_date(date_time!(2025-01-01 T 00:00:00.000))
+            .with_duration(4.0)
+            .finish();
+        let alignment = daterange_alignment(&daterange, &segment_list(PLAYLIST)).unwrap();
+        assert_eq!(u64::MAX..u64::MAX, alignment.covered);
+        assert_eq!(u64::MAX, alignment.start.media_sequence);
+        assert_eq!(Some(u64::MAX), alignment.end.map(|end| end.media_sequence));
+        assert!(alignment.is_aligned());
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    date::DateTime,
+    tag::{
+        KnownTag,
+        hls::{self, TagName},
+    },
+};
+
+/// Reconciles the `PLANNED-DURATION` of `EXT-X-DATERANGE` tags (such as live ad breaks) with their
+/// eventual `DURATION` (or `END-DATE`) across successive versions of a Media Playlist.
+///
+/// When a live ad break starts, the packager typically only knows how long the break is expected to
+/// last, and so signals a `PLANNED-DURATION`. The actual duration is added to the date range (via a
+/// later tag with the same `ID`) once the break has ended. Ad operations monitoring needs to know
+/// when a break closes (and how the actual duration compared to the plan), and when a break has run
+/// beyond its planned duration without having been closed.
+///
+/// Each time a new version of the playlist is loaded it should be provided to [`Self::update`],
+/// which provides the [`DaterangeDurationEvent`]s that happened since the previous version. Only
+/// date ranges that have a `PLANNED-DURATION` are tracked. Each event is provided at most once for
+/// each date range, and a date range is forgotten once it is no longer in the playlist.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{
+/// #     date_time,
+/// #     playlist::{DaterangeDurationEvent, DaterangeDurationTracker},
+/// # };
+/// let mut tracker = DaterangeDurationTracker::new();
+/// let open = r#"#EXT-X-DATERANGE:ID="ad",START-DATE="2025-01-01T00:00:00Z",PLANNED-DURATION=30"#;
+/// assert!(tracker.update(open, date_time!(2025-01-01 T 00:00:10.000)).is_empty());
+/// assert_eq!(
+///     vec![DaterangeDurationEvent::Overrun {
+///         id: "ad".to_string(),
+///         planned_duration: 30.0,
+///         elapsed: 32.0,
+///     }],
+///     tracker.update(open, date_time!(2025-01-01 T 00:00:32.000))
+/// );
+///
+/// let closed = format!("{open}\n#EXT-X-DATERANGE:ID=\"ad\",DURATION=35.5");
+/// assert_eq!(
+///     vec![DaterangeDurationEvent::Closed {
+///         id: "ad".to_string(),
+///         planned_duration: 30.0,
+///         duration: 35.5,
+///     }],
+///     tracker.update(&closed, date_time!(2025-01-01 T 00:00:36.000))
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DaterangeDurationTracker {
+    tracked: Vec<TrackedDaterange>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TrackedDaterange {
+    id: String,
+    start_date: Option<DateTime>,
+    planned_duration: Option<f64>,
+    duration: Option<f64>,
+    overrun: bool,
+    closed: bool,
+}
+
+impl DaterangeDurationTracker {
+    /// Creates a new tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the tracker with a new version of the playlist, loaded at the time `now`, providing
+    /// the events that happened since the previous version (in playlist order).
+    pub fn update(&mut self, playlist: &str, now: DateTime) -> Vec<DaterangeDurationEvent> {
+        let mut present = Vec::new();
+        let mut reader = Reader::from_str(
+            playlist,
+            ParsingOptions::builder()
+                .with_tags([TagName::Daterange])
+                .build(),
+        );
+        loop {
+            match reader.read_line() {
+                Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Daterange(tag))))) => {
+                    let id = tag.id();
+                    if !present.iter().any(|present| present == id) {
+                        present.push(id.to_string());
+                    }
+                    let tracked = match self.tracked.iter_mut().position(|t| t.id == id) {
+                        Some(index) => &mut self.tracked[index],
+                        None => {
+                            self.tracked.push(TrackedDaterange {
+                                id: id.to_string(),
+                                start_date: None,
+                                planned_duration: None,
+                                duration: None,
+                                overrun: false,
+                                closed: false,
+                            });
+                            self.tracked.last_mut().expect("just pushed")
+                        }
+                    };
+                    // Tags with the same ID are merged, and so attributes carry over from earlier
+                    // tags (and earlier versions of the playlist).
+                    tracked.start_date = tag.start_date().or(tracked.start_date);
+                    tracked.planned_duration = tag.planned_duration().or(tracked.planned_duration);
+                    tracked.duration = tag
+                        .duration()
+                        .or(tag
+                            .end_date()
+                            .zip(tracked.start_date)
+                            .map(|(end, start)| end.timestamp() - start.timestamp()))
+                        .or(tracked.duration);
+                }
+                Ok(Some(_)) | Err(_) => (),
+                Ok(None) => break,
+            }
+        }
+        self.tracked.retain(|tracked| present.contains(&tracked.id));
+
+        let mut events = Vec::new();
+        for tracked in &mut self.tracked {
+            let Some(planned_duration) = tracked.planned_duration else {
+                continue;
+            };
+            match tracked.duration {
+                Some(duration) if !tracked.closed => {
+                    tracked.closed = true;
+                    events.push(DaterangeDurationEvent::Closed {
+                        id: tracked.id.clone(),
+                        planned_duration,
+                        duration,
+                    });
+                }
+                None if !tracked.overrun => {
+                    let Some(start_date) = tracked.start_date else {
+                        continue;
+                    };
+                    let elapsed = now.timestamp() - start_date.timestamp();
+                    if elapsed > planned_duration {
+                        tracked.overrun = true;
+                        events.push(DaterangeDurationEvent::Overrun {
+                            id: tracked.id.clone(),
+                            planned_duration,
+                            elapsed,
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+        events
+    }
+}
+
+/// An event provided by [`DaterangeDurationTracker::update`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DaterangeDurationEvent {
+    /// The date range is still open (has no `DURATION` or `END-DATE`) even though more time than
+    /// the `PLANNED-DURATION` has elapsed since its `START-DATE`.
+    Overrun {
+        /// The `ID` of the date range.
+        id: String,
+        /// The `PLANNED-DURATION` of the date range (in seconds).
+        planned_duration: f64,
+        /// The time that had elapsed since the `START-DATE` when the overrun was detected (in
+        /// seconds).
+        elapsed: f64,
+    },
+    /// The actual duration of the date range became known (via `DURATION`, or `END-DATE`).
+    Closed {
+        /// The `ID` of the date range.
+        id: String,
+        /// The `PLANNED-DURATION` of the date range (in seconds).
+        planned_duration: f64,
+        /// The actual duration of the date range (in seconds).
+        duration: f64,
+    },
+}
+
+impl DaterangeDurationEvent {
+    /// The `ID` of the date range that the event is for.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Overrun { id, .. } | Self::Closed { id, .. } => id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_time;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn end_date_should_close_daterange_once() {
+        let mut tracker = DaterangeDurationTracker::new();
+        let playlist = concat!(
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\",PLANNED-DURATION=30\n",
+            "#EXT-X-DATERANGE:ID=\"b\",START-DATE=\"2025-01-01T00:00:00Z\",DURATION=10\n",
+            "#EXT-X-DATERANGE:ID=\"a\",END-DATE=\"2025-01-01T00:00:25Z\"\n",
+        );
+        let now = date_time!(2025-01-01 T 00:01:00.000);
+        assert_eq!(
+            vec![DaterangeDurationEvent::Closed {
+                id: "a".to_string(),
+                planned_duration: 30.0,
+                duration: 25.0,
+            }],
+            tracker.update(playlist, now)
+        );
+        assert_eq!(
+            Vec::<DaterangeDurationEvent>::new(),
+            tracker.update(playlist, now)
+        );
+    }
+
+    #[test]
+    fn removed_daterange_should_be_forgotten() {
+        let mut tracker = DaterangeDurationTracker::new();
+        let playlist =
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\",PLANNED-DURATION=30\n";
+        tracker.update(playlist, date_time!(2025-01-01 T 00:00:40.000));
+        tracker.update("", date_time!(2025-01-01 T 00:00:44.000));
+        let events = tracker.update(playlist, date_time!(2025-01-01 T 00:00:48.000));
+        assert_eq!(vec!["a"], events.iter().map(|e| e.id()).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,490 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    error::DeliveryDirectiveError,
+    tag::{
+        KnownTag,
+        hls::{self, RenditionReport, TagName},
+    },
+};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+};
+
+const MSN: &str = "_HLS_msn";
+const PART: &str = "_HLS_part";
+const REPORT: &str = "_HLS_report";
+const SKIP: &str = "_HLS_skip";
+
+/// The Delivery Directives of a Low-Latency HLS playlist request.
+///
+/// These are the `_HLS_` query parameters that a client adds to the URI of a Media Playlist to ask
+/// the server for a blocking reload (`_HLS_msn` and `_HLS_part`), for a Playlist Delta Update
+/// (`_HLS_skip`), and for `EXT-X-RENDITION-REPORT` tags describing other renditions
+/// (`_HLS_report`).
+///
+/// An origin can interpret an incoming request via [`Self::from_uri`], and a client (or a producer
+/// of requests, such as a load tester) can build the request via [`Self::apply_to`]. The directives
+/// are written in lexicographic order, such that the same request always has the same URI (which
+/// helps with CDN caching).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{DeliveryDirectives, SkipDirective};
+/// let directives = DeliveryDirectives::from_uri(
+///     "low.m3u8?token=abc&_HLS_skip=YES&_HLS_msn=273&_HLS_part=2&_HLS_report=../mid/mid.m3u8",
+/// )?;
+/// assert_eq!(Some(273), directives.msn);
+/// assert_eq!(Some(2), directives.part);
+/// assert_eq!(Some(SkipDirective::Yes), directives.skip);
+/// assert_eq!(vec!["../mid/mid.m3u8"], directives.reports);
+/// assert_eq!(
+///     "low.m3u8?_HLS_msn=273&_HLS_part=2&_HLS_report=../mid/mid.m3u8&_HLS_skip=YES",
+///     directives.apply_to("low.m3u8"),
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct DeliveryDirectives<'a> {
+    /// The Media Sequence Number of the segment that the playlist must contain (`_HLS_msn`).
+    pub msn: Option<u64>,
+    /// The index of the partial segment (of the segment indicated by [`Self::msn`]) that the
+    /// playlist must contain (`_HLS_part`).
+    pub part: Option<u64>,
+    /// The Playlist Delta Update that is requested (`_HLS_skip`).
+    pub skip: Option<SkipDirective>,
+    /// The URIs (relative to the requested playlist) of the renditions for which
+    /// `EXT-X-RENDITION-REPORT` tags are requested (`_HLS_report`).
+    ///
+    /// The URIs are as they appear in the query, and so should be percent-encoded where necessary.
+    pub reports: Vec<Cow<'a, str>>,
+}
+
+/// The value of the `_HLS_skip` directive (see [`DeliveryDirectives::skip`]).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SkipDirective {
+    /// `YES`: skip the segments that precede the `CAN-SKIP-UNTIL` boundary.
+    Yes,
+    /// `v2`: as with `YES`, and also skip the `EXT-X-DATERANGE` tags that were previously provided
+    /// (see the `CAN-SKIP-DATERANGES` attribute).
+    V2,
+}
+
+impl SkipDirective {
+    /// The value as it appears in the query.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Yes => "YES",
+            Self::V2 => "v2",
+        }
+    }
+}
+
+impl<'a> DeliveryDirectives<'a> {
+    /// Extracts the directives from the query of the URI (a query string without the leading `?`
+    /// is also accepted). Query parameters that are not Delivery Directives are ignored.
+    ///
+    /// An error is returned when a directive has an invalid value, when a directive (other than
+    /// `_HLS_report`) is repeated, or when `_HLS_part` is provided without `_HLS_msn`. The HLS
+    /// specification requires that the server responds to such requests with `400 Bad Request`.
+    pub fn from_uri(uri: &'a str) -> Result<Self, DeliveryDirectiveError> {
+        let uri = uri.split_once('#').map_or(uri, |(uri, _)| uri);
+        let query = uri.split_once('?').map_or(uri, |(_, query)| query);
+        let mut directives = Self::default();
+        for parameter in query.split('&') {
+            let (name, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            match name {
+                MSN => set_once(&mut directives.msn, MSN, parse_number(MSN, value)?)?,
+                PART => set_once(&mut directives.part, PART, parse_number(PART, value)?)?,
+                SKIP => {
+                    let skip = match value {
+                        "YES" => SkipDirective::Yes,
+                        "v2" => SkipDirective::V2,
+                        _ => return Err(DeliveryDirectiveError::InvalidValue(SKIP)),
+                    };
+                    set_once(&mut directives.skip, SKIP, skip)?;
+                }
+                REPORT if value.is_empty() => {
+                    return Err(DeliveryDirectiveError::InvalidValue(REPORT));
+                }
+                REPORT => directives.reports.push(Cow::Borrowed(value)),
+                _ => (),
+            }
+        }
+        if directives.part.is_some() && directives.msn.is_none() {
+            return Err(DeliveryDirectiveError::PartWithoutMsn);
+        }
+        Ok(directives)
+    }
+
+    /// The directives for a blocking reload of the rendition described by the report.
+    ///
+    /// This requests the playlist containing the segment (and partial segment) that the report
+    /// indicates was last added, which is what a client needs when switching to the rendition.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::playlist::DeliveryDirectives;
+    /// # use quick_m3u8::tag::hls::RenditionReport;
+    /// let report = RenditionReport::builder()
+    ///     .with_uri("../mid/mid.m3u8")
+    ///     .with_last_msn(273)
+    ///     .with_last_part(3)
+    ///     .finish();
+    /// let directives = DeliveryDirectives::for_rendition_report(&report);
+    /// assert_eq!(
+    ///     "../mid/mid.m3u8?_HLS_msn=273&_HLS_part=3",
+    ///     directives.apply_to(report.uri())
+    /// );
+    /// ```
+    pub fn for_rendition_report(report: &RenditionReport) -> Self {
+        Self {
+            msn: Some(report.last_msn()),
+            part: report.last_part(),
+            ..Default::default()
+        }
+    }
+
+    /// The directives for the next reload of a Low-Latency HLS Media Playlist, given the playlist
+    /// as it was last loaded, and how long ago (in seconds) it was loaded.
+    ///
+    /// When the `EXT-X-SERVER-CONTROL` has `CAN-BLOCK-RELOAD=YES`, a blocking reload is requested
+    /// for the segment after the last segment of the playlist (accounting for the segments of an
+    /// `EXT-X-SKIP`), or, when the playlist has `EXT-X-PART-INF`, for the partial segment after the
+    /// last partial segment. When the `EXT-X-SERVER-CONTROL` has `CAN-SKIP-UNTIL`, a Playlist Delta
+    /// Update is requested, as long as the playlist is no older than half of the Skip Boundary (as
+    /// the HLS specification recommends); `v2` is requested when `CAN-SKIP-DATERANGES=YES`. No
+    /// directives are provided for a playlist that has `EXT-X-ENDLIST`, as it will not change.
+    ///
+    /// An error is returned when a blocking reload would be requested for a media sequence number
+    /// greater than `u64::MAX` (which cannot be represented).
+    ///
+    /// Requests for `EXT-X-RENDITION-REPORT` tags (`_HLS_report`) depend on which renditions the
+    /// client may switch to, and so are left to the caller (see also
+    /// [`Self::for_rendition_report`] for switching to a reported rendition).
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::playlist::{DeliveryDirectives, SkipDirective};
+    /// const PLAYLIST: &str = r#"#EXTM3U
+    /// #EXT-X-TARGETDURATION:4
+    /// #EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,CAN-SKIP-UNTIL=24,PART-HOLD-BACK=3
+    /// #EXT-X-PART-INF:PART-TARGET=1
+    /// #EXT-X-MEDIA-SEQUENCE:266
+    /// #EXTINF:4
+    /// 266.mp4
+    /// #EXT-X-PART:DURATION=1,URI="267.0.mp4",INDEPENDENT=YES
+    /// #EXT-X-PART:DURATION=1,URI="267.1.mp4"
+    /// #EXT-X-PRELOAD-HINT:TYPE=PART,URI="267.2.mp4"
+    /// "#;
+    /// let directives = DeliveryDirectives::for_next_reload(PLAYLIST, 1.5)?;
+    /// assert_eq!(Some(267), directives.msn);
+    /// assert_eq!(Some(2), directives.part);
+    /// assert_eq!(Some(SkipDirective::Yes), directives.skip);
+    /// assert_eq!(
+    ///     "low.m3u8?_HLS_msn=267&_HLS_part=2&_HLS_skip=YES",
+    ///     directives.apply_to("low.m3u8")
+    /// );
+    ///
+    /// // The playlist is too old for a Playlist Delta Update.
+    /// let directives = DeliveryDirectives::for_next_reload(PLAYLIST, 13.0)?;
+    /// assert_eq!(None, directives.skip);
+    /// # Ok::<(), quick_m3u8::error::DeliveryDirectiveError>(())
+    /// ```
+    pub fn for_next_reload(
+        playlist: &str,
+        playlist_age: f64,
+    ) -> Result<Self, DeliveryDirectiveError> {
+        let mut reader = Reader::from_str(
+            playlist,
+            ParsingOptions::builder()
+                .with_parsing_for_server_control()
+                .with_parsing_for_media_sequence()
+                .with_parsing_for_skip()
+                .build(),
+        );
+        let mut can_block_reload = false;
+        let mut skip = None;
+        let mut has_part_inf = false;
+        // `None` when the next media sequence number would be greater than `u64::MAX`.
+        let mut next_msn = Some(0_u64);
+        let mut next_part = 0;
+        loop {
+            match reader.read_line() {
+                Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => match tag {
+                    hls::Tag::ServerControl(tag) => {
+                        can_block_reload = tag.can_block_reload();
+                        skip = tag
+                            .can_skip_until()
+                            .filter(|skip_boundary| playlist_age <= skip_boundary / 2.0)
+                            .map(|_| match tag.can_skip_dateranges() {
+                                true => SkipDirective::V2,
+                                false => SkipDirective::Yes,
+                            });
+                    }
+                    hls::Tag::MediaSequence(tag) => {
+                        next_msn = next_msn.and_then(|msn| msn.checked_add(tag.media_sequence()))
+                    }
+                    hls::Tag::Skip(tag) => {
+                        next_msn = next_msn.and_then(|msn| msn.checked_add(tag.skipped_segments()))
+                    }
+                    _ => (),
+                },
+                Ok(Some(HlsLine::UnknownTag(tag))) => match TagName::try_from(tag.name()) {
+                    Ok(TagName::PartInf) => has_part_inf = true,
+                    Ok(TagName::Part) => next_part += 1,
+                    Ok(TagName::Endlist) => return Ok(Self::default()),
+                    _ => (),
+                },
+                Ok(Some(HlsLine::Uri(_))) => {
+                    next_msn = next_msn.and_then(|msn| msn.checked_add(1));
+                    next_part = 0;
+                }
+                Ok(Some(_)) | Err(_) => (),
+                Ok(None) => break,
+            }
+        }
+        let msn = match (can_block_reload, next_msn) {
+            (false, _) => None,
+            (true, Some(msn)) => Some(msn),
+            (true, None) => return Err(DeliveryDirectiveError::MsnOverflow),
+        };
+        Ok(Self {
+            msn,
+            part: (can_block_reload && has_part_inf).then_some(next_part),
+            skip,
+            reports: Vec::new(),
+        })
+    }
+
+    /// Indicates whether the directives request a blocking reload (i.e. `_HLS_msn` is present).
+    pub fn is_blocking(&self) -> bool {
+        self.msn.is_some()
+    }
+
+    /// Indicates whether there are no directives.
+    pub fn is_empty(&self) -> bool {
+        self.msn.is_none() && self.part.is_none() && self.skip.is_none() && self.reports.is_empty()
+    }
+
+    /// Adds the directives to the query of the URI (any fragment is kept at the end).
+    pub fn apply_to(&self, uri: &str) -> String {
+        if self.is_empty() {
+            return uri.to_string();
+        }
+        let (uri_without_fragment, fragment) = match uri.split_once('#') {
+            Some((uri, fragment)) => (uri, Some(fragment)),
+            None => (uri, None),
+        };
+        let separator = match uri_without_fragment.split_once('?') {
+            Some((_, "")) => "",
+            Some(_) => "&",
+            None => "?",
+        };
+        let mut applied = format!("{uri_without_fragment}{separator}{self}");
+        if let Some(fragment) = fragment {
+            applied.push('#');
+            applied.push_str(fragment);
+        }
+        applied
+    }
+}
+
+/// Provides the directives as a query string (without the leading `?`).
+impl Display for DeliveryDirectives<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut separator = "";
+        let mut write = |f: &mut Formatter<'_>, name: &str, value: &dyn Display| {
+            let result = write!(f, "{separator}{name}={value}");
+            separator = "&";
+            result
+        };
+        if let Some(msn) = self.msn {
+            write(f, MSN, &msn)?;
+        }
+        if let Some(part) = self.part {
+            write(f, PART, &part)?;
+        }
+        for report in &self.reports {
+            write(f, REPORT, report)?;
+        }
+        if let Some(skip) = self.skip {
+            write(f, SKIP, &skip.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_number(directive: &'static str, value: &str) -> Result<u64, DeliveryDirectiveError> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(DeliveryDirectiveError::InvalidValue(directive));
+    }
+    value
+        .parse()
+        .map_err(|_| DeliveryDirectiveError::InvalidValue(directive))
+}
+
+fn set_once<T>(
+    directive: &mut Option<T>,
+    name: &'static str,
+    value: T,
+) -> Result<(), DeliveryDirectiveError> {
+    match directive {
+        Some(_) => Err(DeliveryDirectiveError::RepeatedDirective(name)),
+        None => {
+            *directive = Some(value);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn invalid_directives_should_error() {
+        assert_eq!(
+            Err(DeliveryDirectiveError::PartWithoutMsn),
+            DeliveryDirectives::from_uri("a.m3u8?_HLS_part=1")
+        );
+        assert_eq!(
+            Err(DeliveryDirectiveError::InvalidValue("_HLS_msn")),
+            DeliveryDirectives::from_uri("a.m3u8?_HLS_msn=-1")
+        );
+        assert_eq!(
+            Err(DeliveryDirectiveError::InvalidValue("_HLS_skip")),
+            DeliveryDirectives::from_uri("a.m3u8?_HLS_skip=NO")
+        );
+        assert_eq!(
+            Err(DeliveryDirectiveError::InvalidValue("_HLS_report")),
+            DeliveryDirectives::from_uri("a.m3u8?_HLS_report=")
+        );
+        assert_eq!(
+            Err(DeliveryDirectiveError::RepeatedDirective("_HLS_msn")),
+            DeliveryDirectives::from_uri("a.m3u8?_HLS_msn=1&_HLS_msn=2")
+        );
+    }
+
+    #[test]
+    fn reports_should_be_repeatable_and_round_trip() {
+        let uri = "a.m3u8?_HLS_msn=5&_HLS_report=b.m3u8&_HLS_report=c.m3u8&_HLS_skip=v2#t=1";
+        let directives = DeliveryDirectives::from_uri(uri).unwrap();
+        assert_eq!(
+            DeliveryDirectives {
+                msn: Some(5),
+                part: None,
+                skip: Some(SkipDirective::V2),
+                reports: vec![Cow::Borrowed("b.m3u8"), Cow::Borrowed("c.m3u8")],
+            },
+            directives
+        );
+        assert_eq!(uri, directives.apply_to("a.m3u8#t=1"));
+        assert_eq!(
+            "a.m3u8?x=1&_HLS_msn=5&_HLS_report=b.m3u8&_HLS_report=c.m3u8&_HLS_skip=v2",
+            directives.apply_to("a.m3u8?x=1")
+        );
+    }
+
+    #[test]
+    fn no_directives_should_leave_uri_unchanged() {
+        let directives = DeliveryDirectives::from_uri("a.m3u8?token=1").unwrap();
+        assert!(directives.is_empty());
+        assert!(!directives.is_blocking());
+        assert_eq!("a.m3u8", directives.apply_to("a.m3u8"));
+        assert_eq!("", directives.to_string());
+    }
+
+    #[test]
+    fn next_reload_should_follow_last_segment_and_part() {
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:4\n\
+            #EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,CAN-SKIP-UNTIL=24,CAN-SKIP-DATERANGES=YES\n\
+            #EXT-X-MEDIA-SEQUENCE:10\n\
+            #EXT-X-SKIP:SKIPPED-SEGMENTS=3\n\
+            #EXTINF:4\n\
+            13.ts\n";
+        assert_eq!(
+            DeliveryDirectives {
+                msn: Some(14),
+                part: None,
+                skip: Some(SkipDirective::V2),
+                reports: Vec::new(),
+            },
+            DeliveryDirectives::for_next_reload(playlist, 0.0).unwrap()
+        );
+
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:4\n\
+            #EXT-X-PART-INF:PART-TARGET=1\n\
+            #EXTINF:4\n\
+            0.ts\n";
+        assert!(
+            DeliveryDirectives::for_next_reload(playlist, 0.0)
+                .unwrap()
+                .is_empty()
+        );
+
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:4\n\
+            #EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES\n\
+            #EXT-X-PART-INF:PART-TARGET=1\n\
+            #EXT-X-PART:DURATION=1,URI=\"0.0.ts\"\n\
+            #EXTINF:1\n\
+            0.ts\n";
+        let directives = DeliveryDirectives::for_next_reload(playlist, 0.0).unwrap();
+        assert_eq!((Some(1), Some(0)), (directives.msn, directives.part));
+
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:4\n\
+            #EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES\n\
+            #EXTINF:4\n\
+            0.ts\n\
+            #EXT-X-ENDLIST\n";
+        assert!(
+            DeliveryDirectives::for_next_reload(playlist, 0.0)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn next_reload_beyond_largest_msn_should_error() {
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:4\n\
+            #EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES\n\
+            #EXT-X-MEDIA-SEQUENCE:18446744073709551615\n\
+            #EXT-X-SKIP:SKIPPED-SEGMENTS=18446744073709551615\n\
+            #EXTINF:4\n\
+            0.ts\n";
+        assert_eq!(
+            Err(DeliveryDirectiveError::MsnOverflow),
+            DeliveryDirectives::for_next_reload(playlist, 0.0)
+        );
+
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:4\n\
+            #EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES\n\
+            #EXT-X-MEDIA-SEQUENCE:18446744073709551614\n\
+            #EXTINF:4\n\
+            0.ts\n\
+            #EXTINF:4\n\
+            1.ts\n";
+        assert_eq!(
+            Err(DeliveryDirectiveError::MsnOverflow),
+            DeliveryDirectives::for_next_reload(playlist, 0.0)
+        );
+
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:4\n\
+            #EXT-X-MEDIA-SEQUENCE:18446744073709551615\n\
+            #EXTINF:4\n\
+            0.ts\n";
+        assert_eq!(
+            Ok(DeliveryDirectives::default()),
+            DeliveryDirectives::for_next_reload(playlist, 0.0)
+        );
+    }
+}
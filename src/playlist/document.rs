@@ -0,0 +1,816 @@
+use crate::{
+    HlsLine, Reader,
+    error::{ReaderBytesError, ReaderStrError},
+    playlist::{AudioCapabilities, compatible_audio_renditions},
+    tag::{
+        CustomTag, KnownTag, NoCustomTag,
+        hls::{
+            self, Bitrate, Byterange, ContentSteering, Daterange, Define, DiscontinuitySequence,
+            IFrameStreamInf, Inf, Key, Map, Media, MediaSequence, MediaType, Part, PartInf,
+            PlaylistType, PreloadHint, ProgramDateTime, RenditionReport, ServerControl,
+            SessionData, SessionKey, Skip, Start, StreamInf, TagType, Targetduration, Version,
+        },
+    },
+};
+use std::borrow::Cow;
+
+/// A complete playlist, assembled from the lines of a [`Reader`].
+///
+/// The [`Reader`] remains the core of the library, but some tooling (such as validators, or
+/// inspectors that present a playlist in a UI) is simpler to write against a model of the whole
+/// playlist. This type groups the tags of a Media Playlist under each [`MediaSegment`], and the
+/// tags of a Multivariant Playlist under each [`VariantStream`], so that each consumer does not
+/// need to write the same aggregation loop.
+///
+/// The playlist is considered to be a Multivariant Playlist when it contains any Multivariant
+/// Playlist tag (see [`TagType::MultivariantPlaylist`]), and a Media Playlist otherwise.
+///
+/// Only tags that the reader parsed as known tags are assembled into the model, and so the reader
+/// would usually be constructed with [`crate::config::ParsingOptions::default`] (which parses all
+/// tags). Any other lines (unknown tags, custom tags, and comments) are retained in the `other`
+/// lines of the model, and blank lines are dropped.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{Reader, config::ParsingOptions, playlist::Playlist};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MEDIA-SEQUENCE:10
+/// #EXT-X-KEY:METHOD=AES-128,URI="key"
+/// #EXTINF:4
+/// 10.ts
+/// #EXT-X-DISCONTINUITY
+/// #EXTINF:3.5
+/// #EXT-X-BYTERANGE:800@0
+/// 11.ts
+/// #EXT-X-ENDLIST
+/// "#;
+///
+/// let reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+/// let Playlist::Media(playlist) = Playlist::try_from(reader)? else {
+///     panic!("should be a media playlist");
+/// };
+/// assert_eq!(Some(4), playlist.target_duration.as_ref().map(|t| t.target_duration()));
+/// assert_eq!(10, playlist.media_sequence());
+/// assert!(playlist.ended);
+/// assert_eq!(7.5, playlist.duration());
+///
+/// let segment = &playlist.segments[0];
+/// assert_eq!("10.ts", segment.uri);
+/// assert_eq!(1, segment.keys.len());
+///
+/// let segment = &playlist.segments[1];
+/// assert_eq!("11.ts", segment.uri);
+/// assert!(segment.discontinuity);
+/// assert_eq!(Some(3.5), segment.inf.as_ref().map(|inf| inf.duration()));
+/// assert_eq!(Some(800), segment.byterange.as_ref().map(|b| b.length()));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+// A playlist is assembled once per document (and not in a hot loop), and so the difference in size
+// between the variants is not a concern.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum Playlist<'a, Custom = NoCustomTag>
+where
+    Custom: CustomTag<'a>,
+{
+    /// A Media Playlist.
+    Media(MediaPlaylist<'a, Custom>),
+    /// A Multivariant Playlist.
+    Multivariant(MultivariantPlaylist<'a, Custom>),
+}
+
+impl<'a, Custom> Playlist<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    /// Assembles the playlist from lines that have already been read.
+    pub fn from_lines(lines: impl IntoIterator<Item = HlsLine<'a, Custom>>) -> Self {
+        let lines = lines.into_iter().collect::<Vec<_>>();
+        let is_multivariant = lines.iter().any(|line| {
+            matches!(
+                line,
+                HlsLine::KnownTag(KnownTag::Hls(tag))
+                    if tag.name().tag_type() == TagType::MultivariantPlaylist
+            )
+        });
+        if is_multivariant {
+            Self::Multivariant(MultivariantPlaylist::from_lines(lines))
+        } else {
+            Self::Media(MediaPlaylist::from_lines(lines))
+        }
+    }
+}
+
+impl<'a, Custom> TryFrom<Reader<&'a str, Custom>> for Playlist<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    type Error = ReaderStrError<'a>;
+
+    /// Reads all lines of the reader and assembles the playlist. The first error that the reader
+    /// experiences is returned.
+    fn try_from(mut reader: Reader<&'a str, Custom>) -> Result<Self, Self::Error> {
+        match reader.read_until_error() {
+            (lines, None) => Ok(Self::from_lines(lines)),
+            (_, Some(error)) => Err(error),
+        }
+    }
+}
+
+impl<'a, Custom> TryFrom<Reader<&'a [u8], Custom>> for Playlist<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    type Error = ReaderBytesError<'a>;
+
+    /// Reads all lines of the reader and assembles the playlist. The first error that the reader
+    /// experiences is returned.
+    fn try_from(mut reader: Reader<&'a [u8], Custom>) -> Result<Self, Self::Error> {
+        match reader.read_until_error() {
+            (lines, None) => Ok(Self::from_lines(lines)),
+            (_, Some(error)) => Err(error),
+        }
+    }
+}
+
+/// A Media Playlist, as assembled by [`Playlist`].
+///
+/// Tags that apply to the whole playlist are provided regardless of where they appear in the
+/// playlist. Media Segment tags are grouped under the [`MediaSegment`] that they precede.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MediaPlaylist<'a, Custom = NoCustomTag>
+where
+    Custom: CustomTag<'a>,
+{
+    /// The `EXT-X-VERSION` tag.
+    pub version: Option<Version<'a>>,
+    /// Whether the playlist has the `EXT-X-INDEPENDENT-SEGMENTS` tag.
+    pub independent_segments: bool,
+    /// The `EXT-X-START` tag.
+    pub start: Option<Start<'a>>,
+    /// The `EXT-X-DEFINE` tags.
+    pub defines: Vec<Define<'a>>,
+    /// The `EXT-X-TARGETDURATION` tag.
+    pub target_duration: Option<Targetduration<'a>>,
+    /// The `EXT-X-MEDIA-SEQUENCE` tag.
+    pub media_sequence: Option<MediaSequence<'a>>,
+    /// The `EXT-X-DISCONTINUITY-SEQUENCE` tag.
+    pub discontinuity_sequence: Option<DiscontinuitySequence<'a>>,
+    /// Whether the playlist has the `EXT-X-ENDLIST` tag.
+    pub ended: bool,
+    /// The `EXT-X-PLAYLIST-TYPE` tag.
+    pub playlist_type: Option<PlaylistType>,
+    /// Whether the playlist has the `EXT-X-I-FRAMES-ONLY` tag.
+    pub i_frames_only: bool,
+    /// The `EXT-X-PART-INF` tag.
+    pub part_inf: Option<PartInf<'a>>,
+    /// The `EXT-X-SERVER-CONTROL` tag.
+    pub server_control: Option<ServerControl<'a>>,
+    /// The Media Segments of the playlist (in playlist order). See [`Self::edit_segments`] for
+    /// inserting and removing segments while visiting them.
+    pub segments: Vec<MediaSegment<'a, Custom>>,
+    /// The `EXT-X-PART` tags that follow the last Media Segment (the Partial Segments of the
+    /// segment that is still being produced in a Low-Latency HLS playlist).
+    pub trailing_parts: Vec<Part<'a>>,
+    /// The `EXT-X-DATERANGE` tags.
+    pub dateranges: Vec<Daterange<'a>>,
+    /// The `EXT-X-SKIP` tag.
+    pub skip: Option<Skip<'a>>,
+    /// The `EXT-X-PRELOAD-HINT` tags.
+    pub preload_hints: Vec<PreloadHint<'a>>,
+    /// The `EXT-X-RENDITION-REPORT` tags.
+    pub rendition_reports: Vec<RenditionReport<'a>>,
+    /// Other lines (unknown tags, custom tags, and comments) that follow the last Media Segment,
+    /// along with any Media Segment tags that were not followed by a URI.
+    pub other: Vec<HlsLine<'a, Custom>>,
+}
+
+impl<'a, Custom> Default for MediaPlaylist<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    fn default() -> Self {
+        Self {
+            version: None,
+            independent_segments: false,
+            start: None,
+            defines: Vec::new(),
+            target_duration: None,
+            media_sequence: None,
+            discontinuity_sequence: None,
+            ended: false,
+            playlist_type: None,
+            i_frames_only: false,
+            part_inf: None,
+            server_control: None,
+            segments: Vec::new(),
+            trailing_parts: Vec::new(),
+            dateranges: Vec::new(),
+            skip: None,
+            preload_hints: Vec::new(),
+            rendition_reports: Vec::new(),
+            other: Vec::new(),
+        }
+    }
+}
+
+impl<'a, Custom> MediaPlaylist<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    /// Assembles the Media Playlist from lines that have already been read.
+    pub fn from_lines(lines: impl IntoIterator<Item = HlsLine<'a, Custom>>) -> Self {
+        let mut playlist = Self::default();
+        let mut next = MediaSegment::new(Cow::Borrowed(""));
+        let mut pending = Vec::new();
+        for line in lines {
+            match line {
+                HlsLine::KnownTag(KnownTag::Hls(tag)) => match tag {
+                    hls::Tag::M3u(_) => (),
+                    hls::Tag::Version(tag) => playlist.version = Some(tag),
+                    hls::Tag::IndependentSegments(_) => playlist.independent_segments = true,
+                    hls::Tag::Start(tag) => playlist.start = Some(tag),
+                    hls::Tag::Define(tag) => playlist.defines.push(tag),
+                    hls::Tag::Targetduration(tag) => playlist.target_duration = Some(tag),
+                    hls::Tag::MediaSequence(tag) => playlist.media_sequence = Some(tag),
+                    hls::Tag::DiscontinuitySequence(tag) => {
+                        playlist.discontinuity_sequence = Some(tag)
+                    }
+                    hls::Tag::Endlist(_) => playlist.ended = true,
+                    hls::Tag::PlaylistType(tag) => playlist.playlist_type = Some(tag),
+                    hls::Tag::IFramesOnly(_) => playlist.i_frames_only = true,
+                    hls::Tag::PartInf(tag) => playlist.part_inf = Some(tag),
+                    hls::Tag::ServerControl(tag) => playlist.server_control = Some(tag),
+                    hls::Tag::Inf(tag) => next.inf = Some(tag),
+                    hls::Tag::Byterange(tag) => next.byterange = Some(tag),
+                    hls::Tag::Discontinuity(_) => next.discontinuity = true,
+                    hls::Tag::Key(tag) => next.keys.push(tag),
+                    hls::Tag::Map(tag) => next.map = Some(tag),
+                    hls::Tag::ProgramDateTime(tag) => next.program_date_time = Some(tag),
+                    hls::Tag::Gap(_) => next.gap = true,
+                    hls::Tag::Bitrate(tag) => next.bitrate = Some(tag),
+                    hls::Tag::Part(tag) => next.parts.push(tag),
+                    hls::Tag::Daterange(tag) => playlist.dateranges.push(tag),
+                    hls::Tag::Skip(tag) => playlist.skip = Some(tag),
+                    hls::Tag::PreloadHint(tag) => playlist.preload_hints.push(tag),
+                    hls::Tag::RenditionReport(tag) => playlist.rendition_reports.push(tag),
+                    // Multivariant Playlist tags are not expected in a Media Playlist.
+                    tag => pending.push(HlsLine::from(tag)),
+                },
+                HlsLine::Uri(uri) => {
+                    let mut segment =
+                        std::mem::replace(&mut next, MediaSegment::new(Cow::Borrowed("")));
+                    segment.uri = uri;
+                    segment.other = std::mem::take(&mut pending);
+                    playlist.segments.push(segment);
+                }
+                HlsLine::Blank | HlsLine::Whitespace(_) => (),
+                line => pending.push(line),
+            }
+        }
+        // Media Segment tags that were not followed by a URI.
+        let MediaSegment {
+            uri: _,
+            inf,
+            byterange,
+            discontinuity,
+            keys,
+            map,
+            program_date_time,
+            gap,
+            bitrate,
+            parts,
+            other: _,
+        } = next;
+        playlist.trailing_parts = parts;
+        let other = &mut playlist.other;
+        other.extend(inf.map(HlsLine::from));
+        other.extend(byterange.map(HlsLine::from));
+        if discontinuity {
+            other.push(HlsLine::from(hls::Discontinuity));
+        }
+        other.extend(keys.into_iter().map(HlsLine::from));
+        other.extend(map.map(HlsLine::from));
+        other.extend(program_date_time.map(HlsLine::from));
+        if gap {
+            other.push(HlsLine::from(hls::Gap));
+        }
+        other.extend(bitrate.map(HlsLine::from));
+        other.append(&mut pending);
+        playlist
+    }
+
+    /// The media sequence number of the first segment (`0` when there is no
+    /// `EXT-X-MEDIA-SEQUENCE`).
+    pub fn media_sequence(&self) -> u64 {
+        self.media_sequence
+            .as_ref()
+            .map(|tag| tag.media_sequence())
+            .unwrap_or_default()
+    }
+
+    /// The sum of the durations of the Media Segments.
+    pub fn duration(&self) -> f64 {
+        self.segments
+            .iter()
+            .filter_map(|segment| segment.inf.as_ref())
+            .map(|inf| inf.duration())
+            .sum()
+    }
+}
+
+/// A Media Segment, along with the Media Segment tags that precede its URI.
+///
+/// The tags are as they appear in the playlist. Note that some tags (such as `EXT-X-KEY` and
+/// `EXT-X-MAP`) also apply to the segments that follow, until they are replaced; see
+/// [`crate::playlist::segment_list`] for a model where this state is resolved onto each segment.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MediaSegment<'a, Custom = NoCustomTag>
+where
+    Custom: CustomTag<'a>,
+{
+    /// The URI of the segment.
+    pub uri: Cow<'a, str>,
+    /// The `EXTINF` tag.
+    pub inf: Option<Inf<'a>>,
+    /// The `EXT-X-BYTERANGE` tag.
+    pub byterange: Option<Byterange<'a>>,
+    /// Whether the segment has the `EXT-X-DISCONTINUITY` tag.
+    pub discontinuity: bool,
+    /// The `EXT-X-KEY` tags.
+    pub keys: Vec<Key<'a>>,
+    /// The `EXT-X-MAP` tag.
+    pub map: Option<Map<'a>>,
+    /// The `EXT-X-PROGRAM-DATE-TIME` tag.
+    pub program_date_time: Option<ProgramDateTime<'a>>,
+    /// Whether the segment has the `EXT-X-GAP` tag.
+    pub gap: bool,
+    /// The `EXT-X-BITRATE` tag.
+    pub bitrate: Option<Bitrate<'a>>,
+    /// The `EXT-X-PART` tags (the Partial Segments of this segment).
+    pub parts: Vec<Part<'a>>,
+    /// Other lines (unknown tags, custom tags, and comments) found between the previous segment
+    /// and this one.
+    pub other: Vec<HlsLine<'a, Custom>>,
+}
+
+impl<'a, Custom> MediaSegment<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    fn new(uri: Cow<'a, str>) -> Self {
+        Self {
+            uri,
+            inf: None,
+            byterange: None,
+            discontinuity: false,
+            keys: Vec::new(),
+            map: None,
+            program_date_time: None,
+            gap: false,
+            bitrate: None,
+            parts: Vec::new(),
+            other: Vec::new(),
+        }
+    }
+}
+
+/// A Multivariant Playlist, as assembled by [`Playlist`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultivariantPlaylist<'a, Custom = NoCustomTag>
+where
+    Custom: CustomTag<'a>,
+{
+    /// The `EXT-X-VERSION` tag.
+    pub version: Option<Version<'a>>,
+    /// Whether the playlist has the `EXT-X-INDEPENDENT-SEGMENTS` tag.
+    pub independent_segments: bool,
+    /// The `EXT-X-START` tag.
+    pub start: Option<Start<'a>>,
+    /// The `EXT-X-DEFINE` tags.
+    pub defines: Vec<Define<'a>>,
+    /// The `EXT-X-MEDIA` tags.
+    pub renditions: Vec<Media<'a>>,
+    /// The Variant Streams (`EXT-X-STREAM-INF` along with the URI that follows it).
+    pub variants: Vec<VariantStream<'a>>,
+    /// The `EXT-X-I-FRAME-STREAM-INF` tags.
+    pub i_frame_variants: Vec<IFrameStreamInf<'a>>,
+    /// The `EXT-X-SESSION-DATA` tags.
+    pub session_data: Vec<SessionData<'a>>,
+    /// The `EXT-X-SESSION-KEY` tags.
+    pub session_keys: Vec<SessionKey<'a>>,
+    /// The `EXT-X-CONTENT-STEERING` tag.
+    pub content_steering: Option<ContentSteering<'a>>,
+    /// Other lines (unknown tags, custom tags, comments, tags that are not expected in a
+    /// Multivariant Playlist, and URIs that do not follow an `EXT-X-STREAM-INF`).
+    pub other: Vec<HlsLine<'a, Custom>>,
+}
+
+impl<'a, Custom> Default for MultivariantPlaylist<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    fn default() -> Self {
+        Self {
+            version: None,
+            independent_segments: false,
+            start: None,
+            defines: Vec::new(),
+            renditions: Vec::new(),
+            variants: Vec::new(),
+            i_frame_variants: Vec::new(),
+            session_data: Vec::new(),
+            session_keys: Vec::new(),
+            content_steering: None,
+            other: Vec::new(),
+        }
+    }
+}
+
+impl<'a, Custom> MultivariantPlaylist<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    /// Assembles the Multivariant Playlist from lines that have already been read.
+    pub fn from_lines(lines: impl IntoIterator<Item = HlsLine<'a, Custom>>) -> Self {
+        let mut playlist = Self::default();
+        let mut stream_inf = None;
+        for line in lines {
+            match line {
+                HlsLine::KnownTag(KnownTag::Hls(tag)) => match tag {
+                    hls::Tag::M3u(_) => (),
+                    hls::Tag::Version(tag) => playlist.version = Some(tag),
+                    hls::Tag::IndependentSegments(_) => playlist.independent_segments = true,
+                    hls::Tag::Start(tag) => playlist.start = Some(tag),
+                    hls::Tag::Define(tag) => playlist.defines.push(tag),
+                    hls::Tag::Media(tag) => playlist.renditions.push(tag),
+                    hls::Tag::StreamInf(tag) => {
+                        if let Some(previous) = stream_inf.replace(tag) {
+                            playlist.other.push(HlsLine::from(previous));
+                        }
+                    }
+                    hls::Tag::IFrameStreamInf(tag) => playlist.i_frame_variants.push(tag),
+                    hls::Tag::SessionData(tag) => playlist.session_data.push(tag),
+                    hls::Tag::SessionKey(tag) => playlist.session_keys.push(tag),
+                    hls::Tag::ContentSteering(tag) => playlist.content_steering = Some(tag),
+                    tag => playlist.other.push(HlsLine::from(tag)),
+                },
+                HlsLine::Uri(uri) => match stream_inf.take() {
+                    Some(stream_inf) => playlist.variants.push(VariantStream { stream_inf, uri }),
+                    None => playlist.other.push(HlsLine::Uri(uri)),
+                },
+                HlsLine::Blank | HlsLine::Whitespace(_) => (),
+                line => playlist.other.push(line),
+            }
+        }
+        playlist.other.extend(stream_inf.map(HlsLine::from));
+        playlist
+    }
+
+    /// The renditions (`EXT-X-MEDIA`) of the group with the type and `GROUP-ID`, or `None` when
+    /// there is no such rendition.
+    pub fn rendition_group<'p>(
+        &'p self,
+        media_type: MediaType,
+        group_id: &'p str,
+    ) -> Option<RenditionGroup<'p, 'a>> {
+        let renditions = self
+            .renditions
+            .iter()
+            .filter(|media| {
+                media.media_type().known() == Some(&media_type) && media.group_id() == group_id
+            })
+            .collect::<Vec<_>>();
+        if renditions.is_empty() {
+            None
+        } else {
+            Some(RenditionGroup {
+                media_type,
+                group_id,
+                renditions,
+            })
+        }
+    }
+
+    /// All of the groups of renditions, in the order that each group first appears in the
+    /// playlist. Renditions with an unknown `TYPE` are not included.
+    pub fn rendition_groups(&self) -> Vec<RenditionGroup<'_, 'a>> {
+        let mut groups: Vec<RenditionGroup<'_, 'a>> = Vec::new();
+        for media in &self.renditions {
+            let Some(media_type) = media.media_type().known().copied() else {
+                continue;
+            };
+            let group_id = media.group_id();
+            match groups
+                .iter_mut()
+                .find(|group| group.media_type == media_type && group.group_id == group_id)
+            {
+                Some(group) => group.renditions.push(media),
+                None => groups.push(RenditionGroup {
+                    media_type,
+                    group_id,
+                    renditions: vec![media],
+                }),
+            }
+        }
+        groups
+    }
+
+    /// Resolves the `AUDIO`, `VIDEO`, `SUBTITLES`, and `CLOSED-CAPTIONS` attributes of the Variant
+    /// Stream to the groups of renditions that they reference.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{Reader, config::ParsingOptions};
+    /// # use quick_m3u8::playlist::Playlist;
+    /// const PLAYLIST: &str = r#"#EXTM3U
+    /// #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",NAME="English",DEFAULT=YES,URI="en.m3u8"
+    /// #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",NAME="French",URI="fr.m3u8"
+    /// #EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID="subs",NAME="English",URI="subs/en.m3u8"
+    /// #EXT-X-STREAM-INF:BANDWIDTH=1280000,AUDIO="aac",SUBTITLES="subs",CLOSED-CAPTIONS=NONE
+    /// low.m3u8
+    /// "#;
+    ///
+    /// let reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+    /// let Playlist::Multivariant(playlist) = Playlist::try_from(reader)? else {
+    ///     panic!("should be a multivariant playlist");
+    /// };
+    /// let renditions = playlist.variant_renditions(&playlist.variants[0]);
+    /// let audio = renditions.audio.expect("should reference audio group");
+    /// assert_eq!(
+    ///     vec!["English", "French"],
+    ///     audio.renditions.iter().map(|m| m.name()).collect::<Vec<_>>()
+    /// );
+    /// assert_eq!(Some("en.m3u8"), audio.default_rendition().and_then(|m| m.uri()));
+    /// assert_eq!(1, renditions.subtitles.map_or(0, |s| s.renditions.len()));
+    /// assert!(renditions.video.is_none());
+    /// assert!(renditions.closed_captions.is_none());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// A group is `None` when the attribute is absent (or `NONE`), and also when the attribute
+    /// references a group that is not in the playlist (which can be found via
+    /// [`Self::unresolved_group_references`]).
+    pub fn variant_renditions<'p>(
+        &'p self,
+        variant: &'p VariantStream<'a>,
+    ) -> VariantRenditions<'p, 'a> {
+        let stream_inf = &variant.stream_inf;
+        let group = |media_type, group_id: Option<&'p str>| {
+            group_id.and_then(|group_id| self.rendition_group(media_type, group_id))
+        };
+        VariantRenditions {
+            audio: group(MediaType::Audio, stream_inf.audio()),
+            video: group(MediaType::Video, stream_inf.video()),
+            subtitles: group(MediaType::Subtitles, stream_inf.subtitles()),
+            closed_captions: group(MediaType::ClosedCaptions, stream_inf.closed_captions()),
+        }
+    }
+
+    /// Resolves the `VIDEO` attribute of the `EXT-X-I-FRAME-STREAM-INF` to the group of video
+    /// renditions that it references.
+    pub fn i_frame_video_renditions<'p>(
+        &'p self,
+        i_frame_variant: &'p IFrameStreamInf<'a>,
+    ) -> Option<RenditionGroup<'p, 'a>> {
+        i_frame_variant
+            .video()
+            .and_then(|group_id| self.rendition_group(MediaType::Video, group_id))
+    }
+
+    /// The group references of the Variant Streams (and I-frame Variant Streams) that do not match
+    /// any rendition in the playlist, each along with the type of the group, in the order that they
+    /// appear.
+    pub fn unresolved_group_references(&self) -> Vec<(MediaType, &str)> {
+        let variant_references = self.variants.iter().flat_map(|variant| {
+            let stream_inf = &variant.stream_inf;
+            [
+                (MediaType::Audio, stream_inf.audio()),
+                (MediaType::Video, stream_inf.video()),
+                (MediaType::Subtitles, stream_inf.subtitles()),
+                (MediaType::ClosedCaptions, stream_inf.closed_captions()),
+            ]
+        });
+        let i_frame_references = self
+            .i_frame_variants
+            .iter()
+            .map(|i_frame_variant| (MediaType::Video, i_frame_variant.video()));
+        variant_references
+            .chain(i_frame_references)
+            .filter_map(|(media_type, group_id)| group_id.map(|group_id| (media_type, group_id)))
+            .filter(|(media_type, group_id)| self.rendition_group(*media_type, group_id).is_none())
+            .collect()
+    }
+}
+
+/// A Variant Stream of a Multivariant Playlist.
+#[derive(Debug, PartialEq, Clone)]
+pub struct VariantStream<'a> {
+    /// The `EXT-X-STREAM-INF` tag that describes the Variant Stream.
+    pub stream_inf: StreamInf<'a>,
+    /// The URI of the Media Playlist of the Variant Stream.
+    pub uri: Cow<'a, str>,
+}
+
+/// The renditions (`EXT-X-MEDIA`) of a Multivariant Playlist that share a `TYPE` and `GROUP-ID`
+/// (see [`MultivariantPlaylist::rendition_group`]).
+#[derive(Debug, PartialEq, Clone)]
+pub struct RenditionGroup<'p, 'a> {
+    /// The `TYPE` of the renditions.
+    pub media_type: MediaType,
+    /// The `GROUP-ID` of the renditions.
+    pub group_id: &'p str,
+    /// The renditions of the group, in the order that they appear in the playlist.
+    pub renditions: Vec<&'p Media<'a>>,
+}
+
+impl<'p, 'a> RenditionGroup<'p, 'a> {
+    /// The first rendition of the group that has `DEFAULT=YES`.
+    pub fn default_rendition(&self) -> Option<&'p Media<'a>> {
+        self.renditions
+            .iter()
+            .copied()
+            .find(|media| media.default())
+    }
+
+    /// The renditions of the group that are compatible with the audio capabilities of the device,
+    /// in order of preference (see [`super::compatible_audio_renditions`]).
+    pub fn compatible_audio_renditions(
+        &self,
+        capabilities: &AudioCapabilities,
+    ) -> Vec<&'p Media<'a>> {
+        compatible_audio_renditions(self.renditions.iter().copied(), capabilities)
+    }
+}
+
+/// The groups of renditions referenced by a Variant Stream (see
+/// [`MultivariantPlaylist::variant_renditions`]).
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct VariantRenditions<'p, 'a> {
+    /// The group referenced by the `AUDIO` attribute.
+    pub audio: Option<RenditionGroup<'p, 'a>>,
+    /// The group referenced by the `VIDEO` attribute.
+    pub video: Option<RenditionGroup<'p, 'a>>,
+    /// The group referenced by the `SUBTITLES` attribute.
+    pub subtitles: Option<RenditionGroup<'p, 'a>>,
+    /// The group referenced by the `CLOSED-CAPTIONS` attribute.
+    pub closed_captions: Option<RenditionGroup<'p, 'a>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ParsingOptions;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn multivariant_playlist_should_group_variant_streams() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-INDEPENDENT-SEGMENTS\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",URI=\"en.m3u8\"\n",
+            "# low\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000000,AUDIO=\"aac\"\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=2000000,AUDIO=\"aac\"\n",
+            "high.m3u8\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100000,URI=\"iframe.m3u8\"\n",
+        );
+        let reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+        let Ok(Playlist::Multivariant(playlist)) = Playlist::try_from(reader) else {
+            panic!("should be a multivariant playlist");
+        };
+        assert!(playlist.independent_segments);
+        assert_eq!(1, playlist.renditions.len());
+        assert_eq!(
+            vec![("low.m3u8", 1000000), ("high.m3u8", 2000000)],
+            playlist
+                .variants
+                .iter()
+                .map(|v| (v.uri.as_ref(), v.stream_inf.bandwidth()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, playlist.i_frame_variants.len());
+        assert_eq!(
+            vec![HlsLine::Comment(Cow::Borrowed(" low"))],
+            playlist.other
+        );
+    }
+
+    #[test]
+    fn multivariant_playlist_should_resolve_group_references() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"a\",NAME=\"English\",URI=\"en.m3u8\"\n",
+            "#EXT-X-MEDIA:TYPE=VIDEO,GROUP-ID=\"a\",NAME=\"Angle 1\",URI=\"v1.m3u8\"\n",
+            "#EXT-X-MEDIA:TYPE=CLOSED-CAPTIONS,GROUP-ID=\"cc\",NAME=\"English\",INSTREAM-ID=\"CC1\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"a\",NAME=\"French\",URI=\"fr.m3u8\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000000,AUDIO=\"a\",CLOSED-CAPTIONS=\"cc\"\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=2000000,AUDIO=\"missing\",VIDEO=\"a\"\n",
+            "high.m3u8\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100000,VIDEO=\"a\",URI=\"iframe.m3u8\"\n",
+        );
+        let reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+        let Ok(Playlist::Multivariant(playlist)) = Playlist::try_from(reader) else {
+            panic!("should be a multivariant playlist");
+        };
+        assert_eq!(
+            vec![
+                (MediaType::Audio, "a", 2),
+                (MediaType::Video, "a", 1),
+                (MediaType::ClosedCaptions, "cc", 1)
+            ],
+            playlist
+                .rendition_groups()
+                .iter()
+                .map(|g| (g.media_type, g.group_id, g.renditions.len()))
+                .collect::<Vec<_>>()
+        );
+
+        let low = playlist.variant_renditions(&playlist.variants[0]);
+        let audio = low.audio.unwrap();
+        assert_eq!(
+            vec!["English", "French"],
+            audio
+                .renditions
+                .iter()
+                .map(|m| m.name())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(None, audio.default_rendition());
+        assert_eq!("cc", low.closed_captions.unwrap().group_id);
+        assert_eq!(None, low.video);
+
+        let high = playlist.variant_renditions(&playlist.variants[1]);
+        assert_eq!(None, high.audio);
+        assert_eq!(
+            vec!["Angle 1"],
+            high.video
+                .unwrap()
+                .renditions
+                .iter()
+                .map(|m| m.name())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(MediaType::Video),
+            playlist
+                .i_frame_video_renditions(&playlist.i_frame_variants[0])
+                .map(|g| g.media_type)
+        );
+        assert_eq!(
+            vec![(MediaType::Audio, "missing")],
+            playlist.unresolved_group_references()
+        );
+    }
+
+    #[test]
+    fn media_playlist_should_keep_trailing_parts_and_other_lines() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-PART-INF:PART-TARGET=1\n",
+            "#EXT-X-PART:DURATION=1,URI=\"0.0.mp4\"\n",
+            "#EXT-X-X-CUSTOM:1\n",
+            "#EXTINF:1\n",
+            "0.mp4\n",
+            "#EXT-X-PART:DURATION=1,URI=\"1.0.mp4\"\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:01.000Z\n",
+            "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"1.1.mp4\"\n",
+        );
+        let reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+        let Ok(Playlist::Media(playlist)) = Playlist::try_from(reader) else {
+            panic!("should be a media playlist");
+        };
+        assert_eq!(1, playlist.segments.len());
+        let segment = &playlist.segments[0];
+        assert_eq!(1, segment.parts.len());
+        assert_eq!(1, segment.other.len());
+        assert!(
+            matches!(&segment.other[0], HlsLine::UnknownTag(tag) if tag.name() == "-X-X-CUSTOM")
+        );
+        assert_eq!(
+            vec!["1.0.mp4"],
+            playlist
+                .trailing_parts
+                .iter()
+                .map(|p| p.uri())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, playlist.preload_hints.len());
+        assert_eq!(1, playlist.other.len());
+        assert!(matches!(
+            &playlist.other[0],
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::ProgramDateTime(_)))
+        ));
+    }
+
+    #[test]
+    fn reader_error_should_be_returned() {
+        let reader = Reader::from_str("#EXTM3U\n#EXT\n", ParsingOptions::default());
+        let error = Playlist::try_from(reader).unwrap_err();
+        assert_eq!("#EXT", error.errored_line);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    playlist::range_requests::resolve,
+    tag::{KnownTag, hls},
+};
+use std::ops::Range;
+
+/// Plans the minimal set of HTTP requests needed to download all Media Segments in a Media
+/// Playlist.
+///
+/// Playlists that make use of `EXT-X-BYTERANGE` commonly describe each Media Segment as a sub-range
+/// of one larger resource. Requesting each sub-range separately is wasteful, and so this method
+/// coalesces the sub-ranges of consecutive segments into a single request when they refer to the
+/// same URI and the byte ranges are contiguous. Implicit offsets (where `EXT-X-BYTERANGE` has no
+/// `@o` part) are resolved per the HLS specification as starting at the next byte after the end of
+/// the previous sub-range of the same resource.
+///
+/// The returned requests are in playback order, and each one indicates the indices of the Media
+/// Segments that it covers (index `0` being the first segment in the playlist), so that the
+/// downloaded data can be mapped back to the segments. Segments that do not have an
+/// `EXT-X-BYTERANGE` are requested in full (the [`DownloadRequest::range`] is `None`). Lines that
+/// cannot be parsed are skipped, as are segments with a byte range that cannot be represented
+/// (because the end would overflow `u64`). Note that the `EXT-X-MAP` resource is not included in the
+/// plan.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{plan_downloads, DownloadRequest};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXTINF:4
+/// #EXT-X-BYTERANGE:1000@0
+/// main.mp4
+/// #EXTINF:4
+/// #EXT-X-BYTERANGE:1500
+/// main.mp4
+/// #EXTINF:4
+/// #EXT-X-BYTERANGE:1200@5000
+/// main.mp4
+/// #EXTINF:4
+/// other.mp4
+/// "#;
+///
+/// assert_eq!(
+///     vec![
+///         DownloadRequest { uri: "main.mp4", range: Some(0..2500), segments: 0..2 },
+///         DownloadRequest { uri: "main.mp4", range: Some(5000..6200), segments: 2..3 },
+///         DownloadRequest { uri: "other.mp4", range: None, segments: 3..4 },
+///     ],
+///     plan_downloads(PLAYLIST)
+/// );
+/// assert_eq!(
+///     Some("bytes=0-2499".to_string()),
+///     plan_downloads(PLAYLIST)[0].range_header()
+/// );
+/// ```
+pub fn plan_downloads(playlist: &str) -> Vec<DownloadRequest<'_>> {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_parsing_for_byterange()
+            .build(),
+    );
+    let mut requests: Vec<DownloadRequest> = Vec::new();
+    let mut pending_byterange = None;
+    let mut previous_sub_range_end: Option<(&str, u64)> = None;
+    let mut segment_index = 0;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Byterange(tag))))) => {
+                pending_byterange = Some(tag.range());
+            }
+            Ok(Some(input_uri!(uri))) => {
+                let segments = segment_index..(segment_index + 1);
+                segment_index += 1;
+                let range = match pending_byterange.take() {
+                    Some(byterange) => {
+                        let request = resolve(byterange, previous_sub_range_end, uri);
+                        previous_sub_range_end = request.map(|request| (uri, request.end));
+                        match request {
+                            Some(request) => Some(request.range()),
+                            None => continue,
+                        }
+                    }
+                    None => {
+                        previous_sub_range_end = None;
+                        None
+                    }
+                };
+                if let Some(last) = requests.last_mut()
+                    && last.uri == uri
+                    && let (Some(last_range), Some(range)) = (&mut last.range, &range)
+                    && last_range.end == range.start
+                {
+                    last_range.end = range.end;
+                    last.segments.end = segments.end;
+                    continue;
+                }
+                requests.push(DownloadRequest {
+                    uri,
+                    range,
+                    segments,
+                });
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    requests
+}
+
+/// A request for data needed to download one or more Media Segments.
+///
+/// See [`plan_downloads`] for more information.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DownloadRequest<'a> {
+    /// The URI of the resource (as written in the playlist, so may be relative).
+    pub uri: &'a str,
+    /// The byte range to request (`start..end` where `end` is exclusive). When `None` the whole
+    /// resource should be requested.
+    pub range: Option<Range<u64>>,
+    /// The indices of the Media Segments covered by this request (in playback order).
+    pub segments: Range<usize>,
+}
+
+impl DownloadRequest<'_> {
+    /// The value to use for the HTTP `Range` header of the request (if a range is needed).
+    ///
+    /// Note that the HTTP `Range` header uses an inclusive end byte.
+    pub fn range_header(&self) -> Option<String> {
+        self.range
+            .as_ref()
+            .filter(|range| range.end > range.start)
+            .map(|range| format!("bytes={}-{}", range.start, range.end - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn implicit_offset_should_not_carry_over_different_uri() {
+        const PLAYLIST: &str = concat!(
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:100@50\n",
+            "a.mp4\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:100\n",
+            "b.mp4\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:100\n",
+            "b.mp4\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:100\n",
+            "a.mp4\n",
+        );
+        assert_eq!(
+            vec![
+                DownloadRequest {
+                    uri: "a.mp4",
+                    range: Some(50..150),
+                    segments: 0..1
+                },
+                DownloadRequest {
+                    uri: "b.mp4",
+                    range: Some(0..200),
+                    segments: 1..3
+                },
+                DownloadRequest {
+                    uri: "a.mp4",
+                    range: Some(0..100),
+                    segments: 3..4
+                },
+            ],
+            plan_downloads(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn whole_resources_should_not_be_coalesced() {
+        const PLAYLIST: &str = "#EXTINF:4\na.mp4\n#EXTINF:4\na.mp4\n";
+        assert_eq!(
+            vec![
+                DownloadRequest {
+                    uri: "a.mp4",
+                    range: None,
+                    segments: 0..1
+                },
+                DownloadRequest {
+                    uri: "a.mp4",
+                    range: None,
+                    segments: 1..2
+                },
+            ],
+            plan_downloads(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn byterange_that_overflows_should_be_skipped() {
+        const PLAYLIST: &str = concat!(
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:100@18446744073709551600\n",
+            "a.mp4\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:100\n",
+            "a.mp4\n",
+        );
+        assert_eq!(
+            vec![DownloadRequest {
+                uri: "a.mp4",
+                range: Some(0..100),
+                segments: 1..2
+            }],
+            plan_downloads(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn range_header_should_be_inclusive() {
+        let request = DownloadRequest {
+            uri: "a.mp4",
+            range: Some(100..200),
+            segments: 0..1,
+        };
+        assert_eq!(Some("bytes=100-199".to_string()), request.range_header());
+        let request = DownloadRequest {
+            uri: "a.mp4",
+            range: None,
+            segments: 0..1,
+        };
+        assert_eq!(None, request.range_header());
+    }
+}
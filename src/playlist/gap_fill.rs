@@ -0,0 +1,331 @@
+use crate::{HlsLine, date::DateTime, error::GapFillError, tag::hls};
+use std::borrow::Cow;
+
+/// A Media Segment recovered from a capture of a playlist (see [`fill_gaps`]).
+#[derive(Debug, PartialEq, Clone)]
+pub struct CapturedSegment<'a> {
+    /// The Media Sequence Number of the segment.
+    pub media_sequence: u64,
+    /// The duration of the segment (from `EXTINF`).
+    pub duration: f64,
+    /// The URI of the segment.
+    pub uri: Cow<'a, str>,
+    /// The date and time of the first sample of the segment (from `EXT-X-PROGRAM-DATE-TIME`), if
+    /// known.
+    pub program_date_time: Option<DateTime>,
+}
+
+/// How [`fill_gaps`] estimates the duration of a missing segment from the segments either side of
+/// the gap.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GapDurationEstimate {
+    /// The duration of the segment before the gap.
+    Previous,
+    /// The duration of the segment after the gap.
+    Next,
+    /// The mean of the durations of the segments either side of the gap.
+    MeanOfNeighbors,
+    /// A fixed duration (e.g. the Target Duration).
+    Fixed(f64),
+}
+
+/// The options for [`fill_gaps`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct GapFillOptions {
+    estimate: GapDurationEstimate,
+    use_program_date_time: bool,
+    max_gap: u64,
+}
+
+impl Default for GapFillOptions {
+    /// Estimates with [`GapDurationEstimate::MeanOfNeighbors`], uses the program date time of the
+    /// segments either side of a gap when known, and allows gaps of up to
+    /// [`Self::DEFAULT_MAX_GAP`] segments.
+    fn default() -> Self {
+        Self {
+            estimate: GapDurationEstimate::MeanOfNeighbors,
+            use_program_date_time: true,
+            max_gap: Self::DEFAULT_MAX_GAP,
+        }
+    }
+}
+
+impl GapFillOptions {
+    /// The number of segments that may be missing from a gap by default (see
+    /// [`Self::with_max_gap`]).
+    pub const DEFAULT_MAX_GAP: u64 = 10_000;
+
+    /// Sets how the duration of a missing segment is estimated.
+    pub fn with_estimate(mut self, estimate: GapDurationEstimate) -> Self {
+        self.estimate = estimate;
+        self
+    }
+
+    /// Sets whether the program date time of the segments either side of a gap is used to
+    /// calculate the duration of the missing segments.
+    pub fn with_program_date_time(mut self, use_program_date_time: bool) -> Self {
+        self.use_program_date_time = use_program_date_time;
+        self
+    }
+
+    /// Sets the number of segments that may be missing from a gap, above which [`fill_gaps`]
+    /// provides [`GapFillError::GapTooLarge`] rather than inserting a placeholder for each.
+    ///
+    /// A placeholder is three lines, and so this bounds the memory used when the captures are
+    /// untrusted (e.g. a jump of the Media Sequence Number to near `u64::MAX` would otherwise
+    /// exhaust memory).
+    pub fn with_max_gap(mut self, max_gap: u64) -> Self {
+        self.max_gap = max_gap;
+        self
+    }
+
+    /// How the duration of a missing segment is estimated.
+    pub fn estimate(&self) -> GapDurationEstimate {
+        self.estimate
+    }
+
+    /// Whether the program date time of the segments either side of a gap is used.
+    pub fn use_program_date_time(&self) -> bool {
+        self.use_program_date_time
+    }
+
+    /// The number of segments that may be missing from a gap.
+    pub fn max_gap(&self) -> u64 {
+        self.max_gap
+    }
+}
+
+/// The Media Segments reconstructed by [`fill_gaps`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct GapFill<'a> {
+    /// The Media Sequence Number of the first segment (for `EXT-X-MEDIA-SEQUENCE`).
+    pub media_sequence: u64,
+    /// The lines of the segments, in order.
+    pub lines: Vec<HlsLine<'a>>,
+    /// The Media Sequence Numbers of the placeholder segments that were inserted.
+    pub inserted: Vec<u64>,
+}
+
+/// Reconstructs a continuous run of Media Segments from segments recovered from partial captures
+/// of a playlist, by inserting a placeholder segment (`EXTINF` and `EXT-X-GAP`) for each Media
+/// Sequence Number that is missing.
+///
+/// This keeps the Media Sequence Numbers of the recovered segments intact, and keeps the timeline
+/// continuous, so that the `EXT-X-PROGRAM-DATE-TIME` of each recovered segment still agrees with
+/// the durations before it. The duration of a missing segment is estimated according to the
+/// [`GapFillOptions`]; however, when the program date time of the segments either side of the gap
+/// is known (and enabled via [`GapFillOptions::with_program_date_time`]), the duration of the gap
+/// is known exactly, and is shared equally between the missing segments.
+///
+/// The segments are ordered by Media Sequence Number, and where a segment was captured more than
+/// once, the first capture is used. The URI of each placeholder is provided by `placeholder_uri`
+/// (given the Media Sequence Number), as the HLS specification requires a URI even for a gap.
+/// `EXT-X-PROGRAM-DATE-TIME` is written for each recovered segment where it is known.
+///
+/// An error is provided when more segments are missing from a gap than allowed by
+/// [`GapFillOptions::with_max_gap`].
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{Writer, playlist::{CapturedSegment, GapFillOptions, fill_gaps}};
+/// let segments = [(10, 4.0), (11, 4.0), (14, 2.0)].map(|(media_sequence, duration)| {
+///     CapturedSegment {
+///         media_sequence,
+///         duration,
+///         uri: format!("{media_sequence}.ts").into(),
+///         program_date_time: None,
+///     }
+/// });
+/// let fill = fill_gaps(segments, &GapFillOptions::default(), |msn| format!("gap-{msn}.ts"))?;
+/// assert_eq!(10, fill.media_sequence);
+/// assert_eq!(vec![12, 13], fill.inserted);
+///
+/// let mut writer = Writer::new(Vec::new());
+/// for line in fill.lines {
+///     writer.write_line(line)?;
+/// }
+/// assert_eq!(
+///     "#EXTINF:4\n10.ts\n#EXTINF:4\n11.ts\n\
+///      #EXTINF:3\n#EXT-X-GAP\ngap-12.ts\n#EXTINF:3\n#EXT-X-GAP\ngap-13.ts\n\
+///      #EXTINF:2\n14.ts\n",
+///     String::from_utf8_lossy(&writer.into_inner())
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn fill_gaps<'a>(
+    segments: impl IntoIterator<Item = CapturedSegment<'a>>,
+    options: &GapFillOptions,
+    mut placeholder_uri: impl FnMut(u64) -> String,
+) -> Result<GapFill<'a>, GapFillError> {
+    let mut segments = segments.into_iter().collect::<Vec<_>>();
+    // The sort is stable, and so the first capture of a segment is the one kept.
+    segments.sort_by_key(|segment| segment.media_sequence);
+    segments.dedup_by_key(|segment| segment.media_sequence);
+
+    let mut fill = GapFill {
+        media_sequence: segments.first().map_or(0, |segment| segment.media_sequence),
+        lines: Vec::new(),
+        inserted: Vec::new(),
+    };
+    let mut previous: Option<&CapturedSegment> = None;
+    for segment in &segments {
+        if let Some(previous) = previous {
+            let missing = segment.media_sequence - previous.media_sequence - 1;
+            if missing > options.max_gap {
+                return Err(GapFillError::GapTooLarge {
+                    previous_media_sequence: previous.media_sequence,
+                    missing,
+                });
+            }
+            // Gap filling is skipped when the sequence number following the previous segment
+            // cannot be represented.
+            match previous.media_sequence.checked_add(1) {
+                Some(first_missing) if missing > 0 => {
+                    let duration = gap_duration(previous, segment, missing, options);
+                    for media_sequence in first_missing..segment.media_sequence {
+                        fill.lines.push(HlsLine::from(hls::Inf::new(duration, "")));
+                        fill.lines.push(HlsLine::from(hls::Gap));
+                        fill.lines
+                            .push(HlsLine::Uri(Cow::Owned(placeholder_uri(media_sequence))));
+                        fill.inserted.push(media_sequence);
+                    }
+                }
+                _ => (),
+            }
+        }
+        if let Some(program_date_time) = segment.program_date_time {
+            fill.lines
+                .push(HlsLine::from(hls::ProgramDateTime::new(program_date_time)));
+        }
+        fill.lines
+            .push(HlsLine::from(hls::Inf::new(segment.duration, "")));
+        fill.lines.push(HlsLine::Uri(segment.uri.clone()));
+        previous = Some(segment);
+    }
+    Ok(fill)
+}
+
+// The duration of each of the missing segments between the two segments.
+fn gap_duration(
+    previous: &CapturedSegment,
+    next: &CapturedSegment,
+    missing: u64,
+    options: &GapFillOptions,
+) -> f64 {
+    if options.use_program_date_time
+        && let (Some(previous_date), Some(next_date)) =
+            (previous.program_date_time, next.program_date_time)
+    {
+        let gap = next_date.timestamp() - previous_date.timestamp() - previous.duration;
+        if gap > 0.0 {
+            return gap / missing as f64;
+        }
+    }
+    match options.estimate {
+        GapDurationEstimate::Previous => previous.duration,
+        GapDurationEstimate::Next => next.duration,
+        GapDurationEstimate::MeanOfNeighbors => (previous.duration + next.duration) / 2.0,
+        GapDurationEstimate::Fixed(duration) => duration,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{date_time, tag::KnownTag};
+    use pretty_assertions::assert_eq;
+
+    fn segment(
+        media_sequence: u64,
+        program_date_time: Option<DateTime>,
+    ) -> CapturedSegment<'static> {
+        CapturedSegment {
+            media_sequence,
+            duration: 6.0,
+            uri: Cow::Owned(format!("{media_sequence}.ts")),
+            program_date_time,
+        }
+    }
+
+    fn durations(fill: &GapFill) -> Vec<f64> {
+        fill.lines
+            .iter()
+            .filter_map(|line| match line {
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(inf))) => Some(inf.duration()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn program_date_time_should_determine_gap_duration() {
+        let fill = fill_gaps(
+            [
+                segment(3, Some(date_time!(2025-01-01 T 00:00:20.000))),
+                segment(1, Some(date_time!(2025-01-01 T 00:00:00.000))),
+                segment(3, None),
+            ],
+            &GapFillOptions::default(),
+            |msn| msn.to_string(),
+        )
+        .unwrap();
+        assert_eq!(1, fill.media_sequence);
+        assert_eq!(vec![2], fill.inserted);
+        assert_eq!(vec![6.0, 14.0, 6.0], durations(&fill));
+    }
+
+    #[test]
+    fn estimate_should_be_used_without_program_date_time() {
+        let options = GapFillOptions::default()
+            .with_estimate(GapDurationEstimate::Fixed(4.0))
+            .with_program_date_time(false);
+        let fill = fill_gaps(
+            [
+                segment(1, Some(date_time!(2025-01-01 T 00:00:00.000))),
+                segment(4, Some(date_time!(2025-01-01 T 00:00:20.000))),
+            ],
+            &options,
+            |msn| msn.to_string(),
+        )
+        .unwrap();
+        assert_eq!(vec![2, 3], fill.inserted);
+        assert_eq!(vec![6.0, 4.0, 4.0, 6.0], durations(&fill));
+    }
+
+    #[test]
+    fn gap_above_max_gap_should_be_error() {
+        let segments = [segment(1, None), segment(u64::MAX, None)];
+        assert_eq!(
+            Err(GapFillError::GapTooLarge {
+                previous_media_sequence: 1,
+                missing: u64::MAX - 2,
+            }),
+            fill_gaps(segments.clone(), &GapFillOptions::default(), |msn| {
+                msn.to_string()
+            })
+        );
+        let options = GapFillOptions::default().with_max_gap(2);
+        let segments = [segment(1, None), segment(4, None), segment(8, None)];
+        assert_eq!(
+            Err(GapFillError::GapTooLarge {
+                previous_media_sequence: 4,
+                missing: 3,
+            }),
+            fill_gaps(segments.clone(), &options, |msn| msn.to_string())
+        );
+        let fill = fill_gaps(segments, &options.with_max_gap(3), |msn| msn.to_string()).unwrap();
+        assert_eq!(vec![2, 3, 5, 6, 7], fill.inserted);
+    }
+
+    #[test]
+    fn gaps_at_the_largest_media_sequence_should_be_filled() {
+        let segments = [
+            segment(u64::MAX - 3, None),
+            segment(u64::MAX - 2, None),
+            segment(u64::MAX, None),
+        ];
+        let fill = fill_gaps(segments, &GapFillOptions::default(), |msn| msn.to_string()).unwrap();
+        assert_eq!(u64::MAX - 3, fill.media_sequence);
+        assert_eq!(vec![u64::MAX - 1], fill.inserted);
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{
+    HlsLine,
+    config::ParsingOptions,
+    error::ParseLineStrError,
+    line::{ParsedLineSlice, parse},
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
+
+/// Holds the last N versions of a playlist, sharing the lines that are unchanged between versions.
+///
+/// Each line is hash-consed: when a version is pushed, every line that exists in any held version
+/// (e.g. all but the last few segments of a live playlist refresh) re-uses the existing allocation
+/// rather than holding a copy. The versions themselves are immutable [`PlaylistVersion`] values,
+/// which are cheap to clone and can be shared between threads, and so can be parsed in parallel
+/// while new versions continue to be pushed. When the number of versions exceeds the capacity, the
+/// oldest version is dropped, along with any lines that are no longer used by a held version.
+///
+/// The memory held is described by [`Self::memory_stats`].
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{HlsLine, config::ParsingOptions, playlist::PlaylistHistory};
+/// let mut history = PlaylistHistory::new(2);
+/// history.push("#EXTM3U\n#EXTINF:4\nseg_1.ts\n#EXTINF:4\nseg_2.ts\n");
+/// history.push("#EXTM3U\n#EXTINF:4\nseg_2.ts\n#EXTINF:4\nseg_3.ts\n");
+///
+/// let stats = history.memory_stats();
+/// assert_eq!(2, stats.versions);
+/// assert_eq!(10, stats.total_lines);
+/// assert_eq!(5, stats.unique_lines);
+///
+/// let latest = history.latest().unwrap();
+/// let uris = latest
+///     .lines(&ParsingOptions::default())
+///     .filter_map(|line| match line {
+///         Ok(HlsLine::Uri(uri)) => Some(uri.to_string()),
+///         _ => None,
+///     })
+///     .collect::<Vec<_>>();
+/// assert_eq!(vec!["seg_2.ts", "seg_3.ts"], uris);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlaylistHistory {
+    capacity: usize,
+    pool: HashSet<Arc<str>>,
+    versions: VecDeque<PlaylistVersion>,
+}
+
+impl PlaylistHistory {
+    /// Creates an empty history that holds at most `capacity` versions (and always at least one).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            pool: HashSet::new(),
+            versions: VecDeque::new(),
+        }
+    }
+
+    /// Pushes a new version of the playlist, dropping the oldest version if the history is full.
+    ///
+    /// The returned version shares its lines with the other held versions.
+    pub fn push(&mut self, playlist: &str) -> PlaylistVersion {
+        let lines = playlist
+            .lines()
+            .map(|line| match self.pool.get(line) {
+                Some(shared) => Arc::clone(shared),
+                None => {
+                    let shared = Arc::<str>::from(line);
+                    self.pool.insert(Arc::clone(&shared));
+                    shared
+                }
+            })
+            .collect();
+        let version = PlaylistVersion { lines };
+        self.versions.push_back(version.clone());
+        if self.versions.len() > self.capacity {
+            self.versions.pop_front();
+            // Any line that is only referenced by the pool is no longer used by a held version
+            // (unless a caller still holds a dropped version, in which case the line is kept).
+            self.pool.retain(|line| Arc::strong_count(line) > 1);
+        }
+        version
+    }
+
+    /// The held versions, from oldest to newest.
+    pub fn versions(&self) -> impl DoubleEndedIterator<Item = &PlaylistVersion> {
+        self.versions.iter()
+    }
+
+    /// The most recently pushed version.
+    pub fn latest(&self) -> Option<&PlaylistVersion> {
+        self.versions.back()
+    }
+
+    /// The number of held versions.
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    /// Whether no versions are held.
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+
+    /// Describes the memory held by the history.
+    pub fn memory_stats(&self) -> MemoryStats {
+        MemoryStats {
+            versions: self.versions.len(),
+            total_lines: self.versions.iter().map(PlaylistVersion::len).sum(),
+            unique_lines: self.pool.len(),
+            unique_bytes: self.pool.iter().map(|line| line.len()).sum(),
+            total_bytes: self.versions.iter().map(PlaylistVersion::byte_len).sum(),
+        }
+    }
+}
+
+/// An immutable version of a playlist held by [`PlaylistHistory`].
+///
+/// Cloning is cheap (the lines are reference counted) and the version can be sent to, and shared
+/// between, threads. The lines are parsed on demand using [`Self::lines`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistVersion {
+    lines: Arc<[Arc<str>]>,
+}
+
+impl PlaylistVersion {
+    /// The number of lines in the version.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Whether the version has no lines.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Iterates over the raw lines (without line endings).
+    pub fn raw_lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(|line| line.as_ref())
+    }
+
+    /// Iterates over the parsed lines.
+    pub fn lines<'a>(
+        &'a self,
+        options: &'a ParsingOptions,
+    ) -> impl Iterator<Item = Result<HlsLine<'a>, ParseLineStrError<'a>>> {
+        self.raw_lines()
+            .map(|line| parse(line, options).map(|ParsedLineSlice { parsed, .. }| parsed))
+    }
+
+    /// Whether the line at the index shares its allocation with the same line in the other
+    /// version (i.e. the line was unchanged between the versions).
+    pub fn shares_line_with(&self, index: usize, other: &PlaylistVersion) -> bool {
+        match self.lines.get(index) {
+            Some(line) => other.lines.iter().any(|o| Arc::ptr_eq(line, o)),
+            None => false,
+        }
+    }
+
+    fn byte_len(&self) -> usize {
+        self.lines.iter().map(|line| line.len()).sum()
+    }
+}
+
+/// Describes the memory held by a [`PlaylistHistory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryStats {
+    /// The number of held versions.
+    pub versions: usize,
+    /// The number of lines across all held versions.
+    pub total_lines: usize,
+    /// The number of distinct lines held (i.e. the number of line allocations).
+    pub unique_lines: usize,
+    /// The bytes of line content held (excluding bookkeeping overhead).
+    pub unique_bytes: usize,
+    /// The bytes of line content that would be held if no lines were shared between versions.
+    pub total_bytes: usize,
+}
+
+impl MemoryStats {
+    /// The bytes saved by sharing lines between versions.
+    pub fn saved_bytes(&self) -> usize {
+        self.total_bytes.saturating_sub(self.unique_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn live_playlist(first: u64) -> String {
+        let mut playlist = format!("#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:{first}\n");
+        for n in first..first + 3 {
+            playlist.push_str(&format!("#EXTINF:4\nseg_{n}.ts\n"));
+        }
+        playlist
+    }
+
+    #[test]
+    fn unchanged_lines_should_be_shared_between_versions() {
+        let mut history = PlaylistHistory::new(3);
+        let first = history.push(&live_playlist(1));
+        let second = history.push(&live_playlist(2));
+        assert_eq!(8, second.len());
+        // seg_2.ts is at index 5 in the first version and index 3 in the second.
+        assert!(second.shares_line_with(3, &first));
+        assert!(second.shares_line_with(0, &first));
+        assert!(!second.shares_line_with(1, &first));
+        assert_eq!(
+            MemoryStats {
+                versions: 2,
+                total_lines: 16,
+                // #EXTM3U, #EXTINF:4, 2 media sequences, and seg_1 through seg_4.
+                unique_lines: 8,
+                unique_bytes: 7 + 9 + 23 + 23 + 8 * 4,
+                total_bytes: 2 * (7 + 23 + 3 * 9 + 3 * 8),
+            },
+            history.memory_stats()
+        );
+    }
+
+    #[test]
+    fn dropped_versions_should_release_unused_lines() {
+        let mut history = PlaylistHistory::new(1);
+        history.push(&live_playlist(1));
+        let latest = history.push(&live_playlist(5));
+        assert_eq!(1, history.len());
+        assert_eq!(Some(&latest), history.latest());
+        let stats = history.memory_stats();
+        assert_eq!(6, stats.unique_lines);
+        // Only the repeated #EXTINF:4 lines are shared.
+        assert_eq!(2 * 9, stats.saved_bytes());
+        assert_eq!(
+            vec!["#EXTM3U", "#EXT-X-MEDIA-SEQUENCE:5"],
+            latest.raw_lines().take(2).collect::<Vec<_>>()
+        );
+        assert!(latest.lines(&ParsingOptions::default()).all(|l| l.is_ok()));
+        fn assert_send_sync<T: Send + Sync>(_: T) {}
+        assert_send_sync(latest);
+    }
+}
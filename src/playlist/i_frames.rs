@@ -0,0 +1,221 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    playlist::segment_list,
+    tag::{KnownTag, hls},
+};
+use std::collections::HashMap;
+
+/// Validates a Media Playlist that is expected to be an I-frame playlist (i.e. the playlist of an
+/// `EXT-X-I-FRAME-STREAM-INF`), as used for trick-play.
+///
+/// The following is checked:
+/// * The playlist contains `EXT-X-I-FRAMES-ONLY`.
+/// * Every segment has an applicable `EXT-X-MAP`, or is self-initializing. A segment is considered
+///   to be self-initializing when its URI path ends in `.ts` (i.e. it is an MPEG-2 Transport
+///   Stream).
+/// * Segments that address a sub-range of a resource have an `EXT-X-BYTERANGE`. Each I-frame is
+///   typically a small part of a larger resource, and so, when the same URI is used by more than
+///   one segment, every one of those segments must have a byte range.
+/// * Each `EXTINF` reflects the interval until the next I-frame, meaning that it must be positive,
+///   and must not exceed the `EXT-X-TARGETDURATION` (when rounded to the nearest integer).
+///
+/// The diagnostics are provided in playlist order, with [`IFrameDiagnostic::MissingIFramesOnly`]
+/// first (when applicable). An empty result means that the playlist passed validation.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{validate_i_frames_only, IFrameDiagnostic};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-VERSION:4
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-I-FRAMES-ONLY
+/// #EXTINF:2
+/// #EXT-X-BYTERANGE:1000@0
+/// main.mp4
+/// #EXTINF:2
+/// #EXT-X-BYTERANGE:1000@5000
+/// main.mp4
+/// "#;
+///
+/// assert_eq!(
+///     vec![
+///         IFrameDiagnostic::MissingMap { media_sequence: 0, uri: "main.mp4" },
+///         IFrameDiagnostic::MissingMap { media_sequence: 1, uri: "main.mp4" },
+///     ],
+///     validate_i_frames_only(PLAYLIST)
+/// );
+/// ```
+pub fn validate_i_frames_only(playlist: &str) -> Vec<IFrameDiagnostic<'_>> {
+    let mut diagnostics = Vec::new();
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_parsing_for_i_frames_only()
+            .build(),
+    );
+    let mut i_frames_only = false;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::IFramesOnly(_))))) => {
+                i_frames_only = true;
+                break;
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    if !i_frames_only {
+        diagnostics.push(IFrameDiagnostic::MissingIFramesOnly);
+    }
+
+    let list = segment_list(playlist);
+    let mut uri_counts: HashMap<&str, usize> = HashMap::new();
+    for segment in &list.segments {
+        *uri_counts.entry(segment.uri).or_default() += 1;
+    }
+    for segment in &list.segments {
+        let media_sequence = segment.media_sequence;
+        let uri = segment.uri;
+        if segment.init_segment.is_none() && !is_self_initializing(uri) {
+            diagnostics.push(IFrameDiagnostic::MissingMap {
+                media_sequence,
+                uri,
+            });
+        }
+        if segment.byte_range.is_none() && uri_counts[uri] > 1 {
+            diagnostics.push(IFrameDiagnostic::MissingByterange {
+                media_sequence,
+                uri,
+            });
+        }
+        let duration = segment.duration;
+        if duration <= 0.0 {
+            diagnostics.push(IFrameDiagnostic::InvalidDuration {
+                media_sequence,
+                duration,
+            });
+        } else if let Some(target_duration) = list.target_duration
+            && duration.round() > target_duration as f64
+        {
+            diagnostics.push(IFrameDiagnostic::DurationExceedsTargetDuration {
+                media_sequence,
+                duration,
+                target_duration,
+            });
+        }
+    }
+    diagnostics
+}
+
+/// A problem found by [`validate_i_frames_only`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum IFrameDiagnostic<'a> {
+    /// The playlist does not contain `EXT-X-I-FRAMES-ONLY`.
+    MissingIFramesOnly,
+    /// The segment has no applicable `EXT-X-MAP` and is not self-initializing.
+    MissingMap {
+        /// The media sequence number of the segment.
+        media_sequence: u64,
+        /// The URI of the segment.
+        uri: &'a str,
+    },
+    /// The segment has no `EXT-X-BYTERANGE`, even though its URI is shared with other segments.
+    MissingByterange {
+        /// The media sequence number of the segment.
+        media_sequence: u64,
+        /// The URI of the segment.
+        uri: &'a str,
+    },
+    /// The `EXTINF` duration of the segment is not positive, and so cannot reflect the interval
+    /// until the next I-frame.
+    InvalidDuration {
+        /// The media sequence number of the segment.
+        media_sequence: u64,
+        /// The `EXTINF` duration.
+        duration: f64,
+    },
+    /// The `EXTINF` duration of the segment (rounded to the nearest integer) exceeds the
+    /// `EXT-X-TARGETDURATION`.
+    DurationExceedsTargetDuration {
+        /// The media sequence number of the segment.
+        media_sequence: u64,
+        /// The `EXTINF` duration.
+        duration: f64,
+        /// The `EXT-X-TARGETDURATION`.
+        target_duration: u64,
+    },
+}
+
+fn is_self_initializing(uri: &str) -> bool {
+    let path = uri.split(['?', '#']).next().unwrap_or(uri);
+    path.len() >= 3 && path[path.len() - 3..].eq_ignore_ascii_case(".ts")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn valid_i_frame_playlists_should_have_no_diagnostics() {
+        const FMP4: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-I-FRAMES-ONLY\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:1000@0\n",
+            "main.mp4\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:1000@5000\n",
+            "main.mp4\n",
+        );
+        const TS: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-I-FRAMES-ONLY\n",
+            "#EXTINF:4\n",
+            "a.ts?token=1\n",
+            "#EXTINF:4\n",
+            "b.ts\n",
+        );
+        assert_eq!(Vec::<IFrameDiagnostic>::new(), validate_i_frames_only(FMP4));
+        assert_eq!(Vec::<IFrameDiagnostic>::new(), validate_i_frames_only(TS));
+    }
+
+    #[test]
+    fn invalid_i_frame_playlist_should_report_each_problem() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXTINF:0\n",
+            "a.ts\n",
+            "#EXTINF:4.6\n",
+            "a.ts\n",
+        );
+        assert_eq!(
+            vec![
+                IFrameDiagnostic::MissingIFramesOnly,
+                IFrameDiagnostic::MissingByterange {
+                    media_sequence: 0,
+                    uri: "a.ts",
+                },
+                IFrameDiagnostic::InvalidDuration {
+                    media_sequence: 0,
+                    duration: 0.0,
+                },
+                IFrameDiagnostic::MissingByterange {
+                    media_sequence: 1,
+                    uri: "a.ts",
+                },
+                IFrameDiagnostic::DurationExceedsTargetDuration {
+                    media_sequence: 1,
+                    duration: 4.6,
+                    target_duration: 4,
+                },
+            ],
+            validate_i_frames_only(PLAYLIST)
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    date::DateTime,
+    playlist::{SegmentList, live_edge, segment_list},
+    tag::{
+        KnownTag,
+        hls::{self, Cue, Daterange, Restrict, TagName},
+    },
+};
+
+/// When playback of a Media Playlist is joining.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum JoinTime {
+    /// Join where the playlist indicates that playback should start. This is the `EXT-X-START`
+    /// when present. Otherwise, this is the start of the playlist when it has ended (has
+    /// `EXT-X-ENDLIST`), or the live edge (see [`crate::playlist::live_edge`]) when it has not.
+    #[default]
+    Start,
+    /// Join at the offset (in seconds) from the start of the playlist.
+    Offset(f64),
+    /// Join at the date, as mapped to the playlist using `EXT-X-PROGRAM-DATE-TIME`.
+    ProgramDateTime(DateTime),
+}
+
+/// Where playback should begin, as determined by [`join_point`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum JoinPoint<'a> {
+    /// Playback begins in the primary content.
+    Primary {
+        /// The offset (in seconds) from the start of the playlist.
+        offset: f64,
+    },
+    /// Playback begins in an interstitial.
+    Interstitial {
+        /// The `ID` of the interstitial `EXT-X-DATERANGE`.
+        id: &'a str,
+        /// The offset (in seconds) into the interstitial content.
+        offset: f64,
+        /// The offset (in seconds) from the start of the playlist at which the primary content
+        /// resumes once the interstitial has finished.
+        resume_offset: f64,
+        /// Why playback begins in the interstitial.
+        reason: InterstitialJoinReason,
+    },
+}
+
+/// Why [`join_point`] began playback in an interstitial.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InterstitialJoinReason {
+    /// The interstitial has `CUE=PRE`, and so is played before the primary content, regardless of
+    /// the join time.
+    PreRoll,
+    /// The join time falls within the interstitial, and so playback joins it in progress.
+    InProgress,
+    /// The join time falls within the interstitial, but the interstitial has `X-RESTRICT=JUMP`,
+    /// meaning that the primary content after its `START-DATE` cannot be reached without playing
+    /// it, and so it is played from the beginning.
+    Restricted,
+}
+
+/// Determines where playback of a Media Playlist should begin when joining at the given time,
+/// taking into account the HLS Interstitials (`EXT-X-DATERANGE` with
+/// `CLASS="com.apple.hls.interstitial"`) in the playlist.
+///
+/// The join time is first resolved to an offset in the primary content (see [`JoinTime`]). An
+/// `EXT-X-START` with a negative `TIME-OFFSET` is relative to the end of the playlist, and, unless
+/// it is `PRECISE=YES`, the offset is moved back to the start of the segment that contains it. Then
+/// the interstitials are considered as follows:
+/// * The first interstitial with `CUE=PRE` is played before the primary content.
+/// * Otherwise, when the offset falls within an interstitial (between its `START-DATE` and the end
+///   of its `DURATION`, `PLANNED-DURATION`, or `END-DATE`), playback begins in the interstitial,
+///   at the corresponding offset into it, unless it has `X-RESTRICT=JUMP`, in which case it is
+///   played from the beginning.
+/// * Interstitials with `CUE=POST` are never joined.
+///
+/// An interstitial with `CUE=ONCE` whose `ID` is in `played` (i.e. it has already been played by
+/// this client) is ignored. When playback begins in an interstitial, the primary content resumes
+/// at the join offset for a pre-roll, or otherwise at the `START-DATE` plus the `X-RESUME-OFFSET`
+/// (defaulting to the duration of the interstitial).
+///
+/// `None` is returned when the playlist has no segments, or when the join time (or an
+/// interstitial) cannot be mapped to the playlist because there is no `EXT-X-PROGRAM-DATE-TIME`.
+/// Lines that cannot be parsed are skipped.
+///
+/// For example, joining a live stream during an ad break (the live edge is at 8s, which is 6s into
+/// the ad break):
+/// ```
+/// # use quick_m3u8::playlist::{join_point, InterstitialJoinReason, JoinPoint, JoinTime};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z
+/// #EXT-X-DATERANGE:ID="ad1",CLASS="com.apple.hls.interstitial",START-DATE="2025-01-01T00:00:02.000Z",DURATION=10,X-ASSET-URI="ad.m3u8"
+/// #EXTINF:4
+/// 1.ts
+/// #EXTINF:4
+/// 2.ts
+/// #EXTINF:4
+/// 3.ts
+/// #EXTINF:4
+/// 4.ts
+/// #EXTINF:4
+/// 5.ts
+/// "#;
+///
+/// assert_eq!(
+///     Some(JoinPoint::Interstitial {
+///         id: "ad1",
+///         offset: 6.0,
+///         resume_offset: 12.0,
+///         reason: InterstitialJoinReason::InProgress,
+///     }),
+///     join_point(PLAYLIST, JoinTime::Start, &[])
+/// );
+/// assert_eq!(
+///     Some(JoinPoint::Primary { offset: 1.0 }),
+///     join_point(PLAYLIST, JoinTime::Offset(1.0), &[])
+/// );
+/// ```
+pub fn join_point<'a>(playlist: &'a str, join: JoinTime, played: &[&str]) -> Option<JoinPoint<'a>> {
+    let list = segment_list(playlist);
+    if list.segments.is_empty() {
+        return None;
+    }
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_tags([TagName::Start])
+            .build(),
+    );
+    let mut start = None;
+    let mut interstitials = Vec::new();
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Start(tag))))) => {
+                start = Some((tag.time_offset(), tag.precise()));
+            }
+            Ok(Some(HlsLine::UnknownTag(tag))) if tag.name() == "-X-DATERANGE" => {
+                let Some(id) = tag
+                    .value()
+                    .and_then(|v| v.try_as_attribute_map().ok())
+                    .and_then(|list| list.get("ID"))
+                    .and_then(|v| v.quoted())
+                else {
+                    continue;
+                };
+                if let Ok(daterange) = Daterange::try_from(tag)
+                    && daterange.interstitial_attributes().is_some()
+                {
+                    interstitials.push((id, daterange));
+                }
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+
+    // The bounds of `clamp` must be ordered, which is not the case for a negative (or NaN) duration
+    // (from `EXTINF` durations that are invalid).
+    let duration = list.duration().max(0.0);
+    let offset = match join {
+        JoinTime::Offset(offset) => offset.clamp(0.0, duration),
+        JoinTime::ProgramDateTime(date) => list.offset_of(date)?.clamp(0.0, duration),
+        JoinTime::Start => match start {
+            Some((time_offset, precise)) => {
+                let offset = if time_offset < 0.0 {
+                    duration + time_offset
+                } else {
+                    time_offset
+                };
+                let offset = offset.clamp(0.0, duration);
+                if precise {
+                    offset
+                } else {
+                    segment_start(&list, offset)
+                }
+            }
+            None if list.ended => 0.0,
+            None => live_edge(playlist).map(|edge| edge.start).unwrap_or(0.0),
+        },
+    };
+
+    let not_played = |id: &str, daterange: &Daterange| {
+        let once = daterange.cue().is_some_and(|cue| cue.contains(Cue::Once));
+        !(once && played.contains(&id))
+    };
+    for (id, daterange) in &interstitials {
+        if daterange.cue().is_some_and(|cue| cue.contains(Cue::Pre)) && not_played(id, daterange) {
+            return Some(JoinPoint::Interstitial {
+                id,
+                offset: 0.0,
+                resume_offset: offset,
+                reason: InterstitialJoinReason::PreRoll,
+            });
+        }
+    }
+    for (id, daterange) in &interstitials {
+        let cue = daterange.cue();
+        if cue.is_some_and(|cue| cue.contains(Cue::Pre) || cue.contains(Cue::Post))
+            || !not_played(id, daterange)
+        {
+            continue;
+        }
+        let Some(start_date) = daterange.start_date() else {
+            continue;
+        };
+        let Some(interstitial_start) = list.offset_of(start_date) else {
+            continue;
+        };
+        let interstitial_duration = daterange
+            .duration()
+            .or(daterange.planned_duration())
+            .or(daterange
+                .end_date()
+                .map(|end| end.timestamp() - start_date.timestamp()))
+            .unwrap_or(0.0);
+        if offset < interstitial_start || offset >= interstitial_start + interstitial_duration {
+            continue;
+        }
+        let attributes = daterange.interstitial_attributes()?;
+        let resume_offset =
+            interstitial_start + attributes.resume_offset().unwrap_or(interstitial_duration);
+        let restricted = attributes
+            .restrict()
+            .is_some_and(|restrict| restrict.contains(Restrict::Jump));
+        let (offset, reason) = if restricted {
+            (0.0, InterstitialJoinReason::Restricted)
+        } else {
+            (
+                offset - interstitial_start,
+                InterstitialJoinReason::InProgress,
+            )
+        };
+        return Some(JoinPoint::Interstitial {
+            id,
+            offset,
+            resume_offset,
+            reason,
+        });
+    }
+    Some(JoinPoint::Primary { offset })
+}
+
+fn segment_start(list: &SegmentList, offset: f64) -> f64 {
+    list.segments
+        .iter()
+        .map(|segment| segment.start)
+        .take_while(|start| *start <= offset)
+        .last()
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_time;
+    use pretty_assertions::assert_eq;
+
+    const VOD: &str = concat!(
+        "#EXTM3U\n",
+        "#EXT-X-TARGETDURATION:4\n",
+        "#EXT-X-PLAYLIST-TYPE:VOD\n",
+        "#EXT-X-START:TIME-OFFSET=-6\n",
+        "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+        "#EXT-X-DATERANGE:ID=\"pre\",CLASS=\"com.apple.hls.interstitial\",START-DATE=\"2025-01-01T00:00:00.000Z\",CUE=\"PRE,ONCE\",X-ASSET-URI=\"pre.m3u8\"\n",
+        "#EXT-X-DATERANGE:ID=\"mid\",CLASS=\"com.apple.hls.interstitial\",START-DATE=\"2025-01-01T00:00:04.000Z\",DURATION=2,X-RESUME-OFFSET=0,X-RESTRICT=\"SKIP,JUMP\",X-ASSET-URI=\"mid.m3u8\"\n",
+        "#EXT-X-DATERANGE:ID=\"post\",CLASS=\"com.apple.hls.interstitial\",START-DATE=\"2025-01-01T00:00:00.000Z\",CUE=\"POST\",X-ASSET-URI=\"post.m3u8\"\n",
+        "#EXTINF:4\n",
+        "1.ts\n",
+        "#EXTINF:4\n",
+        "2.ts\n",
+        "#EXTINF:4\n",
+        "3.ts\n",
+        "#EXTINF:4\n",
+        "4.ts\n",
+        "#EXT-X-ENDLIST\n",
+    );
+
+    #[test]
+    fn pre_roll_should_be_played_unless_already_played_once() {
+        assert_eq!(
+            Some(JoinPoint::Interstitial {
+                id: "pre",
+                offset: 0.0,
+                resume_offset: 8.0,
+                reason: InterstitialJoinReason::PreRoll,
+            }),
+            join_point(VOD, JoinTime::Start, &[])
+        );
+        // EXT-X-START of -6 is at 10s, which is moved back to the start of its segment.
+        assert_eq!(
+            Some(JoinPoint::Primary { offset: 8.0 }),
+            join_point(VOD, JoinTime::Start, &["pre"])
+        );
+    }
+
+    #[test]
+    fn joining_restricted_interstitial_should_play_it_from_the_beginning() {
+        assert_eq!(
+            Some(JoinPoint::Interstitial {
+                id: "mid",
+                offset: 0.0,
+                resume_offset: 4.0,
+                reason: InterstitialJoinReason::Restricted,
+            }),
+            join_point(
+                VOD,
+                JoinTime::ProgramDateTime(date_time!(2025-01-01 T 00:00:05.000)),
+                &["pre"]
+            )
+        );
+        assert_eq!(
+            Some(JoinPoint::Primary { offset: 12.0 }),
+            join_point(VOD, JoinTime::Offset(12.0), &["pre"])
+        );
+        assert_eq!(None, join_point("#EXTM3U\n", JoinTime::Start, &[]));
+    }
+
+    #[test]
+    fn negative_durations_should_not_panic() {
+        const PLAYLIST: &str =
+            "#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:-4,\na.ts\n#EXT-X-ENDLIST\n";
+        assert_eq!(
+            Some(JoinPoint::Primary { offset: 0.0 }),
+            join_point(PLAYLIST, JoinTime::Offset(2.0), &[])
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    tag::{
+        KnownTag,
+        hls::{self, Key, Method, SessionKey, TagName},
+    },
+};
+use std::collections::{HashMap, HashSet};
+
+/// Removes duplicate session keys, keeping the first occurrence of each (in order).
+///
+/// Keys are compared semantically (see [`SessionKey`]), which makes this suitable for merging the
+/// `EXT-X-SESSION-KEY` tags of several sources into a Multivariant Playlist without emitting the
+/// same key twice.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{playlist::dedup_session_keys, tag::hls::{Method, SessionKey}};
+/// let key = |iv: &'static str| {
+///     SessionKey::builder()
+///         .with_method(Method::SampleAes)
+///         .with_uri("skd://key")
+///         .with_iv(iv)
+///         .finish()
+/// };
+/// let keys = dedup_session_keys([key("0xABCD"), key("0xabcd"), key("0x1234")]);
+/// assert_eq!(vec![key("0xABCD"), key("0x1234")], keys);
+/// ```
+pub fn dedup_session_keys<'a>(
+    keys: impl IntoIterator<Item = SessionKey<'a>>,
+) -> Vec<SessionKey<'a>> {
+    let mut seen = HashSet::new();
+    keys.into_iter()
+        .filter(|key| seen.insert(key.clone()))
+        .collect()
+}
+
+/// Finds the `EXT-X-KEY` tags of a Media Playlist that are redundant, because they repeat the key
+/// that is already in effect.
+///
+/// A key remains in effect for its `KEYFORMAT` until another `EXT-X-KEY` with the same `KEYFORMAT`
+/// replaces it, or until an `EXT-X-KEY` with a `METHOD` of `NONE` clears every key. A tag is
+/// redundant when it is semantically equal (see [`Key`]) to the key in effect for its `KEYFORMAT`,
+/// meaning that it can be removed without changing how any segment is decrypted.
+///
+/// The redundant keys are provided in playlist order. Lines that cannot be parsed are skipped.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::redundant_keys;
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-KEY:METHOD=AES-128,URI="key1",IV=0xABCD
+/// #EXTINF:4
+/// 1.ts
+/// #EXT-X-KEY:IV=0xabcd,URI="key1",METHOD=AES-128
+/// #EXTINF:4
+/// 2.ts
+/// "#;
+///
+/// let redundant = redundant_keys(PLAYLIST);
+/// assert_eq!(1, redundant.len());
+/// assert_eq!(6, redundant[0].line_number);
+/// ```
+pub fn redundant_keys(playlist: &str) -> Vec<RedundantKey<'_>> {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder().with_tags([TagName::Key]).build(),
+    );
+    let mut redundant = Vec::new();
+    let mut in_effect: HashMap<String, Key> = HashMap::new();
+    let mut line_number = 0;
+    loop {
+        line_number += 1;
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Key(key))))) => {
+                if key.method().known() == Some(&Method::None) {
+                    in_effect.clear();
+                    continue;
+                }
+                let keyformat = key.keyformat().to_string();
+                if in_effect.get(&keyformat) == Some(&key) {
+                    redundant.push(RedundantKey { line_number, key });
+                } else {
+                    in_effect.insert(keyformat, key);
+                }
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    redundant
+}
+
+/// An `EXT-X-KEY` found by [`redundant_keys`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct RedundantKey<'a> {
+    /// The (one-based) line number of the tag.
+    pub line_number: usize,
+    /// The redundant key.
+    pub key: Key<'a>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn keys_should_only_be_redundant_while_in_effect_for_their_keyformat() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://a\",KEYFORMAT=\"com.apple.streamingkeydelivery\"\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"data:a\",KEYFORMAT=\"urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed\"\n",
+            "#EXTINF:4\n",
+            "1.mp4\n",
+            "#EXT-X-KEY:KEYFORMAT=\"com.apple.streamingkeydelivery\",URI=\"skd://a\",METHOD=SAMPLE-AES\n",
+            "#EXTINF:4\n",
+            "2.mp4\n",
+            "#EXT-X-KEY:METHOD=NONE\n",
+            "#EXTINF:4\n",
+            "3.mp4\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://a\",KEYFORMAT=\"com.apple.streamingkeydelivery\"\n",
+            "#EXTINF:4\n",
+            "4.mp4\n",
+        );
+        assert_eq!(
+            vec![6],
+            redundant_keys(PLAYLIST)
+                .iter()
+                .map(|k| k.line_number)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn identity_keyformat_should_equal_absent_keyformat() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\",KEYFORMAT=\"identity\"\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"other\"\n",
+        );
+        let redundant = redundant_keys(PLAYLIST);
+        assert_eq!(1, redundant.len());
+        assert_eq!(3, redundant[0].line_number);
+        assert_eq!(
+            Key::builder()
+                .with_method(Method::Aes128)
+                .with_uri("key")
+                .finish(),
+            redundant[0].key
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    tag::{AttributeMap, AttributeValue, UnknownTag, hls::TagName},
+};
+
+/// The `EXT-X-STREAM-INF` attributes that are compared between Variant Streams.
+const VARIANT_ATTRIBUTES: [&str; 6] = [
+    "BANDWIDTH",
+    "AVERAGE-BANDWIDTH",
+    "CODECS",
+    "RESOLUTION",
+    "FRAME-RATE",
+    "STABLE-VARIANT-ID",
+];
+
+/// The `EXT-X-MEDIA` attributes that are compared between Renditions.
+const RENDITION_ATTRIBUTES: [&str; 4] = ["LANGUAGE", "CHANNELS", "DEFAULT", "STABLE-RENDITION-ID"];
+
+/// Checks that Multivariant Playlists that should be equivalent (for example, the same ladder
+/// generated per-CDN or per-region origin) describe the same Variant Streams and Renditions.
+///
+/// Every playlist after the first is compared against the first (the reference), and each
+/// difference found is reported as a [`LadderDrift`]. The comparison is as follows:
+/// * Variant Streams (`EXT-X-STREAM-INF`) are matched by `STABLE-VARIANT-ID`, or by position among
+///   the Variant Streams of the playlist when there is no `STABLE-VARIANT-ID`. The `BANDWIDTH`,
+///   `AVERAGE-BANDWIDTH`, `CODECS`, `RESOLUTION`, `FRAME-RATE`, and `STABLE-VARIANT-ID` are
+///   compared.
+/// * Renditions (`EXT-X-MEDIA`) are matched by `TYPE`, `GROUP-ID`, and `NAME`. The `LANGUAGE`,
+///   `CHANNELS`, `DEFAULT`, and `STABLE-RENDITION-ID` are compared.
+///
+/// URIs are deliberately not compared, as these are expected to differ between origins. Attribute
+/// values are compared as written (e.g. `FRAME-RATE=30` and `FRAME-RATE=30.000` are considered to
+/// differ), as a difference in the output of a packager is itself an indication of drift.
+///
+/// The drift is provided per playlist (in the order given), with the entries of the reference
+/// first (in playlist order), followed by any unexpected entries. Lines that cannot be parsed are
+/// skipped. An empty result means that no drift was found.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{ladder_drift, LadderDrift, LadderDriftKind, LadderEntry, VariantKey};
+/// const ORIGIN_A: &str = r#"#EXTM3U
+/// #EXT-X-STREAM-INF:BANDWIDTH=1280000,CODECS="avc1.4d401f,mp4a.40.2",STABLE-VARIANT-ID="low"
+/// https://a.example.com/low.m3u8
+/// "#;
+/// const ORIGIN_B: &str = r#"#EXTM3U
+/// #EXT-X-STREAM-INF:BANDWIDTH=1300000,CODECS="avc1.4d401f,mp4a.40.2",STABLE-VARIANT-ID="low"
+/// https://b.example.com/low.m3u8
+/// "#;
+///
+/// assert_eq!(
+///     vec![LadderDrift {
+///         playlist_index: 1,
+///         entry: LadderEntry::Variant(VariantKey::StableVariantId("low")),
+///         kind: LadderDriftKind::AttributeMismatch {
+///             attribute: "BANDWIDTH",
+///             expected: Some("1280000"),
+///             actual: Some("1300000"),
+///         },
+///     }],
+///     ladder_drift(&[ORIGIN_A, ORIGIN_B])
+/// );
+/// ```
+pub fn ladder_drift<'a>(playlists: &[&'a str]) -> Vec<LadderDrift<'a>> {
+    let mut drift = Vec::new();
+    let Some((reference, others)) = playlists.split_first() else {
+        return drift;
+    };
+    let reference = ladder(reference);
+    for (index, other) in others.iter().enumerate() {
+        let playlist_index = index + 1;
+        let other = ladder(other);
+        for (entry, expected) in &reference {
+            let attributes = match entry {
+                LadderEntry::Variant(_) => VARIANT_ATTRIBUTES.as_slice(),
+                LadderEntry::Rendition { .. } => RENDITION_ATTRIBUTES.as_slice(),
+            };
+            let Some((_, actual)) = other.iter().find(|(e, _)| e == entry) else {
+                drift.push(LadderDrift {
+                    playlist_index,
+                    entry: *entry,
+                    kind: LadderDriftKind::Missing,
+                });
+                continue;
+            };
+            for attribute in attributes {
+                let expected = value(expected, attribute);
+                let actual = value(actual, attribute);
+                if expected != actual {
+                    drift.push(LadderDrift {
+                        playlist_index,
+                        entry: *entry,
+                        kind: LadderDriftKind::AttributeMismatch {
+                            attribute,
+                            expected,
+                            actual,
+                        },
+                    });
+                }
+            }
+        }
+        for (entry, _) in &other {
+            if !reference.iter().any(|(e, _)| e == entry) {
+                drift.push(LadderDrift {
+                    playlist_index,
+                    entry: *entry,
+                    kind: LadderDriftKind::Unexpected,
+                });
+            }
+        }
+    }
+    drift
+}
+
+/// A difference found by [`ladder_drift`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LadderDrift<'a> {
+    /// The index (within the playlists provided) of the playlist that drifted from the reference.
+    pub playlist_index: usize,
+    /// The Variant Stream or Rendition that drifted.
+    pub entry: LadderEntry<'a>,
+    /// How the entry drifted.
+    pub kind: LadderDriftKind<'a>,
+}
+
+/// Identifies a Variant Stream or Rendition across playlists.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LadderEntry<'a> {
+    /// A Variant Stream (`EXT-X-STREAM-INF`).
+    Variant(VariantKey<'a>),
+    /// A Rendition (`EXT-X-MEDIA`).
+    Rendition {
+        /// The `TYPE` of the rendition.
+        media_type: &'a str,
+        /// The `GROUP-ID` of the rendition.
+        group_id: &'a str,
+        /// The `NAME` of the rendition.
+        name: &'a str,
+    },
+}
+
+/// How a Variant Stream is matched across playlists.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum VariantKey<'a> {
+    /// The Variant Stream has a `STABLE-VARIANT-ID`.
+    StableVariantId(&'a str),
+    /// The Variant Stream has no `STABLE-VARIANT-ID`, and so is identified by its (zero-based)
+    /// position among the Variant Streams of the playlist.
+    Position(usize),
+}
+
+/// The kind of [`LadderDrift`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LadderDriftKind<'a> {
+    /// The entry exists in the reference but not in the playlist.
+    Missing,
+    /// The entry exists in the playlist but not in the reference.
+    Unexpected,
+    /// The value of an attribute differs from the reference (`None` meaning that the attribute is
+    /// absent).
+    AttributeMismatch {
+        /// The name of the attribute.
+        attribute: &'static str,
+        /// The value in the reference.
+        expected: Option<&'a str>,
+        /// The value in the playlist.
+        actual: Option<&'a str>,
+    },
+}
+
+fn ladder(playlist: &str) -> Vec<(LadderEntry<'_>, AttributeMap<'_>)> {
+    let mut reader = Reader::from_str(playlist, ParsingOptions::builder().build());
+    let mut entries = Vec::new();
+    let mut variant_position = 0;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::UnknownTag(tag))) => {
+                let Some(list) = attribute_map(&tag) else {
+                    continue;
+                };
+                match TagName::try_from(tag.name()) {
+                    Ok(TagName::StreamInf) => {
+                        let key = match list.get("STABLE-VARIANT-ID").and_then(|v| v.quoted()) {
+                            Some(id) => VariantKey::StableVariantId(id),
+                            None => VariantKey::Position(variant_position),
+                        };
+                        variant_position += 1;
+                        entries.push((LadderEntry::Variant(key), list));
+                    }
+                    Ok(TagName::Media) => {
+                        if let (Some(media_type), Some(group_id), Some(name)) = (
+                            value(&list, "TYPE"),
+                            value(&list, "GROUP-ID"),
+                            value(&list, "NAME"),
+                        ) {
+                            let entry = LadderEntry::Rendition {
+                                media_type,
+                                group_id,
+                                name,
+                            };
+                            entries.push((entry, list));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    entries
+}
+
+fn attribute_map<'a>(tag: &UnknownTag<'a>) -> Option<AttributeMap<'a>> {
+    tag.value()?.try_as_attribute_map().ok()
+}
+
+fn value<'a>(list: &AttributeMap<'a>, name: &str) -> Option<&'a str> {
+    match list.get(name)? {
+        AttributeValue::Quoted(value) => Some(value),
+        AttributeValue::Unquoted(value) => value.try_as_utf_8().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const REFERENCE: &str = concat!(
+        "#EXTM3U\n",
+        "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",LANGUAGE=\"en\",URI=\"a/en.m3u8\"\n",
+        "#EXT-X-STREAM-INF:BANDWIDTH=1000,RESOLUTION=640x360,AUDIO=\"aac\"\n",
+        "a/low.m3u8\n",
+        "#EXT-X-STREAM-INF:BANDWIDTH=2000,RESOLUTION=1280x720,AUDIO=\"aac\"\n",
+        "a/high.m3u8\n",
+    );
+
+    #[test]
+    fn equivalent_ladders_with_different_uris_should_have_no_drift() {
+        const OTHER: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",LANGUAGE=\"en\",URI=\"b/en.m3u8\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000,RESOLUTION=640x360,AUDIO=\"aac\"\n",
+            "b/low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=2000,RESOLUTION=1280x720,AUDIO=\"aac\"\n",
+            "b/high.m3u8\n",
+        );
+        assert_eq!(
+            Vec::<LadderDrift>::new(),
+            ladder_drift(&[REFERENCE, OTHER, REFERENCE])
+        );
+        assert_eq!(Vec::<LadderDrift>::new(), ladder_drift(&[]));
+    }
+
+    #[test]
+    fn drift_should_be_reported_per_playlist_and_entry() {
+        const OTHER: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",URI=\"b/en.m3u8\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"French\",URI=\"b/fr.m3u8\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000,RESOLUTION=640x480,AUDIO=\"aac\"\n",
+            "b/low.m3u8\n",
+        );
+        let english = LadderEntry::Rendition {
+            media_type: "AUDIO",
+            group_id: "aac",
+            name: "English",
+        };
+        let drift = |entry, kind| LadderDrift {
+            playlist_index: 1,
+            entry,
+            kind,
+        };
+        assert_eq!(
+            vec![
+                drift(
+                    english,
+                    LadderDriftKind::AttributeMismatch {
+                        attribute: "LANGUAGE",
+                        expected: Some("en"),
+                        actual: None,
+                    }
+                ),
+                drift(
+                    LadderEntry::Variant(VariantKey::Position(0)),
+                    LadderDriftKind::AttributeMismatch {
+                        attribute: "RESOLUTION",
+                        expected: Some("640x360"),
+                        actual: Some("640x480"),
+                    }
+                ),
+                drift(
+                    LadderEntry::Variant(VariantKey::Position(1)),
+                    LadderDriftKind::Missing
+                ),
+                drift(
+                    LadderEntry::Rendition {
+                        media_type: "AUDIO",
+                        group_id: "aac",
+                        name: "French",
+                    },
+                    LadderDriftKind::Unexpected
+                ),
+            ],
+            ladder_drift(&[REFERENCE, OTHER])
+        );
+    }
+}
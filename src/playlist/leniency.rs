@@ -0,0 +1,354 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    custom_parsing,
+    error::{NumericAttributeError, ValidationError},
+    tag::{AttributeMap, AttributeValue, KnownTag, hls},
+};
+
+/// Reports where lenient parsing changed the interpretation of a playlist, compared to a strict
+/// reading of the HLS specification.
+///
+/// The library is lenient by design: a tag that fails validation is read as an unknown tag (and
+/// passed through unchanged), attribute values that cannot be parsed are treated as absent by the
+/// convenience accessors, enumerated values that are not recognized are exposed as
+/// [`crate::tag::hls::EnumeratedString::Unknown`], and so on. This is usually what is wanted in
+/// production; however, it also hides how far a playlist deviates from the specification.
+///
+/// This diagnostic parses each line twice: once with the [`crate::Reader`] (the lenient
+/// interpretation), and once as a raw [`crate::tag::UnknownTag`] (the text as written). The two are
+/// compared, and each divergence found is reported, so that origin teams can quantify (and fix)
+/// the deviations in their playlists without having to enforce strictness in production. The
+/// divergences detected are described by [`LeniencyDivergenceKind`].
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{leniency_report, LeniencyDivergence, LeniencyDivergenceKind};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-SERVER-CONTROL:HOLD-BACK=twelve
+/// #EXTINF:4
+/// segment.ts
+/// "#;
+///
+/// assert_eq!(
+///     vec![
+///         LeniencyDivergence {
+///             line_number: 3,
+///             tag_name: "-X-SERVER-CONTROL",
+///             kind: LeniencyDivergenceKind::AttributeIgnored {
+///                 attribute: "HOLD-BACK",
+///                 value: "twelve",
+///             },
+///         },
+///         LeniencyDivergence {
+///             line_number: 4,
+///             tag_name: "INF",
+///             kind: LeniencyDivergenceKind::DurationReinterpreted {
+///                 value: "4",
+///                 duration: 4.0,
+///             },
+///         },
+///     ],
+///     leniency_report(PLAYLIST)
+/// );
+/// ```
+pub fn leniency_report(playlist: &str) -> Vec<LeniencyDivergence<'_>> {
+    let mut reader = Reader::from_str(playlist, ParsingOptions::default());
+    let mut divergences = Vec::new();
+    let mut version = 1;
+    for (index, text) in playlist.lines().enumerate() {
+        let line = match reader.read_line() {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(_) => continue,
+        };
+        let Ok(raw) = custom_parsing::tag::parse(text).map(|parsed| parsed.parsed) else {
+            continue;
+        };
+        let mut kinds = Vec::new();
+        match line {
+            HlsLine::UnknownTag(tag) => {
+                if let Some(error) = tag.validation_error() {
+                    kinds.push(LeniencyDivergenceKind::TagRejected(error));
+                }
+            }
+            HlsLine::KnownTag(KnownTag::Hls(tag)) => {
+                if let hls::Tag::Version(tag) = &tag {
+                    version = tag.version();
+                }
+                if let Some(attributes) = raw
+                    .value()
+                    .and_then(|value| value.try_as_attribute_map().ok())
+                {
+                    let mut check = Check {
+                        attributes: &attributes,
+                        kinds: &mut kinds,
+                    };
+                    check.tag(&tag);
+                }
+                if let hls::Tag::Inf(inf) = &tag {
+                    let value = raw
+                        .value()
+                        .and_then(|value| std::str::from_utf8(value.0).ok())
+                        .unwrap_or_default();
+                    let strict = value.split_once(',').map(|(duration, _)| duration);
+                    let valid = match strict {
+                        Some(duration) if version < 3 => is_decimal_integer(duration),
+                        Some(duration) => is_decimal_floating_point(duration),
+                        None => false,
+                    };
+                    if !valid {
+                        kinds.push(LeniencyDivergenceKind::DurationReinterpreted {
+                            value: strict.unwrap_or(value),
+                            duration: inf.duration(),
+                        });
+                    }
+                }
+            }
+            _ => (),
+        }
+        divergences.extend(kinds.into_iter().map(|kind| LeniencyDivergence {
+            line_number: index + 1,
+            tag_name: raw.name(),
+            kind,
+        }));
+    }
+    divergences
+}
+
+/// A line where the lenient interpretation diverged from a strict reading of the specification.
+///
+/// See [`leniency_report`] for more information.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LeniencyDivergence<'a> {
+    /// The 1-based line number in the playlist.
+    pub line_number: usize,
+    /// The name of the tag (as it is provided by [`crate::tag::UnknownTag::name`], e.g. `-X-MEDIA`).
+    pub tag_name: &'a str,
+    /// How the interpretation diverged.
+    pub kind: LeniencyDivergenceKind<'a>,
+}
+
+/// How the lenient interpretation of a line diverged from a strict reading of the specification.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LeniencyDivergenceKind<'a> {
+    /// The tag failed validation, and so was read as an unknown tag (and would be passed through
+    /// unchanged rather than interpreted).
+    TagRejected(ValidationError),
+    /// The value of the attribute could not be parsed as the type defined by the specification, and
+    /// so is treated as absent.
+    AttributeIgnored {
+        /// The name of the attribute.
+        attribute: &'static str,
+        /// The value of the attribute as written.
+        value: &'a str,
+    },
+    /// The value of an enumerated attribute is not one defined by the specification, and so is
+    /// exposed as [`crate::tag::hls::EnumeratedString::Unknown`].
+    UnknownEnumeration {
+        /// The name of the attribute.
+        attribute: &'static str,
+        /// The value of the attribute as written.
+        value: &'a str,
+    },
+    /// The duration of `EXTINF` does not match the strict format (a decimal-integer before version
+    /// 3, otherwise a decimal-floating-point, followed by a comma), but was still interpreted.
+    DurationReinterpreted {
+        /// The duration as written (without the title).
+        value: &'a str,
+        /// The duration that it was interpreted as.
+        duration: f64,
+    },
+}
+
+struct Check<'a, 'b> {
+    attributes: &'b AttributeMap<'a>,
+    kinds: &'b mut Vec<LeniencyDivergenceKind<'a>>,
+}
+
+impl<'a> Check<'a, '_> {
+    fn tag(&mut self, tag: &hls::Tag) {
+        match tag {
+            hls::Tag::StreamInf(tag) => {
+                self.numeric("AVERAGE-BANDWIDTH", tag.try_average_bandwidth());
+                self.numeric("SCORE", tag.try_score());
+                self.numeric("FRAME-RATE", tag.try_frame_rate());
+                self.present("RESOLUTION", tag.resolution().is_some());
+                self.enumerated(
+                    "HDCP-LEVEL",
+                    tag.hdcp_level().is_some_and(|l| l.known().is_some()),
+                );
+                self.enumerated(
+                    "VIDEO-RANGE",
+                    tag.video_range().is_some_and(|r| r.known().is_some()),
+                );
+            }
+            hls::Tag::IFrameStreamInf(tag) => {
+                self.numeric("AVERAGE-BANDWIDTH", tag.try_average_bandwidth());
+                self.numeric("SCORE", tag.try_score());
+                self.present("RESOLUTION", tag.resolution().is_some());
+                self.enumerated(
+                    "HDCP-LEVEL",
+                    tag.hdcp_level().is_some_and(|l| l.known().is_some()),
+                );
+                self.enumerated(
+                    "VIDEO-RANGE",
+                    tag.video_range().is_some_and(|r| r.known().is_some()),
+                );
+            }
+            hls::Tag::Media(tag) => {
+                self.enumerated("TYPE", tag.media_type().known().is_some());
+                self.numeric("BIT-DEPTH", tag.try_bit_depth());
+                self.numeric("SAMPLE-RATE", tag.try_sample_rate());
+                self.enumerated(
+                    "INSTREAM-ID",
+                    tag.instream_id().is_some_and(|i| i.known().is_some()),
+                );
+            }
+            hls::Tag::ServerControl(tag) => {
+                self.numeric("CAN-SKIP-UNTIL", tag.try_can_skip_until());
+                self.numeric("HOLD-BACK", tag.try_hold_back());
+                self.numeric("PART-HOLD-BACK", tag.try_part_hold_back());
+            }
+            hls::Tag::RenditionReport(tag) => self.numeric("LAST-PART", tag.try_last_part()),
+            hls::Tag::PreloadHint(tag) => {
+                self.enumerated("TYPE", tag.hint_type().known().is_some());
+                self.numeric("BYTERANGE-START", tag.try_byterange_start().map(Some));
+                self.numeric("BYTERANGE-LENGTH", tag.try_byterange_length());
+            }
+            hls::Tag::Daterange(tag) => {
+                self.numeric("DURATION", tag.try_duration());
+                self.numeric("PLANNED-DURATION", tag.try_planned_duration());
+            }
+            hls::Tag::Key(tag) => self.enumerated("METHOD", tag.method().known().is_some()),
+            hls::Tag::SessionKey(tag) => self.enumerated("METHOD", tag.method().known().is_some()),
+            hls::Tag::SessionData(tag) => self.enumerated("FORMAT", tag.format().known().is_some()),
+            _ => (),
+        }
+    }
+
+    fn numeric<T>(
+        &mut self,
+        attribute: &'static str,
+        result: Result<Option<T>, NumericAttributeError>,
+    ) {
+        if result.is_err() {
+            self.present(attribute, false);
+        }
+    }
+
+    fn present(&mut self, attribute: &'static str, interpreted: bool) {
+        if !interpreted && let Some(value) = self.raw_value(attribute) {
+            self.kinds
+                .push(LeniencyDivergenceKind::AttributeIgnored { attribute, value });
+        }
+    }
+
+    fn enumerated(&mut self, attribute: &'static str, known: bool) {
+        if !known && let Some(value) = self.raw_value(attribute) {
+            self.kinds
+                .push(LeniencyDivergenceKind::UnknownEnumeration { attribute, value });
+        }
+    }
+
+    fn raw_value(&self, attribute: &str) -> Option<&'a str> {
+        match self.attributes.get(attribute)? {
+            AttributeValue::Quoted(value) => Some(value),
+            AttributeValue::Unquoted(value) => value.try_as_utf_8().ok(),
+        }
+    }
+}
+
+fn is_decimal_integer(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_decimal_floating_point(value: &str) -> bool {
+    match value.split_once('.') {
+        Some((integer, fraction)) => is_decimal_integer(integer) && is_decimal_integer(fraction),
+        None => is_decimal_integer(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn kinds(playlist: &str) -> Vec<LeniencyDivergenceKind<'_>> {
+        leniency_report(playlist)
+            .into_iter()
+            .map(|divergence| divergence.kind)
+            .collect()
+    }
+
+    #[test]
+    fn strictly_valid_playlist_should_have_no_divergences() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:3\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXTINF:3.840,title\n",
+            "a.ts\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+            "#EXTINF:4,\n",
+            "b.ts\n",
+        );
+        assert_eq!(Vec::<LeniencyDivergenceKind>::new(), kinds(PLAYLIST));
+    }
+
+    #[test]
+    fn divergences_should_be_reported() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:AVERAGE-BANDWIDTH=1000\n",
+            "a.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000,RESOLUTION=big,VIDEO-RANGE=HDR,FRAME-RATE=fast\n",
+            "b.m3u8\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"a\",NAME=\"a\",INSTREAM-ID=\"CC9\"\n",
+            "#EXT-X-KEY:METHOD=ROT13\n",
+            "#EXTINF:3.84,\n",
+            "c.ts\n",
+        );
+        assert_eq!(
+            vec![
+                LeniencyDivergenceKind::TagRejected(ValidationError::MissingRequiredAttribute(
+                    "BANDWIDTH"
+                )),
+                LeniencyDivergenceKind::AttributeIgnored {
+                    attribute: "FRAME-RATE",
+                    value: "fast",
+                },
+                LeniencyDivergenceKind::AttributeIgnored {
+                    attribute: "RESOLUTION",
+                    value: "big",
+                },
+                LeniencyDivergenceKind::UnknownEnumeration {
+                    attribute: "VIDEO-RANGE",
+                    value: "HDR",
+                },
+                LeniencyDivergenceKind::UnknownEnumeration {
+                    attribute: "INSTREAM-ID",
+                    value: "CC9",
+                },
+                LeniencyDivergenceKind::UnknownEnumeration {
+                    attribute: "METHOD",
+                    value: "ROT13",
+                },
+                LeniencyDivergenceKind::DurationReinterpreted {
+                    value: "3.84",
+                    duration: 3.84,
+                },
+            ],
+            kinds(PLAYLIST)
+        );
+        assert_eq!(
+            vec![2, 4, 4, 4, 6, 7, 8],
+            leniency_report(PLAYLIST)
+                .iter()
+                .map(|divergence| divergence.line_number)
+                .collect::<Vec<_>>()
+        );
+    }
+}
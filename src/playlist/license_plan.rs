@@ -0,0 +1,326 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    date::DateTime,
+    tag::{
+        KnownTag,
+        hls::{self, EnumeratedString, KeyformatVersions, Method, TagName},
+    },
+};
+
+/// Plans the license (key) requests needed to play a presentation, such that a DRM pre-warming
+/// service can acquire each license once, ahead of it being needed.
+///
+/// The keys are gathered from the `EXT-X-SESSION-KEY` tags of the Multivariant Playlist, and from
+/// the `EXT-X-KEY` tags of each of the Media Playlists (in the order provided). Keys are
+/// deduplicated by their `KEYFORMAT` and `URI`, with the `KEYFORMATVERSIONS` of each occurrence
+/// merged into [`LicenseRequest::keyformat_versions`]. Keys with a `METHOD` of `NONE` (and keys
+/// without a `URI`) do not need a license, and so are not included.
+///
+/// For keys used in a Media Playlist, [`LicenseRequest::first_needed`] describes the first Media
+/// Segment that the key applies to (which is the earliest point that the license is needed). A key
+/// applies to each Media Segment that follows it, until another `EXT-X-KEY` with the same
+/// `KEYFORMAT` replaces it, or until an `EXT-X-KEY` with a `METHOD` of `NONE` clears every key.
+/// When a key is used in more than one Media Playlist, the segment described is from the first of
+/// those playlists.
+///
+/// The requests are provided in the order that the keys are first found (session keys first).
+/// Lines that cannot be parsed are skipped.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::license_plan;
+/// const MULTIVARIANT: &str = r#"#EXTM3U
+/// #EXT-X-SESSION-KEY:METHOD=SAMPLE-AES,URI="skd://a",KEYFORMAT="com.apple.streamingkeydelivery"
+/// #EXT-X-STREAM-INF:BANDWIDTH=1280000
+/// low.m3u8
+/// "#;
+/// const MEDIA: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MEDIA-SEQUENCE:100
+/// #EXT-X-KEY:METHOD=SAMPLE-AES,URI="skd://a",KEYFORMAT="com.apple.streamingkeydelivery",KEYFORMATVERSIONS="1/2"
+/// #EXTINF:4
+/// 100.mp4
+/// #EXT-X-KEY:METHOD=SAMPLE-AES,URI="skd://b",KEYFORMAT="com.apple.streamingkeydelivery"
+/// #EXTINF:4
+/// 101.mp4
+/// "#;
+///
+/// let plan = license_plan(MULTIVARIANT, [MEDIA]);
+/// assert_eq!(2, plan.len());
+/// assert_eq!("skd://a", plan[0].uri);
+/// assert!(plan[0].session_key);
+/// assert_eq!("1/2", plan[0].keyformat_versions.to_string());
+/// assert_eq!(Some(100), plan[0].first_needed.as_ref().map(|f| f.media_sequence));
+/// assert_eq!("skd://b", plan[1].uri);
+/// assert!(!plan[1].session_key);
+/// assert_eq!(Some(101), plan[1].first_needed.as_ref().map(|f| f.media_sequence));
+/// ```
+pub fn license_plan<'a>(
+    multivariant_playlist: &str,
+    media_playlists: impl IntoIterator<Item = &'a str>,
+) -> Vec<LicenseRequest> {
+    let mut plan = Plan::default();
+    let mut reader = Reader::from_str(
+        multivariant_playlist,
+        ParsingOptions::builder()
+            .with_tags([TagName::SessionKey])
+            .build(),
+    );
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::SessionKey(key))))) => {
+                if key.method() != EnumeratedString::Known(Method::None) {
+                    let index = plan.request(key.keyformat(), key.uri());
+                    plan.requests[index].session_key = true;
+                    plan.merge_versions(index, key.keyformat_versions().ok());
+                }
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    for (media_playlist, playlist) in media_playlists.into_iter().enumerate() {
+        plan.add_media_playlist(media_playlist, playlist);
+    }
+    plan.requests
+}
+
+/// A license (key) request planned by [`license_plan`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct LicenseRequest {
+    /// The `KEYFORMAT` of the key (`identity` when the attribute is absent).
+    pub keyformat: String,
+    /// The `URI` of the key.
+    pub uri: String,
+    /// The `KEYFORMATVERSIONS` of the key, merged from each occurrence (in the order first listed).
+    pub keyformat_versions: KeyformatVersions,
+    /// Whether the key was declared in an `EXT-X-SESSION-KEY` of the Multivariant Playlist.
+    pub session_key: bool,
+    /// The first Media Segment that the key applies to, if the key was used in a Media Playlist.
+    pub first_needed: Option<FirstNeeded>,
+}
+
+/// The first Media Segment that a key applies to (see [`LicenseRequest::first_needed`]).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct FirstNeeded {
+    /// The index of the Media Playlist (in the order provided to [`license_plan`]).
+    pub media_playlist: usize,
+    /// The Media Sequence Number of the segment (saturating at `u64::MAX`).
+    pub media_sequence: u64,
+    /// The Program Date Time of the segment, when the playlist provides one (the date of the most
+    /// recent `EXT-X-PROGRAM-DATE-TIME` is extended by the durations of the segments since).
+    pub program_date_time: Option<DateTime>,
+}
+
+#[derive(Default)]
+struct Plan {
+    requests: Vec<LicenseRequest>,
+}
+
+impl Plan {
+    // Provides the index of the request for the key, adding a request if the key is new.
+    fn request(&mut self, keyformat: &str, uri: &str) -> usize {
+        if let Some(index) = self
+            .requests
+            .iter()
+            .position(|r| r.keyformat == keyformat && r.uri == uri)
+        {
+            return index;
+        }
+        self.requests.push(LicenseRequest {
+            keyformat: keyformat.to_string(),
+            uri: uri.to_string(),
+            keyformat_versions: KeyformatVersions::new([]),
+            session_key: false,
+            first_needed: None,
+        });
+        self.requests.len() - 1
+    }
+
+    fn merge_versions(&mut self, index: usize, versions: Option<KeyformatVersions>) {
+        for version in versions.into_iter().flatten() {
+            self.requests[index].keyformat_versions.insert(version);
+        }
+    }
+
+    fn add_media_playlist(&mut self, media_playlist: usize, playlist: &str) {
+        let mut reader = Reader::from_str(
+            playlist,
+            ParsingOptions::builder()
+                .with_tags([
+                    TagName::Key,
+                    TagName::MediaSequence,
+                    TagName::Inf,
+                    TagName::ProgramDateTime,
+                ])
+                .build(),
+        );
+        // The keys in effect, as the keyformat along with the index of the request.
+        let mut in_effect: Vec<(String, usize)> = Vec::new();
+        let mut media_sequence: u64 = 0;
+        let mut program_date_time: Option<DateTime> = None;
+        let mut duration = 0.0;
+        loop {
+            match reader.read_line() {
+                Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => match tag {
+                    hls::Tag::Key(key) => {
+                        if key.method() == EnumeratedString::Known(Method::None) {
+                            in_effect.clear();
+                            continue;
+                        }
+                        in_effect.retain(|(keyformat, _)| keyformat != key.keyformat());
+                        let Some(uri) = key.uri() else {
+                            continue;
+                        };
+                        let index = self.request(key.keyformat(), uri);
+                        self.merge_versions(index, key.keyformat_versions().ok());
+                        in_effect.push((key.keyformat().to_string(), index));
+                    }
+                    hls::Tag::MediaSequence(tag) => media_sequence = tag.media_sequence(),
+                    hls::Tag::Inf(tag) => duration = tag.duration(),
+                    hls::Tag::ProgramDateTime(tag) => {
+                        program_date_time = Some(tag.program_date_time())
+                    }
+                    _ => (),
+                },
+                Ok(Some(HlsLine::Uri(_))) => {
+                    for (_, index) in &in_effect {
+                        let request = &mut self.requests[*index];
+                        if request.first_needed.is_none() {
+                            request.first_needed = Some(FirstNeeded {
+                                media_playlist,
+                                media_sequence,
+                                program_date_time,
+                            });
+                        }
+                    }
+                    media_sequence = media_sequence.saturating_add(1);
+                    program_date_time = program_date_time.map(|date| date.add_seconds(duration));
+                    duration = 0.0;
+                }
+                Ok(Some(_)) | Err(_) => (),
+                Ok(None) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_time;
+    use pretty_assertions::assert_eq;
+
+    const FAIRPLAY: &str = "com.apple.streamingkeydelivery";
+    const WIDEVINE: &str = "urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed";
+
+    #[test]
+    fn keys_should_be_deduplicated_across_playlists_with_first_needed_segment() {
+        const MULTIVARIANT: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-SESSION-KEY:METHOD=SAMPLE-AES,URI=\"skd://a\",KEYFORMAT=\"com.apple.streamingkeydelivery\",KEYFORMATVERSIONS=\"1\"\n",
+            "#EXT-X-SESSION-KEY:METHOD=SAMPLE-AES,URI=\"data:w\",KEYFORMAT=\"urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed\"\n",
+        );
+        const LOW: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00Z\n",
+            "#EXTINF:4\n",
+            "10.mp4\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://a\",KEYFORMAT=\"com.apple.streamingkeydelivery\",KEYFORMATVERSIONS=\"2/1\"\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"data:w\",KEYFORMAT=\"urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed\"\n",
+            "#EXTINF:4\n",
+            "11.mp4\n",
+            "#EXT-X-KEY:METHOD=NONE\n",
+            "#EXTINF:4\n",
+            "12.mp4\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+            "#EXTINF:4\n",
+            "13.mp4\n",
+        );
+        const HIGH: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"unused\",KEYFORMAT=\"other\"\n",
+        );
+        let first_needed = |media_playlist, media_sequence, program_date_time| {
+            Some(FirstNeeded {
+                media_playlist,
+                media_sequence,
+                program_date_time,
+            })
+        };
+        assert_eq!(
+            vec![
+                LicenseRequest {
+                    keyformat: FAIRPLAY.to_string(),
+                    uri: "skd://a".to_string(),
+                    keyformat_versions: KeyformatVersions::new([1, 2]),
+                    session_key: true,
+                    first_needed: first_needed(
+                        0,
+                        11,
+                        Some(date_time!(2025-01-01 T 00:00:04.000 0:0))
+                    ),
+                },
+                LicenseRequest {
+                    keyformat: WIDEVINE.to_string(),
+                    uri: "data:w".to_string(),
+                    keyformat_versions: KeyformatVersions::new([1]),
+                    session_key: true,
+                    first_needed: first_needed(
+                        0,
+                        11,
+                        Some(date_time!(2025-01-01 T 00:00:04.000 0:0))
+                    ),
+                },
+                LicenseRequest {
+                    keyformat: "identity".to_string(),
+                    uri: "key".to_string(),
+                    keyformat_versions: KeyformatVersions::new([1]),
+                    session_key: false,
+                    first_needed: first_needed(
+                        0,
+                        13,
+                        Some(date_time!(2025-01-01 T 00:00:12.000 0:0))
+                    ),
+                },
+                LicenseRequest {
+                    keyformat: "other".to_string(),
+                    uri: "unused".to_string(),
+                    keyformat_versions: KeyformatVersions::new([1]),
+                    session_key: false,
+                    first_needed: None,
+                },
+            ],
+            license_plan(MULTIVARIANT, [LOW, HIGH])
+        );
+    }
+
+    #[test]
+    fn extreme_sequences_and_durations_should_saturate() {
+        const MEDIA: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA-SEQUENCE:18446744073709551615\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00Z\n",
+            "#EXTINF:1e308,\n",
+            "a.mp4\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+            "#EXTINF:4\n",
+            "b.mp4\n",
+        );
+        assert_eq!(
+            vec![Some(FirstNeeded {
+                media_playlist: 0,
+                media_sequence: u64::MAX,
+                program_date_time: Some(date_time!(9999-12-31 T 23:59:59.999)),
+            })],
+            license_plan("#EXTM3U\n", [MEDIA])
+                .into_iter()
+                .map(|request| request.first_needed)
+                .collect::<Vec<_>>()
+        );
+    }
+}
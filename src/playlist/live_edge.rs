@@ -0,0 +1,305 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    date::DateTime,
+    tag::{KnownTag, hls},
+};
+
+/// Calculates the recommended live playback position of a Media Playlist.
+///
+/// The HLS specification recommends that clients do not start playback closer to the end of the
+/// playlist than the hold back (the `HOLD-BACK` attribute of `EXT-X-SERVER-CONTROL`, which defaults
+/// to 3 times the target duration). When the playlist is a Low-Latency HLS playlist (it has
+/// `EXT-X-PART-INF`), the `PART-HOLD-BACK` attribute is used instead (defaulting to 3 times the part
+/// target), and the position is resolved to a Partial Segment rather than a Media Segment.
+///
+/// The resolved segment (or part) is the last one that starts at or before the recommended
+/// position, meaning that playback starting at it is at least the hold back distance from the end
+/// of the playlist.
+///
+/// `None` is returned when the playlist has ended (has `EXT-X-ENDLIST`), when it contains no
+/// segments (or parts), or when no hold back can be determined (there is no `EXT-X-TARGETDURATION`
+/// and no explicit hold back).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::live_edge;
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MEDIA-SEQUENCE:100
+/// #EXTINF:4
+/// 100.ts
+/// #EXTINF:4
+/// 101.ts
+/// #EXTINF:4
+/// 102.ts
+/// #EXTINF:4
+/// 103.ts
+/// #EXTINF:4
+/// 104.ts
+/// "#;
+///
+/// let edge = live_edge(PLAYLIST).expect("playlist should have a live edge");
+/// assert_eq!(12.0, edge.hold_back);
+/// assert_eq!(20.0, edge.duration);
+/// assert_eq!(8.0, edge.position);
+/// assert_eq!(102, edge.media_sequence);
+/// assert_eq!(None, edge.part_index);
+/// assert_eq!("102.ts", edge.uri);
+/// assert_eq!(8.0, edge.start);
+/// ```
+pub fn live_edge(playlist: &str) -> Option<LiveEdge<'_>> {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_parsing_for_targetduration()
+            .with_parsing_for_media_sequence()
+            .with_parsing_for_endlist()
+            .with_parsing_for_part_inf()
+            .with_parsing_for_server_control()
+            .with_parsing_for_inf()
+            .with_parsing_for_program_date_time()
+            .build(),
+    );
+    let mut target_duration = None;
+    let mut part_target = None;
+    let mut hold_back = None;
+    let mut part_hold_back = None;
+    let mut media_sequence: u64 = 0;
+    let mut segment_start = 0.0;
+    let mut segment_program_date_time = None;
+    let mut segment_duration = 0.0;
+    let mut parts_duration = 0.0;
+    let mut part_index = 0;
+    let mut segments = Vec::new();
+    let mut parts = Vec::new();
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => match tag {
+                hls::Tag::Targetduration(tag) => target_duration = Some(tag.target_duration()),
+                hls::Tag::MediaSequence(tag) => media_sequence = tag.media_sequence(),
+                hls::Tag::Endlist(_) => return None,
+                hls::Tag::PartInf(tag) => part_target = Some(tag.part_target()),
+                hls::Tag::ServerControl(tag) => {
+                    hold_back = tag.hold_back();
+                    part_hold_back = tag.part_hold_back();
+                }
+                hls::Tag::Inf(tag) => segment_duration = tag.duration(),
+                hls::Tag::ProgramDateTime(tag) => {
+                    segment_program_date_time = Some(tag.program_date_time())
+                }
+                _ => (),
+            },
+            Ok(Some(HlsLine::UnknownTag(tag))) if tag.name() == "-X-PART" => {
+                let Some(list) = tag.value().and_then(|v| v.try_as_attribute_map().ok()) else {
+                    continue;
+                };
+                let (Some(uri), Some(duration)) = (
+                    list.get("URI").and_then(|v| v.quoted()),
+                    list.get("DURATION")
+                        .and_then(|v| v.unquoted())
+                        .and_then(|v| v.try_as_decimal_floating_point().ok()),
+                ) else {
+                    continue;
+                };
+                parts.push(LiveEdge {
+                    hold_back: 0.0,
+                    duration: 0.0,
+                    position: 0.0,
+                    media_sequence,
+                    part_index: Some(part_index),
+                    uri,
+                    start: segment_start + parts_duration,
+                    program_date_time: segment_program_date_time
+                        .map(|date: DateTime| date.add_seconds(parts_duration)),
+                });
+                part_index += 1;
+                parts_duration += duration;
+            }
+            Ok(Some(input_uri!(uri))) => {
+                segments.push(LiveEdge {
+                    hold_back: 0.0,
+                    duration: 0.0,
+                    position: 0.0,
+                    media_sequence,
+                    part_index: None,
+                    uri,
+                    start: segment_start,
+                    program_date_time: segment_program_date_time,
+                });
+                media_sequence = media_sequence.saturating_add(1);
+                segment_start += segment_duration;
+                segment_program_date_time =
+                    segment_program_date_time.map(|date| date.add_seconds(segment_duration));
+                segment_duration = 0.0;
+                parts_duration = 0.0;
+                part_index = 0;
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    // Parts of the segment that is still being produced extend beyond the last full segment.
+    let duration = segment_start + parts_duration;
+    let (hold_back, candidates) = match part_target {
+        Some(part_target) => (part_hold_back.unwrap_or(3.0 * part_target), parts),
+        None => (
+            hold_back.or(target_duration.map(|t| 3.0 * t as f64))?,
+            segments,
+        ),
+    };
+    let position = (duration - hold_back).max(0.0);
+    let index = candidates
+        .iter()
+        .rposition(|candidate| candidate.start <= position)?;
+    let edge = candidates.into_iter().nth(index)?;
+    Some(LiveEdge {
+        hold_back,
+        duration,
+        position,
+        ..edge
+    })
+}
+
+/// The recommended live playback position of a Media Playlist.
+///
+/// See [`live_edge`] for more information.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LiveEdge<'a> {
+    /// The hold back that was applied in seconds (either `PART-HOLD-BACK` or `HOLD-BACK`, or the
+    /// default for whichever applies).
+    pub hold_back: f64,
+    /// The total duration of the playlist in seconds (including any Partial Segments that follow the
+    /// last complete Media Segment).
+    pub duration: f64,
+    /// The recommended playback position in seconds, relative to the start of the playlist (this is
+    /// `duration - hold_back`, or `0.0` if the playlist is shorter than the hold back).
+    pub position: f64,
+    /// The media sequence number of the segment that contains the position (for a Partial Segment,
+    /// this is the media sequence number of the parent segment). This saturates at `u64::MAX`.
+    pub media_sequence: u64,
+    /// The index of the Partial Segment within its parent segment (only set for Low-Latency HLS
+    /// playlists).
+    pub part_index: Option<u64>,
+    /// The URI of the segment (or Partial Segment) to start playback from.
+    pub uri: &'a str,
+    /// The start time of the segment (or Partial Segment) in seconds, relative to the start of the
+    /// playlist. This is at or before `position`.
+    pub start: f64,
+    /// The program date time of the start of the segment (or Partial Segment), derived from the
+    /// most recent `EXT-X-PROGRAM-DATE-TIME`.
+    pub program_date_time: Option<DateTime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_time;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn low_latency_playlist_should_use_part_hold_back() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,PART-HOLD-BACK=3.0\n",
+            "#EXT-X-PART-INF:PART-TARGET=1.0\n",
+            "#EXT-X-MEDIA-SEQUENCE:20\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+            "#EXTINF:4\n",
+            "20.mp4\n",
+            "#EXT-X-PART:DURATION=1.0,URI=\"21.0.mp4\",INDEPENDENT=YES\n",
+            "#EXT-X-PART:DURATION=1.0,URI=\"21.1.mp4\"\n",
+            "#EXT-X-PART:DURATION=1.0,URI=\"21.2.mp4\"\n",
+            "#EXT-X-PART:DURATION=1.0,URI=\"21.3.mp4\"\n",
+            "#EXTINF:4\n",
+            "21.mp4\n",
+            "#EXT-X-PART:DURATION=1.0,URI=\"22.0.mp4\",INDEPENDENT=YES\n",
+            "#EXT-X-PART:DURATION=1.0,URI=\"22.1.mp4\"\n",
+            "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"22.2.mp4\"\n",
+        );
+        assert_eq!(
+            Some(LiveEdge {
+                hold_back: 3.0,
+                duration: 10.0,
+                position: 7.0,
+                media_sequence: 21,
+                part_index: Some(3),
+                uri: "21.3.mp4",
+                start: 7.0,
+                program_date_time: Some(date_time!(2025-01-01 T 00:00:07.000)),
+            }),
+            live_edge(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn hold_back_should_be_used_when_present() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:6\n",
+            "#EXT-X-SERVER-CONTROL:HOLD-BACK=10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+            "#EXTINF:6\n",
+            "0.ts\n",
+            "#EXTINF:6\n",
+            "1.ts\n",
+            "#EXTINF:6\n",
+            "2.ts\n",
+        );
+        assert_eq!(
+            Some(LiveEdge {
+                hold_back: 10.0,
+                duration: 18.0,
+                position: 8.0,
+                media_sequence: 1,
+                part_index: None,
+                uri: "1.ts",
+                start: 6.0,
+                program_date_time: Some(date_time!(2025-01-01 T 00:00:06.000)),
+            }),
+            live_edge(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn short_playlist_should_start_from_first_segment() {
+        const PLAYLIST: &str = "#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:4\n0.ts\n";
+        let edge = live_edge(PLAYLIST).expect("playlist should have a live edge");
+        assert_eq!(0.0, edge.position);
+        assert_eq!("0.ts", edge.uri);
+        assert_eq!(None, edge.program_date_time);
+    }
+
+    #[test]
+    fn ended_or_empty_playlist_should_not_have_live_edge() {
+        assert_eq!(
+            None,
+            live_edge("#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:4\n0.ts\n#EXT-X-ENDLIST\n")
+        );
+        assert_eq!(None, live_edge("#EXTM3U\n#EXT-X-TARGETDURATION:4\n"));
+        assert_eq!(None, live_edge("#EXTM3U\n#EXTINF:4\n0.ts\n"));
+    }
+
+    #[test]
+    fn media_sequence_should_saturate_at_the_largest_value() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-MEDIA-SEQUENCE:18446744073709551614\n",
+            "#EXTINF:4\n",
+            "a.ts\n",
+            "#EXTINF:4\n",
+            "b.ts\n",
+            "#EXTINF:4\n",
+            "c.ts\n",
+            "#EXTINF:4\n",
+            "d.ts\n",
+            "#EXTINF:4\n",
+            "e.ts\n",
+        );
+        let edge = live_edge(PLAYLIST).unwrap();
+        assert_eq!("c.ts", edge.uri);
+        assert_eq!(u64::MAX, edge.media_sequence);
+    }
+}
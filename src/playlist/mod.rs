@@ -0,0 +1,114 @@
+//! Helpers that operate over the lines of a complete playlist.
+//!
+//! The core of the library is line-based (see [`crate::Reader`]), which is intentional, as it keeps
+//! parsing fast and flexible. However, there is some information that can only be derived by
+//! considering the context in which a line was found (for example, a URI line is a Media Segment URI
+//! in a Media Playlist, but a Variant Stream URI when it follows `EXT-X-STREAM-INF`). This module
+//! provides helpers that take care of that cross-line bookkeeping.
+
+// A pattern matching a URI line read from the input, binding the URI with the lifetime of the input.
+// The reader always borrows URI lines from the input (a URI is only owned when the line has been
+// constructed or modified), and so the helpers here can hold on to the URIs they find without
+// copying them.
+macro_rules! input_uri {
+    ($uri:pat) => {
+        $crate::HlsLine::Uri(::std::borrow::Cow::Borrowed($uri))
+    };
+}
+
+mod ad_breaks;
+mod audio_capabilities;
+mod bandwidth_reconciliation;
+mod cache_advice;
+mod cache_buster;
+mod clip;
+mod compact_uris;
+mod daterange_alignment;
+mod daterange_durations;
+mod delivery_directives;
+mod document;
+mod download_plan;
+mod gap_fill;
+mod history;
+mod i_frames;
+mod join;
+mod key_dedup;
+mod ladder_drift;
+mod leniency;
+mod license_plan;
+mod live_edge;
+mod parse;
+mod part_groups;
+mod patch;
+mod pathway;
+mod percent_encoding;
+mod prefetch;
+mod range_requests;
+mod removed_dateranges;
+mod renditions;
+mod segment_editor;
+mod segment_index;
+mod segment_list;
+mod session_data;
+mod size_budget;
+mod template;
+mod timeline;
+mod uris;
+
+pub use ad_breaks::{AdBreak, AdBreakSignal, ad_breaks};
+pub use audio_capabilities::{AudioCapabilities, compatible_audio_renditions};
+pub use bandwidth_reconciliation::{
+    BandwidthAttribute, BandwidthDeviation, MeasuredBandwidth, measure_bandwidth,
+    reconcile_bandwidth,
+};
+pub use cache_advice::{CacheAdvice, CachePolicy, cache_advice};
+pub use cache_buster::CacheBuster;
+pub use clip::{ClipOptions, clip};
+pub use compact_uris::{CompactUriList, UriTemplate};
+pub use daterange_alignment::{
+    ALIGNMENT_TOLERANCE, BoundaryAlignment, DaterangeAlignment, daterange_alignment,
+};
+pub use daterange_durations::{DaterangeDurationEvent, DaterangeDurationTracker};
+pub use delivery_directives::{DeliveryDirectives, SkipDirective};
+pub use document::{
+    MediaPlaylist, MediaSegment, MultivariantPlaylist, Playlist, RenditionGroup, VariantRenditions,
+    VariantStream,
+};
+pub use download_plan::{DownloadRequest, plan_downloads};
+pub use gap_fill::{CapturedSegment, GapDurationEstimate, GapFill, GapFillOptions, fill_gaps};
+pub use history::{MemoryStats, PlaylistHistory, PlaylistVersion};
+pub use i_frames::{IFrameDiagnostic, validate_i_frames_only};
+pub use join::{InterstitialJoinReason, JoinPoint, JoinTime, join_point};
+pub use key_dedup::{RedundantKey, dedup_session_keys, redundant_keys};
+pub use ladder_drift::{LadderDrift, LadderDriftKind, LadderEntry, VariantKey, ladder_drift};
+pub use leniency::{LeniencyDivergence, LeniencyDivergenceKind, leniency_report};
+pub use license_plan::{FirstNeeded, LicenseRequest, license_plan};
+pub use live_edge::{LiveEdge, live_edge};
+pub use parse::{
+    ParseDiagnostic, ParseDiagnosticKind, ParsedPlaylist, parse_media_playlist,
+    parse_multivariant_playlist,
+};
+pub use part_groups::{PartGroup, PartGroups, part_groups};
+pub use patch::{PatchOp, PlaylistPatch};
+pub use pathway::filter_pathway;
+pub use percent_encoding::{
+    PercentEncoding, PercentEncodingNormalizer, has_improper_percent_encoding,
+    normalize_percent_encoding,
+};
+pub use prefetch::{PrefetchRange, PrefetchResource, prefetch_list};
+pub use range_requests::RangeRequestTracker;
+pub use removed_dateranges::RemovedDaterangeTracker;
+pub use renditions::{Rendition, RenditionVariant, renditions};
+pub use segment_editor::SegmentEditor;
+pub use segment_index::SegmentIndex;
+pub use segment_list::{Encryption, ExportedSegment, InitSegment, SegmentList, segment_list};
+pub use session_data::{
+    ResolvedSessionData, SessionDataContent, SessionDataResolver, resolve_session_data,
+    select_session_data,
+};
+pub use size_budget::{
+    DroppedContent, SizeBudgetPolicy, SizeBudgetReport, SizeReduction, fit_to_size,
+};
+pub use template::{PlaylistTemplate, TemplateValues};
+pub use timeline::{Timeline, TimelineSegment, timeline};
+pub use uris::{PlaylistUri, UriKind, Uris, uris};
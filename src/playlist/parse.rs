@@ -0,0 +1,211 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    error::{PlaylistKindError, SyntaxError, ValidationError},
+    playlist::{MediaPlaylist, MultivariantPlaylist, Playlist},
+    tag::{KnownTag, hls},
+};
+
+/// Parses the string as a Media Playlist.
+///
+/// This is the simplest way to get started with the library: the playlist is read with
+/// [`ParsingOptions::default`] (which parses all tags), assembled into a [`MediaPlaylist`], and
+/// any problems found along the way are collected into the [`ParsedPlaylist::diagnostics`]
+/// rather than stopping the parse. When more control is needed (e.g. to only parse some tags, to
+/// use custom tags, or to stream the lines), use the [`Reader`] directly.
+///
+/// An error is returned only when the playlist is a Multivariant Playlist (see [`Playlist`]).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{parse_media_playlist, playlist::ParseDiagnosticKind};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-KEY:URI="key"
+/// #EXTINF:4
+/// 0.ts
+/// #EXTINF:4
+/// 1.ts
+/// "#;
+///
+/// let parsed = parse_media_playlist(PLAYLIST)?;
+/// assert_eq!(2, parsed.playlist.segments.len());
+/// assert_eq!(8.0, parsed.playlist.duration());
+/// // The EXT-X-KEY is missing the required METHOD attribute.
+/// assert_eq!(1, parsed.diagnostics.len());
+/// assert_eq!(3, parsed.diagnostics[0].line_number);
+/// assert!(matches!(parsed.diagnostics[0].kind, ParseDiagnosticKind::TagRejected(_)));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn parse_media_playlist(
+    playlist: &str,
+) -> Result<ParsedPlaylist<'_, MediaPlaylist<'_>>, PlaylistKindError> {
+    match parse(playlist) {
+        (Playlist::Media(playlist), diagnostics) => Ok(ParsedPlaylist {
+            playlist,
+            diagnostics,
+        }),
+        (Playlist::Multivariant(_), _) => Err(PlaylistKindError::ExpectedMediaPlaylist),
+    }
+}
+
+/// Parses the string as a Multivariant Playlist.
+///
+/// This is the Multivariant Playlist counterpart of [`parse_media_playlist`], and so the same
+/// defaults apply. An error is returned only when the playlist is a Media Playlist (see
+/// [`Playlist`]).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::parse_multivariant_playlist;
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-STREAM-INF:BANDWIDTH=1280000
+/// low.m3u8
+/// #EXT-X-STREAM-INF:BANDWIDTH=2560000
+/// mid.m3u8
+/// "#;
+///
+/// let parsed = parse_multivariant_playlist(PLAYLIST)?;
+/// assert!(parsed.diagnostics.is_empty());
+/// let uris = parsed.playlist.variants.iter().map(|v| v.uri.as_ref()).collect::<Vec<_>>();
+/// assert_eq!(vec!["low.m3u8", "mid.m3u8"], uris);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn parse_multivariant_playlist(
+    playlist: &str,
+) -> Result<ParsedPlaylist<'_, MultivariantPlaylist<'_>>, PlaylistKindError> {
+    match parse(playlist) {
+        (Playlist::Multivariant(playlist), diagnostics) => Ok(ParsedPlaylist {
+            playlist,
+            diagnostics,
+        }),
+        (Playlist::Media(_), _) => Err(PlaylistKindError::ExpectedMultivariantPlaylist),
+    }
+}
+
+/// A playlist parsed via [`parse_media_playlist`] or [`parse_multivariant_playlist`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParsedPlaylist<'a, P> {
+    /// The assembled playlist.
+    pub playlist: P,
+    /// The problems found while parsing (in playlist order).
+    pub diagnostics: Vec<ParseDiagnostic<'a>>,
+}
+
+/// A problem found while parsing a playlist (see [`ParsedPlaylist`]).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ParseDiagnostic<'a> {
+    /// The 1-based line number in the playlist.
+    pub line_number: usize,
+    /// The problem found.
+    pub kind: ParseDiagnosticKind<'a>,
+}
+
+/// The kind of problem found while parsing a playlist (see [`ParseDiagnostic`]).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ParseDiagnosticKind<'a> {
+    /// The playlist does not start with the `#EXTM3U` header.
+    MissingHeader,
+    /// The line could not be parsed, and so is not included in the playlist.
+    SyntaxError {
+        /// The line that could not be parsed.
+        line: &'a str,
+        /// The underlying error.
+        error: SyntaxError,
+    },
+    /// The tag failed validation, and so is included in the playlist as an unknown tag (within the
+    /// `other` lines) rather than being interpreted.
+    TagRejected(ValidationError),
+}
+
+fn parse(playlist: &str) -> (Playlist<'_>, Vec<ParseDiagnostic<'_>>) {
+    let mut reader = Reader::from_str(playlist, ParsingOptions::default());
+    let mut lines = Vec::new();
+    let mut diagnostics = Vec::new();
+    let mut has_header = false;
+    loop {
+        let kind = match reader.read_line() {
+            Ok(Some(line)) => {
+                if reader.lines_read() == 1 {
+                    has_header = matches!(line, HlsLine::KnownTag(KnownTag::Hls(hls::Tag::M3u(_))));
+                }
+                let kind = match &line {
+                    HlsLine::UnknownTag(tag) => {
+                        tag.validation_error().map(ParseDiagnosticKind::TagRejected)
+                    }
+                    _ => None,
+                };
+                lines.push(line);
+                kind
+            }
+            Ok(None) => break,
+            Err(error) => Some(ParseDiagnosticKind::SyntaxError {
+                line: error.errored_line,
+                error: error.error,
+            }),
+        };
+        if let Some(kind) = kind {
+            diagnostics.push(ParseDiagnostic {
+                line_number: reader.lines_read(),
+                kind,
+            });
+        }
+    }
+    if !has_header {
+        diagnostics.insert(
+            0,
+            ParseDiagnostic {
+                line_number: 1,
+                kind: ParseDiagnosticKind::MissingHeader,
+            },
+        );
+    }
+    (Playlist::from_lines(lines), diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn diagnostics_should_be_aggregated_in_playlist_order() {
+        const PLAYLIST: &str = concat!(
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-KEY:URI=\"key\"\n",
+            "#EXTINF:4\n",
+            "0.ts\n",
+        );
+        let parsed = parse_media_playlist(PLAYLIST).unwrap();
+        assert_eq!(1, parsed.playlist.segments.len());
+        assert_eq!(
+            vec![
+                ParseDiagnostic {
+                    line_number: 1,
+                    kind: ParseDiagnosticKind::MissingHeader,
+                },
+                ParseDiagnostic {
+                    line_number: 2,
+                    kind: ParseDiagnosticKind::TagRejected(
+                        ValidationError::MissingRequiredAttribute("METHOD")
+                    ),
+                },
+            ],
+            parsed.diagnostics
+        );
+    }
+
+    #[test]
+    fn playlist_of_the_wrong_kind_should_error() {
+        const MEDIA: &str = "#EXTM3U\n#EXTINF:4\n0.ts\n";
+        const MULTIVARIANT: &str = "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1\nlow.m3u8\n";
+        assert_eq!(
+            Err(PlaylistKindError::ExpectedMultivariantPlaylist),
+            parse_multivariant_playlist(MEDIA).map(|_| ())
+        );
+        assert_eq!(
+            Err(PlaylistKindError::ExpectedMediaPlaylist),
+            parse_media_playlist(MULTIVARIANT).map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    tag::{
+        KnownTag, NoCustomTag,
+        hls::{self, Inf, Part, PreloadHint, PreloadHintType, TagName},
+    },
+};
+
+/// Provides an iterator over the Partial Segments (`EXT-X-PART`) of a Low-Latency Media Playlist,
+/// grouped by the Media Segment that they belong to.
+///
+/// Each Partial Segment is grouped with the `EXTINF` and URI of the Media Segment that follows it
+/// (as per the HLS specification, the Partial Segments of a Media Segment appear before its URI
+/// line). A group is provided for every Media Segment, even those that have no Partial Segments
+/// (e.g. older segments that have fallen out of the Part Hold Back window), such that the groups
+/// line up with the segments of the playlist. The last group may be for a segment that is still
+/// being produced, and so has no URI; this group is only provided when it has Partial Segments or
+/// preload hints (`EXT-X-PRELOAD-HINT`).
+///
+/// This makes it simple for packagers (and validators) to check that the Partial Segments add up
+/// to the advertised segment duration (see [`PartGroup::duration_difference`]), find the last
+/// independent Partial Segment (see [`PartGroup::last_independent_part`]), and locate the hinted
+/// Partial Segment (see [`PartGroup::part_hint`]). Lines that cannot be parsed are skipped.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::part_groups;
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-PART-INF:PART-TARGET=1
+/// #EXTINF:4
+/// segment_1.mp4
+/// #EXT-X-PART:DURATION=1,URI="part_2.0.mp4",INDEPENDENT=YES
+/// #EXT-X-PART:DURATION=1,URI="part_2.1.mp4"
+/// #EXT-X-PART:DURATION=1,URI="part_2.2.mp4",INDEPENDENT=YES
+/// #EXT-X-PART:DURATION=0.5,URI="part_2.3.mp4"
+/// #EXTINF:4
+/// segment_2.mp4
+/// #EXT-X-PART:DURATION=1,URI="part_3.0.mp4",INDEPENDENT=YES
+/// #EXT-X-PRELOAD-HINT:TYPE=PART,URI="part_3.1.mp4"
+/// "#;
+///
+/// let mut groups = part_groups(PLAYLIST);
+///
+/// let group = groups.next().unwrap();
+/// assert_eq!(Some("segment_1.mp4"), group.uri);
+/// assert!(group.parts.is_empty());
+///
+/// let group = groups.next().unwrap();
+/// assert_eq!(Some("segment_2.mp4"), group.uri);
+/// assert_eq!(4, group.parts.len());
+/// assert_eq!(Some(-0.5), group.duration_difference());
+/// assert_eq!(Some("part_2.2.mp4"), group.last_independent_part().map(|part| part.uri()));
+///
+/// let group = groups.next().unwrap();
+/// assert!(!group.is_complete());
+/// assert_eq!(Some("part_3.1.mp4"), group.part_hint().map(|hint| hint.uri()));
+///
+/// assert!(groups.next().is_none());
+/// ```
+pub fn part_groups(playlist: &str) -> PartGroups<'_> {
+    PartGroups {
+        reader: Reader::from_str(
+            playlist,
+            ParsingOptions::builder()
+                .with_tags([TagName::Inf, TagName::Part, TagName::PreloadHint])
+                .build(),
+        ),
+        current: PartGroup::default(),
+        finished: false,
+    }
+}
+
+/// The Partial Segments of a Media Segment, as provided by [`part_groups`].
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PartGroup<'a> {
+    /// The `EXTINF` of the parent segment. This is `None` when the segment is still being produced
+    /// (or the playlist is missing the tag).
+    pub inf: Option<Inf<'a>>,
+    /// The URI of the parent segment. This is `None` when the segment is still being produced.
+    pub uri: Option<&'a str>,
+    /// The Partial Segments of the segment, in playlist order.
+    pub parts: Vec<Part<'a>>,
+    /// The preload hints found while the segment was being produced. The HLS specification places
+    /// these at the end of the playlist, and so only the last group is expected to have any.
+    pub preload_hints: Vec<PreloadHint<'a>>,
+}
+
+impl<'a> PartGroup<'a> {
+    /// Indicates whether the parent segment has been published (i.e. has a URI line).
+    pub fn is_complete(&self) -> bool {
+        self.uri.is_some()
+    }
+
+    /// The sum of the durations of the Partial Segments.
+    pub fn parts_duration(&self) -> f64 {
+        self.parts.iter().map(Part::duration).sum()
+    }
+
+    /// The difference between the sum of the durations of the Partial Segments and the `EXTINF`
+    /// duration of the parent segment (negative when the parts fall short of the segment).
+    ///
+    /// This is `None` when there are no Partial Segments or no `EXTINF`, as there is nothing to
+    /// compare. The durations are floating point, and so callers should compare the result against
+    /// a tolerance rather than zero.
+    pub fn duration_difference(&self) -> Option<f64> {
+        if self.parts.is_empty() {
+            return None;
+        }
+        let inf = self.inf.as_ref()?;
+        Some(self.parts_duration() - inf.duration())
+    }
+
+    /// The last Partial Segment that has `INDEPENDENT=YES`.
+    pub fn last_independent_part(&self) -> Option<&Part<'a>> {
+        self.parts.iter().rev().find(|part| part.independent())
+    }
+
+    /// The preload hint of `TYPE=PART`, which indicates the next Partial Segment of the segment.
+    pub fn part_hint(&self) -> Option<&PreloadHint<'a>> {
+        self.preload_hints
+            .iter()
+            .find(|hint| matches!(hint.hint_type().known(), Some(PreloadHintType::Part)))
+    }
+}
+
+/// An iterator over the Partial Segments of a playlist, grouped by segment.
+///
+/// Constructed via [`part_groups`].
+#[derive(Debug)]
+pub struct PartGroups<'a> {
+    reader: Reader<&'a str, NoCustomTag>,
+    current: PartGroup<'a>,
+    finished: bool,
+}
+
+impl<'a> Iterator for PartGroups<'a> {
+    type Item = PartGroup<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        loop {
+            let line = match self.reader.read_line() {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(_) => continue,
+            };
+            match line {
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(tag))) => {
+                    self.current.inf = Some(tag);
+                }
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Part(tag))) => {
+                    self.current.parts.push(tag);
+                }
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::PreloadHint(tag))) => {
+                    self.current.preload_hints.push(tag);
+                }
+                input_uri!(uri) => {
+                    self.current.uri = Some(uri);
+                    return Some(std::mem::take(&mut self.current));
+                }
+                _ => (),
+            }
+        }
+        self.finished = true;
+        let current = std::mem::take(&mut self.current);
+        if current.parts.is_empty() && current.preload_hints.is_empty() {
+            None
+        } else {
+            Some(current)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn segments_without_parts_should_still_be_grouped() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXTINF:4\n",
+            "segment_1.mp4\n",
+            "#EXTINF:4\n",
+            "segment_2.mp4\n",
+        );
+        let groups = part_groups(PLAYLIST).collect::<Vec<_>>();
+        assert_eq!(2, groups.len());
+        assert_eq!(
+            vec![Some("segment_1.mp4"), Some("segment_2.mp4")],
+            groups.iter().map(|group| group.uri).collect::<Vec<_>>()
+        );
+        assert!(
+            groups
+                .iter()
+                .all(|group| group.duration_difference().is_none())
+        );
+    }
+
+    #[test]
+    fn parts_that_sum_to_the_segment_duration_should_have_no_difference() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:2\n",
+            "#EXT-X-PART-INF:PART-TARGET=0.5\n",
+            "#EXT-X-PART:DURATION=0.5,URI=\"part_1.0.mp4\"\n",
+            "#EXT-X-PART:DURATION=0.5,URI=\"part_1.1.mp4\"\n",
+            "#EXT-X-PART:DURATION=0.5,URI=\"part_1.2.mp4\"\n",
+            "#EXT-X-PART:DURATION=0.5,URI=\"part_1.3.mp4\"\n",
+            "#EXTINF:2\n",
+            "segment_1.mp4\n",
+        );
+        let mut groups = part_groups(PLAYLIST);
+        let group = groups.next().unwrap();
+        assert!(group.is_complete());
+        assert_eq!(2.0, group.parts_duration());
+        assert_eq!(Some(0.0), group.duration_difference());
+        assert_eq!(None, group.last_independent_part());
+        assert_eq!(None, group.part_hint());
+        assert_eq!(None, groups.next());
+    }
+
+    #[test]
+    fn trailing_map_hint_should_not_be_the_part_hint() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXTINF:4\n",
+            "segment_1.mp4\n",
+            "#EXT-X-PRELOAD-HINT:TYPE=MAP,URI=\"init_2.mp4\"\n",
+        );
+        let mut groups = part_groups(PLAYLIST);
+        assert_eq!(Some("segment_1.mp4"), groups.next().unwrap().uri);
+        let trailing = groups.next().unwrap();
+        assert!(!trailing.is_complete());
+        assert_eq!(None, trailing.inf);
+        assert_eq!(1, trailing.preload_hints.len());
+        assert_eq!(None, trailing.part_hint());
+        assert_eq!(None, groups.next());
+        assert_eq!(None, groups.next());
+    }
+}
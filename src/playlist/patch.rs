@@ -0,0 +1,547 @@
+use crate::{error::PatchError, utils::fnv1a};
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+const HEADER_PREFIX: &str = "#QUICK-M3U8-PATCH:";
+
+/// A compact, line-level patch that reconstructs the current version of a playlist from the
+/// previous version, for replicating playlist updates between nodes (such as CDN edges) without
+/// transferring the whole playlist.
+///
+/// The patch is created via [`Self::diff`], and applied via [`Self::apply`]. It consists of
+/// [`PatchOp`]s that keep or delete runs of lines of the previous version (by index), or insert new
+/// lines, in order. As a live playlist refresh typically only removes lines from the start of the
+/// playlist and adds lines to the end, the patch is usually much smaller than the playlist.
+///
+/// The patch records the number of lines and a hash of the previous version, such that applying the
+/// patch to any other playlist fails with [`PatchError::SourceMismatch`] (rather than silently
+/// producing a corrupt playlist). Lines are compared exactly (including trailing whitespace), and
+/// the reconstructed playlist always uses `\n` line endings.
+///
+/// The patch is serialized (via [`Display`]) to, and parsed (via [`FromStr`]) from, a stable text
+/// format. The first line is a header that identifies the format version and the source playlist,
+/// and each operation follows on its own line:
+/// * `=<start>,<count>` keeps `count` lines of the previous version, starting at index `start`,
+/// * `-<start>,<count>` deletes `count` lines of the previous version, starting at index `start`,
+/// * `+<count>` inserts the `count` lines that immediately follow.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{PatchOp, PlaylistPatch};
+/// let previous = "#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:1\n#EXTINF:4\nseg_1.ts\n#EXTINF:4\nseg_2.ts\n";
+/// let current = "#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:2\n#EXTINF:4\nseg_2.ts\n#EXTINF:4\nseg_3.ts\n";
+///
+/// let patch = PlaylistPatch::diff(previous, current);
+/// assert_eq!(
+///     &[
+///         PatchOp::Keep { start: 0, count: 1 },
+///         PatchOp::Delete { start: 1, count: 3 },
+///         PatchOp::Insert(vec!["#EXT-X-MEDIA-SEQUENCE:2".to_string()]),
+///         PatchOp::Keep { start: 4, count: 2 },
+///         PatchOp::Insert(vec!["#EXTINF:4".to_string(), "seg_3.ts".to_string()]),
+///     ],
+///     patch.ops()
+/// );
+///
+/// let serialized = patch.to_string();
+/// assert!(serialized.starts_with("#QUICK-M3U8-PATCH:VERSION=1,SOURCE-LINES=6,SOURCE-HASH="));
+/// let patch = serialized.parse::<PlaylistPatch>()?;
+/// assert_eq!(current, patch.apply(previous)?);
+/// # Ok::<(), quick_m3u8::error::PatchError>(())
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlaylistPatch {
+    source_lines: usize,
+    source_hash: u64,
+    ops: Vec<PatchOp>,
+}
+
+/// An operation within a [`PlaylistPatch`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PatchOp {
+    /// Keep `count` lines of the previous version, starting at index `start`.
+    Keep {
+        /// The index of the first line (within the previous version).
+        start: usize,
+        /// The number of lines.
+        count: usize,
+    },
+    /// Delete `count` lines of the previous version, starting at index `start`.
+    Delete {
+        /// The index of the first line (within the previous version).
+        start: usize,
+        /// The number of lines.
+        count: usize,
+    },
+    /// Insert the lines.
+    Insert(Vec<String>),
+}
+
+impl PlaylistPatch {
+    /// The format version that this version of the library writes (and the only version that it
+    /// can parse).
+    pub const VERSION: u64 = 1;
+
+    /// Creates the patch that reconstructs `current` from `previous`.
+    ///
+    /// The diff is computed using the Myers algorithm, which finds the smallest set of deletions
+    /// and insertions, and runs in time (and memory) proportional to the size of the playlists
+    /// multiplied by the number of changed lines.
+    pub fn diff(previous: &str, current: &str) -> Self {
+        let previous_lines = previous.lines().collect::<Vec<_>>();
+        let current_lines = current.lines().collect::<Vec<_>>();
+        let mut ops = Vec::new();
+        for edit in diff_lines(&previous_lines, &current_lines) {
+            match (ops.last_mut(), edit) {
+                (Some(PatchOp::Keep { count, .. }), Edit::Keep(_))
+                | (Some(PatchOp::Delete { count, .. }), Edit::Delete(_)) => *count += 1,
+                (Some(PatchOp::Insert(lines)), Edit::Insert(index)) => {
+                    lines.push(current_lines[index].to_string())
+                }
+                (_, Edit::Keep(start)) => ops.push(PatchOp::Keep { start, count: 1 }),
+                (_, Edit::Delete(start)) => ops.push(PatchOp::Delete { start, count: 1 }),
+                (_, Edit::Insert(index)) => {
+                    ops.push(PatchOp::Insert(vec![current_lines[index].to_string()]))
+                }
+            }
+        }
+        Self {
+            source_lines: previous_lines.len(),
+            source_hash: source_hash(&previous_lines),
+            ops,
+        }
+    }
+
+    /// The operations of the patch, in order.
+    pub fn ops(&self) -> &[PatchOp] {
+        &self.ops
+    }
+
+    /// The number of lines of the playlist that the patch was created from.
+    pub fn source_lines(&self) -> usize {
+        self.source_lines
+    }
+
+    /// The hash of the playlist that the patch was created from.
+    pub fn source_hash(&self) -> u64 {
+        self.source_hash
+    }
+
+    /// Indicates whether the patch makes no changes.
+    pub fn is_unchanged(&self) -> bool {
+        self.ops.iter().all(|op| matches!(op, PatchOp::Keep { .. }))
+    }
+
+    /// Reconstructs the current version of the playlist from the previous version.
+    ///
+    /// This fails if `previous` is not the playlist that the patch was created from (see
+    /// [`PatchError::SourceMismatch`]), or if the operations are inconsistent (which can only be
+    /// the case for a patch that was not created via [`Self::diff`]).
+    pub fn apply(&self, previous: &str) -> Result<String, PatchError> {
+        let previous_lines = previous.lines().collect::<Vec<_>>();
+        if previous_lines.len() != self.source_lines
+            || source_hash(&previous_lines) != self.source_hash
+        {
+            return Err(PatchError::SourceMismatch);
+        }
+        let mut output = String::with_capacity(previous.len());
+        let mut next = 0;
+        for (index, op) in self.ops.iter().enumerate() {
+            match op {
+                PatchOp::Keep { start, count } | PatchOp::Delete { start, count } => {
+                    let end = match start.checked_add(*count) {
+                        Some(end) if *start == next && end <= previous_lines.len() => end,
+                        _ => return Err(PatchError::InvalidOperation(index)),
+                    };
+                    if let PatchOp::Keep { .. } = op {
+                        for line in &previous_lines[*start..end] {
+                            output.push_str(line);
+                            output.push('\n');
+                        }
+                    }
+                    next = end;
+                }
+                PatchOp::Insert(lines) => {
+                    for line in lines {
+                        output.push_str(line);
+                        output.push('\n');
+                    }
+                }
+            }
+        }
+        if next != previous_lines.len() {
+            return Err(PatchError::InvalidOperation(self.ops.len()));
+        }
+        Ok(output)
+    }
+}
+
+impl Display for PlaylistPatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{HEADER_PREFIX}VERSION={},SOURCE-LINES={},SOURCE-HASH={:016x}",
+            Self::VERSION,
+            self.source_lines,
+            self.source_hash
+        )?;
+        for op in &self.ops {
+            match op {
+                PatchOp::Keep { start, count } => writeln!(f, "={start},{count}")?,
+                PatchOp::Delete { start, count } => writeln!(f, "-{start},{count}")?,
+                PatchOp::Insert(lines) => {
+                    writeln!(f, "+{}", lines.len())?;
+                    for line in lines {
+                        writeln!(f, "{line}")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PlaylistPatch {
+    type Err = PatchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().enumerate().map(|(index, line)| (index + 1, line));
+        let invalid = |line_number| PatchError::InvalidFormat { line_number };
+
+        let header = lines
+            .next()
+            .and_then(|(_, line)| line.strip_prefix(HEADER_PREFIX))
+            .ok_or(invalid(1))?;
+        let mut version = None;
+        let mut source_lines = None;
+        let mut source_hash = None;
+        for attribute in header.split(',') {
+            match attribute.split_once('=').ok_or(invalid(1))? {
+                ("VERSION", value) => version = value.parse::<u64>().ok(),
+                ("SOURCE-LINES", value) => source_lines = value.parse::<usize>().ok(),
+                ("SOURCE-HASH", value) => source_hash = u64::from_str_radix(value, 16).ok(),
+                // Unknown attributes are ignored so that metadata can be added without a new
+                // version of the format.
+                _ => (),
+            }
+        }
+        match version.ok_or(invalid(1))? {
+            Self::VERSION => (),
+            version => return Err(PatchError::UnsupportedVersion(version)),
+        }
+        let source_lines = source_lines.ok_or(invalid(1))?;
+        let source_hash = source_hash.ok_or(invalid(1))?;
+
+        let mut ops = Vec::new();
+        while let Some((line_number, line)) = lines.next() {
+            let parse_range = |range: &str| {
+                let (start, count) = range.split_once(',')?;
+                Some((start.parse().ok()?, count.parse().ok()?))
+            };
+            let op = if let Some(range) = line.strip_prefix('=') {
+                let (start, count) = parse_range(range).ok_or(invalid(line_number))?;
+                PatchOp::Keep { start, count }
+            } else if let Some(range) = line.strip_prefix('-') {
+                let (start, count) = parse_range(range).ok_or(invalid(line_number))?;
+                PatchOp::Delete { start, count }
+            } else if let Some(count) = line.strip_prefix('+') {
+                let count = count.parse::<usize>().map_err(|_| invalid(line_number))?;
+                let mut inserted = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let (_, line) = lines.next().ok_or(invalid(line_number))?;
+                    inserted.push(line.to_string());
+                }
+                PatchOp::Insert(inserted)
+            } else {
+                return Err(invalid(line_number));
+            };
+            ops.push(op);
+        }
+        Ok(Self {
+            source_lines,
+            source_hash,
+            ops,
+        })
+    }
+}
+
+fn source_hash(lines: &[&str]) -> u64 {
+    fnv1a(lines.join("\n").as_bytes())
+}
+
+// A single line edit, holding the index of the line (within the previous version for `Keep` and
+// `Delete`, and within the current version for `Insert`).
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Edit {
+    Keep(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+// The Myers diff algorithm ("An O(ND) Difference Algorithm and Its Variations"), providing the
+// edits in order. This is the linear space refinement (section 4b of the paper), which recursively
+// splits the problem at the middle snake of an optimal path, such that memory use is O(N + M)
+// rather than O(D * (N + M)) (where D, the number of lines that differ, is commonly the size of the
+// whole playlist when each URI carries a per-request token).
+fn diff_lines(previous: &[&str], current: &[&str]) -> Vec<Edit> {
+    let mut diff = Diff {
+        previous,
+        current,
+        forward: vec![0; previous.len() + current.len() + 3],
+        backward: vec![0; previous.len() + current.len() + 3],
+        edits: Vec::with_capacity(previous.len().max(current.len())),
+    };
+    diff.diff(0..previous.len(), 0..current.len());
+    diff.edits
+}
+
+struct Diff<'a, 'b> {
+    previous: &'a [&'b str],
+    current: &'a [&'b str],
+    // The furthest reaching x on each diagonal, searching forward from the start, and backward from
+    // the end, of the sub-problem being split.
+    forward: Vec<isize>,
+    backward: Vec<isize>,
+    edits: Vec<Edit>,
+}
+
+impl Diff<'_, '_> {
+    fn diff(&mut self, mut previous: Range<usize>, mut current: Range<usize>) {
+        let mut suffix = 0;
+        while !previous.is_empty()
+            && !current.is_empty()
+            && self.previous[previous.start] == self.current[current.start]
+        {
+            self.edits.push(Edit::Keep(previous.start));
+            previous.start += 1;
+            current.start += 1;
+        }
+        while !previous.is_empty()
+            && !current.is_empty()
+            && self.previous[previous.end - 1] == self.current[current.end - 1]
+        {
+            previous.end -= 1;
+            current.end -= 1;
+            suffix += 1;
+        }
+        if previous.is_empty() {
+            self.edits.extend(current.clone().map(Edit::Insert));
+        } else if current.is_empty() {
+            self.edits.extend(previous.clone().map(Edit::Delete));
+        } else {
+            // With the common prefix and suffix removed, and both sides non-empty, at least two
+            // edits are needed, and so each half of the split is a smaller problem.
+            let (x, y, snake_end) = self.middle_snake(previous.clone(), current.clone());
+            self.diff(previous.start..x, current.start..y);
+            self.edits.extend((x..snake_end).map(Edit::Keep));
+            let snake_len = snake_end - x;
+            self.diff(snake_end..previous.end, (y + snake_len)..current.end);
+        }
+        self.edits
+            .extend((previous.end..previous.end + suffix).map(Edit::Keep));
+    }
+
+    // Finds the middle snake of an optimal path, provided as the absolute start (x, y) and the end x
+    // of the snake.
+    fn middle_snake(
+        &mut self,
+        previous: Range<usize>,
+        current: Range<usize>,
+    ) -> (usize, usize, usize) {
+        let (a, b) = (
+            &self.previous[previous.clone()],
+            &self.current[current.clone()],
+        );
+        let n = a.len() as isize;
+        let m = b.len() as isize;
+        let delta = n - m;
+        let odd = delta % 2 != 0;
+        let max = (n + m + 1) / 2;
+        let index = |k: isize| (k + max + 1) as usize;
+        self.forward[index(1)] = 0;
+        self.backward[index(1)] = 0;
+        for d in 0..=max {
+            for k in (-d..=d).step_by(2) {
+                let forward = &mut self.forward;
+                let mut x = if k == -d || (k != d && forward[index(k - 1)] < forward[index(k + 1)])
+                {
+                    forward[index(k + 1)]
+                } else {
+                    forward[index(k - 1)] + 1
+                };
+                let start = x;
+                while x < n && x - k < m && a[x as usize] == b[(x - k) as usize] {
+                    x += 1;
+                }
+                forward[index(k)] = x;
+                // The backward search is on diagonal `delta - k` (when measured from the end).
+                if odd && (k - delta).abs() < d && x >= n - self.backward[index(delta - k)] {
+                    return (
+                        previous.start + start as usize,
+                        current.start + (start - k) as usize,
+                        previous.start + x as usize,
+                    );
+                }
+            }
+            for c in (-d..=d).step_by(2) {
+                let backward = &mut self.backward;
+                let mut u =
+                    if c == -d || (c != d && backward[index(c - 1)] < backward[index(c + 1)]) {
+                        backward[index(c + 1)]
+                    } else {
+                        backward[index(c - 1)] + 1
+                    };
+                let end = u;
+                while u < n && u - c < m && a[(n - u - 1) as usize] == b[(m - (u - c) - 1) as usize]
+                {
+                    u += 1;
+                }
+                backward[index(c)] = u;
+                let k = delta - c;
+                if !odd && k.abs() <= d && self.forward[index(k)] >= n - u {
+                    return (
+                        previous.start + (n - u) as usize,
+                        current.start + (m - (u - c)) as usize,
+                        previous.start + (n - end) as usize,
+                    );
+                }
+            }
+        }
+        unreachable!("the forward and backward searches meet within (N + M + 1) / 2 rounds")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn diff_should_round_trip() {
+        let cases = [
+            ("", ""),
+            ("", "a\nb\n"),
+            ("a\nb\n", ""),
+            ("a\nb\nc\n", "a\nb\nc\n"),
+            ("a\nb\nc\na\nb\nb\na\n", "c\nb\na\nb\na\nc\n"),
+            ("a\r\nb\r\n", "b\nc"),
+        ];
+        for (previous, current) in cases {
+            let patch = PlaylistPatch::diff(previous, current);
+            let parsed = patch.to_string().parse::<PlaylistPatch>().unwrap();
+            assert_eq!(patch, parsed);
+            let expected = current
+                .lines()
+                .map(|line| format!("{line}\n"))
+                .collect::<String>();
+            assert_eq!(
+                Ok(expected),
+                parsed.apply(previous),
+                "{previous:?} -> {current:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn diff_of_generated_playlists_should_round_trip() {
+        // A simple linear congruential generator, so that the cases are reproducible.
+        let mut state = 42u64;
+        let mut next = |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+        for _ in 0..200 {
+            let mut playlist = || {
+                (0..next(12))
+                    .map(|_| ["a\n", "b\n", "c\n"][next(3) as usize])
+                    .collect::<String>()
+            };
+            let (previous, current) = (playlist(), playlist());
+            assert_eq!(
+                Ok(current.clone()),
+                PlaylistPatch::diff(&previous, &current).apply(&previous),
+                "{previous:?} -> {current:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn diff_of_playlists_without_common_lines_should_round_trip() {
+        let playlist = |token: &str| {
+            (0..3000)
+                .map(|i| format!("segment_{i}.ts?token={token}\n"))
+                .collect::<String>()
+        };
+        let (previous, current) = (playlist("a"), playlist("b"));
+        let patch = PlaylistPatch::diff(&previous, &current);
+        assert_eq!(
+            &[
+                PatchOp::Delete {
+                    start: 0,
+                    count: 3000
+                },
+                PatchOp::Insert(current.lines().map(String::from).collect()),
+            ],
+            patch.ops()
+        );
+        assert_eq!(Ok(current), patch.apply(&previous));
+    }
+
+    #[test]
+    fn unchanged_playlist_should_be_single_keep() {
+        let patch = PlaylistPatch::diff("a\nb\n", "a\nb\n");
+        assert!(patch.is_unchanged());
+        assert_eq!(&[PatchOp::Keep { start: 0, count: 2 }], patch.ops());
+    }
+
+    #[test]
+    fn apply_should_fail_on_other_source() {
+        let patch = PlaylistPatch::diff("a\nb\n", "a\nc\n");
+        assert_eq!(Err(PatchError::SourceMismatch), patch.apply("a\nc\n"));
+        assert_eq!(Err(PatchError::SourceMismatch), patch.apply("a\n"));
+    }
+
+    #[test]
+    fn parse_should_reject_invalid_patches() {
+        let header = "#QUICK-M3U8-PATCH:VERSION=1,SOURCE-LINES=1,SOURCE-HASH=0";
+        assert_eq!(
+            Err(PatchError::UnsupportedVersion(2)),
+            "#QUICK-M3U8-PATCH:VERSION=2,SOURCE-LINES=1,SOURCE-HASH=0".parse::<PlaylistPatch>()
+        );
+        assert_eq!(
+            Err(PatchError::InvalidFormat { line_number: 1 }),
+            "#QUICK-M3U8-PATCH:VERSION=1".parse::<PlaylistPatch>()
+        );
+        assert_eq!(
+            Err(PatchError::InvalidFormat { line_number: 2 }),
+            format!("{header}\n=0").parse::<PlaylistPatch>()
+        );
+        assert_eq!(
+            Err(PatchError::InvalidFormat { line_number: 3 }),
+            format!("{header}\n=0,1\n+2\nonly one line").parse::<PlaylistPatch>()
+        );
+    }
+
+    #[test]
+    fn apply_should_reject_inconsistent_operations() {
+        let source_hash = source_hash(&["a", "b"]);
+        let patch = |ops| PlaylistPatch {
+            source_lines: 2,
+            source_hash,
+            ops,
+        };
+        assert_eq!(
+            Err(PatchError::InvalidOperation(1)),
+            patch(vec![
+                PatchOp::Keep { start: 0, count: 1 },
+                PatchOp::Keep { start: 0, count: 1 },
+            ])
+            .apply("a\nb")
+        );
+        assert_eq!(
+            Err(PatchError::InvalidOperation(1)),
+            patch(vec![PatchOp::Keep { start: 0, count: 1 }]).apply("a\nb")
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    tag::{
+        KnownTag,
+        hls::{self, MediaType},
+    },
+};
+
+/// The `PATHWAY-ID` that applies to a Variant Stream that does not specify one.
+const DEFAULT_PATHWAY_ID: &str = ".";
+
+/// Filters a Multivariant Playlist down to the Variant Streams and Renditions of a single Content
+/// Steering pathway.
+///
+/// This is typically used once a steering decision has been made (i.e. the pathway has been chosen
+/// from the `PATHWAY-PRIORITY` of the Steering Manifest), to produce a playlist that can be handed
+/// to a client that does not support Content Steering. The resulting lines are the lines of the
+/// input playlist, where:
+/// * `EXT-X-STREAM-INF` (along with its URI line) and `EXT-X-I-FRAME-STREAM-INF` are only kept when
+///   their `PATHWAY-ID` matches `pathway_id`. A Variant Stream with no `PATHWAY-ID` belongs to the
+///   default pathway (`"."`).
+/// * `EXT-X-MEDIA` is only kept when its group is referenced by a kept `EXT-X-STREAM-INF` (or, for
+///   `VIDEO`, a kept `EXT-X-I-FRAME-STREAM-INF`).
+/// * `EXT-X-CONTENT-STEERING` is removed, as there is no longer more than one pathway to steer
+///   between.
+///
+/// If no Variant Stream belongs to `pathway_id`, then the playlist falls back to the Variant
+/// Streams of the default pathway (i.e. those with no `PATHWAY-ID`). All other lines are kept
+/// unchanged, and lines that cannot be parsed are skipped.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{Writer, playlist::filter_pathway};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-CONTENT-STEERING:SERVER-URI="/steering",PATHWAY-ID="CDN-A"
+/// #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="a-A",NAME="English",URI="https://a.example.com/en.m3u8"
+/// #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="a-B",NAME="English",URI="https://b.example.com/en.m3u8"
+/// #EXT-X-STREAM-INF:BANDWIDTH=1280000,AUDIO="a-A",PATHWAY-ID="CDN-A"
+/// https://a.example.com/low.m3u8
+/// #EXT-X-STREAM-INF:BANDWIDTH=1280000,AUDIO="a-B",PATHWAY-ID="CDN-B"
+/// https://b.example.com/low.m3u8
+/// "#;
+///
+/// let mut writer = Writer::new(Vec::new());
+/// for line in filter_pathway(PLAYLIST, "CDN-B") {
+///     writer.write_line(line)?;
+/// }
+/// assert_eq!(
+///     r#"#EXTM3U
+/// #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="a-B",NAME="English",URI="https://b.example.com/en.m3u8"
+/// #EXT-X-STREAM-INF:BANDWIDTH=1280000,AUDIO="a-B",PATHWAY-ID="CDN-B"
+/// https://b.example.com/low.m3u8
+/// "#,
+///     String::from_utf8_lossy(&writer.into_inner())
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn filter_pathway<'a>(playlist: &'a str, pathway_id: &str) -> Vec<HlsLine<'a>> {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_parsing_for_stream_inf()
+            .with_parsing_for_i_frame_stream_inf()
+            .with_parsing_for_media()
+            .with_parsing_for_content_steering()
+            .build(),
+    );
+    let mut lines = Vec::new();
+    loop {
+        match reader.read_line() {
+            Ok(Some(line)) => lines.push(line),
+            Ok(None) => break,
+            Err(_) => (),
+        }
+    }
+    let has_pathway = lines
+        .iter()
+        .any(|line| variant_pathway_id(line) == Some(pathway_id));
+    let pathway_id = if has_pathway {
+        pathway_id
+    } else {
+        DEFAULT_PATHWAY_ID
+    };
+
+    let mut kept = Vec::with_capacity(lines.len());
+    let mut groups: Vec<(MediaType, String)> = Vec::new();
+    let mut skip_uri = false;
+    for line in lines {
+        match &line {
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::StreamInf(tag))) => {
+                skip_uri = variant_pathway_id(&line) != Some(pathway_id);
+                if skip_uri {
+                    continue;
+                }
+                for (media_type, group) in [
+                    (MediaType::Audio, tag.audio()),
+                    (MediaType::Video, tag.video()),
+                    (MediaType::Subtitles, tag.subtitles()),
+                    (MediaType::ClosedCaptions, tag.closed_captions()),
+                ] {
+                    if let Some(group) = group {
+                        groups.push((media_type, group.to_string()));
+                    }
+                }
+            }
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::IFrameStreamInf(tag))) => {
+                if variant_pathway_id(&line) != Some(pathway_id) {
+                    continue;
+                }
+                if let Some(group) = tag.video() {
+                    groups.push((MediaType::Video, group.to_string()));
+                }
+            }
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::ContentSteering(_))) => continue,
+            HlsLine::Uri(_) if skip_uri => {
+                skip_uri = false;
+                continue;
+            }
+            _ => (),
+        }
+        kept.push(line);
+    }
+    kept.retain(|line| match line {
+        HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Media(media))) => {
+            let media_type = media.media_type();
+            groups.iter().any(|(t, group)| {
+                media_type.known() == Some(t) && group.as_str() == media.group_id()
+            })
+        }
+        _ => true,
+    });
+    kept
+}
+
+fn variant_pathway_id<'a>(line: &'a HlsLine) -> Option<&'a str> {
+    match line {
+        HlsLine::KnownTag(KnownTag::Hls(hls::Tag::StreamInf(tag))) => {
+            Some(tag.pathway_id().unwrap_or(DEFAULT_PATHWAY_ID))
+        }
+        HlsLine::KnownTag(KnownTag::Hls(hls::Tag::IFrameStreamInf(tag))) => {
+            Some(tag.pathway_id().unwrap_or(DEFAULT_PATHWAY_ID))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Writer;
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = concat!(
+        "#EXTM3U\n",
+        "#EXT-X-CONTENT-STEERING:SERVER-URI=\"/steering\",PATHWAY-ID=\"CDN-A\"\n",
+        "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"a-A\",NAME=\"English\",URI=\"a/en.m3u8\"\n",
+        "#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"subs\",NAME=\"English\",URI=\"en.vtt.m3u8\"\n",
+        "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"default\",NAME=\"English\",URI=\"en.m3u8\"\n",
+        "#EXT-X-STREAM-INF:BANDWIDTH=1000,AUDIO=\"a-A\",SUBTITLES=\"subs\",PATHWAY-ID=\"CDN-A\"\n",
+        "# comment between tag and URI\n",
+        "a/low.m3u8\n",
+        "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100,URI=\"a/iframe.m3u8\",PATHWAY-ID=\"CDN-A\"\n",
+        "#EXT-X-STREAM-INF:BANDWIDTH=1000,AUDIO=\"default\"\n",
+        "low.m3u8\n",
+        "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100,URI=\"iframe.m3u8\"\n",
+    );
+
+    fn write(lines: Vec<HlsLine>) -> String {
+        let mut writer = Writer::new(Vec::new());
+        for line in lines {
+            writer
+                .write_line(line)
+                .expect("writing to Vec should not fail");
+        }
+        String::from_utf8_lossy(&writer.into_inner()).to_string()
+    }
+
+    #[test]
+    fn chosen_pathway_should_be_kept() {
+        assert_eq!(
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"a-A\",NAME=\"English\",URI=\"a/en.m3u8\"\n",
+                "#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"subs\",NAME=\"English\",URI=\"en.vtt.m3u8\"\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=1000,AUDIO=\"a-A\",SUBTITLES=\"subs\",PATHWAY-ID=\"CDN-A\"\n",
+                "# comment between tag and URI\n",
+                "a/low.m3u8\n",
+                "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100,URI=\"a/iframe.m3u8\",PATHWAY-ID=\"CDN-A\"\n",
+            ),
+            write(filter_pathway(PLAYLIST, "CDN-A"))
+        );
+    }
+
+    #[test]
+    fn unknown_pathway_should_fall_back_to_pathway_less_variants() {
+        assert_eq!(
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"default\",NAME=\"English\",URI=\"en.m3u8\"\n",
+                "# comment between tag and URI\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=1000,AUDIO=\"default\"\n",
+                "low.m3u8\n",
+                "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100,URI=\"iframe.m3u8\"\n",
+            ),
+            write(filter_pathway(PLAYLIST, "CDN-Z"))
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use crate::{
+    HlsLine, HlsLineVisitor,
+    tag::hls::{
+        ContentSteering, IFrameStreamInf, Key, Map, Media, Part, PreloadHint, RenditionReport,
+        SessionData, SessionKey,
+    },
+};
+use std::borrow::Cow;
+
+/// How the percent-encoding of a URI should be normalized (see [`normalize_percent_encoding`]).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum PercentEncoding {
+    /// Percent-encode every character that is not allowed in a URI (such as spaces, double quotes,
+    /// and non-ASCII characters), along with any `%` that does not begin a valid escape. Existing
+    /// escapes are kept (with the hexadecimal digits in upper case).
+    Encode,
+    /// Decode the escapes of unreserved characters (`A-Z`, `a-z`, `0-9`, `-`, `.`, `_`, `~`) and of
+    /// non-ASCII characters. Escapes of any other character (such as `%2F` or `%20`) are kept (with
+    /// the hexadecimal digits in upper case), as decoding them may change the meaning of the URI, or
+    /// produce a URI that cannot be written to the playlist.
+    Decode,
+    /// Leave the URI untouched.
+    Preserve,
+}
+
+/// Indicates whether the URI contains characters that should have been percent-encoded (such as
+/// spaces and non-ASCII characters), or a `%` that does not begin a valid escape.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::has_improper_percent_encoding;
+/// assert!(has_improper_percent_encoding("my segment.ts"));
+/// assert!(has_improper_percent_encoding("séquence.ts"));
+/// assert!(has_improper_percent_encoding("100%.ts"));
+/// assert!(!has_improper_percent_encoding("my%20segment.ts?a=1&b=2"));
+/// ```
+pub fn has_improper_percent_encoding(uri: &str) -> bool {
+    let bytes = uri.as_bytes();
+    bytes.iter().enumerate().any(|(index, byte)| match byte {
+        b'%' => escape_at(bytes, index).is_none(),
+        byte => !is_allowed(*byte),
+    })
+}
+
+/// Normalizes the percent-encoding of the URI, such that URIs that were packaged with mixed encoding
+/// are consistently encoded (as is necessary, for example, when signing the URIs downstream).
+///
+/// See [`PercentEncoding`] for what each mode does. The URI is borrowed when it is already
+/// normalized.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{PercentEncoding, normalize_percent_encoding};
+/// let uri = "my segment/s%c3%a9quence%2Dé.ts";
+/// assert_eq!(
+///     "my%20segment/s%C3%A9quence%2D%C3%A9.ts",
+///     normalize_percent_encoding(uri, PercentEncoding::Encode)
+/// );
+/// assert_eq!(
+///     "my segment/séquence-é.ts",
+///     normalize_percent_encoding(uri, PercentEncoding::Decode)
+/// );
+/// assert_eq!(uri, normalize_percent_encoding(uri, PercentEncoding::Preserve));
+/// ```
+pub fn normalize_percent_encoding(uri: &str, mode: PercentEncoding) -> Cow<'_, str> {
+    let normalized = match mode {
+        PercentEncoding::Encode => encode(uri),
+        PercentEncoding::Decode => decode(uri),
+        PercentEncoding::Preserve => return Cow::Borrowed(uri),
+    };
+    if normalized == uri {
+        Cow::Borrowed(uri)
+    } else {
+        Cow::Owned(normalized)
+    }
+}
+
+/// Normalizes the percent-encoding of the URIs of a playlist (see [`normalize_percent_encoding`]).
+///
+/// `PercentEncodingNormalizer` implements [`HlsLineVisitor`], and so can be used as a stage when
+/// rewriting a playlist (alongside other visitors, such as [`crate::playlist::CacheBuster`]). URI
+/// lines and URI attributes may be normalized differently; for example, segment URIs may be encoded
+/// for signing while the URI attributes are left untouched.
+///
+/// The URI attributes that are normalized are the `URI` attribute of `EXT-X-KEY`,
+/// `EXT-X-SESSION-KEY`, `EXT-X-MAP`, `EXT-X-PART`, `EXT-X-PRELOAD-HINT`, `EXT-X-RENDITION-REPORT`,
+/// `EXT-X-MEDIA`, `EXT-X-I-FRAME-STREAM-INF`, and `EXT-X-SESSION-DATA`, along with the `SERVER-URI`
+/// attribute of `EXT-X-CONTENT-STEERING`.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{HlsLineVisitor, Reader, Writer, config::ParsingOptions};
+/// # use quick_m3u8::playlist::{PercentEncoding, PercentEncodingNormalizer};
+/// const PLAYLIST: &str = "#EXTM3U\n#EXT-X-MAP:URI=\"init%2dé.mp4\"\n#EXTINF:4\nmy segment.mp4\n";
+///
+/// let mut normalizer = PercentEncodingNormalizer::new(PercentEncoding::Encode)
+///     .with_uri_attributes(PercentEncoding::Decode);
+/// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+/// let mut writer = Writer::new(Vec::new());
+/// while let Some(line) = reader.read_line()? {
+///     if let Some(line) = normalizer.visit_line(line) {
+///         writer.write_line(line)?;
+///     }
+/// }
+/// assert_eq!(
+///     "#EXTM3U\n#EXT-X-MAP:URI=\"init-é.mp4\"\n#EXTINF:4\nmy%20segment.mp4\n",
+///     String::from_utf8(writer.into_inner())?
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct PercentEncodingNormalizer {
+    uri_lines: PercentEncoding,
+    uri_attributes: PercentEncoding,
+}
+
+impl PercentEncodingNormalizer {
+    /// Creates a new `PercentEncodingNormalizer` that normalizes both URI lines and URI attributes
+    /// using `mode`.
+    pub fn new(mode: PercentEncoding) -> Self {
+        Self {
+            uri_lines: mode,
+            uri_attributes: mode,
+        }
+    }
+
+    /// Sets how URI lines are normalized.
+    pub fn with_uri_lines(mut self, mode: PercentEncoding) -> Self {
+        self.uri_lines = mode;
+        self
+    }
+
+    /// Sets how URI attributes are normalized.
+    pub fn with_uri_attributes(mut self, mode: PercentEncoding) -> Self {
+        self.uri_attributes = mode;
+        self
+    }
+
+    /// How URI lines are normalized.
+    pub fn uri_lines(&self) -> PercentEncoding {
+        self.uri_lines
+    }
+
+    /// How URI attributes are normalized.
+    pub fn uri_attributes(&self) -> PercentEncoding {
+        self.uri_attributes
+    }
+
+    fn normalize_attribute(&self, uri: &str) -> Option<String> {
+        match normalize_percent_encoding(uri, self.uri_attributes) {
+            Cow::Owned(uri) => Some(uri),
+            Cow::Borrowed(_) => None,
+        }
+    }
+}
+
+impl<'a> HlsLineVisitor<'a> for PercentEncodingNormalizer {
+    fn visit_key(&mut self, mut tag: Key<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = tag.uri().and_then(|uri| self.normalize_attribute(uri)) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_session_key(&mut self, mut tag: SessionKey<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.normalize_attribute(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_media(&mut self, mut tag: Media<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = tag.uri().and_then(|uri| self.normalize_attribute(uri)) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_map(&mut self, mut tag: Map<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.normalize_attribute(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_part(&mut self, mut tag: Part<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.normalize_attribute(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_preload_hint(&mut self, mut tag: PreloadHint<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.normalize_attribute(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_rendition_report(&mut self, mut tag: RenditionReport<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.normalize_attribute(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_i_frame_stream_inf(&mut self, mut tag: IFrameStreamInf<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.normalize_attribute(tag.uri()) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_session_data(&mut self, mut tag: SessionData<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = tag.uri().and_then(|uri| self.normalize_attribute(uri)) {
+            tag.set_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_content_steering(&mut self, mut tag: ContentSteering<'a>) -> Option<HlsLine<'a>> {
+        if let Some(uri) = self.normalize_attribute(tag.server_uri()) {
+            tag.set_server_uri(uri);
+        }
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_uri(&mut self, uri: Cow<'a, str>) -> Option<HlsLine<'a>> {
+        match normalize_percent_encoding(&uri, self.uri_lines) {
+            Cow::Owned(normalized) => Some(HlsLine::Uri(Cow::Owned(normalized))),
+            Cow::Borrowed(_) => Some(HlsLine::Uri(uri)),
+        }
+    }
+}
+
+// The characters that are allowed to appear (unencoded) in a URI, other than `%`. These are the
+// unreserved and reserved characters of RFC 3986.
+fn is_allowed(byte: u8) -> bool {
+    is_unreserved(byte) || b":/?#[]@!$&'()*+,;=".contains(&byte)
+}
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+// The value of the escape beginning at the `%` at `index` (if it is a valid escape).
+fn escape_at(bytes: &[u8], index: usize) -> Option<u8> {
+    let hex = bytes.get(index + 1..index + 3)?;
+    let hex = std::str::from_utf8(hex).ok()?;
+    if hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        u8::from_str_radix(hex, 16).ok()
+    } else {
+        None
+    }
+}
+
+fn push_escape(output: &mut String, byte: u8) {
+    output.push_str(&format!("%{byte:02X}"));
+}
+
+fn encode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut output = String::with_capacity(uri.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        if byte == b'%' {
+            match escape_at(bytes, index) {
+                Some(value) => {
+                    push_escape(&mut output, value);
+                    index += 3;
+                    continue;
+                }
+                None => push_escape(&mut output, byte),
+            }
+        } else if is_allowed(byte) {
+            output.push(char::from(byte));
+        } else {
+            push_escape(&mut output, byte);
+        }
+        index += 1;
+    }
+    output
+}
+
+fn decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut output = String::with_capacity(uri.len());
+    // Escaped non-ASCII bytes are collected until the run of escapes ends, such that the multi-byte
+    // UTF-8 sequences can be decoded (and invalid sequences kept escaped).
+    let mut non_ascii = Vec::new();
+    let mut index = 0;
+    let mut literal_start = 0;
+    while index < bytes.len() {
+        let Some(value) = (bytes[index] == b'%')
+            .then(|| escape_at(bytes, index))
+            .flatten()
+        else {
+            index += 1;
+            continue;
+        };
+        if literal_start < index {
+            flush_non_ascii(&mut output, &mut non_ascii);
+            output.push_str(&uri[literal_start..index]);
+        }
+        if value >= 0x80 {
+            non_ascii.push(value);
+        } else {
+            flush_non_ascii(&mut output, &mut non_ascii);
+            if is_unreserved(value) {
+                output.push(char::from(value));
+            } else {
+                push_escape(&mut output, value);
+            }
+        }
+        index += 3;
+        literal_start = index;
+    }
+    flush_non_ascii(&mut output, &mut non_ascii);
+    output.push_str(&uri[literal_start..]);
+    output
+}
+
+fn flush_non_ascii(output: &mut String, non_ascii: &mut Vec<u8>) {
+    for chunk in non_ascii.utf8_chunks() {
+        output.push_str(chunk.valid());
+        for byte in chunk.invalid() {
+            push_escape(output, *byte);
+        }
+    }
+    non_ascii.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn encode_should_be_idempotent() {
+        let uri = "a b/\"é\"/%41%zz%4/100%";
+        let encoded = normalize_percent_encoding(uri, PercentEncoding::Encode);
+        assert_eq!("a%20b/%22%C3%A9%22/%41%25zz%254/100%25", encoded);
+        assert!(!has_improper_percent_encoding(&encoded));
+        assert!(matches!(
+            normalize_percent_encoding(&encoded, PercentEncoding::Encode),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn decode_should_keep_reserved_and_invalid_escapes() {
+        assert_eq!(
+            "a%2Fb%20c/é%FF%C3-%",
+            normalize_percent_encoding("a%2fb%20c/%C3%A9%ff%c3%2d%", PercentEncoding::Decode)
+        );
+        assert!(matches!(
+            normalize_percent_encoding("a b/é.ts", PercentEncoding::Decode),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn uri_lines_and_attributes_should_be_normalized_separately() {
+        let mut normalizer = PercentEncodingNormalizer::new(PercentEncoding::Encode)
+            .with_uri_attributes(PercentEncoding::Preserve);
+        assert_eq!(
+            Some(HlsLine::Uri(Cow::Owned("a%20b.ts".to_string()))),
+            normalizer.visit_uri(Cow::Borrowed("a b.ts"))
+        );
+        assert_eq!(None, normalizer.normalize_attribute("a b.ts"));
+        assert_eq!(PercentEncoding::Preserve, normalizer.uri_attributes());
+    }
+}
@@ -0,0 +1,370 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    playlist::range_requests,
+    tag::{
+        ByteRange, KnownTag, RangeRequest, UnknownTag,
+        hls::{self, Map, Part, PreloadHint, TagName},
+    },
+};
+
+/// Collects the distinct resources referenced at the live edge of a Low-Latency Media Playlist,
+/// such that a CDN (or a client) can prefetch them without making duplicate requests.
+///
+/// The resources at the live edge are:
+/// * the Media Initialization Section in effect (`EXT-X-MAP`),
+/// * the last Media Segment, along with its Partial Segments (`EXT-X-PART`),
+/// * the Partial Segments of the segment that is still being produced,
+/// * the hinted resources (`EXT-X-PRELOAD-HINT`), which may be a Partial Segment or the next Media
+///   Initialization Section.
+///
+/// Partial Segments commonly address the same resource as their parent segment using byte ranges
+/// (and a hinted part is often the next range of the same resource). The byte ranges of each URI
+/// are therefore merged (where they overlap or are contiguous), and a resource that is referenced
+/// in full is requested only once. Implicit offsets (a `BYTERANGE` without the `@o` part) are
+/// resolved per the HLS specification relative to the previous sub-range of the same resource, and
+/// a hint with a `BYTERANGE-START` but no `BYTERANGE-LENGTH` extends to the end of the resource.
+///
+/// The resources are provided in the order that they are first referenced. Lines that cannot be
+/// parsed are skipped, as are byte ranges that cannot be represented (because the end would
+/// overflow `u64`).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{prefetch_list, PrefetchRange, PrefetchResource};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-PART-INF:PART-TARGET=1
+/// #EXT-X-MAP:URI="init.mp4"
+/// #EXT-X-PART:DURATION=1,URI="seg1.mp4",BYTERANGE="1000@0"
+/// #EXT-X-PART:DURATION=1,URI="seg1.mp4",BYTERANGE="1000"
+/// #EXTINF:2
+/// #EXT-X-BYTERANGE:2000@0
+/// seg1.mp4
+/// #EXT-X-PART:DURATION=1,URI="seg2.mp4",BYTERANGE="1000@0"
+/// #EXT-X-PRELOAD-HINT:TYPE=PART,URI="seg2.mp4",BYTERANGE-START=1000
+/// "#;
+///
+/// assert_eq!(
+///     vec![
+///         PrefetchResource { uri: "init.mp4", ranges: vec![PrefetchRange::WHOLE] },
+///         PrefetchResource {
+///             uri: "seg1.mp4",
+///             ranges: vec![PrefetchRange { start: 0, end: Some(2000) }],
+///         },
+///         PrefetchResource {
+///             uri: "seg2.mp4",
+///             ranges: vec![PrefetchRange { start: 0, end: None }],
+///         },
+///     ],
+///     prefetch_list(PLAYLIST)
+/// );
+/// ```
+pub fn prefetch_list(playlist: &str) -> Vec<PrefetchResource<'_>> {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_tags([TagName::Byterange])
+            .build(),
+    );
+    let mut map = None;
+    let mut last_segment = Vec::new();
+    let mut current_parts = Vec::new();
+    let mut hints = Vec::new();
+    let mut pending_byterange = None;
+    let mut previous_segment_end: Option<(&str, u64)> = None;
+    let mut previous_part_end: Option<(&str, u64)> = None;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Byterange(tag))))) => {
+                pending_byterange = Some(tag.range());
+            }
+            Ok(Some(HlsLine::UnknownTag(tag))) => {
+                let Some(uri) = uri_of(&tag) else { continue };
+                match tag.name() {
+                    "-X-MAP" => {
+                        let Ok(tag) = Map::try_from(tag) else {
+                            continue;
+                        };
+                        let Some(range) = map_range(&tag) else {
+                            continue;
+                        };
+                        map = Some((uri, range));
+                    }
+                    "-X-PART" => {
+                        let Ok(tag) = Part::try_from(tag) else {
+                            continue;
+                        };
+                        let range = match tag.byterange() {
+                            Some(byterange) => resolve(byterange, previous_part_end, uri),
+                            None => Some(PrefetchRange::WHOLE),
+                        };
+                        previous_part_end = range.and_then(|range| range.end.map(|end| (uri, end)));
+                        let Some(range) = range else {
+                            continue;
+                        };
+                        current_parts.push((uri, range));
+                    }
+                    "-X-PRELOAD-HINT" => {
+                        let Ok(tag) = PreloadHint::try_from(tag) else {
+                            continue;
+                        };
+                        let start = tag.byterange_start();
+                        let end = match tag.byterange_length() {
+                            Some(length) => match start.checked_add(length) {
+                                Some(end) => Some(end),
+                                None => continue,
+                            },
+                            None => None,
+                        };
+                        let range = PrefetchRange { start, end };
+                        // Hints of an unknown type are ignored, as clients are required to.
+                        if tag.hint_type().known().is_some() {
+                            hints.push((uri, range));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            Ok(Some(input_uri!(uri))) => {
+                let range = match pending_byterange.take() {
+                    Some(byterange) => resolve(byterange, previous_segment_end, uri),
+                    None => Some(PrefetchRange::WHOLE),
+                };
+                previous_segment_end = range.and_then(|range| range.end.map(|end| (uri, end)));
+                last_segment = std::mem::take(&mut current_parts);
+                last_segment.extend(range.map(|range| (uri, range)));
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+
+    let mut resources: Vec<PrefetchResource> = Vec::new();
+    for (uri, range) in map
+        .into_iter()
+        .chain(last_segment)
+        .chain(current_parts)
+        .chain(hints)
+    {
+        match resources.iter_mut().find(|resource| resource.uri == uri) {
+            Some(resource) => resource.insert(range),
+            None => resources.push(PrefetchResource {
+                uri,
+                ranges: vec![range],
+            }),
+        }
+    }
+    resources
+}
+
+/// A resource to prefetch, as provided by [`prefetch_list`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrefetchResource<'a> {
+    /// The URI of the resource (as written in the playlist, so may be relative).
+    pub uri: &'a str,
+    /// The byte ranges of the resource to request, in ascending order and without overlap. The
+    /// whole resource is requested when this is [`PrefetchRange::WHOLE`].
+    pub ranges: Vec<PrefetchRange>,
+}
+
+impl PrefetchResource<'_> {
+    fn insert(&mut self, range: PrefetchRange) {
+        self.ranges.push(range);
+        self.ranges.sort_by_key(|range| range.start);
+        let mut merged: Vec<PrefetchRange> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some(last) if last.end.is_none_or(|end| range.start <= end) => {
+                    last.end = match (last.end, range.end) {
+                        (Some(a), Some(b)) => Some(a.max(b)),
+                        _ => None,
+                    };
+                }
+                _ => merged.push(range),
+            }
+        }
+        self.ranges = merged;
+    }
+}
+
+/// A byte range of a resource to prefetch.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PrefetchRange {
+    /// The first byte of the range.
+    pub start: u64,
+    /// The end of the range (exclusive). When `None` the range extends to the end of the resource.
+    pub end: Option<u64>,
+}
+
+impl From<RangeRequest> for PrefetchRange {
+    fn from(request: RangeRequest) -> Self {
+        Self {
+            start: request.start,
+            end: Some(request.end),
+        }
+    }
+}
+
+impl PrefetchRange {
+    /// The range that covers the whole resource.
+    pub const WHOLE: Self = Self {
+        start: 0,
+        end: None,
+    };
+
+    /// The value to use for the HTTP `Range` header of the request (if a range is needed).
+    ///
+    /// Note that the HTTP `Range` header uses an inclusive end byte.
+    pub fn range_header(&self) -> Option<String> {
+        match self.end {
+            None if self.start == 0 => None,
+            None => Some(format!("bytes={}-", self.start)),
+            Some(end) if end > self.start => Some(format!("bytes={}-{}", self.start, end - 1)),
+            Some(_) => None,
+        }
+    }
+}
+
+fn uri_of<'a>(tag: &UnknownTag<'a>) -> Option<&'a str> {
+    tag.value()?
+        .try_as_attribute_map()
+        .ok()?
+        .get("URI")?
+        .quoted()
+}
+
+fn map_range(map: &Map) -> Option<PrefetchRange> {
+    match map.byterange() {
+        Some(byterange) => RangeRequest::try_from(byterange)
+            .ok()
+            .map(PrefetchRange::from),
+        None => Some(PrefetchRange::WHOLE),
+    }
+}
+
+fn resolve(
+    byterange: ByteRange,
+    previous_end: Option<(&str, u64)>,
+    uri: &str,
+) -> Option<PrefetchRange> {
+    range_requests::resolve(byterange, previous_end, uri).map(PrefetchRange::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn disjoint_ranges_should_be_kept_separate() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MAP:URI=\"main.mp4\",BYTERANGE=\"500@0\"\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:1000@500\n",
+            "main.mp4\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:1000@4000\n",
+            "main.mp4\n",
+            "#EXT-X-PART:DURATION=1,URI=\"main.mp4\",BYTERANGE=\"500@5000\"\n",
+            "#EXT-X-PRELOAD-HINT:TYPE=MAP,URI=\"next-init.mp4\"\n",
+        );
+        assert_eq!(
+            vec![
+                PrefetchResource {
+                    uri: "main.mp4",
+                    ranges: vec![
+                        PrefetchRange {
+                            start: 0,
+                            end: Some(500)
+                        },
+                        PrefetchRange {
+                            start: 4000,
+                            end: Some(5500)
+                        },
+                    ],
+                },
+                PrefetchResource {
+                    uri: "next-init.mp4",
+                    ranges: vec![PrefetchRange::WHOLE],
+                },
+            ],
+            prefetch_list(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn whole_resource_should_absorb_ranges() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-PART:DURATION=1,URI=\"seg.mp4\",BYTERANGE=\"100@0\"\n",
+            "#EXTINF:1\n",
+            "seg.mp4\n",
+            "#EXT-X-PART:DURATION=1,URI=\"next.mp4\",BYTERANGE=\"100@0\"\n",
+            "#EXT-X-PART:DURATION=1,URI=\"next.mp4\",BYTERANGE=\"100\"\n",
+            "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"next.mp4\",BYTERANGE-START=200,BYTERANGE-LENGTH=100\n",
+        );
+        assert_eq!(
+            vec![
+                PrefetchResource {
+                    uri: "seg.mp4",
+                    ranges: vec![PrefetchRange::WHOLE],
+                },
+                PrefetchResource {
+                    uri: "next.mp4",
+                    ranges: vec![PrefetchRange {
+                        start: 0,
+                        end: Some(300)
+                    }],
+                },
+            ],
+            prefetch_list(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn ranges_that_overflow_should_be_skipped() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"100@18446744073709551600\"\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:100@18446744073709551600\n",
+            "main.mp4\n",
+            "#EXT-X-PART:DURATION=1,URI=\"main.mp4\",BYTERANGE=\"100\"\n",
+            "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"next.mp4\",",
+            "BYTERANGE-START=18446744073709551600,BYTERANGE-LENGTH=100\n",
+        );
+        assert_eq!(
+            vec![PrefetchResource {
+                uri: "main.mp4",
+                ranges: vec![PrefetchRange {
+                    start: 0,
+                    end: Some(100)
+                }],
+            }],
+            prefetch_list(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn range_header_should_support_open_ended_ranges() {
+        assert_eq!(None, PrefetchRange::WHOLE.range_header());
+        assert_eq!(
+            Some("bytes=100-".to_string()),
+            PrefetchRange {
+                start: 100,
+                end: None
+            }
+            .range_header()
+        );
+        assert_eq!(
+            Some("bytes=100-199".to_string()),
+            PrefetchRange {
+                start: 100,
+                end: Some(200)
+            }
+            .range_header()
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    HlsLine,
+    tag::{ByteRange, CustomTag, KnownTag, RangeRequest, hls},
+};
+
+/// Resolves the byte ranges of a Media Playlist into absolute [`RangeRequest`]s, as the lines of the
+/// playlist are read.
+///
+/// A byte range without an offset begins at the next byte following the previous sub-range of the
+/// same resource, and so resolving it requires tracking state across lines: an `EXT-X-BYTERANGE`
+/// follows on from the previous Media Segment, and the `BYTERANGE` of an `EXT-X-PART` follows on
+/// from the previous Partial Segment. When there is no previous sub-range of the same resource, the
+/// sub-range is taken to begin at the start of the resource.
+///
+/// Each line read should be provided to [`Self::track`], which gives the range to request when the
+/// line refers to a sub-range of a resource. The `EXT-X-BYTERANGE`, `EXT-X-MAP`, and `EXT-X-PART`
+/// tags must be parsed by the [`crate::Reader`] (as they are with the default options).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions, playlist::RangeRequestTracker};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MAP:URI="main.mp4",BYTERANGE="720@0"
+/// #EXTINF:4
+/// #EXT-X-BYTERANGE:1000@720
+/// main.mp4
+/// #EXTINF:4
+/// #EXT-X-BYTERANGE:1500
+/// main.mp4
+/// "#;
+///
+/// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+/// let mut tracker = RangeRequestTracker::new();
+/// let mut headers = Vec::new();
+/// while let Some(line) = reader.read_line()? {
+///     if let Some(request) = tracker.track(&line) {
+///         headers.extend(request.header_value());
+///     }
+/// }
+/// assert_eq!(vec!["bytes=0-719", "bytes=720-1719", "bytes=1720-3219"], headers);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RangeRequestTracker {
+    pending_byterange: Option<ByteRange>,
+    previous_segment_end: Option<(String, u64)>,
+    previous_part_end: Option<(String, u64)>,
+}
+
+impl RangeRequestTracker {
+    /// Creates a tracker for reading a playlist from the start.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tracks the line, providing the range to request when the line refers to a sub-range of a
+    /// resource.
+    ///
+    /// This is the case for a Media Segment URI that follows an `EXT-X-BYTERANGE`, an `EXT-X-MAP`
+    /// with a `BYTERANGE`, and an `EXT-X-PART` with a `BYTERANGE`. A range that cannot be
+    /// represented (because the end would overflow `u64`) is not provided.
+    pub fn track<'a, Custom>(&mut self, line: &HlsLine<'a, Custom>) -> Option<RangeRequest>
+    where
+        Custom: CustomTag<'a>,
+    {
+        match line {
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Byterange(tag))) => {
+                self.pending_byterange = Some(tag.range());
+                None
+            }
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Map(tag))) => {
+                RangeRequest::try_from(tag.byterange()?).ok()
+            }
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Part(tag))) => {
+                let request = resolve(
+                    tag.byterange()?,
+                    previous(&self.previous_part_end),
+                    tag.uri(),
+                );
+                self.previous_part_end =
+                    request.map(|request| (tag.uri().to_string(), request.end));
+                request
+            }
+            HlsLine::Uri(uri) => {
+                let request = resolve(
+                    self.pending_byterange.take()?,
+                    previous(&self.previous_segment_end),
+                    uri,
+                );
+                self.previous_segment_end = request.map(|request| (uri.to_string(), request.end));
+                request
+            }
+            _ => None,
+        }
+    }
+}
+
+// Resolves the range into an absolute range, where a range without an offset follows on from
+// `previous_end` (the URI and end of the previous sub-range) when it is of the same resource. This
+// is `None` when the end of the range would overflow `u64`.
+pub(super) fn resolve(
+    range: ByteRange,
+    previous_end: Option<(&str, u64)>,
+    uri: &str,
+) -> Option<RangeRequest> {
+    let previous_end = match previous_end {
+        Some((previous_uri, end)) if previous_uri == uri => end,
+        _ => 0,
+    };
+    RangeRequest::try_from(range.with_implicit_offset(previous_end)).ok()
+}
+
+fn previous(previous_end: &Option<(String, u64)>) -> Option<(&str, u64)> {
+    previous_end.as_ref().map(|(uri, end)| (uri.as_str(), *end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Reader, config::ParsingOptions};
+    use pretty_assertions::assert_eq;
+
+    fn requests(playlist: &str) -> Vec<RangeRequest> {
+        let mut reader = Reader::from_str(playlist, ParsingOptions::default());
+        let mut tracker = RangeRequestTracker::new();
+        let mut requests = Vec::new();
+        while let Ok(Some(line)) = reader.read_line() {
+            requests.extend(tracker.track(&line));
+        }
+        requests
+    }
+
+    #[test]
+    fn implicit_offsets_should_follow_previous_range_of_same_resource() {
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:4\n\
+            #EXTINF:4\n\
+            #EXT-X-BYTERANGE:100@50\n\
+            a.ts\n\
+            #EXTINF:4\n\
+            #EXT-X-BYTERANGE:100\n\
+            b.ts\n\
+            #EXTINF:4\n\
+            #EXT-X-BYTERANGE:100\n\
+            b.ts\n\
+            #EXTINF:4\n\
+            whole.ts\n";
+        assert_eq!(
+            vec![
+                RangeRequest {
+                    start: 50,
+                    end: 150
+                },
+                RangeRequest { start: 0, end: 100 },
+                RangeRequest {
+                    start: 100,
+                    end: 200
+                },
+            ],
+            requests(playlist)
+        );
+    }
+
+    #[test]
+    fn parts_should_be_tracked_separately_from_segments() {
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:4\n\
+            #EXT-X-PART-INF:PART-TARGET=2\n\
+            #EXT-X-PART:DURATION=2,URI=\"s.mp4\",BYTERANGE=\"10@0\"\n\
+            #EXT-X-PART:DURATION=2,URI=\"s.mp4\",BYTERANGE=\"20\"\n\
+            #EXTINF:4\n\
+            #EXT-X-BYTERANGE:30@0\n\
+            s.mp4\n\
+            #EXT-X-PART:DURATION=2,URI=\"s.mp4\",BYTERANGE=\"5\"\n";
+        assert_eq!(
+            vec![
+                RangeRequest { start: 0, end: 10 },
+                RangeRequest { start: 10, end: 30 },
+                RangeRequest { start: 0, end: 30 },
+                RangeRequest { start: 30, end: 35 },
+            ],
+            requests(playlist)
+        );
+    }
+
+    #[test]
+    fn empty_range_should_have_no_header_value() {
+        assert_eq!(None, RangeRequest { start: 5, end: 5 }.header_value());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{HlsLine, Reader, config::ParsingOptions, date::DateTime, tag::hls::TagName};
+
+/// Tracks which `EXT-X-DATERANGE` tags have been removed from a Media Playlist across successive
+/// versions of the playlist, for the purpose of generating the `RECENTLY-REMOVED-DATERANGES`
+/// attribute of `EXT-X-SKIP` when producing Playlist Delta Updates.
+///
+/// Each time a new version of the playlist is produced it should be provided to [`Self::update`].
+/// Any daterange ID that was present in the previous version but is not present in the new version
+/// is recorded as removed at the time provided. Removals are remembered for the `CAN-SKIP-UNTIL`
+/// duration (as a client holding a playlist older than that would not be able to request a Delta
+/// Update anyway), after which they are forgotten. If a removed ID reappears in the playlist then it
+/// is no longer considered removed.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{date_time, playlist::RemovedDaterangeTracker, tag::hls::Skip};
+/// let mut tracker = RemovedDaterangeTracker::new(24.0);
+/// tracker.update(
+///     "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\"\n\
+///      #EXT-X-DATERANGE:ID=\"b\",START-DATE=\"2025-01-01T00:00:04Z\"\n",
+///     date_time!(2025-01-01 T 00:00:30.000),
+/// );
+/// tracker.update(
+///     "#EXT-X-DATERANGE:ID=\"b\",START-DATE=\"2025-01-01T00:00:04Z\"\n",
+///     date_time!(2025-01-01 T 00:00:34.000),
+/// );
+/// assert_eq!(Some("a".to_string()), tracker.recently_removed_dateranges());
+///
+/// let skip = Skip::builder()
+///     .with_skipped_segments(10)
+///     .with_recently_removed_dateranges(tracker.recently_removed_dateranges().unwrap())
+///     .finish();
+/// assert_eq!(Some("a"), skip.recently_removed_dateranges());
+///
+/// // After CAN-SKIP-UNTIL has passed the removal is forgotten.
+/// tracker.update("", date_time!(2025-01-01 T 00:00:59.000));
+/// assert_eq!(Some("b".to_string()), tracker.recently_removed_dateranges());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemovedDaterangeTracker {
+    can_skip_until: f64,
+    present: Vec<String>,
+    removed: Vec<(String, DateTime)>,
+}
+
+impl RemovedDaterangeTracker {
+    /// Creates a new tracker for a playlist with the provided `CAN-SKIP-UNTIL` (in seconds).
+    pub fn new(can_skip_until: f64) -> Self {
+        Self {
+            can_skip_until,
+            present: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+
+    /// Updates the tracker with a new version of the playlist, produced at the time `now`.
+    pub fn update(&mut self, playlist: &str, now: DateTime) {
+        let present = daterange_ids(playlist);
+        for id in self.present.iter().filter(|id| !present.contains(id)) {
+            self.removed.push((id.clone(), now));
+        }
+        let oldest = now.timestamp() - self.can_skip_until;
+        self.removed
+            .retain(|(id, removed_at)| !present.contains(id) && removed_at.timestamp() >= oldest);
+        self.present = present;
+    }
+
+    /// The IDs of dateranges that were recently removed, in the order in which they were removed.
+    pub fn recently_removed(&self) -> impl Iterator<Item = &str> {
+        self.removed.iter().map(|(id, _)| id.as_str())
+    }
+
+    /// The value to use for the `RECENTLY-REMOVED-DATERANGES` attribute of `EXT-X-SKIP` (a tab
+    /// delimited list of IDs), or `None` if no dateranges were recently removed.
+    pub fn recently_removed_dateranges(&self) -> Option<String> {
+        if self.removed.is_empty() {
+            None
+        } else {
+            Some(self.recently_removed().collect::<Vec<_>>().join("\t"))
+        }
+    }
+}
+
+fn daterange_ids(playlist: &str) -> Vec<String> {
+    let mut reader = Reader::from_str(playlist, ParsingOptions::builder().build());
+    let mut ids = Vec::new();
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::UnknownTag(tag))) => {
+                if !matches!(TagName::try_from(tag.name()), Ok(TagName::Daterange)) {
+                    continue;
+                }
+                let id = tag
+                    .value()
+                    .and_then(|value| value.try_as_attribute_map().ok())
+                    .and_then(|list| list.get("ID"))
+                    .and_then(|id| id.quoted());
+                if let Some(id) = id
+                    && !ids.iter().any(|existing| existing == id)
+                {
+                    ids.push(id.to_string());
+                }
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_time;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn reappearing_daterange_should_not_be_removed() {
+        let mut tracker = RemovedDaterangeTracker::new(30.0);
+        let with_a = "#EXT-X-DATERANGE:ID=\"a\"\n";
+        tracker.update(with_a, date_time!(2025-01-01 T 00:00:00.000));
+        tracker.update("", date_time!(2025-01-01 T 00:00:04.000));
+        assert_eq!(vec!["a"], tracker.recently_removed().collect::<Vec<_>>());
+        tracker.update(with_a, date_time!(2025-01-01 T 00:00:08.000));
+        assert_eq!(None, tracker.recently_removed_dateranges());
+    }
+
+    #[test]
+    fn recently_removed_should_be_tab_delimited() {
+        let mut tracker = RemovedDaterangeTracker::new(30.0);
+        tracker.update(
+            "#EXT-X-DATERANGE:ID=\"a\"\n",
+            date_time!(2025-01-01 T 00:00:00.000),
+        );
+        tracker.update(
+            "#EXT-X-DATERANGE:ID=\"b\"\n",
+            date_time!(2025-01-01 T 00:00:04.000),
+        );
+        tracker.update("", date_time!(2025-01-01 T 00:00:08.000));
+        assert_eq!(
+            Some("a\tb".to_string()),
+            tracker.recently_removed_dateranges()
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    tag::{
+        AttributeMap, UnknownTag,
+        hls::{EnumeratedString, MediaType, TagName},
+    },
+};
+
+/// Collects every rendition (`EXT-X-MEDIA`) of a Multivariant Playlist, along with the Variant
+/// Streams (`EXT-X-STREAM-INF`) that reference the group of each rendition.
+///
+/// This helps a downloader decide what needs to be fetched. A rendition that is
+/// [`Rendition::is_carried_in_variant`] (e.g. muxed audio, where the `URI` is absent) has no Media
+/// Playlist of its own, and instead its media is found within the segments of each of the
+/// [`Rendition::variants`]. The `CODECS` of each variant is provided so that it can be checked
+/// that the variant does in fact carry the muxed media.
+///
+/// No HLS tags are fully parsed (only the attributes needed are extracted), and the values returned
+/// borrow directly from the input. Lines that cannot be parsed are skipped.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{renditions, RenditionVariant};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",NAME="English",DEFAULT=YES
+/// #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",NAME="French",URI="audio/fr.m3u8"
+/// #EXT-X-STREAM-INF:BANDWIDTH=1280000,CODECS="avc1.4d401f,mp4a.40.2",AUDIO="aac"
+/// low.m3u8
+/// #EXT-X-STREAM-INF:BANDWIDTH=2560000,CODECS="avc1.4d401f,mp4a.40.2",AUDIO="aac"
+/// high.m3u8
+/// "#;
+///
+/// let renditions = renditions(PLAYLIST);
+/// let english = &renditions[0];
+/// assert!(english.is_carried_in_variant());
+/// assert_eq!(
+///     vec![
+///         RenditionVariant { uri: "low.m3u8", codecs: Some("avc1.4d401f,mp4a.40.2") },
+///         RenditionVariant { uri: "high.m3u8", codecs: Some("avc1.4d401f,mp4a.40.2") },
+///     ],
+///     english.variants
+/// );
+/// let french = &renditions[1];
+/// assert!(!french.is_carried_in_variant());
+/// assert_eq!(Some("audio/fr.m3u8"), french.uri);
+/// ```
+pub fn renditions(playlist: &str) -> Vec<Rendition<'_>> {
+    let mut reader = Reader::from_str(playlist, ParsingOptions::builder().build());
+    let mut renditions = Vec::new();
+    let mut variants = Vec::new();
+    let mut pending_variant = None;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::UnknownTag(tag))) => match TagName::try_from(tag.name()) {
+                Ok(TagName::Media) => {
+                    if let Some(rendition) = attribute_map(&tag).and_then(|list| rendition(&list)) {
+                        renditions.push(rendition);
+                    }
+                }
+                Ok(TagName::StreamInf) => pending_variant = attribute_map(&tag),
+                _ => (),
+            },
+            Ok(Some(input_uri!(uri))) => {
+                if let Some(list) = pending_variant.take() {
+                    variants.push((uri, list));
+                }
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    for rendition in &mut renditions {
+        let group_attribute = match rendition.media_type {
+            EnumeratedString::Known(MediaType::Audio) => "AUDIO",
+            EnumeratedString::Known(MediaType::Video) => "VIDEO",
+            EnumeratedString::Known(MediaType::Subtitles) => "SUBTITLES",
+            EnumeratedString::Known(MediaType::ClosedCaptions) => "CLOSED-CAPTIONS",
+            EnumeratedString::Unknown(_) => continue,
+        };
+        rendition.variants = variants
+            .iter()
+            .filter(|(_, list)| {
+                list.get(group_attribute).and_then(|v| v.quoted()) == Some(rendition.group_id)
+            })
+            .map(|(uri, list)| RenditionVariant {
+                uri,
+                codecs: list.get("CODECS").and_then(|v| v.quoted()),
+            })
+            .collect();
+    }
+    renditions
+}
+
+/// A rendition (`EXT-X-MEDIA`) along with the Variant Streams that reference its group.
+///
+/// See [`renditions`] for more information.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Rendition<'a> {
+    /// The `TYPE` of the rendition.
+    pub media_type: EnumeratedString<'a, MediaType>,
+    /// The `GROUP-ID` of the rendition.
+    pub group_id: &'a str,
+    /// The `NAME` of the rendition.
+    pub name: &'a str,
+    /// The `URI` of the rendition (if present).
+    pub uri: Option<&'a str>,
+    /// The Variant Streams that reference the group of the rendition (in playlist order).
+    pub variants: Vec<RenditionVariant<'a>>,
+}
+
+impl Rendition<'_> {
+    /// Whether the `URI` attribute must be present for the `TYPE` of this rendition.
+    ///
+    /// This has the same meaning as [`crate::tag::hls::Media::requires_uri`].
+    pub fn requires_uri(&self) -> bool {
+        matches!(
+            self.media_type,
+            EnumeratedString::Known(MediaType::Subtitles)
+        )
+    }
+
+    /// Whether the media of the rendition is carried within the [`Self::variants`], rather than
+    /// within a separate Media Playlist.
+    ///
+    /// This has the same meaning as [`crate::tag::hls::Media::is_carried_in_variant`].
+    pub fn is_carried_in_variant(&self) -> bool {
+        match self.media_type {
+            EnumeratedString::Known(MediaType::Audio | MediaType::Video) => self.uri.is_none(),
+            EnumeratedString::Known(MediaType::ClosedCaptions) => true,
+            EnumeratedString::Known(MediaType::Subtitles) | EnumeratedString::Unknown(_) => false,
+        }
+    }
+}
+
+/// A Variant Stream that references the group of a [`Rendition`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct RenditionVariant<'a> {
+    /// The URI of the Variant Stream.
+    pub uri: &'a str,
+    /// The `CODECS` of the Variant Stream (if present).
+    pub codecs: Option<&'a str>,
+}
+
+fn attribute_map<'a>(tag: &UnknownTag<'a>) -> Option<AttributeMap<'a>> {
+    tag.value()?.try_as_attribute_map().ok()
+}
+
+fn rendition<'a>(list: &AttributeMap<'a>) -> Option<Rendition<'a>> {
+    let media_type = list
+        .get("TYPE")?
+        .unquoted()?
+        .try_as_utf_8()
+        .ok()
+        .map(EnumeratedString::from)?;
+    Some(Rendition {
+        media_type,
+        group_id: list.get("GROUP-ID")?.quoted()?,
+        name: list.get("NAME")?.quoted()?,
+        uri: list.get("URI").and_then(|v| v.quoted()),
+        variants: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn renditions_should_be_matched_to_variants_by_type_and_group() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA:TYPE=CLOSED-CAPTIONS,GROUP-ID=\"cc\",NAME=\"English\",INSTREAM-ID=\"CC1\"\n",
+            "#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"subs\",NAME=\"English\",URI=\"subs/en.m3u8\"\n",
+            "#EXT-X-MEDIA:TYPE=VIDEO,GROUP-ID=\"cc\",NAME=\"Angle\",URI=\"angle.m3u8\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000,CLOSED-CAPTIONS=\"cc\",SUBTITLES=\"subs\"\n",
+            "a.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=2000,CLOSED-CAPTIONS=NONE,VIDEO=\"cc\"\n",
+            "b.m3u8\n",
+        );
+        let renditions = renditions(PLAYLIST);
+        let a = RenditionVariant {
+            uri: "a.m3u8",
+            codecs: None,
+        };
+        let b = RenditionVariant {
+            uri: "b.m3u8",
+            codecs: None,
+        };
+        assert_eq!(
+            vec![
+                Rendition {
+                    media_type: EnumeratedString::Known(MediaType::ClosedCaptions),
+                    group_id: "cc",
+                    name: "English",
+                    uri: None,
+                    variants: vec![a],
+                },
+                Rendition {
+                    media_type: EnumeratedString::Known(MediaType::Subtitles),
+                    group_id: "subs",
+                    name: "English",
+                    uri: Some("subs/en.m3u8"),
+                    variants: vec![a],
+                },
+                Rendition {
+                    media_type: EnumeratedString::Known(MediaType::Video),
+                    group_id: "cc",
+                    name: "Angle",
+                    uri: Some("angle.m3u8"),
+                    variants: vec![b],
+                },
+            ],
+            renditions
+        );
+        assert!(renditions[0].is_carried_in_variant());
+        assert!(!renditions[0].requires_uri());
+        assert!(!renditions[1].is_carried_in_variant());
+        assert!(renditions[1].requires_uri());
+        assert!(!renditions[2].is_carried_in_variant());
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{
+    playlist::{MediaPlaylist, MediaSegment},
+    tag::{CustomTag, NoCustomTag},
+};
+
+/// Visits the segments of a [`MediaPlaylist`] while allowing segments to be inserted and removed.
+///
+/// Editing passes (such as ad replacement) often need to insert or remove segments around the one
+/// being visited, which is not possible while iterating over `segments` directly. The editor is
+/// constructed via [`MediaPlaylist::edit_segments`] and visits the segments as they were when it
+/// was constructed (a snapshot), each exactly once and in order, regardless of the edits made along
+/// the way: inserted segments are never visited, and removing the current segment does not skip
+/// the next one. The edits are applied to the playlist when the editor is dropped.
+///
+/// For example, replacing segments with an ad break:
+/// ```
+/// # use quick_m3u8::{Reader, config::ParsingOptions, playlist::Playlist};
+/// # use std::borrow::Cow;
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXTINF:4
+/// content_1.ts
+/// #EXTINF:4
+/// slate_1.ts
+/// #EXTINF:4
+/// slate_2.ts
+/// #EXTINF:4
+/// content_2.ts
+/// "#;
+/// let reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+/// let Playlist::Media(mut playlist) = Playlist::try_from(reader)? else {
+///     panic!("should be a media playlist");
+/// };
+/// let ad = playlist.segments[1].clone();
+///
+/// let mut editor = playlist.edit_segments();
+/// let mut in_break = false;
+/// while let Some(segment) = editor.next() {
+///     if !segment.uri.starts_with("slate") {
+///         segment.discontinuity = in_break;
+///         in_break = false;
+///         continue;
+///     }
+///     editor.remove();
+///     if !in_break {
+///         let mut ad = ad.clone();
+///         ad.uri = Cow::Borrowed("ad.ts");
+///         ad.discontinuity = true;
+///         editor.insert_after(ad);
+///         in_break = true;
+///     }
+/// }
+/// drop(editor);
+///
+/// assert_eq!(
+///     vec![("content_1.ts", false), ("ad.ts", true), ("content_2.ts", true)],
+///     playlist
+///         .segments
+///         .iter()
+///         .map(|segment| (segment.uri.as_ref(), segment.discontinuity))
+///         .collect::<Vec<_>>()
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct SegmentEditor<'p, 'a, Custom = NoCustomTag>
+where
+    Custom: CustomTag<'a>,
+{
+    segments: &'p mut Vec<MediaSegment<'a, Custom>>,
+    // The segments that have been visited (and those inserted along the way), in playlist order.
+    edited: Vec<MediaSegment<'a, Custom>>,
+    current: Option<MediaSegment<'a, Custom>>,
+    // The segments inserted after the current segment, which are placed once it has been visited.
+    inserted_after: Vec<MediaSegment<'a, Custom>>,
+    remaining: std::vec::IntoIter<MediaSegment<'a, Custom>>,
+}
+
+impl<'a, Custom> MediaPlaylist<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    /// Provides a [`SegmentEditor`] for visiting the segments while inserting and removing others.
+    pub fn edit_segments(&mut self) -> SegmentEditor<'_, 'a, Custom> {
+        let remaining = std::mem::take(&mut self.segments).into_iter();
+        SegmentEditor {
+            edited: Vec::with_capacity(remaining.len()),
+            segments: &mut self.segments,
+            current: None,
+            inserted_after: Vec::new(),
+            remaining,
+        }
+    }
+}
+
+impl<'p, 'a, Custom> SegmentEditor<'p, 'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    /// Moves on to the next segment of the snapshot, providing it for modification (or `None` when
+    /// all segments have been visited).
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&mut MediaSegment<'a, Custom>> {
+        self.place_current();
+        self.current = self.remaining.next();
+        self.current.as_mut()
+    }
+
+    /// The segment being visited (or `None` before the first call to [`Self::next`], after all
+    /// segments have been visited, or after the segment has been removed).
+    pub fn current(&mut self) -> Option<&mut MediaSegment<'a, Custom>> {
+        self.current.as_mut()
+    }
+
+    /// Removes the segment being visited. Segments that were inserted before or after it remain.
+    pub fn remove(&mut self) -> Option<MediaSegment<'a, Custom>> {
+        self.current.take()
+    }
+
+    /// Inserts a segment before the segment being visited (after any segments previously inserted
+    /// before it). Before the first call to [`Self::next`], this inserts at the start of the
+    /// playlist, and after all segments have been visited, this inserts at the end.
+    pub fn insert_before(&mut self, segment: MediaSegment<'a, Custom>) {
+        self.edited.push(segment);
+    }
+
+    /// Inserts a segment after the segment being visited (after any segments previously inserted
+    /// after it). The inserted segment is not visited by [`Self::next`].
+    pub fn insert_after(&mut self, segment: MediaSegment<'a, Custom>) {
+        if self.current.is_some() {
+            self.inserted_after.push(segment);
+        } else {
+            self.edited.push(segment);
+        }
+    }
+
+    /// The segments that precede the segment being visited, as edited so far.
+    pub fn preceding(&self) -> &[MediaSegment<'a, Custom>] {
+        &self.edited
+    }
+
+    /// The segments of the snapshot that are yet to be visited.
+    pub fn remaining(&self) -> &[MediaSegment<'a, Custom>] {
+        self.remaining.as_slice()
+    }
+
+    fn place_current(&mut self) {
+        self.edited.extend(self.current.take());
+        self.edited.append(&mut self.inserted_after);
+    }
+}
+
+impl<'p, 'a, Custom> Drop for SegmentEditor<'p, 'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    fn drop(&mut self) {
+        self.place_current();
+        self.edited.extend(self.remaining.by_ref());
+        *self.segments = std::mem::take(&mut self.edited);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        HlsLine,
+        tag::hls::{Endlist, Inf, Targetduration},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn playlist(uris: &[&'static str]) -> MediaPlaylist<'static> {
+        let mut lines = vec![HlsLine::from(Targetduration::new(4))];
+        for uri in uris {
+            lines.push(HlsLine::from(Inf::new(4.0, "")));
+            lines.push(HlsLine::uri(*uri));
+        }
+        lines.push(HlsLine::from(Endlist));
+        MediaPlaylist::from_lines(lines)
+    }
+
+    fn uris<'p>(playlist: &'p MediaPlaylist) -> Vec<&'p str> {
+        playlist
+            .segments
+            .iter()
+            .map(|segment| segment.uri.as_ref())
+            .collect()
+    }
+
+    fn segment(uri: &'static str) -> MediaSegment<'static> {
+        playlist(&[uri]).segments.remove(0)
+    }
+
+    #[test]
+    fn editor_should_visit_each_snapshot_segment_once() {
+        let mut playlist = playlist(&["a", "b", "c"]);
+        let mut editor = playlist.edit_segments();
+        let mut visited = Vec::new();
+        while let Some(current) = editor.next() {
+            let uri = current.uri.to_string();
+            editor.insert_before(segment("before"));
+            editor.insert_after(segment("after"));
+            if uri == "b" {
+                assert_eq!(Some("b".to_string()), editor.remove().map(|s| s.uri.into()));
+                assert_eq!(None, editor.current());
+            }
+            assert_eq!(
+                editor.remaining().len(),
+                2 - visited.len(),
+                "remaining at {uri}"
+            );
+            visited.push(uri);
+        }
+        assert_eq!(vec!["a", "b", "c"], visited);
+        drop(editor);
+        assert_eq!(
+            vec![
+                "before", "a", "after", "before", "after", "before", "c", "after"
+            ],
+            uris(&playlist)
+        );
+    }
+
+    #[test]
+    fn dropping_editor_early_should_keep_remaining_segments() {
+        let mut playlist = playlist(&["a", "b", "c"]);
+        let mut editor = playlist.edit_segments();
+        editor.insert_after(segment("start"));
+        editor.next();
+        assert_eq!(
+            vec!["start"],
+            editor
+                .preceding()
+                .iter()
+                .map(|s| s.uri.as_ref())
+                .collect::<Vec<_>>()
+        );
+        editor.insert_after(segment("after_a"));
+        drop(editor);
+        assert_eq!(vec!["start", "a", "after_a", "b", "c"], uris(&playlist));
+        assert!(playlist.ended);
+    }
+
+    #[test]
+    fn inserting_after_all_segments_should_append() {
+        let mut playlist = playlist(&["a"]);
+        let mut editor = playlist.edit_segments();
+        while editor.next().is_some() {}
+        editor.insert_after(segment("end"));
+        drop(editor);
+        assert_eq!(vec!["a", "end"], uris(&playlist));
+    }
+}
@@ -0,0 +1,342 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    tag::{
+        KnownTag, NoCustomTag,
+        hls::{self, EnumeratedString, Method, TagName, TagType},
+    },
+};
+use std::ops::Range;
+
+/// An index of the byte offsets of the Media Segments of a Media Playlist, built with a single scan
+/// of the playlist, that allows the lines of any segment to be retrieved from the original data
+/// without scanning it again.
+///
+/// This is intended for services that repeatedly access arbitrary positions of very large VOD
+/// playlists (e.g. a clipping service over a playlist of tens of thousands of segments), where
+/// reading the playlist from the start for each access is too costly. The index only stores
+/// offsets, and so does not borrow the playlist; the same data must be provided when accessing a
+/// segment. The accessors return `None` when the data provided is not the data that was indexed
+/// (as far as can be cheaply detected, i.e. when the length differs).
+///
+/// The lines of a segment are the lines after the URI of the previous segment up to and including
+/// the URI of the segment. The lines before the first segment (the header) and after the last
+/// segment (the trailer, e.g. `EXT-X-ENDLIST`) are available separately. The `EXT-X-MAP` and
+/// `EXT-X-KEY` tags that apply to a segment may appear on the lines of an earlier segment, and so
+/// the index also records where to find them (see [`Self::map_line`] and [`Self::key_lines`]).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{HlsLine, playlist::SegmentIndex, config::ParsingOptions};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MEDIA-SEQUENCE:100
+/// #EXT-X-MAP:URI="init.mp4"
+/// #EXTINF:4
+/// 100.mp4
+/// #EXTINF:4
+/// 101.mp4
+/// #EXTINF:2
+/// 102.mp4
+/// #EXT-X-ENDLIST
+/// "#;
+///
+/// let index = SegmentIndex::build(PLAYLIST);
+/// assert_eq!(3, index.len());
+/// assert_eq!(Some("#EXTINF:4\n101.mp4\n"), index.segment_str(PLAYLIST, 1));
+/// assert_eq!(Some(101), index.media_sequence(1));
+/// assert_eq!(Some(r#"#EXT-X-MAP:URI="init.mp4""#), index.map_line(PLAYLIST, 2));
+/// assert_eq!(Some("#EXT-X-ENDLIST\n"), index.trailer_str(PLAYLIST));
+///
+/// let mut reader = index
+///     .segment_reader(PLAYLIST, 2, ParsingOptions::default())
+///     .expect("segment should exist");
+/// let mut duration = 0.0;
+/// while let Some(line) = reader.read_line()? {
+///     if let HlsLine::KnownTag(quick_m3u8::tag::KnownTag::Hls(
+///         quick_m3u8::tag::hls::Tag::Inf(inf),
+///     )) = line
+///     {
+///         duration = inf.duration();
+///     }
+/// }
+/// assert_eq!(2.0, duration);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentIndex {
+    source_len: usize,
+    // The start of the first segment, followed by the end of each segment (so one more entry than
+    // there are segments).
+    boundaries: Vec<usize>,
+    segments: Vec<SegmentState>,
+    maps: Vec<Range<usize>>,
+    key_sets: Vec<Vec<Range<usize>>>,
+    media_sequence: u64,
+}
+
+// The indices (into `maps` and `key_sets`) of the state that applies to a segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SegmentState {
+    map: Option<usize>,
+    key_set: Option<usize>,
+}
+
+impl SegmentIndex {
+    /// Builds the index with a single scan of the playlist.
+    ///
+    /// Lines that fail to parse are skipped (they remain part of the segment that they appear in).
+    pub fn build(playlist: &str) -> Self {
+        let options = ParsingOptions::builder()
+            .with_parsing_for_media_sequence()
+            .with_parsing_for_key()
+            .build();
+        let mut reader = Reader::from_str(playlist, options);
+        let mut index = Self {
+            source_len: playlist.len(),
+            boundaries: Vec::new(),
+            segments: Vec::new(),
+            maps: Vec::new(),
+            key_sets: Vec::new(),
+            media_sequence: 0,
+        };
+        let mut state = SegmentState {
+            map: None,
+            key_set: None,
+        };
+        let mut keys: Vec<(String, Range<usize>)> = Vec::new();
+        loop {
+            let start = reader.position().byte_offset;
+            let line = match reader.read_line() {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(_) => continue,
+            };
+            let range = start..reader.position().byte_offset;
+            let is_segment_line = match &line {
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::MediaSequence(tag))) => {
+                    index.media_sequence = tag.media_sequence();
+                    false
+                }
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Key(key))) => {
+                    if key.method() == EnumeratedString::Known(Method::None) {
+                        keys.clear();
+                    } else {
+                        keys.retain(|(keyformat, _)| keyformat != key.keyformat());
+                        keys.push((key.keyformat().to_string(), range.clone()));
+                    }
+                    state.key_set = if keys.is_empty() {
+                        None
+                    } else {
+                        index
+                            .key_sets
+                            .push(keys.iter().map(|(_, range)| range.clone()).collect());
+                        Some(index.key_sets.len() - 1)
+                    };
+                    true
+                }
+                HlsLine::UnknownTag(tag) => match TagName::try_from(tag.name()) {
+                    Ok(TagName::Map) => {
+                        index.maps.push(range.clone());
+                        state.map = Some(index.maps.len() - 1);
+                        true
+                    }
+                    Ok(name) => name.tag_type() == TagType::MediaSegment,
+                    Err(_) => false,
+                },
+                HlsLine::Uri(_) => {
+                    if index.boundaries.is_empty() {
+                        index.boundaries.push(range.start);
+                    }
+                    index.boundaries.push(range.end);
+                    index.segments.push(state);
+                    continue;
+                }
+                _ => false,
+            };
+            if is_segment_line && index.boundaries.is_empty() {
+                index.boundaries.push(range.start);
+            }
+        }
+        if index.boundaries.is_empty() {
+            index.boundaries.push(playlist.len());
+        }
+        index
+    }
+
+    /// The number of segments in the playlist.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Indicates whether the playlist has no segments.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// The Media Sequence Number of the segment at the position (starting at 0). `None` is returned
+    /// when there is no segment at the position, or when the number would be greater than
+    /// `u64::MAX`.
+    pub fn media_sequence(&self, position: usize) -> Option<u64> {
+        (position < self.len())
+            .then(|| self.media_sequence.checked_add(position as u64))
+            .flatten()
+    }
+
+    /// The position of the segment with the Media Sequence Number.
+    pub fn position_of(&self, media_sequence: u64) -> Option<usize> {
+        let position = usize::try_from(media_sequence.checked_sub(self.media_sequence)?).ok()?;
+        (position < self.len()).then_some(position)
+    }
+
+    /// The byte range in the playlist of the lines of the segment at the position.
+    pub fn segment_range(&self, position: usize) -> Option<Range<usize>> {
+        (position < self.len()).then(|| self.boundaries[position]..self.boundaries[position + 1])
+    }
+
+    /// The lines of the segment at the position, as they appear in the playlist (including line
+    /// endings).
+    pub fn segment_str<'a>(&self, playlist: &'a str, position: usize) -> Option<&'a str> {
+        self.slice(playlist, self.segment_range(position)?)
+    }
+
+    /// A [`Reader`] over the lines of the segment at the position.
+    pub fn segment_reader<'a>(
+        &self,
+        playlist: &'a str,
+        position: usize,
+        options: ParsingOptions,
+    ) -> Option<Reader<&'a str, NoCustomTag>> {
+        self.segment_str(playlist, position)
+            .map(|segment| Reader::from_str(segment, options))
+    }
+
+    /// The lines before the first segment (including line endings).
+    pub fn header_str<'a>(&self, playlist: &'a str) -> Option<&'a str> {
+        self.slice(playlist, 0..self.boundaries[0])
+    }
+
+    /// The lines after the last segment (including line endings).
+    pub fn trailer_str<'a>(&self, playlist: &'a str) -> Option<&'a str> {
+        let start = self.boundaries[self.boundaries.len() - 1];
+        self.slice(playlist, start..self.source_len)
+    }
+
+    /// The `EXT-X-MAP` line (without line ending) that applies to the segment at the position.
+    pub fn map_line<'a>(&self, playlist: &'a str, position: usize) -> Option<&'a str> {
+        let map = self.segments.get(position)?.map?;
+        self.slice(playlist, self.maps[map].clone())
+            .map(trim_line_ending)
+    }
+
+    /// The `EXT-X-KEY` lines (without line endings) that apply to the segment at the position (one
+    /// per `KEYFORMAT`). This is empty when the segment is not encrypted (or when the data provided
+    /// is not the data that was indexed).
+    pub fn key_lines<'a>(&self, playlist: &'a str, position: usize) -> Vec<&'a str> {
+        let Some(key_set) = self.segments.get(position).and_then(|state| state.key_set) else {
+            return Vec::new();
+        };
+        self.key_sets[key_set]
+            .iter()
+            .filter_map(|range| self.slice(playlist, range.clone()))
+            .map(trim_line_ending)
+            .collect()
+    }
+
+    fn slice<'a>(&self, playlist: &'a str, range: Range<usize>) -> Option<&'a str> {
+        if playlist.len() != self.source_len {
+            return None;
+        }
+        playlist.get(range)
+    }
+}
+
+fn trim_line_ending(line: &str) -> &str {
+    line.trim_end_matches(['\r', '\n'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = "#EXTM3U\r\n\
+        #EXT-X-TARGETDURATION:4\r\n\
+        #EXT-X-MEDIA-SEQUENCE:7\r\n\
+        #EXT-X-KEY:METHOD=AES-128,URI=\"k1\"\r\n\
+        #EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://a\",KEYFORMAT=\"com.apple.streamingkeydelivery\"\r\n\
+        #EXTINF:4\r\n\
+        0.ts\r\n\
+        # comment\r\n\
+        #EXT-X-KEY:METHOD=AES-128,URI=\"k2\"\r\n\
+        #EXTINF:4\r\n\
+        1.ts\r\n\
+        #EXT-X-KEY:METHOD=NONE\r\n\
+        #EXTINF:4\r\n\
+        2.ts\r\n";
+
+    #[test]
+    fn segments_should_be_retrievable_by_position() {
+        let index = SegmentIndex::build(PLAYLIST);
+        assert_eq!(3, index.len());
+        assert_eq!(
+            Some("#EXTM3U\r\n#EXT-X-TARGETDURATION:4\r\n#EXT-X-MEDIA-SEQUENCE:7\r\n"),
+            index.header_str(PLAYLIST)
+        );
+        assert_eq!(
+            Some("# comment\r\n#EXT-X-KEY:METHOD=AES-128,URI=\"k2\"\r\n#EXTINF:4\r\n1.ts\r\n"),
+            index.segment_str(PLAYLIST, 1)
+        );
+        assert_eq!(Some(""), index.trailer_str(PLAYLIST));
+        assert_eq!(None, index.segment_str(PLAYLIST, 3));
+        assert_eq!(Some(2), index.position_of(9));
+        assert_eq!(None, index.position_of(6));
+        assert_eq!(None, index.segment_str("#EXTM3U\n", 0));
+    }
+
+    #[test]
+    fn keys_should_be_tracked_per_keyformat() {
+        let index = SegmentIndex::build(PLAYLIST);
+        assert_eq!(
+            vec![
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"k1\"",
+                "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://a\",KEYFORMAT=\"com.apple.streamingkeydelivery\"",
+            ],
+            index.key_lines(PLAYLIST, 0)
+        );
+        assert_eq!(
+            vec![
+                "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://a\",KEYFORMAT=\"com.apple.streamingkeydelivery\"",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"k2\"",
+            ],
+            index.key_lines(PLAYLIST, 1)
+        );
+        assert_eq!(Vec::<&str>::new(), index.key_lines(PLAYLIST, 2));
+        assert_eq!(None, index.map_line(PLAYLIST, 0));
+    }
+
+    #[test]
+    fn playlist_without_segments_should_be_all_header() {
+        let playlist = "#EXTM3U\n#EXT-X-TARGETDURATION:4\n";
+        let index = SegmentIndex::build(playlist);
+        assert!(index.is_empty());
+        assert_eq!(Some(playlist), index.header_str(playlist));
+        assert_eq!(Some(""), index.trailer_str(playlist));
+        assert_eq!(None, index.media_sequence(0));
+    }
+
+    #[test]
+    fn media_sequence_beyond_largest_value_should_be_none() {
+        let playlist = "#EXTM3U\n\
+            #EXT-X-MEDIA-SEQUENCE:18446744073709551615\n\
+            #EXTINF:4\n\
+            a.ts\n\
+            #EXTINF:4\n\
+            b.ts\n";
+        let index = SegmentIndex::build(playlist);
+        assert_eq!(2, index.len());
+        assert_eq!(Some(u64::MAX), index.media_sequence(0));
+        assert_eq!(None, index.media_sequence(1));
+        assert_eq!(Some(0), index.position_of(u64::MAX));
+    }
+}
@@ -0,0 +1,412 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    date::DateTime,
+    playlist::range_requests::resolve,
+    tag::{AttributeMap, DecimalIntegerRange, KnownTag, RangeRequest, UnknownTag, hls},
+};
+use std::ops::Range;
+
+/// Exports a Media Playlist into a neutral [`SegmentList`] model.
+///
+/// The segment list is decoupled from the M3U8 text, with all of the state that HLS carries across
+/// lines (media sequence numbers, discontinuity sequence numbers, byte range offsets, initialization
+/// sections, keys, and program date times) resolved onto each segment. This makes it a convenient
+/// interchange format for tools such as transmuxers or DASH converters, that need to know
+/// everything about a segment without having to understand HLS playlist semantics.
+///
+/// Lines that cannot be parsed are skipped, as are segments with a byte range that cannot be
+/// represented (because the end would overflow `u64`). Sequence numbers that would go beyond
+/// `u64::MAX` stay at `u64::MAX`.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{segment_list, ExportedSegment, InitSegment};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MEDIA-SEQUENCE:10
+/// #EXT-X-MAP:URI="init.mp4",BYTERANGE="700@0"
+/// #EXTINF:4
+/// #EXT-X-BYTERANGE:1000@700
+/// main.mp4
+/// #EXT-X-DISCONTINUITY
+/// #EXTINF:3.5
+/// #EXT-X-BYTERANGE:800
+/// main.mp4
+/// #EXT-X-ENDLIST
+/// "#;
+///
+/// let list = segment_list(PLAYLIST);
+/// assert_eq!(Some(4), list.target_duration);
+/// assert!(list.ended);
+/// assert_eq!(7.5, list.duration());
+/// let init_segment = Some(InitSegment { uri: "init.mp4", byte_range: Some(0..700) });
+/// assert_eq!(
+///     ExportedSegment {
+///         uri: "main.mp4",
+///         media_sequence: 11,
+///         discontinuity_sequence: 1,
+///         discontinuity: true,
+///         start: 4.0,
+///         duration: 3.5,
+///         program_date_time: None,
+///         byte_range: Some(1700..2500),
+///         init_segment,
+///         encryption: vec![],
+///         gap: false,
+///     },
+///     list.segments[1]
+/// );
+/// ```
+pub fn segment_list(playlist: &str) -> SegmentList<'_> {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_parsing_for_targetduration()
+            .with_parsing_for_media_sequence()
+            .with_parsing_for_discontinuity_sequence()
+            .with_parsing_for_endlist()
+            .with_parsing_for_inf()
+            .with_parsing_for_byterange()
+            .with_parsing_for_discontinuity()
+            .with_parsing_for_program_date_time()
+            .with_parsing_for_gap()
+            .build(),
+    );
+    let mut list = SegmentList::default();
+    let mut next = NextSegment::default();
+    let mut media_sequence: u64 = 0;
+    let mut discontinuity_sequence: u64 = 0;
+    let mut start = 0.0;
+    let mut program_date_time = None;
+    let mut init_segment = None;
+    let mut encryption: Vec<Encryption> = Vec::new();
+    let mut previous_sub_range_end: Option<(&str, u64)> = None;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => match tag {
+                hls::Tag::Targetduration(tag) => list.target_duration = Some(tag.target_duration()),
+                hls::Tag::MediaSequence(tag) => {
+                    media_sequence = tag.media_sequence();
+                    list.media_sequence = media_sequence;
+                }
+                hls::Tag::DiscontinuitySequence(tag) => {
+                    discontinuity_sequence = tag.discontinuity_sequence();
+                    list.discontinuity_sequence = discontinuity_sequence;
+                }
+                hls::Tag::Endlist(_) => list.ended = true,
+                hls::Tag::Inf(tag) => next.duration = tag.duration(),
+                hls::Tag::Byterange(tag) => next.byterange = Some(tag.range()),
+                hls::Tag::Discontinuity(_) => {
+                    next.discontinuity = true;
+                    discontinuity_sequence = discontinuity_sequence.saturating_add(1);
+                }
+                hls::Tag::ProgramDateTime(tag) => program_date_time = Some(tag.program_date_time()),
+                hls::Tag::Gap(_) => next.gap = true,
+                _ => (),
+            },
+            Ok(Some(HlsLine::UnknownTag(tag))) => match tag.name() {
+                "-X-MAP" => {
+                    if let Some(list) = attribute_map(&tag) {
+                        let byte_range = list
+                            .get("BYTERANGE")
+                            .and_then(|range| range.quoted())
+                            .and_then(|range| DecimalIntegerRange::try_from(range).ok())
+                            .map(|range| RangeRequest::try_from(range.with_implicit_offset(0)))
+                            .transpose();
+                        // A byte range that cannot be represented (because the end would overflow
+                        // `u64`) makes the tag invalid, and so it is skipped.
+                        let Ok(byte_range) = byte_range else {
+                            continue;
+                        };
+                        init_segment =
+                            list.get("URI")
+                                .and_then(|uri| uri.quoted())
+                                .map(|uri| InitSegment {
+                                    uri,
+                                    byte_range: byte_range.map(|request| request.range()),
+                                });
+                    }
+                }
+                "-X-KEY" => {
+                    if let Some(list) = attribute_map(&tag) {
+                        handle_key(&list, &mut encryption);
+                    }
+                }
+                _ => (),
+            },
+            Ok(Some(input_uri!(uri))) => {
+                let next = std::mem::take(&mut next);
+                let byte_range = next
+                    .byterange
+                    .map(|range| resolve(range, previous_sub_range_end, uri));
+                previous_sub_range_end = byte_range.flatten().map(|request| (uri, request.end));
+                // A segment with a byte range that cannot be represented (because the end would
+                // overflow `u64`) is skipped, though it still counts towards the media sequence
+                // and timing of the segments that follow.
+                if byte_range != Some(None) {
+                    list.segments.push(ExportedSegment {
+                        uri,
+                        media_sequence,
+                        discontinuity_sequence,
+                        discontinuity: next.discontinuity,
+                        start,
+                        duration: next.duration,
+                        program_date_time,
+                        byte_range: byte_range.flatten().map(|request| request.range()),
+                        init_segment: init_segment.clone(),
+                        encryption: encryption.clone(),
+                        gap: next.gap,
+                    });
+                }
+                media_sequence = media_sequence.saturating_add(1);
+                start += next.duration;
+                program_date_time = program_date_time.map(|date| date.add_seconds(next.duration));
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    list
+}
+
+/// A neutral model of a Media Playlist, as a list of segments.
+///
+/// See [`segment_list`] for more information.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SegmentList<'a> {
+    /// The value of `EXT-X-TARGETDURATION` (if present).
+    pub target_duration: Option<u64>,
+    /// The media sequence number of the first segment.
+    pub media_sequence: u64,
+    /// The discontinuity sequence number of the first segment.
+    pub discontinuity_sequence: u64,
+    /// Whether the playlist is complete (has `EXT-X-ENDLIST`).
+    pub ended: bool,
+    /// The segments in playback order.
+    pub segments: Vec<ExportedSegment<'a>>,
+}
+
+impl SegmentList<'_> {
+    /// The total duration of all segments in seconds.
+    pub fn duration(&self) -> f64 {
+        self.segments.iter().map(|segment| segment.duration).sum()
+    }
+
+    /// The offset (in seconds, relative to the start of the playlist) of the date, as mapped to the
+    /// playlist using the program date time of the segments.
+    ///
+    /// The mapping is based on the last segment with a program date time at or before the date (or
+    /// the first segment with one, when the date precedes it, in which case the offset may be
+    /// negative). `None` is returned when no segment has a program date time.
+    pub fn offset_of(&self, date: DateTime) -> Option<f64> {
+        let timestamp = date.timestamp();
+        let mut dated = self.segments.iter().filter_map(|segment| {
+            segment
+                .program_date_time
+                .map(|pdt| (segment.start, pdt.timestamp()))
+        });
+        let first = dated.next()?;
+        let (start, pdt) = std::iter::once(first)
+            .chain(dated)
+            .take_while(|(_, pdt)| *pdt <= timestamp)
+            .last()
+            .unwrap_or(first);
+        Some(start + timestamp - pdt)
+    }
+}
+
+/// A Media Segment with all of the state that applies to it resolved.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExportedSegment<'a> {
+    /// The URI of the segment (as written in the playlist, so may be relative).
+    pub uri: &'a str,
+    /// The media sequence number of the segment (saturating at `u64::MAX`).
+    pub media_sequence: u64,
+    /// The discontinuity sequence number of the segment (saturating at `u64::MAX`).
+    pub discontinuity_sequence: u64,
+    /// Whether the segment is preceded by `EXT-X-DISCONTINUITY`.
+    pub discontinuity: bool,
+    /// The start time of the segment in seconds, relative to the start of the playlist.
+    pub start: f64,
+    /// The duration of the segment in seconds (from `EXTINF`).
+    pub duration: f64,
+    /// The program date time of the start of the segment (either as stated by
+    /// `EXT-X-PROGRAM-DATE-TIME` or as derived from a previous one).
+    pub program_date_time: Option<DateTime>,
+    /// The resolved byte range of the segment (`start..end` where `end` is exclusive). When `None`
+    /// the segment is the whole resource.
+    pub byte_range: Option<Range<u64>>,
+    /// The initialization section that applies to the segment (from `EXT-X-MAP`).
+    pub init_segment: Option<InitSegment<'a>>,
+    /// The keys that apply to the segment (from `EXT-X-KEY`). There may be more than one when keys
+    /// with different `KEYFORMAT` values are provided. Empty when the segment is not encrypted.
+    pub encryption: Vec<Encryption<'a>>,
+    /// Whether the segment is marked with `EXT-X-GAP`.
+    pub gap: bool,
+}
+
+/// An initialization section for a segment.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InitSegment<'a> {
+    /// The URI of the initialization section.
+    pub uri: &'a str,
+    /// The resolved byte range of the initialization section (`start..end` where `end` is
+    /// exclusive). When `None` the initialization section is the whole resource.
+    pub byte_range: Option<Range<u64>>,
+}
+
+/// Encryption information for a segment.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Encryption<'a> {
+    /// The encryption method (e.g. `AES-128`, `SAMPLE-AES`, `SAMPLE-AES-CTR`).
+    pub method: &'a str,
+    /// The URI of the key (if provided).
+    pub uri: Option<&'a str>,
+    /// The initialization vector (if provided).
+    pub iv: Option<&'a str>,
+    /// The key format (defaults to `identity`).
+    pub key_format: &'a str,
+}
+
+#[derive(Default)]
+struct NextSegment {
+    duration: f64,
+    byterange: Option<DecimalIntegerRange>,
+    discontinuity: bool,
+    gap: bool,
+}
+
+fn attribute_map<'a>(tag: &UnknownTag<'a>) -> Option<AttributeMap<'a>> {
+    tag.value()?.try_as_attribute_map().ok()
+}
+
+fn handle_key<'a>(list: &AttributeMap<'a>, encryption: &mut Vec<Encryption<'a>>) {
+    let Some(method) = list.get("METHOD").and_then(|m| m.unquoted()) else {
+        return;
+    };
+    let Ok(method) = method.try_as_utf_8() else {
+        return;
+    };
+    if method == "NONE" {
+        encryption.clear();
+        return;
+    }
+    let key_format = list
+        .get("KEYFORMAT")
+        .and_then(|v| v.quoted())
+        .unwrap_or("identity");
+    encryption.retain(|key| key.key_format != key_format);
+    encryption.push(Encryption {
+        method,
+        uri: list.get("URI").and_then(|v| v.quoted()),
+        iv: list
+            .get("IV")
+            .and_then(|v| v.unquoted())
+            .and_then(|v| v.try_as_utf_8().ok()),
+        key_format,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_time;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn keys_should_apply_until_replaced_or_removed() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://a\",KEYFORMAT=\"com.apple.streamingkeydelivery\"\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"data:a\",KEYFORMAT=\"urn:uuid:edef8ba9\"\n",
+            "#EXTINF:4\n",
+            "a.ts\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\",IV=0x1234\n",
+            "#EXT-X-GAP\n",
+            "#EXTINF:4\n",
+            "b.ts\n",
+            "#EXT-X-KEY:METHOD=NONE\n",
+            "#EXTINF:4\n",
+            "c.ts\n",
+        );
+        let list = segment_list(PLAYLIST);
+        assert_eq!(3, list.segments.len());
+        assert_eq!(2, list.segments[0].encryption.len());
+        assert_eq!(
+            vec![
+                list.segments[0].encryption[0].clone(),
+                list.segments[0].encryption[1].clone(),
+                Encryption {
+                    method: "AES-128",
+                    uri: Some("key"),
+                    iv: Some("0x1234"),
+                    key_format: "identity",
+                },
+            ],
+            list.segments[1].encryption
+        );
+        assert!(list.segments[1].gap);
+        assert_eq!(Vec::<Encryption>::new(), list.segments[2].encryption);
+        assert_eq!(
+            Some(date_time!(2025-01-01 T 00:00:08.000)),
+            list.segments[2].program_date_time
+        );
+        assert_eq!(8.0, list.segments[2].start);
+        assert!(!list.ended);
+    }
+
+    #[test]
+    fn byte_ranges_that_overflow_should_be_skipped() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"700@0\"\n",
+            "#EXT-X-MAP:URI=\"init.mp4\",BYTERANGE=\"100@18446744073709551600\"\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:100@18446744073709551600\n",
+            "main.mp4\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:100\n",
+            "main.mp4\n",
+        );
+        let list = segment_list(PLAYLIST);
+        assert_eq!(1, list.segments.len());
+        let segment = &list.segments[0];
+        assert_eq!(1, segment.media_sequence);
+        assert_eq!(4.0, segment.start);
+        assert_eq!(Some(0..100), segment.byte_range);
+        assert_eq!(
+            Some(InitSegment {
+                uri: "init.mp4",
+                byte_range: Some(0..700)
+            }),
+            segment.init_segment
+        );
+    }
+
+    #[test]
+    fn sequence_numbers_should_saturate_at_the_largest_value() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA-SEQUENCE:18446744073709551615\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:18446744073709551615\n",
+            "#EXTINF:4\n",
+            "a.mp4\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:4\n",
+            "b.mp4\n",
+            "#EXTINF:4\n",
+            "c.mp4\n",
+        );
+        let list = segment_list(PLAYLIST);
+        assert_eq!(
+            vec![(u64::MAX, u64::MAX); 3],
+            list.segments
+                .iter()
+                .map(|segment| (segment.media_sequence, segment.discontinuity_sequence))
+                .collect::<Vec<_>>()
+        );
+        assert!(list.segments[1].discontinuity);
+    }
+}
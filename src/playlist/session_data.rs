@@ -0,0 +1,234 @@
+use crate::{
+    error::SessionDataError,
+    tag::hls::{EnumeratedString, Format, SessionData},
+};
+
+/// Fetches the data referenced by the `URI` of an `EXT-X-SESSION-DATA` tag (see
+/// [`resolve_session_data`]).
+///
+/// The library does not perform any I/O itself, and so it is up to the implementation to fetch the
+/// data (including resolving the URI against the URI of the Multivariant Playlist, where the URI is
+/// relative). The trait is implemented for any `FnMut(&str) -> Result<Vec<u8>, E>`, so a closure
+/// can be provided directly.
+pub trait SessionDataResolver {
+    /// The error experienced when fetching fails.
+    type Error;
+
+    /// Fetches the data referenced by the URI.
+    fn fetch(&mut self, uri: &str) -> Result<Vec<u8>, Self::Error>;
+}
+
+impl<F, E> SessionDataResolver for F
+where
+    F: FnMut(&str) -> Result<Vec<u8>, E>,
+{
+    type Error = E;
+
+    fn fetch(&mut self, uri: &str) -> Result<Vec<u8>, Self::Error> {
+        self(uri)
+    }
+}
+
+/// An `EXT-X-SESSION-DATA` tag with its data resolved (see [`resolve_session_data`]).
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResolvedSessionData<'a> {
+    /// The `DATA-ID` of the tag.
+    pub data_id: &'a str,
+    /// The `LANGUAGE` of the tag.
+    pub language: Option<&'a str>,
+    /// The data, whether provided by the `VALUE` or fetched from the `URI`.
+    pub content: SessionDataContent<'a>,
+}
+
+/// The data of a [`ResolvedSessionData`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum SessionDataContent<'a> {
+    /// The `VALUE` provided in the tag.
+    Value(&'a str),
+    /// The JSON text fetched from the `URI` (where `FORMAT` is `JSON`, which is the default). The
+    /// text is checked to be UTF-8 but is not otherwise parsed, and so can be deserialized with a
+    /// JSON library of choice.
+    Json(String),
+    /// The bytes fetched from the `URI` (where `FORMAT` is `RAW`, or is not recognized).
+    Raw(Vec<u8>),
+}
+
+impl SessionDataContent<'_> {
+    /// The data as text, when it is a `VALUE` or JSON.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Value(value) => Some(value),
+            Self::Json(json) => Some(json),
+            Self::Raw(_) => None,
+        }
+    }
+
+    /// The data as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Value(value) => value.as_bytes(),
+            Self::Json(json) => json.as_bytes(),
+            Self::Raw(raw) => raw,
+        }
+    }
+}
+
+/// Resolves the data of an `EXT-X-SESSION-DATA` tag, such that the `VALUE` and `URI` cases can be
+/// handled in the same way.
+///
+/// The HLS specification requires that exactly one of `VALUE` or `URI` is present, and an error is
+/// returned otherwise. The resolver is only used when the tag has a `URI`.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{SessionDataContent, resolve_session_data};
+/// # use quick_m3u8::tag::hls::SessionData;
+/// let fetch = |uri: &str| match uri {
+///     "title.json" => Ok(br#"{"title":"Big Buck Bunny"}"#.to_vec()),
+///     _ => Err(std::io::Error::from(std::io::ErrorKind::NotFound)),
+/// };
+/// let tag = SessionData::builder()
+///     .with_data_id("com.example.title")
+///     .with_uri("title.json")
+///     .finish();
+/// let resolved = resolve_session_data(&tag, fetch)?;
+/// assert_eq!(
+///     SessionDataContent::Json(r#"{"title":"Big Buck Bunny"}"#.to_string()),
+///     resolved.content
+/// );
+///
+/// let tag = SessionData::builder()
+///     .with_data_id("com.example.title")
+///     .with_value("Big Buck Bunny")
+///     .with_language("en")
+///     .finish();
+/// let resolved = resolve_session_data(&tag, fetch)?;
+/// assert_eq!(Some("Big Buck Bunny"), resolved.content.as_str());
+/// assert_eq!(Some("en"), resolved.language);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn resolve_session_data<'a, R>(
+    tag: &'a SessionData,
+    mut resolver: R,
+) -> Result<ResolvedSessionData<'a>, SessionDataError<R::Error>>
+where
+    R: SessionDataResolver,
+{
+    let content = match (tag.value(), tag.uri()) {
+        (Some(value), None) => SessionDataContent::Value(value),
+        (None, Some(uri)) => {
+            let data = resolver.fetch(uri).map_err(SessionDataError::Fetch)?;
+            match tag.format() {
+                EnumeratedString::Known(Format::Json) => SessionDataContent::Json(
+                    String::from_utf8(data)
+                        .map_err(|e| SessionDataError::InvalidJsonEncoding(e.utf8_error()))?,
+                ),
+                _ => SessionDataContent::Raw(data),
+            }
+        }
+        (None, None) => return Err(SessionDataError::MissingValueAndUri),
+        (Some(_), Some(_)) => return Err(SessionDataError::ValueAndUri),
+    };
+    Ok(ResolvedSessionData {
+        data_id: tag.data_id(),
+        language: tag.language(),
+        content,
+    })
+}
+
+/// Selects the `EXT-X-SESSION-DATA` tag with the `DATA-ID` that best matches the preferred
+/// languages (in order of preference, see [`SessionData::matches_language`]).
+///
+/// A playlist may contain the same `DATA-ID` once per `LANGUAGE`. When none of the tags match any of
+/// the preferred languages, a tag without a `LANGUAGE` is selected, falling back to the first tag
+/// with the `DATA-ID`.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::select_session_data;
+/// # use quick_m3u8::tag::hls::SessionData;
+/// let tags = [("en", "Hello"), ("fr", "Bonjour")].map(|(language, value)| {
+///     SessionData::builder()
+///         .with_data_id("com.example.greeting")
+///         .with_value(value)
+///         .with_language(language)
+///         .finish()
+/// });
+/// let selected = select_session_data(&tags, "com.example.greeting", &["fr-CA", "fr"]);
+/// assert_eq!(Some("Bonjour"), selected.and_then(|tag| tag.value()));
+/// let selected = select_session_data(&tags, "com.example.greeting", &["de"]);
+/// assert_eq!(Some("Hello"), selected.and_then(|tag| tag.value()));
+/// ```
+pub fn select_session_data<'b, 'a>(
+    tags: impl IntoIterator<Item = &'b SessionData<'a>>,
+    data_id: &str,
+    preferred_languages: &[&str],
+) -> Option<&'b SessionData<'a>>
+where
+    'a: 'b,
+{
+    let candidates = tags
+        .into_iter()
+        .filter(|tag| tag.data_id() == data_id)
+        .collect::<Vec<_>>();
+    preferred_languages
+        .iter()
+        .find_map(|range| candidates.iter().find(|tag| tag.matches_language(range)))
+        .or_else(|| candidates.iter().find(|tag| tag.language().is_none()))
+        .or_else(|| candidates.first())
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn no_fetch(_: &str) -> Result<Vec<u8>, ()> {
+        panic!("should not fetch");
+    }
+
+    #[test]
+    fn value_and_uri_should_be_exclusive() {
+        let mut tag = SessionData::builder()
+            .with_data_id("a")
+            .with_value("v")
+            .finish();
+        tag.set_uri("u");
+        assert_eq!(
+            Err(SessionDataError::ValueAndUri),
+            resolve_session_data(&tag, no_fetch)
+        );
+        tag.unset_value();
+        tag.unset_uri();
+        assert_eq!(
+            Err(SessionDataError::MissingValueAndUri),
+            resolve_session_data(&tag, no_fetch)
+        );
+    }
+
+    #[test]
+    fn fetched_data_should_follow_format() {
+        let tag = SessionData::builder()
+            .with_data_id("a")
+            .with_uri("u")
+            .with_format("RAW")
+            .finish();
+        let resolved = resolve_session_data(&tag, |_: &str| Ok::<_, ()>(vec![0xFF])).unwrap();
+        assert_eq!(SessionDataContent::Raw(vec![0xFF]), resolved.content);
+        assert_eq!(None, resolved.content.as_str());
+
+        let tag = SessionData::builder()
+            .with_data_id("a")
+            .with_uri("u")
+            .finish();
+        assert!(matches!(
+            resolve_session_data(&tag, |_: &str| Ok::<_, ()>(vec![0xFF])),
+            Err(SessionDataError::InvalidJsonEncoding(_))
+        ));
+        assert_eq!(
+            Err(SessionDataError::Fetch("offline")),
+            resolve_session_data(&tag, |_: &str| Err("offline"))
+        );
+    }
+}
@@ -0,0 +1,662 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    date::DateTime,
+    tag::{
+        KnownTag,
+        hls::{self, EnumeratedString, Method, TagName, TagType},
+    },
+};
+use std::{borrow::Cow, collections::VecDeque};
+
+/// A way of reducing the size of a playlist, applied by [`fit_to_size`] in the priority order of the
+/// [`SizeBudgetPolicy`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SizeReduction {
+    /// Remove all comment lines.
+    Comments,
+    /// Remove all tags that are not known HLS tags.
+    UnknownTags,
+    /// Remove all of the HLS tags with the name.
+    ///
+    /// This is intended for tags that are optional (e.g. `EXT-X-BITRATE` or `EXT-X-DATERANGE`);
+    /// removing tags that are required to describe a segment (e.g. `EXTINF`) results in an invalid
+    /// playlist.
+    Tag(TagName),
+    /// Remove the oldest Media Segments (shrinking the window of a live playlist), one at a time,
+    /// down to [`SizeBudgetPolicy::min_segments`].
+    ///
+    /// `EXT-X-MEDIA-SEQUENCE` and `EXT-X-DISCONTINUITY-SEQUENCE` are updated, and the
+    /// `EXT-X-KEY`, `EXT-X-MAP`, `EXT-X-BITRATE`, `EXT-X-PROGRAM-DATE-TIME`, and `EXT-X-DATERANGE`
+    /// tags of a removed segment are carried over to the next segment where they still apply. No
+    /// more segments are removed once either sequence number would be greater than `u64::MAX`.
+    OldestSegments,
+}
+
+/// The priority order in which [`fit_to_size`] reduces a playlist.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SizeBudgetPolicy {
+    reductions: Vec<SizeReduction>,
+    min_segments: usize,
+}
+
+impl Default for SizeBudgetPolicy {
+    /// Removes comments, then unknown tags, then `EXT-X-BITRATE` tags, before finally shrinking the
+    /// window down to 3 segments.
+    fn default() -> Self {
+        Self::new([
+            SizeReduction::Comments,
+            SizeReduction::UnknownTags,
+            SizeReduction::Tag(TagName::Bitrate),
+            SizeReduction::OldestSegments,
+        ])
+    }
+}
+
+impl SizeBudgetPolicy {
+    /// Creates a policy that applies the reductions in the order provided, keeping at least 3
+    /// segments.
+    pub fn new(reductions: impl IntoIterator<Item = SizeReduction>) -> Self {
+        Self {
+            reductions: reductions.into_iter().collect(),
+            min_segments: 3,
+        }
+    }
+
+    /// Sets the minimum number of segments that [`SizeReduction::OldestSegments`] keeps.
+    pub fn with_min_segments(mut self, min_segments: usize) -> Self {
+        self.min_segments = min_segments;
+        self
+    }
+
+    /// The reductions, in the order that they are applied.
+    pub fn reductions(&self) -> &[SizeReduction] {
+        &self.reductions
+    }
+
+    /// The minimum number of segments that [`SizeReduction::OldestSegments`] keeps.
+    pub fn min_segments(&self) -> usize {
+        self.min_segments
+    }
+}
+
+/// What was removed by one of the reductions of a [`SizeBudgetPolicy`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct DroppedContent {
+    /// The reduction that was applied.
+    pub reduction: SizeReduction,
+    /// The number of lines removed, or, for [`SizeReduction::OldestSegments`], the number of
+    /// segments removed.
+    pub count: usize,
+    /// The number of bytes by which the playlist was reduced.
+    pub bytes: usize,
+}
+
+/// The result of [`fit_to_size`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct SizeBudgetReport<'a> {
+    /// The lines of the reduced playlist.
+    pub lines: Vec<HlsLine<'a>>,
+    /// The serialized size of the reduced playlist in bytes (when written with `\n` line endings).
+    pub size: usize,
+    /// The size that the playlist was reduced to fit within.
+    pub budget: usize,
+    /// What was removed, in the order that the reductions were applied. Reductions that did not
+    /// remove anything are not included.
+    pub dropped: Vec<DroppedContent>,
+}
+
+impl SizeBudgetReport<'_> {
+    /// Indicates whether the reduced playlist fits within the budget. When it does not, all of the
+    /// reductions of the policy were applied and the playlist is as small as the policy allows.
+    pub fn fits(&self) -> bool {
+        self.size <= self.budget
+    }
+}
+
+/// Reduces a Media Playlist until its serialized size fits within `budget` bytes, for devices that
+/// impose a limit on the size of a manifest.
+///
+/// The reductions of the policy are applied one at a time, in order, and no more reductions are
+/// applied once the playlist fits. The report provides the resulting lines, along with what was
+/// removed by each reduction. Lines that cannot be parsed are skipped.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{Writer, playlist::{DroppedContent, SizeBudgetPolicy, SizeReduction, fit_to_size}};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MEDIA-SEQUENCE:10
+/// ## encoder: example
+/// #EXTINF:4
+/// 10.ts
+/// #EXTINF:4
+/// 11.ts
+/// #EXTINF:4
+/// 12.ts
+/// "#;
+///
+/// let policy = SizeBudgetPolicy::default().with_min_segments(1);
+/// let report = fit_to_size(PLAYLIST, 90, &policy);
+/// assert!(report.fits());
+/// assert_eq!(
+///     vec![
+///         DroppedContent { reduction: SizeReduction::Comments, count: 1, bytes: 19 },
+///         DroppedContent { reduction: SizeReduction::OldestSegments, count: 1, bytes: 16 },
+///     ],
+///     report.dropped
+/// );
+/// let mut writer = Writer::new(Vec::new());
+/// for line in report.lines {
+///     writer.write_line(line)?;
+/// }
+/// assert_eq!(
+///     r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MEDIA-SEQUENCE:11
+/// #EXTINF:4
+/// 11.ts
+/// #EXTINF:4
+/// 12.ts
+/// "#,
+///     String::from_utf8_lossy(&writer.into_inner())
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn fit_to_size<'a>(
+    playlist: &'a str,
+    budget: usize,
+    policy: &SizeBudgetPolicy,
+) -> SizeBudgetReport<'a> {
+    let mut sections = Sections::read(playlist);
+    let mut size = sections.size();
+    let mut dropped = Vec::new();
+    for reduction in policy.reductions() {
+        if size <= budget {
+            break;
+        }
+        let (count, bytes) = match reduction {
+            SizeReduction::OldestSegments => {
+                let mut count = 0;
+                let mut bytes = 0;
+                while size.saturating_sub(bytes) > budget
+                    && sections.segments.len() > policy.min_segments()
+                    && sections.can_remove_oldest_segment()
+                {
+                    bytes += sections.remove_oldest_segment();
+                    count += 1;
+                }
+                (count, bytes)
+            }
+            reduction => sections.remove_lines(|line| is_removed_by(*reduction, line)),
+        };
+        if count > 0 {
+            size = size.saturating_sub(bytes);
+            dropped.push(DroppedContent {
+                reduction: *reduction,
+                count,
+                bytes,
+            });
+        }
+    }
+    SizeBudgetReport {
+        // The size is tracked by difference, and so is re-calculated in case the tags that were
+        // updated when removing segments changed size.
+        size: sections.size(),
+        lines: sections.into_lines(),
+        budget,
+        dropped,
+    }
+}
+
+fn is_removed_by(reduction: SizeReduction, line: &HlsLine) -> bool {
+    match (reduction, line) {
+        (SizeReduction::Comments, HlsLine::Comment(_)) => true,
+        (SizeReduction::UnknownTags, HlsLine::UnknownTag(_)) => true,
+        (SizeReduction::Tag(name), HlsLine::KnownTag(KnownTag::Hls(tag))) => tag.name() == name,
+        _ => false,
+    }
+}
+
+// The serialized size of the line, including the line ending.
+fn line_size(line: &HlsLine) -> usize {
+    line.to_string().len() + 1
+}
+
+fn lines_size(lines: &[HlsLine]) -> usize {
+    lines.iter().map(line_size).sum()
+}
+
+// The lines of a Media Playlist, split such that the oldest segment can be removed.
+struct Sections<'a> {
+    // The lines that precede the first Media Segment tag (or URI).
+    header: Vec<HlsLine<'a>>,
+    // The lines of each segment, where each ends with the URI of the segment.
+    segments: VecDeque<Vec<HlsLine<'a>>>,
+    // The lines that follow the URI of the last segment.
+    trailer: Vec<HlsLine<'a>>,
+}
+
+impl<'a> Sections<'a> {
+    fn read(playlist: &'a str) -> Self {
+        let mut reader = Reader::from_str(playlist, ParsingOptions::default());
+        let mut header = Vec::new();
+        let mut segments = VecDeque::new();
+        let mut current = Vec::new();
+        let mut in_header = true;
+        loop {
+            match reader.read_line() {
+                Ok(Some(line)) => {
+                    in_header = in_header && is_header_line(&line);
+                    if in_header {
+                        header.push(line);
+                    } else if let HlsLine::Uri(_) = line {
+                        current.push(line);
+                        segments.push_back(std::mem::take(&mut current));
+                    } else {
+                        current.push(line);
+                    }
+                }
+                Err(_) => (),
+                Ok(None) => break,
+            }
+        }
+        Self {
+            header,
+            segments,
+            trailer: current,
+        }
+    }
+
+    fn size(&self) -> usize {
+        lines_size(&self.header)
+            + self
+                .segments
+                .iter()
+                .map(|lines| lines_size(lines))
+                .sum::<usize>()
+            + lines_size(&self.trailer)
+    }
+
+    // Returns the number of lines removed and the number of bytes that they took up.
+    fn remove_lines(&mut self, should_remove: impl Fn(&HlsLine) -> bool) -> (usize, usize) {
+        let mut count = 0;
+        let mut bytes = 0;
+        let mut retain = |lines: &mut Vec<HlsLine>| {
+            lines.retain(|line| {
+                if should_remove(line) {
+                    count += 1;
+                    bytes += line_size(line);
+                    false
+                } else {
+                    true
+                }
+            })
+        };
+        retain(&mut self.header);
+        self.segments.iter_mut().for_each(&mut retain);
+        retain(&mut self.trailer);
+        (count, bytes)
+    }
+
+    // Whether the sequence numbers can be updated for the removal of the first segment (they cannot
+    // go beyond `u64::MAX`).
+    fn can_remove_oldest_segment(&self) -> bool {
+        let discontinuities = self.segments.front().map_or(0, |lines| {
+            lines
+                .iter()
+                .filter(|line| {
+                    matches!(
+                        line,
+                        HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Discontinuity(_)))
+                    )
+                })
+                .count() as u64
+        });
+        self.header.iter().all(|line| match line {
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::MediaSequence(tag))) => {
+                tag.media_sequence() < u64::MAX
+            }
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::DiscontinuitySequence(tag))) => tag
+                .discontinuity_sequence()
+                .checked_add(discontinuities)
+                .is_some(),
+            _ => true,
+        })
+    }
+
+    // Removes the first segment, carrying over any state that still applies to the next segment,
+    // and returns the number of bytes by which the playlist was reduced.
+    fn remove_oldest_segment(&mut self) -> usize {
+        let Some(removed) = self.segments.pop_front() else {
+            return 0;
+        };
+        let header_size = lines_size(&self.header);
+        let next_size = self.segments.front().map_or(0, |lines| lines_size(lines));
+        let removed_size = lines_size(&removed);
+
+        let mut carried = Carried::default();
+        for line in removed {
+            carried.track(line);
+        }
+        self.increment_sequences(carried.discontinuities);
+        if let Some(next) = self.segments.front_mut() {
+            carried.apply_to(next);
+        }
+
+        let new_next_size = self.segments.front().map_or(0, |lines| lines_size(lines));
+        (header_size + next_size + removed_size)
+            .saturating_sub(lines_size(&self.header) + new_next_size)
+    }
+
+    fn increment_sequences(&mut self, discontinuities: u64) {
+        let mut has_media_sequence = false;
+        let mut has_discontinuity_sequence = false;
+        for line in &mut self.header {
+            match line {
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::MediaSequence(tag))) => {
+                    tag.set_media_sequence(tag.media_sequence() + 1);
+                    has_media_sequence = true;
+                }
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::DiscontinuitySequence(tag))) => {
+                    if discontinuities > 0 {
+                        tag.set_discontinuity_sequence(
+                            tag.discontinuity_sequence() + discontinuities,
+                        );
+                    }
+                    has_discontinuity_sequence = true;
+                }
+                _ => (),
+            }
+        }
+        if !has_media_sequence {
+            self.header.push(HlsLine::from(hls::MediaSequence::new(1)));
+        }
+        if !has_discontinuity_sequence && discontinuities > 0 {
+            self.header
+                .push(HlsLine::from(hls::DiscontinuitySequence::new(
+                    discontinuities,
+                )));
+        }
+    }
+
+    fn into_lines(self) -> Vec<HlsLine<'a>> {
+        let mut lines = self.header;
+        lines.extend(self.segments.into_iter().flatten());
+        lines.extend(self.trailer);
+        lines
+    }
+}
+
+// Whether the line can be part of the header (i.e. it is not a line that describes a segment).
+fn is_header_line(line: &HlsLine) -> bool {
+    match line {
+        HlsLine::KnownTag(KnownTag::Hls(tag)) => matches!(
+            tag.name().tag_type(),
+            TagType::Basic | TagType::MediaOrMultivariantPlaylist | TagType::MediaPlaylist
+        ),
+        HlsLine::Uri(_) => false,
+        _ => true,
+    }
+}
+
+// The state of a removed segment that still applies to the segment that follows it.
+#[derive(Default)]
+struct Carried<'a> {
+    keys: Vec<hls::Key<'a>>,
+    map: Option<hls::Map<'a>>,
+    bitrate: Option<hls::Bitrate<'a>>,
+    dateranges: Vec<hls::Daterange<'a>>,
+    program_date_time: Option<DateTime>,
+    duration: f64,
+    byterange: Option<hls::Byterange<'a>>,
+    byterange_end: Option<(Cow<'a, str>, u64)>,
+    discontinuities: u64,
+}
+
+impl<'a> Carried<'a> {
+    fn track(&mut self, line: HlsLine<'a>) {
+        match line {
+            HlsLine::KnownTag(KnownTag::Hls(tag)) => match tag {
+                hls::Tag::Key(key) => {
+                    if key.method() == EnumeratedString::Known(Method::None) {
+                        self.keys.clear();
+                    } else {
+                        self.keys
+                            .retain(|existing| existing.keyformat() != key.keyformat());
+                    }
+                    self.keys.push(key);
+                }
+                hls::Tag::Map(tag) => self.map = Some(tag),
+                hls::Tag::Bitrate(tag) => self.bitrate = Some(tag),
+                hls::Tag::Daterange(tag) => self.dateranges.push(tag),
+                hls::Tag::ProgramDateTime(tag) => {
+                    self.program_date_time = Some(tag.program_date_time())
+                }
+                hls::Tag::Inf(tag) => self.duration = tag.duration(),
+                hls::Tag::Discontinuity(_) => self.discontinuities += 1,
+                hls::Tag::Byterange(tag) => self.byterange = Some(tag),
+                _ => (),
+            },
+            HlsLine::Uri(uri) => {
+                // The first segment either has an explicit offset or starts at 0, given that any
+                // implicit offset of a later segment is made explicit when its predecessor is
+                // removed. An end that would overflow `u64` is not carried.
+                self.byterange_end = self
+                    .byterange
+                    .take()
+                    .and_then(|tag| Some((uri, tag.range().with_implicit_offset(0).end()?)));
+            }
+            _ => (),
+        }
+    }
+
+    fn apply_to(self, next: &mut Vec<HlsLine<'a>>) {
+        let mut has_map = false;
+        let mut has_bitrate = false;
+        let mut has_program_date_time = false;
+        let next_uri = match next.last() {
+            Some(HlsLine::Uri(uri)) => Some(uri.clone()),
+            _ => None,
+        };
+        for line in next.iter_mut() {
+            match line {
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Map(_))) => has_map = true,
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Bitrate(_))) => has_bitrate = true,
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::ProgramDateTime(_))) => {
+                    has_program_date_time = true
+                }
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Byterange(tag)))
+                    if tag.offset().is_none() =>
+                {
+                    if let Some((uri, end)) = &self.byterange_end
+                        && Some(uri) == next_uri.as_ref()
+                    {
+                        tag.set_offset(*end);
+                    }
+                }
+                _ => (),
+            }
+        }
+        let mut carried = self.keys.into_iter().map(HlsLine::from).collect::<Vec<_>>();
+        carried.extend(self.map.filter(|_| !has_map).map(HlsLine::from));
+        carried.extend(self.bitrate.filter(|_| !has_bitrate).map(HlsLine::from));
+        carried.extend(self.dateranges.into_iter().map(HlsLine::from));
+        if let Some(program_date_time) = self.program_date_time
+            && !has_program_date_time
+        {
+            let program_date_time = program_date_time.add_seconds(self.duration);
+            carried.push(HlsLine::from(hls::ProgramDateTime::new(program_date_time)));
+        }
+        next.splice(0..0, carried);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Writer;
+    use pretty_assertions::assert_eq;
+
+    fn write(lines: Vec<HlsLine>) -> String {
+        let mut writer = Writer::new(Vec::new());
+        for line in lines {
+            writer.write_line(line).unwrap();
+        }
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn removing_segments_should_carry_over_state_to_next_segment() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:1000@0\n",
+            "main.mp4\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:1000\n",
+            "main.mp4\n",
+            "#EXTINF:4\n",
+            "other.mp4\n",
+            "#EXT-X-ENDLIST\n",
+        );
+        let policy = SizeBudgetPolicy::new([SizeReduction::OldestSegments]).with_min_segments(1);
+        let report = fit_to_size(PLAYLIST, 0, &policy);
+        assert!(!report.fits());
+        assert_eq!(
+            vec![SizeReduction::OldestSegments],
+            report
+                .dropped
+                .iter()
+                .map(|d| d.reduction)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, report.dropped[0].count);
+        let output = write(report.lines);
+        assert_eq!(report.size, output.len());
+        assert_eq!(
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-TARGETDURATION:4\n",
+                "#EXT-X-MEDIA-SEQUENCE:2\n",
+                "#EXT-X-DISCONTINUITY-SEQUENCE:1\n",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"key\"\n",
+                "#EXT-X-MAP:URI=\"init.mp4\"\n",
+                "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:08.000Z\n",
+                "#EXTINF:4\n",
+                "other.mp4\n",
+                "#EXT-X-ENDLIST\n",
+            ),
+            output
+        );
+    }
+
+    #[test]
+    fn implicit_byterange_offset_should_be_made_explicit() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:1000@500\n",
+            "main.mp4\n",
+            "#EXTINF:4\n",
+            "#EXT-X-BYTERANGE:1000\n",
+            "main.mp4\n",
+        );
+        let policy = SizeBudgetPolicy::new([SizeReduction::OldestSegments]).with_min_segments(1);
+        let report = fit_to_size(PLAYLIST, 0, &policy);
+        assert_eq!(
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-MEDIA-SEQUENCE:1\n",
+                "#EXTINF:4\n",
+                "#EXT-X-BYTERANGE:1000@1500\n",
+                "main.mp4\n",
+            ),
+            write(report.lines)
+        );
+    }
+
+    #[test]
+    fn reductions_should_stop_once_playlist_fits() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-X-VENDOR:1\n",
+            "# comment\n",
+            "#EXT-X-BITRATE:1000\n",
+            "#EXTINF:4\n",
+            "0.ts\n",
+        );
+        let report = fit_to_size(PLAYLIST, PLAYLIST.len() - 10, &SizeBudgetPolicy::default());
+        assert!(report.fits());
+        assert_eq!(
+            vec![DroppedContent {
+                reduction: SizeReduction::Comments,
+                count: 1,
+                bytes: 10
+            }],
+            report.dropped
+        );
+        assert_eq!(PLAYLIST.len() - 10, report.size);
+    }
+
+    #[test]
+    fn segments_should_not_be_removed_beyond_largest_sequence_numbers() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA-SEQUENCE:18446744073709551614\n",
+            "#EXTINF:4\n",
+            "0.ts\n",
+            "#EXTINF:4\n",
+            "1.ts\n",
+            "#EXTINF:4\n",
+            "2.ts\n",
+            "#EXTINF:4\n",
+            "3.ts\n",
+            "#EXTINF:4\n",
+            "4.ts\n",
+        );
+        let report = fit_to_size(PLAYLIST, 0, &SizeBudgetPolicy::default());
+        assert_eq!(
+            vec![1],
+            report
+                .dropped
+                .iter()
+                .map(|dropped| dropped.count)
+                .collect::<Vec<_>>()
+        );
+        assert!(
+            write(report.lines)
+                .starts_with("#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:18446744073709551615\n")
+        );
+
+        const DISCONTINUITY: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:18446744073709551615\n",
+            "#EXTINF:4\n",
+            "0.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:4\n",
+            "1.ts\n",
+            "#EXTINF:4\n",
+            "2.ts\n",
+            "#EXTINF:4\n",
+            "3.ts\n",
+            "#EXTINF:4\n",
+            "4.ts\n",
+        );
+        let report = fit_to_size(DISCONTINUITY, 0, &SizeBudgetPolicy::default());
+        assert_eq!(
+            vec![1],
+            report
+                .dropped
+                .iter()
+                .map(|dropped| dropped.count)
+                .collect::<Vec<_>>()
+        );
+    }
+}
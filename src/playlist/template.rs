@@ -0,0 +1,322 @@
+use crate::{HlsLine, Writer, error::TemplateError};
+use memchr::memmem;
+use std::fmt::{Display, Write};
+
+/// A playlist skeleton that can be instantiated repeatedly with different values.
+///
+/// The template is made up of header lines (written once), segment lines (written once per segment)
+/// and footer lines (written once, after the segments). Values are substituted wherever a
+/// placeholder (`{{name}}`) is found in the written lines. The placeholder syntax is deliberately
+/// distinct from HLS variable references (`{$name}`, see `EXT-X-DEFINE`), which are written to the
+/// output unchanged, so that a template can produce playlists that use variable substitution. Since
+/// most tags do not allow a placeholder in place of a typed value, template lines are typically read
+/// from a string using a [`crate::Reader`] (in which case a tag like `#EXTINF:{{duration}},` is read
+/// as [`HlsLine::UnknownTag`], and is written back unchanged).
+///
+/// The lines are serialized once, when the template is constructed, and so instantiating the
+/// template only involves copying bytes and looking up values. Together with [`TemplateValues`]
+/// (which reuses its value buffers when a value is updated) and writing into a caller owned buffer,
+/// this means that, once warmed up, instantiating the template does not allocate. This is useful
+/// for high-rate live origins generating many renditions from one template.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions};
+/// # use quick_m3u8::playlist::{PlaylistTemplate, TemplateValues};
+/// fn lines(input: &str) -> Vec<HlsLine<'_>> {
+///     let mut reader = Reader::from_str(input, ParsingOptions::default());
+///     std::iter::from_fn(|| reader.read_line().ok().flatten()).collect()
+/// }
+/// let template = PlaylistTemplate::new(
+///     lines("#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXT-X-MEDIA-SEQUENCE:{{msn}}\n"),
+///     lines("#EXTINF:{{duration}},\n{{rendition}}/segment{{msn}}.mp4\n"),
+///     Vec::new(),
+/// );
+///
+/// let mut values = TemplateValues::new();
+/// values.set("rendition", "1080p");
+/// values.set("msn", 100);
+/// let mut segments = vec![TemplateValues::new(), TemplateValues::new()];
+/// for (i, segment) in segments.iter_mut().enumerate() {
+///     segment.set("msn", 100 + i);
+///     segment.set("duration", "4.0");
+/// }
+///
+/// let mut output = Vec::new();
+/// template.instantiate(&values, &segments, &mut output).unwrap();
+/// assert_eq!(
+///     concat!(
+///         "#EXTM3U\n",
+///         "#EXT-X-TARGETDURATION:4\n",
+///         "#EXT-X-MEDIA-SEQUENCE:100\n",
+///         "#EXTINF:4.0,\n",
+///         "1080p/segment100.mp4\n",
+///         "#EXTINF:4.0,\n",
+///         "1080p/segment101.mp4\n",
+///     ),
+///     String::from_utf8_lossy(&output)
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistTemplate {
+    header: Vec<Chunk>,
+    segment: Vec<Chunk>,
+    footer: Vec<Chunk>,
+}
+
+impl PlaylistTemplate {
+    /// Creates a new template from the header, segment, and footer lines.
+    pub fn new<'a>(
+        header: impl IntoIterator<Item = HlsLine<'a>>,
+        segment: impl IntoIterator<Item = HlsLine<'a>>,
+        footer: impl IntoIterator<Item = HlsLine<'a>>,
+    ) -> Self {
+        Self {
+            header: compile(header),
+            segment: compile(segment),
+            footer: compile(footer),
+        }
+    }
+
+    /// Writes the template to `output`, once for the header and footer (using `values`), and once
+    /// per item of `segments` for the segment lines.
+    ///
+    /// When writing the segment lines, a placeholder is first looked up in the values for that
+    /// segment, and then in `values`. The output is appended to, so that the same buffer can be
+    /// cleared and reused between instantiations. If a placeholder cannot be resolved then an error is
+    /// returned (and `output` may contain a partially written playlist).
+    pub fn instantiate<'a>(
+        &'a self,
+        values: &TemplateValues,
+        segments: &[TemplateValues],
+        output: &mut Vec<u8>,
+    ) -> Result<(), TemplateError<'a>> {
+        write_chunks(&self.header, &[values], output)?;
+        for segment in segments {
+            write_chunks(&self.segment, &[segment, values], output)?;
+        }
+        write_chunks(&self.footer, &[values], output)
+    }
+}
+
+/// Values used to instantiate a [`PlaylistTemplate`].
+///
+/// Setting a value that has already been set reuses the buffer of the previous value, and so, a set
+/// of values can be kept and updated between instantiations without allocating.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TemplateValues {
+    values: Vec<(String, String)>,
+}
+
+impl TemplateValues {
+    /// Creates an empty set of values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value for the placeholder `name` (as referenced by `{{name}}` in the template).
+    pub fn set(&mut self, name: &str, value: impl Display) {
+        let buffer = match self.values.iter().position(|(n, _)| n == name) {
+            Some(index) => &mut self.values[index].1,
+            None => {
+                self.values.push((name.to_string(), String::new()));
+                &mut self.values.last_mut().expect("value was just pushed").1
+            }
+        };
+        buffer.clear();
+        // Writing to a `String` cannot fail.
+        let _ = write!(buffer, "{value}");
+    }
+
+    /// The value for the placeholder `name`, if it has been set.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Removes all values.
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Chunk {
+    Literal(Box<[u8]>),
+    Variable(Box<str>),
+}
+
+fn compile<'a>(lines: impl IntoIterator<Item = HlsLine<'a>>) -> Vec<Chunk> {
+    let mut writer = Writer::new(Vec::new());
+    for line in lines {
+        // Writing to a `Vec` cannot fail.
+        let _ = writer.write_line(line);
+    }
+    let bytes = writer.into_inner();
+    let mut chunks = Vec::new();
+    let mut literal_start = 0;
+    let mut search_start = 0;
+    while let Some(index) = memmem::find(&bytes[search_start..], b"{{") {
+        let reference_start = search_start + index;
+        let name_start = reference_start + 2;
+        let name_len = bytes[name_start..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric() || **b == b'-' || **b == b'_')
+            .count();
+        let name_end = name_start + name_len;
+        if name_len == 0 || bytes.get(name_end..name_end + 2) != Some(b"}}") {
+            search_start = name_start;
+            continue;
+        }
+        if literal_start < reference_start {
+            chunks.push(Chunk::Literal(bytes[literal_start..reference_start].into()));
+        }
+        let name = std::str::from_utf8(&bytes[name_start..name_end])
+            .expect("variable name should be ASCII");
+        chunks.push(Chunk::Variable(name.into()));
+        literal_start = name_end + 2;
+        search_start = literal_start;
+    }
+    if literal_start < bytes.len() {
+        chunks.push(Chunk::Literal(bytes[literal_start..].into()));
+    }
+    chunks
+}
+
+fn write_chunks<'a>(
+    chunks: &'a [Chunk],
+    values: &[&TemplateValues],
+    output: &mut Vec<u8>,
+) -> Result<(), TemplateError<'a>> {
+    for chunk in chunks {
+        match chunk {
+            Chunk::Literal(bytes) => output.extend_from_slice(bytes),
+            Chunk::Variable(name) => {
+                let value = values
+                    .iter()
+                    .find_map(|values| values.get(name))
+                    .ok_or(TemplateError::UndefinedVariable(name))?;
+                output.extend_from_slice(value.as_bytes());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::hls::{Endlist, M3u, Targetduration};
+    use pretty_assertions::assert_eq;
+    use std::borrow::Cow;
+
+    #[test]
+    fn instantiating_repeatedly_should_reuse_values_and_output() {
+        let template = PlaylistTemplate::new(
+            [HlsLine::from(M3u), HlsLine::from(Targetduration::new(2))],
+            [HlsLine::Uri(Cow::Borrowed("{{name}}-{{msn}}.ts"))],
+            [HlsLine::from(Endlist)],
+        );
+        let mut values = TemplateValues::new();
+        let mut segments = vec![TemplateValues::new()];
+        let mut output = Vec::new();
+        for (name, msn) in [("a", 1), ("b", 2)] {
+            values.set("name", name);
+            segments[0].set("msn", msn);
+            output.clear();
+            template
+                .instantiate(&values, &segments, &mut output)
+                .expect("instantiate should succeed");
+            assert_eq!(
+                format!("#EXTM3U\n#EXT-X-TARGETDURATION:2\n{name}-{msn}.ts\n#EXT-X-ENDLIST\n"),
+                String::from_utf8_lossy(&output)
+            );
+        }
+        assert_eq!(Some("b"), values.get("name"));
+    }
+
+    #[test]
+    fn segment_values_should_take_precedence() {
+        let template = PlaylistTemplate::new(
+            [],
+            [HlsLine::Uri(Cow::Borrowed("{{v}}.ts"))],
+            [HlsLine::Comment(Cow::Borrowed("{{v}}"))],
+        );
+        let mut values = TemplateValues::new();
+        values.set("v", "playlist");
+        let mut segment = TemplateValues::new();
+        segment.set("v", "segment");
+        let mut output = Vec::new();
+        template
+            .instantiate(&values, &[segment, TemplateValues::new()], &mut output)
+            .expect("instantiate should succeed");
+        assert_eq!(
+            "segment.ts\nplaylist.ts\n#playlist\n",
+            String::from_utf8_lossy(&output)
+        );
+    }
+
+    #[test]
+    fn invalid_references_should_be_written_as_is() {
+        let template = PlaylistTemplate::new(
+            [HlsLine::Uri(Cow::Borrowed("{{}}{{a b}}{{ok}}{{ok}{{"))],
+            [],
+            [],
+        );
+        let mut values = TemplateValues::new();
+        values.set("ok", 1);
+        let mut output = Vec::new();
+        template
+            .instantiate(&values, &[], &mut output)
+            .expect("instantiate should succeed");
+        assert_eq!("{{}}{{a b}}1{{ok}{{\n", String::from_utf8_lossy(&output));
+    }
+
+    #[test]
+    fn hls_variable_references_should_be_written_as_is() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-DEFINE:QUERYPARAM=\"token\"\n",
+            "#EXT-X-DEFINE:NAME=\"host\",VALUE=\"{{host}}\"\n",
+        );
+        let mut reader = crate::Reader::from_str(input, crate::config::ParsingOptions::default());
+        let header = std::iter::from_fn(|| reader.read_line().ok().flatten()).collect::<Vec<_>>();
+        let template = PlaylistTemplate::new(
+            header,
+            [
+                HlsLine::Comment(Cow::Borrowed("EXTINF:4,")),
+                HlsLine::Uri(Cow::Borrowed("https://{$host}/{{msn}}.ts?{$token}")),
+            ],
+            [],
+        );
+        let mut values = TemplateValues::new();
+        values.set("host", "example.com");
+        let mut segment = TemplateValues::new();
+        segment.set("msn", 1);
+        let mut output = Vec::new();
+        template
+            .instantiate(&values, &[segment], &mut output)
+            .expect("instantiate should succeed");
+        assert_eq!(
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-DEFINE:QUERYPARAM=\"token\"\n",
+                "#EXT-X-DEFINE:NAME=\"host\",VALUE=\"example.com\"\n",
+                "#EXTINF:4,\n",
+                "https://{$host}/1.ts?{$token}\n",
+            ),
+            String::from_utf8_lossy(&output)
+        );
+    }
+
+    #[test]
+    fn undefined_variable_should_error() {
+        let template = PlaylistTemplate::new([HlsLine::Uri(Cow::Borrowed("{{missing}}"))], [], []);
+        assert_eq!(
+            Err(TemplateError::UndefinedVariable("missing")),
+            template.instantiate(&TemplateValues::new(), &[], &mut Vec::new())
+        );
+    }
+}
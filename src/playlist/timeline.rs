@@ -0,0 +1,370 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    date::DateTime,
+    tag::{
+        KnownTag,
+        hls::{self, Key, Map, Method, TagName},
+    },
+};
+
+/// Walks a Media Playlist and computes the timeline of its segments.
+///
+/// Each [`TimelineSegment`] has its position in the playlist (offset and duration in seconds), its
+/// absolute start and end dates (propagated from `EXT-X-PROGRAM-DATE-TIME` using the `EXTINF`
+/// durations), its media sequence and discontinuity sequence numbers, and the `EXT-X-KEY` and
+/// `EXT-X-MAP` tags that apply to it.
+///
+/// A date is propagated forwards from the last `EXT-X-PROGRAM-DATE-TIME` at or before the segment,
+/// and backwards from the first one after the segment, but never across an `EXT-X-DISCONTINUITY`
+/// (as the timeline is not continuous there). This means that segments are only dated when the
+/// date can be known from the playlist. Lines that cannot be parsed are skipped, and sequence
+/// numbers that would go beyond `u64::MAX` stay at `u64::MAX`.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{date_time, playlist::timeline};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MEDIA-SEQUENCE:100
+/// #EXT-X-MAP:URI="init.mp4"
+/// #EXTINF:4
+/// 100.mp4
+/// #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:04.000Z
+/// #EXT-X-KEY:METHOD=AES-128,URI="key"
+/// #EXTINF:4
+/// 101.mp4
+/// #EXT-X-DISCONTINUITY
+/// #EXTINF:2
+/// ad.mp4
+/// "#;
+///
+/// let timeline = timeline(PLAYLIST);
+/// assert_eq!(10.0, timeline.duration());
+///
+/// let first = &timeline.segments[0];
+/// assert_eq!(100, first.media_sequence);
+/// assert_eq!(Some(date_time!(2025-01-01 T 00:00:00.000)), first.start_date);
+/// assert_eq!(Some("init.mp4"), first.map.as_ref().map(|map| map.uri()));
+/// assert!(first.keys.is_empty());
+///
+/// let second = timeline.segment_at(6.5).unwrap();
+/// assert_eq!("101.mp4", second.uri);
+/// assert_eq!(4.0, second.start);
+/// assert_eq!(Some(date_time!(2025-01-01 T 00:00:08.000)), second.end_date());
+/// assert_eq!(Some("key"), second.keys[0].uri());
+///
+/// let ad = &timeline.segments[2];
+/// assert_eq!(1, ad.discontinuity_sequence);
+/// assert_eq!(None, ad.start_date);
+/// ```
+pub fn timeline(playlist: &str) -> Timeline<'_> {
+    let mut reader = Reader::from_str(
+        playlist,
+        ParsingOptions::builder()
+            .with_tags([
+                TagName::MediaSequence,
+                TagName::DiscontinuitySequence,
+                TagName::Inf,
+                TagName::Discontinuity,
+                TagName::ProgramDateTime,
+                TagName::Key,
+                TagName::Map,
+            ])
+            .build(),
+    );
+    let mut segments = Vec::new();
+    // The number of discontinuities before each segment, which (unlike the discontinuity sequence)
+    // cannot saturate, used to know where dates can be propagated backwards.
+    let mut discontinuities = Vec::new();
+    let mut discontinuity_count = 0_usize;
+    let mut media_sequence: u64 = 0;
+    let mut discontinuity_sequence: u64 = 0;
+    let mut start = 0.0;
+    let mut duration = 0.0;
+    let mut date = None;
+    let mut keys: Vec<Key> = Vec::new();
+    let mut map = None;
+    loop {
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => match tag {
+                hls::Tag::MediaSequence(tag) => media_sequence = tag.media_sequence(),
+                hls::Tag::DiscontinuitySequence(tag) => {
+                    discontinuity_sequence = tag.discontinuity_sequence()
+                }
+                hls::Tag::Inf(tag) => duration = tag.duration(),
+                hls::Tag::Discontinuity(_) => {
+                    discontinuity_sequence = discontinuity_sequence.saturating_add(1);
+                    discontinuity_count += 1;
+                    date = None;
+                }
+                hls::Tag::ProgramDateTime(tag) => date = Some(tag.program_date_time()),
+                hls::Tag::Key(key) => {
+                    if matches!(key.method().known(), Some(Method::None)) {
+                        keys.clear();
+                    } else {
+                        keys.retain(|applied| applied.keyformat() != key.keyformat());
+                        keys.push(key);
+                    }
+                }
+                hls::Tag::Map(tag) => map = Some(tag),
+                _ => (),
+            },
+            Ok(Some(input_uri!(uri))) => {
+                segments.push(TimelineSegment {
+                    uri,
+                    media_sequence,
+                    discontinuity_sequence,
+                    start,
+                    duration,
+                    start_date: date,
+                    keys: keys.clone(),
+                    map: map.clone(),
+                });
+                discontinuities.push(discontinuity_count);
+                media_sequence = media_sequence.saturating_add(1);
+                start += duration;
+                date = date.map(|date| date.add_seconds(duration));
+                duration = 0.0;
+            }
+            Ok(Some(_)) | Err(_) => (),
+            Ok(None) => break,
+        }
+    }
+    // Dating the segments that precede the first `EXT-X-PROGRAM-DATE-TIME` of their discontinuity.
+    let mut next: Option<(usize, DateTime)> = None;
+    for (segment, discontinuities) in segments.iter_mut().zip(discontinuities).rev() {
+        if segment.start_date.is_none() {
+            segment.start_date = next
+                .filter(|(next_discontinuities, _)| *next_discontinuities == discontinuities)
+                .map(|(_, date)| date.add_seconds(-segment.duration));
+        }
+        next = segment.start_date.map(|date| (discontinuities, date));
+    }
+    Timeline { segments }
+}
+
+/// The timeline of a Media Playlist.
+///
+/// See [`timeline`] for more information.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Timeline<'a> {
+    /// The segments in playback order.
+    pub segments: Vec<TimelineSegment<'a>>,
+}
+
+impl<'a> Timeline<'a> {
+    /// The total duration of all segments in seconds.
+    pub fn duration(&self) -> f64 {
+        self.segments.iter().map(|segment| segment.duration).sum()
+    }
+
+    /// The segment that is playing at the offset (in seconds, relative to the start of the
+    /// playlist). `None` is returned when the offset is outside of the playlist.
+    pub fn segment_at(&self, offset: f64) -> Option<&TimelineSegment<'a>> {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.start <= offset);
+        self.segments[..index]
+            .last()
+            .filter(|segment| offset < segment.end())
+    }
+
+    /// The segment that is playing at the date. `None` is returned when no dated segment covers the
+    /// date.
+    pub fn segment_at_date(&self, date: &DateTime) -> Option<&TimelineSegment<'a>> {
+        self.segments
+            .iter()
+            .find(|segment| match (segment.start_date, segment.end_date()) {
+                (Some(start), Some(end)) => start <= *date && *date < end,
+                _ => false,
+            })
+    }
+}
+
+/// A Media Segment positioned on the [`Timeline`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct TimelineSegment<'a> {
+    /// The URI of the segment (as written in the playlist, so may be relative).
+    pub uri: &'a str,
+    /// The media sequence number of the segment (saturating at `u64::MAX`).
+    pub media_sequence: u64,
+    /// The discontinuity sequence number of the segment (saturating at `u64::MAX`).
+    pub discontinuity_sequence: u64,
+    /// The start of the segment in seconds, relative to the start of the playlist (i.e. the sum of
+    /// the durations of the preceding segments).
+    pub start: f64,
+    /// The duration of the segment in seconds (from `EXTINF`).
+    pub duration: f64,
+    /// The date of the start of the segment (see [`timeline`] for how this is propagated).
+    pub start_date: Option<DateTime>,
+    /// The `EXT-X-KEY` tags that apply to the segment. There may be more than one when keys with
+    /// different `KEYFORMAT` values are provided. Empty when the segment is not encrypted.
+    pub keys: Vec<Key<'a>>,
+    /// The `EXT-X-MAP` that applies to the segment.
+    pub map: Option<Map<'a>>,
+}
+
+impl TimelineSegment<'_> {
+    /// The end of the segment in seconds, relative to the start of the playlist.
+    pub fn end(&self) -> f64 {
+        self.start + self.duration
+    }
+
+    /// The date of the end of the segment.
+    pub fn end_date(&self) -> Option<DateTime> {
+        self.start_date.map(|date| date.add_seconds(self.duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_time;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn dates_should_not_propagate_across_discontinuities() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:5\n",
+            "#EXTINF:4\n",
+            "a.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:3.5\n",
+            "b.ts\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:03.500+01:00\n",
+            "#EXTINF:2.5\n",
+            "c.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:4\n",
+            "d.ts\n",
+        );
+        let timeline = timeline(PLAYLIST);
+        assert_eq!(
+            vec![
+                (5, None),
+                (6, Some(date_time!(2025-01-01 T 00:00:00.000 01:00))),
+                (6, Some(date_time!(2025-01-01 T 00:00:03.500 01:00))),
+                (7, None),
+            ],
+            timeline
+                .segments
+                .iter()
+                .map(|segment| (segment.discontinuity_sequence, segment.start_date))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some("c.ts"),
+            timeline
+                .segment_at_date(&date_time!(2024-12-31 T 23:00:05.999))
+                .map(|segment| segment.uri)
+        );
+        assert_eq!(
+            None,
+            timeline.segment_at_date(&date_time!(2024-12-31 T 23:00:06.000))
+        );
+    }
+
+    #[test]
+    fn keys_and_maps_should_apply_until_replaced() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-MAP:URI=\"init_1.mp4\"\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://a\",KEYFORMAT=\"com.apple.streamingkeydelivery\"\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"data:a\",KEYFORMAT=\"urn:uuid:edef8ba9\"\n",
+            "#EXTINF:4\n",
+            "a.mp4\n",
+            "#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://b\",KEYFORMAT=\"com.apple.streamingkeydelivery\"\n",
+            "#EXT-X-MAP:URI=\"init_2.mp4\"\n",
+            "#EXTINF:4\n",
+            "b.mp4\n",
+            "#EXT-X-KEY:METHOD=NONE\n",
+            "#EXTINF:4\n",
+            "c.mp4\n",
+        );
+        let timeline = timeline(PLAYLIST);
+        let applied = |index: usize| {
+            let segment = &timeline.segments[index];
+            (
+                segment.map.as_ref().map(|map| map.uri()),
+                segment
+                    .keys
+                    .iter()
+                    .filter_map(|key| key.uri())
+                    .collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!((Some("init_1.mp4"), vec!["skd://a", "data:a"]), applied(0));
+        assert_eq!((Some("init_2.mp4"), vec!["data:a", "skd://b"]), applied(1));
+        assert_eq!((Some("init_2.mp4"), vec![]), applied(2));
+    }
+
+    #[test]
+    fn segment_at_should_only_find_offsets_within_the_playlist() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-MEDIA-SEQUENCE:7\n",
+            "#EXTINF:4\n",
+            "a.ts\n",
+            "#EXTINF:4\n",
+            "b.ts\n",
+        );
+        let timeline = timeline(PLAYLIST);
+        let media_sequence_at = |offset: f64| {
+            timeline
+                .segment_at(offset)
+                .map(|segment| segment.media_sequence)
+        };
+        assert_eq!(None, media_sequence_at(-0.1));
+        assert_eq!(Some(7), media_sequence_at(0.0));
+        assert_eq!(Some(8), media_sequence_at(4.0));
+        assert_eq!(Some(8), media_sequence_at(7.9));
+        assert_eq!(None, media_sequence_at(8.0));
+        assert_eq!(Some(8.0), timeline.segments.last().map(|s| s.end()));
+        assert_eq!(Timeline::default(), super::timeline("#EXTM3U\n"));
+    }
+
+    #[test]
+    fn sequence_numbers_should_saturate_at_the_largest_value() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-MEDIA-SEQUENCE:18446744073709551615\n",
+            "#EXT-X-DISCONTINUITY-SEQUENCE:18446744073709551615\n",
+            "#EXTINF:4\n",
+            "a.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:4\n",
+            "b.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:08.000Z\n",
+            "#EXTINF:4\n",
+            "c.ts\n",
+        );
+        let timeline = timeline(PLAYLIST);
+        assert_eq!(
+            vec![
+                (u64::MAX, u64::MAX, None),
+                (u64::MAX, u64::MAX, None),
+                (
+                    u64::MAX,
+                    u64::MAX,
+                    Some(date_time!(2025-01-01 T 00:00:08.000))
+                ),
+            ],
+            timeline
+                .segments
+                .iter()
+                .map(|segment| (
+                    segment.media_sequence,
+                    segment.discontinuity_sequence,
+                    segment.start_date
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    tag::{NoCustomTag, UnknownTag, hls::TagName},
+};
+
+/// Provides an iterator over every URI found in the playlist (both URI lines and URI attributes of
+/// tags), along with a classification of what the URI refers to.
+///
+/// The classification is determined based on the context in which the URI was found. URI lines are
+/// considered to be [`UriKind::VariantStream`] when they follow an `EXT-X-STREAM-INF` tag, and
+/// otherwise are considered to be [`UriKind::MediaSegment`]. URI attributes are classified based on
+/// the tag that they are found in.
+///
+/// This is useful for crawlers and prefetchers that need to gather everything that is fetchable
+/// from a playlist. No HLS tags are fully parsed during iteration (only the attribute that carries
+/// the URI is extracted), and the URIs returned borrow directly from the input. Lines that cannot
+/// be parsed are skipped.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::playlist::{uris, PlaylistUri, UriKind};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-KEY:METHOD=AES-128,URI="https://example.com/key"
+/// #EXT-X-MAP:URI="init.mp4"
+/// #EXTINF:4
+/// segment_1.mp4
+/// #EXT-X-PART:DURATION=1,URI="part_2.0.mp4"
+/// #EXT-X-PRELOAD-HINT:TYPE=PART,URI="part_2.1.mp4"
+/// "#;
+///
+/// let mut uris = uris(PLAYLIST);
+/// assert_eq!(Some(PlaylistUri::new("https://example.com/key", UriKind::Key)), uris.next());
+/// assert_eq!(Some(PlaylistUri::new("init.mp4", UriKind::Map)), uris.next());
+/// assert_eq!(Some(PlaylistUri::new("segment_1.mp4", UriKind::MediaSegment)), uris.next());
+/// assert_eq!(Some(PlaylistUri::new("part_2.0.mp4", UriKind::Part)), uris.next());
+/// assert_eq!(Some(PlaylistUri::new("part_2.1.mp4", UriKind::PreloadHint)), uris.next());
+/// assert_eq!(None, uris.next());
+/// ```
+pub fn uris(playlist: &str) -> Uris<'_> {
+    Uris {
+        reader: Reader::from_str(playlist, ParsingOptions::builder().build()),
+        after_stream_inf: false,
+    }
+}
+
+/// A URI found in a playlist along with the classification of what it refers to.
+///
+/// See [`uris`] for more information.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PlaylistUri<'a> {
+    /// The URI (as written in the playlist, so may be relative).
+    pub uri: &'a str,
+    /// What the URI refers to.
+    pub kind: UriKind,
+}
+
+impl<'a> PlaylistUri<'a> {
+    /// Construct a new `PlaylistUri`.
+    pub const fn new(uri: &'a str, kind: UriKind) -> Self {
+        Self { uri, kind }
+    }
+}
+
+/// The classification of a URI found in a playlist.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum UriKind {
+    /// A URI line that is not preceded by `EXT-X-STREAM-INF`.
+    MediaSegment,
+    /// A URI line that is preceded by `EXT-X-STREAM-INF`.
+    VariantStream,
+    /// The `URI` attribute of `EXT-X-I-FRAME-STREAM-INF`.
+    IFrameStream,
+    /// The `URI` attribute of `EXT-X-MEDIA`.
+    Rendition,
+    /// The `URI` attribute of `EXT-X-PART`.
+    Part,
+    /// The `URI` attribute of `EXT-X-MAP`.
+    Map,
+    /// The `URI` attribute of `EXT-X-KEY`.
+    Key,
+    /// The `URI` attribute of `EXT-X-SESSION-KEY`.
+    SessionKey,
+    /// The `URI` attribute of `EXT-X-SESSION-DATA`.
+    SessionData,
+    /// The `URI` attribute of `EXT-X-PRELOAD-HINT`.
+    PreloadHint,
+    /// The `URI` attribute of `EXT-X-RENDITION-REPORT`.
+    RenditionReport,
+    /// The `SERVER-URI` attribute of `EXT-X-CONTENT-STEERING`.
+    ContentSteering,
+    /// The `X-ASSET-URI` attribute of an interstitial `EXT-X-DATERANGE`.
+    InterstitialAsset,
+    /// The `X-ASSET-LIST` attribute of an interstitial `EXT-X-DATERANGE`.
+    InterstitialAssetList,
+}
+
+/// An iterator over the URIs in a playlist.
+///
+/// Constructed via [`uris`].
+#[derive(Debug)]
+pub struct Uris<'a> {
+    reader: Reader<&'a str, NoCustomTag>,
+    after_stream_inf: bool,
+}
+
+impl<'a> Iterator for Uris<'a> {
+    type Item = PlaylistUri<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.reader.read_line() {
+                Ok(Some(line)) => line,
+                Ok(None) => return None,
+                Err(_) => continue,
+            };
+            match line {
+                input_uri!(uri) => {
+                    let kind = if self.after_stream_inf {
+                        UriKind::VariantStream
+                    } else {
+                        UriKind::MediaSegment
+                    };
+                    self.after_stream_inf = false;
+                    return Some(PlaylistUri { uri, kind });
+                }
+                HlsLine::UnknownTag(tag) => {
+                    if let Some(uri) = self.uri_from_tag(tag) {
+                        return Some(uri);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl<'a> Uris<'a> {
+    fn uri_from_tag(&mut self, tag: UnknownTag<'a>) -> Option<PlaylistUri<'a>> {
+        let uri_attributes: &[(&str, UriKind)] = match TagName::try_from(tag.name()) {
+            Ok(TagName::StreamInf) => {
+                self.after_stream_inf = true;
+                return None;
+            }
+            Ok(TagName::IFrameStreamInf) => &[("URI", UriKind::IFrameStream)],
+            Ok(TagName::Media) => &[("URI", UriKind::Rendition)],
+            Ok(TagName::Part) => &[("URI", UriKind::Part)],
+            Ok(TagName::Map) => &[("URI", UriKind::Map)],
+            Ok(TagName::Key) => &[("URI", UriKind::Key)],
+            Ok(TagName::SessionKey) => &[("URI", UriKind::SessionKey)],
+            Ok(TagName::SessionData) => &[("URI", UriKind::SessionData)],
+            Ok(TagName::PreloadHint) => &[("URI", UriKind::PreloadHint)],
+            Ok(TagName::RenditionReport) => &[("URI", UriKind::RenditionReport)],
+            Ok(TagName::ContentSteering) => &[("SERVER-URI", UriKind::ContentSteering)],
+            Ok(TagName::Daterange) => &[
+                ("X-ASSET-URI", UriKind::InterstitialAsset),
+                ("X-ASSET-LIST", UriKind::InterstitialAssetList),
+            ],
+            _ => return None,
+        };
+        let attribute_list = tag.value()?.try_as_attribute_map().ok()?;
+        attribute_list.into_iter().find_map(|(name, value)| {
+            let (_, kind) = uri_attributes.iter().find(|(n, _)| *n == name)?;
+            value.quoted().map(|uri| PlaylistUri { uri, kind: *kind })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn multivariant_playlist_uris_should_be_classified() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-CONTENT-STEERING:SERVER-URI=\"steering.json\",PATHWAY-ID=\"CDN-A\"\n",
+            "#EXT-X-SESSION-DATA:DATA-ID=\"com.example.title\",URI=\"title.json\"\n",
+            "#EXT-X-SESSION-KEY:METHOD=SAMPLE-AES,URI=\"skd://key\",KEYFORMAT=\"com.apple.streamingkeydelivery\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",URI=\"audio/en.m3u8\"\n",
+            "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"Muxed\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1280000,AUDIO=\"aac\"\n",
+            "# a comment between the tag and the URI\n",
+            "low/video.m3u8\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=86000,URI=\"low/iframe.m3u8\"\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=2560000,AUDIO=\"aac\"\n",
+            "mid/video.m3u8\n",
+        );
+        assert_eq!(
+            vec![
+                PlaylistUri::new("steering.json", UriKind::ContentSteering),
+                PlaylistUri::new("title.json", UriKind::SessionData),
+                PlaylistUri::new("skd://key", UriKind::SessionKey),
+                PlaylistUri::new("audio/en.m3u8", UriKind::Rendition),
+                PlaylistUri::new("low/video.m3u8", UriKind::VariantStream),
+                PlaylistUri::new("low/iframe.m3u8", UriKind::IFrameStream),
+                PlaylistUri::new("mid/video.m3u8", UriKind::VariantStream),
+            ],
+            uris(PLAYLIST).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn media_playlist_uris_should_be_classified() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\r\n",
+            "#EXT-X-TARGETDURATION:4\r\n",
+            "#EXT-X-KEY:METHOD=NONE\r\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\r\n",
+            "#EXTINF:4\r\n",
+            "segment_1.mp4\r\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",CLASS=\"com.apple.hls.interstitial\",X-ASSET-LIST=\"ads.json\"\r\n",
+            "#EXT-X-DATERANGE:ID=\"ad2\",CLASS=\"com.apple.hls.interstitial\",X-ASSET-URI=\"ad.m3u8\"\r\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\"\r\n",
+            "#EXTINF:4\r\n",
+            "segment_2.mp4\r\n",
+            "#EXT-X-RENDITION-REPORT:URI=\"../high.m3u8\",LAST-MSN=2\r\n",
+        );
+        assert_eq!(
+            vec![
+                PlaylistUri::new("init.mp4", UriKind::Map),
+                PlaylistUri::new("segment_1.mp4", UriKind::MediaSegment),
+                PlaylistUri::new("ads.json", UriKind::InterstitialAssetList),
+                PlaylistUri::new("ad.m3u8", UriKind::InterstitialAsset),
+                PlaylistUri::new("key.bin", UriKind::Key),
+                PlaylistUri::new("segment_2.mp4", UriKind::MediaSegment),
+                PlaylistUri::new("../high.m3u8", UriKind::RenditionReport),
+            ],
+            uris(PLAYLIST).collect::<Vec<_>>()
+        );
+    }
+}
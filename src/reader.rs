@@ -1,10 +1,16 @@
 use crate::{
-    config::ParsingOptions,
-    error::{ReaderBytesError, ReaderStrError},
+    config::{ErrorRecovery, ParsingOptions},
+    encoding::UTF_8_BOM,
+    error::{GenericSyntaxError, ReaderBytesError, ReaderIoError, ReaderStrError},
     line::{HlsLine, parse_bytes_with_custom, parse_with_custom},
+    line_buffer::LineBuffer,
+    reader_metrics::{ReaderMetrics, ReaderPosition},
     tag::{CustomTag, NoCustomTag},
+    utils::{split_on_new_line, str_from},
 };
-use std::marker::PhantomData;
+#[cfg(feature = "hls-tags")]
+use crate::{tag::KnownTag, validation::VersionInference};
+use std::{io::BufRead, marker::PhantomData};
 
 /// A reader that parses lines of input HLS playlist data.
 ///
@@ -14,8 +20,8 @@ use std::marker::PhantomData;
 ///
 /// ## Basic usage
 ///
-/// A reader can take an input `&str` (or `&[u8]`) and sequentially parse information about HLS
-/// lines. For example, you could use the `Reader` to build up a media playlist:
+/// A reader can take an input `&str` (or `&[u8]`, or a [`BufRead`] via [`Reader::from_reader`])
+/// and sequentially parse information about HLS lines. For example, you could use the `Reader` to build up a media playlist:
 /// ```
 /// # use quick_m3u8::{HlsLine, Reader};
 /// # use quick_m3u8::config::ParsingOptions;
@@ -215,6 +221,23 @@ use std::marker::PhantomData;
 /// # Ok::<(), Box<dyn Error>>(())
 /// ```
 ///
+/// ## Byte order mark
+///
+/// Playlists produced by some (particularly Windows based) tooling begin with a UTF-8 byte order
+/// mark (BOM). The `Reader` strips the BOM from the start of the input, so that the first line is
+/// parsed as expected, and records its presence (see [`Self::has_bom`]):
+/// ```
+/// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions, tag::{KnownTag, hls}};
+/// let mut reader = Reader::from_str("\u{FEFF}#EXTM3U\n", ParsingOptions::default());
+/// assert!(reader.has_bom());
+/// assert!(matches!(
+///     reader.read_line(),
+///     Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::M3u(_)))))
+/// ));
+/// ```
+/// For playlists that are not valid UTF-8 (e.g. Latin-1 encoded), see the `latin1` feature and
+/// [`crate::encoding`].
+///
 /// ## Custom tag reading
 ///
 /// We can also configure the `Reader` to accept parsing of custom defined tags. Using the same idea
@@ -350,18 +373,167 @@ use std::marker::PhantomData;
 pub struct Reader<R, Custom> {
     inner: R,
     options: ParsingOptions,
+    has_bom: bool,
+    stopped: bool,
+    stopped_at_line_limit: bool,
+    lines_read: usize,
+    position: ReaderPosition,
+    metrics: Option<ReaderMetrics>,
+    #[cfg(feature = "hls-tags")]
+    version_inference: Option<VersionInference>,
     _marker: PhantomData<Custom>,
 }
 
+impl<R, Custom> Reader<R, Custom> {
+    /// Enables the collection of [`ReaderMetrics`] for the lines read from this point on.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{Reader, config::ParsingOptions, tag::hls::TagName};
+    /// let mut reader = Reader::from_str(
+    ///     "#EXTM3U\n#EXTINF:4\n0.ts\n#EXTINF:4\n1.ts\n",
+    ///     ParsingOptions::default(),
+    /// )
+    /// .with_metrics();
+    /// while let Ok(Some(_)) = reader.read_line() {}
+    ///
+    /// let metrics = reader.metrics().expect("metrics should be enabled");
+    /// assert_eq!(2, metrics.hls_tag_count(TagName::Inf));
+    /// assert_eq!(2, metrics.uris());
+    /// assert_eq!(5, metrics.total());
+    /// ```
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = Some(ReaderMetrics::default());
+        self
+    }
+
+    /// The metrics collected so far (if enabled via [`Self::with_metrics`]).
+    pub fn metrics(&self) -> Option<&ReaderMetrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Enables the inference of the minimum `EXT-X-VERSION` required by the lines read from this
+    /// point on (see [`VersionInference`]).
+    ///
+    /// This is useful for playlists that do not declare a version (or that declare one that is too
+    /// low), as the version implied by the features used is known without a separate pass over the
+    /// playlist. For example:
+    /// ```
+    /// # use quick_m3u8::{Reader, config::ParsingOptions};
+    /// # use quick_m3u8::validation::{VersionedFeature, Violation};
+    /// let mut reader = Reader::from_str(
+    ///     "#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:3.5\n0.ts\n#EXT-X-BYTERANGE:1000@0\n",
+    ///     ParsingOptions::default(),
+    /// )
+    /// .with_version_inference();
+    ///
+    /// while let Ok(Some(_)) = reader.read_line() {
+    ///     if reader.inferred_version() == Some(3) {
+    ///         break;
+    ///     }
+    /// }
+    /// assert_eq!(4, reader.position().line);
+    /// while let Ok(Some(_)) = reader.read_line() {}
+    /// assert_eq!(Some(4), reader.inferred_version());
+    ///
+    /// let inference = reader.version_inference().expect("inference should be enabled");
+    /// assert_eq!(None, inference.declared_version());
+    /// assert_eq!(
+    ///     vec![(VersionedFeature::FloatingPointDuration, 3), (VersionedFeature::Byterange, 5)],
+    ///     inference.features()
+    /// );
+    /// assert_eq!(2, inference.diagnostics().len());
+    /// ```
+    #[cfg(feature = "hls-tags")]
+    pub fn with_version_inference(mut self) -> Self {
+        self.version_inference = Some(VersionInference::default());
+        self
+    }
+
+    /// The version inference so far (if enabled via [`Self::with_version_inference`]).
+    #[cfg(feature = "hls-tags")]
+    pub fn version_inference(&self) -> Option<&VersionInference> {
+        self.version_inference.as_ref()
+    }
+
+    /// The minimum version required by the lines read so far (if enabled via
+    /// [`Self::with_version_inference`]). This is provided regardless of whether the playlist has
+    /// an `EXT-X-VERSION` tag (see [`VersionInference::inferred_version`]).
+    #[cfg(feature = "hls-tags")]
+    pub fn inferred_version(&self) -> Option<u64> {
+        self.version_inference
+            .as_ref()
+            .map(VersionInference::inferred_version)
+    }
+}
+
+// Splits a leading UTF-8 byte order mark off of the input data.
+trait StripBom {
+    fn strip_bom(&self) -> (&Self, bool);
+}
+impl StripBom for str {
+    fn strip_bom(&self) -> (&Self, bool) {
+        match self.strip_prefix('\u{FEFF}') {
+            Some(stripped) => (stripped, true),
+            None => (self, false),
+        }
+    }
+}
+impl StripBom for [u8] {
+    fn strip_bom(&self) -> (&Self, bool) {
+        match self.strip_prefix(UTF_8_BOM.as_slice()) {
+            Some(stripped) => (stripped, true),
+            None => (self, false),
+        }
+    }
+}
+
+// Provides the first line (without the new line characters), or the data remaining after it.
+trait SplitLine {
+    fn first_line(&self) -> &Self;
+    fn skip_line(&self) -> &Self;
+}
+impl SplitLine for str {
+    // Splitting on new line happens on a single byte character, so both parts are still valid
+    // UTF-8.
+    fn first_line(&self) -> &Self {
+        str_from(split_on_new_line(self.as_bytes()).parsed)
+    }
+    fn skip_line(&self) -> &Self {
+        str_from(
+            split_on_new_line(self.as_bytes())
+                .remaining
+                .unwrap_or_default(),
+        )
+    }
+}
+impl SplitLine for [u8] {
+    fn first_line(&self) -> &Self {
+        split_on_new_line(self).parsed
+    }
+    fn skip_line(&self) -> &Self {
+        split_on_new_line(self).remaining.unwrap_or_default()
+    }
+}
+
 macro_rules! impl_reader {
     ($type:ty, $parse_fn:ident, $from_fn_ident:ident, $from_custom_fn_ident:ident, $error_type:ident) => {
         impl<'a> Reader<&'a $type, NoCustomTag> {
             /// Creates a reader without custom tag parsing support (in this case, the generic
             /// `Custom` type is [`NoCustomTag`]).
             pub fn $from_fn_ident(data: &'a $type, options: ParsingOptions) -> Self {
+                let (inner, has_bom) = data.strip_bom();
                 Self {
-                    inner: data,
+                    inner,
                     options,
+                    has_bom,
+                    stopped: false,
+                    stopped_at_line_limit: false,
+                    lines_read: 0,
+                    position: start_position(has_bom),
+                    metrics: None,
+                    #[cfg(feature = "hls-tags")]
+                    version_inference: None,
                     _marker: PhantomData::<NoCustomTag>,
                 }
             }
@@ -377,33 +549,89 @@ macro_rules! impl_reader {
                 options: ParsingOptions,
                 custom: PhantomData<Custom>,
             ) -> Self {
+                let (inner, has_bom) = str.strip_bom();
                 Self {
-                    inner: str,
+                    inner,
                     options,
+                    has_bom,
+                    stopped: false,
+                    stopped_at_line_limit: false,
+                    lines_read: 0,
+                    position: start_position(has_bom),
+                    metrics: None,
+                    #[cfg(feature = "hls-tags")]
+                    version_inference: None,
                     _marker: custom,
                 }
             }
 
+            /// Indicates whether the input data started with a UTF-8 byte order mark (BOM).
+            ///
+            /// The BOM is stripped before parsing (so that it does not prevent the `#EXTM3U` tag
+            /// from being recognized), but this can be used along with [`crate::Writer::write_bom`]
+            /// to preserve the BOM when re-writing the playlist.
+            pub fn has_bom(&self) -> bool {
+                self.has_bom
+            }
+
             /// Returns the inner data of the reader.
             pub fn into_inner(self) -> &'a $type {
                 self.inner
             }
 
             /// Reads a single HLS line from the reference data.
+            ///
+            /// When an error is experienced, what happens on the next call depends on the
+            /// [`crate::config::ErrorRecovery`] set in the [`ParsingOptions`]. By default, the
+            /// offending line is skipped and reading continues from the next line.
+            ///
+            /// Each call that provides a line (or an error) consumes at least one byte of the
+            /// data, and so reading until `Ok(None)` always terminates. The number of lines read
+            /// can also be limited (see [`crate::config::ParsingOptionsBuilder::with_max_lines`]).
             pub fn read_line(&mut self) -> Result<Option<HlsLine<'a, Custom>>, $error_type<'a>> {
-                if self.inner.is_empty() {
+                #[cfg(feature = "hls-tags")]
+                let line_number = self.position.line;
+                let result = self.read_next_line();
+                if let Some(metrics) = &mut self.metrics {
+                    metrics.record(&result, &self.options);
+                }
+                #[cfg(feature = "hls-tags")]
+                if let (Some(inference), Ok(Some(line))) = (&mut self.version_inference, &result) {
+                    inference.observe(line_number, line);
+                }
+                result
+            }
+
+            fn read_next_line(&mut self) -> Result<Option<HlsLine<'a, Custom>>, $error_type<'a>> {
+                if self.inner.is_empty() || self.stopped {
                     return Ok(None);
                 };
+                if self.is_at_line_limit() {
+                    self.stopped = true;
+                    self.stopped_at_line_limit = true;
+                    return Err($error_type {
+                        errored_line: self.inner.first_line(),
+                        error: GenericSyntaxError::LineLimitExceeded.into(),
+                    });
+                }
+                self.lines_read += 1;
                 match $parse_fn(self.inner, &self.options) {
                     Ok(slice) => {
-                        let parsed = slice.parsed;
-                        let remaining = slice.remaining;
-                        std::mem::swap(&mut self.inner, &mut remaining.unwrap_or_default());
-                        Ok(Some(parsed))
+                        self.advance(slice.remaining);
+                        match slice.parsed {
+                            HlsLine::Comment(comment) if self.options.comment_attachment() => {
+                                Ok(Some(self.attach_comment_to_next_tag(comment)))
+                            }
+                            parsed => Ok(Some(parsed)),
+                        }
                     }
                     Err(error) => {
-                        let remaining = error.errored_line_slice.remaining;
-                        std::mem::swap(&mut self.inner, &mut remaining.unwrap_or_default());
+                        match self.options.error_recovery() {
+                            ErrorRecovery::SkipLine => {
+                                self.advance(error.errored_line_slice.remaining);
+                            }
+                            ErrorRecovery::Stop => self.stopped = true,
+                        }
                         Err($error_type {
                             errored_line: error.errored_line_slice.parsed,
                             error: error.error,
@@ -411,6 +639,135 @@ macro_rules! impl_reader {
                     }
                 }
             }
+
+            // Moves on to the data remaining after the line that was read. The parser always
+            // provides the data after the new line, but as a defensive measure, the line is skipped
+            // if the data has not been reduced, so that `read_line` cannot repeatedly provide the
+            // same line.
+            fn advance(&mut self, remaining: Option<&'a $type>) {
+                let remaining = remaining.unwrap_or_default();
+                if remaining.len() < self.inner.len() {
+                    self.consume(remaining);
+                } else {
+                    self.consume(self.inner.skip_line());
+                }
+            }
+
+            fn consume(&mut self, remaining: &'a $type) {
+                let inner: &[u8] = self.inner.as_ref();
+                self.position
+                    .advance(&inner[..inner.len() - remaining.len()]);
+                self.inner = remaining;
+            }
+
+            fn is_at_line_limit(&self) -> bool {
+                is_at_line_limit(&self.options, self.lines_read)
+            }
+
+            // Attaches the comment to the next line when that line is a known HLS tag that supports
+            // comments, otherwise the comment is provided as is and the next line is left unread.
+            fn attach_comment_to_next_tag(
+                &mut self,
+                comment: std::borrow::Cow<'a, str>,
+            ) -> HlsLine<'a, Custom> {
+                if self.inner.is_empty() {
+                    return HlsLine::Comment(comment);
+                }
+                match $parse_fn::<Custom>(self.inner, &self.options) {
+                    Ok(slice) => match slice.parsed {
+                        #[cfg(feature = "hls-tags")]
+                        HlsLine::KnownTag(KnownTag::Hls(mut tag)) => {
+                            match tag.attach_comment(comment) {
+                                Ok(()) => {
+                                    self.advance(slice.remaining);
+                                    HlsLine::KnownTag(KnownTag::Hls(tag))
+                                }
+                                Err(comment) => HlsLine::Comment(comment),
+                            }
+                        }
+                        _ => HlsLine::Comment(comment),
+                    },
+                    Err(_) => HlsLine::Comment(comment),
+                }
+            }
+
+            /// Reads lines until the end of the data or until an error is experienced, providing
+            /// the lines that were read along with the error (if there was one).
+            ///
+            /// This is useful for processing data on a best-effort basis. Reading can be continued
+            /// after the error by calling this method again (or, if the reader was configured with
+            /// [`ErrorRecovery::Stop`], after calling [`Self::resume`]).
+            pub fn read_until_error(
+                &mut self,
+            ) -> (Vec<HlsLine<'a, Custom>>, Option<$error_type<'a>>) {
+                let mut lines = Vec::new();
+                loop {
+                    match self.read_line() {
+                        Ok(Some(line)) => lines.push(line),
+                        Ok(None) => return (lines, None),
+                        Err(error) => return (lines, Some(error)),
+                    }
+                }
+            }
+
+            /// Indicates whether the reader has stopped because of an error (only possible when
+            /// configured with [`ErrorRecovery::Stop`]).
+            pub fn is_stopped(&self) -> bool {
+                self.stopped
+            }
+
+            /// Resumes reading after the reader has stopped because of an error (see
+            /// [`ErrorRecovery::Stop`]), by skipping the offending line.
+            ///
+            /// When the reader stopped because it reached the line limit (see
+            /// [`crate::config::ParsingOptionsBuilder::with_max_lines`]), the line is not skipped,
+            /// and instead the reader is allowed to read up to the limit again.
+            ///
+            /// This has no effect if the reader has not stopped.
+            pub fn resume(&mut self) {
+                if self.stopped {
+                    if self.stopped_at_line_limit {
+                        self.lines_read = 0;
+                    } else {
+                        self.consume(self.inner.skip_line());
+                    }
+                    self.stopped = false;
+                    self.stopped_at_line_limit = false;
+                }
+            }
+
+            /// The number of lines that have been read (since the reader was created, or since it
+            /// was last resumed after reaching the line limit).
+            pub fn lines_read(&self) -> usize {
+                self.lines_read
+            }
+
+            /// The position of the data that has not yet been read.
+            ///
+            /// Unlike [`Self::lines_read`], this counts the lines of the data (rather than the
+            /// lines provided), and so is not reset by [`Self::resume`]. Taking the position before
+            /// reading a line gives the location of that line (or of the error), which is useful
+            /// for pointing at the exact location in the playlist when reporting problems:
+            /// ```
+            /// # use quick_m3u8::{Reader, ReaderPosition, config::ParsingOptions};
+            /// let mut reader = Reader::from_str(
+            ///     "#EXTM3U\n#EXT\n#EXTINF:4\n0.ts\n",
+            ///     ParsingOptions::default(),
+            /// );
+            /// let mut errors = Vec::new();
+            /// loop {
+            ///     let position = reader.position();
+            ///     match reader.read_line() {
+            ///         Ok(Some(_)) => (),
+            ///         Ok(None) => break,
+            ///         Err(_) => errors.push(position),
+            ///     }
+            /// }
+            /// assert_eq!(vec![ReaderPosition { line: 2, byte_offset: 8 }], errors);
+            /// ```
+            pub fn position(&self) -> ReaderPosition {
+                self.position
+            }
         }
     };
 }
@@ -430,19 +787,225 @@ impl_reader!(
     ReaderBytesError
 );
 
+/// The input of a [`Reader`] constructed with [`Reader::from_reader`].
+///
+/// This holds the [`BufRead`] along with the buffers that lines are read into.
+#[derive(Debug)]
+pub struct BufReadInput<R> {
+    inner: R,
+    buffer: LineBuffer,
+}
+
+impl<R> Reader<BufReadInput<R>, NoCustomTag>
+where
+    R: BufRead,
+{
+    /// Creates a reader that parses lines incrementally from the provided [`BufRead`].
+    ///
+    /// Unlike [`Reader::from_str`] (or [`Reader::from_bytes`]), this does not require the whole
+    /// playlist to be in memory, which is useful for very large playlists (such as VOD playlists
+    /// with many hours of short segments). Each line is read into a buffer owned by the reader, and
+    /// the [`HlsLine`] that is provided borrows from that buffer (so parsing remains zero-copy). As a
+    /// consequence, the line must be dropped before the next line is read.
+    ///
+    /// The [`ParsingOptions`] have the same meaning as they do for the other readers, including the
+    /// [`ErrorRecovery`] behavior and comment attachment.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions};
+    /// # use std::io::BufReader;
+    /// let file: &[u8] = b"#EXTM3U\n#EXT-X-TARGETDURATION:2\n#EXTINF:2\n0.ts\n#EXTINF:2\n1.ts\n";
+    /// // This would typically be a `BufReader<File>`.
+    /// let mut reader = Reader::from_reader(BufReader::new(file), ParsingOptions::default());
+    /// let mut segments = 0;
+    /// while let Some(line) = reader.read_line()? {
+    ///     if let HlsLine::Uri(_) = line {
+    ///         segments += 1;
+    ///     }
+    /// }
+    /// assert_eq!(2, segments);
+    /// # Ok::<(), quick_m3u8::error::ReaderIoError>(())
+    /// ```
+    pub fn from_reader(inner: R, options: ParsingOptions) -> Self {
+        Self {
+            inner: BufReadInput {
+                inner,
+                buffer: LineBuffer::default(),
+            },
+            options,
+            has_bom: false,
+            stopped: false,
+            stopped_at_line_limit: false,
+            lines_read: 0,
+            position: ReaderPosition::default(),
+            metrics: None,
+            #[cfg(feature = "hls-tags")]
+            version_inference: None,
+            _marker: PhantomData::<NoCustomTag>,
+        }
+    }
+
+    /// Indicates whether the input data started with a UTF-8 byte order mark (BOM).
+    ///
+    /// This is only known once the first line has been read.
+    pub fn has_bom(&self) -> bool {
+        self.inner.buffer.has_bom()
+    }
+
+    /// Returns the inner [`BufRead`].
+    pub fn into_inner(self) -> R {
+        self.inner.inner
+    }
+
+    /// Reads a single HLS line from the input.
+    ///
+    /// When an error is experienced, what happens on the next call depends on the
+    /// [`ErrorRecovery`] set in the [`ParsingOptions`]. By default, the offending line is skipped
+    /// and reading continues from the next line.
+    pub fn read_line(&mut self) -> Result<Option<HlsLine<'_>>, ReaderIoError> {
+        self.read_line_with_custom::<NoCustomTag>()
+    }
+
+    /// Reads a single HLS line from the input with support for the provided custom tag.
+    ///
+    /// This method differs from [`Self::read_line`] as it allows the user to provide their own
+    /// custom tag implementation for parsing (see [`Reader::with_custom_from_str`]).
+    pub fn read_line_with_custom<'a, Custom>(
+        &'a mut self,
+    ) -> Result<Option<HlsLine<'a, Custom>>, ReaderIoError>
+    where
+        Custom: CustomTag<'a>,
+    {
+        let Self {
+            inner,
+            options,
+            lines_read,
+            metrics,
+            #[cfg(feature = "hls-tags")]
+            version_inference,
+            ..
+        } = self;
+        #[cfg(feature = "hls-tags")]
+        let line_number = inner.buffer.position().line;
+        let result = read_buffered_line(inner, options, lines_read);
+        if let Some(metrics) = metrics {
+            metrics.record(&result, options);
+        }
+        #[cfg(feature = "hls-tags")]
+        if let (Some(inference), Ok(Some(line))) = (version_inference, &result) {
+            inference.observe(line_number, line);
+        }
+        result
+    }
+
+    /// Indicates whether the reader has stopped because of an error (only possible when
+    /// configured with [`ErrorRecovery::Stop`]).
+    pub fn is_stopped(&self) -> bool {
+        self.inner.buffer.stopped
+    }
+
+    /// Allows the reader to continue after it has stopped because of an error.
+    ///
+    /// When the reader stopped because it reached the line limit (see
+    /// [`crate::config::ParsingOptionsBuilder::with_max_lines`]), the reader is allowed to read up
+    /// to the limit again.
+    pub fn resume(&mut self) {
+        if self.inner.buffer.resume() {
+            self.lines_read = 0;
+        }
+    }
+
+    /// The number of lines that have been read (since the reader was created, or since it was last
+    /// resumed after reaching the line limit).
+    pub fn lines_read(&self) -> usize {
+        self.lines_read
+    }
+
+    /// The position of the data that has not yet been read (see [`Reader::position`]).
+    ///
+    /// Lines are read from the [`BufRead`] ahead of being provided when comments are attached to
+    /// tags (or when the line limit is reached), but the position does not include such lines
+    /// until they are provided.
+    pub fn position(&self) -> ReaderPosition {
+        self.inner.buffer.position()
+    }
+}
+
+// Reads a line from the `BufReadInput` (see `LineBuffer` for the steps involved).
+fn read_buffered_line<'a, R, Custom>(
+    input: &'a mut BufReadInput<R>,
+    options: &ParsingOptions,
+    lines_read: &mut usize,
+) -> Result<Option<HlsLine<'a, Custom>>, ReaderIoError>
+where
+    R: BufRead,
+    Custom: CustomTag<'a>,
+{
+    let BufReadInput { inner, buffer } = input;
+    if buffer.stopped {
+        return Ok(None);
+    }
+    if is_at_line_limit(options, *lines_read) {
+        if !buffer.has_next_line {
+            buffer.has_next_line = read_until_new_line(inner, &mut buffer.next_line)?;
+        }
+        return if buffer.has_next_line {
+            Err(buffer.line_limit_error())
+        } else {
+            Ok(None)
+        };
+    }
+    *lines_read += 1;
+    if !buffer.take_next_line() && !read_until_new_line(inner, &mut buffer.line)? {
+        return Ok(None);
+    }
+    buffer.strip_bom();
+    if buffer.wants_next_line(options) {
+        buffer.has_next_line = read_until_new_line(inner, &mut buffer.next_line)?;
+    }
+    buffer.parse(options)
+}
+
+// The position of the data after the byte order mark (if there was one).
+fn start_position(has_bom: bool) -> ReaderPosition {
+    let mut position = ReaderPosition::default();
+    if has_bom {
+        position.advance(UTF_8_BOM.as_slice());
+    }
+    position
+}
+
+// Whether the reader has read the maximum number of lines that it was configured to read.
+pub(crate) fn is_at_line_limit(options: &ParsingOptions, lines_read: usize) -> bool {
+    options
+        .max_lines()
+        .is_some_and(|max_lines| lines_read >= max_lines)
+}
+
+// Reads the next line (including the new line characters) into the buffer, indicating whether
+// there was a line to read.
+fn read_until_new_line(inner: &mut impl BufRead, buffer: &mut Vec<u8>) -> std::io::Result<bool> {
+    buffer.clear();
+    Ok(inner.read_until(b'\n', buffer)? > 0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{config::ParsingOptionsBuilder, error::SyntaxError, tag::UnknownTag};
+    #[cfg(feature = "hls-tags")]
     use crate::{
-        config::ParsingOptionsBuilder,
-        error::{ParseTagValueError, SyntaxError, UnknownTagSyntaxError, ValidationError},
+        error::{ParseTagValueError, UnknownTagSyntaxError, ValidationError},
         tag::{
-            CustomTagAccess, TagValue, UnknownTag,
-            hls::{Endlist, Inf, M3u, Targetduration, Version},
+            CustomTagAccess, TagValue,
+            hls::{self, Endlist, Inf, M3u, Targetduration, Version},
         },
+        validation::{VersionedFeature, Violation},
     };
     use pretty_assertions::assert_eq;
 
+    #[cfg(feature = "hls-tags")]
     macro_rules! reader_test {
         ($reader:tt, $method:tt, $expectation:expr $(, $buf:ident)?) => {
             for i in 0..=11 {
@@ -475,6 +1038,7 @@ mod tests {
         };
     }
 
+    #[cfg(feature = "hls-tags")]
     #[test]
     fn reader_from_str_should_read_as_expected() {
         let mut reader = Reader::from_str(
@@ -491,10 +1055,12 @@ mod tests {
                 value: Some(TagValue(b"MEANING-OF-LIFE=42,QUESTION=\"UNKNOWN\"")),
                 original_input: &EXAMPLE_MANIFEST.as_bytes()[50..],
                 validation_error: None,
+                replacement: None,
             }))
         );
     }
 
+    #[cfg(feature = "hls-tags")]
     #[test]
     fn reader_from_buf_read_should_read_as_expected() {
         let inner = EXAMPLE_MANIFEST.as_bytes();
@@ -512,10 +1078,58 @@ mod tests {
                 value: Some(TagValue(b"MEANING-OF-LIFE=42,QUESTION=\"UNKNOWN\"")),
                 original_input: &EXAMPLE_MANIFEST.as_bytes()[50..],
                 validation_error: None,
+                replacement: None,
             }))
         );
     }
 
+    #[test]
+    fn reader_from_reader_should_match_reader_from_str() {
+        let playlist = format!("\u{FEFF}{EXAMPLE_MANIFEST}# comment\n#EXTINF:4\n#EXT\nlast.ts");
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_all_tags()
+            .with_comment_attachment()
+            .build();
+        let mut expected_reader = Reader::from_str(&playlist, options.clone());
+        // A small capacity means that the internal buffer is refilled many times per line.
+        let inner = std::io::BufReader::with_capacity(8, playlist.as_bytes());
+        let mut reader = Reader::from_reader(inner, options);
+        loop {
+            let expected = expected_reader
+                .read_line()
+                .map(|line| line.map(|line| line.to_string()))
+                .map_err(|e| e.errored_line.to_string());
+            let actual = reader
+                .read_line()
+                .map(|line| line.map(|line| line.to_string()))
+                .map_err(|e| match e {
+                    ReaderIoError::Syntax { errored_line, .. } => {
+                        String::from_utf8_lossy(&errored_line).to_string()
+                    }
+                    ReaderIoError::Io(e) => e.to_string(),
+                });
+            assert_eq!(expected, actual);
+            assert_eq!(expected_reader.position(), reader.position());
+            if let Ok(None) = actual {
+                break;
+            }
+        }
+        assert!(reader.has_bom());
+        assert_eq!(playlist.len(), reader.position().byte_offset);
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn reader_from_bytes_should_strip_bom() {
+        let inner = b"\xEF\xBB\xBF#EXTM3U\n#EXT-X-VERSION:3\n";
+        let mut reader = Reader::from_bytes(inner, ParsingOptions::default());
+        assert!(reader.has_bom());
+        assert_eq!(Ok(Some(HlsLine::from(M3u))), reader.read_line());
+        let reader = Reader::from_bytes(b"#EXTM3U\n", ParsingOptions::default());
+        assert!(!reader.has_bom());
+    }
+
+    #[cfg(feature = "hls-tags")]
     #[test]
     fn reader_from_str_with_custom_should_read_as_expected() {
         let mut reader = Reader::with_custom_from_str(
@@ -531,11 +1145,13 @@ mod tests {
             Some(HlsLine::from(CustomTagAccess {
                 custom_tag: ExampleTag::new(42, "UNKNOWN"),
                 is_dirty: false,
-                original_input: EXAMPLE_MANIFEST[50..].as_bytes(),
+                original_input: &EXAMPLE_MANIFEST.as_bytes()[50..],
+                replacement: None,
             }))
         );
     }
 
+    #[cfg(feature = "hls-tags")]
     #[test]
     fn reader_from_buf_with_custom_read_should_read_as_expected() {
         let inner = EXAMPLE_MANIFEST.as_bytes();
@@ -552,11 +1168,13 @@ mod tests {
             Some(HlsLine::from(CustomTagAccess {
                 custom_tag: ExampleTag::new(42, "UNKNOWN"),
                 is_dirty: false,
-                original_input: EXAMPLE_MANIFEST[50..].as_bytes(),
+                original_input: &EXAMPLE_MANIFEST.as_bytes()[50..],
+                replacement: None,
             }))
         );
     }
 
+    #[cfg(feature = "hls-tags")]
     #[test]
     fn when_reader_fails_it_moves_to_next_line() {
         let input = concat!("#EXTM3U\n", "#EXT\n", "#Comment");
@@ -580,17 +1198,307 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn when_reader_fails_with_stop_recovery_it_stops_until_resumed() {
+        let input = b"#EXTM3U\n\xFF\xFE\n#Comment\n";
+        let mut reader = Reader::from_bytes(
+            input,
+            ParsingOptionsBuilder::new()
+                .with_parsing_for_all_tags()
+                .with_error_recovery(ErrorRecovery::Stop)
+                .build(),
+        );
+        assert_eq!(Ok(Some(HlsLine::from(M3u))), reader.read_line());
+        let error = reader
+            .read_line()
+            .expect_err("line should be invalid UTF-8");
+        assert_eq!(b"\xFF\xFE", error.errored_line);
+        assert!(matches!(error.error, SyntaxError::InvalidUtf8(_)));
+        assert!(reader.is_stopped());
+        assert_eq!(Ok(None), reader.read_line());
+        reader.resume();
+        assert!(!reader.is_stopped());
+        assert_eq!(
+            Ok(Some(HlsLine::Comment("Comment".into()))),
+            reader.read_line()
+        );
+        assert_eq!(Ok(None), reader.read_line());
+    }
+
+    #[test]
+    fn when_reader_fails_with_stop_recovery_into_inner_starts_at_errored_line() {
+        let input = "#EXTM3U\n#EXT\n#Comment";
+        let mut reader = Reader::from_str(
+            input,
+            ParsingOptionsBuilder::new()
+                .with_error_recovery(ErrorRecovery::Stop)
+                .build(),
+        );
+        let (lines, error) = reader.read_until_error();
+        assert_eq!(
+            vec![HlsLine::UnknownTag(UnknownTag {
+                name: "M3U",
+                value: None,
+                original_input: input.as_bytes(),
+                validation_error: None,
+                replacement: None,
+            })],
+            lines
+        );
+        assert_eq!(Some("#EXT"), error.map(|e| e.errored_line));
+        assert_eq!("#EXT\n#Comment", reader.into_inner());
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn read_until_error_should_be_resumable_with_skip_line_recovery() {
+        let input = "#EXTM3U\n#EXT\n#Comment\n";
+        let mut reader = Reader::from_str(input, ParsingOptions::default());
+        let (lines, error) = reader.read_until_error();
+        assert_eq!(vec![HlsLine::from(M3u)], lines);
+        assert!(error.is_some());
+        let (lines, error) = reader.read_until_error();
+        assert_eq!(vec![HlsLine::Comment("Comment".into())], lines);
+        assert_eq!(None, error);
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn comments_should_only_be_attached_to_tags_when_enabled() {
+        let input = "#EXTM3U\n#slate\n#EXTINF:6,\n#gap\n#EXT-X-ENDLIST\n";
+        let mut reader = Reader::from_str(input, ParsingOptions::default());
+        let (lines, _) = reader.read_until_error();
+        assert_eq!(5, lines.len());
+        assert_eq!(HlsLine::Comment("slate".into()), lines[1]);
+
+        let mut reader = Reader::from_str(
+            input,
+            ParsingOptionsBuilder::new()
+                .with_parsing_for_all_tags()
+                .with_comment_attachment()
+                .build(),
+        );
+        assert_eq!(Some(HlsLine::from(M3u)), reader.read_line().unwrap());
+        match reader.read_line() {
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(inf))))) => {
+                assert_eq!(Some("slate"), inf.comment());
+                assert_eq!(6.0, inf.duration());
+            }
+            line => panic!("unexpected line {line:?}"),
+        }
+        // EXT-X-ENDLIST has no value and so does not support comments.
+        assert_eq!(
+            Some(HlsLine::Comment("gap".into())),
+            reader.read_line().unwrap()
+        );
+        assert_eq!(Some(HlsLine::from(Endlist)), reader.read_line().unwrap());
+        assert_eq!(None, reader.read_line().unwrap());
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn position_should_count_lines_of_the_data() {
+        let input = "\u{FEFF}#EXTM3U\r\n#slate\n#EXTINF:6,\n#EXT\n0.ts";
+        let mut reader = Reader::from_str(
+            input,
+            ParsingOptionsBuilder::new()
+                .with_parsing_for_all_tags()
+                .with_comment_attachment()
+                .with_error_recovery(ErrorRecovery::Stop)
+                .build(),
+        );
+        assert_eq!(
+            ReaderPosition {
+                line: 1,
+                byte_offset: 3
+            },
+            reader.position()
+        );
+        assert_eq!(Some(HlsLine::from(M3u)), reader.read_line().unwrap());
+        assert_eq!(
+            ReaderPosition {
+                line: 2,
+                byte_offset: 12
+            },
+            reader.position()
+        );
+        // The comment is attached to the EXTINF, and so two lines are consumed.
+        assert!(reader.read_line().unwrap().is_some());
+        assert_eq!(
+            ReaderPosition {
+                line: 4,
+                byte_offset: 30
+            },
+            reader.position()
+        );
+        // When stopped, the position remains at the errored line until resumed.
+        assert!(reader.read_line().is_err());
+        assert_eq!(4, reader.position().line);
+        reader.resume();
+        assert_eq!(
+            ReaderPosition {
+                line: 5,
+                byte_offset: 35
+            },
+            reader.position()
+        );
+        assert_eq!(
+            Some(HlsLine::Uri("0.ts".into())),
+            reader.read_line().unwrap()
+        );
+        assert_eq!(input.len(), reader.position().byte_offset);
+        assert_eq!(5, reader.position().line);
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn metrics_should_count_lines_provided() {
+        let input = "#EXTM3U\n#EXTINF:4\n0.ts\n\n#comment\n#EXT-X-VERSION:x\n#EXTINF:4\n1.ts\n";
+        let options = ParsingOptionsBuilder::new().with_parsing_for_inf().build();
+        let mut reader = Reader::from_str(input, options.clone());
+        while let Ok(Some(_)) = reader.read_line() {}
+        assert_eq!(None, reader.metrics());
+
+        let mut reader = Reader::from_str(input, options.clone()).with_metrics();
+        while let Ok(Some(_)) = reader.read_line() {}
+        let metrics = reader.metrics().unwrap();
+        assert_eq!(2, metrics.hls_tag_count(hls::TagName::Inf));
+        assert_eq!(0, metrics.hls_tag_count(hls::TagName::M3u));
+        assert_eq!(
+            vec![(hls::TagName::Inf, 2)],
+            metrics.hls_tag_counts().collect::<Vec<_>>()
+        );
+        assert_eq!(2, metrics.unknown_tags());
+        assert_eq!(2, metrics.uris());
+        assert_eq!(1, metrics.blanks());
+        assert_eq!(1, metrics.comments());
+        assert_eq!(0, metrics.errors());
+        assert_eq!(8, metrics.total());
+        // The EXT-X-VERSION is invalid, but it was not selected for parsing.
+        assert_eq!(0, metrics.downgraded_tags());
+        let expected = metrics.clone();
+
+        let inner = std::io::BufReader::new(input.as_bytes());
+        let mut reader = Reader::from_reader(inner, options).with_metrics();
+        while let Ok(Some(_)) = reader.read_line() {}
+        assert_eq!(Some(&expected), reader.metrics());
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn version_inference_should_report_conflict_with_declared_version() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"k\",IV=0x0123456789ABCDEF0123456789ABCDEF\n",
+            "#EXTINF:4\n",
+            "0.ts\n",
+            "#EXT-X-VERSION:3\n",
+            "#EXT-X-I-FRAMES-ONLY\n",
+        );
+        let mut reader = Reader::from_str(input, ParsingOptions::default());
+        while let Ok(Some(_)) = reader.read_line() {}
+        assert_eq!(None, reader.inferred_version());
+
+        let mut reader =
+            Reader::from_str(input, ParsingOptions::default()).with_version_inference();
+        reader.read_line().unwrap();
+        assert_eq!(Some(1), reader.inferred_version());
+        for _ in 0..5 {
+            reader.read_line().unwrap();
+        }
+        let inference = reader.version_inference().unwrap();
+        assert_eq!(Some(2), reader.inferred_version());
+        assert_eq!(Some(3), inference.declared_version());
+        assert!(!inference.is_conflicting());
+        reader.read_line().unwrap();
+        let inference = reader.version_inference().unwrap().clone();
+        assert_eq!(4, inference.inferred_version());
+        assert!(inference.is_conflicting());
+        assert_eq!(
+            vec![(7, VersionedFeature::IFramesOnly)],
+            inference
+                .diagnostics()
+                .into_iter()
+                .map(|diagnostic| match diagnostic.violation {
+                    Violation::VersionTooLow { feature, .. } => (diagnostic.line_number, feature),
+                    violation => panic!("unexpected {violation:?}"),
+                })
+                .collect::<Vec<_>>()
+        );
+
+        let inner = std::io::BufReader::new(input.as_bytes());
+        let mut reader =
+            Reader::from_reader(inner, ParsingOptions::default()).with_version_inference();
+        while let Ok(Some(_)) = reader.read_line() {}
+        assert_eq!(Some(&inference), reader.version_inference());
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn metrics_should_count_tags_downgraded_to_unknown() {
+        let input = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:x\n",
+            "#EXT-X-EXAMPLE-TAG:MEANING-OF-LIFE=42\n",
+            "#EXT-X-EXAMPLE-TAG:MEANING-OF-LIFE=42,QUESTION=\"UNKNOWN\"\n",
+            "#EXT-X-DATERANGE:CLASS=\"a\"\n",
+            "#EXT-X-DATERANGE:START-DATE=\"2025-01-01T00:00:00Z\"\n",
+            "#EXT-X-VENDOR\n",
+        );
+        let mut reader = Reader::with_custom_from_str(
+            input,
+            ParsingOptions::default(),
+            PhantomData::<ExampleTag>,
+        )
+        .with_metrics();
+        while let Ok(Some(_)) = reader.read_line() {}
+        let metrics = reader.metrics().unwrap();
+        assert_eq!(2, metrics.downgraded_hls_tag_count(hls::TagName::Daterange));
+        assert_eq!(1, metrics.downgraded_hls_tag_count(hls::TagName::Version));
+        assert_eq!(0, metrics.downgraded_hls_tag_count(hls::TagName::M3u));
+        let mut counts = metrics.downgraded_hls_tag_counts().collect::<Vec<_>>();
+        counts.sort_by_key(|(_, count)| *count);
+        assert_eq!(
+            vec![(hls::TagName::Version, 1), (hls::TagName::Daterange, 2)],
+            counts
+        );
+        assert_eq!(1, metrics.downgraded_custom_tags());
+        assert_eq!(4, metrics.downgraded_tags());
+        assert_eq!(5, metrics.unknown_tags());
+        assert_eq!(1, metrics.custom_tags());
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn metrics_should_count_errors() {
+        let mut reader =
+            Reader::from_str("#EXTM3U\n#EXT\n", ParsingOptions::default()).with_metrics();
+        let (lines, error) = reader.read_until_error();
+        assert_eq!(1, lines.len());
+        assert!(error.is_some());
+        assert_eq!(None, reader.read_line().unwrap());
+        let metrics = reader.metrics().unwrap();
+        assert_eq!(1, metrics.hls_tag_count(hls::TagName::M3u));
+        assert_eq!(1, metrics.errors());
+        assert_eq!(2, metrics.total());
+    }
+
     // Example custom tag implementation for the tests above.
+    #[cfg(feature = "hls-tags")]
     #[derive(Debug, PartialEq, Clone)]
     struct ExampleTag<'a> {
         answer: u64,
         question: &'a str,
     }
+    #[cfg(feature = "hls-tags")]
     impl ExampleTag<'static> {
         fn new(answer: u64, question: &'static str) -> Self {
             Self { answer, question }
         }
     }
+    #[cfg(feature = "hls-tags")]
     impl<'a> TryFrom<UnknownTag<'a>> for ExampleTag<'a> {
         type Error = ValidationError;
         fn try_from(tag: UnknownTag<'a>) -> Result<Self, Self::Error> {
@@ -611,17 +1519,179 @@ mod tests {
             Ok(Self { answer, question })
         }
     }
+    #[cfg(feature = "hls-tags")]
     impl<'a> CustomTag<'a> for ExampleTag<'a> {
         fn is_known_name(name: &str) -> bool {
             name == "-X-EXAMPLE-TAG"
         }
     }
-}
 
-#[cfg(test)]
-// Example taken from HLS specification with one custom tag added.
-// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-9.1
-const EXAMPLE_MANIFEST: &str = r#"#EXTM3U
+    #[test]
+    fn read_line_should_consume_input_on_pathological_data() {
+        const INPUTS: [&str; 8] = [
+            "\r",
+            "\r\r\r\r",
+            "\r\r\n\r",
+            "\0",
+            "\0\0\n\0\r\0",
+            "#EXT\r\r\n#\r",
+            "#EXT-X-KEY:URI=\"\r\0\n\n\n",
+            "\n\n\r\n\r\n",
+        ];
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_all_tags()
+            .with_comment_attachment()
+            .build();
+        for input in INPUTS {
+            let mut str_reader = Reader::from_str(input, options.clone());
+            let mut bytes_reader = Reader::from_bytes(input.as_bytes(), options.clone());
+            let mut buf_reader = Reader::from_reader(input.as_bytes(), options.clone());
+            // Each read consumes at least one byte, and so there cannot be more reads than bytes.
+            for _ in 0..input.len() {
+                let _ = str_reader.read_line();
+                let _ = bytes_reader.read_line();
+                let _ = buf_reader.read_line();
+            }
+            assert_eq!(Ok(None), str_reader.read_line(), "input {input:?}");
+            assert_eq!(Ok(None), bytes_reader.read_line(), "input {input:?}");
+            assert!(
+                matches!(buf_reader.read_line(), Ok(None)),
+                "input {input:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn max_lines_should_stop_reader_until_resumed() {
+        const PLAYLIST: &str = "#EXTM3U\n# comment\n#EXTINF:4\n0.ts\n#EXT-X-ENDLIST\n";
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_all_tags()
+            .with_comment_attachment()
+            .with_max_lines(2)
+            .build();
+        let mut reader = Reader::from_str(PLAYLIST, options.clone());
+        assert_eq!(Ok(Some(HlsLine::from(M3u))), reader.read_line());
+        // The attached comment is read as one line along with the tag.
+        assert!(matches!(reader.read_line(), Ok(Some(HlsLine::KnownTag(_)))));
+        assert_eq!(2, reader.lines_read());
+        assert_eq!(
+            Err(ReaderStrError {
+                errored_line: "0.ts",
+                error: GenericSyntaxError::LineLimitExceeded.into(),
+            }),
+            reader.read_line()
+        );
+        assert!(reader.is_stopped());
+        assert_eq!(Ok(None), reader.read_line());
+        reader.resume();
+        assert_eq!(0, reader.lines_read());
+        assert_eq!(Ok(Some(HlsLine::Uri("0.ts".into()))), reader.read_line());
+        assert_eq!(Ok(Some(HlsLine::from(Endlist))), reader.read_line());
+        assert_eq!(Ok(None), reader.read_line());
+
+        let mut reader = Reader::from_reader(PLAYLIST.as_bytes(), options);
+        assert!(reader.read_line().unwrap().is_some());
+        assert!(reader.read_line().unwrap().is_some());
+        match reader.read_line() {
+            Err(ReaderIoError::Syntax {
+                errored_line,
+                error,
+            }) => {
+                assert_eq!(b"0.ts", errored_line.as_slice());
+                assert_eq!(
+                    SyntaxError::from(GenericSyntaxError::LineLimitExceeded),
+                    error
+                );
+            }
+            r => panic!("unexpected result {r:?}"),
+        }
+        assert!(reader.read_line().unwrap().is_none());
+        reader.resume();
+        assert_eq!(
+            Some(HlsLine::Uri("0.ts".into())),
+            reader.read_line().unwrap()
+        );
+        assert_eq!(Some(HlsLine::from(Endlist)), reader.read_line().unwrap());
+        assert!(reader.read_line().unwrap().is_none());
+    }
+
+    #[test]
+    fn resume_should_skip_errored_line_when_stopped_at_line_limit_by_error() {
+        const PLAYLIST: &[u8] = b"#EXT-X-\xFF\xFE\n0.ts\n";
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_all_tags()
+            .with_error_recovery(ErrorRecovery::Stop)
+            .with_max_lines(1)
+            .build();
+        let mut reader = Reader::from_bytes(PLAYLIST, options.clone());
+        let error = reader
+            .read_line()
+            .expect_err("line should be invalid UTF-8");
+        assert_eq!(b"#EXT-X-\xFF\xFE", error.errored_line);
+        reader.resume();
+        let error = reader.read_line().expect_err("should reach limit");
+        assert_eq!(b"0.ts", error.errored_line);
+        assert_eq!(
+            SyntaxError::from(GenericSyntaxError::LineLimitExceeded),
+            error.error
+        );
+        reader.resume();
+        assert_eq!(Ok(Some(HlsLine::Uri("0.ts".into()))), reader.read_line());
+        assert_eq!(Ok(None), reader.read_line());
+
+        let mut reader = Reader::from_reader(PLAYLIST, options);
+        match reader.read_line() {
+            Err(ReaderIoError::Syntax { errored_line, .. }) => {
+                assert_eq!(b"#EXT-X-\xFF\xFE", errored_line.as_slice())
+            }
+            r => panic!("unexpected result {r:?}"),
+        }
+        reader.resume();
+        match reader.read_line() {
+            Err(ReaderIoError::Syntax { errored_line, .. }) => {
+                assert_eq!(b"0.ts", errored_line.as_slice())
+            }
+            r => panic!("unexpected result {r:?}"),
+        }
+        reader.resume();
+        assert_eq!(
+            Some(HlsLine::Uri("0.ts".into())),
+            reader.read_line().unwrap()
+        );
+        assert!(reader.read_line().unwrap().is_none());
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn max_lines_of_zero_should_be_taken_as_one() {
+        let options = ParsingOptionsBuilder::new()
+            .with_parsing_for_all_tags()
+            .with_max_lines(0)
+            .build();
+        assert_eq!(Some(1), options.max_lines());
+        let mut reader = Reader::from_str("#EXTM3U\n0.ts\n", options);
+        assert_eq!(Ok(Some(HlsLine::from(M3u))), reader.read_line());
+        assert!(reader.read_line().is_err());
+        reader.resume();
+        assert_eq!(Ok(Some(HlsLine::Uri("0.ts".into()))), reader.read_line());
+        assert_eq!(Ok(None), reader.read_line());
+    }
+
+    #[test]
+    fn max_lines_should_not_error_at_end_of_input() {
+        let options = ParsingOptionsBuilder::new().with_max_lines(1).build();
+        let mut reader = Reader::from_bytes(b"#EXTM3U\n", options.clone());
+        assert!(reader.read_line().unwrap().is_some());
+        assert_eq!(Ok(None), reader.read_line());
+        let mut reader = Reader::from_reader(b"#EXTM3U\n" as &[u8], options);
+        assert!(reader.read_line().unwrap().is_some());
+        assert!(reader.read_line().unwrap().is_none());
+    }
+
+    // Example taken from HLS specification with one custom tag added.
+    // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-9.1
+    const EXAMPLE_MANIFEST: &str = r#"#EXTM3U
 #EXT-X-TARGETDURATION:10
 #EXT-X-VERSION:3
 #EXT-X-EXAMPLE-TAG:MEANING-OF-LIFE=42,QUESTION="UNKNOWN"
@@ -633,3 +1703,4 @@ http://media.example.com/second.ts
 http://media.example.com/third.ts
 #EXT-X-ENDLIST
 "#;
+}
@@ -0,0 +1,222 @@
+use crate::{
+    config::ParsingOptions,
+    line::HlsLine,
+    tag::{CustomTag, KnownTag, hls::TagName},
+};
+use std::collections::HashMap;
+
+/// A position within the data provided to a [`crate::Reader`] (see [`crate::Reader::position`]).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ReaderPosition {
+    /// The 1-based line number.
+    pub line: usize,
+    /// The offset in bytes from the start of the data (including any byte order mark).
+    pub byte_offset: usize,
+}
+
+impl Default for ReaderPosition {
+    fn default() -> Self {
+        Self {
+            line: 1,
+            byte_offset: 0,
+        }
+    }
+}
+
+impl ReaderPosition {
+    // Moves the position past the data that has been consumed.
+    pub(crate) fn advance(&mut self, consumed: &[u8]) {
+        self.byte_offset += consumed.len();
+        self.line += memchr::memchr_iter(b'\n', consumed).count();
+    }
+}
+
+/// Counts of the lines provided by a [`crate::Reader`] (see [`crate::Reader::with_metrics`]).
+///
+/// The lines are counted as they are provided, and so a comment that is attached to a tag (see
+/// [`crate::config::ParsingOptionsBuilder::with_comment_attachment`]) is counted as the tag and not
+/// as a comment. Tags that are not parsed as known HLS tags (e.g. because they were not selected in
+/// the [`crate::config::ParsingOptions`], or because they failed validation) are counted as unknown
+/// tags.
+///
+/// Tags that were selected for parsing but failed to convert into the known tag (see
+/// [`crate::tag::UnknownTag::validation_error`]) are also counted as having been downgraded, per
+/// [`TagName`] for HLS tags (see [`Self::downgraded_hls_tag_count`]). For example:
+/// ```
+/// # use quick_m3u8::{Reader, config::ParsingOptions, tag::hls::TagName};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-DATERANGE:ID="a",START-DATE="2025-01-01T00:00:00Z"
+/// #EXT-X-DATERANGE:START-DATE="2025-01-01T00:00:10Z"
+/// #EXT-X-DATERANGE:CLASS="c",START-DATE="2025-01-01T00:00:20Z"
+/// "#;
+///
+/// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default()).with_metrics();
+/// while let Ok(Some(_)) = reader.read_line() {}
+/// let metrics = reader.metrics().expect("metrics should be enabled");
+/// assert_eq!(1, metrics.hls_tag_count(TagName::Daterange));
+/// assert_eq!(2, metrics.downgraded_hls_tag_count(TagName::Daterange));
+/// assert_eq!(2, metrics.unknown_tags());
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ReaderMetrics {
+    hls_tags: HashMap<TagName, usize>,
+    custom_tags: usize,
+    unknown_tags: usize,
+    downgraded_hls_tags: HashMap<TagName, usize>,
+    downgraded_custom_tags: usize,
+    comments: usize,
+    uris: usize,
+    blanks: usize,
+    errors: usize,
+}
+
+impl ReaderMetrics {
+    /// The number of the HLS tag with the given name that were read.
+    pub fn hls_tag_count(&self, name: TagName) -> usize {
+        self.hls_tags.get(&name).copied().unwrap_or_default()
+    }
+
+    /// The counts of each HLS tag that was read (in no particular order).
+    pub fn hls_tag_counts(&self) -> impl Iterator<Item = (TagName, usize)> + '_ {
+        self.hls_tags.iter().map(|(name, count)| (*name, *count))
+    }
+
+    /// The number of custom tags that were read.
+    pub fn custom_tags(&self) -> usize {
+        self.custom_tags
+    }
+
+    /// The number of unknown tags that were read.
+    pub fn unknown_tags(&self) -> usize {
+        self.unknown_tags
+    }
+
+    /// The number of the HLS tag with the given name that failed to convert into the known tag, and
+    /// so were read as unknown tags.
+    pub fn downgraded_hls_tag_count(&self, name: TagName) -> usize {
+        self.downgraded_hls_tags
+            .get(&name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The counts of each HLS tag that failed to convert into the known tag (in no particular
+    /// order). Tags that never failed are not included.
+    pub fn downgraded_hls_tag_counts(&self) -> impl Iterator<Item = (TagName, usize)> + '_ {
+        self.downgraded_hls_tags
+            .iter()
+            .map(|(name, count)| (*name, *count))
+    }
+
+    /// The number of custom tags that failed to convert into the custom tag, and so were read as
+    /// unknown tags.
+    pub fn downgraded_custom_tags(&self) -> usize {
+        self.downgraded_custom_tags
+    }
+
+    /// The total number of tags (HLS and custom) that were read as unknown tags because they failed
+    /// to convert into the known tag. These are included in [`Self::unknown_tags`].
+    pub fn downgraded_tags(&self) -> usize {
+        self.downgraded_hls_tags.values().sum::<usize>() + self.downgraded_custom_tags
+    }
+
+    /// The number of comments that were read.
+    pub fn comments(&self) -> usize {
+        self.comments
+    }
+
+    /// The number of URIs that were read.
+    pub fn uris(&self) -> usize {
+        self.uris
+    }
+
+    /// The number of blank lines (including lines of only whitespace) that were read.
+    pub fn blanks(&self) -> usize {
+        self.blanks
+    }
+
+    /// The number of errors that were provided.
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    /// The total number of lines (and errors) that were provided.
+    pub fn total(&self) -> usize {
+        self.hls_tags.values().sum::<usize>()
+            + self.custom_tags
+            + self.unknown_tags
+            + self.comments
+            + self.uris
+            + self.blanks
+            + self.errors
+    }
+
+    pub(crate) fn record<'a, Custom, E>(
+        &mut self,
+        result: &Result<Option<HlsLine<'a, Custom>>, E>,
+        options: &ParsingOptions,
+    ) where
+        Custom: CustomTag<'a>,
+    {
+        match result {
+            #[cfg(feature = "hls-tags")]
+            Ok(Some(HlsLine::KnownTag(KnownTag::Hls(tag)))) => {
+                *self.hls_tags.entry(tag.name()).or_default() += 1
+            }
+            Ok(Some(HlsLine::KnownTag(KnownTag::Custom(_)))) => self.custom_tags += 1,
+            Ok(Some(HlsLine::UnknownTag(tag))) => {
+                self.unknown_tags += 1;
+                if tag.validation_error().is_some() {
+                    // The name is matched as it was when the line was parsed.
+                    let name = if options.case_insensitive_tag_names() {
+                        TagName::try_from_ignore_ascii_case(tag.name())
+                    } else {
+                        TagName::try_from(tag.name())
+                    };
+                    match name {
+                        Ok(name) => *self.downgraded_hls_tags.entry(name).or_default() += 1,
+                        Err(_) => self.downgraded_custom_tags += 1,
+                    }
+                }
+            }
+            Ok(Some(HlsLine::Comment(_))) => self.comments += 1,
+            Ok(Some(HlsLine::Uri(_))) => self.uris += 1,
+            Ok(Some(HlsLine::Blank | HlsLine::Whitespace(_))) => self.blanks += 1,
+            Ok(None) => (),
+            Err(_) => self.errors += 1,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "hls-tags"))]
+mod tests {
+    use super::*;
+    use crate::{config::ParsingOptionsBuilder, error::ValidationError, tag::UnknownTag};
+    use pretty_assertions::assert_eq;
+
+    fn downgraded(name: &str) -> Result<Option<HlsLine<'_>>, ()> {
+        Ok(Some(HlsLine::UnknownTag(UnknownTag {
+            name,
+            value: None,
+            original_input: b"",
+            validation_error: Some(ValidationError::NotImplemented),
+            replacement: None,
+        })))
+    }
+
+    #[test]
+    fn downgraded_tag_names_should_be_matched_as_configured() {
+        let case_insensitive = ParsingOptionsBuilder::new()
+            .with_case_insensitive_tag_names()
+            .build();
+        let mut metrics = ReaderMetrics::default();
+        metrics.record(&downgraded("-x-version"), &case_insensitive);
+        assert_eq!(1, metrics.downgraded_hls_tag_count(TagName::Version));
+        assert_eq!(0, metrics.downgraded_custom_tags());
+
+        let mut metrics = ReaderMetrics::default();
+        metrics.record(&downgraded("-x-version"), &ParsingOptions::default());
+        assert_eq!(0, metrics.downgraded_hls_tag_count(TagName::Version));
+        assert_eq!(1, metrics.downgraded_custom_tags());
+    }
+}
@@ -0,0 +1,385 @@
+//! Ready-made transformations for common playlist manipulations.
+//!
+//! Each recipe reads a playlist line by line from a [`BufRead`] and writes the result to a
+//! [`Write`], without ever holding the whole playlist in memory. The recipes are implemented as
+//! small [`HlsLineVisitor`]s driven by a [`Reader`] and [`crate::Writer`], and so, as well as being
+//! useful as they are, the source of this module is intended as a set of worked examples of the
+//! event-driven style that the library is built around. A recipe that does not quite fit a use case
+//! should be straightforward to copy and adapt.
+//!
+//! For example, removing the ad markers of a vendor from a Media Playlist:
+//! ```
+//! # use quick_m3u8::recipes;
+//! const PLAYLIST: &str = r#"#EXTM3U
+//! #EXT-X-TARGETDURATION:4
+//! #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z
+//! #EXT-X-DATERANGE:ID="1",CLASS="com.example.ad",START-DATE="2025-01-01T00:00:00.000Z"
+//! #EXT-X-DATERANGE:ID="2",CLASS="com.example.chapter",START-DATE="2025-01-01T00:00:00.000Z"
+//! #EXTINF:4
+//! 0.ts
+//! "#;
+//!
+//! let mut output = Vec::new();
+//! recipes::strip_dateranges_by_class(PLAYLIST.as_bytes(), &mut output, "com.example.ad")?;
+//! assert_eq!(
+//!     r#"#EXTM3U
+//! #EXT-X-TARGETDURATION:4
+//! #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z
+//! #EXT-X-DATERANGE:ID="2",CLASS="com.example.chapter",START-DATE="2025-01-01T00:00:00.000Z"
+//! #EXTINF:4
+//! 0.ts
+//! "#,
+//!     String::from_utf8_lossy(&output)
+//! );
+//! # Ok::<(), quick_m3u8::error::ReaderIoError>(())
+//! ```
+
+use crate::{
+    HlsLine, HlsLineVisitor, Reader, Writer,
+    config::ParsingOptions,
+    error::ReaderIoError,
+    tag::hls::{Daterange, Endlist, IFrameStreamInf, PreloadHint, ProgramDateTime, StreamInf},
+};
+use std::{
+    borrow::Cow,
+    io::{BufRead, Write},
+};
+
+/// Removes the variants of a Multivariant Playlist with a `RESOLUTION` taller than `max_height`
+/// (e.g. `1080` to cap the ladder at 1080p).
+///
+/// Both `EXT-X-STREAM-INF` (along with the URI line that follows it) and `EXT-X-I-FRAME-STREAM-INF`
+/// tags are removed. Variants without a `RESOLUTION` are kept.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::recipes;
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-STREAM-INF:BANDWIDTH=2000000,RESOLUTION=1280x720
+/// 720p.m3u8
+/// #EXT-X-STREAM-INF:BANDWIDTH=5000000,RESOLUTION=1920x1080
+/// 1080p.m3u8
+/// #EXT-X-STREAM-INF:BANDWIDTH=12000000,RESOLUTION=3840x2160
+/// 2160p.m3u8
+/// "#;
+///
+/// let mut output = Vec::new();
+/// recipes::cap_ladder(PLAYLIST.as_bytes(), &mut output, 1080)?;
+/// assert_eq!(
+///     r#"#EXTM3U
+/// #EXT-X-STREAM-INF:BANDWIDTH=2000000,RESOLUTION=1280x720
+/// 720p.m3u8
+/// #EXT-X-STREAM-INF:BANDWIDTH=5000000,RESOLUTION=1920x1080
+/// 1080p.m3u8
+/// "#,
+///     String::from_utf8_lossy(&output)
+/// );
+/// # Ok::<(), quick_m3u8::error::ReaderIoError>(())
+/// ```
+pub fn cap_ladder(
+    input: impl BufRead,
+    output: impl Write,
+    max_height: u64,
+) -> Result<(), ReaderIoError> {
+    let is_too_tall = |height: u64| height > max_height;
+    let mut filter = VariantFilter {
+        should_remove: |tag: &StreamInf| tag.resolution().is_some_and(|r| is_too_tall(r.height)),
+        should_remove_i_frames: |tag: &IFrameStreamInf| {
+            tag.resolution().is_some_and(|r| is_too_tall(r.height))
+        },
+        removing_uri: false,
+    };
+    rewrite(input, output, &mut filter)?;
+    Ok(())
+}
+
+/// Removes the audio-only variants of a Multivariant Playlist.
+///
+/// A variant is considered to be audio-only when its `CODECS` only contain audio codecs (see
+/// [`crate::tag::hls::CodecKind`]). Variants without `CODECS` are kept, as there is no reliable way
+/// to tell what they contain.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::recipes;
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-STREAM-INF:BANDWIDTH=64000,CODECS="mp4a.40.2"
+/// audio.m3u8
+/// #EXT-X-STREAM-INF:BANDWIDTH=2000000,CODECS="avc1.64001f,mp4a.40.2"
+/// video.m3u8
+/// "#;
+///
+/// let mut output = Vec::new();
+/// recipes::remove_audio_only_variants(PLAYLIST.as_bytes(), &mut output)?;
+/// assert_eq!(
+///     r#"#EXTM3U
+/// #EXT-X-STREAM-INF:BANDWIDTH=2000000,CODECS="avc1.64001f,mp4a.40.2"
+/// video.m3u8
+/// "#,
+///     String::from_utf8_lossy(&output)
+/// );
+/// # Ok::<(), quick_m3u8::error::ReaderIoError>(())
+/// ```
+pub fn remove_audio_only_variants(
+    input: impl BufRead,
+    output: impl Write,
+) -> Result<(), ReaderIoError> {
+    let mut filter = VariantFilter {
+        should_remove: |tag: &StreamInf| {
+            tag.codec_list()
+                .is_some_and(|codecs| codecs.has_audio() && !codecs.has_video())
+        },
+        should_remove_i_frames: |_: &IFrameStreamInf| false,
+        removing_uri: false,
+    };
+    rewrite(input, output, &mut filter)?;
+    Ok(())
+}
+
+/// Ensures that a Media Playlist ends with `EXT-X-ENDLIST`, such that it is treated as complete
+/// (e.g. to close out a live recording as VOD).
+///
+/// The tag is added after the last line unless the playlist already has it. Any
+/// `EXT-X-PRELOAD-HINT` tags are removed, as there are no more segments to be loaded.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::recipes;
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXTINF:4
+/// 0.ts
+/// #EXT-X-PRELOAD-HINT:TYPE=PART,URI="1.0.mp4"
+/// "#;
+///
+/// let mut output = Vec::new();
+/// recipes::force_endlist(PLAYLIST.as_bytes(), &mut output)?;
+/// assert_eq!(
+///     r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXTINF:4
+/// 0.ts
+/// #EXT-X-ENDLIST
+/// "#,
+///     String::from_utf8_lossy(&output)
+/// );
+/// # Ok::<(), quick_m3u8::error::ReaderIoError>(())
+/// ```
+pub fn force_endlist(input: impl BufRead, output: impl Write) -> Result<(), ReaderIoError> {
+    let mut visitor = EndlistEnforcer { has_endlist: false };
+    let mut writer = rewrite(input, output, &mut visitor)?;
+    if !visitor.has_endlist {
+        writer.write_line(HlsLine::from(Endlist))?;
+    }
+    Ok(())
+}
+
+/// Moves every `EXT-X-PROGRAM-DATE-TIME` of a Media Playlist by the number of seconds (which may be
+/// negative, and may have a fractional part).
+///
+/// Only `EXT-X-PROGRAM-DATE-TIME` is changed; in particular, the dates of `EXT-X-DATERANGE` tags are
+/// left as they are (see [`crate::tag::hls::Daterange::set_start_date`] for adapting this recipe).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::recipes;
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00.000Z
+/// #EXTINF:4
+/// 0.ts
+/// "#;
+///
+/// let mut output = Vec::new();
+/// recipes::shift_program_date_time(PLAYLIST.as_bytes(), &mut output, -1.5)?;
+/// assert_eq!(
+///     r#"#EXTM3U
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-PROGRAM-DATE-TIME:2024-12-31T23:59:58.500Z
+/// #EXTINF:4
+/// 0.ts
+/// "#,
+///     String::from_utf8_lossy(&output)
+/// );
+/// # Ok::<(), quick_m3u8::error::ReaderIoError>(())
+/// ```
+pub fn shift_program_date_time(
+    input: impl BufRead,
+    output: impl Write,
+    seconds: f64,
+) -> Result<(), ReaderIoError> {
+    rewrite(input, output, &mut ProgramDateTimeShift { seconds })?;
+    Ok(())
+}
+
+/// Removes the `EXT-X-DATERANGE` tags of a Media Playlist that have the given `CLASS`.
+///
+/// See the [module documentation](self) for an example.
+pub fn strip_dateranges_by_class(
+    input: impl BufRead,
+    output: impl Write,
+    class: &str,
+) -> Result<(), ReaderIoError> {
+    rewrite(input, output, &mut DaterangeClassFilter { class })?;
+    Ok(())
+}
+
+// Drives the visitor over each line of the input, writing the lines that it keeps to the output.
+// The writer is returned so that recipes can add lines at the end.
+fn rewrite<W, V>(
+    input: impl BufRead,
+    output: W,
+    visitor: &mut V,
+) -> Result<Writer<W>, ReaderIoError>
+where
+    W: Write,
+    V: for<'a> HlsLineVisitor<'a>,
+{
+    let mut reader = Reader::from_reader(input, ParsingOptions::default());
+    let mut writer = Writer::new(output);
+    while let Some(line) = reader.read_line()? {
+        if let Some(line) = visitor.visit_line(line) {
+            writer.write_line(line)?;
+        }
+    }
+    writer.flush()?;
+    Ok(writer)
+}
+
+// Removes the variants that match the predicates, along with the URI of each removed
+// EXT-X-STREAM-INF.
+struct VariantFilter<F, G> {
+    should_remove: F,
+    should_remove_i_frames: G,
+    removing_uri: bool,
+}
+
+impl<'a, F, G> HlsLineVisitor<'a> for VariantFilter<F, G>
+where
+    F: Fn(&StreamInf) -> bool,
+    G: Fn(&IFrameStreamInf) -> bool,
+{
+    fn visit_stream_inf(&mut self, tag: StreamInf<'a>) -> Option<HlsLine<'a>> {
+        if (self.should_remove)(&tag) {
+            self.removing_uri = true;
+            None
+        } else {
+            Some(HlsLine::from(tag))
+        }
+    }
+
+    fn visit_i_frame_stream_inf(&mut self, tag: IFrameStreamInf<'a>) -> Option<HlsLine<'a>> {
+        if (self.should_remove_i_frames)(&tag) {
+            None
+        } else {
+            Some(HlsLine::from(tag))
+        }
+    }
+
+    fn visit_uri(&mut self, uri: Cow<'a, str>) -> Option<HlsLine<'a>> {
+        if std::mem::take(&mut self.removing_uri) {
+            None
+        } else {
+            Some(HlsLine::Uri(uri))
+        }
+    }
+}
+
+struct EndlistEnforcer {
+    has_endlist: bool,
+}
+
+impl<'a> HlsLineVisitor<'a> for EndlistEnforcer {
+    fn visit_endlist(&mut self, tag: Endlist) -> Option<HlsLine<'a>> {
+        self.has_endlist = true;
+        Some(HlsLine::from(tag))
+    }
+
+    fn visit_preload_hint(&mut self, _: PreloadHint<'a>) -> Option<HlsLine<'a>> {
+        None
+    }
+}
+
+struct ProgramDateTimeShift {
+    seconds: f64,
+}
+
+impl<'a> HlsLineVisitor<'a> for ProgramDateTimeShift {
+    fn visit_program_date_time(&mut self, mut tag: ProgramDateTime<'a>) -> Option<HlsLine<'a>> {
+        let shifted = tag.program_date_time().add_seconds(self.seconds);
+        tag.set_program_date_time(shifted);
+        Some(HlsLine::from(tag))
+    }
+}
+
+struct DaterangeClassFilter<'c> {
+    class: &'c str,
+}
+
+impl<'a> HlsLineVisitor<'a> for DaterangeClassFilter<'_> {
+    fn visit_daterange(&mut self, tag: Daterange<'a>) -> Option<HlsLine<'a>> {
+        if tag.class() == Some(self.class) {
+            None
+        } else {
+            Some(HlsLine::from(tag))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn run(
+        recipe: impl FnOnce(&[u8], &mut Vec<u8>) -> Result<(), ReaderIoError>,
+        playlist: &str,
+    ) -> String {
+        let mut output = Vec::new();
+        recipe(playlist.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn cap_ladder_should_remove_tall_i_frame_variants_and_keep_unknown_resolutions() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000000\n",
+            "unknown.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=12000000,RESOLUTION=3840x2160\n",
+            "2160p.m3u8\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100000,RESOLUTION=1920x1080,URI=\"i1080.m3u8\"\n",
+            "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=400000,RESOLUTION=3840x2160,URI=\"i2160.m3u8\"\n",
+        );
+        assert_eq!(
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=1000000\n",
+                "unknown.m3u8\n",
+                "#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=100000,RESOLUTION=1920x1080,URI=\"i1080.m3u8\"\n",
+            ),
+            run(|i, o| cap_ladder(i, o, 1080), PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn force_endlist_should_not_duplicate_existing_endlist() {
+        const PLAYLIST: &str = "#EXTM3U\n#EXTINF:4\n0.ts\n#EXT-X-ENDLIST\n";
+        assert_eq!(PLAYLIST, run(|i, o| force_endlist(i, o), PLAYLIST));
+    }
+
+    #[test]
+    fn remove_audio_only_variants_should_keep_variants_without_codecs() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=64000\n",
+            "unknown.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=64000,CODECS=\"ec-3\"\n",
+            "atmos.m3u8\n",
+        );
+        assert_eq!(
+            "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=64000\nunknown.m3u8\n",
+            run(|i, o| remove_audio_only_variants(i, o), PLAYLIST)
+        );
+    }
+}
@@ -0,0 +1,862 @@
+//! Parsing of SCTE-35 payloads (available with the `scte35` feature).
+//!
+//! The `SCTE35-CMD`, `SCTE35-OUT`, and `SCTE35-IN` attributes of `EXT-X-DATERANGE` carry a
+//! [SCTE-35] `splice_info_section` as a hexadecimal sequence. The [`crate::tag::hls::Daterange`]
+//! exposes these as raw strings, and this module parses them into a typed [`SpliceInfoSection`]
+//! (see, for example, [`crate::tag::hls::Daterange::scte35_out_parsed`]).
+//!
+//! The splice commands that are parsed are `splice_null`, `splice_insert`, `time_signal`, and
+//! `bandwidth_reservation` (along with `private_command`, for which the data is provided as is).
+//! The `segmentation_descriptor` is parsed, and all other descriptors are provided as raw data.
+//! Encrypted sections cannot be parsed (see [`Scte35Error::EncryptedPacket`]).
+//!
+//! Times and durations are in 90 kHz ticks, as they are in the section, and can be converted to
+//! seconds with [`ticks_to_seconds`].
+//!
+//! For example:
+//! ```
+//! # use quick_m3u8::scte35::{self, SegmentationType, SpliceCommand, SpliceDescriptor};
+//! let section = scte35::parse_hex(concat!(
+//!     "0xFC302F000000000000FFFFF00506FE72BD0050001A0218435545494800008E7F9F0808000000002CA0A18A",
+//!     "350200A9CC6758",
+//! ))?;
+//! let SpliceCommand::TimeSignal(splice_time) = section.splice_command else {
+//!     panic!("expected time_signal");
+//! };
+//! assert_eq!(Some(0x72BD0050), splice_time.pts_time);
+//! let SpliceDescriptor::Segmentation(descriptor) = &section.descriptors[0] else {
+//!     panic!("expected segmentation_descriptor");
+//! };
+//! assert_eq!(0x4800008E, descriptor.segmentation_event_id);
+//! assert_eq!(
+//!     SegmentationType::ProviderPlacementOpportunityEnd,
+//!     descriptor.segmentation_type()
+//! );
+//! # Ok::<(), quick_m3u8::error::Scte35Error>(())
+//! ```
+//!
+//! [SCTE-35]: https://www.scte.org/standards/library/catalog/scte-35-digital-program-insertion-cueing-message/
+
+use crate::error::Scte35Error;
+
+const TABLE_ID: u8 = 0xFC;
+const SEGMENTATION_DESCRIPTOR_TAG: u8 = 0x02;
+
+/// Parses the `splice_info_section` from a hexadecimal sequence (as found in the `SCTE35-*`
+/// attributes of `EXT-X-DATERANGE`). The `0x` (or `0X`) prefix is optional.
+pub fn parse_hex(hex: &str) -> Result<SpliceInfoSection, Scte35Error> {
+    let hex = hex
+        .strip_prefix("0x")
+        .or_else(|| hex.strip_prefix("0X"))
+        .unwrap_or(hex)
+        .as_bytes();
+    if !hex.len().is_multiple_of(2) {
+        return Err(Scte35Error::InvalidHex);
+    }
+    let bytes = hex
+        .chunks_exact(2)
+        .map(|pair| {
+            let high = char::from(pair[0]).to_digit(16)?;
+            let low = char::from(pair[1]).to_digit(16)?;
+            u8::try_from(high << 4 | low).ok()
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Scte35Error::InvalidHex)?;
+    parse(&bytes)
+}
+
+/// Parses the `splice_info_section` from its bytes.
+pub fn parse(bytes: &[u8]) -> Result<SpliceInfoSection, Scte35Error> {
+    let mut reader = BitReader::new(bytes);
+    let table_id = reader.read_u8()?;
+    if table_id != TABLE_ID {
+        return Err(Scte35Error::UnexpectedTableId(table_id));
+    }
+    reader.skip(2)?; // section_syntax_indicator, private_indicator
+    let sap_type = reader.read(2)? as u8;
+    let section_length = reader.read(12)? as usize;
+    // The section is limited to its declared length (where the input is long enough), such that
+    // any trailing bytes are ignored.
+    let section = bytes
+        .get(..3 + section_length)
+        .ok_or(Scte35Error::UnexpectedEnd)?;
+    let mut reader = BitReader::new(section);
+    reader.skip(24)?;
+    let protocol_version = reader.read_u8()?;
+    if reader.read_flag()? {
+        return Err(Scte35Error::EncryptedPacket);
+    }
+    reader.skip(6)?; // encryption_algorithm
+    let pts_adjustment = reader.read(33)?;
+    let cw_index = reader.read_u8()?;
+    let tier = reader.read(12)? as u16;
+    let splice_command_length = reader.read(12)? as usize;
+    let splice_command_type = reader.read_u8()?;
+    // A splice_command_length of 0xFFF is used by legacy encoders to indicate that the length is
+    // unknown, in which case the command is parsed from the remainder of the section.
+    let splice_command = if splice_command_length == 0xFFF {
+        let remaining = reader.remaining();
+        let mut command_reader = BitReader::new(remaining);
+        let command = parse_splice_command(splice_command_type, &mut command_reader, None)?;
+        reader.skip(command_reader.position())?;
+        command
+    } else {
+        let command_bytes = reader.take(splice_command_length)?;
+        let mut command_reader = BitReader::new(command_bytes);
+        parse_splice_command(
+            splice_command_type,
+            &mut command_reader,
+            Some(command_bytes),
+        )?
+    };
+    let descriptor_loop_length = reader.read(16)? as usize;
+    let mut descriptor_reader = BitReader::new(reader.take(descriptor_loop_length)?);
+    let mut descriptors = Vec::new();
+    while !descriptor_reader.remaining().is_empty() {
+        descriptors.push(parse_splice_descriptor(&mut descriptor_reader)?);
+    }
+    let crc_offset = section
+        .len()
+        .checked_sub(4)
+        .ok_or(Scte35Error::UnexpectedEnd)?;
+    let mut crc_reader = BitReader::new(&section[crc_offset..]);
+    let crc_32 = crc_reader.read(32)? as u32;
+    Ok(SpliceInfoSection {
+        sap_type,
+        protocol_version,
+        pts_adjustment,
+        cw_index,
+        tier,
+        splice_command,
+        descriptors,
+        crc_32,
+        crc_32_valid: crc_32_mpeg_2(&section[..crc_offset]) == crc_32,
+    })
+}
+
+/// Converts a time or duration in 90 kHz ticks to seconds.
+pub fn ticks_to_seconds(ticks: u64) -> f64 {
+    ticks as f64 / 90_000.0
+}
+
+/// A SCTE-35 `splice_info_section`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpliceInfoSection {
+    /// The `SAP_type` (`3` indicates that the type is not specified).
+    pub sap_type: u8,
+    /// The `protocol_version`.
+    pub protocol_version: u8,
+    /// The `pts_adjustment` (in 90 kHz ticks), which is to be added to the times in the command and
+    /// descriptors.
+    pub pts_adjustment: u64,
+    /// The `cw_index`.
+    pub cw_index: u8,
+    /// The `tier` (`0xFFF` indicates that the tier is not used).
+    pub tier: u16,
+    /// The splice command.
+    pub splice_command: SpliceCommand,
+    /// The splice descriptors.
+    pub descriptors: Vec<SpliceDescriptor>,
+    /// The `CRC_32` of the section.
+    pub crc_32: u32,
+    /// Whether the `CRC_32` matches the CRC computed over the section. The section is still parsed
+    /// when it does not (as some packagers are known to get this wrong), and so it is left to the
+    /// user to decide whether to trust the section in that case.
+    pub crc_32_valid: bool,
+}
+
+impl SpliceInfoSection {
+    /// The segmentation descriptors of the section.
+    pub fn segmentation_descriptors(&self) -> impl Iterator<Item = &SegmentationDescriptor> {
+        self.descriptors
+            .iter()
+            .filter_map(|descriptor| match descriptor {
+                SpliceDescriptor::Segmentation(descriptor) => Some(descriptor),
+                SpliceDescriptor::Other { .. } => None,
+            })
+    }
+}
+
+/// A SCTE-35 splice command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpliceCommand {
+    /// `splice_null` (`0x00`).
+    SpliceNull,
+    /// `splice_insert` (`0x05`).
+    SpliceInsert(SpliceInsert),
+    /// `time_signal` (`0x06`).
+    TimeSignal(SpliceTime),
+    /// `bandwidth_reservation` (`0x07`).
+    BandwidthReservation,
+    /// `private_command` (`0xFF`).
+    PrivateCommand {
+        /// The `identifier` (registered with SMPTE).
+        identifier: u32,
+        /// The private bytes of the command.
+        data: Vec<u8>,
+    },
+    /// A command that is not parsed by the library (such as `splice_schedule`).
+    Other {
+        /// The `splice_command_type`.
+        command_type: u8,
+        /// The bytes of the command. This is empty when the section does not declare the length of
+        /// the command (as is the case for some legacy encoders).
+        data: Vec<u8>,
+    },
+}
+
+/// The `splice_time()` structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpliceTime {
+    /// The `pts_time` (in 90 kHz ticks), which is `None` when the time is not specified.
+    pub pts_time: Option<u64>,
+}
+
+/// The `splice_insert()` command.
+///
+/// When [`Self::splice_event_cancel_indicator`] is set, the section carries no more than the ID of
+/// the event that is cancelled, and so all other values are their defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SpliceInsert {
+    /// The `splice_event_id`.
+    pub splice_event_id: u32,
+    /// The `splice_event_cancel_indicator`.
+    pub splice_event_cancel_indicator: bool,
+    /// The `out_of_network_indicator` (set when leaving the network feed, such as at the start of
+    /// an ad break).
+    pub out_of_network_indicator: bool,
+    /// The `program_splice_flag` (set when all components are spliced at the same time).
+    pub program_splice_flag: bool,
+    /// The `splice_immediate_flag`.
+    pub splice_immediate_flag: bool,
+    /// The `event_id_compliance_flag`.
+    pub event_id_compliance_flag: bool,
+    /// The time of the splice, when it is a program splice that is not immediate.
+    pub splice_time: Option<SpliceTime>,
+    /// The components, when it is not a program splice.
+    pub components: Vec<SpliceInsertComponent>,
+    /// The `break_duration()`.
+    pub break_duration: Option<BreakDuration>,
+    /// The `unique_program_id`.
+    pub unique_program_id: u16,
+    /// The `avail_num`.
+    pub avail_num: u8,
+    /// The `avails_expected`.
+    pub avails_expected: u8,
+}
+
+/// A component of a [`SpliceInsert`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpliceInsertComponent {
+    /// The `component_tag`.
+    pub component_tag: u8,
+    /// The time of the splice, when the splice is not immediate.
+    pub splice_time: Option<SpliceTime>,
+}
+
+/// The `break_duration()` structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakDuration {
+    /// The `auto_return` flag (set when the splicer is expected to return to the network at the end
+    /// of the break).
+    pub auto_return: bool,
+    /// The `duration` (in 90 kHz ticks).
+    pub duration: u64,
+}
+
+/// A SCTE-35 splice descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpliceDescriptor {
+    /// The `segmentation_descriptor()` (`0x02`).
+    Segmentation(SegmentationDescriptor),
+    /// A descriptor that is not parsed by the library.
+    Other {
+        /// The `splice_descriptor_tag`.
+        tag: u8,
+        /// The `identifier` (`0x43554549`, or "CUEI", for descriptors defined by SCTE-35).
+        identifier: u32,
+        /// The bytes of the descriptor that follow the identifier.
+        data: Vec<u8>,
+    },
+}
+
+/// The `segmentation_descriptor()`.
+///
+/// When [`Self::segmentation_event_cancel_indicator`] is set, the descriptor carries no more than
+/// the ID of the event that is cancelled, and so all other values are their defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SegmentationDescriptor {
+    /// The `identifier` (`0x43554549`, or "CUEI").
+    pub identifier: u32,
+    /// The `segmentation_event_id`.
+    pub segmentation_event_id: u32,
+    /// The `segmentation_event_cancel_indicator`.
+    pub segmentation_event_cancel_indicator: bool,
+    /// The `segmentation_event_id_compliance_indicator`.
+    pub segmentation_event_id_compliance_indicator: bool,
+    /// The `program_segmentation_flag` (set when the segmentation applies to all components).
+    pub program_segmentation_flag: bool,
+    /// The delivery restrictions, which are `None` when the `delivery_not_restricted_flag` is set.
+    pub delivery_restrictions: Option<DeliveryRestrictions>,
+    /// The components, when the segmentation does not apply to the whole program.
+    pub components: Vec<SegmentationComponent>,
+    /// The `segmentation_duration` (in 90 kHz ticks).
+    pub segmentation_duration: Option<u64>,
+    /// The `segmentation_upid_type`.
+    pub segmentation_upid_type: u8,
+    /// The `segmentation_upid`.
+    pub segmentation_upid: Vec<u8>,
+    /// The `segmentation_type_id` (see [`Self::segmentation_type`]).
+    pub segmentation_type_id: u8,
+    /// The `segment_num`.
+    pub segment_num: u8,
+    /// The `segments_expected`.
+    pub segments_expected: u8,
+    /// The `sub_segment_num` (only present for some placement opportunity types).
+    pub sub_segment_num: Option<u8>,
+    /// The `sub_segments_expected` (only present for some placement opportunity types).
+    pub sub_segments_expected: Option<u8>,
+}
+
+impl SegmentationDescriptor {
+    /// The `segmentation_type_id` as a [`SegmentationType`].
+    pub fn segmentation_type(&self) -> SegmentationType {
+        SegmentationType::from(self.segmentation_type_id)
+    }
+}
+
+/// The delivery restrictions of a [`SegmentationDescriptor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeliveryRestrictions {
+    /// The `web_delivery_allowed_flag`.
+    pub web_delivery_allowed: bool,
+    /// The `no_regional_blackout_flag`.
+    pub no_regional_blackout: bool,
+    /// The `archive_allowed_flag`.
+    pub archive_allowed: bool,
+    /// The `device_restrictions`.
+    pub device_restrictions: u8,
+}
+
+/// A component of a [`SegmentationDescriptor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentationComponent {
+    /// The `component_tag`.
+    pub component_tag: u8,
+    /// The `pts_offset` (in 90 kHz ticks).
+    pub pts_offset: u64,
+}
+
+macro_rules! segmentation_types {
+    ($($(#[$doc:meta])* $variant:ident = $value:literal,)+) => {
+        /// The `segmentation_type_id` of a [`SegmentationDescriptor`].
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum SegmentationType {
+            $($(#[$doc])* $variant,)+
+            /// A value that is not known to the library.
+            Other(u8),
+        }
+        impl From<u8> for SegmentationType {
+            fn from(value: u8) -> Self {
+                match value {
+                    $($value => Self::$variant,)+
+                    value => Self::Other(value),
+                }
+            }
+        }
+        impl From<SegmentationType> for u8 {
+            fn from(value: SegmentationType) -> Self {
+                match value {
+                    $(SegmentationType::$variant => $value,)+
+                    SegmentationType::Other(value) => value,
+                }
+            }
+        }
+    };
+}
+
+segmentation_types! {
+    /// Not Indicated (`0x00`).
+    NotIndicated = 0x00,
+    /// Content Identification (`0x01`).
+    ContentIdentification = 0x01,
+    /// Call Ad Server (`0x02`).
+    CallAdServer = 0x02,
+    /// Program Start (`0x10`).
+    ProgramStart = 0x10,
+    /// Program End (`0x11`).
+    ProgramEnd = 0x11,
+    /// Program Early Termination (`0x12`).
+    ProgramEarlyTermination = 0x12,
+    /// Program Breakaway (`0x13`).
+    ProgramBreakaway = 0x13,
+    /// Program Resumption (`0x14`).
+    ProgramResumption = 0x14,
+    /// Program Runover Planned (`0x15`).
+    ProgramRunoverPlanned = 0x15,
+    /// Program Runover Unplanned (`0x16`).
+    ProgramRunoverUnplanned = 0x16,
+    /// Program Overlap Start (`0x17`).
+    ProgramOverlapStart = 0x17,
+    /// Program Blackout Override (`0x18`).
+    ProgramBlackoutOverride = 0x18,
+    /// Program Join (`0x19`).
+    ProgramJoin = 0x19,
+    /// Chapter Start (`0x20`).
+    ChapterStart = 0x20,
+    /// Chapter End (`0x21`).
+    ChapterEnd = 0x21,
+    /// Break Start (`0x22`).
+    BreakStart = 0x22,
+    /// Break End (`0x23`).
+    BreakEnd = 0x23,
+    /// Opening Credit Start (`0x24`).
+    OpeningCreditStart = 0x24,
+    /// Opening Credit End (`0x25`).
+    OpeningCreditEnd = 0x25,
+    /// Closing Credit Start (`0x26`).
+    ClosingCreditStart = 0x26,
+    /// Closing Credit End (`0x27`).
+    ClosingCreditEnd = 0x27,
+    /// Provider Advertisement Start (`0x30`).
+    ProviderAdvertisementStart = 0x30,
+    /// Provider Advertisement End (`0x31`).
+    ProviderAdvertisementEnd = 0x31,
+    /// Distributor Advertisement Start (`0x32`).
+    DistributorAdvertisementStart = 0x32,
+    /// Distributor Advertisement End (`0x33`).
+    DistributorAdvertisementEnd = 0x33,
+    /// Provider Placement Opportunity Start (`0x34`).
+    ProviderPlacementOpportunityStart = 0x34,
+    /// Provider Placement Opportunity End (`0x35`).
+    ProviderPlacementOpportunityEnd = 0x35,
+    /// Distributor Placement Opportunity Start (`0x36`).
+    DistributorPlacementOpportunityStart = 0x36,
+    /// Distributor Placement Opportunity End (`0x37`).
+    DistributorPlacementOpportunityEnd = 0x37,
+    /// Provider Overlay Placement Opportunity Start (`0x38`).
+    ProviderOverlayPlacementOpportunityStart = 0x38,
+    /// Provider Overlay Placement Opportunity End (`0x39`).
+    ProviderOverlayPlacementOpportunityEnd = 0x39,
+    /// Distributor Overlay Placement Opportunity Start (`0x3A`).
+    DistributorOverlayPlacementOpportunityStart = 0x3A,
+    /// Distributor Overlay Placement Opportunity End (`0x3B`).
+    DistributorOverlayPlacementOpportunityEnd = 0x3B,
+    /// Provider Promo Start (`0x3C`).
+    ProviderPromoStart = 0x3C,
+    /// Provider Promo End (`0x3D`).
+    ProviderPromoEnd = 0x3D,
+    /// Distributor Promo Start (`0x3E`).
+    DistributorPromoStart = 0x3E,
+    /// Distributor Promo End (`0x3F`).
+    DistributorPromoEnd = 0x3F,
+    /// Unscheduled Event Start (`0x40`).
+    UnscheduledEventStart = 0x40,
+    /// Unscheduled Event End (`0x41`).
+    UnscheduledEventEnd = 0x41,
+    /// Alternate Content Opportunity Start (`0x42`).
+    AlternateContentOpportunityStart = 0x42,
+    /// Alternate Content Opportunity End (`0x43`).
+    AlternateContentOpportunityEnd = 0x43,
+    /// Provider Ad Block Start (`0x44`).
+    ProviderAdBlockStart = 0x44,
+    /// Provider Ad Block End (`0x45`).
+    ProviderAdBlockEnd = 0x45,
+    /// Distributor Ad Block Start (`0x46`).
+    DistributorAdBlockStart = 0x46,
+    /// Distributor Ad Block End (`0x47`).
+    DistributorAdBlockEnd = 0x47,
+    /// Network Start (`0x50`).
+    NetworkStart = 0x50,
+    /// Network End (`0x51`).
+    NetworkEnd = 0x51,
+}
+
+// The segmentation types that carry the `sub_segment_num` and `sub_segments_expected` fields.
+const SUB_SEGMENT_TYPES: [u8; 6] = [0x34, 0x36, 0x38, 0x3A, 0x44, 0x46];
+
+fn parse_splice_command(
+    command_type: u8,
+    reader: &mut BitReader,
+    command_bytes: Option<&[u8]>,
+) -> Result<SpliceCommand, Scte35Error> {
+    match command_type {
+        0x00 => Ok(SpliceCommand::SpliceNull),
+        0x05 => parse_splice_insert(reader).map(SpliceCommand::SpliceInsert),
+        0x06 => parse_splice_time(reader).map(SpliceCommand::TimeSignal),
+        0x07 => Ok(SpliceCommand::BandwidthReservation),
+        0xFF => {
+            let identifier = reader.read(32)? as u32;
+            // Without a declared length the private bytes cannot be told apart from the rest of
+            // the section.
+            let data = match command_bytes {
+                Some(_) => reader.remaining().to_vec(),
+                None => Vec::new(),
+            };
+            reader.skip(data.len() * 8)?;
+            Ok(SpliceCommand::PrivateCommand { identifier, data })
+        }
+        command_type => Ok(SpliceCommand::Other {
+            command_type,
+            data: command_bytes.map(<[u8]>::to_vec).unwrap_or_default(),
+        }),
+    }
+}
+
+fn parse_splice_insert(reader: &mut BitReader) -> Result<SpliceInsert, Scte35Error> {
+    let splice_event_id = reader.read(32)? as u32;
+    let splice_event_cancel_indicator = reader.read_flag()?;
+    reader.skip(7)?;
+    if splice_event_cancel_indicator {
+        return Ok(SpliceInsert {
+            splice_event_id,
+            splice_event_cancel_indicator,
+            ..Default::default()
+        });
+    }
+    let out_of_network_indicator = reader.read_flag()?;
+    let program_splice_flag = reader.read_flag()?;
+    let duration_flag = reader.read_flag()?;
+    let splice_immediate_flag = reader.read_flag()?;
+    let event_id_compliance_flag = reader.read_flag()?;
+    reader.skip(3)?;
+    let splice_time = if program_splice_flag && !splice_immediate_flag {
+        Some(parse_splice_time(reader)?)
+    } else {
+        None
+    };
+    let mut components = Vec::new();
+    if !program_splice_flag {
+        let component_count = reader.read_u8()?;
+        for _ in 0..component_count {
+            let component_tag = reader.read_u8()?;
+            let splice_time = if splice_immediate_flag {
+                None
+            } else {
+                Some(parse_splice_time(reader)?)
+            };
+            components.push(SpliceInsertComponent {
+                component_tag,
+                splice_time,
+            });
+        }
+    }
+    let break_duration = if duration_flag {
+        let auto_return = reader.read_flag()?;
+        reader.skip(6)?;
+        Some(BreakDuration {
+            auto_return,
+            duration: reader.read(33)?,
+        })
+    } else {
+        None
+    };
+    Ok(SpliceInsert {
+        splice_event_id,
+        splice_event_cancel_indicator,
+        out_of_network_indicator,
+        program_splice_flag,
+        splice_immediate_flag,
+        event_id_compliance_flag,
+        splice_time,
+        components,
+        break_duration,
+        unique_program_id: reader.read(16)? as u16,
+        avail_num: reader.read_u8()?,
+        avails_expected: reader.read_u8()?,
+    })
+}
+
+fn parse_splice_time(reader: &mut BitReader) -> Result<SpliceTime, Scte35Error> {
+    if reader.read_flag()? {
+        reader.skip(6)?;
+        Ok(SpliceTime {
+            pts_time: Some(reader.read(33)?),
+        })
+    } else {
+        reader.skip(7)?;
+        Ok(SpliceTime { pts_time: None })
+    }
+}
+
+fn parse_splice_descriptor(reader: &mut BitReader) -> Result<SpliceDescriptor, Scte35Error> {
+    let tag = reader.read_u8()?;
+    let length = usize::from(reader.read_u8()?);
+    let mut reader = BitReader::new(reader.take(length)?);
+    let identifier = reader.read(32)? as u32;
+    if tag != SEGMENTATION_DESCRIPTOR_TAG {
+        return Ok(SpliceDescriptor::Other {
+            tag,
+            identifier,
+            data: reader.remaining().to_vec(),
+        });
+    }
+    let segmentation_event_id = reader.read(32)? as u32;
+    let segmentation_event_cancel_indicator = reader.read_flag()?;
+    let segmentation_event_id_compliance_indicator = reader.read_flag()?;
+    reader.skip(6)?;
+    if segmentation_event_cancel_indicator {
+        return Ok(SpliceDescriptor::Segmentation(SegmentationDescriptor {
+            identifier,
+            segmentation_event_id,
+            segmentation_event_cancel_indicator,
+            segmentation_event_id_compliance_indicator,
+            ..Default::default()
+        }));
+    }
+    let program_segmentation_flag = reader.read_flag()?;
+    let segmentation_duration_flag = reader.read_flag()?;
+    let delivery_restrictions = if reader.read_flag()? {
+        reader.skip(5)?;
+        None
+    } else {
+        Some(DeliveryRestrictions {
+            web_delivery_allowed: reader.read_flag()?,
+            no_regional_blackout: reader.read_flag()?,
+            archive_allowed: reader.read_flag()?,
+            device_restrictions: reader.read(2)? as u8,
+        })
+    };
+    let mut components = Vec::new();
+    if !program_segmentation_flag {
+        let component_count = reader.read_u8()?;
+        for _ in 0..component_count {
+            let component_tag = reader.read_u8()?;
+            reader.skip(7)?;
+            components.push(SegmentationComponent {
+                component_tag,
+                pts_offset: reader.read(33)?,
+            });
+        }
+    }
+    let segmentation_duration = if segmentation_duration_flag {
+        Some(reader.read(40)?)
+    } else {
+        None
+    };
+    let segmentation_upid_type = reader.read_u8()?;
+    let segmentation_upid_length = usize::from(reader.read_u8()?);
+    let segmentation_upid = reader.take(segmentation_upid_length)?.to_vec();
+    let segmentation_type_id = reader.read_u8()?;
+    let segment_num = reader.read_u8()?;
+    let segments_expected = reader.read_u8()?;
+    // Older encoders do not write the sub-segment fields, and so they are only read where the
+    // descriptor has room for them.
+    let (sub_segment_num, sub_segments_expected) =
+        if SUB_SEGMENT_TYPES.contains(&segmentation_type_id) && reader.remaining().len() >= 2 {
+            (Some(reader.read_u8()?), Some(reader.read_u8()?))
+        } else {
+            (None, None)
+        };
+    Ok(SpliceDescriptor::Segmentation(SegmentationDescriptor {
+        identifier,
+        segmentation_event_id,
+        segmentation_event_cancel_indicator,
+        segmentation_event_id_compliance_indicator,
+        program_segmentation_flag,
+        delivery_restrictions,
+        components,
+        segmentation_duration,
+        segmentation_upid_type,
+        segmentation_upid,
+        segmentation_type_id,
+        segment_num,
+        segments_expected,
+        sub_segment_num,
+        sub_segments_expected,
+    }))
+}
+
+// The CRC-32/MPEG-2 used by MPEG-2 transport stream sections.
+fn crc_32_mpeg_2(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0xFFFFFFFF, |crc, byte| {
+        (0..8).fold(crc ^ (u32::from(*byte) << 24), |crc, _| {
+            if crc & 0x80000000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x04C11DB7
+            }
+        })
+    })
+}
+
+// Reads big-endian bit fields from a byte slice, failing (rather than panicking) when the end of
+// the slice is reached.
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    // The position in bits.
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn read(&mut self, bits: usize) -> Result<u64, Scte35Error> {
+        debug_assert!(bits <= 64);
+        if self.position + bits > self.bytes.len() * 8 {
+            return Err(Scte35Error::UnexpectedEnd);
+        }
+        let mut value = 0u64;
+        for _ in 0..bits {
+            let byte = self.bytes[self.position / 8];
+            let bit = (byte >> (7 - self.position % 8)) & 1;
+            value = value << 1 | u64::from(bit);
+            self.position += 1;
+        }
+        Ok(value)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Scte35Error> {
+        Ok(self.read(8)? as u8)
+    }
+
+    fn read_flag(&mut self) -> Result<bool, Scte35Error> {
+        Ok(self.read(1)? == 1)
+    }
+
+    fn skip(&mut self, bits: usize) -> Result<(), Scte35Error> {
+        if self.position + bits > self.bytes.len() * 8 {
+            return Err(Scte35Error::UnexpectedEnd);
+        }
+        self.position += bits;
+        Ok(())
+    }
+
+    // The bytes from the current (byte aligned) position.
+    fn remaining(&self) -> &'a [u8] {
+        self.bytes
+            .get(self.position.div_ceil(8)..)
+            .unwrap_or_default()
+    }
+
+    // Takes the next `length` bytes (from the current byte aligned position).
+    fn take(&mut self, length: usize) -> Result<&'a [u8], Scte35Error> {
+        let bytes = self
+            .remaining()
+            .get(..length)
+            .ok_or(Scte35Error::UnexpectedEnd)?;
+        self.position = self.position.div_ceil(8) * 8 + length * 8;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // The splice_insert example of section 14.2 of SCTE-35 (2022).
+    const SPLICE_INSERT: &str = concat!(
+        "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF500000000000A0008435545490000",
+        "013562DBA30A",
+    );
+
+    #[test]
+    fn splice_insert_should_be_parsed() {
+        let section = parse_hex(SPLICE_INSERT).unwrap();
+        assert!(section.crc_32_valid);
+        assert_eq!(
+            SpliceCommand::SpliceInsert(SpliceInsert {
+                splice_event_id: 0x4800008F,
+                splice_event_cancel_indicator: false,
+                out_of_network_indicator: true,
+                program_splice_flag: true,
+                splice_immediate_flag: false,
+                event_id_compliance_flag: true,
+                splice_time: Some(SpliceTime {
+                    pts_time: Some(0x07369C02E)
+                }),
+                components: Vec::new(),
+                break_duration: Some(BreakDuration {
+                    auto_return: true,
+                    duration: 0x00052CCF5,
+                }),
+                unique_program_id: 0,
+                avail_num: 0,
+                avails_expected: 0,
+            }),
+            section.splice_command
+        );
+        assert_eq!(
+            vec![SpliceDescriptor::Other {
+                tag: 0,
+                identifier: 0x43554549,
+                data: vec![0x00, 0x00, 0x01, 0x35],
+            }],
+            section.descriptors
+        );
+        assert_eq!(5_426_421.0 / 90_000.0, ticks_to_seconds(0x00052CCF5));
+    }
+
+    #[test]
+    fn segmentation_descriptor_should_be_parsed() {
+        let section = parse_hex(concat!(
+            "FC302F000000000000FFFFF00506FE72BD0050001A0218435545494800008E7F9F0808000000002CA0A18A",
+            "350200A9CC6758",
+        ))
+        .unwrap();
+        // The published example has a CRC that does not match its contents.
+        assert!(!section.crc_32_valid);
+        assert_eq!(
+            vec![&SegmentationDescriptor {
+                identifier: 0x43554549,
+                segmentation_event_id: 0x4800008E,
+                segmentation_event_cancel_indicator: false,
+                segmentation_event_id_compliance_indicator: true,
+                program_segmentation_flag: true,
+                delivery_restrictions: Some(DeliveryRestrictions {
+                    web_delivery_allowed: true,
+                    no_regional_blackout: true,
+                    archive_allowed: true,
+                    device_restrictions: 3,
+                }),
+                components: Vec::new(),
+                segmentation_duration: None,
+                segmentation_upid_type: 0x08,
+                segmentation_upid: vec![0x00, 0x00, 0x00, 0x00, 0x2C, 0xA0, 0xA1, 0x8A],
+                segmentation_type_id: 0x35,
+                segment_num: 2,
+                segments_expected: 0,
+                sub_segment_num: None,
+                sub_segments_expected: None,
+            }],
+            section.segmentation_descriptors().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn invalid_input_should_error_without_panicking() {
+        assert_eq!(Err(Scte35Error::InvalidHex), parse_hex("0xFC3"));
+        assert_eq!(Err(Scte35Error::InvalidHex), parse_hex("0xFG"));
+        assert_eq!(Err(Scte35Error::UnexpectedTableId(0)), parse_hex("00"));
+        assert_eq!(
+            Err(Scte35Error::EncryptedPacket),
+            parse_hex("FC300F00800000000000000000000000000000")
+        );
+        let bytes = parse_hex_bytes(SPLICE_INSERT);
+        for end in 0..bytes.len() {
+            assert!(parse(&bytes[..end]).is_err());
+        }
+        // Corrupting each byte in turn may produce a different (but still parsed) section, or an
+        // error, but never a panic.
+        for index in 0..bytes.len() {
+            let mut corrupted = bytes.clone();
+            corrupted[index] ^= 0xFF;
+            let _ = parse(&corrupted);
+        }
+    }
+
+    fn parse_hex_bytes(hex: &str) -> Vec<u8> {
+        let hex = hex.trim_start_matches("0x");
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+}
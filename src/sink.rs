@@ -0,0 +1,213 @@
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+/// A [`Write`] over a pre-allocated byte slice, so that a [`crate::Writer`] can write a playlist
+/// without allocating on the heap (e.g. in a WASM edge worker with a fixed output buffer).
+///
+/// A `&mut [u8]` is itself a [`Write`]; however, it silently writes as much of the data as fits,
+/// meaning that a line may be cut short. In contrast, `SliceBuffer` only ever writes data in full.
+/// When the data does not fit within the remaining space, nothing is written, the buffer is marked
+/// as overflowed (see [`Self::is_overflowed`]), and an [`io::Error`] of kind
+/// [`io::ErrorKind::WriteZero`] is returned.
+///
+/// The `Writer` writes each line in more than one part (e.g. the line and then the line ending), and
+/// so, after an overflow, the buffer may end with an incomplete line. [`Self::truncate`] can be used
+/// to remove it, given the length of the buffer before the line was written.
+///
+/// For example, writing as many complete lines as fit:
+/// ```
+/// # use quick_m3u8::{Reader, SliceBuffer, Writer, config::ParsingOptions};
+/// const PLAYLIST: &str = "#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:4\n0.ts\n#EXTINF:4\n1.ts\n";
+///
+/// let mut buffer = [0; 48];
+/// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+/// let mut writer = Writer::new(SliceBuffer::new(&mut buffer));
+/// while let Some(line) = reader.read_line()? {
+///     let complete = writer.get_ref().len();
+///     if writer.write_line(line).is_err() {
+///         writer.get_mut().truncate(complete);
+///         break;
+///     }
+/// }
+/// let output = writer.into_inner();
+/// assert!(output.is_overflowed());
+/// assert_eq!(
+///     b"#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:4\n0.ts\n",
+///     output.into_filled()
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct SliceBuffer<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+    overflowed: bool,
+}
+
+impl<'a> SliceBuffer<'a> {
+    /// Creates an empty `SliceBuffer` that writes to the start of the slice.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            len: 0,
+            overflowed: false,
+        }
+    }
+
+    /// The bytes that have been written.
+    pub fn filled(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// Consumes the `SliceBuffer`, returning the bytes that have been written.
+    pub fn into_filled(self) -> &'a [u8] {
+        let buffer: &'a [u8] = self.buffer;
+        &buffer[..self.len]
+    }
+
+    /// The number of bytes that have been written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indicates whether no bytes have been written.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of bytes that can still be written.
+    pub fn remaining(&self) -> usize {
+        self.buffer.len() - self.len
+    }
+
+    /// Indicates whether a write has failed because the data did not fit.
+    pub fn is_overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    /// Discards the bytes written after the first `len` bytes (has no effect if `len` is greater
+    /// than the number of bytes written).
+    ///
+    /// The buffer remains marked as overflowed, if it was.
+    pub fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+}
+
+impl Write for SliceBuffer<'_> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if data.len() > self.remaining() {
+            self.overflowed = true;
+            // Created from the kind so that no allocation is needed.
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+        self.buffer[self.len..(self.len + data.len())].copy_from_slice(data);
+        self.len += data.len();
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Adapts a [`fmt::Write`] (such as a `String`, or a [`fmt::Formatter`]) into a [`Write`], so that
+/// a [`crate::Writer`] can write a playlist directly to it.
+///
+/// The data written must be valid UTF-8, which is always the case for lines read from a `&str`;
+/// otherwise an [`io::Error`] of kind [`io::ErrorKind::InvalidData`] is returned. An error from the
+/// [`fmt::Write`] is provided as an [`io::Error`] of kind [`io::ErrorKind::Other`].
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{FmtWriteAdapter, HlsLine, Writer, tag::hls::Targetduration};
+/// let mut output = String::new();
+/// let mut writer = Writer::new(FmtWriteAdapter::new(&mut output));
+/// writer.write_line(HlsLine::from(Targetduration::new(4)))?;
+/// writer.write_uri("0.ts")?;
+/// assert_eq!("#EXT-X-TARGETDURATION:4\n0.ts\n", output);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FmtWriteAdapter<W> {
+    inner: W,
+}
+
+impl<W> FmtWriteAdapter<W>
+where
+    W: fmt::Write,
+{
+    /// Creates a new adapter over the [`fmt::Write`].
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the inner [`fmt::Write`].
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Get a reference to the inner [`fmt::Write`].
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner [`fmt::Write`].
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+}
+
+impl<W> Write for FmtWriteAdapter<W>
+where
+    W: fmt::Write,
+{
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let data =
+            std::str::from_utf8(data).map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+        self.inner
+            .write_str(data)
+            .map_err(|_| io::Error::from(io::ErrorKind::Other))?;
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn slice_buffer_should_not_write_data_that_does_not_fit() {
+        let mut buffer = [0; 4];
+        let mut slice_buffer = SliceBuffer::new(&mut buffer);
+        assert_eq!(3, slice_buffer.write(b"abc").unwrap());
+        assert_eq!(
+            io::ErrorKind::WriteZero,
+            slice_buffer.write(b"de").unwrap_err().kind()
+        );
+        assert!(slice_buffer.is_overflowed());
+        assert_eq!(b"abc", slice_buffer.filled());
+        assert_eq!(1, slice_buffer.write(b"d").unwrap());
+        assert_eq!(0, slice_buffer.remaining());
+        slice_buffer.truncate(10);
+        assert_eq!(4, slice_buffer.len());
+    }
+
+    #[test]
+    fn fmt_write_adapter_should_reject_invalid_utf8() {
+        let mut adapter = FmtWriteAdapter::new(String::new());
+        assert_eq!(
+            io::ErrorKind::InvalidData,
+            adapter.write(&[0xFF]).unwrap_err().kind()
+        );
+        adapter.write_all("é".as_bytes()).unwrap();
+        assert_eq!("é", adapter.into_inner());
+    }
+}
@@ -0,0 +1,477 @@
+// The `custom_tag!` macro, which generates the `CustomTag` and `WritableCustomTag` boilerplate for
+// custom tags that are made up of an attribute list.
+
+/// Declares a custom tag (implementing [`crate::tag::CustomTag`] and
+/// [`crate::tag::WritableCustomTag`]) from a struct with annotated attributes.
+///
+/// Each field of the struct is declared with the kind of attribute value that it holds, followed by
+/// the name of the attribute. The supported kinds are:
+/// * `quoted` - a quoted string (stored as `Cow<str>`),
+/// * `unquoted` - an unquoted string, such as an enumerated string (stored as `Cow<str>`),
+/// * `integer` - a decimal integer (stored as `u64`),
+/// * `float` - a (signed) decimal floating point (stored as `f64`),
+///
+/// and each of these may be prefixed with `optional` (stored as `Option<T>`). The struct always
+/// derives `Debug` and `PartialEq` (as required by `CustomTag`), and any other attributes (such as
+/// doc comments or further derives) are passed through.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{
+/// #     HlsLine, Reader, Writer, config::ParsingOptions, custom_tag, tag::KnownTag,
+/// # };
+/// # use std::marker::PhantomData;
+/// custom_tag! {
+///     /// The `EXT-X-JOKE` tag.
+///     #[derive(Clone)]
+///     pub struct JokeTag<'a>("-X-JOKE") {
+///         /// The `TYPE` attribute.
+///         pub joke_type: unquoted "TYPE",
+///         /// The `JOKE` attribute.
+///         pub joke: quoted "JOKE",
+///         /// The `RATING` attribute.
+///         pub rating: optional float "RATING",
+///     }
+/// }
+///
+/// let mut reader = Reader::with_custom_from_str(
+///     "#EXT-X-JOKE:TYPE=DAD,JOKE=\"Why did the bicycle fall over? It was two-tired!\"",
+///     ParsingOptions::default(),
+///     PhantomData::<JokeTag>,
+/// );
+/// let Ok(Some(HlsLine::KnownTag(KnownTag::Custom(mut tag)))) = reader.read_line() else {
+///     panic!("expected custom tag");
+/// };
+/// assert_eq!("DAD", tag.as_ref().joke_type);
+/// assert_eq!(None, tag.as_ref().rating);
+///
+/// tag.as_mut().rating = Some(4.5);
+/// let mut writer = Writer::new(Vec::new());
+/// writer.write_custom_line(HlsLine::from(tag))?;
+/// assert_eq!(
+///     "#EXT-X-JOKE:TYPE=DAD,JOKE=\"Why did the bicycle fall over? It was two-tired!\",RATING=4.5\n",
+///     std::str::from_utf8(&writer.into_inner())?
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// When the struct is declared without a lifetime, the string values are copied into owned values
+/// (`Cow<'static, str>`), and a struct with no fields declares a tag that has no value:
+/// ```
+/// # use quick_m3u8::custom_tag;
+/// custom_tag! {
+///     /// The `EXT-X-PREFETCH-DISCONTINUITY` tag.
+///     pub struct PrefetchDiscontinuity("-X-PREFETCH-DISCONTINUITY") {}
+/// }
+/// ```
+///
+/// The [`crate::Reader`] accepts a single custom tag type, and so several custom tags can be
+/// combined into an enum (where each variant wraps a custom tag type):
+/// ```
+/// # use quick_m3u8::{
+/// #     HlsLine, Reader, config::ParsingOptions, custom_tag, tag::KnownTag,
+/// # };
+/// # use std::marker::PhantomData;
+/// custom_tag! {
+///     pub struct Prefetch<'a>("-X-PREFETCH-URI") {
+///         pub uri: quoted "URI",
+///     }
+/// }
+/// custom_tag! {
+///     pub struct PrefetchDiscontinuity("-X-PREFETCH-DISCONTINUITY") {}
+/// }
+/// custom_tag! {
+///     pub enum LHlsTag<'a> {
+///         Prefetch(Prefetch<'a>),
+///         Discontinuity(PrefetchDiscontinuity),
+///     }
+/// }
+///
+/// let mut reader = Reader::with_custom_from_str(
+///     "#EXT-X-PREFETCH-DISCONTINUITY\n#EXT-X-PREFETCH-URI:URI=\"5.ts\"",
+///     ParsingOptions::default(),
+///     PhantomData::<LHlsTag>,
+/// );
+/// let Ok(Some(HlsLine::KnownTag(KnownTag::Custom(tag)))) = reader.read_line() else {
+///     panic!("expected custom tag");
+/// };
+/// assert_eq!(&LHlsTag::Discontinuity(PrefetchDiscontinuity {}), tag.as_ref());
+/// let Ok(Some(HlsLine::KnownTag(KnownTag::Custom(tag)))) = reader.read_line() else {
+///     panic!("expected custom tag");
+/// };
+/// assert_eq!(&LHlsTag::Prefetch(Prefetch { uri: "5.ts".into() }), tag.as_ref());
+/// ```
+///
+/// Attributes in the tag that are not declared in the struct are ignored when parsing, and are not
+/// written if the tag is mutated (when the tag is not mutated the original line is written as is).
+/// The declared attributes are written in the order of the fields.
+#[macro_export]
+macro_rules! custom_tag {
+    // === TYPES ===
+    (@ty $lt:lifetime, optional $kind:ident) => {
+        ::std::option::Option<$crate::custom_tag!(@ty $lt, $kind)>
+    };
+    (@ty $lt:lifetime, quoted) => { ::std::borrow::Cow<$lt, str> };
+    (@ty $lt:lifetime, unquoted) => { ::std::borrow::Cow<$lt, str> };
+    (@ty $lt:lifetime, integer) => { u64 };
+    (@ty $lt:lifetime, float) => { f64 };
+
+    // === PARSING ===
+    (@cow borrowed, $value:expr) => { ::std::borrow::Cow::Borrowed($value) };
+    (@cow owned, $value:expr) => { ::std::borrow::Cow::Owned(::std::string::String::from($value)) };
+    (@get $mode:ident, $list:ident, $attr:literal, optional $kind:ident) => {
+        $crate::custom_tag!(@value $mode, $list, $attr, $kind)
+    };
+    (@get $mode:ident, $list:ident, $attr:literal, $kind:ident) => {
+        $crate::custom_tag!(@value $mode, $list, $attr, $kind)
+            .ok_or($crate::error::ValidationError::MissingRequiredAttribute($attr))?
+    };
+    (@value $mode:ident, $list:ident, $attr:literal, quoted) => {
+        match $list.get($attr) {
+            Some($crate::tag::AttributeValue::Quoted(value)) => {
+                Some($crate::custom_tag!(@cow $mode, *value))
+            }
+            Some($crate::tag::AttributeValue::Unquoted(_)) => {
+                return Err($crate::error::ParseAttributeValueError::UnexpectedUnquoted {
+                    attr_name: $attr,
+                }
+                .into());
+            }
+            None => None,
+        }
+    };
+    (@value $mode:ident, $list:ident, $attr:literal, unquoted) => {
+        $crate::custom_tag!(@unquoted $list, $attr, value => {
+            value
+                .try_as_utf_8()
+                .map(|value| $crate::custom_tag!(@cow $mode, value))
+                .map_err(|error| $crate::error::ParseAttributeValueError::Utf8 {
+                    attr_name: $attr,
+                    error,
+                })
+        })
+    };
+    (@value $mode:ident, $list:ident, $attr:literal, integer) => {
+        $crate::custom_tag!(@unquoted $list, $attr, value => {
+            value.try_as_decimal_integer().map_err(|error| {
+                $crate::error::ParseAttributeValueError::DecimalInteger {
+                    attr_name: $attr,
+                    error,
+                }
+            })
+        })
+    };
+    (@value $mode:ident, $list:ident, $attr:literal, float) => {
+        $crate::custom_tag!(@unquoted $list, $attr, value => {
+            value.try_as_decimal_floating_point().map_err(|error| {
+                $crate::error::ParseAttributeValueError::DecimalFloatingPoint {
+                    attr_name: $attr,
+                    error,
+                }
+            })
+        })
+    };
+    (@unquoted $list:ident, $attr:literal, $value:ident => $convert:block) => {
+        match $list.get($attr) {
+            Some($crate::tag::AttributeValue::Unquoted($value)) => Some($convert?),
+            Some($crate::tag::AttributeValue::Quoted(_)) => {
+                return Err($crate::error::ParseAttributeValueError::UnexpectedQuoted {
+                    attr_name: $attr,
+                }
+                .into());
+            }
+            None => None,
+        }
+    };
+
+    // === WRITING ===
+    (@write $attributes:ident, $attr:literal, $value:expr, optional $kind:ident) => {
+        if let Some(value) = $value {
+            $crate::custom_tag!(@write $attributes, $attr, value, $kind);
+        }
+    };
+    (@write $attributes:ident, $attr:literal, $value:expr, quoted) => {
+        $attributes.push(format!("{}=\"{}\"", $attr, $value))
+    };
+    (@write $attributes:ident, $attr:literal, $value:expr, float) => {
+        $attributes.push(format!("{}={:?}", $attr, $value))
+    };
+    (@write $attributes:ident, $attr:literal, $value:expr, $kind:ident) => {
+        $attributes.push(format!("{}={}", $attr, $value))
+    };
+
+    // === STRUCT ===
+    (
+        @struct [$(#[$meta:meta])*] $vis:vis $name:ident [$($generics:tt)*] $impl_lt:lifetime
+        $lt:lifetime $mode:ident $tag_name:literal {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: [$($kind:ident)+] $attr:literal),*
+        }
+    ) => {
+        #[derive(Debug, PartialEq)]
+        $(#[$meta])*
+        $vis struct $name $($generics)* {
+            $($(#[$field_meta])* $field_vis $field: $crate::custom_tag!(@ty $lt, $($kind)+),)*
+        }
+        impl<$impl_lt> ::std::convert::TryFrom<$crate::tag::UnknownTag<$impl_lt>>
+            for $name $($generics)*
+        {
+            type Error = $crate::error::ValidationError;
+
+            fn try_from(tag: $crate::tag::UnknownTag<$impl_lt>) -> Result<Self, Self::Error> {
+                if tag.name() != $tag_name {
+                    return Err($crate::error::ValidationError::UnexpectedTagName);
+                }
+                #[allow(unused_variables)]
+                let list = match tag.value() {
+                    Some(value) => value.try_as_attribute_list()?,
+                    None => ::std::collections::HashMap::new(),
+                };
+                Ok(Self {
+                    $($field: $crate::custom_tag!(@get $mode, list, $attr, $($kind)+),)*
+                })
+            }
+        }
+        impl<$impl_lt> $crate::tag::CustomTag<$impl_lt> for $name $($generics)* {
+            fn is_known_name(name: &str) -> bool {
+                name == $tag_name
+            }
+        }
+        impl<$impl_lt> $crate::tag::WritableCustomTag<$impl_lt> for $name $($generics)* {
+            fn into_writable_tag(self) -> $crate::tag::WritableTag<$impl_lt> {
+                #[allow(unused_mut)]
+                let mut attributes = ::std::vec::Vec::<::std::string::String>::new();
+                $($crate::custom_tag!(@write attributes, $attr, self.$field, $($kind)+);)*
+                if attributes.is_empty() {
+                    $crate::tag::WritableTag::new($tag_name, $crate::tag::WritableTagValue::Empty)
+                } else {
+                    $crate::tag::WritableTag::new($tag_name, attributes.join(","))
+                }
+            }
+        }
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident<$lt:lifetime>($tag_name:literal) {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $($kind:ident)+ $attr:literal),*
+            $(,)?
+        }
+    ) => {
+        $crate::custom_tag!(
+            @struct [$(#[$meta])*] $vis $name [<$lt>] $lt $lt borrowed $tag_name {
+                $($(#[$field_meta])* $field_vis $field: [$($kind)+] $attr),*
+            }
+        );
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident($tag_name:literal) {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $($kind:ident)+ $attr:literal),*
+            $(,)?
+        }
+    ) => {
+        $crate::custom_tag!(
+            @struct [$(#[$meta])*] $vis $name [] 'a 'static owned $tag_name {
+                $($(#[$field_meta])* $field_vis $field: [$($kind)+] $attr),*
+            }
+        );
+    };
+
+    // === ENUM ===
+    (
+        @enum [$(#[$meta:meta])*] $vis:vis $name:ident [$($generics:tt)*] $lt:lifetime {
+            $($(#[$variant_meta:meta])* $variant:ident($ty:ty)),*
+        }
+    ) => {
+        #[derive(Debug, PartialEq)]
+        $(#[$meta])*
+        $vis enum $name $($generics)* {
+            $($(#[$variant_meta])* $variant($ty),)*
+        }
+        impl<$lt> ::std::convert::TryFrom<$crate::tag::UnknownTag<$lt>> for $name $($generics)* {
+            type Error = $crate::error::ValidationError;
+
+            fn try_from(tag: $crate::tag::UnknownTag<$lt>) -> Result<Self, Self::Error> {
+                $(
+                    if <$ty as $crate::tag::CustomTag<$lt>>::is_known_name(tag.name()) {
+                        return <$ty as ::std::convert::TryFrom<$crate::tag::UnknownTag<$lt>>>::try_from(tag)
+                            .map(Self::$variant);
+                    }
+                )*
+                Err($crate::error::ValidationError::UnexpectedTagName)
+            }
+        }
+        impl<$lt> $crate::tag::CustomTag<$lt> for $name $($generics)* {
+            fn is_known_name(name: &str) -> bool {
+                $(<$ty as $crate::tag::CustomTag<$lt>>::is_known_name(name))||*
+            }
+        }
+        impl<$lt> $crate::tag::WritableCustomTag<$lt> for $name $($generics)* {
+            fn into_writable_tag(self) -> $crate::tag::WritableTag<$lt> {
+                match self {
+                    $(Self::$variant(tag) => $crate::tag::WritableCustomTag::into_writable_tag(tag),)*
+                }
+            }
+        }
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident<$lt:lifetime> {
+            $($(#[$variant_meta:meta])* $variant:ident($ty:ty)),+ $(,)?
+        }
+    ) => {
+        $crate::custom_tag!(
+            @enum [$(#[$meta])*] $vis $name [<$lt>] $lt {
+                $($(#[$variant_meta])* $variant($ty)),+
+            }
+        );
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident($ty:ty)),+ $(,)?
+        }
+    ) => {
+        $crate::custom_tag!(
+            @enum [$(#[$meta])*] $vis $name [] 'a {
+                $($(#[$variant_meta])* $variant($ty)),+
+            }
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        HlsLine, Reader, Writer,
+        config::ParsingOptions,
+        error::{ParseAttributeValueError, ParseNumberError, ValidationError},
+        tag::{KnownTag, UnknownTag},
+    };
+    use pretty_assertions::assert_eq;
+    use std::marker::PhantomData;
+
+    custom_tag! {
+        struct TestTag<'a>("-X-TEST") {
+            quoted: quoted "QUOTED",
+            unquoted: unquoted "UNQUOTED",
+            integer: integer "INTEGER",
+            float: optional float "FLOAT",
+        }
+    }
+
+    custom_tag! {
+        struct OwnedTag("-X-OWNED") {
+            name: optional quoted "NAME",
+        }
+    }
+
+    custom_tag! {
+        enum TestTags<'a> {
+            Test(TestTag<'a>),
+            Owned(OwnedTag),
+        }
+    }
+
+    fn unknown_tag(line: &str) -> UnknownTag<'_> {
+        crate::custom_parsing::tag::parse(line)
+            .expect("tag should parse")
+            .parsed
+    }
+
+    #[test]
+    fn attributes_should_be_parsed_by_kind() {
+        assert_eq!(
+            Ok(TestTag {
+                quoted: "hello".into(),
+                unquoted: "WORLD".into(),
+                integer: 42,
+                float: Some(-1.5),
+            }),
+            TestTag::try_from(unknown_tag(
+                "#EXT-X-TEST:FLOAT=-1.5,INTEGER=42,UNQUOTED=WORLD,QUOTED=\"hello\",OTHER=1"
+            ))
+        );
+        assert_eq!(
+            Ok(OwnedTag { name: None }),
+            OwnedTag::try_from(unknown_tag("#EXT-X-OWNED"))
+        );
+    }
+
+    #[test]
+    fn invalid_attributes_should_error() {
+        assert_eq!(
+            Err(ValidationError::UnexpectedTagName),
+            OwnedTag::try_from(unknown_tag("#EXT-X-OTHER"))
+        );
+        assert_eq!(
+            Err(ValidationError::MissingRequiredAttribute("INTEGER")),
+            TestTag::try_from(unknown_tag("#EXT-X-TEST:QUOTED=\"a\",UNQUOTED=B"))
+        );
+        assert_eq!(
+            Err(ValidationError::from(
+                ParseAttributeValueError::UnexpectedUnquoted {
+                    attr_name: "QUOTED"
+                }
+            )),
+            TestTag::try_from(unknown_tag("#EXT-X-TEST:QUOTED=a,UNQUOTED=B,INTEGER=1"))
+        );
+        assert_eq!(
+            Err(ValidationError::from(
+                ParseAttributeValueError::UnexpectedQuoted {
+                    attr_name: "UNQUOTED"
+                }
+            )),
+            TestTag::try_from(unknown_tag(
+                "#EXT-X-TEST:QUOTED=\"a\",UNQUOTED=\"B\",INTEGER=1"
+            ))
+        );
+        assert_eq!(
+            Err(ValidationError::from(
+                ParseAttributeValueError::DecimalInteger {
+                    attr_name: "INTEGER",
+                    error: ParseNumberError::InvalidDigit(b'x')
+                }
+            )),
+            TestTag::try_from(unknown_tag(
+                "#EXT-X-TEST:QUOTED=\"a\",UNQUOTED=B,INTEGER=1x"
+            ))
+        );
+    }
+
+    #[test]
+    fn mutated_tags_should_be_written_in_field_order() {
+        const PLAYLIST: &str = concat!(
+            "#EXT-X-TEST:INTEGER=1,QUOTED=\"a\",UNQUOTED=B\n",
+            "#EXT-X-OWNED:NAME=\"n\"\n",
+            "#EXT-X-UNKNOWN:A=1\n",
+        );
+        let mut reader = Reader::with_custom_from_str(
+            PLAYLIST,
+            ParsingOptions::default(),
+            PhantomData::<TestTags>,
+        );
+        let mut writer = Writer::new(Vec::new());
+        while let Some(line) = reader.read_line().expect("lines should be valid") {
+            match line {
+                HlsLine::KnownTag(KnownTag::Custom(mut tag)) => {
+                    match tag.as_mut() {
+                        TestTags::Test(test) => test.float = Some(2.0),
+                        TestTags::Owned(owned) => owned.name = None,
+                    }
+                    writer.write_custom_line(HlsLine::from(tag)).unwrap();
+                }
+                line => {
+                    writer.write_custom_line(line).unwrap();
+                }
+            }
+        }
+        assert_eq!(
+            concat!(
+                "#EXT-X-TEST:QUOTED=\"a\",UNQUOTED=B,INTEGER=1,FLOAT=2.0\n",
+                "#EXT-X-OWNED\n",
+                "#EXT-X-UNKNOWN:A=1\n",
+            ),
+            std::str::from_utf8(&writer.into_inner()).unwrap()
+        );
+    }
+}
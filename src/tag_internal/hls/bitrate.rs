@@ -2,7 +2,7 @@ use crate::{
     error::{ParseTagValueError, ValidationError},
     tag::{
         UnknownTag,
-        hls::{TagName, into_inner_tag},
+        hls::{TagName, into_inner_tag, serde_tag},
     },
 };
 use std::borrow::Cow;
@@ -13,8 +13,9 @@ use std::borrow::Cow;
 #[derive(Debug, Clone)]
 pub struct Bitrate<'a> {
     bitrate: u64,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl PartialEq for Bitrate<'_> {
@@ -35,6 +36,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Bitrate<'a> {
             bitrate,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -46,6 +48,7 @@ impl<'a> Bitrate<'a> {
             bitrate,
             output_line: Cow::Owned(calculate_line(bitrate)),
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -71,6 +74,7 @@ impl<'a> Bitrate<'a> {
 }
 
 into_inner_tag!(Bitrate);
+serde_tag!(Bitrate, u64, |tag| tag.bitrate(), Bitrate::new);
 
 fn calculate_line(bitrate: u64) -> Vec<u8> {
     format!("#EXT{}:{}", TagName::Bitrate.as_str(), bitrate).into_bytes()
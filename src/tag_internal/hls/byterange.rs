@@ -1,8 +1,8 @@
 use crate::{
-    error::{ParseTagValueError, ValidationError},
+    error::{ParseTagValueError, RangeRequestError, ValidationError},
     tag::{
-        UnknownTag,
-        hls::{TagName, into_inner_tag},
+        DecimalIntegerRange, RangeRequest, UnknownTag,
+        hls::{TagName, into_inner_tag, serde_tag},
     },
 };
 use std::borrow::Cow;
@@ -14,8 +14,9 @@ use std::borrow::Cow;
 pub struct Byterange<'a> {
     length: u64,
     offset: Option<u64>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl PartialEq for Byterange<'_> {
@@ -37,6 +38,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Byterange<'a> {
             offset: range.offset,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -49,6 +51,7 @@ impl<'a> Byterange<'a> {
             offset,
             output_line: Cow::Owned(calculate_line(length, offset)),
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -66,6 +69,14 @@ impl<'a> Byterange<'a> {
         self.offset
     }
 
+    /// The value of the tag as a [`DecimalIntegerRange`] (also known as [`crate::tag::ByteRange`]).
+    pub fn range(&self) -> DecimalIntegerRange {
+        DecimalIntegerRange {
+            length: self.length,
+            offset: self.offset,
+        }
+    }
+
     /// Sets the length component value.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
@@ -98,6 +109,35 @@ impl<'a> Byterange<'a> {
 
 into_inner_tag!(Byterange);
 
+impl TryFrom<&Byterange<'_>> for RangeRequest {
+    type Error = RangeRequestError;
+
+    fn try_from(tag: &Byterange<'_>) -> Result<Self, Self::Error> {
+        Self::try_from(tag.range())
+    }
+}
+serde_tag!(
+    Byterange,
+    DecimalIntegerRange,
+    |tag| DecimalIntegerRange {
+        length: tag.length(),
+        offset: tag.offset(),
+    },
+    |range| Byterange::new(range.length, range.offset)
+);
+
+impl From<DecimalIntegerRange> for Byterange<'_> {
+    fn from(range: DecimalIntegerRange) -> Self {
+        Self::new(range.length, range.offset)
+    }
+}
+
+impl From<Byterange<'_>> for DecimalIntegerRange {
+    fn from(tag: Byterange<'_>) -> Self {
+        tag.range()
+    }
+}
+
 fn calculate_line(length: u64, offset: Option<u64>) -> Vec<u8> {
     let mut line = format!("#EXT{}:{}", TagName::Byterange.as_str(), length);
     if let Some(offset) = offset {
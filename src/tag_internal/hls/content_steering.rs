@@ -2,7 +2,7 @@ use crate::{
     error::{ParseTagValueError, ValidationError},
     tag::{
         UnknownTag,
-        hls::{LazyAttribute, TagName, into_inner_tag},
+        hls::{LazyAttribute, TagName, into_inner_tag, serde_tag},
     },
 };
 use std::{borrow::Cow, marker::PhantomData};
@@ -11,6 +11,7 @@ use std::{borrow::Cow, marker::PhantomData};
 ///
 /// See [`ContentSteering`] for a link to the HLS documentation for these attributes.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct ContentSteeringAttributeList<'a> {
     /// Corresponds to the `SERVER-URI` attribute.
     ///
@@ -75,6 +76,14 @@ impl<'a, ServerUriStatus> ContentSteeringBuilder<'a, ServerUriStatus> {
         self.attribute_list.pathway_id = Some(pathway_id.into());
         self
     }
+    /// Add the provided `pathway_id` to the attributes that are built into the `ContentSteering`,
+    /// if it is `Some` (otherwise this is a no-op).
+    pub fn with_pathway_id_opt(self, pathway_id: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match pathway_id {
+            Some(pathway_id) => self.with_pathway_id(pathway_id),
+            None => self,
+        }
+    }
 }
 impl<'a> Default for ContentSteeringBuilder<'a, ContentSteeringServerUriNeedsToBeSet> {
     fn default() -> Self {
@@ -89,8 +98,9 @@ impl<'a> Default for ContentSteeringBuilder<'a, ContentSteeringServerUriNeedsToB
 pub struct ContentSteering<'a> {
     server_uri: Cow<'a, str>,
     pathway_id: LazyAttribute<'a, Cow<'a, str>>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for ContentSteering<'a> {
@@ -106,7 +116,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for ContentSteering<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut server_uri = None;
         let mut pathway_id = LazyAttribute::None;
         for (name, value) in attribute_list {
@@ -124,6 +134,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for ContentSteering<'a> {
             pathway_id,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -141,6 +152,7 @@ impl<'a> ContentSteering<'a> {
             pathway_id: pathway_id.map(LazyAttribute::new).unwrap_or_default(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -207,15 +219,20 @@ impl<'a> ContentSteering<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&ContentSteeringAttributeList {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> ContentSteeringAttributeList<'_> {
+        ContentSteeringAttributeList {
             server_uri: self.server_uri().into(),
             pathway_id: self.pathway_id().map(Into::into),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(ContentSteering);
+serde_tag!(ContentSteering, ContentSteeringAttributeList);
 
 const SERVER_URI: &str = "SERVER-URI";
 const PATHWAY_ID: &str = "PATHWAY-ID";
@@ -1,12 +1,27 @@
+#[cfg(feature = "asset-list")]
 use crate::{
-    date::{self, DateTime},
-    error::{ParseTagValueError, UnrecognizedEnumerationError, ValidationError},
+    asset_list::{self, InterstitialAssets},
+    error::ResolveAssetsError,
+};
+use crate::{
+    date::{self, DateTime, DateTimeParser},
+    error::{
+        NumericAttributeError, ParseTagValueError, UnrecognizedEnumerationError, ValidationError,
+    },
     tag::{
         AttributeValue, UnknownTag, UnquotedAttributeValue,
-        hls::{EnumeratedString, EnumeratedStringList, LazyAttribute, TagName, into_inner_tag},
+        hls::{
+            EnumeratedString, EnumeratedStringList, LazyAttribute, TagName, into_inner_tag,
+            serde_tag,
+        },
     },
     utils::AsStaticCow,
 };
+#[cfg(feature = "scte35")]
+use crate::{
+    error::Scte35Error,
+    scte35::{self, SpliceInfoSection},
+};
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
@@ -313,15 +328,15 @@ impl<'a, 'b> PartialEq for InterstitialExtensionAttributes<'a, 'b> {
 }
 macro_rules! interstitial_getter {
     (@Doc = $doc:literal $name:ident @String) => {
-        interstitial_getter!(@Private $name, &str, @String, $doc);
+        interstitial_getter!(@Private $name, &'b str, @String, $doc);
     };
     (@Doc = $doc:literal $name:ident @EnumeratedString<$type:ty>) => {
-        interstitial_getter!(@Private $name, EnumeratedString<'_, $type>, @EnumeratedString, $doc);
+        interstitial_getter!(@Private $name, EnumeratedString<'b, $type>, @EnumeratedString, $doc);
     };
     (@Doc = $doc:literal $name:ident @EnumeratedStringList<$type:ty>) => {
         interstitial_getter!(
             @Private $name,
-            EnumeratedStringList<'_, $type>,
+            EnumeratedStringList<'b, $type>,
             @EnumeratedStringList,
             $doc
         );
@@ -424,6 +439,29 @@ impl<'a, 'b> InterstitialExtensionAttributes<'a, 'b> {
     interstitial_getter!(@Doc = "Corresponds to the `X-SKIP-CONTROL-DURATION` attribute."
         skip_control_duration @Number);
 
+    /// The asset of the interstitial, from whichever of the `X-ASSET-URI` or `X-ASSET-LIST`
+    /// attributes is present.
+    ///
+    /// The specification requires that exactly one of them is present; when both are, the
+    /// `X-ASSET-URI` is provided.
+    pub fn asset(&self) -> Option<InterstitialAsset<'b>> {
+        self.asset_uri()
+            .map(InterstitialAsset::Uri)
+            .or_else(|| self.asset_list().map(InterstitialAsset::List))
+    }
+
+    /// Resolves the assets of the interstitial, fetching and parsing the asset list when the
+    /// `X-ASSET-LIST` attribute is used (available with the `asset-list` feature).
+    ///
+    /// See [`asset_list::resolve_assets`].
+    #[cfg(feature = "asset-list")]
+    pub fn resolve_assets<E>(
+        &self,
+        fetch: impl FnOnce(&str) -> Result<Vec<u8>, E>,
+    ) -> Result<InterstitialAssets<'b>, ResolveAssetsError<E>> {
+        asset_list::resolve_assets(self, fetch)
+    }
+
     // The X-CONTENT-MAY-VARY is different enough that I didn't want to complicate the macro for it,
     // especialy considering it is just one case.
 
@@ -491,6 +529,16 @@ impl<'a, 'b> From<&'b [(Cow<'a, str>, LazyAttribute<'a, ExtensionAttributeValue<
         }
     }
 }
+/// The asset of an interstitial (see [`InterstitialExtensionAttributes::asset`]).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum InterstitialAsset<'a> {
+    /// The URI of the asset to play (the `X-ASSET-URI` attribute).
+    Uri(&'a str),
+    /// The URI of a JSON asset list that identifies the assets to play (the `X-ASSET-LIST`
+    /// attribute). The asset list can be parsed with the `asset_list` module (available with the
+    /// `asset-list` feature).
+    List(&'a str),
+}
 /// Corresponds to the attributes defined for HLS Interstitials in [Appnedix D].
 ///
 /// This provides mutable access to the properties. Setting or unsetting values here will set/unset
@@ -768,6 +816,7 @@ const X_TARGET_CLASS: &str = "X-TARGET-CLASS";
 ///
 /// See [`Daterange`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct DaterangeAttributeList<'a> {
     /// Corresponds to the `ID` attribute.
     ///
@@ -800,7 +849,7 @@ struct DaterangeAttributeList<'a> {
     /// Corresponds to `X-<extension-attribute>` attributes.
     ///
     /// See [`Daterange`] for a link to the HLS documentation for this attribute.
-    extension_attributes: HashMap<Cow<'a, str>, ExtensionAttributeValue<'a>>,
+    extension_attributes: Vec<(Cow<'a, str>, ExtensionAttributeValue<'a>)>,
     /// Corresponds to the `END-ON-NEXT` attribute.
     ///
     /// See [`Daterange`] for a link to the HLS documentation for this attribute.
@@ -882,36 +931,84 @@ impl<'a, IdStatus> DaterangeBuilder<'a, IdStatus> {
         self.attribute_list.start_date = Some(start_date);
         self
     }
+    /// Add the provided `start_date` to the attributes built into `Daterange`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_start_date_opt(self, start_date: Option<DateTime>) -> Self {
+        match start_date {
+            Some(start_date) => self.with_start_date(start_date),
+            None => self,
+        }
+    }
 
     /// Add the provided `class` to the attributes built into `Daterange`.
     pub fn with_class(mut self, class: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.class = Some(class.into());
         self
     }
+    /// Add the provided `class` to the attributes built into `Daterange`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_class_opt(self, class: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match class {
+            Some(class) => self.with_class(class),
+            None => self,
+        }
+    }
 
     /// Add the provided `cue` to the attributes built into `Daterange`.
     pub fn with_cue(mut self, cue: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.cue = Some(cue.into());
         self
     }
+    /// Add the provided `cue` to the attributes built into `Daterange`, if it is `Some` (otherwise
+    /// this is a no-op).
+    pub fn with_cue_opt(self, cue: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match cue {
+            Some(cue) => self.with_cue(cue),
+            None => self,
+        }
+    }
 
     /// Add the provided `end_date` to the attributes built into `Daterange`.
     pub fn with_end_date(mut self, end_date: DateTime) -> Self {
         self.attribute_list.end_date = Some(end_date);
         self
     }
+    /// Add the provided `end_date` to the attributes built into `Daterange`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_end_date_opt(self, end_date: Option<DateTime>) -> Self {
+        match end_date {
+            Some(end_date) => self.with_end_date(end_date),
+            None => self,
+        }
+    }
 
     /// Add the provided `duration` to the attributes built into `Daterange`.
     pub fn with_duration(mut self, duration: f64) -> Self {
         self.attribute_list.duration = Some(duration);
         self
     }
+    /// Add the provided `duration` to the attributes built into `Daterange`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_duration_opt(self, duration: Option<f64>) -> Self {
+        match duration {
+            Some(duration) => self.with_duration(duration),
+            None => self,
+        }
+    }
 
     /// Add the provided `planned_duration` to the attributes built into `Daterange`.
     pub fn with_planned_duration(mut self, planned_duration: f64) -> Self {
         self.attribute_list.planned_duration = Some(planned_duration);
         self
     }
+    /// Add the provided `planned_duration` to the attributes built into `Daterange`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_planned_duration_opt(self, planned_duration: Option<f64>) -> Self {
+        match planned_duration {
+            Some(planned_duration) => self.with_planned_duration(planned_duration),
+            None => self,
+        }
+    }
 
     /// Add the proivded extension attribute to the attributes built into `Daterange`.
     ///
@@ -936,28 +1033,28 @@ impl<'a, IdStatus> DaterangeBuilder<'a, IdStatus> {
     ///     )
     ///     .finish();
     ///
-    /// // The order of output of attributes may be mixed so we have to assert that it could be
-    /// // either order:
-    /// let expected_output_option_1 = concat!(
-    ///     "#EXT-X-DATERANGE:ID=\"id\",START-DATE=\"2025-08-02T21:03:00.000-05:00\",",
-    ///     "X-MESSAGE=\"Hello, World!\",X-ANSWER=42"
-    /// ).as_bytes();
-    /// let expected_output_option_2 = concat!(
-    ///     "#EXT-X-DATERANGE:ID=\"id\",START-DATE=\"2025-08-02T21:03:00.000-05:00\",",
-    ///     "X-ANSWER=42,X-MESSAGE=\"Hello, World!\""
-    /// ).as_bytes();
-    /// let inner = daterange.into_inner();
-    /// let bytes = inner.value();
-    /// assert!(bytes == expected_output_option_1 || bytes == expected_output_option_2);
+    /// // Attributes are written in the order that they were added:
+    /// assert_eq!(
+    ///     concat!(
+    ///         "#EXT-X-DATERANGE:ID=\"id\",START-DATE=\"2025-08-02T21:03:00.000-05:00\",",
+    ///         "X-MESSAGE=\"Hello, World!\",X-ANSWER=42"
+    ///     ).as_bytes(),
+    ///     daterange.into_inner().value()
+    /// );
     /// ```
+    ///
+    /// If an attribute with the same name has already been added then its value is replaced, while
+    /// keeping its original position.
     pub fn with_extension_attribute(
         mut self,
         extension_attribute_name: impl Into<Cow<'a, str>>,
         extension_attribute_value: ExtensionAttributeValue<'a>,
     ) -> Self {
-        self.attribute_list
-            .extension_attributes
-            .insert(extension_attribute_name.into(), extension_attribute_value);
+        insert_ordered(
+            &mut self.attribute_list.extension_attributes,
+            extension_attribute_name.into(),
+            extension_attribute_value,
+        );
         self
     }
 
@@ -965,11 +1062,21 @@ impl<'a, IdStatus> DaterangeBuilder<'a, IdStatus> {
     ///
     /// The attribute names SHOULD be prefixed with `X-`. The library does not validate that this is
     /// the case and unexpected results may occur if this is not followed.
-    pub fn with_extension_attributes(
+    ///
+    /// This replaces any extension attributes that have previously been added. The attributes are
+    /// written in the iteration order of the provided collection, so an ordered collection (such as
+    /// a `Vec` or an array) should be used if the output order matters.
+    pub fn with_extension_attributes<K>(
         mut self,
-        extension_attributes: HashMap<Cow<'a, str>, ExtensionAttributeValue<'a>>,
-    ) -> Self {
-        self.attribute_list.extension_attributes = extension_attributes;
+        extension_attributes: impl IntoIterator<Item = (K, ExtensionAttributeValue<'a>)>,
+    ) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+    {
+        self.attribute_list.extension_attributes.clear();
+        for (name, value) in extension_attributes {
+            self = self.with_extension_attribute(name, value);
+        }
         self
     }
 
@@ -984,18 +1091,42 @@ impl<'a, IdStatus> DaterangeBuilder<'a, IdStatus> {
         self.attribute_list.scte35_cmd = Some(scte35_cmd.into());
         self
     }
+    /// Add the provided `scte35_cmd` to the attributes that are built into the `Daterange`, if it
+    /// is `Some` (otherwise this is a no-op).
+    pub fn with_scte35_cmd_opt(self, scte35_cmd: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match scte35_cmd {
+            Some(scte35_cmd) => self.with_scte35_cmd(scte35_cmd),
+            None => self,
+        }
+    }
 
     /// Add the provided `scte35_out` to the attributes that are built into the `Daterange`.
     pub fn with_scte35_out(mut self, scte35_out: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.scte35_out = Some(scte35_out.into());
         self
     }
+    /// Add the provided `scte35_out` to the attributes that are built into the `Daterange`, if it
+    /// is `Some` (otherwise this is a no-op).
+    pub fn with_scte35_out_opt(self, scte35_out: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match scte35_out {
+            Some(scte35_out) => self.with_scte35_out(scte35_out),
+            None => self,
+        }
+    }
 
     /// Add the provided `scte35_in` to the attributes that are built into the `Daterange`.
     pub fn with_scte35_in(mut self, scte35_in: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.scte35_in = Some(scte35_in.into());
         self
     }
+    /// Add the provided `scte35_in` to the attributes that are built into the `Daterange`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_scte35_in_opt(self, scte35_in: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match scte35_in {
+            Some(scte35_in) => self.with_scte35_in(scte35_in),
+            None => self,
+        }
+    }
 }
 impl<'a> Default for DaterangeBuilder<'a, DaterangeIdNeedsToBeSet> {
     fn default() -> Self {
@@ -1020,8 +1151,9 @@ pub struct Daterange<'a> {
     scte35_cmd: LazyAttribute<'a, Cow<'a, str>>,
     scte35_out: LazyAttribute<'a, Cow<'a, str>>,
     scte35_in: LazyAttribute<'a, Cow<'a, str>>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Daterange<'a> {
@@ -1048,7 +1180,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Daterange<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut id = None;
         let mut start_date = LazyAttribute::None;
         let mut class = LazyAttribute::None;
@@ -1098,6 +1230,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Daterange<'a> {
             scte35_in,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -1138,6 +1271,7 @@ impl<'a> Daterange<'a> {
             scte35_in: scte35_in.map(LazyAttribute::new).unwrap_or_default(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -1245,27 +1379,37 @@ impl<'a> Daterange<'a> {
     /// Corresponds to the `DURATION` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_duration`] to distinguish an invalid value.
     pub fn duration(&self) -> Option<f64> {
-        match &self.duration {
-            LazyAttribute::UserDefined(d) => Some(*d),
-            LazyAttribute::Unparsed(v) => v
-                .unquoted()
-                .and_then(|d| d.try_as_decimal_floating_point().ok()),
-            LazyAttribute::None => None,
-        }
+        self.try_duration().ok().flatten()
+    }
+
+    /// Corresponds to the `DURATION` attribute.
+    ///
+    /// Unlike [`Self::duration`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_duration(&self) -> Result<Option<f64>, NumericAttributeError<'_>> {
+        self.duration.try_decimal_floating_point()
     }
 
     /// Corresponds to the `PLANNED-DURATION` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_planned_duration`] to distinguish an invalid value.
     pub fn planned_duration(&self) -> Option<f64> {
-        match &self.planned_duration {
-            LazyAttribute::UserDefined(d) => Some(*d),
-            LazyAttribute::Unparsed(v) => v
-                .unquoted()
-                .and_then(|d| d.try_as_decimal_floating_point().ok()),
-            LazyAttribute::None => None,
-        }
+        self.try_planned_duration().ok().flatten()
+    }
+
+    /// Corresponds to the `PLANNED-DURATION` attribute.
+    ///
+    /// Unlike [`Self::planned_duration`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_planned_duration(&self) -> Result<Option<f64>, NumericAttributeError<'_>> {
+        self.planned_duration.try_decimal_floating_point()
     }
 
     /// Corresponds to the `X-<extension-attribute>` attributes.
@@ -1297,17 +1441,42 @@ impl<'a> Daterange<'a> {
     /// }
     /// ```
     pub fn extension_attributes(&self) -> HashMap<&str, ExtensionAttributeValue<'_>> {
-        HashMap::from_iter(self.extension_attributes.iter().filter_map(|(key, value)| {
-            match value {
-                LazyAttribute::UserDefined(a) => {
-                    Some((key.as_ref(), ExtensionAttributeValue::from(a)))
-                }
-                LazyAttribute::Unparsed(v) => ExtensionAttributeValue::try_from(*v)
-                    .ok()
-                    .map(|v| (key.as_ref(), v)),
-                LazyAttribute::None => None,
-            }
-        }))
+        HashMap::from_iter(self.ordered_extension_attributes())
+    }
+
+    /// Corresponds to the `X-<extension-attribute>` attributes, in the order in which they appear.
+    ///
+    /// NOTE: prior to draft 18 these were known as `X-<client-attribute>`.
+    ///
+    /// This is the same as [`Self::extension_attributes`]; however, the order of the attributes is
+    /// preserved (the order in which they were parsed, or added). Mutating an existing attribute
+    /// (via [`Self::set_extension_attribute`]) does not change its position. For example:
+    /// ```
+    /// # use quick_m3u8::{
+    /// # Reader, HlsLine, config::ParsingOptions, tag::KnownTag,
+    /// # tag::hls::{self, ExtensionAttributeValue}
+    /// # };
+    /// let daterange =
+    ///     r#"#EXT-X-DATERANGE:ID="id",START-DATE="2025-08-02T21:31:00Z",X-EX-B=42,X-EX-A="A""#;
+    /// let mut reader = Reader::from_str(daterange, ParsingOptions::default());
+    /// match reader.read_line() {
+    ///     Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Daterange(mut tag))))) => {
+    ///         tag.set_extension_attribute("X-EX-B", ExtensionAttributeValue::QuotedString("B".into()));
+    ///         assert_eq!(
+    ///             vec![
+    ///                 ("X-EX-B", ExtensionAttributeValue::QuotedString("B".into())),
+    ///                 ("X-EX-A", ExtensionAttributeValue::QuotedString("A".into())),
+    ///             ],
+    ///             tag.ordered_extension_attributes().collect::<Vec<_>>()
+    ///         );
+    ///     }
+    ///     r => panic!("unexpected result {r:?}")
+    /// }
+    /// ```
+    pub fn ordered_extension_attributes(
+        &self,
+    ) -> impl Iterator<Item = (&str, ExtensionAttributeValue<'_>)> {
+        iter_extension_attributes(&self.extension_attributes)
     }
 
     /// Corresponds to one of the `X-<extension-attribute>` attributes (keyed by `name`).
@@ -1538,6 +1707,33 @@ impl<'a> Daterange<'a> {
         }
     }
 
+    /// The `SCTE35-CMD` attribute parsed as a SCTE-35 `splice_info_section` (available with the
+    /// `scte35` feature).
+    ///
+    /// This is `None` when the attribute is not present.
+    #[cfg(feature = "scte35")]
+    pub fn scte35_cmd_parsed(&self) -> Option<Result<SpliceInfoSection, Scte35Error>> {
+        self.scte35_cmd().map(scte35::parse_hex)
+    }
+
+    /// The `SCTE35-OUT` attribute parsed as a SCTE-35 `splice_info_section` (available with the
+    /// `scte35` feature).
+    ///
+    /// This is `None` when the attribute is not present.
+    #[cfg(feature = "scte35")]
+    pub fn scte35_out_parsed(&self) -> Option<Result<SpliceInfoSection, Scte35Error>> {
+        self.scte35_out().map(scte35::parse_hex)
+    }
+
+    /// The `SCTE35-IN` attribute parsed as a SCTE-35 `splice_info_section` (available with the
+    /// `scte35` feature).
+    ///
+    /// This is `None` when the attribute is not present.
+    #[cfg(feature = "scte35")]
+    pub fn scte35_in_parsed(&self) -> Option<Result<SpliceInfoSection, Scte35Error>> {
+        self.scte35_in().map(scte35::parse_hex)
+    }
+
     // === SETTERS ===
 
     /// Sets the `ID` attribute.
@@ -1673,6 +1869,9 @@ impl<'a> Daterange<'a> {
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
     ///
+    /// If the attribute already exists then its value is replaced in place (the order of the
+    /// extension attributes is preserved), otherwise it is added after the existing attributes.
+    ///
     /// Note that this silently fails if the name provided does not begin with `"X-"`. This is
     /// likely to change in the future as per issue [#1].
     ///
@@ -1686,13 +1885,15 @@ impl<'a> Daterange<'a> {
         if !name.starts_with("X-") {
             return;
         }
-        self.extension_attributes.retain(|(k, _)| *k != name);
-        self.extension_attributes
-            .push((name, LazyAttribute::new(value)));
+        insert_ordered(
+            &mut self.extension_attributes,
+            name,
+            LazyAttribute::new(value),
+        );
         self.output_line_is_dirty = true;
     }
 
-    /// Unsets an extension attribute (`X-<extension-attribute>`) (removes it from the `HashMap`).
+    /// Unsets an extension attribute (`X-<extension-attribute>`) (removes it from the list).
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
     ///
@@ -1763,8 +1964,27 @@ impl<'a> Daterange<'a> {
         self.output_line_is_dirty = true;
     }
 
+    // Used by the Reader when a `DateTimeParser` is set on the `ParsingOptions`. The dates are
+    // parsed up front (rather than when requested), as the parser is not kept by the tag, and a
+    // date that the parser rejects is treated as not provided. The output line is unaffected.
+    pub(crate) fn parse_dates_with(&mut self, parser: &dyn DateTimeParser) {
+        for date in [&mut self.start_date, &mut self.end_date] {
+            if let LazyAttribute::Unparsed(value) = date {
+                match value.quoted().map(|s| parser.parse(s)) {
+                    Some(Ok(parsed)) => *date = LazyAttribute::UserDefined(parsed),
+                    _ => date.unset(),
+                }
+            }
+        }
+    }
+
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&DaterangeAttributeList {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> DaterangeAttributeList<'_> {
+        DaterangeAttributeList {
             id: self.id().into(),
             class: self.class().map(|x| x.into()),
             start_date: self.start_date(),
@@ -1772,27 +1992,31 @@ impl<'a> Daterange<'a> {
             end_date: self.end_date(),
             duration: self.duration(),
             planned_duration: self.planned_duration(),
-            extension_attributes: {
-                let mut map = HashMap::new();
-                for (key, value) in self.extension_attributes() {
-                    map.insert(Cow::Borrowed(key), value);
-                }
-                map
-            },
+            extension_attributes: self
+                .ordered_extension_attributes()
+                .map(|(key, value)| (Cow::Borrowed(key), value))
+                .collect(),
             scte35_cmd: self.scte35_cmd().map(|x| x.into()),
             scte35_out: self.scte35_out().map(|x| x.into()),
             scte35_in: self.scte35_in().map(|x| x.into()),
             end_on_next: self.end_on_next(),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(Daterange);
+serde_tag!(Daterange, DaterangeAttributeList);
 
 /// Provides the value for an extension attribute (`X-<extension-attribute>` as defined in the
 /// EXT-X-DATERANGE tag specification).
+///
+/// This is also used for the attributes that are not defined by the specification on tags that
+/// preserve them (see [`Media::unknown_attributes`] and [`SessionData::unknown_attributes`]).
+///
+/// [`Media::unknown_attributes`]: crate::tag::hls::Media::unknown_attributes
+/// [`SessionData::unknown_attributes`]: crate::tag::hls::SessionData::unknown_attributes
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExtensionAttributeValue<'a> {
     /// A quoted string value.
     QuotedString(Cow<'a, str>),
@@ -1866,6 +2090,60 @@ fn is_hexadecimal_sequence(s: &str) -> bool {
     true
 }
 
+// The helpers below are shared by the tags that preserve attributes that the library does not
+// otherwise define (`X-<extension-attribute>` on `Daterange`, and vendor attributes on `Media` and
+// `SessionData`). The attributes are stored in a `Vec` so that the order in which they were parsed
+// (or added) is maintained on output.
+
+// Sets the attribute in place if it already exists (so that its position is preserved), otherwise
+// appends it to the end.
+pub(super) fn insert_ordered<'a, T>(
+    attributes: &mut Vec<(Cow<'a, str>, T)>,
+    name: Cow<'a, str>,
+    value: T,
+) {
+    match attributes.iter_mut().find(|(key, _)| *key == name) {
+        Some((_, existing)) => *existing = value,
+        None => attributes.push((name, value)),
+    }
+}
+
+pub(super) fn iter_extension_attributes<'b>(
+    attributes: &'b [(Cow<'_, str>, LazyAttribute<'_, ExtensionAttributeValue<'_>>)],
+) -> impl Iterator<Item = (&'b str, ExtensionAttributeValue<'b>)> {
+    attributes.iter().filter_map(|(key, value)| match value {
+        LazyAttribute::UserDefined(a) => Some((key.as_ref(), ExtensionAttributeValue::from(a))),
+        LazyAttribute::Unparsed(v) => ExtensionAttributeValue::try_from(*v)
+            .ok()
+            .map(|v| (key.as_ref(), v)),
+        LazyAttribute::None => None,
+    })
+}
+
+pub(super) fn push_extension_attributes(
+    line: &mut String,
+    attributes: &[(Cow<'_, str>, ExtensionAttributeValue<'_>)],
+) {
+    for (key, value) in attributes {
+        line.push(',');
+        line.push_str(key);
+        line.push('=');
+        match value {
+            ExtensionAttributeValue::HexadecimalSequence(s) => {
+                line.push_str(s);
+            }
+            ExtensionAttributeValue::QuotedString(s) => {
+                line.push('"');
+                line.push_str(s);
+                line.push('"');
+            }
+            ExtensionAttributeValue::SignedDecimalFloatingPoint(d) => {
+                line.push_str(format!("{d}").as_str());
+            }
+        };
+    }
+}
+
 const ID: &str = "ID";
 const CLASS: &str = "CLASS";
 const START_DATE: &str = "START-DATE";
@@ -1913,24 +2191,7 @@ fn calculate_line(attribute_list: &DaterangeAttributeList) -> Vec<u8> {
     if let Some(planned_duration) = planned_duration {
         line.push_str(format!(",{PLANNED_DURATION}={planned_duration}").as_str());
     }
-    for (key, value) in extension_attributes {
-        line.push(',');
-        line.push_str(key);
-        line.push('=');
-        match value {
-            ExtensionAttributeValue::HexadecimalSequence(s) => {
-                line.push_str(s);
-            }
-            ExtensionAttributeValue::QuotedString(s) => {
-                line.push('"');
-                line.push_str(s);
-                line.push('"');
-            }
-            ExtensionAttributeValue::SignedDecimalFloatingPoint(d) => {
-                line.push_str(format!("{d}").as_str());
-            }
-        };
-    }
+    push_extension_attributes(&mut line, extension_attributes);
     if let Some(scte35_cmd) = scte35_cmd {
         line.push_str(format!(",{SCTE35_CMD}={scte35_cmd}").as_str());
     }
@@ -2012,46 +2273,14 @@ mod tests {
                 ExtensionAttributeValue::HexadecimalSequence("0xABCD".into()),
             )
             .finish();
-        // Client attributes can come in any order (due to it being a HashMap) so we need to be a
-        // little more creative in validating the tag format.
-        let tag_inner = tag.into_inner();
-        let tag_as_bytes = tag_inner.value();
-        let mut found_a = false;
-        let mut found_b = false;
-        let mut found_c = false;
-        for (index, split) in tag_as_bytes.split(|b| b == &b',').enumerate() {
-            match index {
-                0 => assert_eq!(b"#EXT-X-DATERANGE:ID=\"some-id\"", split),
-                1 => assert_eq!(b"START-DATE=\"2025-06-14T23:41:42.000-05:00\"", split),
-                2 | 3 | 4 => {
-                    if split.starts_with(b"X-COM-EXAMPLE-A") {
-                        if found_a {
-                            panic!("Already found A")
-                        }
-                        found_a = true;
-                        assert_eq!(b"X-COM-EXAMPLE-A=\"Example A\"", split);
-                    } else if split.starts_with(b"X-COM-EXAMPLE-B") {
-                        if found_b {
-                            panic!("Already found B")
-                        }
-                        found_b = true;
-                        assert_eq!(b"X-COM-EXAMPLE-B=42", split);
-                    } else if split.starts_with(b"X-COM-EXAMPLE-C") {
-                        if found_c {
-                            panic!("Already found C")
-                        }
-                        found_c = true;
-                        assert_eq!(b"X-COM-EXAMPLE-C=0xABCD", split);
-                    } else {
-                        panic!("Unexpected attribute at index {}", index);
-                    }
-                }
-                _ => panic!("Too many attributes"),
-            }
-        }
-        assert!(found_a);
-        assert!(found_b);
-        assert!(found_c);
+        assert_eq!(
+            concat!(
+                "#EXT-X-DATERANGE:ID=\"some-id\",START-DATE=\"2025-06-14T23:41:42.000-05:00\",",
+                "X-COM-EXAMPLE-A=\"Example A\",X-COM-EXAMPLE-B=42,X-COM-EXAMPLE-C=0xABCD"
+            )
+            .as_bytes(),
+            tag.into_inner().value()
+        );
     }
 
     #[test]
@@ -2091,6 +2320,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mutating_extension_attributes_should_preserve_order() {
+        let mut reader = crate::Reader::from_str(
+            "#EXT-X-DATERANGE:ID=\"id\",X-C=\"c\",X-A=0x0A,X-B=1",
+            crate::config::ParsingOptions::default(),
+        );
+        let Ok(Some(crate::HlsLine::KnownTag(crate::tag::KnownTag::Hls(
+            crate::tag::hls::Tag::Daterange(mut daterange),
+        )))) = reader.read_line()
+        else {
+            panic!("expected daterange");
+        };
+        daterange.set_extension_attribute("X-A", ExtensionAttributeValue::QuotedString("a".into()));
+        daterange.set_extension_attribute("X-D", ExtensionAttributeValue::QuotedString("d".into()));
+        daterange.unset_extension_attribute("X-B");
+        assert_eq!(
+            vec![
+                ("X-C", ExtensionAttributeValue::QuotedString("c".into())),
+                ("X-A", ExtensionAttributeValue::QuotedString("a".into())),
+                ("X-D", ExtensionAttributeValue::QuotedString("d".into())),
+            ],
+            daterange.ordered_extension_attributes().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            b"#EXT-X-DATERANGE:ID=\"id\",X-C=\"c\",X-A=\"a\",X-D=\"d\"",
+            daterange.into_inner().value()
+        );
+    }
+
     #[test]
     fn mutating_cue_works_as_expected() {
         let mut daterange = Daterange::builder()
@@ -2130,6 +2388,10 @@ mod tests {
 
         assert_eq!(None, interstitial_attrs.asset_uri());
         assert_eq!(Some("ad-1.json"), interstitial_attrs.asset_list());
+        assert_eq!(
+            Some(InterstitialAsset::List("ad-1.json")),
+            interstitial_attrs.asset()
+        );
         assert_eq!(Some(10.0), interstitial_attrs.resume_offset());
         assert_eq!(Some(60.0), interstitial_attrs.playout_limit());
         assert_eq!(
@@ -2202,6 +2464,7 @@ mod tests {
             .expect("should have interstitials defined");
         assert_eq!(Some("ad-1.m3u8"), attrs.asset_uri());
         assert_eq!(None, attrs.asset_list());
+        assert_eq!(Some(InterstitialAsset::Uri("ad-1.m3u8")), attrs.asset());
         assert_eq!(Some(70.0), attrs.resume_offset());
         assert_eq!(Some(50.0), attrs.playout_limit());
         assert_eq!(Some(EnumeratedStringList::from([Snap::Out])), attrs.snap());
@@ -2223,6 +2486,39 @@ mod tests {
         assert_eq!(Some("skippy"), attrs.skip_control_label_id());
     }
 
+    #[cfg(feature = "scte35")]
+    #[test]
+    fn scte35_attributes_should_be_parsed_into_splice_info_section() {
+        let daterange_line = concat!(
+            "#EXT-X-DATERANGE:ID=\"splice-1\",START-DATE=\"2025-01-01T00:00:00Z\",",
+            "SCTE35-OUT=0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF500000000000A",
+            "0008435545490000013562DBA30A,SCTE35-IN=\"0xFC\""
+        );
+        let tag = crate::custom_parsing::tag::parse(daterange_line)
+            .expect("parsing should succeed")
+            .parsed;
+        let daterange = Daterange::try_from(tag).expect("tag should be valid daterange");
+
+        assert!(daterange.scte35_cmd_parsed().is_none());
+        let section = daterange
+            .scte35_out_parsed()
+            .expect("SCTE35-OUT should be defined")
+            .expect("SCTE35-OUT should be valid");
+        let crate::scte35::SpliceCommand::SpliceInsert(splice_insert) = section.splice_command
+        else {
+            panic!(
+                "expected splice_insert but got {:?}",
+                section.splice_command
+            );
+        };
+        assert_eq!(0x4800008F, splice_insert.splice_event_id);
+        assert!(splice_insert.out_of_network_indicator);
+        assert_eq!(
+            Some(Err(Scte35Error::UnexpectedEnd)),
+            daterange.scte35_in_parsed()
+        );
+    }
+
     #[test]
     fn preload_attributes_are_parsed_correctly_and_mutable() {
         let daterange_line = concat!(
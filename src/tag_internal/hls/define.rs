@@ -2,10 +2,13 @@ use crate::{
     error::{ParseTagValueError, ValidationError},
     tag::{
         IntoInnerTag, UnknownTag,
-        hls::{TagInner, into_inner_tag},
+        hls::{TagInner, into_inner_tag, serde_tag},
     },
 };
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+};
 
 /// Corresponds to the `#EXT-X-DEFINE` tag where `NAME` and `VALUE` are used.
 ///
@@ -14,8 +17,9 @@ use std::borrow::Cow;
 pub struct Name<'a> {
     name: Cow<'a, str>,
     value: Cow<'a, str>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Name<'a> {
@@ -35,6 +39,7 @@ impl<'a> Name<'a> {
             value,
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -78,7 +83,27 @@ impl<'a> Name<'a> {
     }
 }
 
-into_inner_tag!(Name);
+into_inner_tag!(Name, |tag| match Define::try_from(tag) {
+    Ok(Define::Name(tag)) => Some(tag),
+    _ => None,
+});
+serde_tag!(
+    Name,
+    NameAttributeList,
+    |tag| NameAttributeList {
+        name: Cow::Borrowed(tag.name()),
+        value: Cow::Borrowed(tag.value()),
+    },
+    |attribute_list| Name::new(attribute_list.name, attribute_list.value)
+);
+
+// The representation of the attribute list used with the `serde` feature.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct NameAttributeList<'a> {
+    name: Cow<'a, str>,
+    value: Cow<'a, str>,
+}
 
 /// Corresponds to the `#EXT-X-DEFINE` tag where `IMPORT` is used.
 ///
@@ -86,8 +111,9 @@ into_inner_tag!(Name);
 #[derive(Debug, Clone)]
 pub struct Import<'a> {
     import: Cow<'a, str>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Import<'a> {
@@ -105,6 +131,7 @@ impl<'a> Import<'a> {
             import,
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -133,7 +160,11 @@ impl<'a> Import<'a> {
     }
 }
 
-into_inner_tag!(Import);
+into_inner_tag!(Import, |tag| match Define::try_from(tag) {
+    Ok(Define::Import(tag)) => Some(tag),
+    _ => None,
+});
+serde_tag!(Import, Cow<str>, |tag| tag.import(), Import::new);
 
 /// Corresponds to the `#EXT-X-DEFINE` tag where `QUERYPARAM` is used.
 ///
@@ -141,8 +172,9 @@ into_inner_tag!(Import);
 #[derive(Debug, Clone)]
 pub struct Queryparam<'a> {
     queryparam: Cow<'a, str>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Queryparam<'a> {
@@ -160,6 +192,7 @@ impl<'a> Queryparam<'a> {
             queryparam,
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -188,12 +221,22 @@ impl<'a> Queryparam<'a> {
     }
 }
 
-into_inner_tag!(Queryparam);
+into_inner_tag!(Queryparam, |tag| match Define::try_from(tag) {
+    Ok(Define::Queryparam(tag)) => Some(tag),
+    _ => None,
+});
+serde_tag!(
+    Queryparam,
+    Cow<str>,
+    |tag| tag.queryparam(),
+    Queryparam::new
+);
 
 /// Corresponds to the `#EXT-X-DEFINE` tag.
 ///
 /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.2.3>
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Define<'a> {
     /// The `#EXT-X-DEFINE` tag is being used with `NAME` and `VALUE`.
     Name(Name<'a>),
@@ -210,7 +253,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Define<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut name = None;
         let mut value = None;
         let mut import = None;
@@ -231,6 +274,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Define<'a> {
                     value: Cow::Borrowed(value),
                     output_line: Cow::Borrowed(tag.original_input),
                     output_line_is_dirty: false,
+                    comment: None,
                 }))
             } else {
                 Err(super::ValidationError::MissingRequiredAttribute(VALUE))
@@ -240,12 +284,14 @@ impl<'a> TryFrom<UnknownTag<'a>> for Define<'a> {
                 import: Cow::Borrowed(import),
                 output_line: Cow::Borrowed(tag.original_input),
                 output_line_is_dirty: false,
+                comment: None,
             }))
         } else if let Some(queryparam) = queryparam {
             Ok(Self::Queryparam(Queryparam {
                 queryparam: Cow::Borrowed(queryparam),
                 output_line: Cow::Borrowed(tag.original_input),
                 output_line_is_dirty: false,
+                comment: None,
             }))
         } else {
             Err(super::ValidationError::MissingRequiredAttribute(NAME))
@@ -309,6 +355,16 @@ impl<'a> Define<'a> {
         }
     }
 
+    /// Indicates whether the tag has been modified since it was parsed or constructed (see
+    /// [`crate::tag::hls::Tag::is_dirty`]).
+    pub fn is_dirty(&self) -> bool {
+        match self {
+            Self::Name(name) => name.is_dirty(),
+            Self::Import(import) => import.is_dirty(),
+            Self::Queryparam(queryparam) => queryparam.is_dirty(),
+        }
+    }
+
     /// Sets both `NAME` and `VALUE` attributes.
     ///
     /// This will unset any `IMPORT` or `QUERYPARAM` attributes.
@@ -341,6 +397,17 @@ impl<'a> Define<'a> {
     }
 }
 
+impl<'a> Define<'a> {
+    // Used by Tag::into_unknown.
+    pub(crate) fn borrowed_output_line(&self) -> Option<&'a [u8]> {
+        match self {
+            Define::Name(name) => name.borrowed_output_line(),
+            Define::Import(import) => import.borrowed_output_line(),
+            Define::Queryparam(queryparam) => queryparam.borrowed_output_line(),
+        }
+    }
+}
+
 impl<'a> IntoInnerTag<'a> for Define<'a> {
     fn into_inner(self) -> TagInner<'a> {
         match self {
@@ -351,6 +418,16 @@ impl<'a> IntoInnerTag<'a> for Define<'a> {
     }
 }
 
+impl Display for Define<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Define::Name(name) => name.fmt(f),
+            Define::Import(import) => import.fmt(f),
+            Define::Queryparam(queryparam) => queryparam.fmt(f),
+        }
+    }
+}
+
 const NAME: &str = "NAME";
 const VALUE: &str = "VALUE";
 const IMPORT: &str = "IMPORT";
@@ -1,6 +1,9 @@
 use crate::{
     error::{ParseTagValueError, ValidationError},
-    tag::{UnknownTag, hls::into_inner_tag},
+    tag::{
+        UnknownTag,
+        hls::{into_inner_tag, serde_tag},
+    },
 };
 use std::borrow::Cow;
 
@@ -10,8 +13,9 @@ use std::borrow::Cow;
 #[derive(Debug, Clone)]
 pub struct DiscontinuitySequence<'a> {
     discontinuity_sequence: u64,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for DiscontinuitySequence<'a> {
@@ -32,6 +36,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for DiscontinuitySequence<'a> {
             discontinuity_sequence,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -44,6 +49,7 @@ impl<'a> DiscontinuitySequence<'a> {
             discontinuity_sequence,
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -69,6 +75,12 @@ impl<'a> DiscontinuitySequence<'a> {
 }
 
 into_inner_tag!(DiscontinuitySequence);
+serde_tag!(
+    DiscontinuitySequence,
+    u64,
+    |tag| tag.discontinuity_sequence(),
+    DiscontinuitySequence::new
+);
 
 fn calculate_line(discontinuity_sequence: u64) -> Vec<u8> {
     format!("#EXT-X-DISCONTINUITY-SEQUENCE:{discontinuity_sequence}").into_bytes()
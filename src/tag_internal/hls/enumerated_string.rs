@@ -46,7 +46,7 @@ impl<T> GetKnown<T> for Option<EnumeratedString<'_, T>> {
     fn known(self) -> Option<T> {
         match self {
             Some(EnumeratedString::Known(t)) => Some(t),
-            Some(EnumeratedString::Unknown(_)) | None => todo!(),
+            Some(EnumeratedString::Unknown(_)) | None => None,
         }
     }
 }
@@ -126,8 +126,10 @@ where
 ///
 /// The library aims to provide a pseudo-set-like API to working with enumerated-string-lists. You
 /// can check for presence of values with [`Self::contains`], if the list is empty with
-/// [`Self::is_empty`], add items via [`Self::insert`], and remove via [`Self::remove`]. We also
-/// provide an iterator through the members via the [`Self::iter`] method.
+/// [`Self::is_empty`], add items via [`Self::insert`] (or [`Extend::extend`]), and remove via
+/// [`Self::remove`], [`Self::retain`], or [`Self::clear`]. Two lists can be compared via
+/// [`Self::intersection`] and [`Self::difference`]. We also provide an iterator through the members
+/// via the [`Self::iter`] method.
 ///
 /// To make lists more convenient to use, most methods where they are needed accept
 /// `impl Into<Cow<str>>` as the type, and all library provided `T` allow `EnumeratedStringList<T>`
@@ -203,7 +205,9 @@ where
             EnumeratedString::Unknown(s) => s,
             EnumeratedString::Known(t) => &t.as_cow(),
         };
-        self.inner.split(',').any(|item| item == value_str)
+        self.inner
+            .split_terminator(',')
+            .any(|item| item == value_str)
     }
 
     /// Indicates whether the list is empty (i.e. empty string). For example:
@@ -251,16 +255,26 @@ where
     /// assert_eq!(2, list.iter().count());
     /// assert!(list.contains("UNKNOWN"));
     /// ```
+    /// False is also returned (and the list is left unchanged) when the value cannot be represented
+    /// in the list; that is, when it is empty or contains `,`.
+    /// ```
+    /// # use quick_m3u8::tag::hls::{EnumeratedStringList, Cue};
+    /// let mut list = EnumeratedStringList::from([Cue::Pre]);
+    /// assert!(!list.insert("ONCE,POST"));
+    /// assert!(!list.insert(""));
+    /// assert_eq!("PRE", list.as_ref());
+    /// ```
     pub fn insert<Item: Into<EnumeratedString<'a, T>>>(&mut self, value: Item) -> bool {
         let value = value.into();
-        if self.contains(value) {
+        let value_str = value.as_cow();
+        if value_str.is_empty() || value_str.contains(',') || self.contains(value) {
             return false;
         }
         let mut new_inner = std::mem::take(&mut self.inner).to_string();
         if !new_inner.is_empty() {
             new_inner.push(',');
         }
-        new_inner.push_str(&value.as_cow());
+        new_inner.push_str(&value_str);
         self.inner = Cow::Owned(new_inner);
         true
     }
@@ -306,28 +320,15 @@ where
         }
         let value = &value.as_cow();
         let mut new_inner = String::new();
-        let old_inner = std::mem::take(&mut self.inner).to_string();
-        let mut iter = old_inner.split(',');
-        match iter.next() {
-            Some(v) if v == value => {
-                if let Some(next) = iter.next() {
-                    new_inner.push_str(next);
-                    for item in iter.by_ref() {
-                        new_inner.push(',');
-                        new_inner.push_str(item);
-                    }
-                }
+        for item in self
+            .inner
+            .split_terminator(',')
+            .filter(|item| item != value)
+        {
+            if !new_inner.is_empty() {
+                new_inner.push(',');
             }
-            Some(v) => {
-                new_inner.push_str(v);
-                for item in iter {
-                    if item != value {
-                        new_inner.push(',');
-                        new_inner.push_str(item);
-                    }
-                }
-            }
-            None => (), // This isn't really possible since we check for contains above
+            new_inner.push_str(item);
         }
         self.inner = Cow::Owned(new_inner);
         true
@@ -355,6 +356,104 @@ where
     pub fn to_owned<'b>(&self) -> EnumeratedStringList<'b, T> {
         EnumeratedStringList::from(self.to_string())
     }
+
+    /// Removes all items from the list.
+    /// ```
+    /// # use quick_m3u8::tag::hls::{EnumeratedStringList, Cue};
+    /// let mut list = EnumeratedStringList::from([Cue::Pre, Cue::Once]);
+    /// list.clear();
+    /// assert!(list.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.inner = Cow::Borrowed("");
+    }
+
+    /// Provides a new list containing the items of this list that are also in `other`. The items
+    /// are kept in the order of this list. This is useful for reconciling values between playlists,
+    /// for example, finding the `REQ-VIDEO-LAYOUT` values that two variants have in common:
+    /// ```
+    /// # use quick_m3u8::tag::hls::{EnumeratedStringList, VideoChannelSpecifier};
+    /// let a = EnumeratedStringList::from([VideoChannelSpecifier::Stereo, VideoChannelSpecifier::Mono]);
+    /// let b = EnumeratedStringList::from([VideoChannelSpecifier::Mono]);
+    /// assert_eq!("CH-MONO", a.intersection(&b).as_ref());
+    /// ```
+    /// Unknown values are compared by their string value, just as known values are.
+    pub fn intersection<'b>(
+        &self,
+        other: &EnumeratedStringList<'_, T>,
+    ) -> EnumeratedStringList<'b, T> {
+        self.filtered(|item| other.contains_str(item))
+    }
+
+    /// Provides a new list containing the items of this list that are not in `other`. The items are
+    /// kept in the order of this list.
+    /// ```
+    /// # use quick_m3u8::tag::hls::{EnumeratedStringList, Cue};
+    /// let a = EnumeratedStringList::from(["PRE", "POST", "UNKNOWN"]);
+    /// let b = EnumeratedStringList::from([Cue::Post]);
+    /// assert_eq!("PRE,UNKNOWN", a.difference(&b).as_ref());
+    /// ```
+    pub fn difference<'b>(
+        &self,
+        other: &EnumeratedStringList<'_, T>,
+    ) -> EnumeratedStringList<'b, T> {
+        self.filtered(|item| !other.contains_str(item))
+    }
+
+    fn contains_str(&self, value: &str) -> bool {
+        self.inner.split_terminator(',').any(|item| item == value)
+    }
+
+    fn filtered<'b>(&self, mut keep: impl FnMut(&str) -> bool) -> EnumeratedStringList<'b, T> {
+        let mut inner = String::new();
+        for item in self.inner.split_terminator(',').filter(|item| keep(item)) {
+            if !inner.is_empty() {
+                inner.push(',');
+            }
+            inner.push_str(item);
+        }
+        EnumeratedStringList::from(inner)
+    }
+}
+
+impl<T> EnumeratedStringList<'_, T>
+where
+    T: for<'b> TryFrom<&'b str, Error = UnrecognizedEnumerationError<'b>>,
+{
+    /// Retains only the items for which the predicate returns `true`. The list is left untouched
+    /// (and so not re-allocated) when every item is retained.
+    /// ```
+    /// # use quick_m3u8::tag::hls::{EnumeratedStringList, EnumeratedString, Cue};
+    /// let mut list = EnumeratedStringList::<Cue>::from(["PRE", "UNKNOWN", "ONCE"]);
+    /// // Drop the values that are not known to the library.
+    /// list.retain(|item| matches!(item, EnumeratedString::Known(_)));
+    /// assert_eq!("PRE,ONCE", list.as_ref());
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(EnumeratedString<'_, T>) -> bool) {
+        // Only start building a new list once the first item is rejected.
+        let mut retained: Option<String> = None;
+        let mut offset = 0;
+        for item in self.inner.split_terminator(',') {
+            let keep = f(EnumeratedString::from(item));
+            match (&mut retained, keep) {
+                (None, true) | (Some(_), false) => (),
+                (None, false) => {
+                    let prefix = &self.inner[..offset];
+                    retained = Some(prefix.strip_suffix(',').unwrap_or(prefix).to_string());
+                }
+                (Some(retained), true) => {
+                    if !retained.is_empty() {
+                        retained.push(',');
+                    }
+                    retained.push_str(item);
+                }
+            }
+            offset += item.len() + 1;
+        }
+        if let Some(retained) = retained {
+            self.inner = Cow::Owned(retained);
+        }
+    }
 }
 
 impl<T> Display for EnumeratedStringList<'_, T> {
@@ -425,6 +524,25 @@ where
     }
 }
 
+impl<'a, T, S> Extend<S> for EnumeratedStringList<'a, T>
+where
+    T: AsStaticCow + Copy + Display,
+    S: Into<EnumeratedString<'a, T>>,
+{
+    /// Inserts each item of the iterator, skipping those already in the list (see [`Self::insert`]).
+    /// ```
+    /// # use quick_m3u8::tag::hls::{EnumeratedStringList, Cue};
+    /// let mut list = EnumeratedStringList::from([Cue::Pre]);
+    /// list.extend([Cue::Pre, Cue::Post]);
+    /// assert_eq!("PRE,POST", list.as_ref());
+    /// ```
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
 impl<'a, T> EnumeratedStringList<'a, T>
 where
     T: TryFrom<&'a str, Error = UnrecognizedEnumerationError<'a>>,
@@ -506,6 +624,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn get_known_should_be_none_for_unknown_or_missing_values() {
+        assert_eq!(
+            Some(TestEnum::One),
+            Some(EnumeratedString::Known(TestEnum::One)).known()
+        );
+        assert_eq!(
+            None,
+            Some(EnumeratedString::<TestEnum>::Unknown("CUSTOM")).known()
+        );
+        assert_eq!(None, None::<EnumeratedString<TestEnum>>.known());
+    }
+
     #[test]
     fn enumerated_string_fmt_correctly() {
         assert_eq!("ONE", format!("{}", EnumeratedString::Known(TestEnum::One)));
@@ -709,4 +840,80 @@ mod tests {
             iter.collect()
         );
     }
+
+    #[test]
+    fn enumerated_string_list_retain_keeps_borrowed_inner_when_nothing_removed() {
+        let mut list = EnumeratedStringList::<TestEnum>::from("ONE,UNKNOWN");
+        list.retain(|_| true);
+        assert!(matches!(list.inner, Cow::Borrowed("ONE,UNKNOWN")));
+    }
+
+    #[test]
+    fn enumerated_string_list_retain_removes_rejected_items() {
+        for (inner, expected) in [
+            ("ONE,TWO,THREE", "ONE,THREE"),
+            ("TWO,ONE,THREE", "ONE,THREE"),
+            ("ONE,THREE,TWO", "ONE,THREE"),
+            ("TWO", ""),
+        ] {
+            let mut list = EnumeratedStringList::<TestEnum>::from(inner);
+            list.retain(|item| item != EnumeratedString::Known(TestEnum::Two));
+            assert_eq!(expected, list.as_ref(), "failed for `{inner}`");
+        }
+    }
+
+    #[test]
+    fn enumerated_string_list_set_operations_compare_unknown_values_by_string() {
+        let a = EnumeratedStringList::<TestEnum>::from("ONE,UNKNOWN,THREE");
+        let b = EnumeratedStringList::<TestEnum>::from("THREE,UNKNOWN,TWO");
+        assert_eq!("UNKNOWN,THREE", a.intersection(&b).as_ref());
+        assert_eq!("ONE", a.difference(&b).as_ref());
+        assert_eq!("TWO", b.difference(&a).as_ref());
+        assert!(a.intersection(&EnumeratedStringList::from("")).is_empty());
+    }
+
+    #[test]
+    fn enumerated_string_list_extend_skips_duplicates() {
+        let mut list = EnumeratedStringList::<TestEnum>::from("");
+        list.extend([TestEnum::One, TestEnum::Two, TestEnum::One]);
+        list.extend(["UNKNOWN", "TWO"]);
+        assert_eq!("ONE,TWO,UNKNOWN", list.as_ref());
+        list.clear();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn enumerated_string_list_mutation_should_not_panic_on_adversarial_input() {
+        const LISTS: [&str; 10] = [
+            "",
+            ",",
+            ",,",
+            "ONE",
+            "ONE,",
+            ",ONE",
+            "ONE,ONE",
+            "ONE,,TWO",
+            "é,ü",
+            "ONE,UNKNOWN,ONE",
+        ];
+        const VALUES: [&str; 9] = [
+            "", ",", "ONE", "TWO", "ONE,TWO", "é", " ONE", "UNKNOWN", "\"",
+        ];
+        for list in LISTS {
+            for value in VALUES {
+                let mut enumerated = EnumeratedStringList::<TestEnum>::from(list);
+                if enumerated.insert(value) {
+                    assert!(enumerated.contains(value), "{list:?} insert {value:?}");
+                    assert!(!enumerated.insert(value), "{list:?} insert {value:?} twice");
+                }
+                let _ = enumerated.iter().count();
+                let mut enumerated = EnumeratedStringList::<TestEnum>::from(list);
+                if enumerated.remove(value) {
+                    assert!(!enumerated.contains(value), "{list:?} remove {value:?}");
+                }
+                enumerated.retain(|item| matches!(item, EnumeratedString::Known(_)));
+                let _ = enumerated.iter().count();
+            }
+        }
+    }
 }
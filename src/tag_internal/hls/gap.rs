@@ -7,6 +7,7 @@ use crate::{
 ///
 /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.4.7>
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Gap;
 
 impl TryFrom<UnknownTag<'_>> for Gap {
@@ -37,6 +38,7 @@ mod tests {
             value: None,
             original_input: b"#EXT-X-GAP",
             validation_error: None,
+            replacement: None,
         };
         assert_eq!(Ok(Gap), Gap::try_from(tag));
     }
@@ -48,6 +50,7 @@ mod tests {
             value: Some(TagValue(b"100")),
             original_input: b"#EXT-X-GAP:100",
             validation_error: None,
+            replacement: None,
         };
         assert_eq!(
             Err(ValidationError::ErrorExtractingTagValue(
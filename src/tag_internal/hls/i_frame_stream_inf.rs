@@ -1,9 +1,10 @@
 use crate::{
-    error::{ParseTagValueError, ValidationError},
+    error::{NumericAttributeError, ParseTagValueError, ValidationError},
     tag::{
         DecimalResolution, UnknownTag,
         hls::{
-            AllowedCpc, EnumeratedString, LazyAttribute, into_inner_tag,
+            AllowedCpc, Codecs, EnumeratedString, LazyAttribute, decimal_integer, into_inner_tag,
+            required_numeric, serde_tag,
             stream_inf::{HdcpLevel, VideoLayout, VideoRange},
         },
     },
@@ -14,6 +15,7 @@ use std::{borrow::Cow, marker::PhantomData};
 ///
 /// See [`IFrameStreamInf`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct IFrameStreamInfAttributeList<'a> {
     /// Corresponds to the `URI` attribute.
     ///
@@ -164,18 +166,42 @@ impl<'a, UriStatus, BandwidthStatus> IFrameStreamInfBuilder<'a, UriStatus, Bandw
         self.attribute_list.average_bandwidth = Some(average_bandwidth);
         self
     }
+    /// Add the provided `average_bandwidth` to the attributes built into `IFrameStreamInf`, if it
+    /// is `Some` (otherwise this is a no-op).
+    pub fn with_average_bandwidth_opt(self, average_bandwidth: Option<u64>) -> Self {
+        match average_bandwidth {
+            Some(average_bandwidth) => self.with_average_bandwidth(average_bandwidth),
+            None => self,
+        }
+    }
 
     /// Add the provided `score` to the attributes built into `IFrameStreamInf`.
     pub fn with_score(mut self, score: f64) -> Self {
         self.attribute_list.score = Some(score);
         self
     }
+    /// Add the provided `score` to the attributes built into `IFrameStreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_score_opt(self, score: Option<f64>) -> Self {
+        match score {
+            Some(score) => self.with_score(score),
+            None => self,
+        }
+    }
 
     /// Add the provided `codecs` to the attributes built into `IFrameStreamInf`.
     pub fn with_codecs(mut self, codecs: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.codecs = Some(codecs.into());
         self
     }
+    /// Add the provided `codecs` to the attributes built into `IFrameStreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_codecs_opt(self, codecs: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match codecs {
+            Some(codecs) => self.with_codecs(codecs),
+            None => self,
+        }
+    }
 
     /// Add the provided `supplemental_codecs` to the attributes built into `IFrameStreamInf`.
     pub fn with_supplemental_codecs(
@@ -185,12 +211,31 @@ impl<'a, UriStatus, BandwidthStatus> IFrameStreamInfBuilder<'a, UriStatus, Bandw
         self.attribute_list.supplemental_codecs = Some(supplemental_codecs.into());
         self
     }
+    /// Add the provided `supplemental_codecs` to the attributes built into `IFrameStreamInf`, if it
+    /// is `Some` (otherwise this is a no-op).
+    pub fn with_supplemental_codecs_opt(
+        self,
+        supplemental_codecs: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match supplemental_codecs {
+            Some(supplemental_codecs) => self.with_supplemental_codecs(supplemental_codecs),
+            None => self,
+        }
+    }
 
     /// Add the provided `resolution` to the attributes built into `IFrameStreamInf`.
     pub fn with_resolution(mut self, resolution: DecimalResolution) -> Self {
         self.attribute_list.resolution = Some(resolution);
         self
     }
+    /// Add the provided `resolution` to the attributes built into `IFrameStreamInf`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_resolution_opt(self, resolution: Option<DecimalResolution>) -> Self {
+        match resolution {
+            Some(resolution) => self.with_resolution(resolution),
+            None => self,
+        }
+    }
 
     /// Add the provided `hdcp_level` to the attributes built into `IFrameStreamInf`.
     ///
@@ -215,6 +260,14 @@ impl<'a, UriStatus, BandwidthStatus> IFrameStreamInfBuilder<'a, UriStatus, Bandw
         self.attribute_list.hdcp_level = Some(hdcp_level.into());
         self
     }
+    /// Add the provided `hdcp_level` to the attributes built into `IFrameStreamInf`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_hdcp_level_opt(self, hdcp_level: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match hdcp_level {
+            Some(hdcp_level) => self.with_hdcp_level(hdcp_level),
+            None => self,
+        }
+    }
 
     /// Add the provided `allowed_cpc` to the attributes built into `IFrameStreamInf`.
     ///
@@ -239,6 +292,14 @@ impl<'a, UriStatus, BandwidthStatus> IFrameStreamInfBuilder<'a, UriStatus, Bandw
         self.attribute_list.allowed_cpc = Some(allowed_cpc.into());
         self
     }
+    /// Add the provided `allowed_cpc` to the attributes built into `IFrameStreamInf`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_allowed_cpc_opt(self, allowed_cpc: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match allowed_cpc {
+            Some(allowed_cpc) => self.with_allowed_cpc(allowed_cpc),
+            None => self,
+        }
+    }
 
     /// Add the provided `video_range` to the attributes built into `IFrameStreamInf`.
     ///
@@ -263,6 +324,14 @@ impl<'a, UriStatus, BandwidthStatus> IFrameStreamInfBuilder<'a, UriStatus, Bandw
         self.attribute_list.video_range = Some(video_range.into());
         self
     }
+    /// Add the provided `video_range` to the attributes built into `IFrameStreamInf`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_video_range_opt(self, video_range: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match video_range {
+            Some(video_range) => self.with_video_range(video_range),
+            None => self,
+        }
+    }
 
     /// Add the provided `req_video_layout` to the attributes built into `IFrameStreamInf`.
     ///
@@ -297,24 +366,62 @@ impl<'a, UriStatus, BandwidthStatus> IFrameStreamInfBuilder<'a, UriStatus, Bandw
         self.attribute_list.req_video_layout = Some(req_video_layout.into());
         self
     }
+    /// Add the provided `req_video_layout` to the attributes built into `IFrameStreamInf`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_req_video_layout_opt(
+        self,
+        req_video_layout: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match req_video_layout {
+            Some(req_video_layout) => self.with_req_video_layout(req_video_layout),
+            None => self,
+        }
+    }
 
     /// Add the provided `stable_variant_id` to the attributes built into `IFrameStreamInf`.
     pub fn with_stable_variant_id(mut self, stable_variant_id: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.stable_variant_id = Some(stable_variant_id.into());
         self
     }
+    /// Add the provided `stable_variant_id` to the attributes built into `IFrameStreamInf`, if it
+    /// is `Some` (otherwise this is a no-op).
+    pub fn with_stable_variant_id_opt(
+        self,
+        stable_variant_id: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match stable_variant_id {
+            Some(stable_variant_id) => self.with_stable_variant_id(stable_variant_id),
+            None => self,
+        }
+    }
 
     /// Add the provided `video` to the attributes built into `IFrameStreamInf`.
     pub fn with_video(mut self, video: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.video = Some(video.into());
         self
     }
+    /// Add the provided `video` to the attributes built into `IFrameStreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_video_opt(self, video: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match video {
+            Some(video) => self.with_video(video),
+            None => self,
+        }
+    }
 
     /// Add the provided `pathway_id` to the attributes built into `IFrameStreamInf`.
     pub fn with_pathway_id(mut self, pathway_id: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.pathway_id = Some(pathway_id.into());
         self
     }
+    /// Add the provided `pathway_id` to the attributes built into `IFrameStreamInf`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_pathway_id_opt(self, pathway_id: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match pathway_id {
+            Some(pathway_id) => self.with_pathway_id(pathway_id),
+            None => self,
+        }
+    }
 }
 impl<'a> Default
     for IFrameStreamInfBuilder<
@@ -347,8 +454,9 @@ pub struct IFrameStreamInf<'a> {
     stable_variant_id: LazyAttribute<'a, Cow<'a, str>>,
     video: LazyAttribute<'a, Cow<'a, str>>,
     pathway_id: LazyAttribute<'a, Cow<'a, str>>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for IFrameStreamInf<'a> {
@@ -377,7 +485,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for IFrameStreamInf<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut uri = None;
         let mut bandwidth = None;
         let mut average_bandwidth = LazyAttribute::None;
@@ -395,11 +503,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for IFrameStreamInf<'a> {
         for (name, value) in attribute_list {
             match name {
                 URI => uri = value.quoted(),
-                BANDWIDTH => {
-                    bandwidth = value
-                        .unquoted()
-                        .and_then(|v| v.try_as_decimal_integer().ok())
-                }
+                BANDWIDTH => bandwidth = required_numeric(decimal_integer(value), BANDWIDTH)?,
                 AVERAGE_BANDWIDTH => average_bandwidth.found(value),
                 SCORE => score.found(value),
                 CODECS => codecs.found(value),
@@ -438,6 +542,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for IFrameStreamInf<'a> {
             pathway_id,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -485,6 +590,7 @@ impl<'a> IFrameStreamInf<'a> {
             pathway_id: pathway_id.map(LazyAttribute::new).unwrap_or_default(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -545,27 +651,37 @@ impl<'a> IFrameStreamInf<'a> {
     /// Corresponds to the `AVERAGE-BANDWIDTH` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_average_bandwidth`] to distinguish an invalid value.
     pub fn average_bandwidth(&self) -> Option<u64> {
-        match &self.average_bandwidth {
-            LazyAttribute::UserDefined(b) => Some(*b),
-            LazyAttribute::Unparsed(v) => {
-                v.unquoted().and_then(|v| v.try_as_decimal_integer().ok())
-            }
-            LazyAttribute::None => None,
-        }
+        self.try_average_bandwidth().ok().flatten()
+    }
+
+    /// Corresponds to the `AVERAGE-BANDWIDTH` attribute.
+    ///
+    /// Unlike [`Self::average_bandwidth`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_average_bandwidth(&self) -> Result<Option<u64>, NumericAttributeError<'_>> {
+        self.average_bandwidth.try_decimal_integer()
     }
 
     /// Corresponds to the `SCORE` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_score`] to distinguish an invalid value.
     pub fn score(&self) -> Option<f64> {
-        match &self.score {
-            LazyAttribute::UserDefined(s) => Some(*s),
-            LazyAttribute::Unparsed(v) => v
-                .unquoted()
-                .and_then(|v| v.try_as_decimal_floating_point().ok()),
-            LazyAttribute::None => None,
-        }
+        self.try_score().ok().flatten()
+    }
+
+    /// Corresponds to the `SCORE` attribute.
+    ///
+    /// Unlike [`Self::score`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_score(&self) -> Result<Option<f64>, NumericAttributeError<'_>> {
+        self.score.try_decimal_floating_point()
     }
 
     /// Corresponds to the `CODECS` attribute.
@@ -579,6 +695,13 @@ impl<'a> IFrameStreamInf<'a> {
         }
     }
 
+    /// Corresponds to the `CODECS` attribute, as a list of formats (see [`Codecs`]).
+    ///
+    /// See [`Self`] for a link to the HLS documentation for this attribute.
+    pub fn codec_list(&self) -> Option<Codecs<'_>> {
+        self.codecs().map(Codecs::from)
+    }
+
     /// Corresponds to the `SUPPLEMENTAL-CODECS` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
@@ -932,7 +1055,12 @@ impl<'a> IFrameStreamInf<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&IFrameStreamInfAttributeList {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> IFrameStreamInfAttributeList<'_> {
+        IFrameStreamInfAttributeList {
             uri: self.uri().into(),
             bandwidth: self.bandwidth(),
             average_bandwidth: self.average_bandwidth(),
@@ -947,12 +1075,12 @@ impl<'a> IFrameStreamInf<'a> {
             stable_variant_id: self.stable_variant_id().map(|x| x.into()),
             video: self.video().map(|x| x.into()),
             pathway_id: self.pathway_id().map(|x| x.into()),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(IFrameStreamInf);
+serde_tag!(IFrameStreamInf, IFrameStreamInfAttributeList);
 
 const URI: &str = "URI";
 const BANDWIDTH: &str = "BANDWIDTH";
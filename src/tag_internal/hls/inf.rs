@@ -1,8 +1,12 @@
 use crate::{
     error::{ParseTagValueError, ValidationError},
-    tag::{UnknownTag, hls::into_inner_tag},
+    tag::{
+        AttributeValue, SegmentDuration, UnknownTag, UnquotedAttributeValue,
+        hls::{LazyAttribute, into_inner_tag, serde_tag},
+    },
 };
-use std::borrow::Cow;
+use memchr::memchr;
+use std::{borrow::Cow, fmt::Display};
 
 /// Corresponds to the `#EXTINF` tag.
 ///
@@ -10,9 +14,11 @@ use std::borrow::Cow;
 #[derive(Debug, Clone)]
 pub struct Inf<'a> {
     duration: f64,
+    exact_duration: LazyAttribute<'a, SegmentDuration>,
     title: Cow<'a, str>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Inf<'a> {
@@ -25,15 +31,18 @@ impl<'a> TryFrom<UnknownTag<'a>> for Inf<'a> {
     type Error = ValidationError;
 
     fn try_from(tag: UnknownTag<'a>) -> Result<Self, Self::Error> {
-        let (duration, title) = tag
-            .value()
-            .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_decimal_floating_point_with_title()?;
+        let value = tag.value().ok_or(ParseTagValueError::UnexpectedEmpty)?;
+        let (duration, title) = value.try_as_decimal_floating_point_with_title()?;
+        let duration_bytes = &value.0[..memchr(b',', value.0).unwrap_or(value.0.len())];
         Ok(Self {
             duration,
+            exact_duration: LazyAttribute::Unparsed(AttributeValue::Unquoted(
+                UnquotedAttributeValue(duration_bytes),
+            )),
             title: Cow::Borrowed(title),
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -45,9 +54,11 @@ impl<'a> Inf<'a> {
         let output_line = Cow::Owned(calculate_line(duration, &title));
         Self {
             duration,
+            exact_duration: LazyAttribute::None,
             title,
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -59,6 +70,36 @@ impl<'a> Inf<'a> {
         self.duration
     }
 
+    /// Corresponds to the duration component of the tag value, as a [`SegmentDuration`].
+    ///
+    /// When the tag was parsed, this is exactly the value that was written (e.g. `9.009` and not
+    /// the closest `f64`), including the formatting (e.g. `4.000` is retained), and so is preferred
+    /// over [`Self::duration`] for arithmetic and comparisons. For example:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, custom_parsing::line::parse, tag::{KnownTag, SegmentDuration, hls}};
+    /// let line = parse("#EXTINF:4.000,", &Default::default())?.parsed;
+    /// let HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(inf))) = line else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!(SegmentDuration::from_secs(4), inf.segment_duration());
+    /// assert_eq!("4.000", inf.segment_duration().to_string());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    /// Otherwise (or if the value has more digits than can be represented exactly), this is
+    /// derived from [`Self::duration`] (see [`SegmentDuration::from_secs_f64`]).
+    ///
+    /// See [`Self`] for a link to the HLS documentation for this attribute.
+    pub fn segment_duration(&self) -> SegmentDuration {
+        match &self.exact_duration {
+            LazyAttribute::UserDefined(d) => *d,
+            LazyAttribute::Unparsed(v) => v
+                .unquoted()
+                .and_then(|v| SegmentDuration::try_from(v.0).ok())
+                .unwrap_or_else(|| SegmentDuration::from_secs_f64(self.duration)),
+            LazyAttribute::None => SegmentDuration::from_secs_f64(self.duration),
+        }
+    }
+
     /// Corresponds to the title component of the tag value (`title` in
     /// `#EXTINF:<duration>,[<title>]`).
     ///
@@ -72,6 +113,17 @@ impl<'a> Inf<'a> {
     /// See [`Self`] for a link to the HLS documentation for this attribute.
     pub fn set_duration(&mut self, duration: f64) {
         self.duration = duration;
+        self.exact_duration.unset();
+        self.output_line_is_dirty = true;
+    }
+
+    /// Sets the duration component value from a [`SegmentDuration`], which is written exactly as
+    /// it is displayed (e.g. `SegmentDuration::from_millis(4000)` is written as `4.000`).
+    ///
+    /// See [`Self`] for a link to the HLS documentation for this attribute.
+    pub fn set_segment_duration(&mut self, duration: SegmentDuration) {
+        self.duration = duration.as_secs_f64();
+        self.exact_duration.set(duration);
         self.output_line_is_dirty = true;
     }
 
@@ -84,14 +136,34 @@ impl<'a> Inf<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(self.duration(), self.title()));
+        self.output_line = Cow::Owned(match self.exact_duration {
+            LazyAttribute::UserDefined(duration) => calculate_line(duration, self.title()),
+            _ => calculate_line(self.duration(), self.title()),
+        });
         self.output_line_is_dirty = false;
     }
 }
 
 into_inner_tag!(Inf);
+serde_tag!(
+    Inf,
+    InfValue,
+    |tag| InfValue {
+        duration: tag.duration(),
+        title: Cow::Borrowed(tag.title()),
+    },
+    |value| Inf::new(value.duration, value.title)
+);
+
+// The representation of the tag value used with the `serde` feature.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct InfValue<'a> {
+    duration: f64,
+    title: Cow<'a, str>,
+}
 
-fn calculate_line(duration: f64, title: &str) -> Vec<u8> {
+fn calculate_line(duration: impl Display, title: &str) -> Vec<u8> {
     if title.is_empty() {
         format!("#EXTINF:{duration}").into_bytes()
     } else {
@@ -129,6 +201,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn segment_duration_should_retain_original_formatting() {
+        let tag = crate::custom_parsing::tag::parse("#EXTINF:4.000,title")
+            .unwrap()
+            .parsed;
+        let mut inf = Inf::try_from(tag).unwrap();
+        assert_eq!(4.0, inf.duration());
+        assert_eq!("4.000", inf.segment_duration().to_string());
+        inf.set_title("other");
+        assert_eq!(b"#EXTINF:4,other", inf.clone().into_inner().value());
+        inf.set_segment_duration(SegmentDuration::from_millis(3500));
+        assert_eq!(3.5, inf.duration());
+        assert_eq!(b"#EXTINF:3.500,other", inf.clone().into_inner().value());
+        inf.set_duration(2.0);
+        assert_eq!("2", inf.segment_duration().to_string());
+        assert_eq!(b"#EXTINF:2,other", inf.into_inner().value());
+    }
+
     mutation_tests!(
         Inf::new(6.006, "hello"),
         (duration, 10.0, @Attr="10"),
@@ -1,12 +1,20 @@
 use crate::{
-    error::{ParseTagValueError, UnrecognizedEnumerationError, ValidationError},
+    error::{
+        ParseIvError, ParseNumberError, ParseTagValueError, UnrecognizedEnumerationError,
+        ValidationError,
+    },
     tag::{
         UnknownTag,
-        hls::{EnumeratedString, LazyAttribute, into_inner_tag},
+        hls::{EnumeratedString, LazyAttribute, into_inner_tag, serde_tag},
     },
-    utils::AsStaticCow,
+    utils::{AsStaticCow, parse_u64},
+};
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
 };
-use std::{borrow::Cow, fmt::Display, marker::PhantomData};
 
 /// Corresponds to the `#EXT-X-KEY:METHOD` attribute.
 ///
@@ -69,6 +77,135 @@ impl From<Method> for EnumeratedString<'_, Method> {
         Self::Known(value)
     }
 }
+
+/// Corresponds to the `#EXT-X-KEY:KEYFORMATVERSIONS` attribute value, which is one or more positive
+/// integers separated by `/` (e.g. `1/2/5`).
+///
+/// The value is provided as a string by [`Key::keyformatversions`], and parsed by
+/// [`Key::keyformat_versions`] (which provides the default of `1` when the attribute is absent).
+/// The [`Display`] implementation writes the canonical form of the value (no leading zeros, as
+/// listed, separated by `/`). For example:
+/// ```
+/// # use quick_m3u8::tag::hls::KeyformatVersions;
+/// let versions: KeyformatVersions = "1/2/5".parse()?;
+/// assert!(versions.contains(2));
+/// assert_eq!(vec![1, 2, 5], versions.iter().collect::<Vec<_>>());
+/// assert_eq!("1/2/5", versions.to_string());
+/// # Ok::<(), quick_m3u8::error::ParseNumberError>(())
+/// ```
+///
+/// See [`Key`] for a link to the HLS documentation for this attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyformatVersions(Vec<u64>);
+impl KeyformatVersions {
+    /// Construct a new `KeyformatVersions`.
+    pub fn new(versions: impl IntoIterator<Item = u64>) -> Self {
+        Self(versions.into_iter().collect())
+    }
+
+    /// Iterates over the versions (in the order that they are listed).
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Whether the version is listed.
+    pub fn contains(&self, version: u64) -> bool {
+        self.0.contains(&version)
+    }
+
+    /// Adds the version to the end of the list, returning `false` (and leaving the list unchanged)
+    /// if the version is already listed.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::tag::hls::KeyformatVersions;
+    /// let mut versions = KeyformatVersions::new([1, 2]);
+    /// assert!(versions.insert(5));
+    /// assert!(!versions.insert(2));
+    /// assert_eq!("1/2/5", versions.to_string());
+    /// ```
+    pub fn insert(&mut self, version: u64) -> bool {
+        if self.contains(version) {
+            false
+        } else {
+            self.0.push(version);
+            true
+        }
+    }
+
+    /// Removes the version from the list, returning `false` if the version was not listed.
+    ///
+    /// Note, the attribute requires at least one version, and so an empty list should be unset from
+    /// the tag rather than written.
+    pub fn remove(&mut self, version: u64) -> bool {
+        let length = self.0.len();
+        self.0.retain(|v| *v != version);
+        self.0.len() != length
+    }
+
+    /// The number of versions listed.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no versions are listed.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+/// The default value of the attribute (when it is absent from the tag), which is `1`.
+impl Default for KeyformatVersions {
+    fn default() -> Self {
+        Self(vec![1])
+    }
+}
+impl IntoIterator for KeyformatVersions {
+    type Item = u64;
+    type IntoIter = std::vec::IntoIter<u64>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl<'a> IntoIterator for &'a KeyformatVersions {
+    type Item = u64;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, u64>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().copied()
+    }
+}
+impl TryFrom<&str> for KeyformatVersions {
+    type Error = ParseNumberError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value
+            .split('/')
+            .map(|version| parse_u64(version.as_bytes()))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+impl std::str::FromStr for KeyformatVersions {
+    type Err = ParseNumberError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+impl Display for KeyformatVersions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, version) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            write!(f, "{version}")?;
+        }
+        Ok(())
+    }
+}
+impl From<KeyformatVersions> for Cow<'_, str> {
+    fn from(value: KeyformatVersions) -> Self {
+        Cow::Owned(value.to_string())
+    }
+}
+
 const NONE: &str = "NONE";
 const AES_128: &str = "AES-128";
 const SAMPLE_AES: &str = "SAMPLE-AES";
@@ -78,6 +215,7 @@ const SAMPLE_AES_CTR: &str = "SAMPLE-AES-CTR";
 ///
 /// See [`Key`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct KeyAttributeList<'a> {
     /// Corresponds to the `METHOD` attribute.
     ///
@@ -159,24 +297,59 @@ impl<'a, MethodStatus> KeyBuilder<'a, MethodStatus> {
         self.attribute_list.uri = Some(uri.into());
         self
     }
+    /// Add the proivded `uri` to the attributes built into `Key`, if it is `Some` (otherwise this
+    /// is a no-op).
+    pub fn with_uri_opt(self, uri: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match uri {
+            Some(uri) => self.with_uri(uri),
+            None => self,
+        }
+    }
 
     /// Add the proivded `iv` to the attributes built into `Key`.
     pub fn with_iv(mut self, iv: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.iv = Some(iv.into());
         self
     }
+    /// Add the proivded `iv` to the attributes built into `Key`, if it is `Some` (otherwise this is
+    /// a no-op).
+    pub fn with_iv_opt(self, iv: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match iv {
+            Some(iv) => self.with_iv(iv),
+            None => self,
+        }
+    }
 
     /// Add the proivded `keyformat` to the attributes built into `Key`.
     pub fn with_keyformat(mut self, keyformat: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.keyformat = Some(keyformat.into());
         self
     }
+    /// Add the proivded `keyformat` to the attributes built into `Key`, if it is `Some` (otherwise
+    /// this is a no-op).
+    pub fn with_keyformat_opt(self, keyformat: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match keyformat {
+            Some(keyformat) => self.with_keyformat(keyformat),
+            None => self,
+        }
+    }
 
     /// Add the proivded `keyformatversions` to the attributes built into `Key`.
     pub fn with_keyformatversions(mut self, keyformatversions: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.keyformatversions = Some(keyformatversions.into());
         self
     }
+    /// Add the proivded `keyformatversions` to the attributes built into `Key`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_keyformatversions_opt(
+        self,
+        keyformatversions: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match keyformatversions {
+            Some(keyformatversions) => self.with_keyformatversions(keyformatversions),
+            None => self,
+        }
+    }
 }
 impl<'a> Default for KeyBuilder<'a, KeyMethodNeedsToBeSet> {
     fn default() -> Self {
@@ -186,6 +359,10 @@ impl<'a> Default for KeyBuilder<'a, KeyMethodNeedsToBeSet> {
 
 /// Corresponds to the `#EXT-X-KEY` tag.
 ///
+/// Equality is semantic, meaning that the order of the attributes, the case of the `IV`, and
+/// whether the default `KEYFORMAT` is written explicitly, are all ignored. `Hash` is consistent
+/// with this, and so keys can be held in a `HashSet` (e.g. when planning license prefetches).
+///
 /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.4.4>
 #[derive(Debug, Clone)]
 pub struct Key<'a> {
@@ -194,20 +371,36 @@ pub struct Key<'a> {
     iv: LazyAttribute<'a, Cow<'a, str>>,
     keyformat: LazyAttribute<'a, Cow<'a, str>>,
     keyformatversions: LazyAttribute<'a, Cow<'a, str>>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Key<'a> {
     fn eq(&self, other: &Self) -> bool {
         self.method() == other.method()
             && self.uri() == other.uri()
-            && self.iv() == other.iv()
+            && iv_eq(self.iv(), other.iv())
             && self.keyformat() == other.keyformat()
             && self.keyformatversions() == other.keyformatversions()
     }
 }
 
+impl<'a> Eq for Key<'a> {}
+
+impl<'a> Hash for Key<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_key(
+            self.method.as_ref(),
+            self.uri(),
+            self.iv(),
+            self.keyformat(),
+            self.keyformatversions(),
+            state,
+        );
+    }
+}
+
 impl<'a> TryFrom<UnknownTag<'a>> for Key<'a> {
     type Error = ValidationError;
 
@@ -215,7 +408,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Key<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut method = None;
         let mut uri = LazyAttribute::None;
         let mut iv = LazyAttribute::None;
@@ -242,6 +435,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Key<'a> {
             keyformatversions,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -267,6 +461,7 @@ impl<'a> Key<'a> {
                 .unwrap_or_default(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -321,6 +516,45 @@ impl<'a> Key<'a> {
         }
     }
 
+    /// Corresponds to the `IV` attribute, parsed as the bytes of the 128-bit value (big-endian).
+    ///
+    /// The value is a hexadecimal-sequence (e.g. `0x0A1B...`), and a value of fewer than 32 digits
+    /// is padded on the left with zeros. This provides `Ok(None)` when the attribute is absent (see
+    /// [`Self::segment_iv`] for the IV to use in that case).
+    pub fn iv_bytes(&self) -> Result<Option<[u8; 16]>, ParseIvError> {
+        self.iv().map(parse_iv).transpose()
+    }
+
+    /// The Initialization Vector to use when decrypting the Media Segment with the given Media
+    /// Sequence Number.
+    ///
+    /// This is the `IV` attribute when present (see [`Self::iv_bytes`]). Otherwise, per the HLS
+    /// specification, the Media Sequence Number is used as the IV: its big-endian binary
+    /// representation is put into a 16-octet buffer, padded on the left with zeros. Note that this
+    /// is only defined for `AES-128`, as the IV for other methods is format specific.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::tag::hls::{Key, Method};
+    /// let mut key = Key::builder()
+    ///     .with_method(Method::Aes128)
+    ///     .with_uri("key.bin")
+    ///     .finish();
+    /// let mut expected = [0; 16];
+    /// expected[14..].copy_from_slice(&[0x01, 0x02]);
+    /// assert_eq!(Ok(expected), key.segment_iv(0x0102));
+    ///
+    /// key.set_iv("0x1");
+    /// let mut expected = [0; 16];
+    /// expected[15] = 1;
+    /// assert_eq!(Ok(expected), key.segment_iv(0x0102));
+    /// ```
+    pub fn segment_iv(&self, media_sequence: u64) -> Result<[u8; 16], ParseIvError> {
+        Ok(self
+            .iv_bytes()?
+            .unwrap_or_else(|| u128::from(media_sequence).to_be_bytes()))
+    }
+
     /// Corresponds to the `KEYFORMAT` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
@@ -343,6 +577,29 @@ impl<'a> Key<'a> {
         }
     }
 
+    /// Corresponds to the `KEYFORMATVERSIONS` attribute, parsed as [`KeyformatVersions`].
+    ///
+    /// When the attribute is absent this provides the default value of `1` (as defined in the HLS
+    /// specification). The versions can be modified and set back on the tag, for example:
+    /// ```
+    /// # use quick_m3u8::tag::hls::{Key, Method};
+    /// let mut key = Key::builder()
+    ///     .with_method(Method::SampleAes)
+    ///     .with_uri("skd://key")
+    ///     .finish();
+    /// let mut versions = key.keyformat_versions()?;
+    /// assert!(versions.contains(1));
+    /// versions.insert(2);
+    /// key.set_keyformatversions(versions);
+    /// assert_eq!(Some("1/2"), key.keyformatversions());
+    /// # Ok::<(), quick_m3u8::error::ParseNumberError>(())
+    /// ```
+    pub fn keyformat_versions(&self) -> Result<KeyformatVersions, ParseNumberError> {
+        self.keyformatversions()
+            .map(KeyformatVersions::try_from)
+            .unwrap_or_else(|| Ok(KeyformatVersions::default()))
+    }
+
     /// Sets the `METHOD` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
@@ -416,24 +673,29 @@ impl<'a> Key<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> KeyAttributeList<'_> {
         let keyformat = self.keyformat();
         let keyformat = if keyformat == "identity" {
             None
         } else {
             Some(keyformat)
         };
-        self.output_line = Cow::Owned(calculate_line(&KeyAttributeList {
+        KeyAttributeList {
             method: self.method().into(),
             uri: self.uri().map(|x| x.into()),
             iv: self.iv().map(|x| x.into()),
             keyformat: keyformat.map(|x| x.into()),
             keyformatversions: self.keyformatversions().map(|x| x.into()),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(Key);
+serde_tag!(Key, KeyAttributeList);
 
 const METHOD: &str = "METHOD";
 const URI: &str = "URI";
@@ -441,6 +703,62 @@ const IV: &str = "IV";
 const KEYFORMAT: &str = "KEYFORMAT";
 const KEYFORMATVERSIONS: &str = "KEYFORMATVERSIONS";
 
+/// The `IV` is a hexadecimal-sequence, and so the case of the digits (and of the `0x` prefix) is
+/// insignificant.
+pub(super) fn iv_eq(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+// Parses a hexadecimal-sequence of up to 128 bits into big-endian bytes.
+fn parse_iv(iv: &str) -> Result<[u8; 16], ParseIvError> {
+    let digits = iv
+        .strip_prefix("0x")
+        .or_else(|| iv.strip_prefix("0X"))
+        .ok_or(ParseIvError::MissingHexPrefix)?;
+    if digits.is_empty() {
+        return Err(ParseIvError::Empty);
+    }
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ParseIvError::InvalidDigit);
+    }
+    if digits.len() > 32 {
+        return Err(ParseIvError::TooLong);
+    }
+    u128::from_str_radix(digits, 16)
+        .map(u128::to_be_bytes)
+        .map_err(|_| ParseIvError::InvalidDigit)
+}
+
+/// Hashes the attributes of a key consistently with the `PartialEq` of [`Key`] and
+/// [`super::SessionKey`].
+pub(super) fn hash_key<H: Hasher>(
+    method: &str,
+    uri: Option<&str>,
+    iv: Option<&str>,
+    keyformat: &str,
+    keyformatversions: Option<&str>,
+    state: &mut H,
+) {
+    method.hash(state);
+    uri.hash(state);
+    match iv {
+        Some(iv) => {
+            state.write_u8(1);
+            for byte in iv.bytes() {
+                state.write_u8(byte.to_ascii_lowercase());
+            }
+            state.write_u8(0xff);
+        }
+        None => state.write_u8(0),
+    }
+    keyformat.hash(state);
+    keyformatversions.hash(state);
+}
+
 fn calculate_line(attribute_list: &KeyAttributeList) -> Vec<u8> {
     let KeyAttributeList {
         method,
@@ -471,6 +789,70 @@ mod tests {
     use crate::tag::{IntoInnerTag, hls::test_macro::mutation_tests};
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn iv_bytes_should_parse_hexadecimal_sequence() {
+        let mut key = Key::builder().with_method(Method::Aes128).finish();
+        assert_eq!(Ok(None), key.iv_bytes());
+        key.set_iv("0X000102030405060708090a0b0c0d0E0F");
+        assert_eq!(
+            Ok(Some([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15])),
+            key.iv_bytes()
+        );
+        key.set_iv("000102030405060708090a0b0c0d0e0f");
+        assert_eq!(Err(ParseIvError::MissingHexPrefix), key.iv_bytes());
+        key.set_iv("0x");
+        assert_eq!(Err(ParseIvError::Empty), key.iv_bytes());
+        key.set_iv("0x+1");
+        assert_eq!(Err(ParseIvError::InvalidDigit), key.iv_bytes());
+        key.set_iv("0x100000000000000000000000000000000");
+        assert_eq!(Err(ParseIvError::TooLong), key.iv_bytes());
+        assert_eq!(Err(ParseIvError::TooLong), key.segment_iv(1));
+    }
+
+    #[test]
+    fn keyformat_versions_should_parse_slash_separated_integers() {
+        assert_eq!(Ok(KeyformatVersions::new([1])), "1".parse());
+        assert_eq!(
+            Ok(KeyformatVersions::new([1, 2, 5])),
+            KeyformatVersions::try_from("1/2/5")
+        );
+        assert_eq!(
+            Err(ParseNumberError::Empty),
+            "1//5".parse::<KeyformatVersions>()
+        );
+        assert_eq!(
+            Err(ParseNumberError::InvalidDigit(b'a')),
+            "1/a".parse::<KeyformatVersions>()
+        );
+    }
+
+    #[test]
+    fn keyformat_versions_mutation_should_not_panic_on_adversarial_input() {
+        const LISTS: [&[u64]; 5] = [&[], &[1], &[1, 1], &[u64::MAX, 0], &[5, 2, 5]];
+        const VERSIONS: [u64; 4] = [0, 1, 5, u64::MAX];
+        for list in LISTS {
+            for version in VERSIONS {
+                let mut versions = KeyformatVersions::new(list.iter().copied());
+                if versions.insert(version) {
+                    assert!(versions.contains(version), "{list:?} insert {version}");
+                }
+                assert!(!versions.insert(version), "{list:?} insert {version} twice");
+                let written = versions.to_string();
+                if !versions.is_empty() {
+                    assert_eq!(Ok(versions.clone()), written.parse(), "{list:?} {version}");
+                }
+                let mut versions = KeyformatVersions::new(list.iter().copied());
+                let removed = versions.remove(version);
+                assert_eq!(
+                    list.contains(&version),
+                    removed,
+                    "{list:?} remove {version}"
+                );
+                assert!(!versions.contains(version), "{list:?} remove {version}");
+            }
+        }
+    }
+
     #[test]
     fn as_str_with_no_options_should_be_valid() {
         assert_eq!(
@@ -503,6 +885,18 @@ mod tests {
         )
     }
 
+    #[test]
+    fn keyformat_versions_should_default_to_one_and_report_invalid_values() {
+        let mut key = Key::builder().with_method(Method::None).finish();
+        assert_eq!(Ok(KeyformatVersions::new([1])), key.keyformat_versions());
+        key.set_keyformatversions("1/02/5");
+        let versions = key.keyformat_versions().expect("versions should be valid");
+        assert_eq!(vec![1, 2, 5], versions.iter().collect::<Vec<_>>());
+        assert_eq!("1/2/5", versions.to_string());
+        key.set_keyformatversions("1//5");
+        assert_eq!(Err(ParseNumberError::Empty), key.keyformat_versions());
+    }
+
     mutation_tests!(
         Key::builder()
             .with_method(Method::SampleAes)
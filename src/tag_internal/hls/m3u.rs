@@ -7,6 +7,7 @@ use crate::{
 ///
 /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.1.1>
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct M3u;
 
 impl TryFrom<UnknownTag<'_>> for M3u {
@@ -37,6 +38,7 @@ mod tests {
             value: None,
             original_input: b"#EXTM3U",
             validation_error: None,
+            replacement: None,
         };
         assert_eq!(Ok(M3u), M3u::try_from(tag));
     }
@@ -48,6 +50,7 @@ mod tests {
             value: Some(TagValue(b"100")),
             original_input: b"#EXTM3U:100",
             validation_error: None,
+            replacement: None,
         };
         assert_eq!(
             Err(ValidationError::ErrorExtractingTagValue(
@@ -1,8 +1,8 @@
 use crate::{
-    error::{ParseMapByterangeError, ParseTagValueError, ValidationError},
+    error::{ParseMapByterangeError, ParseTagValueError, RangeRequestError, ValidationError},
     tag::{
-        DecimalIntegerRange, UnknownTag,
-        hls::{LazyAttribute, into_inner_tag},
+        DecimalIntegerRange, RangeRequest, UnknownTag,
+        hls::{LazyAttribute, into_inner_tag, serde_tag},
     },
 };
 use std::{borrow::Cow, fmt::Display, marker::PhantomData};
@@ -11,6 +11,7 @@ use std::{borrow::Cow, fmt::Display, marker::PhantomData};
 ///
 /// See [`Map`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct MapAttributeList<'a> {
     /// Corresponds to the `URI` attribute.
     ///
@@ -72,6 +73,14 @@ impl<'a, UriStatus> MapBuilder<'a, UriStatus> {
         self.attribute_list.byterange = Some(byterange);
         self
     }
+    /// Add the provided `byterange` to the attributes built into `Map`, if it is `Some` (otherwise
+    /// this is a no-op).
+    pub fn with_byterange_opt(self, byterange: Option<MapByterange>) -> Self {
+        match byterange {
+            Some(byterange) => self.with_byterange(byterange),
+            None => self,
+        }
+    }
 }
 impl<'a> Default for MapBuilder<'a, MapUriNeedsToBeSet> {
     fn default() -> Self {
@@ -86,8 +95,9 @@ impl<'a> Default for MapBuilder<'a, MapUriNeedsToBeSet> {
 pub struct Map<'a> {
     uri: Cow<'a, str>,
     byterange: LazyAttribute<'a, MapByterange>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Map<'a> {
@@ -98,6 +108,7 @@ impl<'a> PartialEq for Map<'a> {
 
 /// Corresponds to the value of the `#EXT-X-MAP:BYTERANGE` attribute.
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapByterange {
     /// Corresponds to the length component in the value (`n` in `<n>@<o>`).
     ///
@@ -129,6 +140,50 @@ impl TryFrom<&str> for MapByterange {
         Self::try_from(value.as_bytes())
     }
 }
+impl std::str::FromStr for MapByterange {
+    type Err = ParseMapByterangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+impl TryFrom<DecimalIntegerRange> for MapByterange {
+    type Error = ParseMapByterangeError;
+
+    fn try_from(range: DecimalIntegerRange) -> Result<Self, Self::Error> {
+        let offset = range.offset.ok_or(ParseMapByterangeError::MissingOffset)?;
+        Ok(Self {
+            length: range.length,
+            offset,
+        })
+    }
+}
+impl From<MapByterange> for DecimalIntegerRange {
+    fn from(range: MapByterange) -> Self {
+        Self {
+            length: range.length,
+            offset: Some(range.offset),
+        }
+    }
+}
+impl MapByterange {
+    /// The offset of the first byte after the range (`o + n`), or `None` if the end would overflow
+    /// `u64`.
+    pub fn end(&self) -> Option<u64> {
+        self.offset.checked_add(self.length)
+    }
+}
+impl TryFrom<MapByterange> for RangeRequest {
+    type Error = RangeRequestError;
+
+    fn try_from(range: MapByterange) -> Result<Self, Self::Error> {
+        let end = range.end().ok_or(RangeRequestError::Overflow)?;
+        Ok(Self {
+            start: range.offset,
+            end,
+        })
+    }
+}
 
 impl<'a> TryFrom<UnknownTag<'a>> for Map<'a> {
     type Error = ValidationError;
@@ -137,7 +192,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Map<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut uri = None;
         let mut byterange = LazyAttribute::None;
         for (name, value) in attribute_list {
@@ -155,6 +210,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Map<'a> {
             byterange,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -169,6 +225,7 @@ impl<'a> Map<'a> {
             byterange: byterange.map(LazyAttribute::new).unwrap_or_default(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -235,15 +292,20 @@ impl<'a> Map<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&MapAttributeList {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> MapAttributeList<'_> {
+        MapAttributeList {
             uri: self.uri().into(),
             byterange: self.byterange(),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(Map);
+serde_tag!(Map, MapAttributeList);
 
 const URI: &str = "URI";
 const BYTERANGE: &str = "BYTERANGE";
@@ -263,6 +325,31 @@ mod tests {
     use crate::tag::{IntoInnerTag, hls::test_macro::mutation_tests};
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn map_byterange_should_convert_to_and_from_decimal_integer_range() {
+        let range = DecimalIntegerRange {
+            length: 100,
+            offset: Some(200),
+        };
+        let map_byterange = MapByterange::try_from(range).expect("offset should be present");
+        assert_eq!(
+            MapByterange {
+                length: 100,
+                offset: 200
+            },
+            map_byterange
+        );
+        assert_eq!(Some(300), map_byterange.end());
+        assert_eq!(range, DecimalIntegerRange::from(map_byterange));
+        assert_eq!(
+            Err(ParseMapByterangeError::MissingOffset),
+            MapByterange::try_from(DecimalIntegerRange {
+                length: 100,
+                offset: None
+            })
+        );
+    }
+
     #[test]
     fn as_str_no_byterange_should_be_valid() {
         assert_eq!(
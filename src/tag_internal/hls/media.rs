@@ -1,8 +1,14 @@
 use crate::{
-    error::{ParseTagValueError, UnrecognizedEnumerationError, ValidationError},
+    error::{
+        NumericAttributeError, ParseTagValueError, UnrecognizedEnumerationError, ValidationError,
+    },
     tag::{
         AttributeValue, UnknownTag, UnquotedAttributeValue,
-        hls::{EnumeratedString, EnumeratedStringList, LazyAttribute, into_inner_tag},
+        hls::{
+            EnumeratedString, EnumeratedStringList, ExtensionAttributeValue, LazyAttribute,
+            insert_ordered, into_inner_tag, iter_extension_attributes, push_extension_attributes,
+            serde_tag,
+        },
     },
     utils::AsStaticCow,
 };
@@ -399,6 +405,106 @@ impl<'a> ValidChannels<'a> {
         };
         Self { count, inner }
     }
+
+    /// Sets the count of audio channels, keeping the other parameters as they were.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::tag::hls::ValidChannels;
+    /// let mut channels = ValidChannels::try_from("16/JOC").expect("should be valid");
+    /// channels.set_count(12);
+    /// assert_eq!("12/JOC", channels.as_ref());
+    /// ```
+    pub fn set_count(&mut self, count: u32) {
+        let spatial_audio = self.spatial_audio().to_string();
+        let special_usage = self.special_usage().to_string();
+        self.set_parameters(count, &spatial_audio, &special_usage);
+    }
+
+    /// Sets the spatial audio identifiers, keeping the other parameters as they were.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::tag::hls::{ValidChannels, AudioCodingIdentifier};
+    /// let mut channels = ValidChannels::try_from("6/-/DOWNMIX").expect("should be valid");
+    /// channels.set_spatial_audio([AudioCodingIdentifier::JointObjectCoding]);
+    /// assert_eq!("6/JOC/DOWNMIX", channels.as_ref());
+    /// channels.set_spatial_audio("");
+    /// assert_eq!("6/-/DOWNMIX", channels.as_ref());
+    /// ```
+    /// Identifiers that are empty or contain `/` cannot be represented in the value and so are not
+    /// written.
+    pub fn set_spatial_audio(
+        &mut self,
+        spatial_audio: impl Into<EnumeratedStringList<'a, AudioCodingIdentifier>>,
+    ) {
+        let spatial_audio = spatial_audio.into().to_string();
+        let special_usage = self.special_usage().to_string();
+        self.set_parameters(self.count, &spatial_audio, &special_usage);
+    }
+
+    /// Sets the special usage identifiers, keeping the other parameters as they were.
+    ///
+    /// Individual identifiers can be added or removed by modifying the list provided by
+    /// [`Self::special_usage`]. For example:
+    /// ```
+    /// # use quick_m3u8::tag::hls::{ValidChannels, ChannelSpecialUsageIdentifier};
+    /// let mut channels = ValidChannels::try_from("2").expect("should be valid");
+    /// let mut special_usage = channels.special_usage().to_owned();
+    /// special_usage.insert(ChannelSpecialUsageIdentifier::Binaural);
+    /// channels.set_special_usage(special_usage);
+    /// assert_eq!("2/-/BINAURAL", channels.as_ref());
+    /// assert!(channels.is_binaural());
+    /// ```
+    /// Identifiers that are empty or contain `/` cannot be represented in the value and so are not
+    /// written.
+    pub fn set_special_usage(
+        &mut self,
+        special_usage: impl Into<EnumeratedStringList<'a, ChannelSpecialUsageIdentifier>>,
+    ) {
+        let spatial_audio = self.spatial_audio().to_string();
+        let special_usage = special_usage.into().to_string();
+        self.set_parameters(self.count, &spatial_audio, &special_usage);
+    }
+
+    // Rebuilds the value from the provided parameters, retaining any unknown parameters (which
+    // requires the spatial audio and special usage parameters to be written, using `-` if empty).
+    // Identifiers that are empty or contain `/` cannot be represented (the latter would be read as
+    // separate parameters) and so are not written.
+    fn set_parameters(&mut self, count: u32, spatial_audio: &str, special_usage: &str) {
+        let spatial_audio = &representable_identifiers(spatial_audio);
+        let special_usage = &representable_identifiers(special_usage);
+        let unknown = self.unknown_parameters().collect::<Vec<_>>();
+        let mut inner = format!("{count}");
+        if !spatial_audio.is_empty() || !special_usage.is_empty() || !unknown.is_empty() {
+            let spatial_audio = if spatial_audio.is_empty() {
+                "-"
+            } else {
+                spatial_audio
+            };
+            inner.push_str(&format!("/{spatial_audio}"));
+        }
+        if !special_usage.is_empty() || !unknown.is_empty() {
+            let special_usage = if special_usage.is_empty() {
+                "-"
+            } else {
+                special_usage
+            };
+            inner.push_str(&format!("/{special_usage}"));
+        }
+        for parameter in unknown {
+            inner.push_str(&format!("/{parameter}"));
+        }
+        self.count = count;
+        self.inner = Cow::Owned(inner);
+    }
+}
+fn representable_identifiers(list: &str) -> String {
+    let identifiers = list
+        .split(',')
+        .filter(|identifier| !identifier.is_empty() && !identifier.contains('/'))
+        .collect::<Vec<_>>();
+    identifiers.join(",")
 }
 impl ValidChannels<'_> {
     /// Incicates a count of audio, incicating the maximum number of independent, simultaneous audio
@@ -435,6 +541,24 @@ impl ValidChannels<'_> {
         }
         EnumeratedStringList::from(sui_str)
     }
+    /// Indicates that the audio is binaural ([`ChannelSpecialUsageIdentifier::Binaural`] is
+    /// present in [`Self::special_usage`]).
+    pub fn is_binaural(&self) -> bool {
+        self.special_usage()
+            .contains(ChannelSpecialUsageIdentifier::Binaural)
+    }
+    /// Indicates that the audio is immersive ([`ChannelSpecialUsageIdentifier::Immersive`] is
+    /// present in [`Self::special_usage`]).
+    pub fn is_immersive(&self) -> bool {
+        self.special_usage()
+            .contains(ChannelSpecialUsageIdentifier::Immersive)
+    }
+    /// Indicates that the audio is a downmix ([`ChannelSpecialUsageIdentifier::Downmix`] is
+    /// present in [`Self::special_usage`]).
+    pub fn is_downmix(&self) -> bool {
+        self.special_usage()
+            .contains(ChannelSpecialUsageIdentifier::Downmix)
+    }
     /// At the time of writing the HLS specification only defined 3 parameters (described here via
     /// [`Self::count`], [`Self::spatial_audio`], and [`Self::special_usage`]). In case more
     /// parameters are added later, this method will expose those as a split on `'/'`.
@@ -458,6 +582,19 @@ impl<'a> TryFrom<&'a str> for ValidChannels<'a> {
         }
     }
 }
+/// Parses an owned `ValidChannels` (use [`ValidChannels::try_from`] to borrow the string instead).
+/// The only failure is when the channel count (the first parameter) is not a valid `u32`.
+impl std::str::FromStr for ValidChannels<'static> {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let count = s.split('/').next().unwrap_or_default().parse::<u32>()?;
+        Ok(Self {
+            count,
+            inner: Cow::Owned(s.to_string()),
+        })
+    }
+}
 impl Display for ValidChannels<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_ref())
@@ -541,6 +678,7 @@ impl<'a> From<Channels<'a>> for Cow<'a, str> {
 ///
 /// See [`Media`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct MediaAttributeList<'a> {
     /// Corresponds to the `TYPE` attribute.
     ///
@@ -602,6 +740,9 @@ struct MediaAttributeList<'a> {
     ///
     /// See [`Media`] for a link to the HLS documentation for this attribute.
     channels: Option<Cow<'a, str>>,
+    /// Corresponds to any attributes that are not defined by the HLS specification for this tag
+    /// (such as vendor specific attributes), in the order that they were added.
+    unknown_attributes: Vec<(Cow<'a, str>, ExtensionAttributeValue<'a>)>,
 }
 
 /// Placeholder struct for [`MediaBuilder`] indicating that `media_type` needs to be set.
@@ -655,6 +796,7 @@ impl<'a> MediaBuilder<'a, MediaTypeNeedsToBeSet, MediaNameNeedsToBeSet, MediaGro
                 sample_rate: Default::default(),
                 characteristics: Default::default(),
                 channels: Default::default(),
+                unknown_attributes: Default::default(),
             },
             type_status: PhantomData,
             name_status: PhantomData,
@@ -715,16 +857,40 @@ impl<'a, TypeStatus, NameStatus, GroupIdStatus>
         self.attribute_list.uri = Some(uri.into());
         self
     }
+    /// Add the provided `uri` to the attributes built into `Media`, if it is `Some` (otherwise this
+    /// is a no-op).
+    pub fn with_uri_opt(self, uri: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match uri {
+            Some(uri) => self.with_uri(uri),
+            None => self,
+        }
+    }
     /// Add the provided `language` to the attributes built into `Media`.
     pub fn with_language(mut self, language: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.language = Some(language.into());
         self
     }
+    /// Add the provided `language` to the attributes built into `Media`, if it is `Some` (otherwise
+    /// this is a no-op).
+    pub fn with_language_opt(self, language: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match language {
+            Some(language) => self.with_language(language),
+            None => self,
+        }
+    }
     /// Add the provided `assoc_language` to the attributes built into `Media`.
     pub fn with_assoc_language(mut self, assoc_language: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.assoc_language = Some(assoc_language.into());
         self
     }
+    /// Add the provided `assoc_language` to the attributes built into `Media`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_assoc_language_opt(self, assoc_language: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match assoc_language {
+            Some(assoc_language) => self.with_assoc_language(assoc_language),
+            None => self,
+        }
+    }
     /// Add the provided `stable_rendition_id` to the attributes built into `Media`.
     pub fn with_stable_rendition_id(
         mut self,
@@ -733,6 +899,17 @@ impl<'a, TypeStatus, NameStatus, GroupIdStatus>
         self.attribute_list.stable_rendition_id = Some(stable_rendition_id.into());
         self
     }
+    /// Add the provided `stable_rendition_id` to the attributes built into `Media`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_stable_rendition_id_opt(
+        self,
+        stable_rendition_id: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match stable_rendition_id {
+            Some(stable_rendition_id) => self.with_stable_rendition_id(stable_rendition_id),
+            None => self,
+        }
+    }
     /// Add the provided `default` to the attributes built into `Media`.
     pub fn with_default(mut self) -> Self {
         self.attribute_list.default = true;
@@ -753,21 +930,56 @@ impl<'a, TypeStatus, NameStatus, GroupIdStatus>
         self.attribute_list.instream_id = Some(instream_id.into());
         self
     }
+    /// Add the provided `instream_id` to the attributes built into `Media`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_instream_id_opt(self, instream_id: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match instream_id {
+            Some(instream_id) => self.with_instream_id(instream_id),
+            None => self,
+        }
+    }
     /// Add the provided `bit_depth` to the attributes built into `Media`.
     pub fn with_bit_depth(mut self, bit_depth: u64) -> Self {
         self.attribute_list.bit_depth = Some(bit_depth);
         self
     }
+    /// Add the provided `bit_depth` to the attributes built into `Media`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_bit_depth_opt(self, bit_depth: Option<u64>) -> Self {
+        match bit_depth {
+            Some(bit_depth) => self.with_bit_depth(bit_depth),
+            None => self,
+        }
+    }
     /// Add the provided `sample_rate` to the attributes built into `Media`.
     pub fn with_sample_rate(mut self, sample_rate: u64) -> Self {
         self.attribute_list.sample_rate = Some(sample_rate);
         self
     }
+    /// Add the provided `sample_rate` to the attributes built into `Media`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_sample_rate_opt(self, sample_rate: Option<u64>) -> Self {
+        match sample_rate {
+            Some(sample_rate) => self.with_sample_rate(sample_rate),
+            None => self,
+        }
+    }
     /// Add the provided `characteristics` to the attributes built into `Media`.
     pub fn with_characteristics(mut self, characteristics: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.characteristics = Some(characteristics.into());
         self
     }
+    /// Add the provided `characteristics` to the attributes built into `Media`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_characteristics_opt(
+        self,
+        characteristics: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match characteristics {
+            Some(characteristics) => self.with_characteristics(characteristics),
+            None => self,
+        }
+    }
     /// Add the provided `channels` to the attributes built into `Media`.
     ///
     /// Note that [`ValidChannels`] implements `Into<Cow<str>>` and therefore can be
@@ -801,6 +1013,33 @@ impl<'a, TypeStatus, NameStatus, GroupIdStatus>
         self.attribute_list.channels = Some(channels.into());
         self
     }
+    /// Add the provided `channels` to the attributes built into `Media`, if it is `Some` (otherwise
+    /// this is a no-op).
+    pub fn with_channels_opt(self, channels: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match channels {
+            Some(channels) => self.with_channels(channels),
+            None => self,
+        }
+    }
+
+    /// Add the provided attribute, that is not otherwise defined by the HLS specification for
+    /// this tag, to the attributes built into `Media`.
+    ///
+    /// Attributes are written in the order that they are added. If an attribute with the same
+    /// name has already been added then its value is replaced, while keeping its original
+    /// position. Names that are defined by the specification for this tag are ignored (the
+    /// dedicated builder methods should be used for those).
+    pub fn with_unknown_attribute(
+        mut self,
+        name: impl Into<Cow<'a, str>>,
+        value: ExtensionAttributeValue<'a>,
+    ) -> Self {
+        let name = name.into();
+        if !is_known_attribute(&name) {
+            insert_ordered(&mut self.attribute_list.unknown_attributes, name, value);
+        }
+        self
+    }
 }
 impl<'a> Default
     for MediaBuilder<'a, MediaTypeNeedsToBeSet, MediaNameNeedsToBeSet, MediaGroupIdNeedsToBeSet>
@@ -830,8 +1069,10 @@ pub struct Media<'a> {
     sample_rate: LazyAttribute<'a, u64>,
     characteristics: LazyAttribute<'a, Cow<'a, str>>,
     channels: LazyAttribute<'a, Cow<'a, str>>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    unknown_attributes: Vec<(Cow<'a, str>, LazyAttribute<'a, ExtensionAttributeValue<'a>>)>,
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Media<'a> {
@@ -851,6 +1092,7 @@ impl<'a> PartialEq for Media<'a> {
             && self.sample_rate() == other.sample_rate()
             && self.characteristics() == other.characteristics()
             && self.channels() == other.channels()
+            && self.unknown_attributes().eq(other.unknown_attributes())
     }
 }
 
@@ -861,7 +1103,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Media<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut media_type = None;
         let mut group_id = None;
         let mut name = None;
@@ -877,6 +1119,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Media<'a> {
         let mut sample_rate = LazyAttribute::None;
         let mut characteristics = LazyAttribute::None;
         let mut channels = LazyAttribute::None;
+        let mut unknown_attributes = Vec::new();
         for (attr_name, value) in attribute_list {
             match attr_name {
                 TYPE => media_type = value.unquoted().and_then(|v| v.try_as_utf_8().ok()),
@@ -894,7 +1137,8 @@ impl<'a> TryFrom<UnknownTag<'a>> for Media<'a> {
                 SAMPLE_RATE => sample_rate.found(value),
                 CHARACTERISTICS => characteristics.found(value),
                 CHANNELS => channels.found(value),
-                _ => (),
+                _ => unknown_attributes
+                    .push((Cow::Borrowed(attr_name), LazyAttribute::Unparsed(value))),
             }
         }
         let Some(media_type) = media_type else {
@@ -922,8 +1166,10 @@ impl<'a> TryFrom<UnknownTag<'a>> for Media<'a> {
             sample_rate,
             characteristics,
             channels,
+            unknown_attributes,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -948,6 +1194,7 @@ impl<'a> Media<'a> {
             sample_rate,
             characteristics,
             channels,
+            unknown_attributes,
         } = attribute_list;
         Self {
             media_type,
@@ -967,8 +1214,13 @@ impl<'a> Media<'a> {
             sample_rate: sample_rate.map(LazyAttribute::new).unwrap_or_default(),
             characteristics: characteristics.map(LazyAttribute::new).unwrap_or_default(),
             channels: channels.map(LazyAttribute::new).unwrap_or_default(),
+            unknown_attributes: unknown_attributes
+                .into_iter()
+                .map(|(key, value)| (key, LazyAttribute::new(value)))
+                .collect(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -1070,6 +1322,40 @@ impl<'a> Media<'a> {
             LazyAttribute::None => None,
         }
     }
+    /// Whether the `URI` attribute must be present for this `TYPE`.
+    ///
+    /// The `URI` attribute is required for `SUBTITLES`, is optional for `AUDIO` and `VIDEO`, and
+    /// must not be present for `CLOSED-CAPTIONS`. `false` is returned for an unknown `TYPE`.
+    pub fn requires_uri(&self) -> bool {
+        matches!(self.media_type().known(), Some(MediaType::Subtitles))
+    }
+    /// Whether the media of the rendition is carried within the Variant Streams that reference its
+    /// group, rather than within a separate Media Playlist.
+    ///
+    /// This is the case for an `AUDIO` or `VIDEO` rendition with no `URI` (for example, audio that
+    /// is muxed into the video segments of the variant), and for `CLOSED-CAPTIONS` (which are
+    /// always carried in the video stream). A downloader does not need to fetch anything beyond the
+    /// Variant Stream for such a rendition. See [`crate::playlist::renditions`] for finding the
+    /// variants that reference a rendition group.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::tag::hls::{Media, MediaType};
+    /// let muxed = Media::builder()
+    ///     .with_media_type(MediaType::Audio)
+    ///     .with_name("English")
+    ///     .with_group_id("aac")
+    ///     .finish();
+    /// assert!(muxed.is_carried_in_variant());
+    /// assert!(!muxed.requires_uri());
+    /// ```
+    pub fn is_carried_in_variant(&self) -> bool {
+        match self.media_type().known() {
+            Some(MediaType::Audio | MediaType::Video) => self.uri().is_none(),
+            Some(MediaType::ClosedCaptions) => true,
+            Some(MediaType::Subtitles) | None => false,
+        }
+    }
     /// Corresponds to the `LANGUAGE` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
@@ -1164,26 +1450,36 @@ impl<'a> Media<'a> {
     /// Corresponds to the `BIT-DEPTH` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_bit_depth`] to distinguish an invalid value.
     pub fn bit_depth(&self) -> Option<u64> {
-        match &self.bit_depth {
-            LazyAttribute::UserDefined(d) => Some(*d),
-            LazyAttribute::Unparsed(v) => {
-                v.unquoted().and_then(|v| v.try_as_decimal_integer().ok())
-            }
-            LazyAttribute::None => None,
-        }
+        self.try_bit_depth().ok().flatten()
+    }
+
+    /// Corresponds to the `BIT-DEPTH` attribute.
+    ///
+    /// Unlike [`Self::bit_depth`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_bit_depth(&self) -> Result<Option<u64>, NumericAttributeError<'_>> {
+        self.bit_depth.try_decimal_integer()
     }
     /// Corresponds to the `SAMPLE-RATE` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_sample_rate`] to distinguish an invalid value.
     pub fn sample_rate(&self) -> Option<u64> {
-        match &self.sample_rate {
-            LazyAttribute::UserDefined(d) => Some(*d),
-            LazyAttribute::Unparsed(v) => {
-                v.unquoted().and_then(|v| v.try_as_decimal_integer().ok())
-            }
-            LazyAttribute::None => None,
-        }
+        self.try_sample_rate().ok().flatten()
+    }
+
+    /// Corresponds to the `SAMPLE-RATE` attribute.
+    ///
+    /// Unlike [`Self::sample_rate`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_sample_rate(&self) -> Result<Option<u64>, NumericAttributeError<'_>> {
+        self.sample_rate.try_decimal_integer()
     }
     /// Corresponds to the `CHARACTERISTICS` attribute.
     ///
@@ -1482,8 +1778,83 @@ impl<'a> Media<'a> {
         self.output_line_is_dirty = true;
     }
 
+    /// Corresponds to any attributes that are not defined by the HLS specification for this tag
+    /// (such as vendor specific attributes).
+    ///
+    /// The attributes are provided in the order in which they appear (the order in which they were
+    /// parsed, or added), and mutating an existing attribute (via [`Self::set_unknown_attribute`])
+    /// does not change its position. Values that cannot be represented as an
+    /// [`ExtensionAttributeValue`] are skipped (and so are not written if the tag is mutated). For
+    /// example:
+    /// ```
+    /// # use quick_m3u8::{
+    /// # Reader, HlsLine, config::ParsingOptions, tag::KnownTag,
+    /// # tag::hls::{self, ExtensionAttributeValue}
+    /// # };
+    /// let tag = r#"#EXT-X-MEDIA:TYPE=AUDIO,NAME="a",GROUP-ID="a",X-B=0x1F,X-A="A""#;
+    /// let mut reader = Reader::from_str(tag, ParsingOptions::default());
+    /// match reader.read_line() {
+    ///     Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Media(mut media))))) => {
+    ///         media.set_unknown_attribute("X-B", ExtensionAttributeValue::QuotedString("B".into()));
+    ///         assert_eq!(
+    ///             vec![
+    ///                 ("X-B", ExtensionAttributeValue::QuotedString("B".into())),
+    ///                 ("X-A", ExtensionAttributeValue::QuotedString("A".into())),
+    ///             ],
+    ///             media.unknown_attributes().collect::<Vec<_>>()
+    ///         );
+    ///     }
+    ///     r => panic!("unexpected result {r:?}"),
+    /// }
+    /// ```
+    pub fn unknown_attributes(&self) -> impl Iterator<Item = (&str, ExtensionAttributeValue<'_>)> {
+        iter_extension_attributes(&self.unknown_attributes)
+    }
+
+    /// Corresponds to one of the attributes that are not defined by the HLS specification for
+    /// this tag (keyed by `name`).
+    pub fn unknown_attribute(&self, name: &str) -> Option<ExtensionAttributeValue<'_>> {
+        self.unknown_attributes()
+            .find_map(|(key, value)| if key == name { Some(value) } else { None })
+    }
+
+    /// Sets an attribute that is not defined by the HLS specification for this tag.
+    ///
+    /// If the attribute already exists then its value is replaced in place (the order of the
+    /// attributes is preserved), otherwise it is added after the existing attributes. Names that
+    /// are defined by the specification for this tag are ignored (the dedicated setters should be
+    /// used for those).
+    pub fn set_unknown_attribute(
+        &mut self,
+        name: impl Into<Cow<'a, str>>,
+        value: ExtensionAttributeValue<'a>,
+    ) {
+        let name = name.into();
+        if is_known_attribute(&name) {
+            return;
+        }
+        insert_ordered(
+            &mut self.unknown_attributes,
+            name,
+            LazyAttribute::new(value),
+        );
+        self.output_line_is_dirty = true;
+    }
+
+    /// Unsets an attribute that is not defined by the HLS specification for this tag (removes it
+    /// from the list).
+    pub fn unset_unknown_attribute(&mut self, name: &str) {
+        self.unknown_attributes.retain(|(key, _)| key != name);
+        self.output_line_is_dirty = true;
+    }
+
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&MediaAttributeList {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> MediaAttributeList<'_> {
+        MediaAttributeList {
             media_type: self.media_type().into(),
             name: self.name().into(),
             group_id: self.group_id().into(),
@@ -1499,12 +1870,16 @@ impl<'a> Media<'a> {
             sample_rate: self.sample_rate(),
             characteristics: self.characteristics().map(|x| x.into()),
             channels: self.channels().map(|x| x.into()),
-        }));
-        self.output_line_is_dirty = false;
+            unknown_attributes: self
+                .unknown_attributes()
+                .map(|(key, value)| (Cow::Borrowed(key), value))
+                .collect(),
+        }
     }
 }
 
 into_inner_tag!(Media);
+serde_tag!(Media, MediaAttributeList);
 
 const TYPE: &str = "TYPE";
 const URI: &str = "URI";
@@ -1523,6 +1898,26 @@ const CHARACTERISTICS: &str = "CHARACTERISTICS";
 const CHANNELS: &str = "CHANNELS";
 const YES: &[u8] = b"YES";
 
+fn is_known_attribute(name: &str) -> bool {
+    matches!(
+        name,
+        TYPE | URI
+            | GROUP_ID
+            | LANGUAGE
+            | ASSOC_LANGUAGE
+            | NAME
+            | STABLE_RENDITION_ID
+            | DEFAULT
+            | AUTOSELECT
+            | FORCED
+            | INSTREAM_ID
+            | BIT_DEPTH
+            | SAMPLE_RATE
+            | CHARACTERISTICS
+            | CHANNELS
+    )
+}
+
 fn calculate_line(attribute_list: &MediaAttributeList) -> Vec<u8> {
     let MediaAttributeList {
         media_type,
@@ -1540,6 +1935,7 @@ fn calculate_line(attribute_list: &MediaAttributeList) -> Vec<u8> {
         sample_rate,
         characteristics,
         channels,
+        unknown_attributes,
     } = attribute_list;
     let mut line =
         format!("#EXT-X-MEDIA:{TYPE}={media_type},{NAME}=\"{name}\",{GROUP_ID}=\"{group_id}\"");
@@ -1579,6 +1975,7 @@ fn calculate_line(attribute_list: &MediaAttributeList) -> Vec<u8> {
     if let Some(channels) = channels {
         line.push_str(format!(",{CHANNELS}=\"{channels}\"").as_str());
     }
+    push_extension_attributes(&mut line, unknown_attributes);
     line.into_bytes()
 }
 
@@ -1588,6 +1985,17 @@ mod tests {
     use crate::tag::{IntoInnerTag, hls::test_macro::mutation_tests};
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn valid_channels_from_str_should_be_owned() {
+        let channels = "16/JOC/BINAURAL".parse::<ValidChannels<'static>>().unwrap();
+        assert_eq!(
+            ValidChannels::try_from("16/JOC/BINAURAL"),
+            Ok(channels.clone())
+        );
+        assert_eq!(16, channels.count());
+        assert!("JOC/16".parse::<ValidChannels>().is_err());
+    }
+
     #[test]
     fn as_str_with_no_options_should_be_valid() {
         assert_eq!(
@@ -1777,6 +2185,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn channels_setters_should_retain_other_parameters() {
+        let mut channels = ValidChannels::try_from("16/JOC/BINAURAL/FUTURE").unwrap();
+        channels.set_count(12);
+        assert_eq!("12/JOC/BINAURAL/FUTURE", channels.as_ref());
+        channels.set_spatial_audio("");
+        assert_eq!("12/-/BINAURAL/FUTURE", channels.as_ref());
+        channels.set_special_usage([
+            ChannelSpecialUsageIdentifier::Immersive,
+            ChannelSpecialUsageIdentifier::Downmix,
+        ]);
+        assert_eq!("12/-/IMMERSIVE,DOWNMIX/FUTURE", channels.as_ref());
+        assert_eq!(12, channels.count());
+        assert!(!channels.is_binaural());
+        assert!(channels.is_immersive());
+        assert!(channels.is_downmix());
+        assert_eq!(
+            vec!["FUTURE"],
+            channels.unknown_parameters().collect::<Vec<_>>()
+        );
+
+        let mut channels = ValidChannels::new(2, "JOC", "BINAURAL");
+        channels.set_special_usage("");
+        assert_eq!("2/JOC", channels.as_ref());
+        channels.set_spatial_audio("");
+        assert_eq!("2", channels.as_ref());
+    }
+
+    #[test]
+    fn channels_mutation_should_not_panic_on_adversarial_input() {
+        const CHANNELS: [&str; 9] = [
+            "0",
+            "2/",
+            "2//",
+            "2/-",
+            "2/-/-",
+            "2/JOC/",
+            "2/,/,",
+            "2/JOC/BINAURAL/FUTURE/",
+            "2/é/ü",
+        ];
+        const LISTS: [&str; 9] = ["", "-", ",", "/", "JOC", "JOC/", "A,B/C,D", "é", "JOC,"];
+        fn identifiers(list: &str) -> Vec<&str> {
+            list.split(',')
+                .filter(|identifier| !identifier.is_empty() && !identifier.contains('/'))
+                .collect()
+        }
+        for value in CHANNELS {
+            for list in LISTS {
+                let original = ValidChannels::try_from(value).unwrap();
+                let unknown = original.unknown_parameters().collect::<Vec<_>>();
+                let expected = if list == "-" {
+                    Vec::new()
+                } else {
+                    identifiers(list)
+                };
+
+                let mut channels = original.clone();
+                channels.set_spatial_audio(list);
+                assert_eq!(
+                    expected,
+                    identifiers(channels.spatial_audio().as_ref()),
+                    "{value:?} {list:?}"
+                );
+                assert_eq!(
+                    identifiers(original.special_usage().as_ref()),
+                    identifiers(channels.special_usage().as_ref()),
+                    "{value:?} {list:?}"
+                );
+                assert_eq!(unknown, channels.unknown_parameters().collect::<Vec<_>>());
+
+                let mut channels = original.clone();
+                channels.set_special_usage(list);
+                assert_eq!(
+                    expected,
+                    identifiers(channels.special_usage().as_ref()),
+                    "{value:?} {list:?}"
+                );
+                assert_eq!(
+                    identifiers(original.spatial_audio().as_ref()),
+                    identifiers(channels.spatial_audio().as_ref()),
+                    "{value:?} {list:?}"
+                );
+                assert_eq!(unknown, channels.unknown_parameters().collect::<Vec<_>>());
+
+                channels.set_count(u32::MAX);
+                assert_eq!(u32::MAX, channels.count());
+                assert_eq!(
+                    expected,
+                    identifiers(channels.special_usage().as_ref()),
+                    "{value:?} {list:?}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn channels_special_works_as_expected() {
         let test_instances = channels_test_instances();
@@ -1815,4 +2319,57 @@ mod tests {
             ),
         ]
     }
+
+    #[test]
+    fn unknown_attributes_should_be_preserved_in_order_when_mutated() {
+        let mut reader = crate::Reader::from_str(
+            "#EXT-X-MEDIA:TYPE=AUDIO,NAME=\"a\",GROUP-ID=\"g\",X-C=\"c\",X-A=0x0A,X-B=1",
+            crate::config::ParsingOptions::default(),
+        );
+        let Ok(Some(crate::HlsLine::KnownTag(crate::tag::KnownTag::Hls(
+            crate::tag::hls::Tag::Media(mut tag),
+        )))) = reader.read_line()
+        else {
+            panic!("expected Media");
+        };
+        assert_eq!(
+            Some(ExtensionAttributeValue::SignedDecimalFloatingPoint(1.0)),
+            tag.unknown_attribute("X-B")
+        );
+        tag.set_name("b");
+        tag.set_unknown_attribute("X-A", ExtensionAttributeValue::QuotedString("a".into()));
+        tag.set_unknown_attribute("X-D", ExtensionAttributeValue::QuotedString("d".into()));
+        tag.unset_unknown_attribute("X-B");
+        assert_eq!(
+            "#EXT-X-MEDIA:TYPE=AUDIO,NAME=\"b\",GROUP-ID=\"g\",X-C=\"c\",X-A=\"a\",X-D=\"d\""
+                .as_bytes(),
+            tag.into_inner().value()
+        );
+    }
+
+    #[test]
+    fn builder_should_ignore_known_attributes_as_unknown_attributes() {
+        let tag = Media::builder()
+            .with_media_type(MediaType::Audio)
+            .with_name("a")
+            .with_group_id("g")
+            .with_unknown_attribute(
+                "X-B",
+                ExtensionAttributeValue::SignedDecimalFloatingPoint(1.0),
+            )
+            .with_unknown_attribute("URI", ExtensionAttributeValue::QuotedString("u".into()))
+            .with_unknown_attribute("X-A", ExtensionAttributeValue::QuotedString("a".into()))
+            .finish();
+        assert_eq!(
+            vec![
+                (
+                    "X-B",
+                    ExtensionAttributeValue::SignedDecimalFloatingPoint(1.0)
+                ),
+                ("X-A", ExtensionAttributeValue::QuotedString("a".into())),
+            ],
+            tag.unknown_attributes().collect::<Vec<_>>()
+        );
+        assert_eq!(None, tag.uri());
+    }
 }
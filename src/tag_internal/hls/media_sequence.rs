@@ -1,6 +1,9 @@
 use crate::{
     error::{ParseTagValueError, ValidationError},
-    tag::{UnknownTag, hls::into_inner_tag},
+    tag::{
+        UnknownTag,
+        hls::{into_inner_tag, serde_tag},
+    },
 };
 use std::borrow::Cow;
 
@@ -10,8 +13,9 @@ use std::borrow::Cow;
 #[derive(Debug, Clone)]
 pub struct MediaSequence<'a> {
     media_sequence: u64,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for MediaSequence<'a> {
@@ -32,6 +36,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for MediaSequence<'a> {
             media_sequence,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -43,6 +48,7 @@ impl<'a> MediaSequence<'a> {
             media_sequence,
             output_line: Cow::Owned(calculate_line(media_sequence)),
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -68,6 +74,12 @@ impl<'a> MediaSequence<'a> {
 }
 
 into_inner_tag!(MediaSequence);
+serde_tag!(
+    MediaSequence,
+    u64,
+    |tag| tag.media_sequence(),
+    MediaSequence::new
+);
 
 fn calculate_line(media_sequence: u64) -> Vec<u8> {
     format!("#EXT-X-MEDIA-SEQUENCE:{media_sequence}").into_bytes()
@@ -3,10 +3,10 @@
 //! At the time of writing `draft-pantos-hls-rfc8216bis-18` was used for all tag definitions.
 
 use crate::{
-    error::ValidationError,
-    tag::{AttributeValue, IntoInnerTag, TagInner, UnknownTag},
+    error::{NumericAttributeError, ParseNumberError, ValidationError},
+    tag::{AttributeValue, IntoInnerTag, TagInner, UnknownTag, UnquotedAttributeValue},
 };
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 
 mod bitrate;
 mod byterange;
@@ -79,12 +79,15 @@ pub use stream_inf::*;
 pub use targetduration::*;
 pub use version::*;
 
+pub use crate::tag_internal::tag_name::{TagName, TagType};
+
 /// A HLS tag.
 ///
 /// This includes all 32 known tags defined in the draft-pantos-hls specification. The associated
 /// value of each enum case contains the strongly typed tag definition which will have more
 /// documentation on its parameters.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Tag<'a> {
     /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.1.1>
     M3u(M3u),
@@ -198,35 +201,206 @@ impl<'a> TryFrom<UnknownTag<'a>> for Tag<'a> {
     }
 }
 
-// Helper macro for implementing IntoInnerTag for each of the HLS tag implementations. It works
-// because all of the HLS tag implementations have a "recalculate_output_line" method. In the future
-// I may look to move the entire tag implementation into a macro (given that, at least the attribute
-// list tags, share a lot of the same implementation details).
+// Helper macro for implementing IntoInnerTag (and Display) for each of the HLS tag
+// implementations. It works because all of the HLS tag implementations have a
+// "recalculate_output_line" method. In the future I may look to move the entire tag implementation
+// into a macro (given that, at least the attribute list tags, share a lot of the same implementation
+// details).
 macro_rules! into_inner_tag {
     ($name:ident) => {
+        $crate::tag::hls::into_inner_tag!($name, |tag| $name::try_from(tag).ok());
+    };
+    // The expression parses an `UnknownTag` back into the tag type, and is only used with the
+    // `debug-invariants` feature (see `assert_output_line_round_trips`).
+    ($name:ident, |$tag:ident| $reparse:expr) => {
+        impl<'a> $name<'a> {
+            /// The comment attached to the tag (if any).
+            ///
+            /// An attached comment is written by the [`crate::Writer`] on the line before the tag.
+            /// When reading, comments are only attached to tags when enabled via
+            /// [`crate::config::ParsingOptionsBuilder::with_comment_attachment`]. The comment is
+            /// not considered when comparing tags for equality.
+            pub fn comment(&self) -> Option<&str> {
+                self.comment.as_deref()
+            }
+
+            /// Attaches a comment to the tag (see [`Self::comment`]).
+            ///
+            /// The comment is provided without the leading `#` (in the same way as
+            /// [`crate::HlsLine::Comment`]).
+            pub fn set_comment(&mut self, comment: impl Into<std::borrow::Cow<'a, str>>) {
+                self.comment = Some(comment.into());
+            }
+
+            /// Removes the comment attached to the tag (see [`Self::comment`]).
+            pub fn unset_comment(&mut self) {
+                self.comment = None;
+            }
+
+            /// Indicates whether the tag has been modified (via one of its setters) since it was
+            /// parsed or constructed.
+            ///
+            /// A tag that has not been modified is written by the [`crate::Writer`] as the line
+            /// that it was parsed from, byte for byte, whereas the line of a modified tag is
+            /// recalculated from its values (see also
+            /// [`crate::Writer::with_preserved_formatting`]). A line set via
+            /// [`crate::HlsLine::replace_raw`] is written as provided, and so does not count as a
+            /// modification.
+            pub fn is_dirty(&self) -> bool {
+                self.output_line_is_dirty
+            }
+
+            // Used by HlsLine::replace_raw. The typed attributes are left as they are, and so any
+            // later use of a setter recalculates the output line, discarding the replacement.
+            pub(crate) fn replace_output_line(&mut self, bytes: std::borrow::Cow<'a, [u8]>) {
+                self.output_line = bytes;
+                self.output_line_is_dirty = false;
+            }
+
+            // Used by Tag::into_unknown. The line is only available when it can be borrowed for
+            // the lifetime of the tag (i.e. it was parsed, or replaced with borrowed bytes, and has
+            // not been mutated since) and there is no comment that would be lost.
+            pub(crate) fn borrowed_output_line(&self) -> Option<&'a [u8]> {
+                match self.output_line {
+                    std::borrow::Cow::Borrowed(line)
+                        if !self.output_line_is_dirty && self.comment.is_none() =>
+                    {
+                        Some(line)
+                    }
+                    _ => None,
+                }
+            }
+
+            // Checks that the recalculated output line parses back to an equal tag, such that each
+            // getter provides exactly what was set. This catches formatting mistakes in
+            // `calculate_line` that would otherwise only be found when the output is read again.
+            // Values that cannot be represented in the line at all (e.g. a quoted string containing
+            // a double quote) are a mistake of the caller rather than of the formatting, and are
+            // left to `crate::ValueValidation`.
+            #[cfg(all(feature = "debug-invariants", debug_assertions))]
+            fn assert_output_line_round_trips(&self) {
+                let line: &[u8] = &self.output_line;
+                if $crate::writer::validate_tag_line(line).is_some() {
+                    return;
+                }
+                let reparsed = $crate::tag_internal::unknown::parse_assuming_ext_taken(
+                    line.get(4..).unwrap_or_default(),
+                    line,
+                )
+                .ok()
+                .and_then(|parsed| {
+                    let $tag = parsed.parsed;
+                    $reparse
+                });
+                assert!(
+                    reparsed.as_ref() == Some(self),
+                    "output line {:?} does not round trip for {:?} (parsed as {:?})",
+                    String::from_utf8_lossy(line),
+                    self,
+                    reparsed,
+                );
+            }
+        }
         impl<'a> $crate::tag::IntoInnerTag<'a> for $name<'a> {
             fn into_inner(mut self) -> $crate::tag::TagInner<'a> {
+                let mut original_line = None;
                 if self.output_line_is_dirty {
+                    if let std::borrow::Cow::Borrowed(line) = self.output_line {
+                        original_line = Some(line);
+                    }
                     self.recalculate_output_line();
+                    #[cfg(all(feature = "debug-invariants", debug_assertions))]
+                    self.assert_output_line_round_trips();
                 }
                 $crate::tag::TagInner {
                     output_line: self.output_line,
+                    comment: self.comment,
+                    original_line,
+                }
+            }
+        }
+        impl std::fmt::Display for $name<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                if let Some(comment) = &self.comment {
+                    writeln!(f, "#{comment}")?;
+                }
+                if self.output_line_is_dirty {
+                    let mut tag = self.clone();
+                    tag.recalculate_output_line();
+                    #[cfg(all(feature = "debug-invariants", debug_assertions))]
+                    tag.assert_output_line_round_trips();
+                    $crate::utils::fmt_line(&tag.output_line, f)
+                } else {
+                    $crate::utils::fmt_line(&self.output_line, f)
                 }
             }
         }
     };
     ($name:ident @Static $val:literal) => {
+        impl $name {
+            /// Always `false`, as a tag without a value has nothing that can be modified (see
+            /// [`crate::tag::hls::Tag::is_dirty`]).
+            pub fn is_dirty(&self) -> bool {
+                false
+            }
+        }
         impl $crate::tag::IntoInnerTag<'static> for $name {
             fn into_inner(self) -> $crate::tag::TagInner<'static> {
                 $crate::tag::TagInner {
                     output_line: std::borrow::Cow::Borrowed($val),
+                    comment: None,
+                    original_line: None,
                 }
             }
         }
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                $crate::utils::fmt_line($val, f)
+            }
+        }
     };
 }
 use into_inner_tag;
 
+// Helper macro for implementing `Serialize` and `Deserialize` (when the `serde` feature is enabled)
+// for each of the HLS tag implementations. A tag is serialized via a representation of its data,
+// and deserialized by constructing a new tag from that representation (meaning that the output line
+// of a deserialized tag is always recalculated). For the attribute list tags, the representation is
+// the same attribute list struct that is used to calculate the output line (and so the tag must
+// have an `attribute_list` method and a `new` constructor taking the attribute list).
+macro_rules! serde_tag {
+    ($name:ident, $attribute_list:ident) => {
+        $crate::tag::hls::serde_tag!(
+            $name,
+            $attribute_list,
+            |tag| tag.attribute_list(),
+            $name::new
+        );
+    };
+    ($name:ident, $repr:ty, |$tag:ident| $to_repr:expr, $from_repr:expr) => {
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $name<'_> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                let $tag = self;
+                serde::Serialize::serialize(&$to_repr, serializer)
+            }
+        }
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $name<'_> {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                <$repr as serde::Deserialize>::deserialize(deserializer).map($from_repr)
+            }
+        }
+    };
+}
+use serde_tag;
+
 impl<'a> IntoInnerTag<'a> for Tag<'a> {
     fn into_inner(self) -> TagInner<'a> {
         match self {
@@ -266,7 +440,101 @@ impl<'a> IntoInnerTag<'a> for Tag<'a> {
     }
 }
 
+impl Display for Tag<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tag::M3u(t) => Display::fmt(t, f),
+            Tag::Version(t) => Display::fmt(t, f),
+            Tag::IndependentSegments(t) => Display::fmt(t, f),
+            Tag::Start(t) => Display::fmt(t, f),
+            Tag::Define(t) => Display::fmt(t, f),
+            Tag::Targetduration(t) => Display::fmt(t, f),
+            Tag::MediaSequence(t) => Display::fmt(t, f),
+            Tag::DiscontinuitySequence(t) => Display::fmt(t, f),
+            Tag::Endlist(t) => Display::fmt(t, f),
+            Tag::PlaylistType(t) => Display::fmt(t, f),
+            Tag::IFramesOnly(t) => Display::fmt(t, f),
+            Tag::PartInf(t) => Display::fmt(t, f),
+            Tag::ServerControl(t) => Display::fmt(t, f),
+            Tag::Inf(t) => Display::fmt(t, f),
+            Tag::Byterange(t) => Display::fmt(t, f),
+            Tag::Discontinuity(t) => Display::fmt(t, f),
+            Tag::Key(t) => Display::fmt(t, f),
+            Tag::Map(t) => Display::fmt(t, f),
+            Tag::ProgramDateTime(t) => Display::fmt(t, f),
+            Tag::Gap(t) => Display::fmt(t, f),
+            Tag::Bitrate(t) => Display::fmt(t, f),
+            Tag::Part(t) => Display::fmt(t, f),
+            Tag::Daterange(t) => Display::fmt(t, f),
+            Tag::Skip(t) => Display::fmt(t, f),
+            Tag::PreloadHint(t) => Display::fmt(t, f),
+            Tag::RenditionReport(t) => Display::fmt(t, f),
+            Tag::Media(t) => Display::fmt(t, f),
+            Tag::StreamInf(t) => Display::fmt(t, f),
+            Tag::IFrameStreamInf(t) => Display::fmt(t, f),
+            Tag::SessionData(t) => Display::fmt(t, f),
+            Tag::SessionKey(t) => Display::fmt(t, f),
+            Tag::ContentSteering(t) => Display::fmt(t, f),
+        }
+    }
+}
+
 impl Tag<'_> {
+    /// Indicates whether the tag has been modified since it was parsed or constructed, meaning that
+    /// the [`crate::Writer`] writes a line recalculated from the values of the tag, rather than the
+    /// line that it was parsed from.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions, tag::{KnownTag, hls}};
+    /// let mut reader = Reader::from_str("#EXTINF:4.000,\n", ParsingOptions::default());
+    /// let Some(HlsLine::KnownTag(KnownTag::Hls(mut tag))) = reader.read_line()? else {
+    ///     unreachable!()
+    /// };
+    /// assert!(!tag.is_dirty());
+    /// if let hls::Tag::Inf(inf) = &mut tag {
+    ///     inf.set_title("Intro");
+    /// }
+    /// assert!(tag.is_dirty());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn is_dirty(&self) -> bool {
+        match self {
+            Tag::M3u(t) => t.is_dirty(),
+            Tag::Version(t) => t.is_dirty(),
+            Tag::IndependentSegments(t) => t.is_dirty(),
+            Tag::Start(t) => t.is_dirty(),
+            Tag::Define(t) => t.is_dirty(),
+            Tag::Targetduration(t) => t.is_dirty(),
+            Tag::MediaSequence(t) => t.is_dirty(),
+            Tag::DiscontinuitySequence(t) => t.is_dirty(),
+            Tag::Endlist(t) => t.is_dirty(),
+            Tag::PlaylistType(t) => t.is_dirty(),
+            Tag::IFramesOnly(t) => t.is_dirty(),
+            Tag::PartInf(t) => t.is_dirty(),
+            Tag::ServerControl(t) => t.is_dirty(),
+            Tag::Inf(t) => t.is_dirty(),
+            Tag::Byterange(t) => t.is_dirty(),
+            Tag::Discontinuity(t) => t.is_dirty(),
+            Tag::Key(t) => t.is_dirty(),
+            Tag::Map(t) => t.is_dirty(),
+            Tag::ProgramDateTime(t) => t.is_dirty(),
+            Tag::Gap(t) => t.is_dirty(),
+            Tag::Bitrate(t) => t.is_dirty(),
+            Tag::Part(t) => t.is_dirty(),
+            Tag::Daterange(t) => t.is_dirty(),
+            Tag::Skip(t) => t.is_dirty(),
+            Tag::PreloadHint(t) => t.is_dirty(),
+            Tag::RenditionReport(t) => t.is_dirty(),
+            Tag::Media(t) => t.is_dirty(),
+            Tag::StreamInf(t) => t.is_dirty(),
+            Tag::IFrameStreamInf(t) => t.is_dirty(),
+            Tag::SessionData(t) => t.is_dirty(),
+            Tag::SessionKey(t) => t.is_dirty(),
+            Tag::ContentSteering(t) => t.is_dirty(),
+        }
+    }
+
     /// Get the known name of the tag.
     pub fn name(&self) -> TagName {
         match self {
@@ -306,260 +574,191 @@ impl Tag<'_> {
     }
 }
 
-/// An enumeration of all the tag names defined in the HLS specification.
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-pub enum TagName {
-    /// Corresponds to `#EXTM3U`.
-    M3u,
-    /// Corresponds to `#EXT-X-VERSION`.
-    Version,
-    /// Corresponds to `#EXT-X-INDEPENDENT-SEGMENTS`.
-    IndependentSegments,
-    /// Corresponds to `#EXT-X-START`.
-    Start,
-    /// Corresponds to `#EXT-X-DEFINE`.
-    Define,
-    /// Corresponds to `#EXT-X-TARGETDURATION`.
-    Targetduration,
-    /// Corresponds to `#EXT-X-MEDIA-SEQUENCE`.
-    MediaSequence,
-    /// Corresponds to `#EXT-X-DISCONTINUITY-SEQUENCE`.
-    DiscontinuitySequence,
-    /// Corresponds to `#EXT-X-ENDLIST`.
-    Endlist,
-    /// Corresponds to `#EXT-X-PLAYLIST-TYPE`.
-    PlaylistType,
-    /// Corresponds to `#EXT-X-I-FRAMES-ONLY`.
-    IFramesOnly,
-    /// Corresponds to `#EXT-X-PART-INF`.
-    PartInf,
-    /// Corresponds to `#EXT-X-SERVER-CONTROL`.
-    ServerControl,
-    /// Corresponds to `#EXTINF`.
-    Inf,
-    /// Corresponds to `#EXT-X-BYTERANGE`.
-    Byterange,
-    /// Corresponds to `#EXT-X-DISCONTINUITY`.
-    Discontinuity,
-    /// Corresponds to `#EXT-X-KEY`.
-    Key,
-    /// Corresponds to `#EXT-X-MAP`.
-    Map,
-    /// Corresponds to `#EXT-X-PROGRAM-DATE-TIME`.
-    ProgramDateTime,
-    /// Corresponds to `#EXT-X-GAP`.
-    Gap,
-    /// Corresponds to `#EXT-X-BITRATE`.
-    Bitrate,
-    /// Corresponds to `#EXT-X-PART`.
-    Part,
-    /// Corresponds to `#EXT-X-DATERANGE`.
-    Daterange,
-    /// Corresponds to `#EXT-X-SKIP`.
-    Skip,
-    /// Corresponds to `#EXT-X-PRELOAD-HINT`.
-    PreloadHint,
-    /// Corresponds to `#EXT-X-RENDITION-REPORT`.
-    RenditionReport,
-    /// Corresponds to `#EXT-X-MEDIA`.
-    Media,
-    /// Corresponds to `#EXT-X-STREAM-INF`.
-    StreamInf,
-    /// Corresponds to `#EXT-X-I-FRAME-STREAM-INF`.
-    IFrameStreamInf,
-    /// Corresponds to `#EXT-X-SESSION-DATA`.
-    SessionData,
-    /// Corresponds to `#EXT-X-SESSION-KEY`.
-    SessionKey,
-    /// Corresponds to `#EXT-X-CONTENT-STEERING`.
-    ContentSteering,
+impl<'a> Tag<'a> {
+    /// Converts the tag into an [`UnknownTag`], so that it can be handled in the same way as any
+    /// other tag line (e.g. by a layer that operates generically on raw tags), without writing and
+    /// reading the line again.
+    ///
+    /// The `UnknownTag` borrows its line, and so the conversion is only possible when the line of
+    /// the tag can be borrowed for the lifetime of the tag, which is the case for a tag that was
+    /// parsed (or had its line replaced with borrowed data via [`crate::HlsLine::replace_raw`]) and
+    /// has not been mutated since. A tag that was constructed or mutated, or that has an attached
+    /// comment (which would otherwise be lost), is provided back as the error. Such a tag can be
+    /// written (or displayed) and the output read again instead.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions, tag::{KnownTag, hls}};
+    /// let mut reader = Reader::from_str("#EXT-X-BITRATE:1500", ParsingOptions::default());
+    /// let Some(HlsLine::KnownTag(KnownTag::Hls(tag))) = reader.read_line()? else {
+    ///     panic!("unexpected line")
+    /// };
+    /// let unknown = tag.into_unknown().expect("parsed tag should be borrowed");
+    /// assert_eq!("-X-BITRATE", unknown.name());
+    /// assert_eq!(b"#EXT-X-BITRATE:1500", unknown.as_bytes());
+    ///
+    /// let mut tag = unknown.try_into_hls()?;
+    /// assert!(matches!(tag, hls::Tag::Bitrate(_)));
+    /// if let hls::Tag::Bitrate(bitrate) = &mut tag {
+    ///     bitrate.set_bitrate(2000);
+    /// }
+    /// assert!(tag.into_unknown().is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[allow(clippy::result_large_err)] // See comment on crate::tag::known::Tag.
+    pub fn into_unknown(self) -> Result<UnknownTag<'a>, Self> {
+        let line = match &self {
+            Tag::M3u(t) => static_output_line(t.into_inner()),
+            Tag::Version(t) => t.borrowed_output_line(),
+            Tag::IndependentSegments(t) => static_output_line(t.into_inner()),
+            Tag::Start(t) => t.borrowed_output_line(),
+            Tag::Define(t) => t.borrowed_output_line(),
+            Tag::Targetduration(t) => t.borrowed_output_line(),
+            Tag::MediaSequence(t) => t.borrowed_output_line(),
+            Tag::DiscontinuitySequence(t) => t.borrowed_output_line(),
+            Tag::Endlist(t) => static_output_line(t.into_inner()),
+            Tag::PlaylistType(t) => static_output_line(t.into_inner()),
+            Tag::IFramesOnly(t) => static_output_line(t.into_inner()),
+            Tag::PartInf(t) => t.borrowed_output_line(),
+            Tag::ServerControl(t) => t.borrowed_output_line(),
+            Tag::Inf(t) => t.borrowed_output_line(),
+            Tag::Byterange(t) => t.borrowed_output_line(),
+            Tag::Discontinuity(t) => static_output_line(t.into_inner()),
+            Tag::Key(t) => t.borrowed_output_line(),
+            Tag::Map(t) => t.borrowed_output_line(),
+            Tag::ProgramDateTime(t) => t.borrowed_output_line(),
+            Tag::Gap(t) => static_output_line(t.into_inner()),
+            Tag::Bitrate(t) => t.borrowed_output_line(),
+            Tag::Part(t) => t.borrowed_output_line(),
+            Tag::Daterange(t) => t.borrowed_output_line(),
+            Tag::Skip(t) => t.borrowed_output_line(),
+            Tag::PreloadHint(t) => t.borrowed_output_line(),
+            Tag::RenditionReport(t) => t.borrowed_output_line(),
+            Tag::Media(t) => t.borrowed_output_line(),
+            Tag::StreamInf(t) => t.borrowed_output_line(),
+            Tag::IFrameStreamInf(t) => t.borrowed_output_line(),
+            Tag::SessionData(t) => t.borrowed_output_line(),
+            Tag::SessionKey(t) => t.borrowed_output_line(),
+            Tag::ContentSteering(t) => t.borrowed_output_line(),
+        };
+        match line.and_then(|line| {
+            crate::tag_internal::unknown::parse_assuming_ext_taken(line.get(4..)?, line).ok()
+        }) {
+            Some(parsed) => Ok(parsed.parsed),
+            None => Err(self),
+        }
+    }
 }
 
-impl TryFrom<&'_ str> for TagName {
-    type Error = ValidationError;
-
-    fn try_from(value: &'_ str) -> Result<Self, Self::Error> {
-        match value {
-            "M3U" => Ok(Self::M3u),
-            "-X-VERSION" => Ok(Self::Version),
-            "-X-INDEPENDENT-SEGMENTS" => Ok(Self::IndependentSegments),
-            "-X-START" => Ok(Self::Start),
-            "-X-DEFINE" => Ok(Self::Define),
-            "-X-TARGETDURATION" => Ok(Self::Targetduration),
-            "-X-MEDIA-SEQUENCE" => Ok(Self::MediaSequence),
-            "-X-DISCONTINUITY-SEQUENCE" => Ok(Self::DiscontinuitySequence),
-            "-X-ENDLIST" => Ok(Self::Endlist),
-            "-X-PLAYLIST-TYPE" => Ok(Self::PlaylistType),
-            "-X-I-FRAMES-ONLY" => Ok(Self::IFramesOnly),
-            "-X-PART-INF" => Ok(Self::PartInf),
-            "-X-SERVER-CONTROL" => Ok(Self::ServerControl),
-            "INF" => Ok(Self::Inf),
-            "-X-BYTERANGE" => Ok(Self::Byterange),
-            "-X-DISCONTINUITY" => Ok(Self::Discontinuity),
-            "-X-KEY" => Ok(Self::Key),
-            "-X-MAP" => Ok(Self::Map),
-            "-X-PROGRAM-DATE-TIME" => Ok(Self::ProgramDateTime),
-            "-X-GAP" => Ok(Self::Gap),
-            "-X-BITRATE" => Ok(Self::Bitrate),
-            "-X-PART" => Ok(Self::Part),
-            "-X-DATERANGE" => Ok(Self::Daterange),
-            "-X-SKIP" => Ok(Self::Skip),
-            "-X-PRELOAD-HINT" => Ok(Self::PreloadHint),
-            "-X-RENDITION-REPORT" => Ok(Self::RenditionReport),
-            "-X-MEDIA" => Ok(Self::Media),
-            "-X-STREAM-INF" => Ok(Self::StreamInf),
-            "-X-I-FRAME-STREAM-INF" => Ok(Self::IFrameStreamInf),
-            "-X-SESSION-DATA" => Ok(Self::SessionData),
-            "-X-SESSION-KEY" => Ok(Self::SessionKey),
-            "-X-CONTENT-STEERING" => Ok(Self::ContentSteering),
-            _ => Err(ValidationError::UnexpectedTagName),
-        }
+// The tags without a value (along with `EXT-X-PLAYLIST-TYPE`) always have a static output line.
+fn static_output_line(inner: TagInner<'static>) -> Option<&'static [u8]> {
+    match inner.output_line {
+        std::borrow::Cow::Borrowed(line) => Some(line),
+        std::borrow::Cow::Owned(_) => None,
     }
 }
 
-impl TagName {
-    /// The string value that the library will parse as the name of the tag (i.e. the name portion
-    /// after the `#EXT` prefix).
-    pub fn as_str(&self) -> &'static str {
+impl<'a> Tag<'a> {
+    // Replaces the output line of the tag, providing the bytes back when the tag does not support
+    // this (the same tags that do not support comments).
+    pub(crate) fn replace_output_line(
+        &mut self,
+        bytes: std::borrow::Cow<'a, [u8]>,
+    ) -> Result<(), std::borrow::Cow<'a, [u8]>> {
         match self {
-            Self::M3u => "M3U",
-            Self::Version => "-X-VERSION",
-            Self::IndependentSegments => "-X-INDEPENDENT-SEGMENTS",
-            Self::Start => "-X-START",
-            Self::Define => "-X-DEFINE",
-            Self::Targetduration => "-X-TARGETDURATION",
-            Self::MediaSequence => "-X-MEDIA-SEQUENCE",
-            Self::DiscontinuitySequence => "-X-DISCONTINUITY-SEQUENCE",
-            Self::Endlist => "-X-ENDLIST",
-            Self::PlaylistType => "-X-PLAYLIST-TYPE",
-            Self::IFramesOnly => "-X-I-FRAMES-ONLY",
-            Self::PartInf => "-X-PART-INF",
-            Self::ServerControl => "-X-SERVER-CONTROL",
-            Self::Inf => "INF",
-            Self::Byterange => "-X-BYTERANGE",
-            Self::Discontinuity => "-X-DISCONTINUITY",
-            Self::Key => "-X-KEY",
-            Self::Map => "-X-MAP",
-            Self::ProgramDateTime => "-X-PROGRAM-DATE-TIME",
-            Self::Gap => "-X-GAP",
-            Self::Bitrate => "-X-BITRATE",
-            Self::Part => "-X-PART",
-            Self::Daterange => "-X-DATERANGE",
-            Self::Skip => "-X-SKIP",
-            Self::PreloadHint => "-X-PRELOAD-HINT",
-            Self::RenditionReport => "-X-RENDITION-REPORT",
-            Self::Media => "-X-MEDIA",
-            Self::StreamInf => "-X-STREAM-INF",
-            Self::IFrameStreamInf => "-X-I-FRAME-STREAM-INF",
-            Self::SessionData => "-X-SESSION-DATA",
-            Self::SessionKey => "-X-SESSION-KEY",
-            Self::ContentSteering => "-X-CONTENT-STEERING",
+            Tag::M3u(_)
+            | Tag::IndependentSegments(_)
+            | Tag::Endlist(_)
+            | Tag::PlaylistType(_)
+            | Tag::IFramesOnly(_)
+            | Tag::Discontinuity(_)
+            | Tag::Gap(_) => return Err(bytes),
+            Tag::Version(t) => t.replace_output_line(bytes),
+            Tag::Start(t) => t.replace_output_line(bytes),
+            Tag::Define(Define::Name(t)) => t.replace_output_line(bytes),
+            Tag::Define(Define::Import(t)) => t.replace_output_line(bytes),
+            Tag::Define(Define::Queryparam(t)) => t.replace_output_line(bytes),
+            Tag::Targetduration(t) => t.replace_output_line(bytes),
+            Tag::MediaSequence(t) => t.replace_output_line(bytes),
+            Tag::DiscontinuitySequence(t) => t.replace_output_line(bytes),
+            Tag::PartInf(t) => t.replace_output_line(bytes),
+            Tag::ServerControl(t) => t.replace_output_line(bytes),
+            Tag::Inf(t) => t.replace_output_line(bytes),
+            Tag::Byterange(t) => t.replace_output_line(bytes),
+            Tag::Key(t) => t.replace_output_line(bytes),
+            Tag::Map(t) => t.replace_output_line(bytes),
+            Tag::ProgramDateTime(t) => t.replace_output_line(bytes),
+            Tag::Bitrate(t) => t.replace_output_line(bytes),
+            Tag::Part(t) => t.replace_output_line(bytes),
+            Tag::Daterange(t) => t.replace_output_line(bytes),
+            Tag::Skip(t) => t.replace_output_line(bytes),
+            Tag::PreloadHint(t) => t.replace_output_line(bytes),
+            Tag::RenditionReport(t) => t.replace_output_line(bytes),
+            Tag::Media(t) => t.replace_output_line(bytes),
+            Tag::StreamInf(t) => t.replace_output_line(bytes),
+            Tag::IFrameStreamInf(t) => t.replace_output_line(bytes),
+            Tag::SessionData(t) => t.replace_output_line(bytes),
+            Tag::SessionKey(t) => t.replace_output_line(bytes),
+            Tag::ContentSteering(t) => t.replace_output_line(bytes),
         }
+        Ok(())
     }
 
-    /// The type of tag this is, as defined by what sub-section of section 4 the tag appears in.
-    pub fn tag_type(&self) -> TagType {
+    // Attaches the comment to the tag, providing the comment back when the tag does not support
+    // comments (i.e. tags that have no value, along with `EXT-X-PLAYLIST-TYPE`).
+    pub(crate) fn attach_comment(
+        &mut self,
+        comment: std::borrow::Cow<'a, str>,
+    ) -> Result<(), std::borrow::Cow<'a, str>> {
         match self {
-            Self::M3u => TagType::Basic,
-            Self::Version => TagType::Basic,
-            Self::IndependentSegments => TagType::MediaOrMultivariantPlaylist,
-            Self::Start => TagType::MediaOrMultivariantPlaylist,
-            Self::Define => TagType::MediaOrMultivariantPlaylist,
-            Self::Targetduration => TagType::MediaPlaylist,
-            Self::MediaSequence => TagType::MediaPlaylist,
-            Self::DiscontinuitySequence => TagType::MediaPlaylist,
-            Self::Endlist => TagType::MediaPlaylist,
-            Self::PlaylistType => TagType::MediaPlaylist,
-            Self::IFramesOnly => TagType::MediaPlaylist,
-            Self::PartInf => TagType::MediaPlaylist,
-            Self::ServerControl => TagType::MediaPlaylist,
-            Self::Inf => TagType::MediaSegment,
-            Self::Byterange => TagType::MediaSegment,
-            Self::Discontinuity => TagType::MediaSegment,
-            Self::Key => TagType::MediaSegment,
-            Self::Map => TagType::MediaSegment,
-            Self::ProgramDateTime => TagType::MediaSegment,
-            Self::Gap => TagType::MediaSegment,
-            Self::Bitrate => TagType::MediaSegment,
-            Self::Part => TagType::MediaSegment,
-            Self::Daterange => TagType::MediaMetadata,
-            Self::Skip => TagType::MediaMetadata,
-            Self::PreloadHint => TagType::MediaMetadata,
-            Self::RenditionReport => TagType::MediaMetadata,
-            Self::Media => TagType::MultivariantPlaylist,
-            Self::StreamInf => TagType::MultivariantPlaylist,
-            Self::IFrameStreamInf => TagType::MultivariantPlaylist,
-            Self::SessionData => TagType::MultivariantPlaylist,
-            Self::SessionKey => TagType::MultivariantPlaylist,
-            Self::ContentSteering => TagType::MultivariantPlaylist,
+            Tag::M3u(_)
+            | Tag::IndependentSegments(_)
+            | Tag::Endlist(_)
+            | Tag::PlaylistType(_)
+            | Tag::IFramesOnly(_)
+            | Tag::Discontinuity(_)
+            | Tag::Gap(_) => return Err(comment),
+            Tag::Version(t) => t.set_comment(comment),
+            Tag::Start(t) => t.set_comment(comment),
+            Tag::Define(Define::Name(t)) => t.set_comment(comment),
+            Tag::Define(Define::Import(t)) => t.set_comment(comment),
+            Tag::Define(Define::Queryparam(t)) => t.set_comment(comment),
+            Tag::Targetduration(t) => t.set_comment(comment),
+            Tag::MediaSequence(t) => t.set_comment(comment),
+            Tag::DiscontinuitySequence(t) => t.set_comment(comment),
+            Tag::PartInf(t) => t.set_comment(comment),
+            Tag::ServerControl(t) => t.set_comment(comment),
+            Tag::Inf(t) => t.set_comment(comment),
+            Tag::Byterange(t) => t.set_comment(comment),
+            Tag::Key(t) => t.set_comment(comment),
+            Tag::Map(t) => t.set_comment(comment),
+            Tag::ProgramDateTime(t) => t.set_comment(comment),
+            Tag::Bitrate(t) => t.set_comment(comment),
+            Tag::Part(t) => t.set_comment(comment),
+            Tag::Daterange(t) => t.set_comment(comment),
+            Tag::Skip(t) => t.set_comment(comment),
+            Tag::PreloadHint(t) => t.set_comment(comment),
+            Tag::RenditionReport(t) => t.set_comment(comment),
+            Tag::Media(t) => t.set_comment(comment),
+            Tag::StreamInf(t) => t.set_comment(comment),
+            Tag::IFrameStreamInf(t) => t.set_comment(comment),
+            Tag::SessionData(t) => t.set_comment(comment),
+            Tag::SessionKey(t) => t.set_comment(comment),
+            Tag::ContentSteering(t) => t.set_comment(comment),
         }
+        Ok(())
     }
 }
 
-/// The type of tag, as determined by the tag sections in the HLS specification.
-///
-/// The sections that are defined are:
-/// * 4.4.1. Basic Tags
-///     * These tags are allowed in both Media Playlists and Multivariant Playlists.
-/// * 4.4.2. Media or Multivariant Playlist Tags
-///     * The tags in this section can appear in either Multivariant Playlists or Media Playlists.
-///       
-///       Tags in this section MUST NOT appear more than once in a Playlist. If one does, clients
-///       MUST fail to parse the Playlist. The only exception to this rule is EXT-X-DEFINE, which
-///       MAY appear more than once.
-/// * 4.4.3. Media Playlist Tags
-///     * Media Playlist tags describe global parameters of the Media Playlist. There MUST NOT be
-///       more than one Media Playlist tag of each type in any Media Playlist.
-///       
-///       A Media Playlist tag MUST NOT appear in a Multivariant Playlist.
-/// * 4.4.4. Media Segment Tags
-///     * Each Media Segment is specified by a series of Media Segment tags followed by a URI. Some
-///       Media Segment tags apply to just the next segment; others apply to all subsequent segments
-///       until another instance of the same tag.
-///       
-///       A Media Segment tag MUST NOT appear in a Multivariant Playlist. Clients MUST fail to parse
-///       Playlists that contain both Media Segment tags and Multivariant Playlist tags.
-/// * 4.4.5. Media Metadata Tags
-///     * Media Metadata tags provide information about the playlist that is not associated with
-///       specific Media Segments. There MAY be more than one Media Metadata tag of each type in any
-///       Media Playlist. The only exception to this rule is an EXT-X-SKIP, which MUST NOT appear
-///       more than once.
-/// * 4.4.6. Multivariant Playlist Tags
-///     * Multivariant Playlist tags define the Variant Streams, Renditions, and other global
-///       parameters of the presentation.
-///       
-///       Multivariant Playlist tags MUST NOT appear in a Media Playlist; clients MUST fail to parse
-///       any Playlist that contains both a Multivariant Playlist tag and either a Media Playlist
-///       tag or a Media Segment tag.
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum TagType {
-    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.1>
-    Basic,
-    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.2>
-    MediaOrMultivariantPlaylist,
-    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.3>
-    MediaPlaylist,
-    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.4>
-    MediaSegment,
-    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.5>
-    MediaMetadata,
-    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.6>
-    MultivariantPlaylist,
-}
-
 // This is used by all the tag implementations as a means of delaying the parsing of the attribute
 // until the value is retrieved from one of the get methods.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 enum LazyAttribute<'a, T> {
     UserDefined(T),
     Unparsed(AttributeValue<'a>),
+    #[default]
     None,
 }
-impl<T> Default for LazyAttribute<'_, T> {
-    fn default() -> Self {
-        Self::None
-    }
-}
 impl<'a, T> LazyAttribute<'a, T> {
     fn new(t: T) -> Self {
         Self::UserDefined(t)
@@ -577,6 +776,87 @@ impl<'a, T> LazyAttribute<'a, T> {
         *self = Self::None;
     }
 }
+impl<'a> LazyAttribute<'a, u64> {
+    fn try_decimal_integer(&self) -> Result<Option<u64>, NumericAttributeError<'a>> {
+        match self {
+            Self::UserDefined(n) => Ok(Some(*n)),
+            Self::Unparsed(v) => decimal_integer(*v).map(Some),
+            Self::None => Ok(None),
+        }
+    }
+}
+impl<'a> LazyAttribute<'a, f64> {
+    fn try_decimal_floating_point(&self) -> Result<Option<f64>, NumericAttributeError<'a>> {
+        match self {
+            Self::UserDefined(n) => Ok(Some(*n)),
+            Self::Unparsed(v) => decimal_floating_point(*v).map(Some),
+            Self::None => Ok(None),
+        }
+    }
+}
+
+// Numeric attribute values are parsed such that a value that is a number, but cannot be represented
+// by the type of the attribute (e.g. `-1` or an integer that overflows `u64`), is distinguished from
+// a value that is not a number at all.
+fn decimal_integer(value: AttributeValue<'_>) -> Result<u64, NumericAttributeError<'_>> {
+    let (raw, unquoted) = raw_attribute_value(value);
+    let Some(unquoted) = unquoted else {
+        return Err(NumericAttributeError::NotNumeric(raw));
+    };
+    match unquoted.try_as_decimal_integer() {
+        Ok(n) => Ok(n),
+        Err(ParseNumberError::NumberTooBig) => Err(NumericAttributeError::NumericOutOfRange(raw)),
+        Err(_) => match unquoted.0 {
+            [b'-', digits @ ..] if !digits.is_empty() && digits.iter().all(u8::is_ascii_digit) => {
+                Err(NumericAttributeError::NumericOutOfRange(raw))
+            }
+            _ => Err(NumericAttributeError::NotNumeric(raw)),
+        },
+    }
+}
+
+fn decimal_floating_point(value: AttributeValue<'_>) -> Result<f64, NumericAttributeError<'_>> {
+    let (raw, unquoted) = raw_attribute_value(value);
+    let Some(unquoted) = unquoted else {
+        return Err(NumericAttributeError::NotNumeric(raw));
+    };
+    match unquoted.try_as_decimal_floating_point() {
+        Ok(n) if n.is_finite() => Ok(n),
+        // `inf` and `nan` are parsed successfully, but they are not numbers as far as HLS goes.
+        Ok(_)
+            if unquoted
+                .0
+                .iter()
+                .all(|b| b.is_ascii_digit() || b".eE+-".contains(b)) =>
+        {
+            Err(NumericAttributeError::NumericOutOfRange(raw))
+        }
+        _ => Err(NumericAttributeError::NotNumeric(raw)),
+    }
+}
+
+// Quoted values are never numeric, so the unquoted value is only provided for unquoted values.
+fn raw_attribute_value(value: AttributeValue<'_>) -> (&str, Option<UnquotedAttributeValue<'_>>) {
+    match value {
+        AttributeValue::Quoted(s) => (s, None),
+        AttributeValue::Unquoted(v) => (v.try_as_utf_8().unwrap_or_default(), Some(v)),
+    }
+}
+
+// Used when parsing required numeric attributes. A value that is not a number is treated as missing,
+// whereas a number that is out of range is reported as such.
+fn required_numeric<T>(
+    result: Result<T, NumericAttributeError>,
+    name: &'static str,
+) -> Result<Option<T>, ValidationError> {
+    match result {
+        Ok(n) => Ok(Some(n)),
+        Err(NumericAttributeError::NumericOutOfRange(_)) => {
+            Err(ValidationError::NumericOutOfRange(name))
+        }
+        Err(NumericAttributeError::NotNumeric(_)) => Ok(None),
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -597,6 +877,7 @@ mod tests {
                 value: None,
                 original_input: format!("#EXT{}", $name).as_bytes(),
                 validation_error: None,
+                replacement: None,
             })
         };
         ($name:literal $($value:literal)+) => {
@@ -605,6 +886,7 @@ mod tests {
                 value: Some(TagValue([$($value,)+].join("").as_bytes())),
                 original_input: format!("#EXT{}:{}", $name, [$($value,)+].join("")).as_bytes(),
                 validation_error: None,
+                replacement: None,
             })
         };
     }
@@ -647,6 +929,7 @@ mod tests {
             value: Some(TagValue(b"TIME-OFFSET=10,PRECISE=YES")),
             original_input: b"#EXT-X-START:TIME-OFFSET=10,PRECISE=YES",
             validation_error: None,
+            replacement: None,
         })
         .unwrap();
         match (expected, actual) {
@@ -857,6 +1140,7 @@ mod tests {
                 value: None,
                 original_input: b"#EXT-X-GAP",
                 validation_error: None,
+                replacement: None,
             })
         );
     }
@@ -1,16 +1,17 @@
 use crate::{
     error::{ParseTagValueError, ValidationError},
     tag::{
-        AttributeValue, DecimalIntegerRange, UnknownTag, UnquotedAttributeValue,
-        hls::{LazyAttribute, into_inner_tag},
+        AttributeValue, DecimalIntegerRange, SegmentDuration, UnknownTag, UnquotedAttributeValue,
+        hls::{LazyAttribute, decimal_floating_point, into_inner_tag, required_numeric, serde_tag},
     },
 };
-use std::{borrow::Cow, marker::PhantomData};
+use std::{borrow::Cow, fmt::Display, marker::PhantomData};
 
 /// The attribute list for the tag (`#EXT-X-PART:<attribute-list>`).
 ///
 /// See [`Part`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct PartAttributeList<'a> {
     /// Corresponds to the `URI` attribute.
     ///
@@ -115,6 +116,14 @@ impl<'a, UriStatus, DurationStatus> PartBuilder<'a, UriStatus, DurationStatus> {
         self.attribute_list.byterange = Some(byterange);
         self
     }
+    /// Add the provided `byterange` to the attributes built into `Part`, if it is `Some` (otherwise
+    /// this is a no-op).
+    pub fn with_byterange_opt(self, byterange: Option<DecimalIntegerRange>) -> Self {
+        match byterange {
+            Some(byterange) => self.with_byterange(byterange),
+            None => self,
+        }
+    }
     /// Add the provided `gap` to the attributes built into `Part`.
     pub fn with_gap(mut self) -> Self {
         self.attribute_list.gap = true;
@@ -134,11 +143,13 @@ impl<'a> Default for PartBuilder<'a, PartUriNeedsToBeSet, PartDurationNeedsToBeS
 pub struct Part<'a> {
     uri: Cow<'a, str>,
     duration: f64,
+    exact_duration: LazyAttribute<'a, SegmentDuration>,
     independent: LazyAttribute<'a, bool>,
     byterange: LazyAttribute<'a, DecimalIntegerRange>,
     gap: LazyAttribute<'a, bool>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Part<'a> {
@@ -158,9 +169,10 @@ impl<'a> TryFrom<UnknownTag<'a>> for Part<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut uri = None;
         let mut duration = None;
+        let mut exact_duration = LazyAttribute::None;
         let mut independent = LazyAttribute::None;
         let mut byterange = LazyAttribute::None;
         let mut gap = LazyAttribute::None;
@@ -168,9 +180,8 @@ impl<'a> TryFrom<UnknownTag<'a>> for Part<'a> {
             match name {
                 URI => uri = value.quoted(),
                 DURATION => {
-                    duration = value
-                        .unquoted()
-                        .and_then(|v| v.try_as_decimal_floating_point().ok())
+                    duration = required_numeric(decimal_floating_point(value), DURATION)?;
+                    exact_duration.found(value);
                 }
                 INDEPENDENT => independent.found(value),
                 BYTERANGE => byterange.found(value),
@@ -187,11 +198,13 @@ impl<'a> TryFrom<UnknownTag<'a>> for Part<'a> {
         Ok(Self {
             uri: Cow::Borrowed(uri),
             duration,
+            exact_duration,
             independent,
             byterange,
             gap,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -210,11 +223,13 @@ impl<'a> Part<'a> {
         Self {
             uri,
             duration,
+            exact_duration: LazyAttribute::None,
             independent: LazyAttribute::new(independent),
             byterange: byterange.map(LazyAttribute::new).unwrap_or_default(),
             gap: LazyAttribute::new(gap),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -262,6 +277,24 @@ impl<'a> Part<'a> {
         self.duration
     }
 
+    /// Corresponds to the `DURATION` attribute, as a [`SegmentDuration`].
+    ///
+    /// When the tag was parsed, this is exactly the value that was written (including the
+    /// formatting), and otherwise is derived from [`Self::duration`] (see
+    /// [`crate::tag::hls::Inf::segment_duration`] for more details).
+    ///
+    /// See [`Self`] for a link to the HLS documentation for this attribute.
+    pub fn segment_duration(&self) -> SegmentDuration {
+        match &self.exact_duration {
+            LazyAttribute::UserDefined(d) => *d,
+            LazyAttribute::Unparsed(v) => v
+                .unquoted()
+                .and_then(|v| SegmentDuration::try_from(v.0).ok())
+                .unwrap_or_else(|| SegmentDuration::from_secs_f64(self.duration)),
+            LazyAttribute::None => SegmentDuration::from_secs_f64(self.duration),
+        }
+    }
+
     /// Corresponds to the `INDEPENDENT` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
@@ -313,6 +346,16 @@ impl<'a> Part<'a> {
     /// See [`Self`] for a link to the HLS documentation for this attribute.
     pub fn set_duration(&mut self, duration: f64) {
         self.duration = duration;
+        self.exact_duration.unset();
+        self.output_line_is_dirty = true;
+    }
+    /// Sets the `DURATION` attribute from a [`SegmentDuration`], which is written exactly as it is
+    /// displayed.
+    ///
+    /// See [`Self`] for a link to the HLS documentation for this attribute.
+    pub fn set_segment_duration(&mut self, duration: SegmentDuration) {
+        self.duration = duration.as_secs_f64();
+        self.exact_duration.set(duration);
         self.output_line_is_dirty = true;
     }
     /// Sets the `INDEPENDENT` attribute.
@@ -345,18 +388,29 @@ impl<'a> Part<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&PartAttributeList {
+        let attribute_list = self.attribute_list();
+        self.output_line = Cow::Owned(match self.exact_duration {
+            LazyAttribute::UserDefined(duration) => {
+                calculate_line_with_duration(&attribute_list, duration)
+            }
+            _ => calculate_line(&attribute_list),
+        });
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> PartAttributeList<'_> {
+        PartAttributeList {
             uri: self.uri().into(),
             duration: self.duration(),
             independent: self.independent(),
             byterange: self.byterange(),
             gap: self.gap(),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(Part);
+serde_tag!(Part, PartAttributeList);
 
 const URI: &str = "URI";
 const DURATION: &str = "DURATION";
@@ -366,9 +420,16 @@ const GAP: &str = "GAP";
 const YES: &[u8] = b"YES";
 
 fn calculate_line(attribute_list: &PartAttributeList) -> Vec<u8> {
+    calculate_line_with_duration(attribute_list, attribute_list.duration)
+}
+
+fn calculate_line_with_duration(
+    attribute_list: &PartAttributeList,
+    duration: impl Display,
+) -> Vec<u8> {
     let PartAttributeList {
         uri,
-        duration,
+        duration: _,
         independent,
         byterange,
         gap,
@@ -440,6 +501,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn segment_duration_should_retain_original_formatting() {
+        let tag = crate::custom_parsing::tag::parse("#EXT-X-PART:DURATION=1.000,URI=\"part.mp4\"")
+            .unwrap()
+            .parsed;
+        let mut part = Part::try_from(tag).unwrap();
+        assert_eq!("1.000", part.segment_duration().to_string());
+        part.set_independent(true);
+        assert_eq!(
+            b"#EXT-X-PART:URI=\"part.mp4\",DURATION=1,INDEPENDENT=YES",
+            part.clone().into_inner().value()
+        );
+        part.set_segment_duration(SegmentDuration::from_millis(500));
+        assert_eq!(0.5, part.duration());
+        assert_eq!(
+            b"#EXT-X-PART:URI=\"part.mp4\",DURATION=0.500,INDEPENDENT=YES",
+            part.into_inner().value()
+        );
+    }
+
     mutation_tests!(
         Part::builder()
             .with_uri("part.1.0.mp4")
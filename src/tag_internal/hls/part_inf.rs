@@ -1,8 +1,11 @@
 use crate::{
     error::{ParseTagValueError, ValidationError},
-    tag::{UnknownTag, hls::into_inner_tag},
+    tag::{
+        SegmentDuration, UnknownTag,
+        hls::{LazyAttribute, decimal_floating_point, into_inner_tag, required_numeric, serde_tag},
+    },
 };
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt::Display};
 
 /// Corresponds to the `#EXT-X-PART-INF` tag.
 ///
@@ -10,8 +13,10 @@ use std::borrow::Cow;
 #[derive(Debug, Clone)]
 pub struct PartInf<'a> {
     part_target: f64,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    exact_part_target: LazyAttribute<'a, SegmentDuration>,
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for PartInf<'a> {
@@ -27,24 +32,24 @@ impl<'a> TryFrom<UnknownTag<'a>> for PartInf<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
-        let Some(part_target) = attribute_list.iter().find_map(|(name, value)| {
-            if *name == PART_TARGET {
-                value
-                    .unquoted()
-                    .and_then(|v| v.try_as_decimal_floating_point().ok())
-            } else {
-                None
-            }
-        }) else {
+            .try_as_attribute_map()?;
+        let value = attribute_list.get(PART_TARGET);
+        let part_target = value
+            .map(|value| required_numeric(decimal_floating_point(value), PART_TARGET))
+            .transpose()?
+            .flatten();
+        let exact_part_target = value.map(LazyAttribute::Unparsed).unwrap_or_default();
+        let Some(part_target) = part_target else {
             return Err(super::ValidationError::MissingRequiredAttribute(
                 PART_TARGET,
             ));
         };
         Ok(Self {
             part_target,
+            exact_part_target,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -54,8 +59,10 @@ impl<'a> PartInf<'a> {
     pub fn new(part_target: f64) -> Self {
         Self {
             part_target,
+            exact_part_target: LazyAttribute::None,
             output_line: Cow::Owned(calculate_line(part_target)),
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -66,25 +73,58 @@ impl<'a> PartInf<'a> {
         self.part_target
     }
 
+    /// Corresponds to the `PART-TARGET` attribute, as a [`SegmentDuration`].
+    ///
+    /// When the tag was parsed, this is exactly the value that was written (including the
+    /// formatting), and otherwise is derived from [`Self::part_target`] (see
+    /// [`crate::tag::hls::Inf::segment_duration`] for more details).
+    ///
+    /// See [`Self`] for a link to the HLS documentation for this attribute.
+    pub fn part_target_duration(&self) -> SegmentDuration {
+        match &self.exact_part_target {
+            LazyAttribute::UserDefined(d) => *d,
+            LazyAttribute::Unparsed(v) => v
+                .unquoted()
+                .and_then(|v| SegmentDuration::try_from(v.0).ok())
+                .unwrap_or_else(|| SegmentDuration::from_secs_f64(self.part_target)),
+            LazyAttribute::None => SegmentDuration::from_secs_f64(self.part_target),
+        }
+    }
+
     /// Sets `PART-TARGET` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
     pub fn set_part_target(&mut self, part_target: f64) {
         self.part_target = part_target;
+        self.exact_part_target.unset();
+        self.output_line_is_dirty = true;
+    }
+
+    /// Sets `PART-TARGET` attribute from a [`SegmentDuration`], which is written exactly as it is
+    /// displayed.
+    ///
+    /// See [`Self`] for a link to the HLS documentation for this attribute.
+    pub fn set_part_target_duration(&mut self, part_target: SegmentDuration) {
+        self.part_target = part_target.as_secs_f64();
+        self.exact_part_target.set(part_target);
         self.output_line_is_dirty = true;
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(self.part_target()));
+        self.output_line = Cow::Owned(match self.exact_part_target {
+            LazyAttribute::UserDefined(part_target) => calculate_line(part_target),
+            _ => calculate_line(self.part_target()),
+        });
         self.output_line_is_dirty = false;
     }
 }
 
 into_inner_tag!(PartInf);
+serde_tag!(PartInf, f64, |tag| tag.part_target(), PartInf::new);
 
 const PART_TARGET: &str = "PART-TARGET";
 
-fn calculate_line(part_target: f64) -> Vec<u8> {
+fn calculate_line(part_target: impl Display) -> Vec<u8> {
     format!("#EXT-X-PART-INF:{PART_TARGET}={part_target}").into_bytes()
 }
 
@@ -102,5 +142,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn part_target_duration_should_retain_original_formatting() {
+        let tag = crate::custom_parsing::tag::parse("#EXT-X-PART-INF:PART-TARGET=1.000")
+            .unwrap()
+            .parsed;
+        let mut part_inf = PartInf::try_from(tag).unwrap();
+        assert_eq!(
+            SegmentDuration::from_secs(1),
+            part_inf.part_target_duration()
+        );
+        part_inf.set_part_target_duration(SegmentDuration::from_millis(1500));
+        assert_eq!(1.5, part_inf.part_target());
+        assert_eq!(
+            b"#EXT-X-PART-INF:PART-TARGET=1.500",
+            part_inf.into_inner().value()
+        );
+    }
+
+    #[test]
+    fn duplicate_part_target_should_use_last_value() {
+        let tag =
+            crate::custom_parsing::tag::parse("#EXT-X-PART-INF:PART-TARGET=1,PART-TARGET=0.5")
+                .unwrap()
+                .parsed;
+        assert_eq!(0.5, PartInf::try_from(tag).unwrap().part_target());
+    }
+
     mutation_tests!(PartInf::new(0.5), (part_target, 1.0, @Attr="PART-TARGET=1"));
 }
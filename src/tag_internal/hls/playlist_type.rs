@@ -2,12 +2,16 @@ use crate::{
     error::{ParseTagValueError, ValidationError},
     tag::{HlsPlaylistType, IntoInnerTag, UnknownTag, hls::TagInner},
 };
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+};
 
 /// Corresponds to the `#EXT-X-PLAYLIST-TYPE` tag.
 ///
 /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.3.5>
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlaylistType(HlsPlaylistType);
 
 impl TryFrom<UnknownTag<'_>> for PlaylistType {
@@ -41,6 +45,12 @@ impl PlaylistType {
     pub fn set_playlist_type(&mut self, playlist_type: HlsPlaylistType) {
         self.0 = playlist_type;
     }
+
+    /// Always `false`, as the tag is always written in its canonical form (`EVENT` or `VOD`), and
+    /// so there is no original line to compare to (see [`crate::tag::hls::Tag::is_dirty`]).
+    pub fn is_dirty(&self) -> bool {
+        false
+    }
 }
 
 impl IntoInnerTag<'static> for PlaylistType {
@@ -48,14 +58,24 @@ impl IntoInnerTag<'static> for PlaylistType {
         match self.0 {
             HlsPlaylistType::Event => TagInner {
                 output_line: Cow::Borrowed(b"#EXT-X-PLAYLIST-TYPE:EVENT"),
+                comment: None,
+                original_line: None,
             },
             HlsPlaylistType::Vod => TagInner {
                 output_line: Cow::Borrowed(b"#EXT-X-PLAYLIST-TYPE:VOD"),
+                comment: None,
+                original_line: None,
             },
         }
     }
 }
 
+impl Display for PlaylistType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        crate::utils::fmt_line(self.into_inner().value(), f)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,8 +1,10 @@
 use crate::{
-    error::{ParseTagValueError, UnrecognizedEnumerationError, ValidationError},
+    error::{
+        NumericAttributeError, ParseTagValueError, UnrecognizedEnumerationError, ValidationError,
+    },
     tag::{
         UnknownTag,
-        hls::{EnumeratedString, LazyAttribute, into_inner_tag},
+        hls::{EnumeratedString, LazyAttribute, into_inner_tag, serde_tag},
     },
     utils::AsStaticCow,
 };
@@ -58,6 +60,7 @@ const MAP: &str = "MAP";
 ///
 /// See [`PreloadHint`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct PreloadHintAttributeList<'a> {
     /// Corresponds to the `TYPE` attribute.
     ///
@@ -154,12 +157,28 @@ impl<'a, TypeStatus, UriStatus> PreloadHintBuilder<'a, TypeStatus, UriStatus> {
         self.attribute_list.byterange_start = Some(byterange_start);
         self
     }
+    /// Add the provided `byterange_start` to the attributes built into `PreloadHint`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_byterange_start_opt(self, byterange_start: Option<u64>) -> Self {
+        match byterange_start {
+            Some(byterange_start) => self.with_byterange_start(byterange_start),
+            None => self,
+        }
+    }
 
     /// Add the provided `byterange_length` to the attributes built into `PreloadHint`.
     pub fn with_byterange_length(mut self, byterange_length: u64) -> Self {
         self.attribute_list.byterange_length = Some(byterange_length);
         self
     }
+    /// Add the provided `byterange_length` to the attributes built into `PreloadHint`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_byterange_length_opt(self, byterange_length: Option<u64>) -> Self {
+        match byterange_length {
+            Some(byterange_length) => self.with_byterange_length(byterange_length),
+            None => self,
+        }
+    }
 }
 impl<'a> Default
     for PreloadHintBuilder<'a, PreloadHintTypeNeedsToBeSet, PreloadHintUriNeedsToBeSet>
@@ -178,8 +197,9 @@ pub struct PreloadHint<'a> {
     uri: Cow<'a, str>,
     byterange_start: LazyAttribute<'a, u64>,
     byterange_length: LazyAttribute<'a, u64>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for PreloadHint<'a> {
@@ -198,7 +218,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for PreloadHint<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut hint_type = None;
         let mut uri = None;
         let mut byterange_start = LazyAttribute::None;
@@ -225,6 +245,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for PreloadHint<'a> {
             byterange_length,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -246,6 +267,7 @@ impl<'a> PreloadHint<'a> {
             byterange_length: byterange_length.map(LazyAttribute::new).unwrap_or_default(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -297,28 +319,40 @@ impl<'a> PreloadHint<'a> {
     /// Corresponds to the `BYTERANGE-START` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `0` (the default) if the attribute is not present or the value is not valid.
+    /// See [`Self::try_byterange_start`] to distinguish an invalid value.
     pub fn byterange_start(&self) -> u64 {
-        match &self.byterange_start {
-            LazyAttribute::UserDefined(d) => *d,
-            LazyAttribute::Unparsed(v) => v
-                .unquoted()
-                .and_then(|v| v.try_as_decimal_integer().ok())
-                .unwrap_or(0),
-            LazyAttribute::None => 0,
-        }
+        self.try_byterange_start().unwrap_or(0)
+    }
+
+    /// Corresponds to the `BYTERANGE-START` attribute.
+    ///
+    /// Unlike [`Self::byterange_start`], this provides the error (which includes the raw text of
+    /// the value) when the value is present but is not a number, or is a number that is out of
+    /// range.
+    pub fn try_byterange_start(&self) -> Result<u64, NumericAttributeError<'_>> {
+        self.byterange_start
+            .try_decimal_integer()
+            .map(|start| start.unwrap_or(0))
     }
 
     /// Corresponds to the `BYTERANGE-LENGTH` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_byterange_length`] to distinguish an invalid value.
     pub fn byterange_length(&self) -> Option<u64> {
-        match &self.byterange_length {
-            LazyAttribute::UserDefined(d) => Some(*d),
-            LazyAttribute::Unparsed(v) => {
-                v.unquoted().and_then(|v| v.try_as_decimal_integer().ok())
-            }
-            LazyAttribute::None => None,
-        }
+        self.try_byterange_length().ok().flatten()
+    }
+
+    /// Corresponds to the `BYTERANGE-LENGTH` attribute.
+    ///
+    /// Unlike [`Self::byterange_length`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_byterange_length(&self) -> Result<Option<u64>, NumericAttributeError<'_>> {
+        self.byterange_length.try_decimal_integer()
     }
 
     /// Sets the `TYPE` attribute.
@@ -370,22 +404,27 @@ impl<'a> PreloadHint<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> PreloadHintAttributeList<'_> {
         let byterange_start = if self.byterange_start() == 0 {
             None
         } else {
             Some(self.byterange_start())
         };
-        self.output_line = Cow::Owned(calculate_line(&PreloadHintAttributeList {
+        PreloadHintAttributeList {
             hint_type: self.hint_type().into(),
             uri: self.uri().into(),
             byterange_start,
             byterange_length: self.byterange_length(),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(PreloadHint);
+serde_tag!(PreloadHint, PreloadHintAttributeList);
 
 const TYPE: &str = "TYPE";
 const URI: &str = "URI";
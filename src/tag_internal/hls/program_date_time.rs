@@ -1,7 +1,10 @@
 use crate::{
-    date::DateTime,
-    error::{ParseTagValueError, ValidationError},
-    tag::{UnknownTag, hls::into_inner_tag},
+    date::{DateTime, DateTimeParser},
+    error::{DateTimeSyntaxError, GenericSyntaxError, ParseTagValueError, ValidationError},
+    tag::{
+        UnknownTag,
+        hls::{into_inner_tag, serde_tag},
+    },
 };
 use std::borrow::Cow;
 
@@ -11,8 +14,9 @@ use std::borrow::Cow;
 #[derive(Debug, Clone)]
 pub struct ProgramDateTime<'a> {
     program_date_time: DateTime,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for ProgramDateTime<'a> {
@@ -29,11 +33,30 @@ impl<'a> TryFrom<UnknownTag<'a>> for ProgramDateTime<'a> {
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
             .try_as_date_time()?;
-        Ok(Self {
+        Ok(Self::parsed(program_date_time, tag.original_input))
+    }
+}
+
+impl<'a> ProgramDateTime<'a> {
+    // Used by the Reader in place of `try_from` when a `DateTimeParser` is set on the
+    // `ParsingOptions`.
+    pub(crate) fn try_from_with_parser(
+        tag: UnknownTag<'a>,
+        parser: &dyn DateTimeParser,
+    ) -> Result<Self, ValidationError> {
+        let value = tag.value().ok_or(ParseTagValueError::UnexpectedEmpty)?;
+        let value = std::str::from_utf8(value.0)
+            .map_err(|e| DateTimeSyntaxError::Generic(GenericSyntaxError::InvalidUtf8(e)))?;
+        Ok(Self::parsed(parser.parse(value)?, tag.original_input))
+    }
+
+    fn parsed(program_date_time: DateTime, original_input: &'a [u8]) -> Self {
+        Self {
             program_date_time,
-            output_line: Cow::Borrowed(tag.original_input),
+            output_line: Cow::Borrowed(original_input),
             output_line_is_dirty: false,
-        })
+            comment: None,
+        }
     }
 }
 
@@ -71,6 +94,7 @@ impl<'a> ProgramDateTime<'a> {
             program_date_time,
             output_line: Cow::Owned(calculate_line(program_date_time)),
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -96,6 +120,16 @@ impl<'a> ProgramDateTime<'a> {
 }
 
 into_inner_tag!(ProgramDateTime);
+serde_tag!(
+    ProgramDateTime,
+    DateTime,
+    |tag| tag.program_date_time(),
+    ProgramDateTime::new
+);
+
+fn calculate_line(date_time: DateTime) -> Vec<u8> {
+    format!("#EXT-X-PROGRAM-DATE-TIME:{date_time}").into_bytes()
+}
 
 #[cfg(test)]
 mod tests {
@@ -121,7 +155,3 @@ mod tests {
         (program_date_time, DateTime::default(), @Attr=":1970-01-01T00:00:00.000Z")
     );
 }
-
-fn calculate_line(date_time: DateTime) -> Vec<u8> {
-    format!("#EXT-X-PROGRAM-DATE-TIME:{date_time}").into_bytes()
-}
@@ -1,8 +1,8 @@
 use crate::{
-    error::{ParseTagValueError, ValidationError},
+    error::{NumericAttributeError, ParseTagValueError, ValidationError},
     tag::{
         UnknownTag,
-        hls::{LazyAttribute, into_inner_tag},
+        hls::{LazyAttribute, decimal_integer, into_inner_tag, required_numeric, serde_tag},
     },
 };
 use std::{borrow::Cow, marker::PhantomData};
@@ -11,6 +11,7 @@ use std::{borrow::Cow, marker::PhantomData};
 ///
 /// See [`RenditionReport`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct RenditionReportAttributeList<'a> {
     /// Corresponds to the `URI` attribute.
     ///
@@ -104,6 +105,14 @@ impl<'a, UriStatus, LastMsnStatus> RenditionReportBuilder<'a, UriStatus, LastMsn
         self.attribute_list.last_part = Some(last_part);
         self
     }
+    /// Add the provided `last_part` to the attributes built into `RenditionReport`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_last_part_opt(self, last_part: Option<u64>) -> Self {
+        match last_part {
+            Some(last_part) => self.with_last_part(last_part),
+            None => self,
+        }
+    }
 }
 impl<'a> Default
     for RenditionReportBuilder<
@@ -125,8 +134,9 @@ pub struct RenditionReport<'a> {
     uri: Cow<'a, str>,
     last_msn: u64,
     last_part: LazyAttribute<'a, u64>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for RenditionReport<'a> {
@@ -144,18 +154,14 @@ impl<'a> TryFrom<UnknownTag<'a>> for RenditionReport<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut uri = None;
         let mut last_msn = None;
         let mut last_part = LazyAttribute::None;
         for (name, value) in attribute_list {
             match name {
                 URI => uri = value.quoted(),
-                LAST_MSN => {
-                    last_msn = value
-                        .unquoted()
-                        .and_then(|v| v.try_as_decimal_integer().ok())
-                }
+                LAST_MSN => last_msn = required_numeric(decimal_integer(value), LAST_MSN)?,
                 LAST_PART => last_part.found(value),
                 _ => (),
             }
@@ -172,6 +178,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for RenditionReport<'a> {
             last_part,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -191,6 +198,7 @@ impl<'a> RenditionReport<'a> {
             last_part: last_part.map(LazyAttribute::new).unwrap_or_default(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -244,14 +252,19 @@ impl<'a> RenditionReport<'a> {
     /// Corresponds to the `LAST-PART` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_last_part`] to distinguish an invalid value.
     pub fn last_part(&self) -> Option<u64> {
-        match &self.last_part {
-            LazyAttribute::UserDefined(n) => Some(*n),
-            LazyAttribute::Unparsed(v) => {
-                v.unquoted().and_then(|v| v.try_as_decimal_integer().ok())
-            }
-            LazyAttribute::None => None,
-        }
+        self.try_last_part().ok().flatten()
+    }
+
+    /// Corresponds to the `LAST-PART` attribute.
+    ///
+    /// Unlike [`Self::last_part`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_last_part(&self) -> Result<Option<u64>, NumericAttributeError<'_>> {
+        self.last_part.try_decimal_integer()
     }
 
     /// Sets the `URI` attribute.
@@ -287,16 +300,21 @@ impl<'a> RenditionReport<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&RenditionReportAttributeList {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> RenditionReportAttributeList<'_> {
+        RenditionReportAttributeList {
             uri: self.uri().into(),
             last_msn: self.last_msn(),
             last_part: self.last_part(),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(RenditionReport);
+serde_tag!(RenditionReport, RenditionReportAttributeList);
 
 const URI: &str = "URI";
 const LAST_MSN: &str = "LAST-MSN";
@@ -1,8 +1,8 @@
 use crate::{
-    error::{ParseTagValueError, ValidationError},
+    error::{NumericAttributeError, ParseTagValueError, ValidationError},
     tag::{
         AttributeValue, UnknownTag, UnquotedAttributeValue,
-        hls::{LazyAttribute, into_inner_tag},
+        hls::{LazyAttribute, into_inner_tag, serde_tag},
     },
 };
 use std::{borrow::Cow, marker::PhantomData};
@@ -11,6 +11,7 @@ use std::{borrow::Cow, marker::PhantomData};
 ///
 /// See [`ServerControl`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct ServerControlAttributeList {
     /// Corresponds to the `CAN-SKIP-UNTIL` attribute.
     ///
@@ -143,8 +144,9 @@ pub struct ServerControl<'a> {
     hold_back: LazyAttribute<'a, f64>,
     part_hold_back: LazyAttribute<'a, f64>,
     can_block_reload: LazyAttribute<'a, bool>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for ServerControl<'a> {
@@ -164,7 +166,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for ServerControl<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut can_skip_until = LazyAttribute::None;
         let mut can_skip_dateranges = LazyAttribute::None;
         let mut hold_back = LazyAttribute::None;
@@ -188,6 +190,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for ServerControl<'a> {
             can_block_reload,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -211,6 +214,7 @@ impl<'a> ServerControl<'a> {
             can_block_reload: LazyAttribute::new(can_block_reload),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -237,14 +241,19 @@ impl<'a> ServerControl<'a> {
     /// Corresponds to the `CAN-SKIP-UNTIL` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_can_skip_until`] to distinguish an invalid value.
     pub fn can_skip_until(&self) -> Option<f64> {
-        match self.can_skip_until {
-            LazyAttribute::UserDefined(n) => Some(n),
-            LazyAttribute::Unparsed(v) => v
-                .unquoted()
-                .and_then(|v| v.try_as_decimal_floating_point().ok()),
-            LazyAttribute::None => None,
-        }
+        self.try_can_skip_until().ok().flatten()
+    }
+
+    /// Corresponds to the `CAN-SKIP-UNTIL` attribute.
+    ///
+    /// Unlike [`Self::can_skip_until`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_can_skip_until(&self) -> Result<Option<f64>, NumericAttributeError<'_>> {
+        self.can_skip_until.try_decimal_floating_point()
     }
 
     /// Corresponds to the `CAN-SKIP-DATERANGES` attribute.
@@ -263,26 +272,36 @@ impl<'a> ServerControl<'a> {
     /// Corresponds to the `HOLD-BACK` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_hold_back`] to distinguish an invalid value.
     pub fn hold_back(&self) -> Option<f64> {
-        match self.hold_back {
-            LazyAttribute::UserDefined(n) => Some(n),
-            LazyAttribute::Unparsed(v) => v
-                .unquoted()
-                .and_then(|v| v.try_as_decimal_floating_point().ok()),
-            LazyAttribute::None => None,
-        }
+        self.try_hold_back().ok().flatten()
+    }
+
+    /// Corresponds to the `HOLD-BACK` attribute.
+    ///
+    /// Unlike [`Self::hold_back`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_hold_back(&self) -> Result<Option<f64>, NumericAttributeError<'_>> {
+        self.hold_back.try_decimal_floating_point()
     }
     /// Corresponds to the `PART-HOLD-BACK` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_part_hold_back`] to distinguish an invalid value.
     pub fn part_hold_back(&self) -> Option<f64> {
-        match self.part_hold_back {
-            LazyAttribute::UserDefined(n) => Some(n),
-            LazyAttribute::Unparsed(v) => v
-                .unquoted()
-                .and_then(|v| v.try_as_decimal_floating_point().ok()),
-            LazyAttribute::None => None,
-        }
+        self.try_part_hold_back().ok().flatten()
+    }
+
+    /// Corresponds to the `PART-HOLD-BACK` attribute.
+    ///
+    /// Unlike [`Self::part_hold_back`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_part_hold_back(&self) -> Result<Option<f64>, NumericAttributeError<'_>> {
+        self.part_hold_back.try_decimal_floating_point()
     }
 
     /// Corresponds to the `CAN-BLOCK-RELOAD` attribute.
@@ -363,18 +382,23 @@ impl<'a> ServerControl<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&ServerControlAttributeList {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> ServerControlAttributeList {
+        ServerControlAttributeList {
             can_skip_until: self.can_skip_until(),
             can_skip_dateranges: self.can_skip_dateranges(),
             hold_back: self.hold_back(),
             part_hold_back: self.part_hold_back(),
             can_block_reload: self.can_block_reload(),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(ServerControl);
+serde_tag!(ServerControl, ServerControlAttributeList);
 
 const CAN_SKIP_UNTIL: &str = "CAN-SKIP-UNTIL";
 const CAN_SKIP_DATERANGES: &str = "CAN-SKIP-DATERANGES";
@@ -2,7 +2,10 @@ use crate::{
     error::{ParseTagValueError, UnrecognizedEnumerationError, ValidationError},
     tag::{
         UnknownTag,
-        hls::{EnumeratedString, LazyAttribute, into_inner_tag},
+        hls::{
+            EnumeratedString, ExtensionAttributeValue, LazyAttribute, insert_ordered,
+            into_inner_tag, iter_extension_attributes, push_extension_attributes, serde_tag,
+        },
     },
     utils::AsStaticCow,
 };
@@ -58,6 +61,7 @@ const RAW: &str = "RAW";
 ///
 /// See [`SessionData`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct SessionDataAttributeList<'a> {
     /// Corresponds to the `DATA-ID` attribute.
     ///
@@ -79,6 +83,9 @@ struct SessionDataAttributeList<'a> {
     ///
     /// See [`SessionData`] for a link to the HLS documentation for this attribute.
     language: Option<Cow<'a, str>>,
+    /// Corresponds to any attributes that are not defined by the HLS specification for this tag
+    /// (such as vendor specific attributes), in the order that they were added.
+    unknown_attributes: Vec<(Cow<'a, str>, ExtensionAttributeValue<'a>)>,
 }
 
 /// Placeholder struct for [`SessionDataBuilder`] indicating that `data_id` needs to be set.
@@ -129,6 +136,7 @@ impl<'a>
                 uri: Default::default(),
                 format: Default::default(),
                 language: Default::default(),
+                unknown_attributes: Default::default(),
             },
             data_id_status: PhantomData,
             value_status: PhantomData,
@@ -223,11 +231,46 @@ impl<'a, DataIdStatus, ValueStatus, UriStatus>
         self.attribute_list.format = Some(format.into());
         self
     }
+    /// Add the provided `format` to the attributes built into `SessionData`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_format_opt(self, format: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match format {
+            Some(format) => self.with_format(format),
+            None => self,
+        }
+    }
     /// Add the provided `language` to the attributes built into `SessionData`.
     pub fn with_language(mut self, language: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.language = Some(language.into());
         self
     }
+    /// Add the provided `language` to the attributes built into `SessionData`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_language_opt(self, language: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match language {
+            Some(language) => self.with_language(language),
+            None => self,
+        }
+    }
+
+    /// Add the provided attribute, that is not otherwise defined by the HLS specification for
+    /// this tag, to the attributes built into `SessionData`.
+    ///
+    /// Attributes are written in the order that they are added. If an attribute with the same
+    /// name has already been added then its value is replaced, while keeping its original
+    /// position. Names that are defined by the specification for this tag are ignored (the
+    /// dedicated builder methods should be used for those).
+    pub fn with_unknown_attribute(
+        mut self,
+        name: impl Into<Cow<'a, str>>,
+        value: ExtensionAttributeValue<'a>,
+    ) -> Self {
+        let name = name.into();
+        if !is_known_attribute(&name) {
+            insert_ordered(&mut self.attribute_list.unknown_attributes, name, value);
+        }
+        self
+    }
 }
 impl<'a> Default
     for SessionDataBuilder<
@@ -252,8 +295,10 @@ pub struct SessionData<'a> {
     uri: LazyAttribute<'a, Cow<'a, str>>,
     format: LazyAttribute<'a, Cow<'a, str>>,
     language: LazyAttribute<'a, Cow<'a, str>>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    unknown_attributes: Vec<(Cow<'a, str>, LazyAttribute<'a, ExtensionAttributeValue<'a>>)>,
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for SessionData<'a> {
@@ -263,6 +308,7 @@ impl<'a> PartialEq for SessionData<'a> {
             && self.uri() == other.uri()
             && self.format() == other.format()
             && self.language() == other.language()
+            && self.unknown_attributes().eq(other.unknown_attributes())
     }
 }
 
@@ -273,12 +319,13 @@ impl<'a> TryFrom<UnknownTag<'a>> for SessionData<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut data_id = None;
         let mut value = LazyAttribute::None;
         let mut uri = LazyAttribute::None;
         let mut format = LazyAttribute::None;
         let mut language = LazyAttribute::None;
+        let mut unknown_attributes = Vec::new();
         for (name, v) in attribute_list {
             match name {
                 DATA_ID => data_id = v.quoted(),
@@ -286,7 +333,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for SessionData<'a> {
                 URI => uri.found(v),
                 FORMAT => format.found(v),
                 LANGUAGE => language.found(v),
-                _ => (),
+                _ => unknown_attributes.push((Cow::Borrowed(name), LazyAttribute::Unparsed(v))),
             }
         }
         let Some(data_id) = data_id else {
@@ -298,8 +345,10 @@ impl<'a> TryFrom<UnknownTag<'a>> for SessionData<'a> {
             uri,
             format,
             language,
+            unknown_attributes,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -314,6 +363,7 @@ impl<'a> SessionData<'a> {
             uri,
             format,
             language,
+            unknown_attributes,
         } = attribute_list;
         Self {
             data_id,
@@ -321,8 +371,13 @@ impl<'a> SessionData<'a> {
             uri: uri.map(LazyAttribute::new).unwrap_or_default(),
             format: format.map(LazyAttribute::new).unwrap_or_default(),
             language: language.map(LazyAttribute::new).unwrap_or_default(),
+            unknown_attributes: unknown_attributes
+                .into_iter()
+                .map(|(key, value)| (key, LazyAttribute::new(value)))
+                .collect(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -424,6 +479,28 @@ impl<'a> SessionData<'a> {
         }
     }
 
+    /// Indicates whether the `LANGUAGE` matches the language range, using the basic filtering of
+    /// [RFC4647] (i.e. the language is the same as the range, or starts with the range followed by
+    /// `-`, ignoring case). For example, the range `en` matches `en` and `en-GB`, but not `eng`.
+    ///
+    /// The range `*` matches any language. A tag without a `LANGUAGE` does not match any range.
+    ///
+    /// [RFC4647]: https://datatracker.ietf.org/doc/html/rfc4647#section-3.3.1
+    pub fn matches_language(&self, range: &str) -> bool {
+        let Some(language) = self.language() else {
+            return false;
+        };
+        if range == "*" {
+            return true;
+        }
+        match language.get(..range.len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(range) => {
+                matches!(language.as_bytes().get(range.len()), None | Some(b'-'))
+            }
+            _ => false,
+        }
+    }
+
     /// Sets the `DATA-ID` attribute.
     ///
     /// See [`SessionData`] for a link to the HLS documentation for this attribute.
@@ -496,25 +573,104 @@ impl<'a> SessionData<'a> {
         self.output_line_is_dirty = true;
     }
 
+    /// Corresponds to any attributes that are not defined by the HLS specification for this tag
+    /// (such as vendor specific attributes).
+    ///
+    /// The attributes are provided in the order in which they appear (the order in which they were
+    /// parsed, or added), and mutating an existing attribute (via [`Self::set_unknown_attribute`])
+    /// does not change its position. Values that cannot be represented as an
+    /// [`ExtensionAttributeValue`] are skipped (and so are not written if the tag is mutated). For
+    /// example:
+    /// ```
+    /// # use quick_m3u8::{
+    /// # Reader, HlsLine, config::ParsingOptions, tag::KnownTag,
+    /// # tag::hls::{self, ExtensionAttributeValue}
+    /// # };
+    /// let tag = r#"#EXT-X-SESSION-DATA:DATA-ID="a",VALUE="v",X-B=42,X-A="A""#;
+    /// let mut reader = Reader::from_str(tag, ParsingOptions::default());
+    /// match reader.read_line() {
+    ///     Ok(Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::SessionData(mut data))))) => {
+    ///         data.set_unknown_attribute("X-B", ExtensionAttributeValue::QuotedString("B".into()));
+    ///         assert_eq!(
+    ///             vec![
+    ///                 ("X-B", ExtensionAttributeValue::QuotedString("B".into())),
+    ///                 ("X-A", ExtensionAttributeValue::QuotedString("A".into())),
+    ///             ],
+    ///             data.unknown_attributes().collect::<Vec<_>>()
+    ///         );
+    ///     }
+    ///     r => panic!("unexpected result {r:?}"),
+    /// }
+    /// ```
+    pub fn unknown_attributes(&self) -> impl Iterator<Item = (&str, ExtensionAttributeValue<'_>)> {
+        iter_extension_attributes(&self.unknown_attributes)
+    }
+
+    /// Corresponds to one of the attributes that are not defined by the HLS specification for
+    /// this tag (keyed by `name`).
+    pub fn unknown_attribute(&self, name: &str) -> Option<ExtensionAttributeValue<'_>> {
+        self.unknown_attributes()
+            .find_map(|(key, value)| if key == name { Some(value) } else { None })
+    }
+
+    /// Sets an attribute that is not defined by the HLS specification for this tag.
+    ///
+    /// If the attribute already exists then its value is replaced in place (the order of the
+    /// attributes is preserved), otherwise it is added after the existing attributes. Names that
+    /// are defined by the specification for this tag are ignored (the dedicated setters should be
+    /// used for those).
+    pub fn set_unknown_attribute(
+        &mut self,
+        name: impl Into<Cow<'a, str>>,
+        value: ExtensionAttributeValue<'a>,
+    ) {
+        let name = name.into();
+        if is_known_attribute(&name) {
+            return;
+        }
+        insert_ordered(
+            &mut self.unknown_attributes,
+            name,
+            LazyAttribute::new(value),
+        );
+        self.output_line_is_dirty = true;
+    }
+
+    /// Unsets an attribute that is not defined by the HLS specification for this tag (removes it
+    /// from the list).
+    pub fn unset_unknown_attribute(&mut self, name: &str) {
+        self.unknown_attributes.retain(|(key, _)| key != name);
+        self.output_line_is_dirty = true;
+    }
+
     fn recalculate_output_line(&mut self) {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> SessionDataAttributeList<'_> {
         let format = self.format();
         let format = if format == EnumeratedString::Known(Format::Json) {
             None
         } else {
             Some(format)
         };
-        self.output_line = Cow::Owned(calculate_line(&SessionDataAttributeList {
+        SessionDataAttributeList {
             data_id: self.data_id().into(),
             value: self.value().map(|x| x.into()),
             uri: self.uri().map(|x| x.into()),
             format: format.map(|x| x.into()),
             language: self.language().map(|x| x.into()),
-        }));
-        self.output_line_is_dirty = false;
+            unknown_attributes: self
+                .unknown_attributes()
+                .map(|(key, value)| (Cow::Borrowed(key), value))
+                .collect(),
+        }
     }
 }
 
 into_inner_tag!(SessionData);
+serde_tag!(SessionData, SessionDataAttributeList);
 
 const DATA_ID: &str = "DATA-ID";
 const VALUE: &str = "VALUE";
@@ -522,6 +678,10 @@ const URI: &str = "URI";
 const FORMAT: &str = "FORMAT";
 const LANGUAGE: &str = "LANGUAGE";
 
+fn is_known_attribute(name: &str) -> bool {
+    matches!(name, DATA_ID | VALUE | URI | FORMAT | LANGUAGE)
+}
+
 fn calculate_line(attribute_list: &SessionDataAttributeList) -> Vec<u8> {
     let SessionDataAttributeList {
         data_id,
@@ -529,6 +689,7 @@ fn calculate_line(attribute_list: &SessionDataAttributeList) -> Vec<u8> {
         uri,
         format,
         language,
+        unknown_attributes,
     } = attribute_list;
     let mut line = format!("#EXT-X-SESSION-DATA:{DATA_ID}=\"{data_id}\"");
     if let Some(value) = value {
@@ -543,6 +704,7 @@ fn calculate_line(attribute_list: &SessionDataAttributeList) -> Vec<u8> {
     if let Some(language) = language {
         line.push_str(format!(",{LANGUAGE}=\"{language}\"").as_str());
     }
+    push_extension_attributes(&mut line, unknown_attributes);
     line.into_bytes()
 }
 
@@ -639,4 +801,76 @@ mod tests {
                 @Attr="FORMAT=INVALID")
         );
     }
+
+    #[test]
+    fn unknown_attributes_should_be_preserved_in_order_when_mutated() {
+        let mut reader = crate::Reader::from_str(
+            "#EXT-X-SESSION-DATA:DATA-ID=\"a\",VALUE=\"v\",X-C=\"c\",X-A=0x0A,X-B=1",
+            crate::config::ParsingOptions::default(),
+        );
+        let Ok(Some(crate::HlsLine::KnownTag(crate::tag::KnownTag::Hls(
+            crate::tag::hls::Tag::SessionData(mut tag),
+        )))) = reader.read_line()
+        else {
+            panic!("expected SessionData");
+        };
+        assert_eq!(
+            Some(ExtensionAttributeValue::SignedDecimalFloatingPoint(1.0)),
+            tag.unknown_attribute("X-B")
+        );
+        tag.set_data_id("b");
+        tag.set_unknown_attribute("X-A", ExtensionAttributeValue::QuotedString("a".into()));
+        tag.set_unknown_attribute("X-D", ExtensionAttributeValue::QuotedString("d".into()));
+        tag.unset_unknown_attribute("X-B");
+        assert_eq!(
+            "#EXT-X-SESSION-DATA:DATA-ID=\"b\",VALUE=\"v\",X-C=\"c\",X-A=\"a\",X-D=\"d\""
+                .as_bytes(),
+            tag.into_inner().value()
+        );
+    }
+
+    #[test]
+    fn builder_should_ignore_known_attributes_as_unknown_attributes() {
+        let tag = SessionData::builder()
+            .with_data_id("a")
+            .with_value("v")
+            .with_unknown_attribute(
+                "X-B",
+                ExtensionAttributeValue::SignedDecimalFloatingPoint(1.0),
+            )
+            .with_unknown_attribute("URI", ExtensionAttributeValue::QuotedString("u".into()))
+            .with_unknown_attribute("X-A", ExtensionAttributeValue::QuotedString("a".into()))
+            .finish();
+        assert_eq!(
+            vec![
+                (
+                    "X-B",
+                    ExtensionAttributeValue::SignedDecimalFloatingPoint(1.0)
+                ),
+                ("X-A", ExtensionAttributeValue::QuotedString("a".into())),
+            ],
+            tag.unknown_attributes().collect::<Vec<_>>()
+        );
+        assert_eq!(None, tag.uri());
+    }
+
+    #[test]
+    fn matches_language_should_use_basic_filtering() {
+        let tag = SessionData::builder()
+            .with_data_id("a")
+            .with_value("v")
+            .with_language("en-GB")
+            .finish();
+        assert!(tag.matches_language("en"));
+        assert!(tag.matches_language("EN-gb"));
+        assert!(tag.matches_language("*"));
+        assert!(!tag.matches_language("en-US"));
+        assert!(!tag.matches_language("e"));
+        assert!(!tag.matches_language("en-GB-x"));
+        let tag = SessionData::builder()
+            .with_data_id("a")
+            .with_value("v")
+            .finish();
+        assert!(!tag.matches_language("*"));
+    }
 }
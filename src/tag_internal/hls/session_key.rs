@@ -1,16 +1,25 @@
 use crate::{
-    error::{ParseTagValueError, ValidationError},
+    error::{ParseNumberError, ParseTagValueError, ValidationError},
     tag::{
         UnknownTag,
-        hls::{EnumeratedString, LazyAttribute, into_inner_tag, key::Method},
+        hls::{
+            EnumeratedString, KeyformatVersions, LazyAttribute, into_inner_tag,
+            key::{Method, hash_key, iv_eq},
+            serde_tag,
+        },
     },
 };
-use std::{borrow::Cow, marker::PhantomData};
+use std::{
+    borrow::Cow,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
 
 /// The attribute list for the tag (`#EXT-X-SESSION-KEY:<attribute-list>`).
 ///
 /// See [`SessionKey`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct SessionKeyAttributeList<'a> {
     /// Corresponds to the `METHOD` attribute.
     ///
@@ -110,16 +119,43 @@ impl<'a, MethodStatus, UriStatus> SessionKeyBuilder<'a, MethodStatus, UriStatus>
         self.attribute_list.iv = Some(iv.into());
         self
     }
+    /// Add the provided `iv` to the attributes built into `SessionKey`, if it is `Some` (otherwise
+    /// this is a no-op).
+    pub fn with_iv_opt(self, iv: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match iv {
+            Some(iv) => self.with_iv(iv),
+            None => self,
+        }
+    }
     /// Add the provided `keyformat` to the attributes built into `SessionKey`.
     pub fn with_keyformat(mut self, keyformat: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.keyformat = Some(keyformat.into());
         self
     }
+    /// Add the provided `keyformat` to the attributes built into `SessionKey`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_keyformat_opt(self, keyformat: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match keyformat {
+            Some(keyformat) => self.with_keyformat(keyformat),
+            None => self,
+        }
+    }
     /// Add the provided `keyformatversions` to the attributes built into `SessionKey`.
     pub fn with_keyformatversions(mut self, keyformatversions: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.keyformatversions = Some(keyformatversions.into());
         self
     }
+    /// Add the provided `keyformatversions` to the attributes built into `SessionKey`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_keyformatversions_opt(
+        self,
+        keyformatversions: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match keyformatversions {
+            Some(keyformatversions) => self.with_keyformatversions(keyformatversions),
+            None => self,
+        }
+    }
 }
 impl<'a> Default
     for SessionKeyBuilder<'a, SessionKeyMethodNeedsToBeSet, SessionKeyUriNeedsToBeSet>
@@ -131,6 +167,10 @@ impl<'a> Default
 
 /// Corresponds to the `#EXT-X-SESSION-KEY` tag.
 ///
+/// Equality is semantic, meaning that the order of the attributes, the case of the `IV`, and
+/// whether the default `KEYFORMAT` is written explicitly, are all ignored. `Hash` is consistent
+/// with this, and so keys can be held in a `HashSet` (e.g. when planning license prefetches).
+///
 /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.6.5>
 #[derive(Debug, Clone)]
 pub struct SessionKey<'a> {
@@ -139,20 +179,36 @@ pub struct SessionKey<'a> {
     iv: LazyAttribute<'a, Cow<'a, str>>,
     keyformat: LazyAttribute<'a, Cow<'a, str>>,
     keyformatversions: LazyAttribute<'a, Cow<'a, str>>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for SessionKey<'a> {
     fn eq(&self, other: &Self) -> bool {
         self.method() == other.method()
             && self.uri() == other.uri()
-            && self.iv() == other.iv()
+            && iv_eq(self.iv(), other.iv())
             && self.keyformat() == other.keyformat()
             && self.keyformatversions() == other.keyformatversions()
     }
 }
 
+impl<'a> Eq for SessionKey<'a> {}
+
+impl<'a> Hash for SessionKey<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_key(
+            self.method.as_ref(),
+            Some(self.uri()),
+            self.iv(),
+            self.keyformat(),
+            self.keyformatversions(),
+            state,
+        );
+    }
+}
+
 impl<'a> TryFrom<UnknownTag<'a>> for SessionKey<'a> {
     type Error = ValidationError;
 
@@ -160,7 +216,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for SessionKey<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut method = None;
         let mut uri = None;
         let mut iv = LazyAttribute::None;
@@ -190,6 +246,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for SessionKey<'a> {
             keyformatversions,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -215,6 +272,7 @@ impl<'a> SessionKey<'a> {
                 .unwrap_or_default(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -296,6 +354,16 @@ impl<'a> SessionKey<'a> {
         }
     }
 
+    /// Corresponds to the `KEYFORMATVERSIONS` attribute, parsed as [`KeyformatVersions`].
+    ///
+    /// When the attribute is absent this provides the default value of `1` (as defined in the HLS
+    /// specification).
+    pub fn keyformat_versions(&self) -> Result<KeyformatVersions, ParseNumberError> {
+        self.keyformatversions()
+            .map(KeyformatVersions::try_from)
+            .unwrap_or_else(|| Ok(KeyformatVersions::default()))
+    }
+
     /// Sets the `METHOD` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
@@ -361,24 +429,29 @@ impl<'a> SessionKey<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> SessionKeyAttributeList<'_> {
         let keyformat = self.keyformat();
         let keyformat = if keyformat == "identity" {
             None
         } else {
             Some(keyformat)
         };
-        self.output_line = Cow::Owned(calculate_line(&SessionKeyAttributeList {
+        SessionKeyAttributeList {
             method: self.method().into(),
             uri: self.uri().into(),
             iv: self.iv().map(|x| x.into()),
             keyformat: keyformat.map(|x| x.into()),
             keyformatversions: self.keyformatversions().map(|x| x.into()),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(SessionKey);
+serde_tag!(SessionKey, SessionKeyAttributeList);
 
 const METHOD: &str = "METHOD";
 const URI: &str = "URI";
@@ -2,13 +2,14 @@ use crate::{
     error::{ParseTagValueError, ValidationError},
     tag::{
         UnknownTag,
-        hls::{LazyAttribute, into_inner_tag},
+        hls::{LazyAttribute, decimal_integer, into_inner_tag, required_numeric, serde_tag},
     },
 };
 use std::{borrow::Cow, marker::PhantomData};
 
 /// The attribute list for the tag (`#EXT-X-SKIP:<attribute-list>`).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct SkipAttributeList<'a> {
     /// Corresponds to the `SKIPPED-SEGMENTS` attribute.
     ///
@@ -75,6 +76,19 @@ impl<'a, SkippedSegmentsStatus> SkipBuilder<'a, SkippedSegmentsStatus> {
         self.attribute_list.recently_removed_dateranges = Some(recently_removed_dateranges.into());
         self
     }
+    /// Add the provided `recently_removed_dateranges` to the attributes built for `Skip`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_recently_removed_dateranges_opt(
+        self,
+        recently_removed_dateranges: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match recently_removed_dateranges {
+            Some(recently_removed_dateranges) => {
+                self.with_recently_removed_dateranges(recently_removed_dateranges)
+            }
+            None => self,
+        }
+    }
 }
 impl<'a> Default for SkipBuilder<'a, SkipSkippedSegmentsNeedsToBeSet> {
     fn default() -> Self {
@@ -89,8 +103,9 @@ impl<'a> Default for SkipBuilder<'a, SkipSkippedSegmentsNeedsToBeSet> {
 pub struct Skip<'a> {
     skipped_segments: u64,
     recently_removed_dateranges: LazyAttribute<'a, Cow<'a, str>>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Skip<'a> {
@@ -107,15 +122,13 @@ impl<'a> TryFrom<UnknownTag<'a>> for Skip<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut skipped_segments = None;
         let mut recently_removed_dateranges = LazyAttribute::None;
         for (name, value) in attribute_list {
             match name {
                 SKIPPED_SEGMENTS => {
-                    skipped_segments = value
-                        .unquoted()
-                        .and_then(|v| v.try_as_decimal_integer().ok())
+                    skipped_segments = required_numeric(decimal_integer(value), SKIPPED_SEGMENTS)?
                 }
                 RECENTLY_REMOVED_DATERANGES => recently_removed_dateranges.found(value),
                 _ => (),
@@ -131,6 +144,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Skip<'a> {
             recently_removed_dateranges,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -150,6 +164,7 @@ impl<'a> Skip<'a> {
                 .unwrap_or_default(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -220,15 +235,20 @@ impl<'a> Skip<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&SkipAttributeList {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> SkipAttributeList<'_> {
+        SkipAttributeList {
             skipped_segments: self.skipped_segments(),
             recently_removed_dateranges: self.recently_removed_dateranges().map(|x| x.into()),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(Skip);
+serde_tag!(Skip, SkipAttributeList);
 
 const SKIPPED_SEGMENTS: &str = "SKIPPED-SEGMENTS";
 const RECENTLY_REMOVED_DATERANGES: &str = "RECENTLY-REMOVED-DATERANGES";
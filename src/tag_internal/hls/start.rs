@@ -2,7 +2,7 @@ use crate::{
     error::{ParseTagValueError, ValidationError},
     tag::{
         AttributeValue, UnknownTag, UnquotedAttributeValue,
-        hls::{LazyAttribute, into_inner_tag},
+        hls::{LazyAttribute, decimal_floating_point, into_inner_tag, required_numeric, serde_tag},
     },
 };
 use std::{borrow::Cow, marker::PhantomData};
@@ -11,6 +11,7 @@ use std::{borrow::Cow, marker::PhantomData};
 ///
 /// See [`Start`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct StartAttributeList {
     /// Corresponds to the `TIME-OFFSET` attribute.
     ///
@@ -85,8 +86,9 @@ impl Default for StartBuilder<StartTimeOffsetNeedsToBeSet> {
 pub struct Start<'a> {
     time_offset: f64,
     precise: LazyAttribute<'a, bool>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Start<'a> {
@@ -102,15 +104,13 @@ impl<'a> TryFrom<UnknownTag<'a>> for Start<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut time_offset = None;
         let mut precise = LazyAttribute::None;
         for (name, value) in attribute_list {
             match name {
                 TIME_OFFSET => {
-                    time_offset = value
-                        .unquoted()
-                        .and_then(|v| v.try_as_decimal_floating_point().ok())
+                    time_offset = required_numeric(decimal_floating_point(value), TIME_OFFSET)?
                 }
                 PRECISE => precise.found(value),
                 _ => (),
@@ -126,6 +126,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Start<'a> {
             precise,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -143,6 +144,7 @@ impl<'a> Start<'a> {
             precise: LazyAttribute::new(precise),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -203,15 +205,20 @@ impl<'a> Start<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&StartAttributeList {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> StartAttributeList {
+        StartAttributeList {
             time_offset: self.time_offset(),
             precise: self.precise(),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(Start);
+serde_tag!(Start, StartAttributeList);
 
 const TIME_OFFSET: &str = "TIME-OFFSET";
 const PRECISE: &str = "PRECISE";
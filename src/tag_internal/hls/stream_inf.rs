@@ -1,8 +1,13 @@
 use crate::{
-    error::{ParseTagValueError, UnrecognizedEnumerationError, ValidationError},
+    error::{
+        NumericAttributeError, ParseTagValueError, UnrecognizedEnumerationError, ValidationError,
+    },
     tag::{
         DecimalResolution, UnknownTag,
-        hls::{EnumeratedString, EnumeratedStringList, LazyAttribute, into_inner_tag},
+        hls::{
+            EnumeratedString, EnumeratedStringList, LazyAttribute, decimal_integer, into_inner_tag,
+            required_numeric, serde_tag,
+        },
     },
     utils::AsStaticCow,
 };
@@ -392,6 +397,16 @@ impl<'a> From<&'a str> for VideoLayout<'a> {
         }
     }
 }
+/// Parses an owned `VideoLayout` (use [`VideoLayout::from`] to borrow the string instead).
+impl std::str::FromStr for VideoLayout<'static> {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            inner: Cow::Owned(s.to_string()),
+        })
+    }
+}
 impl Display for VideoLayout<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_ref())
@@ -667,11 +682,25 @@ impl<'a> AllowedCpc<'a> {
     ///     "PC does not exist already and so the insert will return true"
     /// );
     /// ```
+    ///
+    /// False is also returned (and the list is left unchanged) when the `KEYFORMAT` or label cannot
+    /// be represented in the list; that is, when either is empty, the `KEYFORMAT` contains `,`, the
+    /// label contains `/` or `,`, or either contains `"` or a line break.
+    /// ```
+    /// # use quick_m3u8::tag::hls::AllowedCpc;
+    /// let mut allowed_cpc = AllowedCpc::from("com.example.drm1:SMART-TV");
+    /// assert_eq!(false, allowed_cpc.insert_cpc_for_keyformat("com.example.drm1", "PC/HW"));
+    /// assert_eq!(false, allowed_cpc.insert_cpc_for_keyformat("", "PC"));
+    /// assert_eq!("com.example.drm1:SMART-TV", allowed_cpc.as_ref());
+    /// ```
     pub fn insert_cpc_for_keyformat(
         &mut self,
         keyformat: impl AsRef<str>,
         cpc_label: impl AsRef<str>,
     ) -> bool {
+        if !is_valid_keyformat(keyformat.as_ref()) || !is_valid_cpc_label(cpc_label.as_ref()) {
+            return false;
+        }
         if let Some((start, end)) = self.keyformat_value_start_and_end_indices(&keyformat) {
             let value = self.inner_value(start, end);
             if value.split_terminator('/').any(|c| c == cpc_label.as_ref()) {
@@ -747,6 +776,9 @@ impl<'a> AllowedCpc<'a> {
         keyformat: impl AsRef<str>,
         cpc_label: impl AsRef<str>,
     ) -> bool {
+        if !is_valid_cpc_label(cpc_label.as_ref()) {
+            return false;
+        }
         let keyformat_length = keyformat.as_ref().len();
         if let Some((start, end)) = self.keyformat_value_start_and_end_indices(keyformat) {
             let value = self.inner_value(start, end);
@@ -803,13 +835,18 @@ impl<'a> AllowedCpc<'a> {
         keyformat: impl AsRef<str>,
     ) -> Option<(usize, Option<usize>)> {
         let keyformat_bytes = keyformat.as_ref().as_bytes();
+        if keyformat_bytes.is_empty() {
+            return None;
+        }
         let inner_bytes = self.inner.as_bytes();
         let bytes_count = keyformat_bytes.len();
         let finder = memmem::find_iter(inner_bytes, keyformat_bytes);
         for i in finder {
-            // if the match does not end with b':' then it is not a real match and we should
+            // if the match is not the whole key of an entry (i.e. it does not start the list or
+            // follow b',', or it does not end with b':') then it is not a real match and we should
             // continue searching
-            if inner_bytes[i + bytes_count] == b':' {
+            let starts_entry = i == 0 || inner_bytes.get(i - 1) == Some(&b',');
+            if starts_entry && inner_bytes.get(i + bytes_count) == Some(&b':') {
                 // we have a real match so there is at least something (though maybe just b',')
                 let start = i + bytes_count + 1;
                 if let Some(n) = memchr(b',', &inner_bytes[start..]) {
@@ -831,6 +868,12 @@ impl<'a> AllowedCpc<'a> {
         }
     }
 }
+fn is_valid_keyformat(keyformat: &str) -> bool {
+    !keyformat.is_empty() && !keyformat.contains([',', '"', '\r', '\n'])
+}
+fn is_valid_cpc_label(cpc_label: &str) -> bool {
+    !cpc_label.is_empty() && !cpc_label.contains(['/', ',', '"', '\r', '\n'])
+}
 impl<'a> AsRef<str> for AllowedCpc<'a> {
     fn as_ref(&self) -> &str {
         &self.inner
@@ -855,6 +898,16 @@ impl<'a> From<&'a str> for AllowedCpc<'a> {
         }
     }
 }
+/// Parses an owned `AllowedCpc` (use [`AllowedCpc::from`] to borrow the string instead).
+impl std::str::FromStr for AllowedCpc<'static> {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            inner: Cow::Owned(s.to_string()),
+        })
+    }
+}
 impl<'a> From<AllowedCpc<'a>> for Cow<'a, str> {
     fn from(value: AllowedCpc<'a>) -> Self {
         value.inner
@@ -879,10 +932,272 @@ impl From<Vec<FairPlayCpcLabel>> for AllowedCpc<'_> {
     }
 }
 
+/// Corresponds to the `#EXT-X-STREAM-INF:CODECS` attribute (also used by `EXT-X-I-FRAME-STREAM-INF`).
+///
+/// See [`StreamInf`] for a link to the HLS documentation for this attribute.
+///
+/// The format described in HLS is a comma-separated list of formats, where each format specifies a
+/// media sample type as defined by [RFC6381] (e.g. `avc1.640028` or `mp4a.40.2`). This abstraction
+/// provides iteration over the individual formats, along with insert, remove, and contains
+/// operations, and some light classification of the formats (see [`CodecKind`]).
+///
+/// It is always possible to return back to working with strings directly by using the
+/// [`Self::as_ref`] method.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::tag::hls::{CodecKind, Codecs};
+/// let mut codecs = Codecs::from("avc1.640028, mp4a.40.2");
+/// assert_eq!(vec!["avc1.640028", "mp4a.40.2"], codecs.iter().collect::<Vec<_>>());
+/// assert!(codecs.has_video());
+/// assert!(codecs.remove("avc1.640028"));
+/// assert!(codecs.insert("ec-3"));
+/// assert_eq!("mp4a.40.2,ec-3", codecs.as_ref());
+/// assert!(!codecs.has_video());
+/// assert_eq!(vec![CodecKind::Audio, CodecKind::Audio], codecs.kinds().collect::<Vec<_>>());
+/// ```
+///
+/// [RFC6381]: https://datatracker.ietf.org/doc/html/rfc6381
+#[derive(Debug, PartialEq, Clone)]
+pub struct Codecs<'a> {
+    inner: Cow<'a, str>,
+}
+impl<'a> Codecs<'a> {
+    /// Provides an iterator over the formats in the list (with surrounding whitespace trimmed).
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.inner
+            .split(',')
+            .map(str::trim)
+            .filter(|codec| !codec.is_empty())
+    }
+
+    /// Provides an iterator over the kind of each format in the list (see [`CodecKind::of`]).
+    pub fn kinds(&self) -> impl Iterator<Item = CodecKind> {
+        self.iter().map(CodecKind::of)
+    }
+
+    /// The number of formats in the list.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Indicates whether the list has no formats.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Indicates whether the list contains the format (compared exactly, as the formats are case
+    /// sensitive).
+    pub fn contains(&self, codec: impl AsRef<str>) -> bool {
+        self.iter().any(|c| c == codec.as_ref())
+    }
+
+    /// Indicates whether the list contains a format that is known to be video.
+    pub fn has_video(&self) -> bool {
+        self.kinds().any(|kind| kind == CodecKind::Video)
+    }
+
+    /// Indicates whether the list contains a format that is known to be audio.
+    pub fn has_audio(&self) -> bool {
+        self.kinds().any(|kind| kind == CodecKind::Audio)
+    }
+
+    /// Provides an iterator over the formats of the given kind.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::tag::hls::{CodecKind, Codecs};
+    /// let codecs = Codecs::from("hvc1.2.4.L123.B0,ec-3,mp4a.40.2,wvtt");
+    /// assert_eq!(
+    ///     vec!["ec-3", "mp4a.40.2"],
+    ///     codecs.of_kind(CodecKind::Audio).collect::<Vec<_>>()
+    /// );
+    /// ```
+    pub fn of_kind(&self, kind: CodecKind) -> impl Iterator<Item = &str> {
+        self.iter()
+            .filter(move |codec| CodecKind::of(codec) == kind)
+    }
+
+    /// Inserts the format at the end of the list.
+    ///
+    /// The value returns true if the insert was successful, and false otherwise. False is returned
+    /// (and the list is left unchanged) when the format is already in the list, or when it cannot be
+    /// represented in the list; that is, when it is empty, or contains `,`, `"`, whitespace, or a
+    /// line break.
+    /// ```
+    /// # use quick_m3u8::tag::hls::Codecs;
+    /// let mut codecs = Codecs::from("avc1.640028");
+    /// assert_eq!(false, codecs.insert("avc1.640028"));
+    /// assert_eq!(false, codecs.insert("mp4a.40.2,ec-3"));
+    /// assert_eq!(true, codecs.insert("mp4a.40.2"));
+    /// assert_eq!("avc1.640028,mp4a.40.2", codecs.as_ref());
+    /// ```
+    pub fn insert(&mut self, codec: impl AsRef<str>) -> bool {
+        let codec = codec.as_ref();
+        if !is_valid_codec(codec) || self.contains(codec) {
+            return false;
+        }
+        self.inner = if self.is_empty() {
+            Cow::Owned(codec.to_string())
+        } else {
+            let mut new_string = std::mem::take(&mut self.inner).into_owned();
+            new_string.push(',');
+            new_string.push_str(codec);
+            Cow::Owned(new_string)
+        };
+        true
+    }
+
+    /// Removes the format from the list.
+    ///
+    /// The value returns true if the remove was successful, and false otherwise.
+    /// ```
+    /// # use quick_m3u8::tag::hls::Codecs;
+    /// let mut codecs = Codecs::from("avc1.640028,mp4a.40.2");
+    /// assert_eq!(false, codecs.remove("ec-3"));
+    /// assert_eq!(true, codecs.remove("avc1.640028"));
+    /// assert_eq!("mp4a.40.2", codecs.as_ref());
+    /// ```
+    pub fn remove(&mut self, codec: impl AsRef<str>) -> bool {
+        let codec = codec.as_ref();
+        if !self.contains(codec) {
+            return false;
+        }
+        let new_string = self
+            .iter()
+            .filter(|c| *c != codec)
+            .collect::<Vec<_>>()
+            .join(",");
+        self.inner = Cow::Owned(new_string);
+        true
+    }
+
+    /// Retains only the formats for which the predicate returns true.
+    ///
+    /// For example, to remove all but the AAC audio formats:
+    /// ```
+    /// # use quick_m3u8::tag::hls::{CodecKind, Codecs};
+    /// let mut codecs = Codecs::from("avc1.640028,ec-3,mp4a.40.2");
+    /// codecs.retain(|codec| CodecKind::of(codec) != CodecKind::Audio || codec.starts_with("mp4a"));
+    /// assert_eq!("avc1.640028,mp4a.40.2", codecs.as_ref());
+    /// ```
+    pub fn retain(&mut self, mut predicate: impl FnMut(&str) -> bool) {
+        if self.iter().all(&mut predicate) {
+            return;
+        }
+        let new_string = self
+            .iter()
+            .filter(|codec| predicate(codec))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.inner = Cow::Owned(new_string);
+    }
+
+    /// This overrides the default `to_owned` provided as part of `#[derive(Clone)]`.
+    ///
+    /// The reason this exists is to provide better lifetime semantics by completely breaking ties
+    /// to the reference data. This is done by converting the inner into an owned String.
+    pub fn to_owned<'b>(&self) -> Codecs<'b> {
+        Codecs::from(self.to_string())
+    }
+}
+fn is_valid_codec(codec: &str) -> bool {
+    !codec.is_empty() && !codec.contains([',', '"']) && !codec.contains(char::is_whitespace)
+}
+impl<'a> AsRef<str> for Codecs<'a> {
+    fn as_ref(&self) -> &str {
+        &self.inner
+    }
+}
+impl Display for Codecs<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.inner)
+    }
+}
+impl From<String> for Codecs<'_> {
+    fn from(value: String) -> Self {
+        Self {
+            inner: Cow::Owned(value),
+        }
+    }
+}
+impl<'a> From<&'a str> for Codecs<'a> {
+    fn from(value: &'a str) -> Self {
+        Self {
+            inner: Cow::Borrowed(value),
+        }
+    }
+}
+/// Parses an owned `Codecs` (use [`Codecs::from`] to borrow the string instead).
+impl std::str::FromStr for Codecs<'static> {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            inner: Cow::Owned(s.to_string()),
+        })
+    }
+}
+impl<'a> From<Codecs<'a>> for Cow<'a, str> {
+    fn from(value: Codecs<'a>) -> Self {
+        value.inner
+    }
+}
+
+/// The kind of media described by a format within the `CODECS` attribute (see [`Codecs`]).
+///
+/// The classification is based on the sample entry (the part of the format before the first `.`)
+/// of well-known formats, and so formats that are not known are [`CodecKind::Unknown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CodecKind {
+    /// A video format (e.g. `avc1`, `hvc1`, `dvh1`, `av01`, `vp09`).
+    Video,
+    /// An audio format (e.g. `mp4a`, `ac-3`, `ec-3`, `ac-4`, `fLaC`, `Opus`).
+    Audio,
+    /// A subtitle format (e.g. `wvtt`, `stpp`).
+    Subtitles,
+    /// A format that is not known.
+    Unknown,
+}
+impl CodecKind {
+    /// Classifies the format.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::tag::hls::CodecKind;
+    /// assert_eq!(CodecKind::Video, CodecKind::of("avc1.640028"));
+    /// assert_eq!(CodecKind::Audio, CodecKind::of("ec-3"));
+    /// assert_eq!(CodecKind::Subtitles, CodecKind::of("stpp.ttml.im1t"));
+    /// assert_eq!(CodecKind::Unknown, CodecKind::of("xyz1"));
+    /// ```
+    pub fn of(codec: &str) -> Self {
+        let sample_entry = codec.trim().split('.').next().unwrap_or_default();
+        match sample_entry {
+            "avc1" | "avc3" | "hvc1" | "hev1" | "dvh1" | "dvhe" | "dva1" | "dvav" | "dav1"
+            | "av01" | "vp08" | "vp09" | "mp4v" => Self::Video,
+            "mp4a" | "ac-3" | "ec-3" | "ac-4" | "mhm1" | "mhm2" | "mha1" | "mha2" | "alac"
+            | "fLaC" | "Opus" | "dtsc" | "dtse" | "dtsh" | "dtsl" | "dtsx" => Self::Audio,
+            "wvtt" | "stpp" | "tx3g" | "c608" => Self::Subtitles,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Indicates whether the format is known to be video.
+    pub fn is_video(&self) -> bool {
+        *self == Self::Video
+    }
+
+    /// Indicates whether the format is known to be audio.
+    pub fn is_audio(&self) -> bool {
+        *self == Self::Audio
+    }
+}
+
 /// The attribute list for the tag (`#EXT-X-STREAM-INF:<attribute-list>`).
 ///
 /// See [`StreamInf`] for a link to the HLS documentation for this attribute.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct StreamInfAttributeList<'a> {
     /// Corresponds to the `BANDWIDTH` attribute.
     ///
@@ -1021,16 +1336,40 @@ impl<'a, BandwidthStatus> StreamInfBuilder<'a, BandwidthStatus> {
         self.attribute_list.average_bandwidth = Some(average_bandwidth);
         self
     }
+    /// Add the provided `average_bandwidth` to the attributes built into `StreamInf`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_average_bandwidth_opt(self, average_bandwidth: Option<u64>) -> Self {
+        match average_bandwidth {
+            Some(average_bandwidth) => self.with_average_bandwidth(average_bandwidth),
+            None => self,
+        }
+    }
     /// Add the provided `score` to the attributes built into `StreamInf`.
     pub fn with_score(mut self, score: f64) -> Self {
         self.attribute_list.score = Some(score);
         self
     }
+    /// Add the provided `score` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_score_opt(self, score: Option<f64>) -> Self {
+        match score {
+            Some(score) => self.with_score(score),
+            None => self,
+        }
+    }
     /// Add the provided `codecs` to the attributes built into `StreamInf`.
     pub fn with_codecs(mut self, codecs: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.codecs = Some(codecs.into());
         self
     }
+    /// Add the provided `codecs` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_codecs_opt(self, codecs: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match codecs {
+            Some(codecs) => self.with_codecs(codecs),
+            None => self,
+        }
+    }
     /// Add the provided `supplemental_codecs` to the attributes built into `StreamInf`.
     pub fn with_supplemental_codecs(
         mut self,
@@ -1039,16 +1378,43 @@ impl<'a, BandwidthStatus> StreamInfBuilder<'a, BandwidthStatus> {
         self.attribute_list.supplemental_codecs = Some(supplemental_codecs.into());
         self
     }
+    /// Add the provided `supplemental_codecs` to the attributes built into `StreamInf`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_supplemental_codecs_opt(
+        self,
+        supplemental_codecs: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match supplemental_codecs {
+            Some(supplemental_codecs) => self.with_supplemental_codecs(supplemental_codecs),
+            None => self,
+        }
+    }
     /// Add the provided `resolution` to the attributes built into `StreamInf`.
     pub fn with_resolution(mut self, resolution: DecimalResolution) -> Self {
         self.attribute_list.resolution = Some(resolution);
         self
     }
+    /// Add the provided `resolution` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_resolution_opt(self, resolution: Option<DecimalResolution>) -> Self {
+        match resolution {
+            Some(resolution) => self.with_resolution(resolution),
+            None => self,
+        }
+    }
     /// Add the provided `frame_rate` to the attributes built into `StreamInf`.
     pub fn with_frame_rate(mut self, frame_rate: f64) -> Self {
         self.attribute_list.frame_rate = Some(frame_rate);
         self
     }
+    /// Add the provided `frame_rate` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_frame_rate_opt(self, frame_rate: Option<f64>) -> Self {
+        match frame_rate {
+            Some(frame_rate) => self.with_frame_rate(frame_rate),
+            None => self,
+        }
+    }
     /// Add the provided `hdcp_level` to the attributes built into `StreamInf`.
     ///
     /// Note that [`HdcpLevel`] implements `Into<Cow<str>>` and therefore can be used directly here.
@@ -1068,6 +1434,14 @@ impl<'a, BandwidthStatus> StreamInfBuilder<'a, BandwidthStatus> {
         self.attribute_list.hdcp_level = Some(hdcp_level.into());
         self
     }
+    /// Add the provided `hdcp_level` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_hdcp_level_opt(self, hdcp_level: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match hdcp_level {
+            Some(hdcp_level) => self.with_hdcp_level(hdcp_level),
+            None => self,
+        }
+    }
     /// Add the provided `allowed_cpc` to the attributes built into `StreamInf`.
     ///
     /// Note that [`AllowedCpc`] implements `Into<Cow<str>>` and therefore can be used directly
@@ -1091,6 +1465,14 @@ impl<'a, BandwidthStatus> StreamInfBuilder<'a, BandwidthStatus> {
         self.attribute_list.allowed_cpc = Some(allowed_cpc.into());
         self
     }
+    /// Add the provided `allowed_cpc` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_allowed_cpc_opt(self, allowed_cpc: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match allowed_cpc {
+            Some(allowed_cpc) => self.with_allowed_cpc(allowed_cpc),
+            None => self,
+        }
+    }
     /// Add the provided `video_range` to the attributes built into `StreamInf`.
     ///
     /// Note that [`VideoRange`] implements `Into<Cow<str>>` and therefore can be used directly
@@ -1110,6 +1492,14 @@ impl<'a, BandwidthStatus> StreamInfBuilder<'a, BandwidthStatus> {
         self.attribute_list.video_range = Some(video_range.into());
         self
     }
+    /// Add the provided `video_range` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_video_range_opt(self, video_range: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match video_range {
+            Some(video_range) => self.with_video_range(video_range),
+            None => self,
+        }
+    }
     /// Add the provided `req_video_layout` to the attributes built into `StreamInf`.
     ///
     /// Note that [`VideoLayout`] implements `Into<Cow<str>>` and therefore can be used directly
@@ -1139,36 +1529,101 @@ impl<'a, BandwidthStatus> StreamInfBuilder<'a, BandwidthStatus> {
         self.attribute_list.req_video_layout = Some(req_video_layout.into());
         self
     }
+    /// Add the provided `req_video_layout` to the attributes built into `StreamInf`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_req_video_layout_opt(
+        self,
+        req_video_layout: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match req_video_layout {
+            Some(req_video_layout) => self.with_req_video_layout(req_video_layout),
+            None => self,
+        }
+    }
     /// Add the provided `stable_variant_id` to the attributes built into `StreamInf`.
     pub fn with_stable_variant_id(mut self, stable_variant_id: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.stable_variant_id = Some(stable_variant_id.into());
         self
     }
+    /// Add the provided `stable_variant_id` to the attributes built into `StreamInf`, if it is
+    /// `Some` (otherwise this is a no-op).
+    pub fn with_stable_variant_id_opt(
+        self,
+        stable_variant_id: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match stable_variant_id {
+            Some(stable_variant_id) => self.with_stable_variant_id(stable_variant_id),
+            None => self,
+        }
+    }
     /// Add the provided `audio` to the attributes built into `StreamInf`.
     pub fn with_audio(mut self, audio: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.audio = Some(audio.into());
         self
     }
+    /// Add the provided `audio` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_audio_opt(self, audio: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match audio {
+            Some(audio) => self.with_audio(audio),
+            None => self,
+        }
+    }
     /// Add the provided `video` to the attributes built into `StreamInf`.
     pub fn with_video(mut self, video: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.video = Some(video.into());
         self
     }
+    /// Add the provided `video` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_video_opt(self, video: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match video {
+            Some(video) => self.with_video(video),
+            None => self,
+        }
+    }
     /// Add the provided `subtitles` to the attributes built into `StreamInf`.
     pub fn with_subtitles(mut self, subtitles: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.subtitles = Some(subtitles.into());
         self
     }
+    /// Add the provided `subtitles` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_subtitles_opt(self, subtitles: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match subtitles {
+            Some(subtitles) => self.with_subtitles(subtitles),
+            None => self,
+        }
+    }
     /// Add the provided `closed_captions` to the attributes built into `StreamInf`.
     pub fn with_closed_captions(mut self, closed_captions: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.closed_captions = Some(closed_captions.into());
         self
     }
+    /// Add the provided `closed_captions` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_closed_captions_opt(
+        self,
+        closed_captions: Option<impl Into<Cow<'a, str>>>,
+    ) -> Self {
+        match closed_captions {
+            Some(closed_captions) => self.with_closed_captions(closed_captions),
+            None => self,
+        }
+    }
     /// Add the provided `pathway_id` to the attributes built into `StreamInf`.
     pub fn with_pathway_id(mut self, pathway_id: impl Into<Cow<'a, str>>) -> Self {
         self.attribute_list.pathway_id = Some(pathway_id.into());
         self
     }
+    /// Add the provided `pathway_id` to the attributes built into `StreamInf`, if it is `Some`
+    /// (otherwise this is a no-op).
+    pub fn with_pathway_id_opt(self, pathway_id: Option<impl Into<Cow<'a, str>>>) -> Self {
+        match pathway_id {
+            Some(pathway_id) => self.with_pathway_id(pathway_id),
+            None => self,
+        }
+    }
 }
 impl<'a> Default for StreamInfBuilder<'a, StreamInfBandwidthNeedsToBeSet> {
     fn default() -> Self {
@@ -1198,8 +1653,9 @@ pub struct StreamInf<'a> {
     subtitles: LazyAttribute<'a, Cow<'a, str>>,
     closed_captions: LazyAttribute<'a, Cow<'a, str>>,
     pathway_id: LazyAttribute<'a, Cow<'a, str>>,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for StreamInf<'a> {
@@ -1231,7 +1687,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for StreamInf<'a> {
         let attribute_list = tag
             .value()
             .ok_or(ParseTagValueError::UnexpectedEmpty)?
-            .try_as_ordered_attribute_list()?;
+            .try_as_attribute_map()?;
         let mut bandwidth = None;
         let mut average_bandwidth = LazyAttribute::None;
         let mut score = LazyAttribute::None;
@@ -1251,11 +1707,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for StreamInf<'a> {
         let mut pathway_id = LazyAttribute::None;
         for (name, value) in attribute_list {
             match name {
-                BANDWIDTH => {
-                    bandwidth = value
-                        .unquoted()
-                        .and_then(|v| v.try_as_decimal_integer().ok())
-                }
+                BANDWIDTH => bandwidth = required_numeric(decimal_integer(value), BANDWIDTH)?,
                 AVERAGE_BANDWIDTH => average_bandwidth.found(value),
                 SCORE => score.found(value),
                 CODECS => codecs.found(value),
@@ -1298,6 +1750,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for StreamInf<'a> {
             pathway_id,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -1351,6 +1804,7 @@ impl<'a> StreamInf<'a> {
             pathway_id: pathway_id.map(LazyAttribute::new).unwrap_or_default(),
             output_line,
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -1388,27 +1842,37 @@ impl<'a> StreamInf<'a> {
     /// Corresponds to the `AVERAGE-BANDWIDTH` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_average_bandwidth`] to distinguish an invalid value.
     pub fn average_bandwidth(&self) -> Option<u64> {
-        match self.average_bandwidth {
-            LazyAttribute::UserDefined(n) => Some(n),
-            LazyAttribute::Unparsed(v) => {
-                v.unquoted().and_then(|v| v.try_as_decimal_integer().ok())
-            }
-            LazyAttribute::None => None,
-        }
+        self.try_average_bandwidth().ok().flatten()
+    }
+
+    /// Corresponds to the `AVERAGE-BANDWIDTH` attribute.
+    ///
+    /// Unlike [`Self::average_bandwidth`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_average_bandwidth(&self) -> Result<Option<u64>, NumericAttributeError<'_>> {
+        self.average_bandwidth.try_decimal_integer()
     }
 
     /// Corresponds to the `SCORE` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_score`] to distinguish an invalid value.
     pub fn score(&self) -> Option<f64> {
-        match self.score {
-            LazyAttribute::UserDefined(n) => Some(n),
-            LazyAttribute::Unparsed(v) => v
-                .unquoted()
-                .and_then(|v| v.try_as_decimal_floating_point().ok()),
-            LazyAttribute::None => None,
-        }
+        self.try_score().ok().flatten()
+    }
+
+    /// Corresponds to the `SCORE` attribute.
+    ///
+    /// Unlike [`Self::score`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_score(&self) -> Result<Option<f64>, NumericAttributeError<'_>> {
+        self.score.try_decimal_floating_point()
     }
 
     /// Corresponds to the `CODECS` attribute.
@@ -1422,6 +1886,26 @@ impl<'a> StreamInf<'a> {
         }
     }
 
+    /// Corresponds to the `CODECS` attribute, as a list of formats (see [`Codecs`]).
+    ///
+    /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::tag::hls::StreamInf;
+    /// let mut tag = StreamInf::builder()
+    ///     .with_bandwidth(1280000)
+    ///     .with_codecs("avc1.640028,mp4a.40.2,ec-3")
+    ///     .finish();
+    /// let mut codecs = tag.codec_list().expect("should have codecs").to_owned();
+    /// codecs.remove("ec-3");
+    /// tag.set_codecs(codecs);
+    /// assert_eq!(Some("avc1.640028,mp4a.40.2"), tag.codecs());
+    /// ```
+    pub fn codec_list(&self) -> Option<Codecs<'_>> {
+        self.codecs().map(Codecs::from)
+    }
+
     /// Corresponds to the `SUPPLEMENTAL-CODECS` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
@@ -1449,14 +1933,19 @@ impl<'a> StreamInf<'a> {
     /// Corresponds to the `FRAME-RATE` attribute.
     ///
     /// See [`Self`] for a link to the HLS documentation for this attribute.
+    ///
+    /// This returns `None` if the attribute is not present or the value is not valid. See
+    /// [`Self::try_frame_rate`] to distinguish an invalid value.
     pub fn frame_rate(&self) -> Option<f64> {
-        match self.frame_rate {
-            LazyAttribute::UserDefined(n) => Some(n),
-            LazyAttribute::Unparsed(v) => v
-                .unquoted()
-                .and_then(|v| v.try_as_decimal_floating_point().ok()),
-            LazyAttribute::None => None,
-        }
+        self.try_frame_rate().ok().flatten()
+    }
+
+    /// Corresponds to the `FRAME-RATE` attribute.
+    ///
+    /// Unlike [`Self::frame_rate`], this provides the error (which includes the raw text of the
+    /// value) when the value is present but is not a number, or is a number that is out of range.
+    pub fn try_frame_rate(&self) -> Result<Option<f64>, NumericAttributeError<'_>> {
+        self.frame_rate.try_decimal_floating_point()
     }
 
     /// Corresponds to the `HDCP-LEVEL` attribute.
@@ -2016,7 +2505,12 @@ impl<'a> StreamInf<'a> {
     }
 
     fn recalculate_output_line(&mut self) {
-        self.output_line = Cow::Owned(calculate_line(&StreamInfAttributeList {
+        self.output_line = Cow::Owned(calculate_line(&self.attribute_list()));
+        self.output_line_is_dirty = false;
+    }
+
+    fn attribute_list(&self) -> StreamInfAttributeList<'_> {
+        StreamInfAttributeList {
             bandwidth: self.bandwidth(),
             average_bandwidth: self.average_bandwidth(),
             score: self.score(),
@@ -2034,12 +2528,12 @@ impl<'a> StreamInf<'a> {
             subtitles: self.subtitles().map(|x| x.into()),
             closed_captions: self.closed_captions().map(|x| x.into()),
             pathway_id: self.pathway_id().map(|x| x.into()),
-        }));
-        self.output_line_is_dirty = false;
+        }
     }
 }
 
 into_inner_tag!(StreamInf);
+serde_tag!(StreamInf, StreamInfAttributeList);
 
 const BANDWIDTH: &str = "BANDWIDTH";
 const AVERAGE_BANDWIDTH: &str = "AVERAGE-BANDWIDTH";
@@ -2200,6 +2694,24 @@ mod tests {
         )
     }
 
+    #[test]
+    fn opt_builder_methods_should_only_set_some_values() {
+        let codecs = Some(String::from("avc1.64002a"));
+        let audio: Option<&str> = None;
+        assert_eq!(
+            StreamInf::builder()
+                .with_bandwidth(10000000)
+                .with_codecs("avc1.64002a")
+                .finish(),
+            StreamInf::builder()
+                .with_bandwidth(10000000)
+                .with_average_bandwidth_opt(None)
+                .with_codecs_opt(codecs)
+                .with_audio_opt(audio)
+                .finish()
+        );
+    }
+
     #[test]
     fn new_view_presentation_entries_displays_as_expected() {
         assert_eq!(
@@ -2483,6 +2995,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn allowed_cpc_should_only_match_whole_keyformat_keys() {
+        let mut allowed_cpc = AllowedCpc::from("com.example.drm1:PC");
+        assert_eq!(None, allowed_cpc.allowed_cpc_for_keyformat("drm1").next());
+        assert_eq!(false, allowed_cpc.remove_cpc_for_keyformat("drm1", "PC"));
+        assert_eq!("com.example.drm1:PC", allowed_cpc.as_ref());
+
+        // A keyformat at the very end of the list (with no `:`) used to index out of bounds.
+        let mut allowed_cpc = AllowedCpc::from("com.example.drm1");
+        assert_eq!(
+            None,
+            allowed_cpc
+                .allowed_cpc_for_keyformat("com.example.drm1")
+                .next()
+        );
+        assert!(allowed_cpc.insert_cpc_for_keyformat("com.example.drm1", "PC"));
+        assert_eq!("com.example.drm1,com.example.drm1:PC", allowed_cpc.as_ref());
+    }
+
+    #[test]
+    fn codecs_should_tolerate_whitespace_and_empty_entries() {
+        let mut codecs = Codecs::from(" avc1.640028 ,, mp4a.40.2 ");
+        assert_eq!(2, codecs.len());
+        assert!(codecs.contains("mp4a.40.2"));
+        assert!(codecs.has_audio());
+        assert!(codecs.remove("mp4a.40.2"));
+        assert_eq!("avc1.640028", codecs.as_ref());
+
+        let mut codecs = Codecs::from(" , ");
+        assert!(codecs.is_empty());
+        assert!(codecs.insert("ec-3"));
+        assert_eq!("ec-3", codecs.as_ref());
+        assert!(!codecs.insert("mp4a.40.2 "));
+        assert!(!codecs.insert(""));
+        assert_eq!("ec-3", codecs.as_ref());
+
+        let mut codecs = Codecs::from("avc1.640028,mp4a.40.2");
+        codecs.retain(|_| true);
+        assert!(matches!(codecs.inner, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn allowed_cpc_mutation_should_not_panic_on_adversarial_input() {
+        const LISTS: [&str; 14] = [
+            "", ",", ":", "a", "a:", ":a", "a:b", "a:b/c", "a:,", ",a:b", "a:b,a:c", "xa:b",
+            "é:ü/é", "a:b//c,",
+        ];
+        const KEYFORMATS: [&str; 8] = ["", "a", ":", ",", "é", "xa", "a:b", "a:"];
+        const LABELS: [&str; 8] = ["", "b", "c", "/", ",", "b/c", "ü", "\""];
+        for list in LISTS {
+            for keyformat in KEYFORMATS {
+                for label in LABELS {
+                    let mut allowed_cpc = AllowedCpc::from(list);
+                    let _ = allowed_cpc.allowed_cpc_for_keyformat(keyformat).count();
+                    if allowed_cpc.insert_cpc_for_keyformat(keyformat, label) {
+                        assert!(
+                            allowed_cpc
+                                .allowed_cpc_for_keyformat(keyformat)
+                                .any(|l| l == label),
+                            "{list:?} insert {keyformat:?} {label:?}"
+                        );
+                    }
+                    let mut allowed_cpc = AllowedCpc::from(list);
+                    if allowed_cpc.remove_cpc_for_keyformat(keyformat, label) {
+                        assert!(
+                            !allowed_cpc
+                                .allowed_cpc_for_keyformat(keyformat)
+                                .any(|l| l == label),
+                            "{list:?} remove {keyformat:?} {label:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
     mutation_tests!(
         StreamInf::builder()
             .with_bandwidth(10000000)
@@ -2532,4 +3119,68 @@ mod tests {
         (closed_captions, @Option "example", @Attr="CLOSED-CAPTIONS=\"example\""),
         (pathway_id, @Option "abcd", @Attr="PATHWAY-ID=\"abcd\"")
     );
+
+    #[test]
+    fn out_of_range_optional_numeric_attributes_should_be_distinguished_from_invalid() {
+        let tag = crate::custom_parsing::tag::parse(concat!(
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000,AVERAGE-BANDWIDTH=-1,",
+            "SCORE=1e999,FRAME-RATE=fast"
+        ))
+        .expect("parsing should succeed")
+        .parsed;
+        let stream_inf = StreamInf::try_from(tag).expect("tag should be valid stream inf");
+        assert_eq!(None, stream_inf.average_bandwidth());
+        let error = stream_inf
+            .try_average_bandwidth()
+            .expect_err("AVERAGE-BANDWIDTH should be out of range");
+        assert_eq!(NumericAttributeError::NumericOutOfRange("-1"), error);
+        assert_eq!("-1", error.raw());
+        assert_eq!(None, stream_inf.score());
+        assert_eq!(
+            Err(NumericAttributeError::NumericOutOfRange("1e999")),
+            stream_inf.try_score()
+        );
+        assert_eq!(None, stream_inf.frame_rate());
+        assert_eq!(
+            Err(NumericAttributeError::NotNumeric("fast")),
+            stream_inf.try_frame_rate()
+        );
+    }
+
+    #[test]
+    fn overflowing_optional_integer_attribute_should_be_out_of_range() {
+        let tag = crate::custom_parsing::tag::parse(
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000,AVERAGE-BANDWIDTH=99999999999999999999999",
+        )
+        .expect("parsing should succeed")
+        .parsed;
+        let stream_inf = StreamInf::try_from(tag).expect("tag should be valid stream inf");
+        assert_eq!(
+            Err(NumericAttributeError::NumericOutOfRange(
+                "99999999999999999999999"
+            )),
+            stream_inf.try_average_bandwidth()
+        );
+    }
+
+    #[test]
+    fn out_of_range_required_numeric_attribute_should_fail_validation() {
+        let tag = crate::custom_parsing::tag::parse("#EXT-X-STREAM-INF:BANDWIDTH=-1")
+            .expect("parsing should succeed")
+            .parsed;
+        assert_eq!(
+            Err(ValidationError::NumericOutOfRange(BANDWIDTH)),
+            StreamInf::try_from(tag)
+        );
+    }
+
+    #[test]
+    fn valid_optional_numeric_attributes_should_be_ok() {
+        let stream_inf = StreamInf::builder()
+            .with_bandwidth(1000)
+            .with_average_bandwidth(900)
+            .finish();
+        assert_eq!(Ok(Some(900)), stream_inf.try_average_bandwidth());
+        assert_eq!(Ok(None), stream_inf.try_score());
+    }
 }
@@ -1,6 +1,9 @@
 use crate::{
     error::{ParseTagValueError, ValidationError},
-    tag::{UnknownTag, hls::into_inner_tag},
+    tag::{
+        UnknownTag,
+        hls::{into_inner_tag, serde_tag},
+    },
 };
 use std::borrow::Cow;
 
@@ -10,8 +13,9 @@ use std::borrow::Cow;
 #[derive(Debug, Clone)]
 pub struct Targetduration<'a> {
     target_duration: u64,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Targetduration<'a> {
@@ -32,6 +36,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Targetduration<'a> {
             target_duration,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -43,6 +48,7 @@ impl<'a> Targetduration<'a> {
             target_duration,
             output_line: Cow::Owned(calculate_line(target_duration)),
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -68,6 +74,12 @@ impl<'a> Targetduration<'a> {
 }
 
 into_inner_tag!(Targetduration);
+serde_tag!(
+    Targetduration,
+    u64,
+    |tag| tag.target_duration(),
+    Targetduration::new
+);
 
 fn calculate_line(target_duration: u64) -> Vec<u8> {
     format!("#EXT-X-TARGETDURATION:{target_duration}").into_bytes()
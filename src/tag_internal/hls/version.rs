@@ -1,6 +1,9 @@
 use crate::{
     error::{ParseTagValueError, ValidationError},
-    tag::{UnknownTag, hls::into_inner_tag},
+    tag::{
+        UnknownTag,
+        hls::{into_inner_tag, serde_tag},
+    },
 };
 use std::borrow::Cow;
 
@@ -10,8 +13,9 @@ use std::borrow::Cow;
 #[derive(Debug, Clone)]
 pub struct Version<'a> {
     version: u64,
-    output_line: Cow<'a, [u8]>, // Used with Writer
-    output_line_is_dirty: bool, // If should recalculate output_line
+    output_line: Cow<'a, [u8]>,    // Used with Writer
+    output_line_is_dirty: bool,    // If should recalculate output_line
+    comment: Option<Cow<'a, str>>, // Written by Writer on the line before the tag
 }
 
 impl<'a> PartialEq for Version<'a> {
@@ -32,6 +36,7 @@ impl<'a> TryFrom<UnknownTag<'a>> for Version<'a> {
             version,
             output_line: Cow::Borrowed(tag.original_input),
             output_line_is_dirty: false,
+            comment: None,
         })
     }
 }
@@ -43,6 +48,7 @@ impl<'a> Version<'a> {
             version,
             output_line: Cow::Owned(calculate_line(version)),
             output_line_is_dirty: false,
+            comment: None,
         }
     }
 
@@ -68,6 +74,7 @@ impl<'a> Version<'a> {
 }
 
 into_inner_tag!(Version);
+serde_tag!(Version, u64, |tag| tag.version(), Version::new);
 
 fn calculate_line(version: u64) -> Vec<u8> {
     format!("#EXT-X-VERSION:{version}").into_bytes()
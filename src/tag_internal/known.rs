@@ -3,12 +3,18 @@
 //! The definitions in this module provide the constructs necessary for both parsing to strongly
 //! typed tags as well as writing when using [`crate::Writer`].
 
+#[cfg(feature = "hls-tags")]
+use crate::tag::hls;
 use crate::{
     error::ValidationError,
-    tag::{UnknownTag, WritableAttributeValue, WritableTagValue, hls},
-    utils::split_on_new_line,
+    tag::{UnknownTag, WritableAttributeValue, WritableTagValue},
+    utils::{fmt_line, split_on_new_line},
+};
+use std::{
+    borrow::Cow,
+    cmp::PartialEq,
+    fmt::{Debug, Display, Formatter},
 };
-use std::{borrow::Cow, cmp::PartialEq, fmt::Debug};
 
 /// Represents a HLS tag that is known to the library.
 ///
@@ -28,6 +34,7 @@ use std::{borrow::Cow, cmp::PartialEq, fmt::Debug};
 /// [Roku Developers]: https://developer.roku.com/docs/developer-program/media-playback/trick-mode/hls-and-dash.md#image-media-playlists-for-hls
 /// [LHLS]: https://video-dev.github.io/hlsjs-rfcs/docs/0001-lhls
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::large_enum_variant)]
 pub enum KnownTag<'a, Custom = NoCustomTag>
 where
@@ -61,6 +68,9 @@ where
     /// that are supported here in this library _(and that were not ignored by
     /// [`crate::config::ParsingOptions`])_. See [`hls::Tag`] for a more complete documentation of
     /// all of the known tag types.
+    ///
+    /// This variant requires the `hls-tags` feature.
+    #[cfg(feature = "hls-tags")]
     Hls(hls::Tag<'a>),
     /// Indicates that the tag found was one matching the [`CustomTag`] definition that the user of
     /// the library has defined. The tag is wrapped in a [`CustomTagAccess`] struct (see that struct
@@ -82,8 +92,18 @@ where
 #[derive(Debug)]
 pub struct TagInner<'a> {
     pub(crate) output_line: Cow<'a, [u8]>,
+    pub(crate) comment: Option<Cow<'a, str>>,
+    // The line as originally parsed, when the output line has been recalculated due to mutation
+    // (used by the Writer to preserve the original formatting).
+    pub(crate) original_line: Option<&'a [u8]>,
 }
 impl<'a> TagInner<'a> {
+    /// Provides the comment attached to the tag (if any), which is written on the line before the
+    /// tag.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
     /// Provides the value of the inner data.
     ///
     /// The method ensures that only data from this line is provided as the value (even if the slice
@@ -223,6 +243,10 @@ pub trait IntoInnerTag<'a> {
 ///     }
 /// }
 /// ```
+/// Here the attribute list is collected so that attributes can be looked up by name. When a single
+/// pass through the attributes is enough, [`crate::tag::TagValue::attribute_list_iter`] avoids
+/// collecting the attributes at all, and is the recommended route for tags with many attributes.
+///
 /// Now we can simply implement the `CustomTag` requirement via the `is_known_name` method. Note
 /// that the tag name is everything after `#EXT` (and before `:`), implying that the `-X-` is
 /// included in the name:
@@ -906,6 +930,7 @@ where
     pub(crate) custom_tag: Custom,
     pub(crate) is_dirty: bool,
     pub(crate) original_input: &'a [u8],
+    pub(crate) replacement: Option<Cow<'a, [u8]>>, // Set via HlsLine::replace_raw
 }
 
 impl<'a, Custom> TryFrom<UnknownTag<'a>> for CustomTagAccess<'a, Custom>
@@ -921,9 +946,55 @@ where
             custom_tag,
             is_dirty: false,
             original_input,
+            replacement: None,
+        })
+    }
+}
+// Only the custom tag is serialized, and so a deserialized custom tag is always written via
+// `WritableCustomTag::into_writable_tag`.
+#[cfg(feature = "serde")]
+impl<'a, Custom> serde::Serialize for CustomTagAccess<'a, Custom>
+where
+    Custom: CustomTag<'a> + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.custom_tag.serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de, 'a, Custom> serde::Deserialize<'de> for CustomTagAccess<'a, Custom>
+where
+    Custom: CustomTag<'a> + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Self {
+            custom_tag: Custom::deserialize(deserializer)?,
+            is_dirty: true,
+            original_input: b"",
+            replacement: None,
         })
     }
 }
+
+impl<'a, Custom> CustomTagAccess<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    /// Indicates whether the custom tag has been mutably borrowed ([`Self::as_mut`]), meaning that
+    /// it is written via [`WritableCustomTag::into_writable_tag`] rather than as the line that it
+    /// was parsed from. A line set via [`crate::HlsLine::replace_raw`] is written as provided, and
+    /// so does not count as a modification.
+    pub fn is_dirty(&self) -> bool {
+        self.is_dirty && self.replacement.is_none()
+    }
+}
+
 impl<'a, Custom> AsRef<Custom> for CustomTagAccess<'a, Custom>
 where
     Custom: CustomTag<'a>,
@@ -938,6 +1009,7 @@ where
 {
     fn as_mut(&mut self) -> &mut Custom {
         self.is_dirty = true;
+        self.replacement = None;
         &mut self.custom_tag
     }
 }
@@ -947,16 +1019,43 @@ where
     Custom: WritableCustomTag<'a>,
 {
     fn into_inner(self) -> TagInner<'a> {
-        if self.is_dirty {
-            self.custom_tag.into_inner()
+        if let Some(replacement) = self.replacement {
+            TagInner {
+                output_line: replacement,
+                comment: None,
+                original_line: None,
+            }
+        } else if self.is_dirty {
+            let mut inner = self.custom_tag.into_inner();
+            if !self.original_input.is_empty() {
+                inner.original_line = Some(self.original_input);
+            }
+            inner
         } else {
             TagInner {
                 output_line: Cow::Borrowed(self.original_input),
+                comment: None,
+                original_line: None,
             }
         }
     }
 }
 
+impl<'a, Custom> Display for CustomTagAccess<'a, Custom>
+where
+    Custom: WritableCustomTag<'a> + Clone,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(replacement) = &self.replacement {
+            fmt_line(replacement, f)
+        } else if self.is_dirty {
+            fmt_line(self.custom_tag.clone().into_inner().value(), f)
+        } else {
+            fmt_line(self.original_input, f)
+        }
+    }
+}
+
 impl<'a, Custom> IntoInnerTag<'a> for Custom
 where
     Custom: WritableCustomTag<'a>,
@@ -965,6 +1064,8 @@ where
         let output = calculate_output(self);
         TagInner {
             output_line: Cow::Owned(output.into_bytes()),
+            comment: None,
+            original_line: None,
         }
     }
 }
@@ -1133,6 +1234,7 @@ impl<'a> WritableTag<'a> {
 /// implementation of `CustomTag`. This implementation ensures that it is never parsed from source
 /// data, because [`Self::is_known_name`] always returns false.
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NoCustomTag;
 impl TryFrom<UnknownTag<'_>> for NoCustomTag {
     type Error = ValidationError;
@@ -1166,9 +1268,28 @@ where
                 custom_tag,
                 is_dirty: false,
                 original_input,
+                replacement: None,
             }))
         } else {
-            Ok(Self::Hls(hls::Tag::try_from(tag)?))
+            #[cfg(feature = "hls-tags")]
+            return Ok(Self::Hls(hls::Tag::try_from(tag)?));
+            #[cfg(not(feature = "hls-tags"))]
+            return Err(ValidationError::UnexpectedTagName);
+        }
+    }
+}
+
+impl<'a, Custom> KnownTag<'a, Custom>
+where
+    Custom: CustomTag<'a>,
+{
+    /// Indicates whether the tag has been modified since it was parsed or constructed (see
+    /// [`hls::Tag::is_dirty`] and [`CustomTagAccess::is_dirty`]).
+    pub fn is_dirty(&self) -> bool {
+        match self {
+            #[cfg(feature = "hls-tags")]
+            KnownTag::Hls(tag) => tag.is_dirty(),
+            KnownTag::Custom(tag) => tag.is_dirty(),
         }
     }
 }
@@ -1179,12 +1300,26 @@ where
 {
     fn into_inner(self) -> TagInner<'a> {
         match self {
+            #[cfg(feature = "hls-tags")]
             KnownTag::Hls(tag) => tag.into_inner(),
             KnownTag::Custom(tag) => tag.into_inner(),
         }
     }
 }
 
+impl<'a, Custom> Display for KnownTag<'a, Custom>
+where
+    Custom: WritableCustomTag<'a> + Clone,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "hls-tags")]
+            KnownTag::Hls(tag) => Display::fmt(tag, f),
+            KnownTag::Custom(tag) => Display::fmt(tag, f),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,7 +4,18 @@
 // and even the whole hls module is public. The "internal" naming is based on the fact that the
 // module is not directly exposed as public, and furthermore, there *is* a public module that we
 // call "tag", and so we needed some sort of disambiguation.
+mod custom_tag;
+#[cfg(feature = "hls-tags")]
 pub mod hls;
+#[cfg(not(feature = "hls-tags"))]
+pub mod hls {
+    //! The names of all tags defined in the HLS specification.
+    //!
+    //! The definitions and parsing of the tags themselves require the `hls-tags` feature.
+    pub use crate::tag_internal::tag_name::{TagName, TagType};
+}
 pub mod known;
+pub(crate) mod splice;
+mod tag_name;
 pub mod unknown;
 pub mod value;
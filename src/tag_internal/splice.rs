@@ -0,0 +1,292 @@
+// Splicing of the recalculated output line of a mutated tag into the line that was originally
+// parsed, so that the line written differs from the original only where the tag was changed (see
+// `crate::WriterBuilder::with_preserved_formatting`).
+
+use crate::{
+    date,
+    tag::{AttributeValue, TagValue, hls::TagName},
+    utils::split_on_new_line,
+};
+
+// Provides the original line with the changes found in the recalculated line spliced in, or `None`
+// if the lines cannot be spliced (in which case the recalculated line should be written as is).
+//
+// For attribute lists, the attributes of the original line are kept in their original order and
+// with their original formatting, unless the value was changed, in which case the recalculated
+// value is written in its place. Attributes that the tag implementation does not retain when
+// parsing are kept as they were, attributes that were removed are dropped, and attributes that
+// were added are appended to the end of the list.
+pub(crate) fn splice_line(original: &[u8], recalculated: &[u8]) -> Option<Vec<u8>> {
+    let (name, original_value) = split_tag(split_on_new_line(original).parsed)?;
+    let (recalculated_name, recalculated_value) = split_tag(recalculated)?;
+    if !name.eq_ignore_ascii_case(recalculated_name) {
+        return None;
+    }
+    let tag_name = std::str::from_utf8(recalculated_name)
+        .ok()
+        .and_then(|name| TagName::try_from(name).ok());
+    let value = match (original_value, recalculated_value) {
+        (Some(original), Some(recalculated)) => splice_value(tag_name, original, recalculated)?,
+        (None, None) => Vec::new(),
+        _ => return None,
+    };
+    let mut line = Vec::with_capacity(5 + name.len() + value.len());
+    line.extend_from_slice(b"#EXT");
+    line.extend_from_slice(name);
+    if original_value.is_some() {
+        line.push(b':');
+        line.extend_from_slice(&value);
+    }
+    Some(line)
+}
+
+fn split_tag(line: &[u8]) -> Option<(&[u8], Option<&[u8]>)> {
+    let line = line.strip_prefix(b"#EXT")?;
+    match line.iter().position(|b| *b == b':') {
+        Some(i) => Some((&line[..i], Some(&line[(i + 1)..]))),
+        None => Some((line, None)),
+    }
+}
+
+fn splice_value(
+    tag_name: Option<TagName>,
+    original: &[u8],
+    recalculated: &[u8],
+) -> Option<Vec<u8>> {
+    if original == recalculated {
+        return Some(original.to_vec());
+    }
+    match tag_name {
+        Some(TagName::Inf) => Some(splice_inf(original, recalculated)),
+        Some(tag_name) if !tag_name.has_attribute_list() => {
+            values_eq(original, recalculated).then(|| original.to_vec())
+        }
+        _ => splice_attribute_list(tag_name, original, recalculated),
+    }
+}
+
+// The duration is kept as it was formatted, unless it was changed.
+fn splice_inf(original: &[u8], recalculated: &[u8]) -> Vec<u8> {
+    let (original_duration, original_title) = split_inf(original);
+    let (recalculated_duration, recalculated_title) = split_inf(recalculated);
+    let mut value = if values_eq(original_duration, recalculated_duration) {
+        original_duration.to_vec()
+    } else {
+        recalculated_duration.to_vec()
+    };
+    // An empty title is written without the `,` separator, so the separator is kept if it was
+    // there originally.
+    if let Some(title) = recalculated_title.or(original_title.filter(|t| t.is_empty())) {
+        value.push(b',');
+        value.extend_from_slice(title);
+    }
+    value
+}
+
+fn split_inf(value: &[u8]) -> (&[u8], Option<&[u8]>) {
+    match value.iter().position(|b| *b == b',') {
+        Some(i) => (&value[..i], Some(&value[(i + 1)..])),
+        None => (value, None),
+    }
+}
+
+fn splice_attribute_list(
+    tag_name: Option<TagName>,
+    original: &[u8],
+    recalculated: &[u8],
+) -> Option<Vec<u8>> {
+    let original = TagValue(original).try_as_ordered_attribute_list().ok()?;
+    let mut recalculated = TagValue(recalculated)
+        .try_as_ordered_attribute_list()
+        .ok()?;
+    let mut value = Vec::new();
+    for (name, original_value) in original {
+        match recalculated.iter().position(|(n, _)| *n == name) {
+            Some(i) => {
+                let (_, recalculated_value) = recalculated.remove(i);
+                if attribute_values_eq(tag_name, name, original_value, recalculated_value) {
+                    push_attribute(&mut value, name, original_value);
+                } else {
+                    push_attribute(&mut value, name, recalculated_value);
+                }
+            }
+            None if tag_name.is_some_and(|tag_name| !tag_name.retains_attribute(name)) => {
+                push_attribute(&mut value, name, original_value);
+            }
+            None => (),
+        }
+    }
+    for (name, recalculated_value) in recalculated {
+        push_attribute(&mut value, name, recalculated_value);
+    }
+    Some(value)
+}
+
+fn push_attribute(value: &mut Vec<u8>, name: &str, attribute_value: AttributeValue) {
+    if !value.is_empty() {
+        value.push(b',');
+    }
+    value.extend_from_slice(name.as_bytes());
+    value.push(b'=');
+    match attribute_value {
+        AttributeValue::Quoted(s) => {
+            value.push(b'"');
+            value.extend_from_slice(s.as_bytes());
+            value.push(b'"');
+        }
+        AttributeValue::Unquoted(v) => value.extend_from_slice(v.0),
+    }
+}
+
+// Quoted values are compared byte for byte (e.g. `VALUE="1.0"` and `VALUE="1"` are different
+// strings), and only the unquoted values of numeric attributes can differ due to formatting.
+fn attribute_values_eq(
+    tag_name: Option<TagName>,
+    name: &str,
+    original: AttributeValue,
+    recalculated: AttributeValue,
+) -> bool {
+    match (original, recalculated) {
+        (AttributeValue::Quoted(o), AttributeValue::Quoted(r)) => o == r,
+        (AttributeValue::Unquoted(o), AttributeValue::Unquoted(r))
+            if tag_name.is_some_and(|tag_name| tag_name.has_numeric_attribute(name)) =>
+        {
+            values_eq(o.0, r.0)
+        }
+        (AttributeValue::Unquoted(o), AttributeValue::Unquoted(r)) => o.0 == r.0,
+        _ => false,
+    }
+}
+
+// Values are considered equal when they are the same, or when they represent the same number or
+// date (e.g. `4.000` and `4`, which differ only due to formatting). This is only used for values
+// known to be numbers or dates.
+fn values_eq(original: &[u8], recalculated: &[u8]) -> bool {
+    if original == recalculated {
+        return true;
+    }
+    let number = |value| {
+        std::str::from_utf8(value)
+            .ok()
+            .and_then(|value| value.parse::<f64>().ok())
+    };
+    if let (Some(o), Some(r)) = (number(original), number(recalculated)) {
+        return o == r;
+    }
+    matches!(
+        (date::parse_bytes(original), date::parse_bytes(recalculated)),
+        (Ok(o), Ok(r)) if o == r
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn splice(original: &str, recalculated: &str) -> Option<String> {
+        splice_line(original.as_bytes(), recalculated.as_bytes())
+            .map(|line| String::from_utf8(line).unwrap())
+    }
+
+    #[test]
+    fn changed_attribute_should_be_spliced_in_original_position() {
+        assert_eq!(
+            Some("#EXT-X-STREAM-INF:CODECS=\"avc1.64001f\",BANDWIDTH=2000000,FRAME-RATE=30.000"),
+            splice(
+                "#EXT-X-STREAM-INF:CODECS=\"avc1.64001f\",BANDWIDTH=1000000,FRAME-RATE=30.000\n",
+                "#EXT-X-STREAM-INF:BANDWIDTH=2000000,CODECS=\"avc1.64001f\",FRAME-RATE=30"
+            )
+            .as_deref()
+        );
+    }
+
+    #[test]
+    fn unretained_attributes_should_be_kept_and_removed_attributes_dropped() {
+        assert_eq!(
+            Some("#EXT-X-KEY:METHOD=AES-128,X-VENDOR=1,URI=\"new.bin\",KEYFORMAT=\"identity\""),
+            splice(
+                "#EXT-X-KEY:METHOD=AES-128,X-VENDOR=1,URI=\"key.bin\",IV=0x1234",
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"new.bin\",KEYFORMAT=\"identity\""
+            )
+            .as_deref()
+        );
+        // Custom tags are assumed to retain all attributes.
+        assert_eq!(
+            Some("#EXT-X-CUSTOM:B=2"),
+            splice("#EXT-X-CUSTOM:A=1,B=\"x\"", "#EXT-X-CUSTOM:B=2").as_deref()
+        );
+    }
+
+    #[test]
+    fn inf_duration_formatting_should_be_kept_when_unchanged() {
+        assert_eq!(
+            Some("#EXTINF:4.000,New title"),
+            splice("#EXTINF:4.000,Old title", "#EXTINF:4,New title").as_deref()
+        );
+        assert_eq!(
+            Some("#EXTINF:4.000"),
+            splice("#EXTINF:4.000,Old title", "#EXTINF:4").as_deref()
+        );
+        assert_eq!(
+            Some("#EXTINF:6,"),
+            splice("#EXTINF:4.000,", "#EXTINF:6").as_deref()
+        );
+    }
+
+    #[test]
+    fn original_spelling_of_tag_name_should_be_kept() {
+        assert_eq!(
+            Some("#EXT-x-targetduration:10.0"),
+            splice("#EXT-x-targetduration:10.0", "#EXT-X-TARGETDURATION:10").as_deref()
+        );
+    }
+
+    #[test]
+    fn quoted_values_should_be_compared_as_written() {
+        assert_eq!(
+            Some("#EXT-X-DEFINE:NAME=\"v\",VALUE=\"1\""),
+            splice(
+                "#EXT-X-DEFINE:NAME=\"v\",VALUE=\"1.0\"",
+                "#EXT-X-DEFINE:NAME=\"v\",VALUE=\"1\""
+            )
+            .as_deref()
+        );
+        assert_eq!(
+            Some("#EXT-X-DEFINE:NAME=\"1\",VALUE=\"1000\""),
+            splice(
+                "#EXT-X-DEFINE:NAME=\"01\",VALUE=\"1e3\"",
+                "#EXT-X-DEFINE:NAME=\"1\",VALUE=\"1000\""
+            )
+            .as_deref()
+        );
+        assert_eq!(
+            Some("#EXT-X-MAP:URI=\"1.0\""),
+            splice("#EXT-X-MAP:URI=\"1\"", "#EXT-X-MAP:URI=\"1.0\"").as_deref()
+        );
+    }
+
+    #[test]
+    fn only_numeric_attributes_should_be_compared_as_numbers() {
+        assert_eq!(
+            Some("#EXT-X-PART:DURATION=2.000,URI=\"1.0\""),
+            splice(
+                "#EXT-X-PART:DURATION=2.000,URI=\"1\"",
+                "#EXT-X-PART:DURATION=2,URI=\"1.0\""
+            )
+            .as_deref()
+        );
+        // Custom tags are not known to have numeric attributes.
+        assert_eq!(
+            Some("#EXT-X-CUSTOM:A=1,B=2"),
+            splice("#EXT-X-CUSTOM:A=1.0,B=1", "#EXT-X-CUSTOM:A=1,B=2").as_deref()
+        );
+    }
+
+    #[test]
+    fn lines_that_cannot_be_spliced_should_be_none() {
+        assert_eq!(None, splice("#EXT-X-VERSION:3", "#EXT-X-VERSION:4"));
+        assert_eq!(None, splice("#EXT-X-VERSION:3", "#EXT-X-BITRATE:3"));
+        assert_eq!(None, splice("#EXT-X-CUSTOM:1,2", "#EXT-X-CUSTOM:A=1"));
+    }
+}
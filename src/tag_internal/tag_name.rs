@@ -0,0 +1,482 @@
+use crate::error::ValidationError;
+use std::fmt::{Display, Formatter};
+
+/// An enumeration of all the tag names defined in the HLS specification.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum TagName {
+    /// Corresponds to `#EXTM3U`.
+    M3u,
+    /// Corresponds to `#EXT-X-VERSION`.
+    Version,
+    /// Corresponds to `#EXT-X-INDEPENDENT-SEGMENTS`.
+    IndependentSegments,
+    /// Corresponds to `#EXT-X-START`.
+    Start,
+    /// Corresponds to `#EXT-X-DEFINE`.
+    Define,
+    /// Corresponds to `#EXT-X-TARGETDURATION`.
+    Targetduration,
+    /// Corresponds to `#EXT-X-MEDIA-SEQUENCE`.
+    MediaSequence,
+    /// Corresponds to `#EXT-X-DISCONTINUITY-SEQUENCE`.
+    DiscontinuitySequence,
+    /// Corresponds to `#EXT-X-ENDLIST`.
+    Endlist,
+    /// Corresponds to `#EXT-X-PLAYLIST-TYPE`.
+    PlaylistType,
+    /// Corresponds to `#EXT-X-I-FRAMES-ONLY`.
+    IFramesOnly,
+    /// Corresponds to `#EXT-X-PART-INF`.
+    PartInf,
+    /// Corresponds to `#EXT-X-SERVER-CONTROL`.
+    ServerControl,
+    /// Corresponds to `#EXTINF`.
+    Inf,
+    /// Corresponds to `#EXT-X-BYTERANGE`.
+    Byterange,
+    /// Corresponds to `#EXT-X-DISCONTINUITY`.
+    Discontinuity,
+    /// Corresponds to `#EXT-X-KEY`.
+    Key,
+    /// Corresponds to `#EXT-X-MAP`.
+    Map,
+    /// Corresponds to `#EXT-X-PROGRAM-DATE-TIME`.
+    ProgramDateTime,
+    /// Corresponds to `#EXT-X-GAP`.
+    Gap,
+    /// Corresponds to `#EXT-X-BITRATE`.
+    Bitrate,
+    /// Corresponds to `#EXT-X-PART`.
+    Part,
+    /// Corresponds to `#EXT-X-DATERANGE`.
+    Daterange,
+    /// Corresponds to `#EXT-X-SKIP`.
+    Skip,
+    /// Corresponds to `#EXT-X-PRELOAD-HINT`.
+    PreloadHint,
+    /// Corresponds to `#EXT-X-RENDITION-REPORT`.
+    RenditionReport,
+    /// Corresponds to `#EXT-X-MEDIA`.
+    Media,
+    /// Corresponds to `#EXT-X-STREAM-INF`.
+    StreamInf,
+    /// Corresponds to `#EXT-X-I-FRAME-STREAM-INF`.
+    IFrameStreamInf,
+    /// Corresponds to `#EXT-X-SESSION-DATA`.
+    SessionData,
+    /// Corresponds to `#EXT-X-SESSION-KEY`.
+    SessionKey,
+    /// Corresponds to `#EXT-X-CONTENT-STEERING`.
+    ContentSteering,
+}
+
+impl TryFrom<&'_ str> for TagName {
+    type Error = ValidationError;
+
+    fn try_from(value: &'_ str) -> Result<Self, Self::Error> {
+        match value {
+            "M3U" => Ok(Self::M3u),
+            "-X-VERSION" => Ok(Self::Version),
+            "-X-INDEPENDENT-SEGMENTS" => Ok(Self::IndependentSegments),
+            "-X-START" => Ok(Self::Start),
+            "-X-DEFINE" => Ok(Self::Define),
+            "-X-TARGETDURATION" => Ok(Self::Targetduration),
+            "-X-MEDIA-SEQUENCE" => Ok(Self::MediaSequence),
+            "-X-DISCONTINUITY-SEQUENCE" => Ok(Self::DiscontinuitySequence),
+            "-X-ENDLIST" => Ok(Self::Endlist),
+            "-X-PLAYLIST-TYPE" => Ok(Self::PlaylistType),
+            "-X-I-FRAMES-ONLY" => Ok(Self::IFramesOnly),
+            "-X-PART-INF" => Ok(Self::PartInf),
+            "-X-SERVER-CONTROL" => Ok(Self::ServerControl),
+            "INF" => Ok(Self::Inf),
+            "-X-BYTERANGE" => Ok(Self::Byterange),
+            "-X-DISCONTINUITY" => Ok(Self::Discontinuity),
+            "-X-KEY" => Ok(Self::Key),
+            "-X-MAP" => Ok(Self::Map),
+            "-X-PROGRAM-DATE-TIME" => Ok(Self::ProgramDateTime),
+            "-X-GAP" => Ok(Self::Gap),
+            "-X-BITRATE" => Ok(Self::Bitrate),
+            "-X-PART" => Ok(Self::Part),
+            "-X-DATERANGE" => Ok(Self::Daterange),
+            "-X-SKIP" => Ok(Self::Skip),
+            "-X-PRELOAD-HINT" => Ok(Self::PreloadHint),
+            "-X-RENDITION-REPORT" => Ok(Self::RenditionReport),
+            "-X-MEDIA" => Ok(Self::Media),
+            "-X-STREAM-INF" => Ok(Self::StreamInf),
+            "-X-I-FRAME-STREAM-INF" => Ok(Self::IFrameStreamInf),
+            "-X-SESSION-DATA" => Ok(Self::SessionData),
+            "-X-SESSION-KEY" => Ok(Self::SessionKey),
+            "-X-CONTENT-STEERING" => Ok(Self::ContentSteering),
+            _ => Err(ValidationError::UnexpectedTagName),
+        }
+    }
+}
+
+impl std::str::FromStr for TagName {
+    type Err = ValidationError;
+
+    /// Parses the name of the tag, either as provided by [`TagName::as_str`] (e.g. `-X-VERSION`) or
+    /// as written in a playlist (e.g. `#EXT-X-VERSION`), such that parsing is consistent with the
+    /// [`Display`] implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.strip_prefix("#EXT").unwrap_or(s))
+    }
+}
+
+impl Display for TagName {
+    /// Writes the name of the tag as it appears in a playlist (e.g. `#EXT-X-VERSION`).
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#EXT{}", self.as_str())
+    }
+}
+
+const ALL_TAG_NAMES: [TagName; 32] = [
+    TagName::M3u,
+    TagName::Version,
+    TagName::IndependentSegments,
+    TagName::Start,
+    TagName::Define,
+    TagName::Targetduration,
+    TagName::MediaSequence,
+    TagName::DiscontinuitySequence,
+    TagName::Endlist,
+    TagName::PlaylistType,
+    TagName::IFramesOnly,
+    TagName::PartInf,
+    TagName::ServerControl,
+    TagName::Inf,
+    TagName::Byterange,
+    TagName::Discontinuity,
+    TagName::Key,
+    TagName::Map,
+    TagName::ProgramDateTime,
+    TagName::Gap,
+    TagName::Bitrate,
+    TagName::Part,
+    TagName::Daterange,
+    TagName::Skip,
+    TagName::PreloadHint,
+    TagName::RenditionReport,
+    TagName::Media,
+    TagName::StreamInf,
+    TagName::IFrameStreamInf,
+    TagName::SessionData,
+    TagName::SessionKey,
+    TagName::ContentSteering,
+];
+
+impl TagName {
+    /// All of the tag names defined in the HLS specification (i.e. all of the known tags), in the
+    /// order that they appear in the specification.
+    pub fn all() -> &'static [TagName] {
+        &ALL_TAG_NAMES
+    }
+
+    /// All of the tag names of the given [`TagType`], in the order that they appear in the
+    /// specification.
+    pub fn of_type(tag_type: TagType) -> impl Iterator<Item = TagName> {
+        ALL_TAG_NAMES
+            .into_iter()
+            .filter(move |name| name.tag_type() == tag_type)
+    }
+
+    /// Provides the tag name that matches the name portion after the `#EXT` prefix without regard
+    /// to ASCII case (e.g. `-x-endlist` matches [`TagName::Endlist`]).
+    ///
+    /// The HLS specification defines tag names as case-sensitive, and so the `TryFrom<&str>`
+    /// implementation matches exactly; this is intended for reading playlists from legacy encoders
+    /// (see [`crate::config::ParsingOptionsBuilder::with_case_insensitive_tag_names`]).
+    pub fn try_from_ignore_ascii_case(name: &str) -> Result<Self, ValidationError> {
+        Self::try_from(name).or_else(|error| {
+            ALL_TAG_NAMES
+                .into_iter()
+                .find(|tag_name| tag_name.as_str().eq_ignore_ascii_case(name))
+                .ok_or(error)
+        })
+    }
+
+    /// The string value that the library will parse as the name of the tag (i.e. the name portion
+    /// after the `#EXT` prefix).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::M3u => "M3U",
+            Self::Version => "-X-VERSION",
+            Self::IndependentSegments => "-X-INDEPENDENT-SEGMENTS",
+            Self::Start => "-X-START",
+            Self::Define => "-X-DEFINE",
+            Self::Targetduration => "-X-TARGETDURATION",
+            Self::MediaSequence => "-X-MEDIA-SEQUENCE",
+            Self::DiscontinuitySequence => "-X-DISCONTINUITY-SEQUENCE",
+            Self::Endlist => "-X-ENDLIST",
+            Self::PlaylistType => "-X-PLAYLIST-TYPE",
+            Self::IFramesOnly => "-X-I-FRAMES-ONLY",
+            Self::PartInf => "-X-PART-INF",
+            Self::ServerControl => "-X-SERVER-CONTROL",
+            Self::Inf => "INF",
+            Self::Byterange => "-X-BYTERANGE",
+            Self::Discontinuity => "-X-DISCONTINUITY",
+            Self::Key => "-X-KEY",
+            Self::Map => "-X-MAP",
+            Self::ProgramDateTime => "-X-PROGRAM-DATE-TIME",
+            Self::Gap => "-X-GAP",
+            Self::Bitrate => "-X-BITRATE",
+            Self::Part => "-X-PART",
+            Self::Daterange => "-X-DATERANGE",
+            Self::Skip => "-X-SKIP",
+            Self::PreloadHint => "-X-PRELOAD-HINT",
+            Self::RenditionReport => "-X-RENDITION-REPORT",
+            Self::Media => "-X-MEDIA",
+            Self::StreamInf => "-X-STREAM-INF",
+            Self::IFrameStreamInf => "-X-I-FRAME-STREAM-INF",
+            Self::SessionData => "-X-SESSION-DATA",
+            Self::SessionKey => "-X-SESSION-KEY",
+            Self::ContentSteering => "-X-CONTENT-STEERING",
+        }
+    }
+
+    // Whether the value of the tag is an attribute list.
+    pub(crate) fn has_attribute_list(&self) -> bool {
+        matches!(
+            self,
+            Self::Start
+                | Self::Define
+                | Self::PartInf
+                | Self::ServerControl
+                | Self::Key
+                | Self::Map
+                | Self::Part
+                | Self::Daterange
+                | Self::Skip
+                | Self::PreloadHint
+                | Self::RenditionReport
+                | Self::Media
+                | Self::StreamInf
+                | Self::IFrameStreamInf
+                | Self::SessionData
+                | Self::SessionKey
+                | Self::ContentSteering
+        )
+    }
+
+    // Whether the attribute is retained by the tag implementation when parsed (and so would be
+    // included when the output line is recalculated, unless it has since been removed).
+    pub(crate) fn retains_attribute(&self, name: &str) -> bool {
+        let names: &[&str] = match self {
+            // These tags retain all attributes, including those unknown to the specification.
+            Self::Media | Self::SessionData => return true,
+            Self::Daterange if name.starts_with("X-") => return true,
+            Self::Start => &["TIME-OFFSET", "PRECISE"],
+            Self::Define => &["NAME", "VALUE", "IMPORT", "QUERYPARAM"],
+            Self::PartInf => &["PART-TARGET"],
+            Self::ServerControl => &[
+                "CAN-SKIP-UNTIL",
+                "CAN-SKIP-DATERANGES",
+                "HOLD-BACK",
+                "PART-HOLD-BACK",
+                "CAN-BLOCK-RELOAD",
+            ],
+            Self::Key | Self::SessionKey => {
+                &["METHOD", "URI", "IV", "KEYFORMAT", "KEYFORMATVERSIONS"]
+            }
+            Self::Map => &["URI", "BYTERANGE"],
+            Self::Part => &["URI", "DURATION", "INDEPENDENT", "BYTERANGE", "GAP"],
+            Self::Daterange => &[
+                "ID",
+                "CLASS",
+                "START-DATE",
+                "CUE",
+                "END-DATE",
+                "DURATION",
+                "PLANNED-DURATION",
+                "SCTE35-CMD",
+                "SCTE35-OUT",
+                "SCTE35-IN",
+                "END-ON-NEXT",
+            ],
+            Self::Skip => &["SKIPPED-SEGMENTS", "RECENTLY-REMOVED-DATERANGES"],
+            Self::PreloadHint => &["TYPE", "URI", "BYTERANGE-START", "BYTERANGE-LENGTH"],
+            Self::RenditionReport => &["URI", "LAST-MSN", "LAST-PART"],
+            Self::StreamInf => &[
+                "BANDWIDTH",
+                "AVERAGE-BANDWIDTH",
+                "SCORE",
+                "CODECS",
+                "SUPPLEMENTAL-CODECS",
+                "RESOLUTION",
+                "FRAME-RATE",
+                "HDCP-LEVEL",
+                "ALLOWED-CPC",
+                "VIDEO-RANGE",
+                "REQ-VIDEO-LAYOUT",
+                "STABLE-VARIANT-ID",
+                "AUDIO",
+                "VIDEO",
+                "SUBTITLES",
+                "CLOSED-CAPTIONS",
+                "PATHWAY-ID",
+            ],
+            Self::IFrameStreamInf => &[
+                "URI",
+                "BANDWIDTH",
+                "AVERAGE-BANDWIDTH",
+                "SCORE",
+                "CODECS",
+                "SUPPLEMENTAL-CODECS",
+                "RESOLUTION",
+                "HDCP-LEVEL",
+                "ALLOWED-CPC",
+                "VIDEO-RANGE",
+                "REQ-VIDEO-LAYOUT",
+                "STABLE-VARIANT-ID",
+                "VIDEO",
+                "PATHWAY-ID",
+            ],
+            Self::ContentSteering => &["SERVER-URI", "PATHWAY-ID"],
+            _ => &[],
+        };
+        names.contains(&name)
+    }
+
+    // Whether the attribute has a numeric value (a decimal-integer or a decimal-floating-point),
+    // and so may be written with different formatting for the same value (e.g. `4.000` and `4`).
+    pub(crate) fn has_numeric_attribute(&self, name: &str) -> bool {
+        let names: &[&str] = match self {
+            Self::Start => &["TIME-OFFSET"],
+            Self::PartInf => &["PART-TARGET"],
+            Self::ServerControl => &["CAN-SKIP-UNTIL", "HOLD-BACK", "PART-HOLD-BACK"],
+            Self::Part => &["DURATION"],
+            Self::Daterange => &["DURATION", "PLANNED-DURATION"],
+            Self::Skip => &["SKIPPED-SEGMENTS"],
+            Self::PreloadHint => &["BYTERANGE-START", "BYTERANGE-LENGTH"],
+            Self::RenditionReport => &["LAST-MSN", "LAST-PART"],
+            Self::StreamInf => &["BANDWIDTH", "AVERAGE-BANDWIDTH", "SCORE", "FRAME-RATE"],
+            Self::IFrameStreamInf => &["BANDWIDTH", "AVERAGE-BANDWIDTH", "SCORE"],
+            _ => &[],
+        };
+        names.contains(&name)
+    }
+
+    /// The type of tag this is, as defined by what sub-section of section 4 the tag appears in.
+    pub fn tag_type(&self) -> TagType {
+        match self {
+            Self::M3u => TagType::Basic,
+            Self::Version => TagType::Basic,
+            Self::IndependentSegments => TagType::MediaOrMultivariantPlaylist,
+            Self::Start => TagType::MediaOrMultivariantPlaylist,
+            Self::Define => TagType::MediaOrMultivariantPlaylist,
+            Self::Targetduration => TagType::MediaPlaylist,
+            Self::MediaSequence => TagType::MediaPlaylist,
+            Self::DiscontinuitySequence => TagType::MediaPlaylist,
+            Self::Endlist => TagType::MediaPlaylist,
+            Self::PlaylistType => TagType::MediaPlaylist,
+            Self::IFramesOnly => TagType::MediaPlaylist,
+            Self::PartInf => TagType::MediaPlaylist,
+            Self::ServerControl => TagType::MediaPlaylist,
+            Self::Inf => TagType::MediaSegment,
+            Self::Byterange => TagType::MediaSegment,
+            Self::Discontinuity => TagType::MediaSegment,
+            Self::Key => TagType::MediaSegment,
+            Self::Map => TagType::MediaSegment,
+            Self::ProgramDateTime => TagType::MediaSegment,
+            Self::Gap => TagType::MediaSegment,
+            Self::Bitrate => TagType::MediaSegment,
+            Self::Part => TagType::MediaSegment,
+            Self::Daterange => TagType::MediaMetadata,
+            Self::Skip => TagType::MediaMetadata,
+            Self::PreloadHint => TagType::MediaMetadata,
+            Self::RenditionReport => TagType::MediaMetadata,
+            Self::Media => TagType::MultivariantPlaylist,
+            Self::StreamInf => TagType::MultivariantPlaylist,
+            Self::IFrameStreamInf => TagType::MultivariantPlaylist,
+            Self::SessionData => TagType::MultivariantPlaylist,
+            Self::SessionKey => TagType::MultivariantPlaylist,
+            Self::ContentSteering => TagType::MultivariantPlaylist,
+        }
+    }
+}
+
+/// The type of tag, as determined by the tag sections in the HLS specification.
+///
+/// The sections that are defined are:
+/// * 4.4.1. Basic Tags
+///     * These tags are allowed in both Media Playlists and Multivariant Playlists.
+/// * 4.4.2. Media or Multivariant Playlist Tags
+///     * The tags in this section can appear in either Multivariant Playlists or Media Playlists.
+///       
+///       Tags in this section MUST NOT appear more than once in a Playlist. If one does, clients
+///       MUST fail to parse the Playlist. The only exception to this rule is EXT-X-DEFINE, which
+///       MAY appear more than once.
+/// * 4.4.3. Media Playlist Tags
+///     * Media Playlist tags describe global parameters of the Media Playlist. There MUST NOT be
+///       more than one Media Playlist tag of each type in any Media Playlist.
+///       
+///       A Media Playlist tag MUST NOT appear in a Multivariant Playlist.
+/// * 4.4.4. Media Segment Tags
+///     * Each Media Segment is specified by a series of Media Segment tags followed by a URI. Some
+///       Media Segment tags apply to just the next segment; others apply to all subsequent segments
+///       until another instance of the same tag.
+///       
+///       A Media Segment tag MUST NOT appear in a Multivariant Playlist. Clients MUST fail to parse
+///       Playlists that contain both Media Segment tags and Multivariant Playlist tags.
+/// * 4.4.5. Media Metadata Tags
+///     * Media Metadata tags provide information about the playlist that is not associated with
+///       specific Media Segments. There MAY be more than one Media Metadata tag of each type in any
+///       Media Playlist. The only exception to this rule is an EXT-X-SKIP, which MUST NOT appear
+///       more than once.
+/// * 4.4.6. Multivariant Playlist Tags
+///     * Multivariant Playlist tags define the Variant Streams, Renditions, and other global
+///       parameters of the presentation.
+///       
+///       Multivariant Playlist tags MUST NOT appear in a Media Playlist; clients MUST fail to parse
+///       any Playlist that contains both a Multivariant Playlist tag and either a Media Playlist
+///       tag or a Media Segment tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagType {
+    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.1>
+    Basic,
+    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.2>
+    MediaOrMultivariantPlaylist,
+    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.3>
+    MediaPlaylist,
+    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.4>
+    MediaSegment,
+    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.5>
+    MediaMetadata,
+    /// <https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.6>
+    MultivariantPlaylist,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tag_name_display_should_round_trip_through_from_str() {
+        for name in TagName::all() {
+            assert_eq!(Ok(*name), name.to_string().parse());
+            assert_eq!(Ok(*name), name.as_str().parse());
+            assert_eq!(Ok(*name), TagName::try_from(name.as_str()));
+        }
+        assert_eq!("#EXT-X-VERSION", TagName::Version.to_string());
+        assert!("#EXT-X-UNKNOWN".parse::<TagName>().is_err());
+    }
+
+    #[test]
+    fn tag_names_of_type_should_partition_all() {
+        let types = [
+            TagType::Basic,
+            TagType::MediaOrMultivariantPlaylist,
+            TagType::MediaPlaylist,
+            TagType::MediaSegment,
+            TagType::MediaMetadata,
+            TagType::MultivariantPlaylist,
+        ];
+        let count: usize = types.iter().map(|t| TagName::of_type(*t).count()).sum();
+        assert_eq!(TagName::all().len(), count);
+        assert_eq!(
+            vec![TagName::M3u, TagName::Version],
+            TagName::of_type(TagType::Basic).collect::<Vec<_>>()
+        );
+    }
+}
@@ -9,10 +9,13 @@ use crate::{
     error::{UnknownTagSyntaxError, ValidationError},
     line::{ParsedByteSlice, ParsedLineSlice},
     tag::TagValue,
-    utils::{split_on_new_line, str_from},
+    utils::{fmt_line, split_on_new_line, str_from},
 };
 use memchr::memchr2;
-use std::fmt::Debug;
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Display, Formatter},
+};
 
 /// A tag that is unknown to the library found during parsing input data.
 ///
@@ -70,12 +73,13 @@ use std::fmt::Debug;
 ///     tag.as_bytes()
 /// );
 /// ```
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct UnknownTag<'a> {
     pub(crate) name: &'a str,
     pub(crate) value: Option<TagValue<'a>>,
     pub(crate) original_input: &'a [u8],
     pub(crate) validation_error: Option<ValidationError>,
+    pub(crate) replacement: Option<Cow<'a, [u8]>>, // Set via HlsLine::replace_raw
 }
 
 impl<'a> UnknownTag<'a> {
@@ -107,9 +111,77 @@ impl<'a> UnknownTag<'a> {
 
     /// The raw bytes of the tag line for output.
     ///
-    /// This is useful for when the tag needs to be writtern to an output.
-    pub fn as_bytes(&self) -> &'a [u8] {
-        split_on_new_line(self.original_input).parsed
+    /// This is useful for when the tag needs to be writtern to an output. When the raw bytes have
+    /// been replaced (see [`crate::HlsLine::replace_raw`]) then the replacement is provided.
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.replacement {
+            Some(replacement) => replacement,
+            None => split_on_new_line(self.original_input).parsed,
+        }
+    }
+
+    #[cfg(feature = "hls-tags")]
+    /// Attempts to convert the tag into a known HLS tag (the reverse of
+    /// [`crate::tag::hls::Tag::into_unknown`]), without writing and reading the line again.
+    ///
+    /// This is useful for when a tag was left unknown while reading (e.g. because parsing was not
+    /// enabled for it via [`crate::config::ParsingOptions`]) and is later found to be needed. When
+    /// the raw bytes have been replaced (see [`crate::HlsLine::replace_raw`]), the known tag is
+    /// parsed from the original line and keeps the replacement for output, in the same way as when
+    /// replacing the raw bytes of a known tag.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, Reader, config::ParsingOptions, tag::hls};
+    /// let mut reader = Reader::from_str("#EXT-X-BITRATE:1500", ParsingOptions::builder().build());
+    /// let Some(HlsLine::UnknownTag(tag)) = reader.read_line()? else {
+    ///     panic!("unexpected line")
+    /// };
+    /// let Ok(hls::Tag::Bitrate(bitrate)) = tag.try_into_hls() else {
+    ///     panic!("unexpected tag")
+    /// };
+    /// assert_eq!(1500, bitrate.bitrate());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn try_into_hls(mut self) -> Result<crate::tag::hls::Tag<'a>, ValidationError> {
+        let replacement = self.replacement.take();
+        let mut tag = crate::tag::hls::Tag::try_from(self)?;
+        if let Some(replacement) = replacement {
+            // Tags without a value have no content that can be replaced.
+            let _ = tag.replace_output_line(replacement);
+        }
+        Ok(tag)
+    }
+}
+
+impl Display for UnknownTag<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt_line(self.as_bytes(), f)
+    }
+}
+
+// An unknown tag is serialized as its line, and deserialized by parsing the line again. As the tag
+// borrows the line, it can only be deserialized from data that can be borrowed.
+#[cfg(feature = "serde")]
+impl serde::Serialize for UnknownTag<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de: 'a, 'a> serde::Deserialize<'de> for UnknownTag<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let line = <&'de str>::deserialize(deserializer)?;
+        parse(line)
+            .map(|tag| tag.parsed)
+            .map_err(serde::de::Error::custom)
     }
 }
 
@@ -149,6 +221,7 @@ pub(crate) fn parse_assuming_ext_taken<'a>(
                     value: Some(TagValue(parsed)),
                     original_input,
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining,
             })
@@ -161,6 +234,7 @@ pub(crate) fn parse_assuming_ext_taken<'a>(
                     value: None,
                     original_input,
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: Some(&input[(n + 1)..]),
             })
@@ -173,6 +247,7 @@ pub(crate) fn parse_assuming_ext_taken<'a>(
                     value: None,
                     original_input,
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: Some(&input[(n + 1)..]),
             })
@@ -185,6 +260,7 @@ pub(crate) fn parse_assuming_ext_taken<'a>(
                     value: None,
                     original_input,
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: None,
             })
@@ -204,6 +280,7 @@ mod tests {
             value: None,
             original_input: b"#EXT-X-TEST",
             validation_error: None,
+            replacement: None,
         };
         assert_eq!(None, tag.value());
         assert_eq!(b"#EXT-X-TEST", tag.as_bytes());
@@ -216,6 +293,7 @@ mod tests {
             value: Some(TagValue(b"")),
             original_input: b"#EXT-X-TEST:",
             validation_error: None,
+            replacement: None,
         };
         assert_eq!(Some(TagValue(b"")), tag.value());
         assert_eq!(b"#EXT-X-TEST:", tag.as_bytes());
@@ -228,6 +306,7 @@ mod tests {
             value: Some(TagValue(b"42")),
             original_input: b"#EXT-X-TEST:42",
             validation_error: None,
+            replacement: None,
         };
         assert_eq!(Some(TagValue(b"42")), tag.value());
         assert_eq!(b"#EXT-X-TEST:42", tag.as_bytes());
@@ -240,6 +319,7 @@ mod tests {
             value: Some(TagValue(b"42")),
             original_input: b"#EXT-X-TEST:42\r\n#EXT-X-NEW-TEST\r\n",
             validation_error: None,
+            replacement: None,
         };
         assert_eq!(Some(TagValue(b"42")), tag.value());
         assert_eq!(b"#EXT-X-TEST:42", tag.as_bytes());
@@ -252,6 +332,7 @@ mod tests {
             value: Some(TagValue(b"42")),
             original_input: b"#EXT-X-TEST:42\n#EXT-X-NEW-TEST\n",
             validation_error: None,
+            replacement: None,
         };
         assert_eq!(Some(TagValue(b"42")), tag.value());
         assert_eq!(b"#EXT-X-TEST:42", tag.as_bytes());
@@ -266,6 +347,7 @@ mod tests {
                     value: None,
                     original_input: b"#EXT-TEST-TAG",
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: None
             }),
@@ -278,6 +360,7 @@ mod tests {
                     value: None,
                     original_input: b"#EXT-TEST-TAG\r\n",
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: Some("")
             }),
@@ -290,6 +373,7 @@ mod tests {
                     value: None,
                     original_input: b"#EXT-TEST-TAG\n",
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: Some("")
             }),
@@ -306,6 +390,7 @@ mod tests {
                     value: Some(TagValue(b"42")),
                     original_input: b"#EXT-TEST-TAG:42",
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: None
             }),
@@ -318,6 +403,7 @@ mod tests {
                     value: Some(TagValue(b"42")),
                     original_input: b"#EXT-TEST-TAG:42\r\n",
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: Some("")
             }),
@@ -330,6 +416,7 @@ mod tests {
                     value: Some(TagValue(b"42")),
                     original_input: b"#EXT-TEST-TAG:42\n",
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: Some("")
             }),
@@ -346,6 +433,7 @@ mod tests {
                     value: Some(TagValue(b"42")),
                     original_input: b"#EXT-X-TEST:42\r\n#EXT-X-NEW-TEST\r\n",
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: Some("#EXT-X-NEW-TEST\r\n")
             }),
@@ -362,10 +450,40 @@ mod tests {
                     value: Some(TagValue(b"42")),
                     original_input: b"#EXT-X-TEST:42\n#EXT-X-NEW-TEST\n",
                     validation_error: None,
+                    replacement: None,
                 },
                 remaining: Some("#EXT-X-NEW-TEST\n")
             }),
             parse("#EXT-X-TEST:42\n#EXT-X-NEW-TEST\n")
         );
     }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn conversion_to_and_from_hls_tag_should_round_trip() {
+        let line = "#EXT-X-DEFINE:NAME=\"a\",VALUE=\"b\"\n#EXTINF:4\n";
+        let unknown = parse(line).unwrap().parsed;
+        let tag = unknown.clone().try_into_hls().unwrap();
+        assert!(matches!(
+            tag,
+            crate::tag::hls::Tag::Define(crate::tag::hls::Define::Name(_))
+        ));
+        assert_eq!(Ok(unknown), tag.into_unknown());
+
+        let mut tag = parse("#EXT-X-BITRATE:10")
+            .unwrap()
+            .parsed
+            .try_into_hls()
+            .unwrap();
+        if let crate::tag::hls::Tag::Bitrate(bitrate) = &mut tag {
+            bitrate.set_comment("note");
+        }
+        assert!(tag.into_unknown().is_err());
+
+        let mut unknown = parse("#EXT-X-BITRATE:10").unwrap().parsed;
+        unknown.replacement = Some(Cow::Borrowed(b"#EXT-X-BITRATE:20"));
+        let tag = unknown.try_into_hls().unwrap();
+        assert_eq!("#EXT-X-BITRATE:20", tag.to_string());
+        assert_eq!(Some(TagValue(b"20")), tag.into_unknown().unwrap().value());
+    }
 }
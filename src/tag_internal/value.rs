@@ -8,12 +8,21 @@ use crate::{
     error::{
         AttributeListParsingError, DateTimeSyntaxError, DecimalResolutionParseError,
         ParseDecimalFloatingPointWithTitleError, ParseDecimalIntegerRangeError, ParseFloatError,
-        ParseNumberError, ParsePlaylistTypeError,
+        ParseNumberError, ParsePlaylistTypeError, ParseSegmentDurationError, RangeRequestError,
     },
     utils::parse_u64,
 };
 use memchr::{memchr, memchr3_iter};
-use std::{borrow::Cow, collections::HashMap, fmt::Display};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    iter::Sum,
+    ops::{Add, AddAssign, Range},
+    str::FromStr,
+};
 
 /// A wrapper struct that provides many convenience methods for converting a tag value into a more
 /// specialized type.
@@ -36,7 +45,7 @@ use std::{borrow::Cow, collections::HashMap, fmt::Display};
 /// # use quick_m3u8::{
 /// #     HlsLine, Reader,
 /// #     config::ParsingOptions,
-/// #     date, date_time,
+/// #     date_time,
 /// #     custom_parsing::ParsedByteSlice,
 /// #     tag::{TagValue, AttributeValue},
 /// #     error::ValidationError,
@@ -66,8 +75,7 @@ use std::{borrow::Cow, collections::HashMap, fmt::Display};
 ///         // Prove that we can extract the value of LOCAL
 ///         let local = list
 ///             .get("LOCAL")
-///             .and_then(AttributeValue::unquoted)
-///             .and_then(|v| date::parse_bytes(v.0).ok())
+///             .and_then(|v| v.try_as_date_time().ok())
 ///             .ok_or(ValidationError::MissingRequiredAttribute("LOCAL"))?;
 ///         assert_eq!(date_time!(1970-01-01 T 00:00:00.000), local);
 ///     }
@@ -261,114 +269,334 @@ impl<'a> TagValue<'a> {
         &self,
     ) -> Result<Vec<(&'a str, AttributeValue<'a>)>, AttributeListParsingError> {
         let mut attribute_list = Vec::new();
-        let mut list_iter = memchr3_iter(b'=', b',', b'"', self.0);
-        // Name in first position is special because we want to capture the whole value from the
-        // previous_match_index (== 0), rather than in the rest of cases, where we want to capture
-        // the value at the index after the previous match (which should be b','). Therefore, we use
-        // the `next` method to step through the first match and handle it specially, then proceed
-        // to loop through the iterator for all others.
-        let Some(first_match_index) = list_iter.next() else {
-            return Err(AttributeListParsingError::EndOfLineWhileReadingAttributeName);
-        };
-        if self.0[first_match_index] != b'=' {
-            return Err(AttributeListParsingError::UnexpectedCharacterInAttributeName);
+        self.parse_attribute_list(|name, value| attribute_list.push((name, value)))?;
+        Ok(attribute_list)
+    }
+
+    /// Attempt to convert the tag value bytes into an [`AttributeMap`].
+    ///
+    /// This provides the same ordering guarantees as [`Self::try_as_ordered_attribute_list`];
+    /// however, the attributes are stored inline (without allocating) when there are no more than
+    /// [`AttributeMap::INLINE_CAPACITY`] of them, which is the case for almost all attribute lists
+    /// found in practice. Lookups are a linear scan, which for lists of this size is faster than
+    /// hashing. This is the method used by the library when parsing the attribute list of known
+    /// tags, and it is recommended over [`Self::try_as_attribute_list`] for custom tags too (or
+    /// [`Self::attribute_list_iter`] when a single pass through the attributes is enough).
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::tag::{AttributeValue, UnquotedAttributeValue};
+    /// let tag = quick_m3u8::custom_parsing::tag::parse(
+    ///     "#EXT-X-EXAMPLE:TYPE=LIST,VALUE=\"example\""
+    /// )?.parsed;
+    /// if let Some(value) = tag.value() {
+    ///     let map = value.try_as_attribute_map()?;
+    ///     assert_eq!(2, map.len());
+    ///     assert_eq!(Some(AttributeValue::Quoted("example")), map.get("VALUE"));
+    ///     assert_eq!(
+    ///         vec![
+    ///             ("TYPE", AttributeValue::Unquoted(UnquotedAttributeValue(b"LIST"))),
+    ///             ("VALUE", AttributeValue::Quoted("example"))
+    ///         ],
+    ///         map.iter().collect::<Vec<_>>()
+    ///     );
+    /// }
+    /// # else { panic!("unexpected empty value"); }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn try_as_attribute_map(&self) -> Result<AttributeMap<'a>, AttributeListParsingError> {
+        let mut attribute_map = AttributeMap::new();
+        self.parse_attribute_list(|name, value| attribute_map.push(name, value))?;
+        Ok(attribute_map)
+    }
+
+    /// Provides an iterator through the attributes of the tag value (as an attribute list), in the
+    /// order in which they are found, parsing each attribute lazily.
+    ///
+    /// Nothing is allocated, and iteration can be stopped as soon as the attributes of interest
+    /// have been found, which makes this the recommended route for implementations of
+    /// [`crate::tag::CustomTag`] (and consumers of [`crate::tag::UnknownTag`]) that make a single
+    /// pass through the attributes. When attributes need to be looked up by name more than once,
+    /// [`Self::try_as_attribute_map`] is more convenient.
+    ///
+    /// Each item is a `Result`, as the attribute list is validated as it is iterated. After an
+    /// error is provided the iterator is finished (as there is no way to know where the next
+    /// attribute starts). For example:
+    /// ```
+    /// # use quick_m3u8::tag::{AttributeValue, UnquotedAttributeValue};
+    /// # use quick_m3u8::error::AttributeListParsingError;
+    /// let tag = quick_m3u8::custom_parsing::tag::parse(
+    ///     "#EXT-X-EXAMPLE:TYPE=LIST,VALUE=\"example\",BAD"
+    /// )?.parsed;
+    /// if let Some(value) = tag.value() {
+    ///     let mut attributes = value.attribute_list_iter();
+    ///     assert_eq!(
+    ///         Some(Ok(("TYPE", AttributeValue::Unquoted(UnquotedAttributeValue(b"LIST"))))),
+    ///         attributes.next()
+    ///     );
+    ///     assert_eq!(Some(Ok(("VALUE", AttributeValue::Quoted("example")))), attributes.next());
+    ///     assert_eq!(
+    ///         Some(Err(AttributeListParsingError::EndOfLineWhileReadingAttributeName)),
+    ///         attributes.next()
+    ///     );
+    ///     assert_eq!(None, attributes.next());
+    /// }
+    /// # else { panic!("unexpected empty value"); }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn attribute_list_iter(&self) -> AttributeListIter<'a> {
+        AttributeListIter {
+            input: self.0,
+            matches: memchr3_iter(b'=', b',', b'"', self.0),
+            state: AttributeListParsingState::Start,
+            previous_match_index: 0,
         }
-        let mut previous_match_index = first_match_index;
-        let mut state = AttributeListParsingState::ReadingValue {
-            name: std::str::from_utf8(&self.0[..first_match_index])?,
-        };
-        for i in list_iter {
-            let byte = self.0[i];
-            match state {
+    }
+
+    fn parse_attribute_list(
+        &self,
+        mut push: impl FnMut(&'a str, AttributeValue<'a>),
+    ) -> Result<(), AttributeListParsingError> {
+        for attribute in self.attribute_list_iter() {
+            let (name, value) = attribute?;
+            push(name, value);
+        }
+        Ok(())
+    }
+}
+
+/// An iterator through the attributes of an attribute list, provided by
+/// [`TagValue::attribute_list_iter`].
+#[derive(Debug, Clone)]
+pub struct AttributeListIter<'a> {
+    input: &'a [u8],
+    matches: memchr::Memchr3<'a>,
+    state: AttributeListParsingState<'a>,
+    previous_match_index: usize,
+}
+
+impl<'a> Iterator for AttributeListIter<'a> {
+    type Item = Result<(&'a str, AttributeValue<'a>), AttributeListParsingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.step() {
+            Ok(attribute) => attribute.map(Ok),
+            Err(error) => {
+                self.state = AttributeListParsingState::Finished;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl std::iter::FusedIterator for AttributeListIter<'_> {}
+
+impl<'a> AttributeListIter<'a> {
+    fn step(&mut self) -> Result<Option<(&'a str, AttributeValue<'a>)>, AttributeListParsingError> {
+        let input = self.input;
+        for i in self.matches.by_ref() {
+            let byte = input[i];
+            match self.state {
+                // Name in first position is special because we want to capture the whole value
+                // from the start of the input, rather than in the rest of cases, where we want to
+                // capture the value at the index after the previous match (which should be b',').
+                AttributeListParsingState::Start => {
+                    if byte != b'=' {
+                        return Err(AttributeListParsingError::UnexpectedCharacterInAttributeName);
+                    }
+                    let name = std::str::from_utf8(&input[..i])?;
+                    self.state = AttributeListParsingState::ReadingValue { name };
+                    self.previous_match_index = i;
+                }
                 AttributeListParsingState::ReadingName => {
                     if byte == b'=' {
                         // end of name section
-                        let name = std::str::from_utf8(&self.0[(previous_match_index + 1)..i])?;
+                        let name = std::str::from_utf8(&input[(self.previous_match_index + 1)..i])?;
                         if name.is_empty() {
                             return Err(AttributeListParsingError::EmptyAttributeName);
                         }
-                        state = AttributeListParsingState::ReadingValue { name };
+                        self.state = AttributeListParsingState::ReadingValue { name };
                     } else {
                         // b',' and b'"' are both unexpected
                         return Err(AttributeListParsingError::UnexpectedCharacterInAttributeName);
                     }
-                    previous_match_index = i;
+                    self.previous_match_index = i;
                 }
                 AttributeListParsingState::ReadingQuotedValue { name } => {
                     if byte == b'"' {
                         // only byte that ends the quoted value is b'"'
-                        let value = std::str::from_utf8(&self.0[(previous_match_index + 1)..i])?;
-                        state =
+                        let value =
+                            std::str::from_utf8(&input[(self.previous_match_index + 1)..i])?;
+                        self.state =
                             AttributeListParsingState::FinishedReadingQuotedValue { name, value };
-                        previous_match_index = i;
+                        self.previous_match_index = i;
                     }
                 }
                 AttributeListParsingState::ReadingValue { name } => {
                     if byte == b'"' {
                         // must check that this is the first character of the value
-                        if previous_match_index != (i - 1) {
+                        if self.previous_match_index != (i - 1) {
                             // finding b'"' mid-value is unexpected
                             return Err(
                                 AttributeListParsingError::UnexpectedCharacterInAttributeValue,
                             );
                         }
-                        state = AttributeListParsingState::ReadingQuotedValue { name };
+                        self.state = AttributeListParsingState::ReadingQuotedValue { name };
+                        self.previous_match_index = i;
                     } else if byte == b',' {
-                        let value = UnquotedAttributeValue(&self.0[(previous_match_index + 1)..i]);
+                        let value =
+                            UnquotedAttributeValue(&input[(self.previous_match_index + 1)..i]);
                         if value.0.is_empty() {
                             // an empty unquoted value is unexpected (only quoted may be empty)
                             return Err(AttributeListParsingError::EmptyUnquotedValue);
                         }
-                        attribute_list.push((name, AttributeValue::Unquoted(value)));
-                        state = AttributeListParsingState::ReadingName;
+                        self.state = AttributeListParsingState::ReadingName;
+                        self.previous_match_index = i;
+                        return Ok(Some((name, AttributeValue::Unquoted(value))));
                     } else {
                         // b'=' is unexpected while reading value (only b',' or b'"' are expected)
                         return Err(AttributeListParsingError::UnexpectedCharacterInAttributeValue);
                     }
-                    previous_match_index = i;
                 }
                 AttributeListParsingState::FinishedReadingQuotedValue { name, value } => {
                     if byte == b',' {
-                        attribute_list.push((name, AttributeValue::Quoted(value)));
-                        state = AttributeListParsingState::ReadingName;
+                        self.state = AttributeListParsingState::ReadingName;
+                        self.previous_match_index = i;
+                        return Ok(Some((name, AttributeValue::Quoted(value))));
                     } else {
                         // b',' (or end of line) must come after end of quote - all else is invalid
                         return Err(AttributeListParsingError::UnexpectedCharacterAfterQuoteEnd);
                     }
-                    previous_match_index = i;
                 }
+                AttributeListParsingState::Finished => return Ok(None),
             }
         }
         // Need to check state at end of line as this will likely not be a match in the above
         // iteration.
-        match state {
-            AttributeListParsingState::ReadingName => {
-                return Err(AttributeListParsingError::EndOfLineWhileReadingAttributeName);
+        match std::mem::replace(&mut self.state, AttributeListParsingState::Finished) {
+            AttributeListParsingState::Start | AttributeListParsingState::ReadingName => {
+                Err(AttributeListParsingError::EndOfLineWhileReadingAttributeName)
             }
             AttributeListParsingState::ReadingValue { name } => {
-                let value = UnquotedAttributeValue(&self.0[(previous_match_index + 1)..]);
+                let value = UnquotedAttributeValue(&input[(self.previous_match_index + 1)..]);
                 if value.0.is_empty() {
                     // an empty unquoted value is unexpected (only quoted may be empty)
                     return Err(AttributeListParsingError::EmptyUnquotedValue);
                 }
-                attribute_list.push((name, AttributeValue::Unquoted(value)));
+                Ok(Some((name, AttributeValue::Unquoted(value))))
             }
             AttributeListParsingState::ReadingQuotedValue { name: _ } => {
-                return Err(AttributeListParsingError::EndOfLineWhileReadingQuotedValue);
+                Err(AttributeListParsingError::EndOfLineWhileReadingQuotedValue)
             }
             AttributeListParsingState::FinishedReadingQuotedValue { name, value } => {
-                attribute_list.push((name, AttributeValue::Quoted(value)));
+                Ok(Some((name, AttributeValue::Quoted(value))))
             }
+            AttributeListParsingState::Finished => Ok(None),
         }
-        Ok(attribute_list)
     }
 }
 
+#[derive(Debug, Clone, Copy)]
 enum AttributeListParsingState<'a> {
+    Start,
     ReadingName,
     ReadingValue { name: &'a str },
     ReadingQuotedValue { name: &'a str },
     FinishedReadingQuotedValue { name: &'a str, value: &'a str },
+    Finished,
+}
+
+/// An ordered map of attribute names to values, optimized for the small attribute lists found in
+/// HLS playlists.
+///
+/// The first [`Self::INLINE_CAPACITY`] attributes are stored inline, and so constructing the map
+/// does not allocate unless the attribute list is unusually long (in which case the remaining
+/// attributes spill over into a `Vec`). Lookups via [`Self::get`] are a linear scan. Iteration
+/// order is the order in which the attributes were found in the tag.
+///
+/// This is constructed via [`TagValue::try_as_attribute_map`].
+#[derive(Clone, PartialEq)]
+pub struct AttributeMap<'a> {
+    len: usize,
+    inline: [(&'a str, AttributeValue<'a>); AttributeMap::INLINE_CAPACITY],
+    spilled: Vec<(&'a str, AttributeValue<'a>)>,
+}
+
+impl<'a> AttributeMap<'a> {
+    /// The number of attributes that can be held before the map needs to allocate.
+    pub const INLINE_CAPACITY: usize = 16;
+
+    fn new() -> Self {
+        Self {
+            len: 0,
+            inline: [("", AttributeValue::Quoted("")); Self::INLINE_CAPACITY],
+            spilled: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, name: &'a str, value: AttributeValue<'a>) {
+        if self.len < Self::INLINE_CAPACITY {
+            self.inline[self.len] = (name, value);
+        } else {
+            self.spilled.push((name, value));
+        }
+        self.len += 1;
+    }
+
+    /// Get the value of the attribute with the provided name (if it exists).
+    ///
+    /// If the name is duplicated in the list then the last value is returned (consistent with
+    /// [`TagValue::try_as_attribute_list`]).
+    pub fn get(&self, name: &str) -> Option<AttributeValue<'a>> {
+        self.as_slices()
+            .into_iter()
+            .rev()
+            .flat_map(|slice| slice.iter().rev())
+            .find_map(|(n, v)| (*n == name).then_some(*v))
+    }
+
+    /// Indicates whether an attribute with the provided name exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.iter().any(|(n, _)| n == name)
+    }
+
+    /// The number of attributes in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indicates whether the map has no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate through the attributes in the order in which they were found.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, AttributeValue<'a>)> + '_ {
+        self.as_slices().into_iter().flatten().copied()
+    }
+
+    fn as_slices(&self) -> [&[(&'a str, AttributeValue<'a>)]; 2] {
+        let inline_len = self.len.min(Self::INLINE_CAPACITY);
+        [&self.inline[..inline_len], &self.spilled]
+    }
+}
+
+impl std::fmt::Debug for AttributeMap<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for AttributeMap<'a> {
+    type Item = (&'a str, AttributeValue<'a>);
+    type IntoIter = std::iter::Chain<
+        std::iter::Take<std::array::IntoIter<Self::Item, { AttributeMap::INLINE_CAPACITY }>>,
+        std::vec::IntoIter<Self::Item>,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inline.into_iter().take(self.len).chain(self.spilled)
+    }
 }
 
 /// An attribute value within an attribute list.
@@ -422,6 +650,32 @@ impl<'a> AttributeValue<'a> {
             AttributeValue::Quoted(s) => Some(*s),
         }
     }
+    /// Attempt to convert the attribute value into a [`DateTime`].
+    ///
+    /// Date-time attributes are normally quoted (e.g. `START-DATE` in `EXT-X-DATERANGE`), and so
+    /// the value is parsed from within the quotes; however, unquoted values are also accepted, as
+    /// some custom tags do not quote their dates. For example:
+    /// ```
+    /// # use quick_m3u8::{date_time, error::ParseTagValueError};
+    /// let tag = quick_m3u8::custom_parsing::tag::parse(
+    ///     "#EXT-X-EXAMPLE:ID=\"1\",EXPIRES=\"2025-08-10T17:27:42.213Z\""
+    /// )?.parsed;
+    /// let list = tag
+    ///     .value()
+    ///     .ok_or(ParseTagValueError::UnexpectedEmpty)?
+    ///     .try_as_attribute_list()?;
+    /// if let Some(expires) = list.get("EXPIRES") {
+    ///     assert_eq!(date_time!(2025-08-10 T 17:27:42.213), expires.try_as_date_time()?);
+    /// }
+    /// # else { panic!("unexpected missing EXPIRES"); }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn try_as_date_time(&self) -> Result<DateTime, DateTimeSyntaxError> {
+        match self {
+            AttributeValue::Unquoted(v) => date::parse_bytes(v.0),
+            AttributeValue::Quoted(s) => date::parse(s),
+        }
+    }
 }
 
 /// A wrapper struct that provides many convenience methods for converting an unquoted attribute
@@ -563,6 +817,7 @@ impl<'a> UnquotedAttributeValue<'a> {
 ///
 /// [`#EXT-X-PLAYLIST-TYPE`]: https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-4.4.3.5
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HlsPlaylistType {
     /// If the `EXT-X-PLAYLIST-TYPE` value is EVENT, Media Segments can only be added to the end of
     /// the Media Playlist.
@@ -769,6 +1024,7 @@ impl From<DecimalResolution> for WritableAttributeValue<'_> {
 
 /// A decimal resolution (`<width>x<height>`).
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DecimalResolution {
     /// A horizontal pixel dimension (width).
     pub width: u64,
@@ -801,6 +1057,13 @@ impl TryFrom<&str> for DecimalResolution {
         Self::try_from(s.as_bytes())
     }
 }
+impl FromStr for DecimalResolution {
+    type Err = DecimalResolutionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
 
 /// Represents the decimal-integer-range that is found in several places, from tag values to
 /// attribute values, and has structure `<n>[@<o>]`.
@@ -818,6 +1081,7 @@ impl TryFrom<&str> for DecimalResolution {
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DecimalIntegerRange {
     /// Corresponds to the length component in the value (`n` in `<n>@<o>`).
     pub length: u64,
@@ -864,6 +1128,416 @@ impl TryFrom<&str> for DecimalIntegerRange {
         Self::try_from(s.as_bytes())
     }
 }
+impl FromStr for DecimalIntegerRange {
+    type Err = ParseDecimalIntegerRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+impl DecimalIntegerRange {
+    /// The offset of the first byte after the range (`o + n`), or `None` if the offset is not
+    /// known (or the end would overflow `u64`).
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::tag::ByteRange;
+    /// assert_eq!(Some(1536), ByteRange { length: 1024, offset: Some(512) }.end());
+    /// assert_eq!(None, ByteRange { length: 1024, offset: None }.end());
+    /// ```
+    pub fn end(&self) -> Option<u64> {
+        self.offset
+            .and_then(|offset| offset.checked_add(self.length))
+    }
+
+    /// Indicates whether the `next` range immediately follows this range.
+    ///
+    /// This is the case when `next` starts at the [`Self::end`] of this range, or when `next` has no
+    /// offset, as HLS defines that a missing offset means that the sub-range begins at the next byte
+    /// following the previous sub-range (of the same resource). For example:
+    /// ```
+    /// # use quick_m3u8::tag::ByteRange;
+    /// let range = ByteRange { length: 1024, offset: Some(512) };
+    /// assert!(range.contiguous_with(&ByteRange { length: 10, offset: Some(1536) }));
+    /// assert!(range.contiguous_with(&ByteRange { length: 10, offset: None }));
+    /// assert!(!range.contiguous_with(&ByteRange { length: 10, offset: Some(1537) }));
+    /// ```
+    pub fn contiguous_with(&self, next: &Self) -> bool {
+        match next.offset {
+            Some(offset) => self.end() == Some(offset),
+            None => true,
+        }
+    }
+
+    /// Provides the range with the offset resolved, using `previous_end` (the end of the previous
+    /// sub-range of the same resource) if there is no offset.
+    pub fn with_implicit_offset(&self, previous_end: u64) -> Self {
+        Self {
+            length: self.length,
+            offset: Some(self.offset.unwrap_or(previous_end)),
+        }
+    }
+}
+
+/// A byte range (`<n>[@<o>]`), as found in `EXT-X-BYTERANGE`, and the `BYTERANGE` attributes of
+/// `EXT-X-PART` and `EXT-X-MAP`.
+///
+/// This is the same type as [`DecimalIntegerRange`], and provides conversions to and from the other
+/// representations of byte ranges in the library (the [`crate::tag::hls::Byterange`] tag and
+/// [`crate::tag::hls::MapByterange`], where the offset is required). For example:
+/// ```
+/// # use quick_m3u8::tag::{ByteRange, hls::{Byterange, MapByterange}};
+/// let range = ByteRange::try_from("1024@512")?;
+/// assert_eq!("1024@512", range.to_string());
+///
+/// let tag = Byterange::from(range);
+/// assert_eq!("#EXT-X-BYTERANGE:1024@512", tag.to_string());
+/// assert_eq!(range, tag.range());
+///
+/// let map_byterange = MapByterange::try_from(range)?;
+/// assert_eq!(range, ByteRange::from(map_byterange));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub type ByteRange = DecimalIntegerRange;
+
+/// An absolute byte range of a resource (`start..end`, where `end` is exclusive), resolved from a
+/// byte range in a playlist, for use in an HTTP request.
+///
+/// A [`ByteRange`] may omit the offset, in which case the sub-range begins at the next byte
+/// following the previous sub-range of the same resource, and so the offset must be resolved (see
+/// [`DecimalIntegerRange::with_implicit_offset`]) before converting. The conversion is provided
+/// from each of the representations of byte ranges in the library ([`ByteRange`], which is also the
+/// `BYTERANGE` of [`crate::tag::hls::Part`], the [`crate::tag::hls::Byterange`] tag, and
+/// [`crate::tag::hls::MapByterange`]). The [`crate::playlist::RangeRequestTracker`] takes care of
+/// resolving implicit offsets while reading a playlist.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{error::RangeRequestError, tag::{ByteRange, RangeRequest}};
+/// let range = ByteRange { length: 1024, offset: None };
+/// assert_eq!(Err(RangeRequestError::MissingOffset), RangeRequest::try_from(range));
+///
+/// let request = RangeRequest::try_from(range.with_implicit_offset(512))?;
+/// assert_eq!(512..1536, request.range());
+/// assert_eq!(Some("bytes=512-1535".to_string()), request.header_value());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RangeRequest {
+    /// The offset of the first byte of the range.
+    pub start: u64,
+    /// The offset of the first byte after the range.
+    pub end: u64,
+}
+impl RangeRequest {
+    /// The range as `start..end`.
+    pub fn range(&self) -> Range<u64> {
+        self.start..self.end
+    }
+
+    /// The number of bytes in the range.
+    pub fn len(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Indicates whether the range contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The value to use for the HTTP `Range` header (`bytes=<first>-<last>`, where both are
+    /// inclusive). This is `None` when the range is empty, as an empty range cannot be requested.
+    pub fn header_value(&self) -> Option<String> {
+        (!self.is_empty()).then(|| format!("bytes={}-{}", self.start, self.end - 1))
+    }
+}
+impl TryFrom<DecimalIntegerRange> for RangeRequest {
+    type Error = RangeRequestError;
+
+    fn try_from(range: DecimalIntegerRange) -> Result<Self, Self::Error> {
+        let start = range.offset.ok_or(RangeRequestError::MissingOffset)?;
+        let end = range.end().ok_or(RangeRequestError::Overflow)?;
+        Ok(Self { start, end })
+    }
+}
+impl From<RangeRequest> for DecimalIntegerRange {
+    fn from(request: RangeRequest) -> Self {
+        Self {
+            length: request.len(),
+            offset: Some(request.start),
+        }
+    }
+}
+
+/// The duration of a Media Segment or Partial Segment, held as an exact decimal.
+///
+/// Durations are provided as `f64` by the tags (e.g. [`crate::tag::hls::Inf::duration`]), which
+/// is convenient, but summing and comparing floating point numbers accumulates errors (for example,
+/// `0.1 + 0.2 != 0.3`), which leads to subtle bugs when calculating the duration of a playlist
+/// window, or when checking segment durations against the `EXT-X-TARGETDURATION`. The
+/// `SegmentDuration` holds the decimal exactly as it was written (up to 19 significant digits), so
+/// arithmetic and comparisons are exact, and the original textual representation is retained (e.g.
+/// `4.000` is displayed as `4.000`, while being equal to `4`).
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::tag::SegmentDuration;
+/// let a = SegmentDuration::try_from("0.1")?;
+/// let b = SegmentDuration::try_from("0.2")?;
+/// assert_eq!(SegmentDuration::try_from("0.3")?, a + b);
+/// assert_ne!(0.3, 0.1 + 0.2);
+///
+/// let duration = SegmentDuration::try_from("4.000")?;
+/// assert_eq!("4.000", duration.to_string());
+/// assert_eq!(SegmentDuration::from_secs(4), duration);
+/// assert_eq!(4000, duration.as_millis());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentDuration {
+    // The duration is `units / 10^scale` seconds.
+    units: u64,
+    scale: u8,
+}
+// 10^19 is the largest power of 10 that fits within a u64.
+const MAX_SEGMENT_DURATION_SCALE: u8 = 19;
+impl SegmentDuration {
+    /// A duration of zero.
+    pub const ZERO: Self = Self { units: 0, scale: 0 };
+
+    /// Constructs a duration of a whole number of seconds.
+    pub const fn from_secs(secs: u64) -> Self {
+        Self {
+            units: secs,
+            scale: 0,
+        }
+    }
+
+    /// Constructs a duration of a whole number of milliseconds (displayed with 3 decimal places).
+    pub const fn from_millis(millis: u64) -> Self {
+        Self {
+            units: millis,
+            scale: 3,
+        }
+    }
+
+    /// Constructs a duration of a whole number of microseconds (displayed with 6 decimal places).
+    pub const fn from_micros(micros: u64) -> Self {
+        Self {
+            units: micros,
+            scale: 6,
+        }
+    }
+
+    /// Constructs a duration from a number of seconds.
+    ///
+    /// The duration takes the shortest decimal representation that converts back to the same
+    /// `f64` (the same as the `Display` of the `f64`), and so `0.1` is exactly `0.1`. Values that
+    /// need more than 19 significant digits are rounded to the nearest microsecond, and values
+    /// that are negative (or not a number) are taken to be zero.
+    pub fn from_secs_f64(secs: f64) -> Self {
+        if secs.is_nan() || secs <= 0.0 {
+            return Self::ZERO;
+        }
+        match Self::try_from(format!("{secs}").as_str()) {
+            Ok(duration) => duration,
+            Err(_) => Self::from_micros((secs * 1_000_000.0).round() as u64),
+        }
+    }
+
+    /// The duration as a number of seconds.
+    pub fn as_secs_f64(&self) -> f64 {
+        self.units as f64 / 10u64.pow(u32::from(self.scale)) as f64
+    }
+
+    /// The duration as a number of milliseconds (rounded to the nearest millisecond).
+    pub fn as_millis(&self) -> u128 {
+        self.rounded(3)
+    }
+
+    /// The duration as a number of microseconds (rounded to the nearest microsecond).
+    pub fn as_micros(&self) -> u128 {
+        self.rounded(6)
+    }
+
+    /// The duration rounded to the nearest whole number of seconds.
+    pub fn round_secs(&self) -> u64 {
+        self.rounded(0) as u64
+    }
+
+    /// Indicates whether the duration exceeds the target duration (the value of the
+    /// `EXT-X-TARGETDURATION` tag).
+    ///
+    /// The HLS specification requires that the duration of each Media Segment, when rounded to the
+    /// nearest integer, is less than or equal to the target duration. For example:
+    /// ```
+    /// # use quick_m3u8::tag::SegmentDuration;
+    /// assert!(!SegmentDuration::try_from("4.499")?.exceeds_target_duration(4));
+    /// assert!(SegmentDuration::try_from("4.5")?.exceeds_target_duration(4));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn exceeds_target_duration(&self, target_duration: u64) -> bool {
+        self.round_secs() > target_duration
+    }
+
+    /// Adds the durations, returning `None` if the result cannot be represented.
+    ///
+    /// The result has the greater number of decimal places of the two durations (e.g. `4.000`
+    /// plus `2` is `6.000`).
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+        let units = self
+            .units_at_scale(scale)?
+            .checked_add(other.units_at_scale(scale)?)?;
+        Some(Self { units, scale })
+    }
+
+    fn units_at_scale(&self, scale: u8) -> Option<u64> {
+        self.units
+            .checked_mul(10u64.pow(u32::from(scale - self.scale)))
+    }
+
+    // The units scaled up to the maximum scale, such that durations can be compared exactly.
+    fn comparable(&self) -> u128 {
+        u128::from(self.units) * 10u128.pow(u32::from(MAX_SEGMENT_DURATION_SCALE - self.scale))
+    }
+
+    // The duration as a number of `10^-scale` units, rounded to the nearest unit.
+    fn rounded(&self, scale: u8) -> u128 {
+        let units = u128::from(self.units);
+        if scale >= self.scale {
+            units * 10u128.pow(u32::from(scale - self.scale))
+        } else {
+            let divisor = 10u128.pow(u32::from(self.scale - scale));
+            (units + divisor / 2) / divisor
+        }
+    }
+
+    // The duration with trailing zeros removed from the decimal places, such that equal durations
+    // have the same representation.
+    fn normalized(&self) -> Self {
+        let mut normalized = *self;
+        while normalized.scale > 0 && normalized.units.is_multiple_of(10) {
+            normalized.units /= 10;
+            normalized.scale -= 1;
+        }
+        normalized
+    }
+}
+impl Default for SegmentDuration {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+impl PartialEq for SegmentDuration {
+    fn eq(&self, other: &Self) -> bool {
+        self.comparable() == other.comparable()
+    }
+}
+impl Eq for SegmentDuration {}
+impl PartialOrd for SegmentDuration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for SegmentDuration {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.comparable().cmp(&other.comparable())
+    }
+}
+impl Hash for SegmentDuration {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalized();
+        normalized.units.hash(state);
+        normalized.scale.hash(state);
+    }
+}
+/// Panics if the result cannot be represented (see [`SegmentDuration::checked_add`]).
+impl Add for SegmentDuration {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        self.checked_add(other)
+            .expect("overflow when adding segment durations")
+    }
+}
+impl AddAssign for SegmentDuration {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+impl Sum for SegmentDuration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+impl<'a> Sum<&'a SegmentDuration> for SegmentDuration {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+impl Display for SegmentDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.scale == 0 {
+            return write!(f, "{}", self.units);
+        }
+        let divisor = 10u64.pow(u32::from(self.scale));
+        write!(
+            f,
+            "{}.{:0width$}",
+            self.units / divisor,
+            self.units % divisor,
+            width = usize::from(self.scale)
+        )
+    }
+}
+impl TryFrom<&[u8]> for SegmentDuration {
+    type Error = ParseSegmentDurationError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let (integer, fraction) = match memchr(b'.', value) {
+            Some(n) => (&value[..n], &value[(n + 1)..]),
+            None => (value, &[][..]),
+        };
+        let is_decimal = |digits: &[u8]| digits.iter().all(u8::is_ascii_digit);
+        if integer.is_empty()
+            || !is_decimal(integer)
+            || !is_decimal(fraction)
+            || (fraction.is_empty() && integer.len() < value.len())
+        {
+            return Err(ParseSegmentDurationError::InvalidDecimal);
+        }
+        if fraction.len() > usize::from(MAX_SEGMENT_DURATION_SCALE) {
+            return Err(ParseSegmentDurationError::TooPrecise);
+        }
+        let units = integer
+            .iter()
+            .chain(fraction)
+            .try_fold(0u64, |units, digit| {
+                units.checked_mul(10)?.checked_add(u64::from(digit - b'0'))
+            })
+            .ok_or(ParseSegmentDurationError::TooPrecise)?;
+        Ok(Self {
+            units,
+            scale: fraction.len() as u8,
+        })
+    }
+}
+impl TryFrom<&str> for SegmentDuration {
+    type Error = ParseSegmentDurationError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::try_from(s.as_bytes())
+    }
+}
+impl FromStr for SegmentDuration {
+    type Err = ParseSegmentDurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -871,6 +1545,123 @@ mod tests {
 
     use super::*;
     use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    #[test]
+    fn segment_duration_should_parse_exactly_and_retain_formatting() {
+        let duration = SegmentDuration::try_from("9.009").unwrap();
+        assert_eq!("9.009", duration.to_string());
+        assert_eq!(9009, duration.as_millis());
+        assert_eq!(9.009, duration.as_secs_f64());
+        assert_eq!(
+            "0.050",
+            SegmentDuration::try_from("0.050").unwrap().to_string()
+        );
+        assert_eq!("12", SegmentDuration::try_from("12").unwrap().to_string());
+        assert_eq!(
+            Err(ParseSegmentDurationError::InvalidDecimal),
+            SegmentDuration::try_from("4.")
+        );
+        assert_eq!(
+            Err(ParseSegmentDurationError::InvalidDecimal),
+            SegmentDuration::try_from(".5")
+        );
+        assert_eq!(
+            Err(ParseSegmentDurationError::InvalidDecimal),
+            SegmentDuration::try_from("-1")
+        );
+        assert_eq!(
+            Err(ParseSegmentDurationError::TooPrecise),
+            SegmentDuration::try_from("0.12345678901234567890")
+        );
+    }
+
+    #[test]
+    fn segment_duration_should_compare_and_hash_by_value() {
+        let four = SegmentDuration::try_from("4").unwrap();
+        let four_with_decimals = SegmentDuration::try_from("4.000").unwrap();
+        assert_eq!(four, four_with_decimals);
+        assert_eq!(
+            1,
+            HashSet::from([four, four_with_decimals, SegmentDuration::from_millis(4000)]).len()
+        );
+        assert!(SegmentDuration::try_from("4.0001").unwrap() > four);
+        assert!(SegmentDuration::from_micros(3_999_999) < four);
+    }
+
+    #[test]
+    fn segment_duration_addition_should_be_exact() {
+        let durations =
+            ["3.003", "3.003", "3.003", "0.001"].map(|d| SegmentDuration::try_from(d).unwrap());
+        let total = durations.iter().sum::<SegmentDuration>();
+        assert_eq!(SegmentDuration::from_millis(9010), total);
+        assert_eq!("9.010", total.to_string());
+        let mut total = SegmentDuration::try_from("4.000").unwrap();
+        total += SegmentDuration::from_secs(2);
+        assert_eq!("6.000", total.to_string());
+        assert_eq!(
+            None,
+            SegmentDuration::from_secs(u64::MAX).checked_add(SegmentDuration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn segment_duration_should_round_for_target_duration() {
+        assert_eq!(4, SegmentDuration::try_from("4.4999").unwrap().round_secs());
+        assert_eq!(5, SegmentDuration::try_from("4.5").unwrap().round_secs());
+        assert!(
+            !SegmentDuration::try_from("10.4")
+                .unwrap()
+                .exceeds_target_duration(10)
+        );
+        assert!(
+            SegmentDuration::try_from("10.5")
+                .unwrap()
+                .exceeds_target_duration(10)
+        );
+    }
+
+    #[test]
+    fn segment_duration_from_secs_f64_should_use_shortest_representation() {
+        assert_eq!("0.1", SegmentDuration::from_secs_f64(0.1).to_string());
+        assert_eq!("6", SegmentDuration::from_secs_f64(6.0).to_string());
+        assert_eq!(SegmentDuration::ZERO, SegmentDuration::from_secs_f64(-1.0));
+        assert_eq!(
+            SegmentDuration::ZERO,
+            SegmentDuration::from_secs_f64(f64::NAN)
+        );
+        assert_eq!(
+            SegmentDuration::from_micros(1),
+            SegmentDuration::from_secs_f64(0.000000999999999999999)
+        );
+    }
+
+    #[test]
+    fn from_str_should_match_try_from() {
+        assert_eq!(
+            DecimalResolution::try_from("1920x1080"),
+            "1920x1080".parse::<DecimalResolution>()
+        );
+        assert_eq!(
+            Err(DecimalResolutionParseError::MissingSeparator),
+            "1920".parse::<DecimalResolution>()
+        );
+        assert_eq!(
+            Ok(DecimalIntegerRange {
+                length: 1024,
+                offset: Some(512)
+            }),
+            "1024@512".parse::<DecimalIntegerRange>()
+        );
+        assert_eq!(
+            Ok(date_time!(2025-06-04 T 13:50:42.148)),
+            "2025-06-04T13:50:42.148Z".parse::<DateTime>()
+        );
+        assert_eq!(
+            DateTime::try_from("2025-06-04T13:50:42.148Z"),
+            DateTime::try_from(b"2025-06-04T13:50:42.148Z".as_slice())
+        );
+    }
 
     #[test]
     fn type_enum() {
@@ -964,8 +1755,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn byte_range_helpers() {
+        let range = ByteRange {
+            length: 100,
+            offset: Some(u64::MAX - 10),
+        };
+        assert_eq!(None, range.end());
+        assert!(!range.contiguous_with(&ByteRange {
+            length: 1,
+            offset: Some(0)
+        }));
+        let range = ByteRange {
+            length: 100,
+            offset: None,
+        };
+        assert_eq!(None, range.end());
+        assert_eq!(
+            ByteRange {
+                length: 100,
+                offset: Some(50)
+            },
+            range.with_implicit_offset(50)
+        );
+        assert_eq!(Some(150), range.with_implicit_offset(50).end());
+        assert_eq!(
+            ByteRange {
+                length: 100,
+                offset: Some(10)
+            },
+            ByteRange {
+                length: 100,
+                offset: Some(10)
+            }
+            .with_implicit_offset(50)
+        );
+    }
+
+    #[test]
+    fn attribute_value_date_time() {
+        let value = AttributeValue::Quoted("2025-06-03T17:56:42.123Z");
+        assert_eq!(
+            Ok(date_time!(2025-06-03 T 17:56:42.123)),
+            value.try_as_date_time(),
+        );
+        let value =
+            AttributeValue::Unquoted(UnquotedAttributeValue(b"2025-06-03T17:56:42.123-05:00"));
+        assert_eq!(
+            Ok(date_time!(2025-06-03 T 17:56:42.123 -05:00)),
+            value.try_as_date_time(),
+        );
+        assert!(
+            AttributeValue::Quoted("not a date")
+                .try_as_date_time()
+                .is_err()
+        );
+    }
+
     mod attribute_list {
         use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn iter_should_provide_attributes_before_error_and_then_finish() {
+            for (input, error) in [
+                (
+                    b"A=1,B=\"2\"=".as_slice(),
+                    AttributeListParsingError::UnexpectedCharacterAfterQuoteEnd,
+                ),
+                (
+                    b"A=1,B=\"2",
+                    AttributeListParsingError::EndOfLineWhileReadingQuotedValue,
+                ),
+                (b"A=1,B=", AttributeListParsingError::EmptyUnquotedValue),
+                (b"A=1,=2", AttributeListParsingError::EmptyAttributeName),
+            ] {
+                let mut iter = TagValue(input).attribute_list_iter();
+                assert_eq!(
+                    Some(Ok((
+                        "A",
+                        AttributeValue::Unquoted(UnquotedAttributeValue(b"1"))
+                    ))),
+                    iter.next()
+                );
+                assert_eq!(Some(Err(error)), iter.next());
+                assert_eq!(None, iter.next());
+                assert_eq!(None, iter.next());
+            }
+        }
+
+        #[test]
+        fn iter_should_error_on_empty_value() {
+            let mut iter = TagValue(b"").attribute_list_iter();
+            assert_eq!(
+                Some(Err(
+                    AttributeListParsingError::EndOfLineWhileReadingAttributeName
+                )),
+                iter.next()
+            );
+            assert_eq!(None, iter.next());
+        }
+
+        #[test]
+        fn iter_should_end_after_last_attribute() {
+            let mut iter = TagValue(b"A=\"1\",B=2").attribute_list_iter();
+            assert_eq!(Some(Ok(("A", AttributeValue::Quoted("1")))), iter.next());
+            assert_eq!(
+                Some(Ok((
+                    "B",
+                    AttributeValue::Unquoted(UnquotedAttributeValue(b"2"))
+                ))),
+                iter.next()
+            );
+            assert_eq!(None, iter.next());
+        }
 
         macro_rules! unquoted_value_test {
             (TagValue is $tag_value:literal $($name_lit:literal=$val:literal expects $exp:literal from $method:ident)+) => {
@@ -986,6 +1889,14 @@ mod tests {
                         )+
                     ]
                 );
+                assert_eq!(
+                    value.try_as_attribute_map().expect("should be valid map").iter().collect::<Vec<_>>(),
+                    vec![
+                        $(
+                            ($name_lit, AttributeValue::Unquoted(UnquotedAttributeValue($val))),
+                        )+
+                    ]
+                );
                 $(
                     assert_eq!(Ok($exp), UnquotedAttributeValue($val).$method());
                 )+
@@ -1011,9 +1922,54 @@ mod tests {
                         )+
                     ]
                 );
+                assert_eq!(
+                    value.try_as_attribute_map().expect("should be valid map").iter().collect::<Vec<_>>(),
+                    vec![
+                        $(
+                            ($name_lit, AttributeValue::Quoted($exp)),
+                        )+
+                    ]
+                );
             };
         }
 
+        #[test]
+        fn attribute_map_should_spill_beyond_inline_capacity() {
+            let input = (0..20)
+                .map(|i| format!("A{i}={i}"))
+                .collect::<Vec<_>>()
+                .join(",");
+            let map = TagValue(input.as_bytes())
+                .try_as_attribute_map()
+                .expect("should be valid map");
+            assert_eq!(20, map.len());
+            assert_eq!(
+                (0..20).map(|i| format!("A{i}")).collect::<Vec<_>>(),
+                map.iter()
+                    .map(|(name, _)| name.to_string())
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                Some(AttributeValue::Unquoted(UnquotedAttributeValue(b"18"))),
+                map.get("A18")
+            );
+            assert!(map.contains("A3"));
+            assert!(!map.contains("A20"));
+            assert_eq!(20, map.into_iter().count());
+        }
+
+        #[test]
+        fn attribute_map_get_should_return_last_duplicate() {
+            let map = TagValue(b"A=1,B=2,A=3")
+                .try_as_attribute_map()
+                .expect("should be valid map");
+            assert_eq!(
+                Some(AttributeValue::Unquoted(UnquotedAttributeValue(b"3"))),
+                map.get("A")
+            );
+            assert_eq!(3, map.len());
+        }
+
         mod decimal_integer {
             use super::*;
             use pretty_assertions::assert_eq;
@@ -1,3 +1,4 @@
+#[cfg(feature = "hls-tags")]
 use std::borrow::Cow;
 
 use crate::{
@@ -7,6 +8,7 @@ use crate::{
 };
 use memchr::{memchr, memchr3};
 
+#[cfg(feature = "hls-tags")]
 pub trait AsStaticCow {
     fn as_cow(&self) -> Cow<'static, str>;
 }
@@ -28,6 +30,13 @@ pub fn split_on_new_line<'a>(bytes: &'a [u8]) -> ParsedByteSlice<'a, &'a [u8]> {
     }
 }
 
+// Formats the line (up to the first new line) for a `Display` implementation, matching the bytes
+// that the `Writer` would write (minus the new line).
+pub(crate) fn fmt_line(bytes: &[u8], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&String::from_utf8_lossy(split_on_new_line(bytes).parsed))
+}
+
+#[allow(unsafe_code)]
 pub(crate) fn str_from(bytes: &[u8]) -> &str {
     unsafe {
         // SAFETY: The input for bytes is always &str in this project, and I only break on single
@@ -36,6 +45,14 @@ pub(crate) fn str_from(bytes: &[u8]) -> &str {
     }
 }
 
+#[cfg(feature = "hls-tags")]
+// The 64-bit FNV-1a hash, used where a stable (and cheap) hash of some bytes is needed.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
 pub fn parse_date_time_bytes<'a>(
     input: &'a [u8],
 ) -> Result<ParsedByteSlice<'a, DateTime>, DateTimeSyntaxError> {
@@ -0,0 +1,1260 @@
+//! Opt-in validation of playlists against rules of the HLS specification.
+//!
+//! The library is deliberately lenient when parsing: each tag is validated on its own (see
+//! [`crate::error::ValidationError`]), but rules that span several lines of a playlist are not
+//! enforced, so that playlists that are slightly out of spec can still be read (and fixed). This
+//! module provides a [`Validator`] that checks some of those rules over a sequence of lines, and
+//! reports any violations as [`Diagnostic`]s with a [`Severity`].
+//!
+//! The rules checked can be configured by [`ValidationRule`], both to disable rules and to change
+//! the severity with which violations are reported.
+//!
+//! For example:
+//! ```
+//! # use quick_m3u8::validation::{Severity, ValidationRule, Validator, Violation};
+//! const PLAYLIST: &str = r#"#EXTM3U
+//! #EXT-X-TARGETDURATION:4
+//! #EXTINF:4.5
+//! 0.ts
+//! #EXT-X-DATERANGE:ID="ad",START-DATE="2025-01-01T00:00:00Z",DURATION=30
+//! #EXT-X-DATERANGE:ID="ad",START-DATE="2025-01-01T00:00:00Z",DURATION=15
+//! "#;
+//!
+//! let diagnostics = Validator::new()
+//!     .with_severity(ValidationRule::TargetDuration, Severity::Warning)
+//!     .validate_playlist(PLAYLIST);
+//! assert_eq!(3, diagnostics.len());
+//! assert_eq!(Severity::Warning, diagnostics[0].severity);
+//! assert_eq!(
+//!     Violation::SegmentExceedsTargetDuration { duration: 4.5, target_duration: 4 },
+//!     diagnostics[0].violation
+//! );
+//! assert_eq!(
+//!     vec![ValidationRule::TargetDuration, ValidationRule::Version, ValidationRule::DaterangeId],
+//!     diagnostics.iter().map(|d| d.rule()).collect::<Vec<_>>()
+//! );
+//! ```
+
+use crate::{
+    HlsLine, Reader,
+    config::ParsingOptions,
+    date::DateTime,
+    error::ReaderIoError,
+    tag::{
+        CustomTag, KnownTag, SegmentDuration,
+        hls::{self, Daterange, InstreamId, Method},
+    },
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{Display, Formatter},
+    io::BufRead,
+};
+
+/// Validates playlists against a configurable set of [`ValidationRule`]s.
+///
+/// By default, all rules are checked, and each violation is reported with the default severity of
+/// the violation (see [`Violation::default_severity`]).
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Validator {
+    disabled: Vec<ValidationRule>,
+    severities: HashMap<ValidationRule, Severity>,
+}
+
+impl Validator {
+    /// Creates a validator that checks all rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Disables the rule, such that violations of it are not reported.
+    pub fn without_rule(mut self, rule: ValidationRule) -> Self {
+        if !self.disabled.contains(&rule) {
+            self.disabled.push(rule);
+        }
+        self
+    }
+
+    /// Reports all violations of the rule with the given severity (rather than the default severity
+    /// of each violation).
+    pub fn with_severity(mut self, rule: ValidationRule, severity: Severity) -> Self {
+        self.severities.insert(rule, severity);
+        self
+    }
+
+    /// Indicates whether the rule is checked.
+    pub fn is_enabled(&self, rule: ValidationRule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+
+    /// Validates the lines of a playlist.
+    ///
+    /// The [`Diagnostic::line_number`] is the 1-based position of the line within the sequence
+    /// (which is the line number in the playlist when every line was read without error, and
+    /// without comment attachment). Lines that were not parsed as known HLS tags are not checked.
+    /// The diagnostics are provided in line order.
+    pub fn validate<'a, 'b, Custom>(
+        &self,
+        lines: impl IntoIterator<Item = &'b HlsLine<'a, Custom>>,
+    ) -> Vec<Diagnostic>
+    where
+        'a: 'b,
+        Custom: CustomTag<'a> + 'b,
+    {
+        let mut checker = Checker::default();
+        for (index, line) in lines.into_iter().enumerate() {
+            checker.check(index + 1, line);
+        }
+        self.finish(checker)
+    }
+
+    /// Validates the playlist, reading it with [`ParsingOptions::default`].
+    ///
+    /// Unlike [`Self::validate`], the [`Diagnostic::line_number`] is always the line number in the
+    /// playlist (lines that cannot be parsed are skipped).
+    pub fn validate_playlist(&self, playlist: &str) -> Vec<Diagnostic> {
+        let mut reader = Reader::from_str(playlist, ParsingOptions::default());
+        let mut checker = Checker::default();
+        loop {
+            let line_number = reader.position().line;
+            match reader.read_line() {
+                Ok(Some(line)) => checker.check(line_number, &line),
+                Ok(None) => break,
+                Err(_) => (),
+            }
+        }
+        self.finish(checker)
+    }
+
+    /// Creates a [`StreamingValidator`] that checks the same rules (with the same severities) as
+    /// lines are observed, without holding the playlist in memory.
+    pub fn streaming(self) -> StreamingValidator {
+        StreamingValidator::new(self)
+    }
+
+    /// Validates the playlist as it is read from the [`BufRead`], calling `on_diagnostic` with
+    /// each diagnostic as soon as it is known.
+    ///
+    /// This uses a [`StreamingValidator`], and so memory use does not grow with the size of the
+    /// playlist (see [`StreamingValidator`] for how this affects the diagnostics). As with
+    /// [`Self::validate_playlist`], the [`Diagnostic::line_number`] is the line number in the
+    /// playlist, and lines that cannot be parsed are skipped. An error is only returned when the
+    /// [`BufRead`] fails.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::validation::{Validator, Violation};
+    /// # use std::io::BufReader;
+    /// let file: &[u8] = b"#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXTINF:6\n0.ts\n#EXTINF:4\n1.ts\n";
+    /// // This would typically be a `BufReader<File>`.
+    /// let mut diagnostics = Vec::new();
+    /// Validator::new().validate_reader(BufReader::new(file), |d| diagnostics.push(d))?;
+    /// assert_eq!(1, diagnostics.len());
+    /// assert_eq!(3, diagnostics[0].line_number);
+    /// assert_eq!(
+    ///     Violation::SegmentExceedsTargetDuration { duration: 6.0, target_duration: 4 },
+    ///     diagnostics[0].violation
+    /// );
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn validate_reader(
+        &self,
+        input: impl BufRead,
+        mut on_diagnostic: impl FnMut(Diagnostic),
+    ) -> std::io::Result<()> {
+        let mut reader = Reader::from_reader(input, ParsingOptions::default());
+        let mut validator = self.clone().streaming();
+        loop {
+            let line_number = reader.position().line;
+            match reader.read_line() {
+                Ok(Some(line)) => validator
+                    .check(line_number, &line)
+                    .into_iter()
+                    .for_each(&mut on_diagnostic),
+                Ok(None) => break,
+                Err(ReaderIoError::Io(error)) => return Err(error),
+                Err(_) => (),
+            }
+        }
+        validator.finish().into_iter().for_each(on_diagnostic);
+        Ok(())
+    }
+
+    fn finish(&self, checker: Checker) -> Vec<Diagnostic> {
+        let mut diagnostics = checker
+            .finish()
+            .into_iter()
+            .filter_map(|(line_number, violation)| self.diagnostic(line_number, violation))
+            .collect::<Vec<_>>();
+        diagnostics.sort_by_key(|diagnostic| diagnostic.line_number);
+        diagnostics
+    }
+
+    // The diagnostic for the violation, or `None` when its rule is disabled.
+    fn diagnostic(&self, line_number: usize, violation: Violation) -> Option<Diagnostic> {
+        if !self.is_enabled(violation.rule()) {
+            return None;
+        }
+        Some(Diagnostic {
+            line_number,
+            severity: self
+                .severities
+                .get(&violation.rule())
+                .copied()
+                .unwrap_or_else(|| violation.default_severity()),
+            violation,
+        })
+    }
+}
+
+/// Validates a playlist line by line, providing diagnostics as soon as they are known, while only
+/// keeping the state needed by each rule (rather than the playlist itself).
+///
+/// This is created by [`Validator::streaming`] (or used via [`Validator::validate_reader`]), and is
+/// intended for playlists that are too large to hold in memory, such as long-running event
+/// playlists. Each line is passed to [`Self::check`], which returns the diagnostics found on that
+/// line, and [`Self::finish`] returns any diagnostics that could only be determined once the whole
+/// playlist was seen.
+///
+/// The memory used does not grow with the size of the playlist, and so some rules are checked
+/// slightly differently to the [`Validator`]:
+/// * [`ValidationRule::TargetDuration`]: segments that precede the `EXT-X-TARGETDURATION` are not
+///   remembered; only the longest of them is checked (and reported) once the target duration is
+///   known.
+/// * [`ValidationRule::Version`]: violations are reported on the line that uses the feature when
+///   the `EXT-X-VERSION` has already been seen, otherwise when the `EXT-X-VERSION` is seen (or from
+///   [`Self::finish`] when there is none). Whether an `EXT-X-MAP` is in an I-frame only playlist
+///   is judged by whether `EXT-X-I-FRAMES-ONLY` came before it.
+/// * [`ValidationRule::DaterangeId`]: only the attributes of the most recent
+///   [`Self::DEFAULT_DATERANGE_CAPACITY`] IDs are remembered (configurable via
+///   [`Self::with_daterange_capacity`]), and so conflicts with older date ranges are not reported.
+///
+/// The diagnostics returned by [`Self::check`] are those of the line checked, except for those
+/// reported late as described above; as such, diagnostics are not always in line order.
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{Reader, config::ParsingOptions};
+/// # use quick_m3u8::validation::{Validator, ValidationRule, Violation};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-VERSION:3
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MAP:URI="init.mp4"
+/// #EXTINF:4.5
+/// 0.mp4
+/// "#;
+///
+/// let mut validator = Validator::new().streaming();
+/// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+/// let mut diagnostics = Vec::new();
+/// loop {
+///     let line_number = reader.position().line;
+///     match reader.read_line() {
+///         Ok(Some(line)) => diagnostics.extend(validator.check(line_number, &line)),
+///         Ok(None) => break,
+///         Err(_) => (),
+///     }
+/// }
+/// diagnostics.extend(validator.finish());
+///
+/// assert_eq!(
+///     vec![(4, ValidationRule::Version), (5, ValidationRule::TargetDuration)],
+///     diagnostics.iter().map(|d| (d.line_number, d.rule())).collect::<Vec<_>>()
+/// );
+/// ```
+#[derive(Debug)]
+pub struct StreamingValidator {
+    validator: Validator,
+    target_duration: Option<u64>,
+    // The longest segment seen before the target duration was known.
+    longest_early_segment: Option<(usize, SegmentDuration)>,
+    versions: VersionInference,
+    reported_features: Vec<VersionedFeature>,
+    key_map: KeyMapOrder,
+    dateranges: DaterangeIds,
+}
+
+impl StreamingValidator {
+    /// The number of `EXT-X-DATERANGE` IDs remembered by default (see
+    /// [`Self::with_daterange_capacity`]).
+    pub const DEFAULT_DATERANGE_CAPACITY: usize = 1024;
+
+    /// Creates a streaming validator that checks the rules configured on the [`Validator`].
+    pub fn new(validator: Validator) -> Self {
+        Self {
+            validator,
+            target_duration: None,
+            longest_early_segment: None,
+            versions: VersionInference::default(),
+            reported_features: Vec::new(),
+            key_map: KeyMapOrder::default(),
+            dateranges: DaterangeIds::with_capacity(Self::DEFAULT_DATERANGE_CAPACITY),
+        }
+    }
+
+    /// Sets the number of `EXT-X-DATERANGE` IDs remembered for checking
+    /// [`ValidationRule::DaterangeId`], which bounds the memory used by the rule.
+    ///
+    /// When a date range with a new ID is seen and the capacity is reached, the ID that was first
+    /// seen earliest is forgotten. This should be set before any lines are checked.
+    pub fn with_daterange_capacity(mut self, capacity: usize) -> Self {
+        self.dateranges = DaterangeIds::with_capacity(capacity);
+        self
+    }
+
+    /// Checks a line of the playlist, where `line_number` is the 1-based position of the line
+    /// (used as the [`Diagnostic::line_number`]), and returns the diagnostics that became known.
+    pub fn check<'a, Custom>(
+        &mut self,
+        line_number: usize,
+        line: &HlsLine<'a, Custom>,
+    ) -> Vec<Diagnostic>
+    where
+        Custom: CustomTag<'a>,
+    {
+        let mut violations = Vec::new();
+        self.versions.observe(line_number, line);
+        if let Some(violation) = self.key_map.check(line) {
+            violations.push((line_number, violation));
+        }
+        if let HlsLine::KnownTag(KnownTag::Hls(tag)) = line {
+            match tag {
+                hls::Tag::Targetduration(tag) => {
+                    let target_duration = tag.target_duration();
+                    self.target_duration = Some(target_duration);
+                    if let Some((early_line_number, duration)) = self.longest_early_segment.take()
+                        && let Some(violation) =
+                            target_duration_violation(duration, target_duration)
+                    {
+                        violations.push((early_line_number, violation));
+                    }
+                }
+                hls::Tag::Inf(tag) => {
+                    let duration = tag.segment_duration();
+                    match self.target_duration {
+                        Some(target_duration) => violations.extend(
+                            target_duration_violation(duration, target_duration)
+                                .map(|violation| (line_number, violation)),
+                        ),
+                        None => {
+                            if self
+                                .longest_early_segment
+                                .is_none_or(|(_, longest)| duration > longest)
+                            {
+                                self.longest_early_segment = Some((line_number, duration));
+                            }
+                        }
+                    }
+                }
+                hls::Tag::Daterange(daterange) => violations.extend(
+                    self.dateranges
+                        .check(line_number, daterange)
+                        .map(|violation| (line_number, violation)),
+                ),
+                _ => (),
+            }
+        }
+        if self.versions.declared_version().is_some() {
+            violations.extend(self.unreported_version_violations());
+        }
+        self.diagnostics(violations)
+    }
+
+    /// Returns the diagnostics that could only be determined once the whole playlist was checked.
+    pub fn finish(mut self) -> Vec<Diagnostic> {
+        let violations = self.unreported_version_violations();
+        self.diagnostics(violations)
+    }
+
+    fn unreported_version_violations(&mut self) -> Vec<(usize, Violation)> {
+        // The version required by EXT-X-MAP changes once EXT-X-I-FRAMES-ONLY is seen, but it is
+        // the same use of the feature, and so is only reported once.
+        let normalize = |feature| match feature {
+            VersionedFeature::MapInIFramesOnly => VersionedFeature::Map,
+            feature => feature,
+        };
+        let violations = self
+            .versions
+            .violations()
+            .filter(|(_, violation)| match violation {
+                Violation::VersionTooLow { feature, .. } => {
+                    !self.reported_features.contains(&normalize(*feature))
+                }
+                _ => true,
+            })
+            .collect::<Vec<_>>();
+        for (_, violation) in &violations {
+            if let Violation::VersionTooLow { feature, .. } = violation {
+                self.reported_features.push(normalize(*feature));
+            }
+        }
+        violations
+    }
+
+    fn diagnostics(&self, mut violations: Vec<(usize, Violation)>) -> Vec<Diagnostic> {
+        violations.sort_by_key(|(line_number, _)| *line_number);
+        violations
+            .into_iter()
+            .filter_map(|(line_number, violation)| {
+                self.validator.diagnostic(line_number, violation)
+            })
+            .collect()
+    }
+}
+
+/// A violation found by the [`Validator`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    /// The 1-based line number of the offending line (see [`Validator::validate`]).
+    pub line_number: usize,
+    /// The severity with which the violation is reported.
+    pub severity: Severity,
+    /// The violation found.
+    pub violation: Violation,
+}
+
+impl Diagnostic {
+    /// The rule that was violated.
+    pub fn rule(&self) -> ValidationRule {
+        self.violation.rule()
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: {}: {}",
+            self.line_number, self.severity, self.violation
+        )
+    }
+}
+
+/// How severe a [`Diagnostic`] is.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum Severity {
+    /// The playlist is likely to play, but may not behave as intended.
+    Warning,
+    /// The playlist breaks a requirement of the specification.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A rule checked by the [`Validator`] (see [`Violation`] for how each rule can be violated).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ValidationRule {
+    /// The `EXTINF` duration of each segment, rounded to the nearest integer, must be less than or
+    /// equal to the `EXT-X-TARGETDURATION`.
+    TargetDuration,
+    /// The `EXT-X-VERSION` must be at least the version required by the features used in the
+    /// playlist (a playlist without `EXT-X-VERSION` is version 1).
+    Version,
+    /// The `EXT-X-KEY` that applies to an `EXT-X-MAP` must be consistent with the encryption of the
+    /// Media Initialization Section.
+    KeyMapOrder,
+    /// `EXT-X-DATERANGE` tags with the same `ID` must have the same value for any attribute that
+    /// appears in both.
+    DaterangeId,
+}
+
+/// A violation of a [`ValidationRule`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum Violation {
+    /// The segment duration (rounded to the nearest integer) exceeds the target duration.
+    SegmentExceedsTargetDuration {
+        /// The duration of the segment.
+        duration: f64,
+        /// The target duration of the playlist.
+        target_duration: u64,
+    },
+    /// A feature is used that requires a higher `EXT-X-VERSION` than the one declared.
+    VersionTooLow {
+        /// The version declared (or `None` when there is no `EXT-X-VERSION`).
+        declared: Option<u64>,
+        /// The feature used.
+        feature: VersionedFeature,
+    },
+    /// An `EXT-X-MAP` follows an `EXT-X-KEY` with `METHOD=AES-128` that has no `IV` attribute
+    /// (which is required, as the Media Sequence Number cannot be used as the IV of the Media
+    /// Initialization Section).
+    MapKeyWithoutIv,
+    /// An `EXT-X-KEY` with `METHOD=AES-128` follows an `EXT-X-MAP` before any segment, and so the
+    /// Media Initialization Section is not encrypted while its segments are (which usually means
+    /// that the tags are in the wrong order).
+    KeyAfterMap,
+    /// An `EXT-X-DATERANGE` has a different value for an attribute than an earlier
+    /// `EXT-X-DATERANGE` with the same `ID`.
+    ConflictingDaterange {
+        /// The `ID` of the date ranges.
+        id: String,
+        /// The name of the attribute that differs.
+        attribute: String,
+        /// The line number of the earlier `EXT-X-DATERANGE` on which the attribute was first given.
+        first_line_number: usize,
+    },
+}
+
+impl Violation {
+    /// The rule that was violated.
+    pub fn rule(&self) -> ValidationRule {
+        match self {
+            Self::SegmentExceedsTargetDuration { .. } => ValidationRule::TargetDuration,
+            Self::VersionTooLow { .. } => ValidationRule::Version,
+            Self::MapKeyWithoutIv | Self::KeyAfterMap => ValidationRule::KeyMapOrder,
+            Self::ConflictingDaterange { .. } => ValidationRule::DaterangeId,
+        }
+    }
+
+    /// The severity with which the violation is reported unless configured otherwise (see
+    /// [`Validator::with_severity`]). This is [`Severity::Error`] for requirements of the
+    /// specification, and [`Severity::Warning`] otherwise.
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            Self::KeyAfterMap => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SegmentExceedsTargetDuration {
+                duration,
+                target_duration,
+            } => write!(
+                f,
+                "segment duration of {duration}s exceeds the target duration of {target_duration}s"
+            ),
+            Self::VersionTooLow { declared, feature } => write!(
+                f,
+                "{feature} requires version {} but the playlist is version {}",
+                feature.required_version(),
+                declared.unwrap_or(1)
+            ),
+            Self::MapKeyWithoutIv => {
+                write!(f, "EXT-X-KEY that applies to EXT-X-MAP must have an IV")
+            }
+            Self::KeyAfterMap => write!(f, "EXT-X-KEY does not apply to the preceding EXT-X-MAP"),
+            Self::ConflictingDaterange {
+                id,
+                attribute,
+                first_line_number,
+            } => write!(
+                f,
+                "{attribute} of EXT-X-DATERANGE with ID {id} differs from line {first_line_number}"
+            ),
+        }
+    }
+}
+
+/// A feature that requires a minimum `EXT-X-VERSION` (see [`Violation::VersionTooLow`]).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum VersionedFeature {
+    /// The `IV` attribute of `EXT-X-KEY`.
+    KeyIv,
+    /// A floating-point `EXTINF` duration.
+    FloatingPointDuration,
+    /// The `EXT-X-BYTERANGE` tag.
+    Byterange,
+    /// The `EXT-X-I-FRAMES-ONLY` tag.
+    IFramesOnly,
+    /// The `KEYFORMAT` or `KEYFORMATVERSIONS` attributes of `EXT-X-KEY`.
+    Keyformat,
+    /// The `EXT-X-MAP` tag in an I-frame only playlist.
+    MapInIFramesOnly,
+    /// The `EXT-X-MAP` tag in a playlist that is not I-frame only.
+    Map,
+    /// A `SERVICE` value of the `INSTREAM-ID` attribute of `EXT-X-MEDIA`.
+    InstreamIdService,
+    /// Variable substitution (the `EXT-X-DEFINE` tag).
+    VariableSubstitution,
+    /// The `EXT-X-SKIP` tag.
+    Skip,
+}
+
+impl VersionedFeature {
+    /// The minimum `EXT-X-VERSION` required to use the feature.
+    pub fn required_version(&self) -> u64 {
+        match self {
+            Self::KeyIv => 2,
+            Self::FloatingPointDuration => 3,
+            Self::Byterange | Self::IFramesOnly => 4,
+            Self::Keyformat | Self::MapInIFramesOnly => 5,
+            Self::Map => 6,
+            Self::InstreamIdService => 7,
+            Self::VariableSubstitution => 8,
+            Self::Skip => 9,
+        }
+    }
+}
+
+impl Display for VersionedFeature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeyIv => write!(f, "IV attribute of EXT-X-KEY"),
+            Self::FloatingPointDuration => write!(f, "floating-point EXTINF duration"),
+            Self::Byterange => write!(f, "EXT-X-BYTERANGE"),
+            Self::IFramesOnly => write!(f, "EXT-X-I-FRAMES-ONLY"),
+            Self::Keyformat => write!(f, "KEYFORMAT attribute of EXT-X-KEY"),
+            Self::MapInIFramesOnly => write!(f, "EXT-X-MAP in an I-frame only playlist"),
+            Self::Map => write!(f, "EXT-X-MAP"),
+            Self::InstreamIdService => write!(f, "SERVICE value of INSTREAM-ID"),
+            Self::VariableSubstitution => write!(f, "EXT-X-DEFINE"),
+            Self::Skip => write!(f, "EXT-X-SKIP"),
+        }
+    }
+}
+
+/// Infers the minimum `EXT-X-VERSION` required by the features used in a playlist, as the lines of
+/// the playlist are observed.
+///
+/// A playlist without an `EXT-X-VERSION` tag is version 1, though many playlists in the wild use
+/// later features without declaring a version. The inferred version is the minimum version that
+/// the playlist should declare given the [`VersionedFeature`]s seen so far, and so is useful both
+/// for deciding how to treat such playlists and for re-writing them with the correct version. This
+/// is the same inference as is used to check [`ValidationRule::Version`], and may be collected
+/// while reading via [`crate::Reader::with_version_inference`].
+///
+/// For example:
+/// ```
+/// # use quick_m3u8::{Reader, config::ParsingOptions};
+/// # use quick_m3u8::validation::{VersionInference, VersionedFeature, Violation};
+/// const PLAYLIST: &str = r#"#EXTM3U
+/// #EXT-X-VERSION:3
+/// #EXT-X-TARGETDURATION:4
+/// #EXT-X-MAP:URI="init.mp4"
+/// #EXTINF:3.003
+/// 0.mp4
+/// "#;
+///
+/// let mut inference = VersionInference::new();
+/// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+/// loop {
+///     let line_number = reader.position().line;
+///     match reader.read_line() {
+///         Ok(Some(line)) => inference.observe(line_number, &line),
+///         Ok(None) => break,
+///         Err(_) => (),
+///     }
+/// }
+///
+/// assert_eq!(Some(3), inference.declared_version());
+/// assert_eq!(6, inference.inferred_version());
+/// assert!(inference.is_conflicting());
+/// let diagnostics = inference.diagnostics();
+/// assert_eq!(1, diagnostics.len());
+/// assert_eq!(4, diagnostics[0].line_number);
+/// assert_eq!(
+///     Violation::VersionTooLow { declared: Some(3), feature: VersionedFeature::Map },
+///     diagnostics[0].violation
+/// );
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct VersionInference {
+    declared: Option<u64>,
+    // The line number of the first use of each feature.
+    features: HashMap<VersionedFeature, usize>,
+    i_frames_only: bool,
+    // The line number of the first EXT-X-MAP, which requires a version that depends on whether the
+    // playlist is I-frame only (and EXT-X-I-FRAMES-ONLY may appear after it).
+    first_map: Option<usize>,
+}
+
+impl VersionInference {
+    /// Creates an inference that has not observed any lines.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Observes a line of the playlist, where `line_number` is the 1-based position of the line
+    /// (used as the [`Diagnostic::line_number`]). Lines that are not known HLS tags are ignored.
+    pub fn observe<'a, Custom>(&mut self, line_number: usize, line: &HlsLine<'a, Custom>)
+    where
+        Custom: CustomTag<'a>,
+    {
+        let HlsLine::KnownTag(KnownTag::Hls(tag)) = line else {
+            return;
+        };
+        match tag {
+            hls::Tag::Version(tag) => self.declared = Some(tag.version()),
+            hls::Tag::Inf(tag) if tag.segment_duration().to_string().contains('.') => {
+                self.use_feature(VersionedFeature::FloatingPointDuration, line_number)
+            }
+            hls::Tag::Byterange(_) => self.use_feature(VersionedFeature::Byterange, line_number),
+            hls::Tag::IFramesOnly(_) => {
+                self.i_frames_only = true;
+                self.use_feature(VersionedFeature::IFramesOnly, line_number);
+            }
+            hls::Tag::Define(_) => {
+                self.use_feature(VersionedFeature::VariableSubstitution, line_number)
+            }
+            hls::Tag::Skip(_) => self.use_feature(VersionedFeature::Skip, line_number),
+            hls::Tag::Key(key) => {
+                if key.iv().is_some() {
+                    self.use_feature(VersionedFeature::KeyIv, line_number);
+                }
+                if key.keyformat() != "identity" || key.keyformatversions().is_some() {
+                    self.use_feature(VersionedFeature::Keyformat, line_number);
+                }
+            }
+            hls::Tag::Map(_) => {
+                self.first_map.get_or_insert(line_number);
+            }
+            hls::Tag::Media(media) => {
+                if let Some(Some(InstreamId::Cea708(_))) =
+                    media.instream_id().map(|id| id.known().copied())
+                {
+                    self.use_feature(VersionedFeature::InstreamIdService, line_number);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// The version declared by the `EXT-X-VERSION` tag (`None` when no tag has been observed).
+    pub fn declared_version(&self) -> Option<u64> {
+        self.declared
+    }
+
+    /// The minimum version required by the features observed so far (`1` when no such feature has
+    /// been observed).
+    pub fn inferred_version(&self) -> u64 {
+        self.features()
+            .iter()
+            .map(|(feature, _)| feature.required_version())
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// The features that have been observed, along with the line number of their first use, in
+    /// ascending order of the version that they require.
+    pub fn features(&self) -> Vec<(VersionedFeature, usize)> {
+        let mut features = self
+            .features
+            .iter()
+            .map(|(feature, line_number)| (*feature, *line_number))
+            .collect::<Vec<_>>();
+        if let Some(line_number) = self.first_map {
+            let feature = if self.i_frames_only {
+                VersionedFeature::MapInIFramesOnly
+            } else {
+                VersionedFeature::Map
+            };
+            features.push((feature, line_number));
+        }
+        features.sort();
+        features
+    }
+
+    /// Indicates whether the declared version (or version 1, when there is no `EXT-X-VERSION`) is
+    /// lower than the inferred version.
+    pub fn is_conflicting(&self) -> bool {
+        self.declared.unwrap_or(1) < self.inferred_version()
+    }
+
+    /// A [`Violation::VersionTooLow`] diagnostic (with the default severity) for each feature that
+    /// requires a higher version than the declared version, in line order.
+    ///
+    /// Unlike the [`Validator`], this may be checked at any point while reading; however, the
+    /// `EXT-X-VERSION` tag may appear anywhere in the playlist, and so the diagnostics are only
+    /// final once the whole playlist has been observed.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = self
+            .violations()
+            .map(|(line_number, violation)| Diagnostic {
+                line_number,
+                severity: violation.default_severity(),
+                violation,
+            })
+            .collect::<Vec<_>>();
+        diagnostics.sort_by_key(|diagnostic| diagnostic.line_number);
+        diagnostics
+    }
+
+    fn use_feature(&mut self, feature: VersionedFeature, line_number: usize) {
+        self.features.entry(feature).or_insert(line_number);
+    }
+
+    fn violations(&self) -> impl Iterator<Item = (usize, Violation)> + '_ {
+        let declared = self.declared.unwrap_or(1);
+        self.features()
+            .into_iter()
+            .filter(move |(feature, _)| feature.required_version() > declared)
+            .map(|(feature, line_number)| {
+                (
+                    line_number,
+                    Violation::VersionTooLow {
+                        declared: self.declared,
+                        feature,
+                    },
+                )
+            })
+    }
+}
+
+// The state carried across lines while checking a playlist, where violations that can only be
+// known once the whole playlist has been seen (such as those depending on EXT-X-VERSION, which may
+// appear anywhere in the playlist) are determined in `finish`.
+#[derive(Default)]
+struct Checker {
+    violations: Vec<(usize, Violation)>,
+    target_duration: Option<u64>,
+    // Segment durations that are checked once the target duration is known.
+    durations: Vec<(usize, SegmentDuration)>,
+    versions: VersionInference,
+    key_map: KeyMapOrder,
+    dateranges: DaterangeIds,
+}
+
+impl Checker {
+    fn check<'a, Custom>(&mut self, line_number: usize, line: &HlsLine<'a, Custom>)
+    where
+        Custom: CustomTag<'a>,
+    {
+        self.versions.observe(line_number, line);
+        if let Some(violation) = self.key_map.check(line) {
+            self.violations.push((line_number, violation));
+        }
+        let HlsLine::KnownTag(KnownTag::Hls(tag)) = line else {
+            return;
+        };
+        match tag {
+            hls::Tag::Targetduration(tag) => self.target_duration = Some(tag.target_duration()),
+            hls::Tag::Inf(tag) => self.durations.push((line_number, tag.segment_duration())),
+            hls::Tag::Daterange(daterange) => {
+                if let Some(violation) = self.dateranges.check(line_number, daterange) {
+                    self.violations.push((line_number, violation));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn finish(mut self) -> Vec<(usize, Violation)> {
+        // The target duration is usually declared before the segments, but it applies to the
+        // playlist as a whole.
+        if let Some(target_duration) = self.target_duration {
+            for (line_number, duration) in self.durations {
+                if let Some(violation) = target_duration_violation(duration, target_duration) {
+                    self.violations.push((line_number, violation));
+                }
+            }
+        }
+        self.violations.extend(self.versions.violations());
+        self.violations
+    }
+}
+
+fn target_duration_violation(duration: SegmentDuration, target_duration: u64) -> Option<Violation> {
+    duration.exceeds_target_duration(target_duration).then(|| {
+        Violation::SegmentExceedsTargetDuration {
+            duration: duration.as_secs_f64(),
+            target_duration,
+        }
+    })
+}
+
+// The state needed to check `ValidationRule::KeyMapOrder`, which only depends on the most recent
+// EXT-X-KEY and EXT-X-MAP.
+#[derive(Debug, Default)]
+struct KeyMapOrder {
+    aes_128_key_without_iv: bool,
+    map_without_segment: bool,
+}
+
+impl KeyMapOrder {
+    fn check<'a, Custom>(&mut self, line: &HlsLine<'a, Custom>) -> Option<Violation>
+    where
+        Custom: CustomTag<'a>,
+    {
+        match line {
+            HlsLine::Uri(_) => self.map_without_segment = false,
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Key(key))) => {
+                let aes_128 = key.method().known() == Some(&Method::Aes128);
+                let key_after_map = aes_128 && self.map_without_segment;
+                self.aes_128_key_without_iv = aes_128 && key.iv().is_none();
+                if key_after_map {
+                    return Some(Violation::KeyAfterMap);
+                }
+            }
+            HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Map(_))) => {
+                self.map_without_segment = true;
+                if self.aes_128_key_without_iv {
+                    return Some(Violation::MapKeyWithoutIv);
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+}
+
+// The attributes of the EXT-X-DATERANGE tags seen for each ID, needed to check
+// `ValidationRule::DaterangeId`. When a capacity is set, only that many IDs are remembered, and the
+// ID that was first seen earliest is forgotten to make room for a new one.
+#[derive(Debug, Default)]
+struct DaterangeIds {
+    attributes: HashMap<String, DaterangeAttributes>,
+    // The IDs in the order that they were first seen (only maintained when there is a capacity).
+    order: VecDeque<String>,
+    capacity: Option<usize>,
+}
+
+impl DaterangeIds {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: Some(capacity),
+            ..Self::default()
+        }
+    }
+
+    fn check(&mut self, line_number: usize, daterange: &Daterange) -> Option<Violation> {
+        let attributes = DaterangeAttributes::new(line_number, daterange);
+        if let Some(seen) = self.attributes.get_mut(daterange.id()) {
+            let conflict = seen.conflict(&attributes);
+            seen.merge(attributes);
+            return conflict.map(|(attribute, first_line_number)| {
+                Violation::ConflictingDaterange {
+                    id: daterange.id().to_string(),
+                    attribute,
+                    first_line_number,
+                }
+            });
+        }
+        if let Some(capacity) = self.capacity {
+            if capacity == 0 {
+                return None;
+            }
+            if self.order.len() >= capacity
+                && let Some(oldest) = self.order.pop_front()
+            {
+                self.attributes.remove(&oldest);
+            }
+            self.order.push_back(daterange.id().to_string());
+        }
+        self.attributes
+            .insert(daterange.id().to_string(), attributes);
+        None
+    }
+}
+
+// The attributes of an EXT-X-DATERANGE that must be consistent between tags with the same ID, along
+// with the line number of the tag that each attribute was first seen on.
+#[derive(Debug, PartialEq)]
+struct DaterangeAttributes(Vec<(String, DaterangeValue, usize)>);
+
+// Dates are compared as instants (so that the same time written with a different timezone offset is
+// not a conflict), and all other values are compared as strings.
+#[derive(Debug, PartialEq)]
+enum DaterangeValue {
+    Date(DateTime),
+    Other(String),
+}
+
+impl DaterangeAttributes {
+    fn new(line_number: usize, daterange: &Daterange<'_>) -> Self {
+        let mut attributes = Vec::new();
+        let mut push = |name: &str, value: Option<DaterangeValue>| {
+            if let Some(value) = value {
+                attributes.push((name.to_string(), value, line_number));
+            }
+        };
+        let other = |value: String| DaterangeValue::Other(value);
+        push("CLASS", daterange.class().map(str::to_string).map(other));
+        push(
+            "START-DATE",
+            daterange.start_date().map(DaterangeValue::Date),
+        );
+        push("CUE", daterange.cue().map(|c| other(c.to_string())));
+        push("END-DATE", daterange.end_date().map(DaterangeValue::Date));
+        push(
+            "DURATION",
+            daterange.duration().map(|d| other(d.to_string())),
+        );
+        push(
+            "PLANNED-DURATION",
+            daterange.planned_duration().map(|d| other(d.to_string())),
+        );
+        let mut extension_attributes = daterange
+            .extension_attributes()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), other(format!("{value:?}")), line_number))
+            .collect::<Vec<_>>();
+        extension_attributes.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+        attributes.extend(extension_attributes);
+        Self(attributes)
+    }
+
+    // The name of the first attribute that appears in both but with a different value, along with
+    // the line number that it was first seen on.
+    fn conflict(&self, other: &Self) -> Option<(String, usize)> {
+        self.0.iter().find_map(|(name, value, line_number)| {
+            other
+                .0
+                .iter()
+                .find(|(other_name, _, _)| other_name == name)
+                .filter(|(_, other_value, _)| other_value != value)
+                .map(|_| (name.clone(), *line_number))
+        })
+    }
+
+    // Adds the attributes that have not been seen before (the value first seen is kept for those
+    // that have).
+    fn merge(&mut self, other: Self) {
+        for attribute in other.0 {
+            if !self.0.iter().any(|(name, _, _)| *name == attribute.0) {
+                self.0.push(attribute);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn key_and_map_order_should_be_checked() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:6\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"k1\"\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXTINF:4\n",
+            "0.mp4\n",
+            "#EXT-X-MAP:URI=\"init2.mp4\"\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"k2\",IV=0x0123456789ABCDEF0123456789ABCDEF\n",
+            "#EXTINF:4\n",
+            "1.mp4\n",
+        );
+        let diagnostics = Validator::new().validate_playlist(PLAYLIST);
+        assert_eq!(
+            vec![
+                Diagnostic {
+                    line_number: 5,
+                    severity: Severity::Error,
+                    violation: Violation::MapKeyWithoutIv,
+                },
+                // The first EXT-X-KEY still applies to the second EXT-X-MAP.
+                Diagnostic {
+                    line_number: 8,
+                    severity: Severity::Error,
+                    violation: Violation::MapKeyWithoutIv,
+                },
+                Diagnostic {
+                    line_number: 9,
+                    severity: Severity::Warning,
+                    violation: Violation::KeyAfterMap,
+                },
+            ],
+            diagnostics
+        );
+        assert_eq!(
+            Vec::<Diagnostic>::new(),
+            Validator::new()
+                .without_rule(ValidationRule::KeyMapOrder)
+                .validate_playlist(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn version_should_account_for_i_frames_only() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-VERSION:5\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXTINF:4\n",
+            "0.mp4\n",
+        );
+        let diagnostics = Validator::new().validate_playlist(PLAYLIST);
+        assert_eq!(
+            vec![Diagnostic {
+                line_number: 4,
+                severity: Severity::Error,
+                violation: Violation::VersionTooLow {
+                    declared: Some(5),
+                    feature: VersionedFeature::Map,
+                },
+            }],
+            diagnostics
+        );
+        assert_eq!(
+            "line 4: error: EXT-X-MAP requires version 6 but the playlist is version 5",
+            diagnostics[0].to_string()
+        );
+        let i_frames_only = PLAYLIST.replace("#EXTINF:4\n", "#EXT-X-I-FRAMES-ONLY\n#EXTINF:4\n");
+        assert!(
+            Validator::new()
+                .validate_playlist(&i_frames_only)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn dateranges_with_the_same_id_and_consistent_attributes_should_be_allowed() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\",X-A=\"1\"\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\",DURATION=5\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\",X-A=\"2\"\n",
+        );
+        let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+        let (lines, _) = reader.read_until_error();
+        assert_eq!(
+            vec![Diagnostic {
+                line_number: 4,
+                severity: Severity::Error,
+                violation: Violation::ConflictingDaterange {
+                    id: "a".to_string(),
+                    attribute: "X-A".to_string(),
+                    first_line_number: 2,
+                },
+            }],
+            Validator::new().validate(&lines)
+        );
+    }
+
+    #[test]
+    fn dateranges_should_be_checked_against_attributes_of_all_earlier_tags_with_the_same_id() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\"\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\",END-DATE=\"2025-01-01T00:00:10Z\"\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\",END-DATE=\"2025-01-01T00:00:20Z\"\n",
+        );
+        assert_eq!(
+            vec![Diagnostic {
+                line_number: 4,
+                severity: Severity::Error,
+                violation: Violation::ConflictingDaterange {
+                    id: "a".to_string(),
+                    attribute: "END-DATE".to_string(),
+                    first_line_number: 3,
+                },
+            }],
+            Validator::new().validate_playlist(PLAYLIST)
+        );
+    }
+
+    #[test]
+    fn daterange_dates_should_be_compared_as_instants() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\"\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T01:00:00.000+01:00\"\n",
+        );
+        assert_eq!(
+            Vec::<Diagnostic>::new(),
+            Validator::new().validate_playlist(PLAYLIST)
+        );
+    }
+
+    fn validate_streaming(validator: StreamingValidator, playlist: &str) -> Vec<Diagnostic> {
+        let mut validator = validator;
+        let mut reader = Reader::from_str(playlist, ParsingOptions::default());
+        let mut diagnostics = Vec::new();
+        loop {
+            let line_number = reader.position().line;
+            match reader.read_line() {
+                Ok(Some(line)) => diagnostics.extend(validator.check(line_number, &line)),
+                Ok(None) => break,
+                Err(_) => (),
+            }
+        }
+        diagnostics.extend(validator.finish());
+        diagnostics
+    }
+
+    #[test]
+    fn streaming_should_report_the_same_key_and_map_order_diagnostics() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-VERSION:6\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"k1\"\n",
+            "#EXT-X-MAP:URI=\"init.mp4\"\n",
+            "#EXTINF:4\n",
+            "0.mp4\n",
+            "#EXT-X-MAP:URI=\"init2.mp4\"\n",
+            "#EXT-X-KEY:METHOD=AES-128,URI=\"k2\",IV=0x0123456789ABCDEF0123456789ABCDEF\n",
+            "#EXTINF:5\n",
+            "1.mp4\n",
+        );
+        let validator =
+            Validator::new().with_severity(ValidationRule::KeyMapOrder, Severity::Warning);
+        assert_eq!(
+            validator.validate_playlist(PLAYLIST),
+            validate_streaming(validator.clone().streaming(), PLAYLIST)
+        );
+        let mut diagnostics = Vec::new();
+        validator
+            .validate_reader(PLAYLIST.as_bytes(), |d| diagnostics.push(d))
+            .unwrap();
+        assert_eq!(validator.validate_playlist(PLAYLIST), diagnostics);
+    }
+
+    #[test]
+    fn streaming_should_report_longest_segment_before_target_duration_and_late_version() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXTINF:5\n",
+            "0.ts\n",
+            "#EXTINF:6.5\n",
+            "1.ts\n",
+            "#EXT-X-TARGETDURATION:4\n",
+            "#EXTINF:4.5\n",
+            "2.ts\n",
+        );
+        let diagnostics = validate_streaming(Validator::new().streaming(), PLAYLIST);
+        assert_eq!(
+            vec![
+                (4, ValidationRule::TargetDuration),
+                (7, ValidationRule::TargetDuration),
+                // Without an EXT-X-VERSION, the version is only reported when finishing.
+                (4, ValidationRule::Version),
+            ],
+            diagnostics
+                .iter()
+                .map(|d| (d.line_number, d.rule()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn streaming_should_only_remember_daterange_ids_up_to_capacity() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\",DURATION=5\n",
+            "#EXT-X-DATERANGE:ID=\"b\",START-DATE=\"2025-01-01T00:00:00Z\",DURATION=5\n",
+            "#EXT-X-DATERANGE:ID=\"b\",START-DATE=\"2025-01-01T00:00:00Z\",DURATION=6\n",
+            "#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2025-01-01T00:00:00Z\",DURATION=6\n",
+        );
+        let line_numbers = |validator| {
+            validate_streaming(validator, PLAYLIST)
+                .iter()
+                .map(|d| d.line_number)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![4, 5], line_numbers(Validator::new().streaming()));
+        assert_eq!(
+            vec![4],
+            line_numbers(Validator::new().streaming().with_daterange_capacity(1))
+        );
+        assert_eq!(
+            Vec::<usize>::new(),
+            line_numbers(Validator::new().streaming().with_daterange_capacity(0))
+        );
+    }
+}
@@ -0,0 +1,234 @@
+#[cfg(feature = "hls-tags")]
+use crate::tag::hls;
+use crate::{
+    HlsLine,
+    tag::{CustomTag, CustomTagAccess, KnownTag, NoCustomTag, UnknownTag},
+};
+use std::borrow::Cow;
+
+macro_rules! hls_line_visitor {
+    ($($variant:ident => $method:ident($tag_type:ty)),+ $(,)?) => {
+        /// A visitor over [`HlsLine`] values, intended to remove the boilerplate of matching on
+        /// nested enums when transforming a playlist.
+        ///
+        /// Transforming a playlist with the [`crate::Reader`] and [`crate::Writer`] typically
+        /// involves a `match` on patterns such as
+        /// `HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(tag)))`, where only a couple of the cases
+        /// are interesting, and all others need to be passed through unchanged. This trait provides
+        /// a method per tag type (as well as for the other line types), each of which has a default
+        /// implementation that passes the line through unchanged, so that an implementation only
+        /// needs to override the methods that it is interested in. Lines are dispatched to the
+        /// relevant method via [`Self::visit_line`].
+        ///
+        /// Each method returns an `Option`, where `None` indicates that the line should be removed.
+        ///
+        /// For example, prefixing the title of every `EXTINF` and removing all comments:
+        /// ```
+        /// # use quick_m3u8::{HlsLine, HlsLineVisitor, Reader, Writer};
+        /// # use quick_m3u8::config::ParsingOptions;
+        /// # use quick_m3u8::tag::hls::Inf;
+        /// # use std::borrow::Cow;
+        /// struct Retitle;
+        /// impl<'a> HlsLineVisitor<'a> for Retitle {
+        ///     fn visit_inf(&mut self, mut tag: Inf<'a>) -> Option<HlsLine<'a>> {
+        ///         tag.set_title(format!("Episode 1: {}", tag.title()));
+        ///         Some(HlsLine::from(tag))
+        ///     }
+        ///
+        ///     fn visit_comment(&mut self, _: Cow<'a, str>) -> Option<HlsLine<'a>> {
+        ///         None
+        ///     }
+        /// }
+        ///
+        /// const PLAYLIST: &str = "#EXTM3U\n# a comment\n#EXTINF:4,Intro\nsegment.ts\n";
+        /// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+        /// let mut writer = Writer::new(Vec::new());
+        /// let mut visitor = Retitle;
+        /// while let Some(line) = reader.read_line()? {
+        ///     if let Some(line) = visitor.visit_line(line) {
+        ///         writer.write_line(line)?;
+        ///     }
+        /// }
+        /// assert_eq!(
+        ///     "#EXTM3U\n#EXTINF:4,Episode 1: Intro\nsegment.ts\n",
+        ///     String::from_utf8_lossy(&writer.into_inner())
+        /// );
+        /// # Ok::<(), Box<dyn std::error::Error>>(())
+        /// ```
+        pub trait HlsLineVisitor<'a, Custom = NoCustomTag>
+        where
+            Custom: CustomTag<'a>,
+        {
+            /// Dispatches the line to the relevant `visit_*` method.
+            ///
+            /// This should not usually need to be overridden.
+            fn visit_line(&mut self, line: HlsLine<'a, Custom>) -> Option<HlsLine<'a, Custom>> {
+                match line {
+                    #[cfg(feature = "hls-tags")]
+                    HlsLine::KnownTag(KnownTag::Hls(tag)) => self.visit_hls_tag(tag),
+                    HlsLine::KnownTag(KnownTag::Custom(tag)) => self.visit_custom_tag(tag),
+                    HlsLine::UnknownTag(tag) => self.visit_unknown_tag(tag),
+                    HlsLine::Comment(comment) => self.visit_comment(comment),
+                    HlsLine::Uri(uri) => self.visit_uri(uri),
+                    HlsLine::Blank => self.visit_blank(),
+                    HlsLine::Whitespace(whitespace) => self.visit_whitespace(whitespace),
+                }
+            }
+
+            /// Dispatches the tag to the relevant tag specific `visit_*` method.
+            ///
+            /// This can be overridden to handle all HLS tags in one place; however, in that case,
+            /// none of the tag specific methods will be called (unless done so by the override).
+            #[cfg(feature = "hls-tags")]
+            fn visit_hls_tag(&mut self, tag: hls::Tag<'a>) -> Option<HlsLine<'a, Custom>> {
+                match tag {
+                    $(hls::Tag::$variant(tag) => self.$method(tag),)+
+                }
+            }
+
+            $(
+                #[doc = concat!(
+                    "Visit a [`hls::", stringify!($variant), "`] tag. ",
+                    "By default the tag is passed through unchanged."
+                )]
+                #[cfg(feature = "hls-tags")]
+                fn $method(&mut self, tag: $tag_type) -> Option<HlsLine<'a, Custom>> {
+                    Some(HlsLine::from(tag))
+                }
+            )+
+
+            /// Visit a custom tag (see [`CustomTag`]). By default the tag is passed through
+            /// unchanged.
+            fn visit_custom_tag(
+                &mut self,
+                tag: CustomTagAccess<'a, Custom>,
+            ) -> Option<HlsLine<'a, Custom>> {
+                Some(HlsLine::from(tag))
+            }
+
+            /// Visit an unknown tag (see [`UnknownTag`]). By default the tag is passed through
+            /// unchanged.
+            fn visit_unknown_tag(&mut self, tag: UnknownTag<'a>) -> Option<HlsLine<'a, Custom>> {
+                Some(HlsLine::from(tag))
+            }
+
+            /// Visit a comment line. By default the line is passed through unchanged.
+            fn visit_comment(&mut self, comment: Cow<'a, str>) -> Option<HlsLine<'a, Custom>> {
+                Some(HlsLine::Comment(comment))
+            }
+
+            /// Visit a URI line. By default the line is passed through unchanged.
+            fn visit_uri(&mut self, uri: Cow<'a, str>) -> Option<HlsLine<'a, Custom>> {
+                Some(HlsLine::Uri(uri))
+            }
+
+            /// Visit a blank line. By default the line is passed through unchanged.
+            fn visit_blank(&mut self) -> Option<HlsLine<'a, Custom>> {
+                Some(HlsLine::Blank)
+            }
+
+            /// Visit a whitespace only line. By default the line is passed through unchanged.
+            fn visit_whitespace(
+                &mut self,
+                whitespace: Cow<'a, str>,
+            ) -> Option<HlsLine<'a, Custom>> {
+                Some(HlsLine::Whitespace(whitespace))
+            }
+        }
+    };
+}
+
+hls_line_visitor!(
+    M3u => visit_m3u(hls::M3u),
+    Version => visit_version(hls::Version<'a>),
+    IndependentSegments => visit_independent_segments(hls::IndependentSegments),
+    Start => visit_start(hls::Start<'a>),
+    Define => visit_define(hls::Define<'a>),
+    Targetduration => visit_targetduration(hls::Targetduration<'a>),
+    MediaSequence => visit_media_sequence(hls::MediaSequence<'a>),
+    DiscontinuitySequence => visit_discontinuity_sequence(hls::DiscontinuitySequence<'a>),
+    Endlist => visit_endlist(hls::Endlist),
+    PlaylistType => visit_playlist_type(hls::PlaylistType),
+    IFramesOnly => visit_i_frames_only(hls::IFramesOnly),
+    PartInf => visit_part_inf(hls::PartInf<'a>),
+    ServerControl => visit_server_control(hls::ServerControl<'a>),
+    Inf => visit_inf(hls::Inf<'a>),
+    Byterange => visit_byterange(hls::Byterange<'a>),
+    Discontinuity => visit_discontinuity(hls::Discontinuity),
+    Key => visit_key(hls::Key<'a>),
+    Map => visit_map(hls::Map<'a>),
+    ProgramDateTime => visit_program_date_time(hls::ProgramDateTime<'a>),
+    Gap => visit_gap(hls::Gap),
+    Bitrate => visit_bitrate(hls::Bitrate<'a>),
+    Part => visit_part(hls::Part<'a>),
+    Daterange => visit_daterange(hls::Daterange<'a>),
+    Skip => visit_skip(hls::Skip<'a>),
+    PreloadHint => visit_preload_hint(hls::PreloadHint<'a>),
+    RenditionReport => visit_rendition_report(hls::RenditionReport<'a>),
+    Media => visit_media(hls::Media<'a>),
+    StreamInf => visit_stream_inf(hls::StreamInf<'a>),
+    IFrameStreamInf => visit_i_frame_stream_inf(hls::IFrameStreamInf<'a>),
+    SessionData => visit_session_data(hls::SessionData<'a>),
+    SessionKey => visit_session_key(hls::SessionKey<'a>),
+    ContentSteering => visit_content_steering(hls::ContentSteering<'a>),
+);
+
+#[cfg(all(test, feature = "hls-tags"))]
+mod tests {
+    use super::*;
+    use crate::{Reader, config::ParsingOptions};
+    use pretty_assertions::assert_eq;
+
+    #[derive(Default)]
+    struct VariantFilter {
+        max_bandwidth: u64,
+        removing_uri: bool,
+    }
+    impl<'a> HlsLineVisitor<'a> for VariantFilter {
+        fn visit_stream_inf(&mut self, tag: hls::StreamInf<'a>) -> Option<HlsLine<'a>> {
+            if tag.bandwidth() > self.max_bandwidth {
+                self.removing_uri = true;
+                None
+            } else {
+                Some(HlsLine::from(tag))
+            }
+        }
+
+        fn visit_uri(&mut self, uri: Cow<'a, str>) -> Option<HlsLine<'a>> {
+            if std::mem::take(&mut self.removing_uri) {
+                None
+            } else {
+                Some(HlsLine::Uri(uri))
+            }
+        }
+    }
+
+    #[test]
+    fn visitor_should_pass_through_lines_that_are_not_overridden() {
+        const PLAYLIST: &str = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-INDEPENDENT-SEGMENTS\n",
+            "# comment\n",
+            "\n",
+            "#EXT-X-UNKNOWN:1\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=1000000\n",
+            "low.m3u8\n",
+            "#EXT-X-STREAM-INF:BANDWIDTH=9000000\n",
+            "high.m3u8\n",
+        );
+        let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+        let mut visitor = VariantFilter {
+            max_bandwidth: 5000000,
+            ..Default::default()
+        };
+        let mut lines = Vec::new();
+        while let Ok(Some(line)) = reader.read_line() {
+            lines.extend(visitor.visit_line(line));
+        }
+        let mut expected = Reader::from_str(PLAYLIST, ParsingOptions::default());
+        let expected = (0..7)
+            .map(|_| expected.read_line().unwrap().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(expected, lines);
+    }
+}
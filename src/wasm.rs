@@ -0,0 +1,132 @@
+//! Bindings for using the library from JavaScript (via `wasm-bindgen`).
+//!
+//! This is intended for tooling that runs in the browser (such as playlist debuggers), and so the
+//! surface is deliberately small: [`parse_lines`] provides the lines of a playlist as JavaScript
+//! objects, and [`write_lines`] writes such objects back into a playlist. The objects have the same
+//! shape as the `serde` representation of [`HlsLine`] (see the `serde` feature), for example:
+//! ```json
+//! [
+//!   { "KnownTag": { "Hls": { "M3u": null } } },
+//!   { "KnownTag": { "Hls": { "Inf": { "duration": 4.0, "title": "" } } } },
+//!   { "Uri": "0.ts" },
+//!   { "Error": { "line": "#EXT", "message": "..." } }
+//! ]
+//! ```
+//!
+//! Lines that cannot be parsed are provided as `Error` objects (with the line and a description of
+//! the problem) so that each object corresponds to a line of the playlist. `Error` objects are not
+//! accepted by [`write_lines`].
+//!
+//! The bindings are plain Rust functions, and so can be called from Rust too, though they are only
+//! useful when compiled for `wasm32-unknown-unknown` (outside of WebAssembly the `JsValue` can not
+//! be constructed).
+
+use crate::{HlsLine, Reader, Writer, config::ParsingOptions};
+use wasm_bindgen::prelude::{JsError, JsValue, wasm_bindgen};
+
+/// Parses the playlist, providing an array with an object describing each line.
+///
+/// Exported to JavaScript as `parseLines(playlist: string): object[]`.
+#[wasm_bindgen(js_name = parseLines)]
+pub fn parse_lines(playlist: &str) -> Result<JsValue, JsError> {
+    js_sys::JSON::parse(&lines_to_json(playlist)?).map_err(|_| JsError::new("invalid JSON"))
+}
+
+/// Writes the array of objects describing lines (as provided by [`parse_lines`]) as a playlist.
+///
+/// Exported to JavaScript as `writeLines(lines: object[]): string`.
+#[wasm_bindgen(js_name = writeLines)]
+pub fn write_lines(lines: JsValue) -> Result<String, JsError> {
+    let json = js_sys::JSON::stringify(&lines)
+        .map_err(|_| JsError::new("lines cannot be converted to JSON"))?;
+    json_to_playlist(&String::from(json)).map_err(|error| JsError::new(&error.to_string()))
+}
+
+#[derive(serde::Serialize)]
+#[allow(clippy::large_enum_variant)] // See comment on crate::tag::known::Tag.
+enum LineObject<'a> {
+    Error {
+        line: &'a str,
+        message: String,
+    },
+    #[serde(untagged)]
+    Line(HlsLine<'a>),
+}
+
+fn lines_to_json(playlist: &str) -> Result<String, serde_json::Error> {
+    let mut reader = Reader::from_str(playlist, ParsingOptions::default());
+    let mut lines = Vec::new();
+    loop {
+        match reader.read_line() {
+            Ok(Some(line)) => lines.push(LineObject::Line(line)),
+            Ok(None) => break,
+            Err(error) => lines.push(LineObject::Error {
+                line: error.errored_line,
+                message: error.error.to_string(),
+            }),
+        }
+    }
+    serde_json::to_string(&lines)
+}
+
+// Lines are deserialized from JSON (rather than directly from the `JsValue`) as the tags borrow
+// from the data that they are deserialized from.
+fn json_to_playlist(json: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let lines: Vec<HlsLine> = serde_json::from_str(json)?;
+    let mut writer = Writer::new(Vec::new());
+    for line in lines {
+        writer.write_line(line)?;
+    }
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = concat!(
+        "#EXTM3U\n",
+        "#EXT\n",
+        "#EXTINF:4,\n",
+        "# comment\n",
+        "0.ts\n",
+    );
+
+    #[test]
+    fn lines_should_be_described_as_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&lines_to_json(PLAYLIST).unwrap()).unwrap();
+        assert_eq!(
+            serde_json::json!([
+                { "KnownTag": { "Hls": { "M3u": null } } },
+                {
+                    "Error": {
+                        "line": "#EXT",
+                        "message": json[1]["Error"]["message"],
+                    }
+                },
+                { "KnownTag": { "Hls": { "Inf": { "duration": 4.0, "title": "" } } } },
+                { "Comment": " comment" },
+                { "Uri": "0.ts" },
+            ]),
+            json
+        );
+        assert!(
+            json[1]["Error"]["message"]
+                .as_str()
+                .is_some_and(|m| !m.is_empty())
+        );
+    }
+
+    #[test]
+    fn lines_without_errors_should_write_back_to_playlist() {
+        let playlist = PLAYLIST.replace("#EXT\n", "");
+        let json = lines_to_json(&playlist).unwrap();
+        assert_eq!(
+            "#EXTM3U\n#EXTINF:4\n# comment\n0.ts\n",
+            json_to_playlist(&json).unwrap()
+        );
+        assert!(json_to_playlist(&lines_to_json(PLAYLIST).unwrap()).is_err());
+    }
+}
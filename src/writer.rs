@@ -1,10 +1,13 @@
 use crate::{
+    encoding::UTF_8_BOM,
+    error::{UnparsableTagError, UnparsableTagReason},
     line::HlsLine,
-    tag::{IntoInnerTag, WritableCustomTag},
+    tag::{AttributeValue, IntoInnerTag, KnownTag, TagValue, WritableCustomTag, hls},
+    tag_internal::splice::splice_line,
 };
 use std::{
     borrow::Cow,
-    io::{self, Write},
+    io::{self, BufWriter, Write},
 };
 
 /// A writer of HLS lines.
@@ -140,6 +143,13 @@ use std::{
 /// # Ok::<(), Box<dyn Error>>(())
 /// ```
 ///
+/// ## Configure the output
+///
+/// The line endings, whether the `#EXTM3U` header is written automatically, and when the underlying
+/// writer is flushed, can be configured via a [`WriterBuilder`]. The number of
+/// bytes written is available via [`Self::bytes_written`], so that the size of the payload can be
+/// accounted for without wrapping the underlying writer.
+///
 /// [9.4. Multivariant Playlist]: https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-18#section-9.4
 #[derive(Debug, Clone)]
 pub struct Writer<W>
@@ -148,6 +158,234 @@ where
 {
     /// underlying writer
     writer: W,
+    blank_lines: BlankLines,
+    line_ending: LineEnding,
+    m3u_header: bool,
+    line_flushing: bool,
+    has_written_line: bool,
+    bytes_written: u64,
+    verbatim_lines: u64,
+    reserialized_lines: u64,
+    value_validation: ValueValidation,
+    preserve_formatting: bool,
+    warnings: Vec<UnparsableTagError>,
+}
+
+/// The line ending that the [`Writer`] writes after each line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    /// `\n` (this is the default).
+    #[default]
+    Lf,
+    /// `\r\n`.
+    Crlf,
+}
+
+impl LineEnding {
+    /// The bytes of the line ending.
+    pub const fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            Self::Lf => b"\n",
+            Self::Crlf => b"\r\n",
+        }
+    }
+}
+
+/// A builder for a configured [`Writer`].
+///
+/// For example, writing a playlist for a streamed HTTP response body with CRLF line endings, where
+/// the `#EXTM3U` header is added if the first line written is not already the header:
+/// ```
+/// # use quick_m3u8::{HlsLine, LineEnding, WriterBuilder, tag::hls::Targetduration};
+/// let mut writer = WriterBuilder::new()
+///     .with_line_ending(LineEnding::Crlf)
+///     .with_m3u_header()
+///     .with_line_flushing()
+///     .build(Vec::new());
+/// writer.write_line(HlsLine::from(Targetduration::new(4)))?;
+/// writer.write_uri("segment.ts")?;
+/// assert_eq!(46, writer.bytes_written());
+/// assert_eq!(
+///     "#EXTM3U\r\n#EXT-X-TARGETDURATION:4\r\nsegment.ts\r\n",
+///     String::from_utf8(writer.into_inner())?
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WriterBuilder {
+    blank_lines: BlankLines,
+    line_ending: LineEnding,
+    m3u_header: bool,
+    line_flushing: bool,
+    buffer_capacity: Option<usize>,
+    value_validation: ValueValidation,
+    preserve_formatting: bool,
+}
+
+impl WriterBuilder {
+    /// Creates a new builder with the default configuration (which is the same as that of
+    /// [`Writer::new`]).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the `Writer` over the provided writer.
+    pub fn build<W>(&self, inner: W) -> Writer<W>
+    where
+        W: Write,
+    {
+        Writer {
+            writer: inner,
+            blank_lines: self.blank_lines,
+            line_ending: self.line_ending,
+            m3u_header: self.m3u_header,
+            line_flushing: self.line_flushing,
+            has_written_line: false,
+            bytes_written: 0,
+            verbatim_lines: 0,
+            reserialized_lines: 0,
+            value_validation: self.value_validation,
+            preserve_formatting: self.preserve_formatting,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Builds the `Writer` over a [`BufWriter`] wrapping the provided writer, using the capacity set
+    /// via [`Self::with_buffer_capacity`] (or the default capacity of the `BufWriter`).
+    ///
+    /// Note that the `BufWriter` must be flushed (e.g. via [`Writer::flush`]) once writing is done,
+    /// unless line flushing is enabled.
+    pub fn build_buffered<W>(&self, inner: W) -> Writer<BufWriter<W>>
+    where
+        W: Write,
+    {
+        let inner = match self.buffer_capacity {
+            Some(capacity) => BufWriter::with_capacity(capacity, inner),
+            None => BufWriter::new(inner),
+        };
+        self.build(inner)
+    }
+
+    /// Sets how blank and whitespace only lines are written (see [`BlankLines`]).
+    pub fn with_blank_lines(&mut self, blank_lines: BlankLines) -> &mut Self {
+        self.blank_lines = blank_lines;
+        self
+    }
+
+    /// Sets the line ending written after each line (see [`LineEnding`]).
+    pub fn with_line_ending(&mut self, line_ending: LineEnding) -> &mut Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Writes the `#EXTM3U` header before the first line, unless the first line is the header.
+    ///
+    /// A BOM written via [`Writer::write_bom`] is still written before the header.
+    pub fn with_m3u_header(&mut self) -> &mut Self {
+        self.m3u_header = true;
+        self
+    }
+
+    /// Flushes the underlying writer after each line is written, such that each line is made
+    /// available to the consumer as soon as it is written (e.g. when streaming a response body).
+    pub fn with_line_flushing(&mut self) -> &mut Self {
+        self.line_flushing = true;
+        self
+    }
+
+    /// Sets the capacity of the [`BufWriter`] used by [`Self::build_buffered`].
+    pub fn with_buffer_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.buffer_capacity = Some(capacity);
+        self
+    }
+
+    /// Sets how modified tags are validated before being written (see [`ValueValidation`]).
+    pub fn with_value_validation(&mut self, value_validation: ValueValidation) -> &mut Self {
+        self.value_validation = value_validation;
+        self
+    }
+
+    /// Writes tags that have been modified since being read with the formatting of the original
+    /// line preserved as much as possible (see [`Writer::with_preserved_formatting`]).
+    pub fn with_preserved_formatting(&mut self) -> &mut Self {
+        self.preserve_formatting = true;
+        self
+    }
+}
+
+/// How the [`Writer`] writes blank ([`HlsLine::Blank`]) and whitespace only
+/// ([`HlsLine::Whitespace`]) lines.
+///
+/// For example, stripping blank lines used as visual separators:
+/// ```
+/// # use quick_m3u8::{BlankLines, Reader, Writer, config::ParsingOptions};
+/// const PLAYLIST: &str = "#EXTM3U\n\n#EXTINF:4\nsegment.ts\n  \n";
+/// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+/// let mut writer = Writer::new(Vec::new()).with_blank_lines(BlankLines::Strip);
+/// while let Some(line) = reader.read_line()? {
+///     writer.write_line(line)?;
+/// }
+/// assert_eq!(
+///     "#EXTM3U\n#EXTINF:4\nsegment.ts\n",
+///     String::from_utf8_lossy(&writer.into_inner())
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlankLines {
+    /// Blank and whitespace only lines are written as they were found (this is the default).
+    #[default]
+    Preserve,
+    /// Whitespace only lines are written as blank lines (i.e. the whitespace is removed).
+    TrimWhitespace,
+    /// Blank and whitespace only lines are not written at all.
+    Strip,
+}
+
+/// How the [`Writer`] validates tags that have been modified (or constructed) before writing them.
+///
+/// The setters on the tags in [`crate::tag::hls`] do not validate the values provided, and so it is
+/// possible to set a value that breaks the rules of the attribute list, such that the line written
+/// would not be parsable when read back. For example, a quoted-string containing `"`, an
+/// enumerated-string containing whitespace, or any value containing a line break. When validation
+/// is enabled, each tag that was not written directly from the original input data is checked for
+/// these problems (tags that are unchanged since being read are always written as they were
+/// found). Note that a `,` within a quoted-string is valid, as the value is delimited by the
+/// quotes.
+///
+/// For example, rejecting a `METHOD` that is not a valid enumerated-string:
+/// ```
+/// # use quick_m3u8::{HlsLine, ValueValidation, WriterBuilder, tag::hls::Key};
+/// # use quick_m3u8::error::{UnparsableTagError, UnparsableTagReason};
+/// let mut writer = WriterBuilder::new()
+///     .with_value_validation(ValueValidation::Reject)
+///     .build(Vec::new());
+/// let mut key = Key::builder().with_method("AES-128").with_uri("key.bin").finish();
+/// key.set_method("AES 128");
+/// let error = writer.write_line(HlsLine::from(key)).unwrap_err();
+/// assert_eq!(std::io::ErrorKind::InvalidData, error.kind());
+/// assert_eq!(
+///     Some(&UnparsableTagError {
+///         tag_name: "-X-KEY".to_string(),
+///         reason: UnparsableTagReason::WhitespaceInUnquotedValue {
+///             attr_name: "METHOD".to_string()
+///         }
+///     }),
+///     error.get_ref().and_then(|e| e.downcast_ref::<UnparsableTagError>())
+/// );
+/// assert!(writer.into_inner().is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueValidation {
+    /// Tags are written without validation (this is the default).
+    #[default]
+    Disabled,
+    /// Tags are written regardless, but each problem found is recorded as a warning, available via
+    /// [`Writer::warnings`].
+    Warn,
+    /// Tags with a problem are not written, and instead an [`io::Error`] of kind
+    /// [`io::ErrorKind::InvalidData`] wrapping the [`UnparsableTagError`] is returned.
+    Reject,
 }
 
 impl<W> Writer<W>
@@ -156,7 +394,83 @@ where
 {
     /// Creates a `Writer` from a generic writer.
     pub const fn new(inner: W) -> Writer<W> {
-        Writer { writer: inner }
+        Writer {
+            writer: inner,
+            blank_lines: BlankLines::Preserve,
+            line_ending: LineEnding::Lf,
+            m3u_header: false,
+            line_flushing: false,
+            has_written_line: false,
+            bytes_written: 0,
+            verbatim_lines: 0,
+            reserialized_lines: 0,
+            value_validation: ValueValidation::Disabled,
+            preserve_formatting: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Sets how blank and whitespace only lines are written (see [`BlankLines`]).
+    pub const fn with_blank_lines(mut self, blank_lines: BlankLines) -> Self {
+        self.blank_lines = blank_lines;
+        self
+    }
+
+    /// Sets how modified tags are validated before being written (see [`ValueValidation`]).
+    pub const fn with_value_validation(mut self, value_validation: ValueValidation) -> Self {
+        self.value_validation = value_validation;
+        self
+    }
+
+    /// Writes tags that have been modified since being read with the formatting of the original
+    /// line preserved as much as possible.
+    ///
+    /// When a tag is modified, the line is recalculated from the values of the tag, which
+    /// normalizes the order of the attributes, the formatting of numbers, and drops any attributes
+    /// that the tag does not retain (e.g. unknown attributes). With this mode enabled, the line
+    /// written is instead the original line with only the changes spliced in: unchanged attributes
+    /// keep their original position and formatting, attributes that are not retained by the tag
+    /// are kept as they were found, removed attributes are dropped, and added attributes are
+    /// appended to the end of the attribute list. This keeps the difference between the input and
+    /// output playlists minimal (e.g. when making surgical edits to a playlist).
+    ///
+    /// Tags that were constructed (rather than read), and tags that cannot be spliced (e.g. a tag
+    /// with a single value that was changed), are written as recalculated.
+    ///
+    /// For example:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, Reader, Writer, config::ParsingOptions, tag::KnownTag};
+    /// # use quick_m3u8::tag::hls;
+    /// const LINE: &str = "#EXT-X-STREAM-INF:CODECS=\"avc1.64001f\",BANDWIDTH=1000000,X-CDN=a\n";
+    /// let mut reader = Reader::from_str(LINE, ParsingOptions::default());
+    /// let Some(HlsLine::KnownTag(KnownTag::Hls(hls::Tag::StreamInf(mut tag)))) =
+    ///     reader.read_line()?
+    /// else {
+    ///     unreachable!()
+    /// };
+    /// tag.set_bandwidth(2000000);
+    /// let mut writer = Writer::new(Vec::new()).with_preserved_formatting();
+    /// writer.write_line(HlsLine::from(tag))?;
+    /// assert_eq!(
+    ///     "#EXT-X-STREAM-INF:CODECS=\"avc1.64001f\",BANDWIDTH=2000000,X-CDN=a\n",
+    ///     String::from_utf8(writer.into_inner())?
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub const fn with_preserved_formatting(mut self) -> Self {
+        self.preserve_formatting = true;
+        self
+    }
+
+    /// The problems found in tags written so far when validating with [`ValueValidation::Warn`].
+    pub fn warnings(&self) -> &[UnparsableTagError] {
+        &self.warnings
+    }
+
+    /// Takes the problems found in tags written so far when validating with
+    /// [`ValueValidation::Warn`], leaving no warnings recorded.
+    pub fn take_warnings(&mut self) -> Vec<UnparsableTagError> {
+        std::mem::take(&mut self.warnings)
     }
 
     /// Consumes this `Writer`, returning the underlying writer.
@@ -174,6 +488,77 @@ where
         &self.writer
     }
 
+    /// The total number of bytes written by this `Writer` (not including any bytes written directly
+    /// to the underlying writer via [`Self::get_mut`]).
+    pub const fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// The number of lines written as they were provided (e.g. tags that have not been modified
+    /// since they were read are written as the line that they were read from, byte for byte).
+    ///
+    /// This includes all lines other than those counted by [`Self::reserialized_lines`], but does
+    /// not include blank lines that were stripped (see [`BlankLines::Strip`]), or the `#EXTM3U`
+    /// header added via [`WriterBuilder::with_m3u_header`].
+    ///
+    /// For example, checking how much of a playlist a proxy passed through untouched:
+    /// ```
+    /// # use quick_m3u8::{HlsLine, Reader, Writer, config::ParsingOptions, tag::{KnownTag, hls}};
+    /// const PLAYLIST: &str = "#EXTM3U\n#EXTINF:4,\na.ts\n#EXTINF:4,\nb.ts\n";
+    /// let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+    /// let mut writer = Writer::new(Vec::new());
+    /// while let Some(mut line) = reader.read_line()? {
+    ///     if let HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(inf))) = &mut line {
+    ///         inf.set_title("Intro");
+    ///     }
+    ///     writer.write_line(line)?;
+    /// }
+    /// assert_eq!(3, writer.verbatim_lines());
+    /// assert_eq!(2, writer.reserialized_lines());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub const fn verbatim_lines(&self) -> u64 {
+        self.verbatim_lines
+    }
+
+    /// The number of tags written with a line recalculated from their values, either because they
+    /// were modified since being read (see [`crate::tag::KnownTag::is_dirty`]), or because they
+    /// were written via [`Self::write_custom_tag`] (see [`Self::verbatim_lines`]).
+    pub const fn reserialized_lines(&self) -> u64 {
+        self.reserialized_lines
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Write a UTF-8 byte order mark (BOM) to the underlying writer. Returns the number of bytes
+    /// written.
+    ///
+    /// The HLS specification does not allow for a BOM, so this should only be used for byte-for-byte
+    /// stability with an input playlist that had one (see [`crate::Reader::has_bom`]), and it must
+    /// be written before any other line.
+    ///
+    /// Example:
+    /// ```
+    /// # use quick_m3u8::{Reader, Writer, config::ParsingOptions};
+    /// let input = "\u{FEFF}#EXTM3U\n";
+    /// let mut reader = Reader::from_str(input, ParsingOptions::default());
+    /// let mut writer = Writer::new(Vec::new());
+    /// if reader.has_bom() {
+    ///     writer.write_bom()?;
+    /// }
+    /// while let Some(line) = reader.read_line()? {
+    ///     writer.write_line(line)?;
+    /// }
+    /// assert_eq!(input.as_bytes(), writer.into_inner());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn write_bom(&mut self) -> io::Result<usize> {
+        self.write(&UTF_8_BOM)
+    }
+
     /// Write the `HlsLine` to the underlying writer. Returns the number of bytes consumed during
     /// writing or an `io::Error` from the underlying writer.
     ///
@@ -275,8 +660,10 @@ where
     where
         Custom: WritableCustomTag<'a>,
     {
-        let mut count = self.write(tag.into_inner().value())?;
-        count += self.write(b"\n")?;
+        let mut count = self.write_header_if_needed(false)?;
+        count += self.write(tag.into_inner().value())?;
+        count += self.end_line()?;
+        self.reserialized_lines += 1;
         Ok(count)
     }
 
@@ -365,18 +752,102 @@ where
     where
         Custom: WritableCustomTag<'a>,
     {
-        let mut count = 0usize;
+        let is_header = matches!(&line, HlsLine::KnownTag(tag) if is_m3u(tag))
+            || matches!(&line, HlsLine::UnknownTag(tag) if tag.name() == "M3U");
+        if matches!(line, HlsLine::Blank | HlsLine::Whitespace(_))
+            && self.blank_lines == BlankLines::Strip
+        {
+            return Ok(0);
+        }
+        if let HlsLine::KnownTag(t) = line {
+            return self.write_known_tag(t);
+        }
+        let mut count = self.write_header_if_needed(is_header)?;
         match line {
             HlsLine::Blank => (),
+            HlsLine::Whitespace(w) => {
+                if self.blank_lines == BlankLines::Preserve {
+                    count += self.write(w.as_bytes())?;
+                }
+            }
             HlsLine::Comment(c) => {
                 count += self.write(b"#")?;
                 count += self.write(c.as_bytes())?;
             }
             HlsLine::Uri(u) => count += self.write(u.as_bytes())?,
             HlsLine::UnknownTag(t) => count += self.write(t.as_bytes())?,
-            HlsLine::KnownTag(t) => count += self.write(t.into_inner().value())?,
+            HlsLine::KnownTag(_) => unreachable!("known tags are written above"),
+        };
+        count += self.end_line()?;
+        self.verbatim_lines += 1;
+        Ok(count)
+    }
+
+    fn write_known_tag<'a, Custom>(&mut self, tag: KnownTag<'a, Custom>) -> io::Result<usize>
+    where
+        Custom: WritableCustomTag<'a>,
+    {
+        let is_header = is_m3u(&tag);
+        let is_dirty = tag.is_dirty();
+        let inner = tag.into_inner();
+        let spliced = match inner.original_line {
+            Some(original) if self.preserve_formatting => splice_line(original, inner.value()),
+            _ => None,
         };
-        count += self.write(b"\n")?;
+        // Lines borrowed from the original input were parsable when read, so only lines that have
+        // been generated (including spliced lines) need validating.
+        if let (Cow::Owned(line), ValueValidation::Warn | ValueValidation::Reject) =
+            (&inner.output_line, self.value_validation)
+            && let Some(error) = validate_tag_line(spliced.as_deref().unwrap_or(line))
+        {
+            if self.value_validation == ValueValidation::Reject {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+            }
+            self.warnings.push(error);
+        }
+        let mut count = self.write_header_if_needed(is_header)?;
+        if let Some(comment) = inner.comment() {
+            count += self.write(b"#")?;
+            count += self.write(comment.as_bytes())?;
+            count += self.write(self.line_ending.as_bytes())?;
+        }
+        count += self.write(spliced.as_deref().unwrap_or(inner.value()))?;
+        count += self.end_line()?;
+        if is_dirty {
+            self.reserialized_lines += 1;
+        } else {
+            self.verbatim_lines += 1;
+        }
+        Ok(count)
+    }
+
+    // Writes the bytes as a line (appending a new line). Used for lines that have no `HlsLine`
+    // representation that can be constructed without parsing (e.g. non-HLS tags).
+    #[cfg(feature = "hls-tags")]
+    pub(crate) fn write_raw_line(&mut self, line: &[u8]) -> io::Result<usize> {
+        let mut count = self.write_header_if_needed(line == b"#EXTM3U")?;
+        count += self.write(line)?;
+        count += self.end_line()?;
+        self.verbatim_lines += 1;
+        Ok(count)
+    }
+
+    // Writes the `#EXTM3U` header if configured to and no line has been written yet (and the line
+    // about to be written is not itself the header).
+    fn write_header_if_needed(&mut self, is_header: bool) -> io::Result<usize> {
+        if std::mem::replace(&mut self.has_written_line, true) || !self.m3u_header || is_header {
+            return Ok(0);
+        }
+        let mut count = self.write(b"#EXTM3U")?;
+        count += self.write(self.line_ending.as_bytes())?;
+        Ok(count)
+    }
+
+    fn end_line(&mut self) -> io::Result<usize> {
+        let count = self.write(self.line_ending.as_bytes())?;
+        if self.line_flushing {
+            self.writer.flush()?;
+        }
         Ok(count)
     }
 
@@ -392,6 +863,7 @@ where
                 }
                 Ok(n) => {
                     count += n;
+                    self.bytes_written += n as u64;
                     buf = &buf[n..];
                 }
                 Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
@@ -402,9 +874,68 @@ where
     }
 }
 
+// Whether the tag is `EXTM3U`, which can only be a known tag when the HLS tags are defined.
+fn is_m3u<'a, Custom>(tag: &KnownTag<'a, Custom>) -> bool
+where
+    Custom: WritableCustomTag<'a>,
+{
+    #[cfg(feature = "hls-tags")]
+    return matches!(tag, KnownTag::Hls(hls::Tag::M3u(_)));
+    #[cfg(not(feature = "hls-tags"))]
+    return {
+        let _ = tag;
+        false
+    };
+}
+
+// Checks that a generated tag line would be parsed back as the same tag, returning the reason that
+// it would not be otherwise.
+pub(crate) fn validate_tag_line(line: &[u8]) -> Option<UnparsableTagError> {
+    let line = line.strip_prefix(b"#EXT").unwrap_or(line);
+    let (name, value) = match line.iter().position(|b| *b == b':') {
+        Some(i) => (&line[..i], Some(&line[(i + 1)..])),
+        None => (line, None),
+    };
+    let error = |reason| {
+        Some(UnparsableTagError {
+            tag_name: String::from_utf8_lossy(name).into_owned(),
+            reason,
+        })
+    };
+    if line.iter().any(|b| *b == b'\r' || *b == b'\n') {
+        return error(UnparsableTagReason::LineBreak);
+    }
+    let value = value?;
+    let has_attribute_list = std::str::from_utf8(name)
+        .ok()
+        .and_then(|name| hls::TagName::try_from(name).ok())
+        .is_some_and(|name| name.has_attribute_list());
+    if !has_attribute_list {
+        return None;
+    }
+    match TagValue(value).try_as_ordered_attribute_list() {
+        Err(e) => error(UnparsableTagReason::InvalidAttributeList(e)),
+        Ok(list) => list.into_iter().find_map(|(attr_name, value)| match value {
+            AttributeValue::Unquoted(v) if v.0.iter().any(u8::is_ascii_whitespace) => {
+                error(UnparsableTagReason::WhitespaceInUnquotedValue {
+                    attr_name: attr_name.to_string(),
+                })
+            }
+            _ => None,
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "hls-tags")]
+    use crate::{
+        Reader,
+        config::ParsingOptions,
+        error::AttributeListParsingError,
+        tag::hls::{self, Inf, M3u, MediaSequence, Targetduration, Version},
+    };
     use crate::{
         config::ParsingOptionsBuilder,
         date_time,
@@ -412,7 +943,6 @@ mod tests {
         tag::{
             CustomTag, DecimalResolution, UnknownTag, WritableAttributeValue, WritableTag,
             WritableTagValue,
-            hls::{self, Inf, M3u, MediaSequence, Targetduration, Version},
         },
     };
     use pretty_assertions::assert_eq;
@@ -598,6 +1128,7 @@ mod tests {
             .to_string()
     }
 
+    #[cfg(feature = "hls-tags")]
     #[test]
     fn writer_should_output_expected() {
         let mut writer = Writer::new(Vec::new());
@@ -640,6 +1171,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "hls-tags")]
     #[test]
     fn write_line_should_return_correct_byte_count() {
         let mut writer = Writer::new(Vec::new());
@@ -666,6 +1198,97 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn comment_attached_to_tag_should_be_written_on_the_preceding_line() {
+        let mut inf = Inf::new(6.0, String::new());
+        inf.set_comment("ad-slate");
+        let mut writer = Writer::new(Vec::new());
+        assert_eq!(
+            20, // 10 (#ad-slate\n) + 9 (#EXTINF:6) + 1 (\n) == 20
+            writer.write_line(HlsLine::from(inf)).unwrap()
+        );
+        assert_eq!(
+            "#ad-slate\n#EXTINF:6\n",
+            std::str::from_utf8(&writer.into_inner()).unwrap()
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn comments_attached_while_reading_should_survive_read_modify_write() {
+        let input = "#EXTM3U\n# ad-slate\n#EXTINF:6\nslate.ts\n# trailing\n#EXT-X-ENDLIST\n";
+        let mut reader = crate::Reader::from_str(
+            input,
+            ParsingOptionsBuilder::new()
+                .with_parsing_for_all_tags()
+                .with_comment_attachment()
+                .build(),
+        );
+        let mut writer = Writer::new(Vec::new());
+        while let Some(line) = reader.read_line().unwrap() {
+            let line = match line {
+                HlsLine::KnownTag(crate::tag::KnownTag::Hls(hls::Tag::Inf(mut inf))) => {
+                    assert_eq!(Some(" ad-slate"), inf.comment());
+                    inf.set_duration(4.0);
+                    HlsLine::from(inf)
+                }
+                line => line,
+            };
+            writer.write_line(line).unwrap();
+        }
+        assert_eq!(
+            "#EXTM3U\n# ad-slate\n#EXTINF:4\nslate.ts\n# trailing\n#EXT-X-ENDLIST\n",
+            std::str::from_utf8(&writer.into_inner()).unwrap()
+        );
+    }
+
+    #[test]
+    fn blank_and_whitespace_lines_should_be_written_according_to_blank_lines_option() {
+        const INPUT: &str = "#EXTM3U\n\n  \n\t \n\r\r\n#EXTINF:4\nsegment.ts\n\r";
+        let write = |blank_lines| {
+            let mut reader =
+                crate::Reader::from_str(INPUT, crate::config::ParsingOptions::default());
+            let mut writer = Writer::new(Vec::new()).with_blank_lines(blank_lines);
+            while let Some(line) = reader.read_line().unwrap() {
+                writer.write_line(line).unwrap();
+            }
+            String::from_utf8(writer.into_inner()).unwrap()
+        };
+        // The writer uses LF line endings by default, and so the CR that precedes the LF of the fifth
+        // line is treated as part of the line ending, but the CR-only content is kept.
+        assert_eq!(
+            "#EXTM3U\n\n  \n\t \n\r\n#EXTINF:4\nsegment.ts\n\r\n",
+            write(BlankLines::Preserve)
+        );
+        assert_eq!(
+            "#EXTM3U\n\n\n\n\n#EXTINF:4\nsegment.ts\n\n",
+            write(BlankLines::TrimWhitespace)
+        );
+        assert_eq!("#EXTM3U\n#EXTINF:4\nsegment.ts\n", write(BlankLines::Strip));
+    }
+
+    #[test]
+    fn whitespace_lines_should_not_be_parsed_as_uris() {
+        let options = crate::config::ParsingOptions::default();
+        for (input, expected) in [
+            (" ", HlsLine::Whitespace(Cow::Borrowed(" "))),
+            ("\t\r\n", HlsLine::Whitespace(Cow::Borrowed("\t"))),
+            ("\r", HlsLine::Whitespace(Cow::Borrowed("\r"))),
+            ("\r\n", HlsLine::Blank),
+            (" a ", HlsLine::Uri(Cow::Borrowed(" a "))),
+        ] {
+            assert_eq!(
+                expected,
+                crate::line::parse(input, &options).unwrap().parsed
+            );
+        }
+        assert_eq!(
+            " \t",
+            HlsLine::<crate::tag::NoCustomTag>::Whitespace(Cow::Borrowed(" \t")).to_string()
+        );
+    }
+
     #[test]
     fn writing_with_no_manipulation_should_leave_output_unchaged_except_for_new_lines() {
         let mut writer = Writer::new(Vec::new());
@@ -686,10 +1309,254 @@ mod tests {
             std::str::from_utf8(&writer.into_inner()).unwrap()
         );
     }
-}
 
-#[cfg(test)]
-const EXPECTED_WRITE_OUTPUT: &str = r#"#EXTM3U
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn m3u_header_should_only_be_written_when_missing() {
+        let write = |lines: Vec<HlsLine>| {
+            let mut writer = WriterBuilder::new()
+                .with_m3u_header()
+                .with_line_ending(LineEnding::Crlf)
+                .build(Vec::new());
+            writer.write_bom().unwrap();
+            for line in lines {
+                writer.write_line(line).unwrap();
+            }
+            let count = writer.bytes_written();
+            let output = writer.into_inner();
+            assert_eq!(output.len() as u64, count);
+            String::from_utf8(output).unwrap()
+        };
+        assert_eq!(
+            "\u{FEFF}#EXTM3U\r\n#EXT-X-VERSION:3\r\n",
+            write(vec![HlsLine::from(Version::new(3))])
+        );
+        assert_eq!(
+            "\u{FEFF}#EXTM3U\r\n#EXT-X-VERSION:3\r\n",
+            write(vec![HlsLine::from(M3u), HlsLine::from(Version::new(3))])
+        );
+        let mut inf = Inf::new(6.0, String::new());
+        inf.set_comment("ad-slate");
+        assert_eq!(
+            "\u{FEFF}#EXTM3U\r\n#ad-slate\r\n#EXTINF:6\r\n",
+            write(vec![HlsLine::from(inf)])
+        );
+    }
+
+    #[test]
+    fn buffered_writer_should_flush_each_line_when_configured() {
+        let mut writer = WriterBuilder::new()
+            .with_buffer_capacity(1024)
+            .with_line_flushing()
+            .build_buffered(Vec::new());
+        writer.write_uri("segment.ts").unwrap();
+        assert_eq!(b"segment.ts\n", writer.get_ref().get_ref().as_slice());
+
+        let mut writer = WriterBuilder::new()
+            .with_buffer_capacity(1024)
+            .build_buffered(Vec::new());
+        writer.write_uri("segment.ts").unwrap();
+        assert!(writer.get_ref().get_ref().is_empty());
+        writer.flush().unwrap();
+        assert_eq!(b"segment.ts\n", writer.get_ref().get_ref().as_slice());
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn value_validation_should_warn_on_values_that_break_quoting_rules() {
+        let mut writer = Writer::new(Vec::new()).with_value_validation(ValueValidation::Warn);
+        let mut key = hls::Key::builder()
+            .with_method("AES-128")
+            .with_uri("key.bin")
+            .finish();
+        key.set_uri("key\".bin");
+        writer.write_line(HlsLine::from(key)).unwrap();
+        let mut key = hls::Key::builder().with_method("NONE").finish();
+        key.set_uri("key\n.bin");
+        writer.write_line(HlsLine::from(key)).unwrap();
+        // A comma is valid within a quoted-string.
+        let mut key = hls::Key::builder().with_method("NONE").finish();
+        key.set_uri("key,1.bin");
+        writer.write_line(HlsLine::from(key)).unwrap();
+        assert_eq!(
+            vec![
+                UnparsableTagError {
+                    tag_name: "-X-KEY".to_string(),
+                    reason: UnparsableTagReason::InvalidAttributeList(
+                        AttributeListParsingError::UnexpectedCharacterAfterQuoteEnd
+                    ),
+                },
+                UnparsableTagError {
+                    tag_name: "-X-KEY".to_string(),
+                    reason: UnparsableTagReason::LineBreak,
+                },
+            ],
+            writer.take_warnings()
+        );
+        assert!(writer.warnings().is_empty());
+        assert_eq!(
+            concat!(
+                "#EXT-X-KEY:METHOD=AES-128,URI=\"key\".bin\"\n",
+                "#EXT-X-KEY:METHOD=NONE,URI=\"key\n",
+                "#EXT-X-KEY:METHOD=NONE,URI=\"key,1.bin\"\n",
+            ),
+            String::from_utf8(writer.into_inner()).unwrap()
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn value_validation_should_reject_values_that_break_quoting_rules() {
+        let mut writer = WriterBuilder::new()
+            .with_value_validation(ValueValidation::Reject)
+            .with_m3u_header()
+            .build(Vec::new());
+        let mut media = hls::Media::builder()
+            .with_media_type(hls::MediaType::Audio)
+            .with_name("English")
+            .with_group_id("aac")
+            .finish();
+        media.set_media_type("AUDIO\"");
+        let error = writer.write_line(HlsLine::from(media)).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert!(writer.get_ref().is_empty());
+        // Unchanged tags are written as they were read, without validation.
+        let tag = crate::Reader::from_str(
+            "#EXT-X-MEDIA:TYPE=AUDIO,NAME=\"English\",GROUP-ID=\"aac\"",
+            ParsingOptionsBuilder::new()
+                .with_parsing_for_media()
+                .build(),
+        )
+        .read_line()
+        .unwrap()
+        .unwrap();
+        writer.write_line(tag).unwrap();
+        writer.write_line(HlsLine::from(Inf::new(4.0, ""))).unwrap();
+        assert_eq!(
+            concat!(
+                "#EXTM3U\n",
+                "#EXT-X-MEDIA:TYPE=AUDIO,NAME=\"English\",GROUP-ID=\"aac\"\n",
+                "#EXTINF:4\n",
+            ),
+            String::from_utf8(writer.into_inner()).unwrap()
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn preserved_formatting_should_only_splice_in_changes_to_mutated_tags() {
+        const PLAYLIST: &str = concat!(
+            "#EXTINF:4.000,Old title\n",
+            "#EXT-X-KEY:METHOD=AES-128,X-VENDOR=1,URI=\"key.bin\",IV=0x1234\n",
+            "#EXT-X-PART:DURATION=1.000,URI=\"part.mp4\"\n",
+            "#EXT-X-VERSION:3\n",
+        );
+        let mut reader = Reader::from_str(PLAYLIST, ParsingOptions::default());
+        let mut writer = Writer::new(Vec::new()).with_preserved_formatting();
+        while let Some(line) = reader.read_line().unwrap() {
+            let line = match line {
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Inf(mut tag))) => {
+                    tag.set_title("New title");
+                    HlsLine::from(tag)
+                }
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Key(mut tag))) => {
+                    tag.set_uri("new.bin");
+                    tag.unset_iv();
+                    HlsLine::from(tag)
+                }
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Part(mut tag))) => {
+                    tag.set_independent(true);
+                    HlsLine::from(tag)
+                }
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Version(mut tag))) => {
+                    tag.set_version(4);
+                    HlsLine::from(tag)
+                }
+                line => line,
+            };
+            writer.write_line(line).unwrap();
+        }
+        assert_eq!(
+            concat!(
+                "#EXTINF:4.000,New title\n",
+                "#EXT-X-KEY:METHOD=AES-128,X-VENDOR=1,URI=\"new.bin\"\n",
+                "#EXT-X-PART:DURATION=1.000,URI=\"part.mp4\",INDEPENDENT=YES\n",
+                "#EXT-X-VERSION:4\n",
+            ),
+            String::from_utf8(writer.into_inner()).unwrap()
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn preserved_formatting_should_write_changes_to_quoted_numeric_values() {
+        let mut reader = Reader::from_str(
+            "#EXT-X-DEFINE:NAME=\"v\",VALUE=\"1.0\"\n",
+            ParsingOptions::default(),
+        );
+        let mut writer = Writer::new(Vec::new()).with_preserved_formatting();
+        while let Some(line) = reader.read_line().unwrap() {
+            let line = match line {
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Define(hls::Define::Name(mut tag)))) => {
+                    tag.set_value("1");
+                    HlsLine::from(hls::Define::Name(tag))
+                }
+                line => line,
+            };
+            writer.write_line(line).unwrap();
+        }
+        assert_eq!(
+            "#EXT-X-DEFINE:NAME=\"v\",VALUE=\"1\"\n",
+            String::from_utf8(writer.into_inner()).unwrap()
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn preserved_formatting_should_not_apply_to_constructed_tags() {
+        let mut writer = WriterBuilder::new()
+            .with_preserved_formatting()
+            .build(Vec::new());
+        let mut inf = Inf::new(4.0, "");
+        inf.set_title("title");
+        writer.write_line(HlsLine::from(inf)).unwrap();
+        assert_eq!(
+            "#EXTINF:4,title\n",
+            String::from_utf8(writer.into_inner()).unwrap()
+        );
+    }
+
+    #[cfg(feature = "hls-tags")]
+    #[test]
+    fn line_counts_should_distinguish_replaced_from_modified_tags() {
+        let mut reader = Reader::from_str(
+            "#EXTM3U\n#EXT-X-TARGETDURATION:4\n#EXT-X-MEDIA-SEQUENCE:1\n\n",
+            ParsingOptions::default(),
+        );
+        let mut writer = WriterBuilder::new()
+            .with_blank_lines(BlankLines::Strip)
+            .build(Vec::new());
+        while let Some(mut line) = reader.read_line().unwrap() {
+            match &mut line {
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::Targetduration(_))) => {
+                    line.replace_raw(b"#EXT-X-TARGETDURATION:6".as_slice())
+                        .unwrap();
+                    assert!(matches!(&line, HlsLine::KnownTag(tag) if !tag.is_dirty()));
+                }
+                HlsLine::KnownTag(KnownTag::Hls(hls::Tag::MediaSequence(tag))) => {
+                    tag.set_media_sequence(2);
+                    assert!(tag.is_dirty());
+                }
+                _ => (),
+            }
+            writer.write_line(line).unwrap();
+        }
+        writer.write_line(HlsLine::from(M3u)).unwrap();
+        assert_eq!(3, writer.verbatim_lines());
+        assert_eq!(1, writer.reserialized_lines());
+    }
+
+    const EXPECTED_WRITE_OUTPUT: &str = r#"#EXTM3U
 #EXT-X-VERSION:3
 #EXT-X-TARGETDURATION:8
 #EXT-X-MEDIA-SEQUENCE:2680
@@ -701,3 +1568,4 @@ https://priv.example.com/fileSequence2681.ts
 #EXTINF:7.975
 https://priv.example.com/fileSequence2682.ts
 "#;
+}